@@ -0,0 +1,180 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{Data, DataProvider, FinalizationHandler};
+use async_trait::async_trait;
+use codec::{Decode, Encode, Error as CodecError, Input};
+use futures::FutureExt;
+
+/// The maximal number of data items a single unit can carry.
+pub const MAX_DATA_BATCH_SIZE: usize = 1024;
+
+/// A batch of data items ordered together, as a single piece of data of a unit.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Encode)]
+pub struct DataBatch<D>(Vec<D>);
+
+impl<D> DataBatch<D> {
+    /// The items in the batch, in the order they were provided.
+    pub fn items(&self) -> &[D] {
+        &self.0
+    }
+
+    pub fn into_items(self) -> Vec<D> {
+        self.0
+    }
+}
+
+impl<D: Decode> Decode for DataBatch<D> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        let items = Vec::<D>::decode(input)?;
+        if items.len() > MAX_DATA_BATCH_SIZE {
+            return Err("data batch exceeds the maximal size".into());
+        }
+        Ok(DataBatch(items))
+    }
+}
+
+/// A data provider that lets units carry up to `max_batch_size` items of the wrapped provider.
+///
+/// It waits for the first item as long as the wrapped provider does, and then takes further items
+/// only as long as they are immediately available, so batching never delays unit creation. This
+/// requires `get_data` of the wrapped provider to be cancellation safe.
+pub struct BatchingDataProvider<DP> {
+    data_provider: DP,
+    max_batch_size: usize,
+}
+
+impl<DP: DataProvider> BatchingDataProvider<DP> {
+    /// Wraps the provider, `max_batch_size` is clamped to between 1 and [`MAX_DATA_BATCH_SIZE`].
+    pub fn new(data_provider: DP, max_batch_size: usize) -> Self {
+        BatchingDataProvider {
+            data_provider,
+            max_batch_size: max_batch_size.clamp(1, MAX_DATA_BATCH_SIZE),
+        }
+    }
+}
+
+#[async_trait]
+impl<DP: DataProvider> DataProvider for BatchingDataProvider<DP> {
+    type Output = DataBatch<DP::Output>;
+
+    async fn get_data(&mut self) -> Option<Self::Output> {
+        let mut items = vec![self.data_provider.get_data().await?];
+        while items.len() < self.max_batch_size {
+            match self.data_provider.get_data().now_or_never() {
+                Some(Some(item)) => items.push(item),
+                _ => break,
+            }
+        }
+        Some(DataBatch(items))
+    }
+}
+
+/// A finalization handler passing the items of finalized batches one by one to the wrapped
+/// handler.
+pub struct BatchFinalizationHandler<FH> {
+    finalization_handler: FH,
+}
+
+impl<FH> BatchFinalizationHandler<FH> {
+    pub fn new(finalization_handler: FH) -> Self {
+        BatchFinalizationHandler {
+            finalization_handler,
+        }
+    }
+}
+
+impl<D: Data, FH: FinalizationHandler<D>> FinalizationHandler<DataBatch<D>>
+    for BatchFinalizationHandler<FH>
+{
+    fn data_finalized(&mut self, batch: DataBatch<D>) {
+        for item in batch.into_items() {
+            self.finalization_handler.data_finalized(item);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        batching::{
+            BatchFinalizationHandler, BatchingDataProvider, DataBatch, MAX_DATA_BATCH_SIZE,
+        },
+        DataProvider as _, FinalizationHandler as _,
+    };
+    use codec::{Decode, Encode};
+    use futures::StreamExt;
+    use set_bft_mock::{DataProvider, FinalizationHandler, StalledDataProvider};
+
+    #[tokio::test]
+    async fn batches_up_to_the_maximal_size() {
+        let mut data_provider = BatchingDataProvider::new(DataProvider::new_finite(5), 3);
+        assert_eq!(
+            data_provider.get_data().await.map(DataBatch::into_items),
+            Some(vec![0, 1, 2])
+        );
+        assert_eq!(
+            data_provider.get_data().await.map(DataBatch::into_items),
+            Some(vec![3, 4])
+        );
+        assert_eq!(data_provider.get_data().await, None);
+    }
+
+    #[test]
+    fn waits_for_the_first_item() {
+        let mut data_provider = BatchingDataProvider::new(StalledDataProvider::new(), 3);
+        assert!(futures::FutureExt::now_or_never(data_provider.get_data()).is_none());
+    }
+
+    #[tokio::test]
+    async fn finalizes_items_in_order() {
+        let (finalization_handler, mut finalized) = FinalizationHandler::new();
+        let mut finalization_handler = BatchFinalizationHandler::new(finalization_handler);
+        finalization_handler.data_finalized(DataBatch(vec![7, 3, 5]));
+        assert_eq!(finalized.next().await, Some(7));
+        assert_eq!(finalized.next().await, Some(3));
+        assert_eq!(finalized.next().await, Some(5));
+    }
+
+    #[test]
+    fn refuses_to_decode_too_large_batches() {
+        let encoded = vec![0u32; MAX_DATA_BATCH_SIZE + 1].encode();
+        assert!(DataBatch::<u32>::decode(&mut &encoded[..]).is_err());
+        let encoded = vec![0u32; MAX_DATA_BATCH_SIZE].encode();
+        assert!(DataBatch::<u32>::decode(&mut &encoded[..]).is_ok());
+    }
+}
@@ -79,6 +79,40 @@ pub struct DelayConfig {
     /// newest_request_delay(k) represents the delay between the kth and (k+1)st try when sending
     /// a broadcast request for newest units
     pub newest_request_delay: DelaySchedule,
+    /// If set, the delay between creating units is adjusted to the observed latency of parents
+    /// instead of following `unit_creation_delay`.
+    pub adaptive_unit_creation: Option<AdaptiveDelayConfig>,
+}
+
+/// Configuration of the adaptive unit creation delay.
+///
+/// The creator measures how long it takes for the consensus threshold of units of a round to
+/// arrive after it created its own unit of that round, and waits for a smoothed multiple of that
+/// time before creating the next unit. The multiple grows whenever the created unit has a smaller
+/// share of parents than `target_fresh_parents` and shrinks otherwise.
+#[derive(Clone)]
+pub struct AdaptiveDelayConfig {
+    /// min_delay(k) is the lower bound on the delay between creating the (k-1)th and kth unit.
+    /// It takes precedence over `max_delay`, so it can be used to slow down late rounds, and it is
+    /// used instead of `unit_creation_delay` when validating `max_round`.
+    pub min_delay: DelaySchedule,
+    /// Upper bound on the delay between creating subsequent units.
+    pub max_delay: Duration,
+    /// The share of all members, between 0 and 1, we want our units to have as parents.
+    pub target_fresh_parents: f64,
+    /// Weight of the newest latency measurement in the smoothed latency, between 0 (exclusive)
+    /// and 1.
+    pub smoothing: f64,
+}
+
+impl Debug for AdaptiveDelayConfig {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdaptiveDelayConfig")
+            .field("max delay", &self.max_delay)
+            .field("target fresh parents", &self.target_fresh_parents)
+            .field("smoothing", &self.smoothing)
+            .finish()
+    }
 }
 
 impl Debug for DelayConfig {
@@ -93,6 +127,7 @@ impl Debug for DelayConfig {
                 "max unit rebroadcast interval",
                 &self.unit_rebroadcast_interval_max,
             )
+            .field("adaptive unit creation", &self.adaptive_unit_creation)
             .finish()
     }
 }
@@ -161,7 +196,22 @@ pub fn create_config(
     delay_config: DelayConfig,
     time_to_reach_max_round: Duration,
 ) -> Result<Config, InvalidConfigError> {
-    if time_to_reach_round(max_round, &delay_config.unit_creation_delay) < time_to_reach_max_round {
+    let fastest_unit_creation = match &delay_config.adaptive_unit_creation {
+        Some(adaptive) => {
+            if !(f64::MIN_POSITIVE..=1.0).contains(&adaptive.smoothing)
+                || !(0.0..=1.0).contains(&adaptive.target_fresh_parents)
+            {
+                error!(
+                    target: "SetBFT-config",
+                    "Adaptive unit creation requires smoothing in (0, 1] and target_fresh_parents in [0, 1]."
+                );
+                return Err(InvalidConfigError);
+            }
+            &adaptive.min_delay
+        }
+        None => &delay_config.unit_creation_delay,
+    };
+    if time_to_reach_round(max_round, fastest_unit_creation) < time_to_reach_max_round {
         error!(
             target: "SetBFT-config",
             "Reaching max_round will happen too fast with the given Config. Consider increasing max_round or lowering time_to_reach_max_round."
@@ -211,9 +261,29 @@ pub fn default_delay_config() -> DelayConfig {
         parent_request_delay: Arc::new(|_| Duration::from_millis(3000)),
         parent_request_recipients: Arc::new(|_| 1),
         newest_request_delay: Arc::new(|_| Duration::from_millis(3000)),
+        adaptive_unit_creation: None,
     }
 }
 
+/// Creates an [`AdaptiveDelayConfig`] with default parameters, suggested by the creators of this
+/// package. To use it, set it as `adaptive_unit_creation` of a [`DelayConfig`].
+pub fn default_adaptive_delay_config() -> AdaptiveDelayConfig {
+    AdaptiveDelayConfig {
+        min_delay: default_min_unit_creation_delay(),
+        max_delay: Duration::from_millis(2000),
+        target_fresh_parents: 0.8,
+        smoothing: 0.2,
+    }
+}
+
+/// 5000, 100, 100, 100, ... (till step 3000), 100, 100*1.005, 100*(1.005)^2, ...
+fn default_min_unit_creation_delay() -> DelaySchedule {
+    Arc::new(|t| match t {
+        0 => Duration::from_millis(5000),
+        _ => exponential_slowdown(t, 100.0, 3000, 1.005),
+    })
+}
+
 /// 5000, 500, 500, 500, ... (till step 3000), 500, 500*1.005, 500*(1.005)^2, 500*(1.005)^3, ..., 10742207 (last step)
 fn default_unit_creation_delay() -> DelaySchedule {
     Arc::new(|t| match t {
@@ -253,7 +323,8 @@ mod tests {
             default_coord_request_delay, default_coord_request_recipients, time_to_reach_round,
             DelaySchedule,
         },
-        create_config, exponential_slowdown, DelayConfig, NodeCount, NodeIndex,
+        create_config, default_adaptive_delay_config, exponential_slowdown, DelayConfig, NodeCount,
+        NodeIndex,
     };
     use std::{sync::Arc, time::Duration};
 
@@ -273,6 +344,7 @@ mod tests {
             parent_request_delay: Arc::new(|_| Duration::from_millis(3000)),
             parent_request_recipients: Arc::new(|_| 1),
             newest_request_delay: Arc::new(|_| Duration::from_millis(3000)),
+            adaptive_unit_creation: None,
         }
     }
 
@@ -334,4 +406,40 @@ mod tests {
 
         assert!(config.is_ok());
     }
+
+    #[test]
+    fn adaptive_creation_is_checked_against_min_delay() {
+        let mut delay_config = delay_config_for_tests();
+        let mut adaptive = default_adaptive_delay_config();
+        adaptive.min_delay = Arc::new(|_| Duration::from_millis(1));
+        delay_config.adaptive_unit_creation = Some(adaptive);
+        let config = create_config(
+            NodeCount(5),
+            NodeIndex(1),
+            3,
+            7000,
+            delay_config,
+            Duration::from_millis(MILLIS_IN_WEEK),
+        );
+
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn adaptive_creation_with_invalid_smoothing_fails() {
+        let mut delay_config = delay_config_for_tests();
+        let mut adaptive = default_adaptive_delay_config();
+        adaptive.smoothing = 0.0;
+        delay_config.adaptive_unit_creation = Some(adaptive);
+        let config = create_config(
+            NodeCount(5),
+            NodeIndex(1),
+            3,
+            7000,
+            delay_config,
+            Duration::ZERO,
+        );
+
+        assert!(config.is_err());
+    }
 }
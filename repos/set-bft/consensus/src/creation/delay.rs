@@ -0,0 +1,348 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    config::{AdaptiveDelayConfig, DelayConfig, DelaySchedule},
+    NodeCount, NodeIndex, NodeSubset, Round,
+};
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+const MIN_MULTIPLIER: f64 = 1.0;
+const MAX_MULTIPLIER: f64 = 4.0;
+const MULTIPLIER_STEP: f64 = 1.1;
+
+struct AdaptiveDelay {
+    config: AdaptiveDelayConfig,
+    n_members: NodeCount,
+    // The round and creation time of our newest unit.
+    last_created: Option<(Round, Instant)>,
+    // Whether we are still waiting for the consensus threshold of units of that round.
+    measuring: bool,
+    // Creators of the units received in the rounds we might still measure.
+    received: BTreeMap<Round, NodeSubset>,
+    latency: Option<Duration>,
+    multiplier: f64,
+}
+
+impl AdaptiveDelay {
+    fn new(config: AdaptiveDelayConfig, n_members: NodeCount) -> Self {
+        AdaptiveDelay {
+            config,
+            n_members,
+            last_created: None,
+            measuring: false,
+            received: BTreeMap::new(),
+            latency: None,
+            multiplier: MIN_MULTIPLIER,
+        }
+    }
+
+    fn delay(&self, round: Round, scheduled: Duration) -> Duration {
+        let estimate = match self.latency {
+            Some(latency) => latency.mul_f64(self.multiplier),
+            None => scheduled,
+        };
+        max(
+            min(estimate, self.config.max_delay),
+            (self.config.min_delay)(round.into()),
+        )
+    }
+
+    fn unit_created(&mut self, round: Round, n_parents: usize, now: Instant) {
+        if round > 0 {
+            let fresh_parents = n_parents as f64 / self.n_members.0 as f64;
+            self.multiplier = match fresh_parents < self.config.target_fresh_parents {
+                true => (self.multiplier * MULTIPLIER_STEP).min(MAX_MULTIPLIER),
+                false => (self.multiplier / MULTIPLIER_STEP).max(MIN_MULTIPLIER),
+            };
+        }
+        self.received = self.received.split_off(&round);
+        self.last_created = Some((round, now));
+        self.measuring = true;
+        self.check_threshold(now);
+    }
+
+    fn unit_received(&mut self, round: Round, creator: NodeIndex, now: Instant) {
+        let oldest_relevant = self.last_created.map_or(0, |(round, _)| round);
+        // Units of further rounds arrive only when we are behind, and then we don't wait anyway.
+        if round < oldest_relevant || round > oldest_relevant + 1 || creator.0 >= self.n_members.0 {
+            return;
+        }
+        self.received
+            .entry(round)
+            .or_insert_with(|| NodeSubset::with_size(self.n_members))
+            .insert(creator);
+        self.check_threshold(now);
+    }
+
+    fn check_threshold(&mut self, now: Instant) {
+        let Some((round, created)) = self.last_created else {
+            return;
+        };
+        if !self.measuring {
+            return;
+        }
+        let received = self.received.get(&round).map_or(0, NodeSubset::len);
+        if received < self.n_members.consensus_threshold().0 {
+            return;
+        }
+        let sample = now.saturating_duration_since(created);
+        self.latency = Some(match self.latency {
+            Some(latency) => {
+                latency.mul_f64(1.0 - self.config.smoothing) + sample.mul_f64(self.config.smoothing)
+            }
+            None => sample,
+        });
+        self.measuring = false;
+    }
+}
+
+/// Decides how long to wait before creating a unit of a given round, either following the
+/// configured schedule or adapting to the latency observed in previous rounds.
+pub struct CreationDelay {
+    schedule: DelaySchedule,
+    adaptive: Option<AdaptiveDelay>,
+}
+
+impl CreationDelay {
+    pub fn new(delay_config: &DelayConfig, n_members: NodeCount) -> Self {
+        CreationDelay {
+            schedule: delay_config.unit_creation_delay.clone(),
+            adaptive: delay_config
+                .adaptive_unit_creation
+                .clone()
+                .map(|config| AdaptiveDelay::new(config, n_members)),
+        }
+    }
+
+    /// The delay between creating a unit of the previous round and a unit of `round`.
+    pub fn delay(&self, round: Round) -> Duration {
+        let scheduled = (self.schedule)(round.into());
+        match &self.adaptive {
+            Some(adaptive) => adaptive.delay(round, scheduled),
+            None => scheduled,
+        }
+    }
+
+    /// Notes that we created a unit of `round` with `n_parents` parents.
+    pub fn unit_created(&mut self, round: Round, n_parents: usize) {
+        if let Some(adaptive) = &mut self.adaptive {
+            adaptive.unit_created(round, n_parents, Instant::now());
+        }
+    }
+
+    /// Notes that a unit of `round` created by `creator` was added to the dag.
+    pub fn unit_received(&mut self, round: Round, creator: NodeIndex) {
+        if let Some(adaptive) = &mut self.adaptive {
+            adaptive.unit_received(round, creator, Instant::now());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AdaptiveDelay;
+    use crate::{config::AdaptiveDelayConfig, NodeCount, NodeIndex, Round};
+    use std::{
+        cmp::max,
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    fn adaptive_delay() -> AdaptiveDelay {
+        AdaptiveDelay::new(
+            AdaptiveDelayConfig {
+                min_delay: Arc::new(|_| Duration::from_millis(10)),
+                max_delay: Duration::from_millis(1000),
+                target_fresh_parents: 0.75,
+                smoothing: 0.5,
+            },
+            NodeCount(4),
+        )
+    }
+
+    fn receive_round(delay: &mut AdaptiveDelay, round: u16, creators: usize, now: Instant) {
+        for creator in 0..creators {
+            delay.unit_received(round, NodeIndex(creator), now);
+        }
+    }
+
+    #[test]
+    fn follows_schedule_within_bounds_before_measuring() {
+        let delay = adaptive_delay();
+        assert_eq!(
+            delay.delay(0, Duration::from_millis(5000)),
+            Duration::from_millis(1000)
+        );
+        assert_eq!(
+            delay.delay(0, Duration::from_millis(1)),
+            Duration::from_millis(10)
+        );
+        assert_eq!(
+            delay.delay(0, Duration::from_millis(300)),
+            Duration::from_millis(300)
+        );
+    }
+
+    #[test]
+    fn adapts_to_observed_latency() {
+        let mut delay = adaptive_delay();
+        let start = Instant::now();
+        delay.unit_created(0, 0, start);
+        receive_round(&mut delay, 0, 3, start + Duration::from_millis(100));
+        assert_eq!(
+            delay.delay(1, Duration::from_millis(500)),
+            Duration::from_millis(100)
+        );
+
+        let start = start + Duration::from_millis(200);
+        delay.unit_created(1, 4, start);
+        receive_round(&mut delay, 1, 3, start + Duration::from_millis(50));
+        assert_eq!(
+            delay.delay(2, Duration::from_millis(500)),
+            Duration::from_millis(75)
+        );
+    }
+
+    #[test]
+    fn counts_units_received_before_own_as_immediate() {
+        let mut delay = adaptive_delay();
+        let start = Instant::now();
+        delay.unit_created(0, 0, start);
+        receive_round(&mut delay, 0, 3, start + Duration::from_millis(100));
+        receive_round(&mut delay, 1, 3, start + Duration::from_millis(150));
+        delay.unit_created(1, 4, start + Duration::from_millis(200));
+        assert_eq!(
+            delay.delay(2, Duration::from_millis(500)),
+            Duration::from_millis(50)
+        );
+    }
+
+    #[test]
+    fn waits_longer_when_missing_fresh_parents() {
+        let mut delay = adaptive_delay();
+        let start = Instant::now();
+        delay.unit_created(0, 0, start);
+        receive_round(&mut delay, 0, 3, start + Duration::from_millis(100));
+        let before = delay.delay(1, Duration::from_millis(500));
+        delay.unit_created(1, 2, start + Duration::from_millis(200));
+        assert!(delay.delay(2, Duration::from_millis(500)) > before);
+    }
+
+    const SIMULATED_MEMBERS: usize = 7;
+    const SLOW_CREATORS: [usize; 2] = [5, 6];
+
+    fn skewed_latency(sender: usize, recipient: usize) -> Duration {
+        if sender == recipient {
+            Duration::ZERO
+        } else if SLOW_CREATORS.contains(&sender) || SLOW_CREATORS.contains(&recipient) {
+            Duration::from_millis(100)
+        } else {
+            Duration::from_millis(5)
+        }
+    }
+
+    // Simulates creators in virtual time: every creator waits for its delay after creating a
+    // unit, and then for the consensus threshold of parents, like the creator task does.
+    // Returns the time at which the last creator created its unit of `max_round`.
+    fn time_to_reach_round(adaptive: Option<AdaptiveDelayConfig>, max_round: Round) -> Duration {
+        let n_members = NodeCount(SIMULATED_MEMBERS);
+        let threshold = n_members.consensus_threshold().0;
+        let scheduled = Duration::from_millis(150);
+        let start = Instant::now();
+        let mut delays: Vec<_> = (0..SIMULATED_MEMBERS)
+            .map(|_| {
+                adaptive
+                    .clone()
+                    .map(|config| AdaptiveDelay::new(config, n_members))
+            })
+            .collect();
+        for delay in delays.iter_mut().flatten() {
+            delay.unit_created(0, 0, start);
+        }
+        // When every creator created its unit of the previous round.
+        let mut created = vec![Duration::ZERO; SIMULATED_MEMBERS];
+        for round in 1..=max_round {
+            let mut next = created.clone();
+            for (node, delay) in delays.iter_mut().enumerate() {
+                let mut arrivals: Vec<_> = (0..SIMULATED_MEMBERS)
+                    .map(|creator| (created[creator] + skewed_latency(creator, node), creator))
+                    .collect();
+                arrivals.sort();
+                let wait = delay
+                    .as_ref()
+                    .map_or(scheduled, |delay| delay.delay(round, scheduled));
+                let at = max(created[node] + wait, arrivals[threshold - 1].0);
+                let parents: Vec<_> = arrivals.iter().filter(|(time, _)| *time <= at).collect();
+                if let Some(delay) = delay {
+                    for (time, creator) in &parents {
+                        delay.unit_received(round - 1, NodeIndex(*creator), start + *time);
+                    }
+                    delay.unit_created(round, parents.len(), start + at);
+                }
+                next[node] = at;
+            }
+            created = next;
+        }
+        created.into_iter().max().expect("there are creators")
+    }
+
+    #[test]
+    fn adaptive_creators_are_faster_under_skewed_latency() {
+        let max_round = 20;
+        let fixed_time = time_to_reach_round(None, max_round);
+        let adaptive_time = time_to_reach_round(
+            Some(AdaptiveDelayConfig {
+                min_delay: Arc::new(|_| Duration::from_millis(5)),
+                max_delay: Duration::from_millis(500),
+                target_fresh_parents: 0.7,
+                smoothing: 0.5,
+            }),
+            max_round,
+        );
+        assert!(
+            adaptive_time < fixed_time,
+            "adaptive creators took {:?}, fixed delay creators took {:?}",
+            adaptive_time,
+            fixed_time
+        );
+    }
+}
@@ -52,9 +52,11 @@ use log::{debug, error, trace, warn};
 
 mod collector;
 mod creator;
+mod delay;
 mod packer;
 
 pub use creator::Creator;
+use delay::CreationDelay;
 use packer::Packer;
 
 const LOG_TARGET: &str = "SetBFT-creator";
@@ -79,6 +81,7 @@ pub struct IO<U: Unit, MK: MultiKeychain, DP: DataProvider> {
 async fn create_unit<U: Unit>(
     round: Round,
     creator: &mut Creator<U::Hasher>,
+    creation_delay: &mut CreationDelay,
     incoming_parents: &mut Receiver<U>,
 ) -> Result<PreUnit<U::Hasher>, CreatorError> {
     loop {
@@ -88,7 +91,7 @@ async fn create_unit<U: Unit>(
                 trace!(target: LOG_TARGET, "Creator unable to create a new unit at round {}: {}.", round, err)
             }
         }
-        process_unit(creator, incoming_parents).await?;
+        process_unit(creator, creation_delay, incoming_parents).await?;
     }
 }
 
@@ -96,6 +99,7 @@ async fn create_unit<U: Unit>(
 /// Returns error when `incoming_parents` channel is closed.
 async fn process_unit<U: Unit>(
     creator: &mut Creator<U::Hasher>,
+    creation_delay: &mut CreationDelay,
     incoming_parents: &mut Receiver<U>,
 ) -> anyhow::Result<(), CreatorError> {
     let unit = incoming_parents
@@ -103,25 +107,28 @@ async fn process_unit<U: Unit>(
         .await
         .ok_or(CreatorError::ParentsChannelClosed)?;
     creator.add_unit(&unit);
+    creation_delay.unit_received(unit.round(), unit.creator());
     Ok(())
 }
 
 async fn keep_processing_units<U: Unit>(
     creator: &mut Creator<U::Hasher>,
+    creation_delay: &mut CreationDelay,
     incoming_parents: &mut Receiver<U>,
 ) -> anyhow::Result<(), CreatorError> {
     loop {
-        process_unit(creator, incoming_parents).await?;
+        process_unit(creator, creation_delay, incoming_parents).await?;
     }
 }
 
 async fn keep_processing_units_until<U: Unit>(
     creator: &mut Creator<U::Hasher>,
+    creation_delay: &mut CreationDelay,
     incoming_parents: &mut Receiver<U>,
     until: Delay,
 ) -> anyhow::Result<(), CreatorError> {
     futures::select! {
-        result = keep_processing_units(creator, incoming_parents).fuse() => {
+        result = keep_processing_units(creator, creation_delay, incoming_parents).fuse() => {
             result?
         },
         _ = until.fuse() => {
@@ -201,7 +208,7 @@ async fn run_creator<U: Unit, MK: MultiKeychain, DP: DataProvider>(
 ) -> anyhow::Result<(), CreatorError> {
     let node_id = conf.node_ix();
     let n_members = conf.n_members();
-    let create_delay = conf.delay_config().unit_creation_delay.clone();
+    let mut creation_delay = CreationDelay::new(conf.delay_config(), n_members);
     let max_round = conf.max_round();
     let session_id = conf.session_id();
     let mut creator = Creator::new(node_id, n_members);
@@ -217,12 +224,15 @@ async fn run_creator<U: Unit, MK: MultiKeychain, DP: DataProvider>(
         // delay we should observe.
         let skip_delay = creator.current_round() > round;
         if !skip_delay {
-            let delay = Delay::new(create_delay(round.into()));
+            let delay = Delay::new(creation_delay.delay(round));
 
-            keep_processing_units_until(&mut creator, incoming_parents, delay).await?;
+            keep_processing_units_until(&mut creator, &mut creation_delay, incoming_parents, delay)
+                .await?;
         }
 
-        let preunit = create_unit(round, &mut creator, incoming_parents).await?;
+        let preunit =
+            create_unit(round, &mut creator, &mut creation_delay, incoming_parents).await?;
+        creation_delay.unit_created(round, preunit.control_hash().parents().count());
         trace!(target: LOG_TARGET, "Created a new preunit {:?} at round {:?}.", preunit, round);
         let data = data_provider.get_data().await;
         trace!(target: LOG_TARGET, "Received data: {:?}.", data);
@@ -40,6 +40,7 @@
 //! gives appropriate access to the set of available data that we need to make consensus on.
 
 mod alerts;
+mod batching;
 mod collection;
 mod config;
 mod consensus;
@@ -57,8 +58,12 @@ mod task_queue;
 #[cfg(test)]
 mod testing;
 
+pub use batching::{
+    BatchFinalizationHandler, BatchingDataProvider, DataBatch, MAX_DATA_BATCH_SIZE,
+};
 pub use config::{
-    create_config, default_config, default_delay_config, exponential_slowdown, Config, DelayConfig,
+    create_config, default_adaptive_delay_config, default_config, default_delay_config,
+    exponential_slowdown, AdaptiveDelayConfig, Config, DelayConfig,
 };
 pub use consensus::run_session;
 pub use interface::LocalIO;
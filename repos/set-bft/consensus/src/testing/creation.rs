@@ -39,13 +39,14 @@ use crate::{
     creation::{run, IO},
     testing::{gen_config, gen_delay_config},
     units::{SignedUnit as GenericSignedUnit, Unit as GenericUnit},
-    NodeCount, Receiver, Round, Sender, Terminator,
+    AdaptiveDelayConfig, DelayConfig, NodeCount, NodeIndex, Receiver, Round, Sender, Terminator,
 };
 use futures::{
     channel::{mpsc, oneshot},
     FutureExt, StreamExt,
 };
 use set_bft_mock::{Data, DataProvider, Hasher64, Keychain};
+use std::{sync::Arc, time::Duration};
 
 type SignedUnit = GenericSignedUnit<Hasher64, Data, Keychain>;
 
@@ -96,6 +97,10 @@ struct TestSetup {
 }
 
 fn setup_test(n_members: NodeCount) -> TestSetup {
+    setup_test_with_delay_config(n_members, gen_delay_config())
+}
+
+fn setup_test_with_delay_config(n_members: NodeCount, delay_config: DelayConfig) -> TestSetup {
     let (units_for_controller, units_from_creators) = mpsc::unbounded();
     let (units_for_creators, units_from_controller) = mpsc::unbounded();
 
@@ -113,7 +118,7 @@ fn setup_test(n_members: NodeCount) -> TestSetup {
             outgoing_units: units_for_controller.clone(),
             data_provider: DataProvider::new(),
         };
-        let config = gen_config(node_ix, n_members, gen_delay_config());
+        let config = gen_config(node_ix, n_members, delay_config.clone());
         let (starting_round_for_consensus, starting_round) = oneshot::channel();
 
         units_for_creators.push(parents_for_creator);
@@ -331,3 +336,65 @@ async fn late_creators_should_create_dag() {
     );
     finish(killers, handles).await;
 }
+
+const SLOW_CREATORS: [NodeIndex; 2] = [NodeIndex(5), NodeIndex(6)];
+
+fn skewed_latency(sender: NodeIndex, recipient: NodeIndex) -> Duration {
+    if sender == recipient {
+        Duration::ZERO
+    } else if SLOW_CREATORS.contains(&sender) || SLOW_CREATORS.contains(&recipient) {
+        Duration::from_millis(100)
+    } else {
+        Duration::from_millis(5)
+    }
+}
+
+async fn reach_round_with_skewed_latency(delay_config: DelayConfig, max_round: Round) {
+    let n_members = NodeCount(7);
+
+    let TestSetup {
+        mut test_controller,
+        killers,
+        handles,
+        mut units_from_controller,
+        units_for_creators,
+    } = setup_test_with_delay_config(n_members, delay_config);
+    loop {
+        futures::select! {
+            _ = test_controller.control_until(max_round).fuse() => break,
+            unit = units_from_controller.next() => match unit {
+                Some(unit) => for (recipient, units_for_creator) in units_for_creators.iter().enumerate() {
+                    let latency = skewed_latency(unit.creator(), NodeIndex(recipient));
+                    let unit = unit.clone();
+                    let units_for_creator = units_for_creator.clone();
+                    tokio::spawn(async move {
+                        tokio::time::sleep(latency).await;
+                        // the creator might have already been killed
+                        let _ = units_for_creator.unbounded_send(unit);
+                    });
+                },
+                None => panic!("Channel from controller should be open."),
+            }
+        }
+    }
+    finish(killers, handles).await;
+}
+
+// Skewed latency test
+// This test starts with 7 creators, 2 of which have high latency links to everyone else, and
+// checks that adaptive creators keep making progress. That they are faster than creators with a
+// fixed delay is checked deterministically, in virtual time, by the tests of `creation::delay`.
+#[tokio::test(flavor = "multi_thread", worker_threads = 3)]
+async fn adaptive_creators_make_progress_under_skewed_latency() {
+    let max_round: Round = 20;
+
+    let mut adaptive_delay_config = gen_delay_config();
+    adaptive_delay_config.unit_creation_delay = Arc::new(|_| Duration::from_millis(150));
+    adaptive_delay_config.adaptive_unit_creation = Some(AdaptiveDelayConfig {
+        min_delay: Arc::new(|_| Duration::from_millis(5)),
+        max_delay: Duration::from_millis(500),
+        target_fresh_parents: 0.7,
+        smoothing: 0.5,
+    });
+    reach_round_with_skewed_latency(adaptive_delay_config, max_round).await;
+}
@@ -85,6 +85,7 @@ pub fn gen_delay_config() -> DelayConfig {
         parent_request_recipients: Arc::new(|_| 1),
         // 50, 50, 50, 50, ...
         newest_request_delay: Arc::new(|_| Duration::from_millis(50)),
+        adaptive_unit_creation: None,
     }
 }
 
@@ -0,0 +1,143 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    crypto::{KeyError, PublicKey},
+    network::Config,
+};
+use serde::{Deserialize, Serialize};
+use set_bft::SessionId;
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    fs, io,
+    net::SocketAddr,
+    path::Path,
+};
+
+/// A single committee member, as listed in the peer config file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerEntry {
+    pub address: SocketAddr,
+    /// Hex encoded ed25519 public key.
+    pub public_key: String,
+}
+
+/// Contents of the peer config file, describing the whole committee.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PeerConfig {
+    pub session_id: SessionId,
+    /// All the committee members, ordered by their indices.
+    pub peers: Vec<PeerEntry>,
+}
+
+/// Errors when loading the peer config file.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// The public key of the member with the given index is invalid.
+    Key(usize, KeyError),
+    /// There are no members in the committee.
+    EmptyCommittee,
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use ConfigError::*;
+        match self {
+            Io(e) => write!(f, "cannot read peer config: {}", e),
+            Json(e) => write!(f, "malformed peer config: {}", e),
+            Key(index, e) => write!(f, "public key of node {}: {}", index, e),
+            EmptyCommittee => write!(f, "peer config lists no peers"),
+        }
+    }
+}
+
+impl PeerConfig {
+    /// Read the config from a JSON file.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = fs::read_to_string(path).map_err(ConfigError::Io)?;
+        serde_json::from_str(&contents).map_err(ConfigError::Json)
+    }
+
+    /// The network config and the public keys of the committee, both ordered by member indices.
+    pub fn into_network_config(self) -> Result<(Config, Vec<PublicKey>), ConfigError> {
+        if self.peers.is_empty() {
+            return Err(ConfigError::EmptyCommittee);
+        }
+        let public_keys = self
+            .peers
+            .iter()
+            .enumerate()
+            .map(|(index, peer)| {
+                PublicKey::from_hex(&peer.public_key).map_err(|e| ConfigError::Key(index, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let addresses = self.peers.iter().map(|peer| peer.address).collect();
+        Ok((Config::new(addresses, self.session_id), public_keys))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigError, PeerConfig};
+    use crate::crypto::SecretKey;
+
+    #[test]
+    fn parses_peer_config() {
+        let public_key = SecretKey::generate().public_key();
+        let json = format!(
+            r#"{{"session_id":3,"peers":[{{"address":"127.0.0.1:10000","public_key":"{}"}}]}}"#,
+            public_key.to_hex()
+        );
+        let peer_config: PeerConfig = serde_json::from_str(&json).expect("valid json");
+        let (config, public_keys) = peer_config.into_network_config().expect("valid config");
+        assert_eq!(config.session_id, 3);
+        assert_eq!(config.addresses, vec!["127.0.0.1:10000".parse().unwrap()]);
+        assert_eq!(public_keys, vec![public_key]);
+    }
+
+    #[test]
+    fn refuses_invalid_public_key() {
+        let json = r#"{"session_id":0,"peers":[{"address":"127.0.0.1:10000","public_key":"00"}]}"#;
+        let peer_config: PeerConfig = serde_json::from_str(json).expect("valid json");
+        assert!(matches!(
+            peer_config.into_network_config(),
+            Err(ConfigError::Key(0, _))
+        ));
+    }
+}
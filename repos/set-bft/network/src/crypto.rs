@@ -0,0 +1,304 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use blake2::{Blake2s256, Digest};
+use codec::{Decode, Encode};
+use ed25519_dalek::{Signer, SigningKey, VerifyingKey};
+use rand::RngCore;
+use set_bft::{
+    Hasher, Index, Keychain, MultiKeychain, NodeCount, NodeIndex, PartialMultisignature,
+    SignatureSet,
+};
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+};
+
+/// A hasher producing 256-bit Blake2s hashes.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+pub struct Blake2Hasher;
+
+impl Hasher for Blake2Hasher {
+    type Hash = [u8; 32];
+
+    fn hash(x: &[u8]) -> Self::Hash {
+        Blake2s256::digest(x).into()
+    }
+}
+
+/// An ed25519 signature.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug, Encode, Decode)]
+pub struct Signature([u8; 64]);
+
+/// Public key of a committee member.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
+pub struct PublicKey([u8; 32]);
+
+/// Secret key of a committee member.
+#[derive(Clone)]
+pub struct SecretKey([u8; 32]);
+
+/// Errors when parsing keys or creating a keychain.
+#[derive(Debug, PartialEq, Eq)]
+pub enum KeyError {
+    /// The key is not a valid hex string of the right length.
+    Malformed,
+    /// The bytes do not represent a valid ed25519 public key.
+    InvalidPublicKey,
+    /// Our index is outside of the committee.
+    IndexOutOfRange(NodeIndex),
+    /// The secret key does not match the public key listed for our index.
+    KeyMismatch(NodeIndex),
+}
+
+impl Display for KeyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use KeyError::*;
+        match self {
+            Malformed => write!(f, "key is not a hex encoded 32 byte value"),
+            InvalidPublicKey => write!(f, "not a valid ed25519 public key"),
+            IndexOutOfRange(index) => write!(f, "index {} is outside of the committee", index.0),
+            KeyMismatch(index) => write!(
+                f,
+                "secret key does not match the public key of node {}",
+                index.0
+            ),
+        }
+    }
+}
+
+fn parse_hex_key(hex_key: &str) -> Result<[u8; 32], KeyError> {
+    let bytes =
+        hex::decode(hex_key.trim().trim_start_matches("0x")).map_err(|_| KeyError::Malformed)?;
+    bytes.try_into().map_err(|_| KeyError::Malformed)
+}
+
+impl PublicKey {
+    /// Parse a hex encoded public key.
+    pub fn from_hex(hex_key: &str) -> Result<Self, KeyError> {
+        let key = PublicKey(parse_hex_key(hex_key)?);
+        key.verifying_key()?;
+        Ok(key)
+    }
+
+    /// The hex encoding of the key.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    fn verifying_key(&self) -> Result<VerifyingKey, KeyError> {
+        VerifyingKey::from_bytes(&self.0).map_err(|_| KeyError::InvalidPublicKey)
+    }
+}
+
+impl SecretKey {
+    /// Generate a new random secret key.
+    pub fn generate() -> Self {
+        let mut bytes = [0; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        SecretKey(bytes)
+    }
+
+    /// Parse a hex encoded secret key.
+    pub fn from_hex(hex_key: &str) -> Result<Self, KeyError> {
+        parse_hex_key(hex_key).map(SecretKey)
+    }
+
+    /// The hex encoding of the key.
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// The public key corresponding to this secret key.
+    pub fn public_key(&self) -> PublicKey {
+        PublicKey(self.signing_key().verifying_key().to_bytes())
+    }
+
+    fn signing_key(&self) -> SigningKey {
+        SigningKey::from_bytes(&self.0)
+    }
+}
+
+/// A keychain signing with ed25519 keys, one for every committee member.
+#[derive(Clone)]
+pub struct Ed25519Keychain {
+    index: NodeIndex,
+    signing_key: Arc<SigningKey>,
+    verifying_keys: Arc<Vec<VerifyingKey>>,
+}
+
+impl Ed25519Keychain {
+    /// Create a keychain for the member with the given index, `public_keys` have to be ordered by
+    /// the indices of the members.
+    pub fn new(
+        index: NodeIndex,
+        secret_key: &SecretKey,
+        public_keys: &[PublicKey],
+    ) -> Result<Self, KeyError> {
+        match public_keys.get(index.0) {
+            Some(public_key) if *public_key == secret_key.public_key() => (),
+            Some(_) => return Err(KeyError::KeyMismatch(index)),
+            None => return Err(KeyError::IndexOutOfRange(index)),
+        }
+        let verifying_keys = public_keys
+            .iter()
+            .map(PublicKey::verifying_key)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Ed25519Keychain {
+            index,
+            signing_key: Arc::new(secret_key.signing_key()),
+            verifying_keys: Arc::new(verifying_keys),
+        })
+    }
+}
+
+impl Index for Ed25519Keychain {
+    fn index(&self) -> NodeIndex {
+        self.index
+    }
+}
+
+impl Keychain for Ed25519Keychain {
+    type Signature = Signature;
+
+    fn node_count(&self) -> NodeCount {
+        self.verifying_keys.len().into()
+    }
+
+    fn sign(&self, msg: &[u8]) -> Self::Signature {
+        Signature(self.signing_key.sign(msg).to_bytes())
+    }
+
+    fn verify(&self, msg: &[u8], sgn: &Self::Signature, index: NodeIndex) -> bool {
+        match self.verifying_keys.get(index.0) {
+            Some(key) => key
+                .verify_strict(msg, &ed25519_dalek::Signature::from_bytes(&sgn.0))
+                .is_ok(),
+            None => false,
+        }
+    }
+}
+
+impl MultiKeychain for Ed25519Keychain {
+    type PartialMultisignature = SignatureSet<Signature>;
+
+    fn bootstrap_multi(
+        &self,
+        signature: &Self::Signature,
+        index: NodeIndex,
+    ) -> Self::PartialMultisignature {
+        SignatureSet::add_signature(SignatureSet::with_size(self.node_count()), signature, index)
+    }
+
+    fn is_complete(&self, msg: &[u8], partial: &Self::PartialMultisignature) -> bool {
+        let signature_count = partial.iter().count();
+        if signature_count < self.node_count().consensus_threshold().0 {
+            return false;
+        }
+        partial.iter().all(|(i, sgn)| self.verify(msg, sgn, i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Ed25519Keychain, KeyError, PublicKey, SecretKey};
+    use set_bft::{Keychain, MultiKeychain, NodeIndex, PartialMultisignature};
+
+    fn keychains(count: usize) -> Vec<Ed25519Keychain> {
+        let secret_keys: Vec<_> = (0..count).map(|_| SecretKey::generate()).collect();
+        let public_keys: Vec<_> = secret_keys.iter().map(SecretKey::public_key).collect();
+        secret_keys
+            .iter()
+            .enumerate()
+            .map(|(index, secret_key)| {
+                Ed25519Keychain::new(NodeIndex(index), secret_key, &public_keys)
+                    .expect("keys match")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn verifies_signatures_of_the_right_member() {
+        let keychains = keychains(4);
+        let signature = keychains[1].sign(b"message");
+        assert!(keychains[0].verify(b"message", &signature, NodeIndex(1)));
+        assert!(!keychains[0].verify(b"message", &signature, NodeIndex(2)));
+        assert!(!keychains[0].verify(b"other message", &signature, NodeIndex(1)));
+        assert!(!keychains[0].verify(b"message", &signature, NodeIndex(4)));
+    }
+
+    #[test]
+    fn completes_multisignature_at_threshold() {
+        let keychains = keychains(4);
+        let message = b"message";
+        let mut partial = keychains[0].bootstrap_multi(&keychains[0].sign(message), NodeIndex(0));
+        partial = partial.add_signature(&keychains[1].sign(message), NodeIndex(1));
+        assert!(!keychains[0].is_complete(message, &partial));
+        partial = partial.add_signature(&keychains[2].sign(message), NodeIndex(2));
+        assert!(keychains[0].is_complete(message, &partial));
+    }
+
+    #[test]
+    fn keys_survive_hex_encoding() {
+        let secret_key = SecretKey::generate();
+        let public_key = secret_key.public_key();
+        assert_eq!(
+            SecretKey::from_hex(&secret_key.to_hex())
+                .expect("valid key")
+                .public_key(),
+            public_key
+        );
+        assert_eq!(
+            PublicKey::from_hex(&public_key.to_hex()).expect("valid key"),
+            public_key
+        );
+        assert_eq!(PublicKey::from_hex("abcd"), Err(KeyError::Malformed));
+    }
+
+    #[test]
+    fn refuses_mismatched_secret_key() {
+        let public_keys = vec![SecretKey::generate().public_key()];
+        assert_eq!(
+            Ed25519Keychain::new(NodeIndex(0), &SecretKey::generate(), &public_keys).err(),
+            Some(KeyError::KeyMismatch(NodeIndex(0)))
+        );
+        assert_eq!(
+            Ed25519Keychain::new(NodeIndex(1), &SecretKey::generate(), &public_keys).err(),
+            Some(KeyError::IndexOutOfRange(NodeIndex(1)))
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    io,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// The maximal size of a single frame. Units can carry arbitrary data, so this is generous, but
+/// we still refuse to allocate unbounded buffers on behalf of a peer.
+pub const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
+
+/// The maximal size of a frame read before the peer is authenticated. Handshake messages are
+/// tiny, so an unauthenticated peer cannot make us allocate more than this per connection.
+pub const MAX_HANDSHAKE_FRAME_SIZE: usize = 4 * 1024;
+
+/// Errors that can happen while sending or receiving a frame.
+#[derive(Debug)]
+pub enum FrameError {
+    /// The underlying connection failed.
+    Io(io::Error),
+    /// The frame of the given size exceeds the given limit.
+    TooLarge(usize, usize),
+}
+
+impl Display for FrameError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use FrameError::*;
+        match self {
+            Io(e) => write!(f, "connection error: {}", e),
+            TooLarge(size, limit) => write!(
+                f,
+                "frame of {} bytes exceeds the limit of {} bytes",
+                size, limit
+            ),
+        }
+    }
+}
+
+impl From<io::Error> for FrameError {
+    fn from(e: io::Error) -> Self {
+        FrameError::Io(e)
+    }
+}
+
+/// Write a single frame, i.e. the length of the payload as a big-endian `u32` followed by the
+/// payload itself.
+pub async fn write_frame<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    payload: &[u8],
+) -> Result<(), FrameError> {
+    if payload.len() > MAX_FRAME_SIZE {
+        return Err(FrameError::TooLarge(payload.len(), MAX_FRAME_SIZE));
+    }
+    writer.write_u32(payload.len() as u32).await?;
+    writer.write_all(payload).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+/// Read a single frame written by [`write_frame`] and return its payload.
+pub async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> Result<Vec<u8>, FrameError> {
+    read_limited_frame(reader, MAX_FRAME_SIZE).await
+}
+
+/// Read a single frame, refusing frames larger than `limit` before allocating anything.
+pub async fn read_limited_frame<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    limit: usize,
+) -> Result<Vec<u8>, FrameError> {
+    let size = reader.read_u32().await? as usize;
+    if size > limit {
+        return Err(FrameError::TooLarge(size, limit));
+    }
+    let mut payload = vec![0; size];
+    reader.read_exact(&mut payload).await?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        read_frame, read_limited_frame, write_frame, FrameError, MAX_FRAME_SIZE,
+        MAX_HANDSHAKE_FRAME_SIZE,
+    };
+    use tokio::io::{duplex, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn frames_survive_the_round_trip() {
+        let (mut sender, mut receiver) = duplex(1024);
+        let payloads = vec![vec![], vec![7; 10], vec![1; 100_000]];
+        let expected = payloads.clone();
+        tokio::spawn(async move {
+            for payload in payloads {
+                write_frame(&mut sender, &payload)
+                    .await
+                    .expect("the receiver is alive");
+            }
+        });
+        for payload in expected {
+            assert_eq!(
+                read_frame(&mut receiver).await.expect("frame was sent"),
+                payload
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn refuses_too_large_frames() {
+        let (mut sender, mut receiver) = duplex(1024);
+        sender
+            .write_u32(MAX_FRAME_SIZE as u32 + 1)
+            .await
+            .expect("the receiver is alive");
+        assert!(matches!(
+            read_frame(&mut receiver).await,
+            Err(FrameError::TooLarge(..))
+        ));
+    }
+
+    #[tokio::test]
+    async fn refuses_frames_above_the_given_limit() {
+        let (mut sender, mut receiver) = duplex(1024);
+        sender
+            .write_u32(MAX_HANDSHAKE_FRAME_SIZE as u32 + 1)
+            .await
+            .expect("the receiver is alive");
+        assert!(matches!(
+            read_limited_frame(&mut receiver, MAX_HANDSHAKE_FRAME_SIZE).await,
+            Err(FrameError::TooLarge(_, MAX_HANDSHAKE_FRAME_SIZE))
+        ));
+    }
+
+    #[tokio::test]
+    async fn reports_truncated_frames() {
+        let (mut sender, mut receiver) = duplex(1024);
+        sender.write_u32(10).await.expect("the receiver is alive");
+        sender
+            .write_all(&[1, 2, 3])
+            .await
+            .expect("the receiver is alive");
+        drop(sender);
+        assert!(matches!(
+            read_frame(&mut receiver).await,
+            Err(FrameError::Io(_))
+        ));
+    }
+}
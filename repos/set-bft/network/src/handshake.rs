@@ -0,0 +1,257 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::frame::{read_limited_frame, write_frame, FrameError, MAX_HANDSHAKE_FRAME_SIZE};
+use codec::{Decode, Encode};
+use rand::RngCore;
+use set_bft::{Keychain, NodeIndex, SessionId};
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    time::Duration,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+/// How long a peer has to complete the handshake.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+const DOMAIN: &[u8] = b"set-bft-network/handshake/v1";
+
+/// Errors that can occur during the handshake.
+#[derive(Debug)]
+pub enum HandshakeError {
+    /// Reading or writing the stream failed.
+    Frame(FrameError),
+    /// The peer sent something we could not decode.
+    Decode(codec::Error),
+    /// The peer claims an index that is ours or outside of the committee.
+    InvalidIndex(NodeIndex),
+    /// The peer is not the one we dialed.
+    UnexpectedPeer {
+        expected: NodeIndex,
+        actual: NodeIndex,
+    },
+    /// The peer runs a different session.
+    SessionMismatch { ours: SessionId, theirs: SessionId },
+    /// The peer failed to prove it owns the key of the index it claims.
+    BadSignature(NodeIndex),
+    /// The peer did not complete the handshake in time.
+    TimedOut,
+}
+
+impl Display for HandshakeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use HandshakeError::*;
+        match self {
+            Frame(e) => write!(f, "{}", e),
+            Decode(e) => write!(f, "malformed handshake message: {}", e),
+            InvalidIndex(index) => write!(f, "peer claims invalid index {}", index.0),
+            UnexpectedPeer { expected, actual } => write!(
+                f,
+                "expected node {} but node {} answered",
+                expected.0, actual.0
+            ),
+            SessionMismatch { ours, theirs } => {
+                write!(f, "peer runs session {}, we run session {}", theirs, ours)
+            }
+            BadSignature(index) => write!(f, "node {} failed to authenticate", index.0),
+            TimedOut => write!(f, "handshake timed out"),
+        }
+    }
+}
+
+impl From<FrameError> for HandshakeError {
+    fn from(e: FrameError) -> Self {
+        HandshakeError::Frame(e)
+    }
+}
+
+impl From<codec::Error> for HandshakeError {
+    fn from(e: codec::Error) -> Self {
+        HandshakeError::Decode(e)
+    }
+}
+
+#[derive(Encode, Decode)]
+struct Hello {
+    index: u64,
+    session_id: SessionId,
+    challenge: [u8; 32],
+}
+
+fn signed_message(session_id: SessionId, challenge: &[u8; 32], signer: NodeIndex) -> Vec<u8> {
+    let mut message = DOMAIN.to_vec();
+    session_id.encode_to(&mut message);
+    message.extend_from_slice(challenge);
+    (signer.0 as u64).encode_to(&mut message);
+    message
+}
+
+async fn receive<M: Decode, S: AsyncRead + Unpin>(stream: &mut S) -> Result<M, HandshakeError> {
+    // The peer is not authenticated yet, so only small frames are accepted.
+    let frame = read_limited_frame(stream, MAX_HANDSHAKE_FRAME_SIZE).await?;
+    Ok(M::decode(&mut &frame[..])?)
+}
+
+async fn execute<K: Keychain, S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    keychain: &K,
+    session_id: SessionId,
+    expected_peer: Option<NodeIndex>,
+) -> Result<NodeIndex, HandshakeError> {
+    let mut challenge = [0; 32];
+    rand::thread_rng().fill_bytes(&mut challenge);
+    let hello = Hello {
+        index: keychain.index().0 as u64,
+        session_id,
+        challenge,
+    };
+    write_frame(stream, &hello.encode()).await?;
+    let peer_hello: Hello = receive(stream).await?;
+
+    let peer = NodeIndex(peer_hello.index as usize);
+    if peer_hello.index >= keychain.node_count().0 as u64 || peer == keychain.index() {
+        return Err(HandshakeError::InvalidIndex(peer));
+    }
+    if let Some(expected) = expected_peer {
+        if expected != peer {
+            return Err(HandshakeError::UnexpectedPeer {
+                expected,
+                actual: peer,
+            });
+        }
+    }
+    if peer_hello.session_id != session_id {
+        return Err(HandshakeError::SessionMismatch {
+            ours: session_id,
+            theirs: peer_hello.session_id,
+        });
+    }
+
+    let signature = keychain.sign(&signed_message(
+        session_id,
+        &peer_hello.challenge,
+        keychain.index(),
+    ));
+    write_frame(stream, &signature.encode()).await?;
+    let peer_signature: K::Signature = receive(stream).await?;
+    if !keychain.verify(
+        &signed_message(session_id, &challenge, peer),
+        &peer_signature,
+        peer,
+    ) {
+        return Err(HandshakeError::BadSignature(peer));
+    }
+    Ok(peer)
+}
+
+/// Mutually authenticate with the node on the other side of the stream. Both sides exchange
+/// random challenges and sign the challenge of the other side, so a peer cannot impersonate
+/// another committee member or replay an old handshake. If we dialed a specific node,
+/// `expected_peer` should be set to its index.
+///
+/// Returns the index of the authenticated peer.
+pub async fn handshake<K: Keychain, S: AsyncRead + AsyncWrite + Unpin>(
+    stream: &mut S,
+    keychain: &K,
+    session_id: SessionId,
+    expected_peer: Option<NodeIndex>,
+) -> Result<NodeIndex, HandshakeError> {
+    tokio::time::timeout(
+        HANDSHAKE_TIMEOUT,
+        execute(stream, keychain, session_id, expected_peer),
+    )
+    .await
+    .map_err(|_| HandshakeError::TimedOut)?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{handshake, HandshakeError};
+    use set_bft::{NodeCount, NodeIndex};
+    use set_bft_mock::Keychain;
+    use tokio::io::duplex;
+
+    #[tokio::test]
+    async fn authenticates_both_sides() {
+        let (mut left, mut right) = duplex(1024);
+        let left_keychain = Keychain::new(NodeCount(4), NodeIndex(0));
+        let right_keychain = Keychain::new(NodeCount(4), NodeIndex(3));
+        let (left_result, right_result) = tokio::join!(
+            handshake(&mut left, &left_keychain, 7, Some(NodeIndex(3))),
+            handshake(&mut right, &right_keychain, 7, None),
+        );
+        assert_eq!(left_result.expect("handshake succeeds"), NodeIndex(3));
+        assert_eq!(right_result.expect("handshake succeeds"), NodeIndex(0));
+    }
+
+    #[tokio::test]
+    async fn refuses_unexpected_peer() {
+        let (mut left, mut right) = duplex(1024);
+        let left_keychain = Keychain::new(NodeCount(4), NodeIndex(0));
+        let right_keychain = Keychain::new(NodeCount(4), NodeIndex(2));
+        let (left_result, _) = tokio::join!(
+            handshake(&mut left, &left_keychain, 7, Some(NodeIndex(3))),
+            handshake(&mut right, &right_keychain, 7, None),
+        );
+        assert!(matches!(
+            left_result,
+            Err(HandshakeError::UnexpectedPeer {
+                expected: NodeIndex(3),
+                actual: NodeIndex(2)
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn refuses_other_session() {
+        let (mut left, mut right) = duplex(1024);
+        let left_keychain = Keychain::new(NodeCount(4), NodeIndex(0));
+        let right_keychain = Keychain::new(NodeCount(4), NodeIndex(1));
+        let (left_result, right_result) = tokio::join!(
+            handshake(&mut left, &left_keychain, 7, None),
+            handshake(&mut right, &right_keychain, 8, None),
+        );
+        assert!(matches!(
+            left_result,
+            Err(HandshakeError::SessionMismatch { ours: 7, theirs: 8 })
+        ));
+        assert!(matches!(
+            right_result,
+            Err(HandshakeError::SessionMismatch { ours: 8, theirs: 7 })
+        ));
+    }
+}
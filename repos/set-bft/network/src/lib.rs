@@ -0,0 +1,58 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! An authenticated TCP implementation of the [`set_bft::Network`] trait, together with the
+//! keychain and hasher needed to run SetBFT as a standalone atomic broadcast service.
+//!
+//! Messages are sent as length-prefixed frames, so `NetworkData` of any size up to
+//! [`MAX_FRAME_SIZE`] can be exchanged. Peers prove on every connection that they own the key of
+//! the committee member they claim to be.
+
+mod config;
+mod crypto;
+mod frame;
+mod handshake;
+mod network;
+
+pub use config::{ConfigError, PeerConfig, PeerEntry};
+pub use crypto::{Blake2Hasher, Ed25519Keychain, KeyError, PublicKey, SecretKey, Signature};
+pub use frame::{
+    read_frame, read_limited_frame, write_frame, FrameError, MAX_FRAME_SIZE,
+    MAX_HANDSHAKE_FRAME_SIZE,
+};
+pub use handshake::{handshake, HandshakeError, HANDSHAKE_TIMEOUT};
+pub use network::{Config, Network};
@@ -0,0 +1,385 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use async_trait::async_trait;
+use clap::{Parser, Subcommand};
+use futures::channel::oneshot;
+use log::{debug, error, info, warn};
+use set_bft::{
+    create_config, default_delay_config, run_session, BatchFinalizationHandler,
+    BatchingDataProvider, DataBatch, DataProvider as DataProviderT,
+    FinalizationHandler as FinalizationHandlerT, LocalIO, NodeIndex, Round, SignatureSet,
+    SpawnHandle, TaskHandle, Terminator,
+};
+use set_bft_network::{
+    read_frame, write_frame, Blake2Hasher, Ed25519Keychain, Network, PeerConfig, SecretKey,
+    Signature,
+};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    process,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::{
+    fs::{self, File},
+    net::TcpListener,
+    sync::{
+        broadcast::{self, error::RecvError},
+        mpsc,
+    },
+};
+use tokio_util::compat::{Compat, TokioAsyncWriteCompatExt};
+
+const LOG_TARGET: &str = "set-bft-node";
+
+type Batch = DataBatch<Vec<u8>>;
+type NetworkData = set_bft::NetworkData<Blake2Hasher, Batch, Signature, SignatureSet<Signature>>;
+
+/// A standalone SetBFT committee member, ordering arbitrary byte strings.
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a new secret key and print the corresponding public key
+    Keygen {
+        /// File to write the hex encoded secret key to, printed if not given
+        #[clap(long, value_parser)]
+        secret_key_file: Option<PathBuf>,
+    },
+    /// Run a committee member
+    Run(RunArgs),
+}
+
+#[derive(Parser, Debug)]
+struct RunArgs {
+    /// Index of the node in the committee
+    #[clap(long, value_parser)]
+    index: usize,
+
+    /// JSON file listing the addresses and public keys of all the committee members
+    #[clap(long, value_parser)]
+    peers: PathBuf,
+
+    /// File containing the hex encoded secret key of the node
+    #[clap(long, value_parser)]
+    secret_key_file: PathBuf,
+
+    /// Address on which applications can submit items and receive the ordered ones
+    #[clap(long, value_parser)]
+    local_address: SocketAddr,
+
+    /// Directory for the unit backup, used to safely recover after a crash
+    #[clap(long, default_value = "./set-bft-node-backup", value_parser)]
+    backup_path: PathBuf,
+
+    /// Unit creation delay (milliseconds)
+    #[clap(long, default_value = "200", value_parser)]
+    unit_creation_delay: u64,
+
+    /// Last round of the session, after which no more items are ordered
+    #[clap(long, default_value_t = Round::MAX, value_parser)]
+    max_round: Round,
+
+    /// Time for which the session has to keep ordering items (seconds), refused if `max_round`
+    /// is reached earlier with the given unit creation delay
+    #[clap(long, default_value = "3600", value_parser)]
+    session_duration: u64,
+
+    /// Maximal number of items included in a single unit, at most 1024
+    #[clap(long, default_value = "1000", value_parser)]
+    max_batch_size: usize,
+
+    /// Maximal number of submitted items waiting to be included in a unit
+    #[clap(long, default_value = "100000", value_parser)]
+    submit_queue_size: usize,
+}
+
+/// Provides a submitted item, if there is any. Never waits, so that units are created on time
+/// even if nothing is submitted, and batched by `BatchingDataProvider`.
+struct DataProvider {
+    items: mpsc::Receiver<Vec<u8>>,
+}
+
+#[async_trait]
+impl DataProviderT for DataProvider {
+    type Output = Vec<u8>;
+
+    async fn get_data(&mut self) -> Option<Vec<u8>> {
+        self.items.try_recv().ok()
+    }
+}
+
+/// Passes the ordered items to all the connected applications.
+struct FinalizationHandler {
+    finalized: broadcast::Sender<Vec<u8>>,
+}
+
+impl FinalizationHandlerT<Vec<u8>> for FinalizationHandler {
+    fn data_finalized(&mut self, item: Vec<u8>) {
+        // fails only if no application is connected, in which case nobody needs the item
+        let _ = self.finalized.send(item);
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct Spawner;
+
+impl SpawnHandle for Spawner {
+    fn spawn(&self, _name: &str, task: impl Future<Output = ()> + Send + 'static) {
+        tokio::spawn(task);
+    }
+
+    fn spawn_essential(
+        &self,
+        _: &str,
+        task: impl Future<Output = ()> + Send + 'static,
+    ) -> TaskHandle {
+        let (res_tx, res_rx) = oneshot::channel();
+        tokio::spawn(async move {
+            task.await;
+            let _ = res_tx.send(());
+        });
+        Box::pin(async move { res_rx.await.map_err(|_| ()) })
+    }
+}
+
+async fn create_backup(
+    path: &Path,
+    index: NodeIndex,
+) -> Result<(Compat<File>, futures::io::Cursor<Vec<u8>>), std::io::Error> {
+    fs::create_dir_all(path).await?;
+    let file_path = path.join(format!("{}.units", index.0));
+    let loader = if file_path.exists() {
+        futures::io::Cursor::new(fs::read(&file_path).await?)
+    } else {
+        futures::io::Cursor::new(Vec::new())
+    };
+    let saver = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file_path)
+        .await?;
+    Ok((saver.compat_write(), loader))
+}
+
+async fn serve_applications(
+    listener: TcpListener,
+    submitted: mpsc::Sender<Vec<u8>>,
+    finalized: broadcast::Sender<Vec<u8>>,
+) {
+    loop {
+        let (stream, address) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to accept application connection: {}.", e);
+                continue;
+            }
+        };
+        info!(target: LOG_TARGET, "Application {} connected.", address);
+        let (mut reader, mut writer) = stream.into_split();
+        let submitted = submitted.clone();
+        let mut finalized = finalized.subscribe();
+        tokio::spawn(async move {
+            loop {
+                match read_frame(&mut reader).await {
+                    Ok(item) => {
+                        if submitted.send(item).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => {
+                        debug!(target: LOG_TARGET, "Application {} stopped submitting: {}.", address, e);
+                        return;
+                    }
+                }
+            }
+        });
+        tokio::spawn(async move {
+            loop {
+                match finalized.recv().await {
+                    Ok(item) => {
+                        if let Err(e) = write_frame(&mut writer, &item).await {
+                            info!(target: LOG_TARGET, "Application {} disconnected: {}.", address, e);
+                            return;
+                        }
+                    }
+                    Err(RecvError::Lagged(skipped)) => warn!(
+                        target: LOG_TARGET,
+                        "Application {} is too slow, skipped {} ordered items.", address, skipped
+                    ),
+                    Err(RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+}
+
+fn keygen(secret_key_file: Option<PathBuf>) -> Result<(), String> {
+    let secret_key = SecretKey::generate();
+    match secret_key_file {
+        Some(path) => std::fs::write(&path, secret_key.to_hex())
+            .map_err(|e| format!("cannot write {}: {}", path.display(), e))?,
+        None => println!("secret key: {}", secret_key.to_hex()),
+    }
+    println!("public key: {}", secret_key.public_key().to_hex());
+    Ok(())
+}
+
+async fn run(args: RunArgs) -> Result<(), String> {
+    let RunArgs {
+        index,
+        peers,
+        secret_key_file,
+        local_address,
+        backup_path,
+        unit_creation_delay,
+        max_round,
+        session_duration,
+        max_batch_size,
+        submit_queue_size,
+    } = args;
+    let index = NodeIndex(index);
+
+    let (network_config, public_keys) = PeerConfig::load(&peers)
+        .and_then(PeerConfig::into_network_config)
+        .map_err(|e| e.to_string())?;
+    let secret_key = std::fs::read_to_string(&secret_key_file)
+        .map_err(|e| format!("cannot read {}: {}", secret_key_file.display(), e))?;
+    let secret_key = SecretKey::from_hex(&secret_key).map_err(|e| e.to_string())?;
+    let keychain =
+        Ed25519Keychain::new(index, &secret_key, &public_keys).map_err(|e| e.to_string())?;
+    let n_members = public_keys.len().into();
+    let session_id = network_config.session_id;
+
+    info!(target: LOG_TARGET, "Getting network up.");
+    let network: Network<NetworkData> = Network::new(network_config, keychain.clone())
+        .await
+        .map_err(|e| format!("cannot start network: {}", e))?;
+
+    let applications = TcpListener::bind(local_address)
+        .await
+        .map_err(|e| format!("cannot listen on {}: {}", local_address, e))?;
+    let (submitted_tx, submitted_rx) = mpsc::channel(submit_queue_size);
+    let (finalized_tx, _) = broadcast::channel(submit_queue_size);
+    tokio::spawn(serve_applications(
+        applications,
+        submitted_tx,
+        finalized_tx.clone(),
+    ));
+
+    let data_provider = BatchingDataProvider::new(
+        DataProvider {
+            items: submitted_rx,
+        },
+        max_batch_size,
+    );
+    let finalization_handler = BatchFinalizationHandler::new(FinalizationHandler {
+        finalized: finalized_tx,
+    });
+    let (backup_saver, backup_loader) = create_backup(&backup_path, index)
+        .await
+        .map_err(|e| format!("cannot set up unit backup: {}", e))?;
+    let local_io = LocalIO::new(
+        data_provider,
+        finalization_handler,
+        backup_saver,
+        backup_loader,
+    );
+
+    let mut delay_config = default_delay_config();
+    delay_config.unit_creation_delay =
+        Arc::new(move |_| Duration::from_millis(unit_creation_delay));
+    let config = create_config(
+        n_members,
+        index,
+        session_id,
+        max_round,
+        delay_config,
+        Duration::from_secs(session_duration),
+    )
+    .map_err(|_| "invalid consensus config".to_string())?;
+    info!(
+        target: LOG_TARGET,
+        "Session {} orders items for about {}s, until round {}.",
+        session_id,
+        unit_creation_delay.saturating_mul(max_round.into()) / 1000,
+        max_round
+    );
+
+    let (exit_tx, exit_rx) = oneshot::channel();
+    let member_terminator = Terminator::create_root(exit_rx, "SetBFT-member");
+    let member_handle = tokio::spawn(run_session(
+        config,
+        local_io,
+        network,
+        keychain,
+        Spawner,
+        member_terminator,
+    ));
+    info!(target: LOG_TARGET, "Node {} running, applications can connect to {}.", index.0, local_address);
+
+    if let Err(e) = tokio::signal::ctrl_c().await {
+        error!(target: LOG_TARGET, "Cannot listen for the shutdown signal: {}.", e);
+    }
+    info!(target: LOG_TARGET, "Shutting down.");
+    let _ = exit_tx.send(());
+    member_handle
+        .await
+        .map_err(|e| format!("member task failed: {}", e))
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let result = match Cli::parse().command {
+        Command::Keygen { secret_key_file } => keygen(secret_key_file),
+        Command::Run(args) => run(args).await,
+    };
+    if let Err(e) = result {
+        error!(target: LOG_TARGET, "{}", e);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,434 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    frame::{read_frame, write_frame},
+    handshake::handshake,
+};
+use codec::{Decode, Encode};
+use log::{debug, info, warn};
+use set_bft::{Keychain, NodeIndex, Recipient, SessionId};
+use std::{cmp::min, future::pending, io, net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{
+    io::BufWriter,
+    net::{
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+        TcpListener, TcpStream,
+    },
+    sync::{
+        mpsc::{self, error::TrySendError},
+        watch,
+    },
+    task::JoinHandle,
+    time::{sleep, timeout, Instant},
+};
+
+const LOG_TARGET: &str = "SetBFT-network";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const MIN_RECONNECT_DELAY: Duration = Duration::from_millis(100);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(10);
+
+/// Configuration of the network of a single committee member.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Addresses of all the committee members, ordered by their indices.
+    pub addresses: Vec<SocketAddr>,
+    /// The session the committee runs, peers running other sessions are refused.
+    pub session_id: SessionId,
+    /// How many messages can wait to be sent to a single peer, further messages are dropped.
+    pub send_queue_size: usize,
+    /// How many received messages can wait to be processed before we stop reading from peers.
+    pub receive_queue_size: usize,
+}
+
+impl Config {
+    pub fn new(addresses: Vec<SocketAddr>, session_id: SessionId) -> Self {
+        Config {
+            addresses,
+            session_id,
+            send_queue_size: 1024,
+            receive_queue_size: 4096,
+        }
+    }
+}
+
+/// An implementation of [`set_bft::Network`] over authenticated TCP connections.
+///
+/// Every pair of members keeps a single connection, dialed by the member with the lower index and
+/// reestablished whenever it breaks. Messages for a peer are queued in a bounded queue and dropped
+/// when it is full, which SetBFT tolerates, while received messages are read only as fast as they
+/// are processed, so a slow consumer slows down the senders instead of buffering without bounds.
+pub struct Network<D> {
+    peers: Vec<Option<mpsc::Sender<D>>>,
+    incoming: mpsc::Receiver<D>,
+    _exit: watch::Sender<()>,
+}
+
+impl<D: Clone + Encode + Decode + Send + 'static> Network<D> {
+    /// Bind to the address of our index and start connecting to the other members.
+    pub async fn new<K: Keychain>(config: Config, keychain: K) -> io::Result<Self> {
+        let address = *config.addresses.get(keychain.index().0).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "no address for our index")
+        })?;
+        let listener = TcpListener::bind(address).await?;
+        Ok(Self::with_listener(config, keychain, listener))
+    }
+
+    /// Start the network accepting connections on an already bound listener, useful when binding
+    /// to an ephemeral port. Has to be called within a tokio runtime.
+    pub fn with_listener<K: Keychain>(config: Config, keychain: K, listener: TcpListener) -> Self {
+        let own_index = keychain.index();
+        let (incoming_for_peers, incoming) = mpsc::channel(config.receive_queue_size);
+        let (exit_tx, exit_rx) = watch::channel(());
+        let mut peers = Vec::new();
+        let mut accepted = Vec::new();
+        for (index, address) in config.addresses.iter().enumerate() {
+            let peer = NodeIndex(index);
+            if peer == own_index {
+                peers.push(None);
+                accepted.push(None);
+                continue;
+            }
+            let (outgoing_tx, outgoing_rx) = mpsc::channel(config.send_queue_size);
+            let (accepted_tx, accepted_rx) = mpsc::channel(1);
+            peers.push(Some(outgoing_tx));
+            accepted.push(Some(accepted_tx));
+            let connection = PeerConnection {
+                peer,
+                address: *address,
+                dialer: own_index < peer,
+                keychain: keychain.clone(),
+                session_id: config.session_id,
+                outgoing: outgoing_rx,
+                accepted: accepted_rx,
+                incoming: incoming_for_peers.clone(),
+                exit: exit_rx.clone(),
+            };
+            tokio::spawn(connection.run());
+        }
+        tokio::spawn(listen(
+            listener,
+            keychain,
+            config.session_id,
+            Arc::new(accepted),
+            exit_rx,
+        ));
+        Network {
+            peers,
+            incoming,
+            _exit: exit_tx,
+        }
+    }
+
+    fn send_to(&self, data: D, node: NodeIndex) {
+        let Some(Some(peer)) = self.peers.get(node.0) else {
+            debug!(target: LOG_TARGET, "Not sending a message to unknown or own node {}.", node.0);
+            return;
+        };
+        match peer.try_send(data) {
+            Ok(()) => (),
+            Err(TrySendError::Full(_)) => {
+                debug!(target: LOG_TARGET, "Send queue of node {} full, dropping message.", node.0)
+            }
+            Err(TrySendError::Closed(_)) => {
+                debug!(target: LOG_TARGET, "Connection task of node {} stopped.", node.0)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<D: Clone + Encode + Decode + Send + 'static> set_bft::Network<D> for Network<D> {
+    fn send(&self, data: D, recipient: Recipient) {
+        match recipient {
+            Recipient::Node(node) => self.send_to(data, node),
+            Recipient::Everyone => {
+                for (index, peer) in self.peers.iter().enumerate() {
+                    if peer.is_some() {
+                        self.send_to(data.clone(), NodeIndex(index));
+                    }
+                }
+            }
+        }
+    }
+
+    async fn next_event(&mut self) -> Option<D> {
+        self.incoming.recv().await
+    }
+}
+
+async fn listen<K: Keychain>(
+    listener: TcpListener,
+    keychain: K,
+    session_id: SessionId,
+    accepted: Arc<Vec<Option<mpsc::Sender<TcpStream>>>>,
+    mut exit: watch::Receiver<()>,
+) {
+    loop {
+        tokio::select! {
+            _ = exit.changed() => return,
+            result = listener.accept() => match result {
+                Ok((mut stream, address)) => {
+                    let keychain = keychain.clone();
+                    let accepted = accepted.clone();
+                    tokio::spawn(async move {
+                        match handshake(&mut stream, &keychain, session_id, None).await {
+                            Ok(peer) => {
+                                if let Some(Some(peer_connection)) = accepted.get(peer.0) {
+                                    let _ = peer_connection.send(stream).await;
+                                }
+                            }
+                            Err(e) => debug!(
+                                target: LOG_TARGET,
+                                "Refused connection from {}: {}.", address, e
+                            ),
+                        }
+                    });
+                }
+                Err(e) => {
+                    warn!(target: LOG_TARGET, "Failed to accept connection: {}.", e);
+                    sleep(MIN_RECONNECT_DELAY).await;
+                }
+            },
+        }
+    }
+}
+
+async fn read_messages<D: Decode>(
+    mut reader: OwnedReadHalf,
+    peer: NodeIndex,
+    incoming: mpsc::Sender<D>,
+) {
+    loop {
+        let frame = match read_frame(&mut reader).await {
+            Ok(frame) => frame,
+            Err(e) => {
+                debug!(target: LOG_TARGET, "Connection with node {} closed: {}.", peer.0, e);
+                return;
+            }
+        };
+        match D::decode(&mut &frame[..]) {
+            Ok(data) => {
+                if incoming.send(data).await.is_err() {
+                    return;
+                }
+            }
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Node {} sent a malformed message: {}.", peer.0, e);
+                return;
+            }
+        }
+    }
+}
+
+struct Connection {
+    writer: BufWriter<OwnedWriteHalf>,
+    reader: JoinHandle<()>,
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+async fn closed(connection: &mut Option<Connection>) {
+    match connection {
+        Some(connection) => {
+            let _ = (&mut connection.reader).await;
+        }
+        None => pending().await,
+    }
+}
+
+struct PeerConnection<K: Keychain, D> {
+    peer: NodeIndex,
+    address: SocketAddr,
+    dialer: bool,
+    keychain: K,
+    session_id: SessionId,
+    outgoing: mpsc::Receiver<D>,
+    accepted: mpsc::Receiver<TcpStream>,
+    incoming: mpsc::Sender<D>,
+    exit: watch::Receiver<()>,
+}
+
+impl<K: Keychain, D: Encode + Decode + Send + 'static> PeerConnection<K, D> {
+    async fn dial(&self) -> Result<TcpStream, String> {
+        let mut stream = timeout(CONNECT_TIMEOUT, TcpStream::connect(self.address))
+            .await
+            .map_err(|_| "connecting timed out".to_string())?
+            .map_err(|e| e.to_string())?;
+        handshake(
+            &mut stream,
+            &self.keychain,
+            self.session_id,
+            Some(self.peer),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(stream)
+    }
+
+    fn connect(&self, stream: TcpStream) -> Connection {
+        if let Err(e) = stream.set_nodelay(true) {
+            debug!(target: LOG_TARGET, "Failed to disable Nagle's algorithm: {}.", e);
+        }
+        let (reader, writer) = stream.into_split();
+        Connection {
+            writer: BufWriter::new(writer),
+            reader: tokio::spawn(read_messages(reader, self.peer, self.incoming.clone())),
+        }
+    }
+
+    async fn run(mut self) {
+        let mut connection = None;
+        let mut reconnect_delay = MIN_RECONNECT_DELAY;
+        let reconnect = sleep(Duration::ZERO);
+        tokio::pin!(reconnect);
+        loop {
+            let connected = connection.is_some();
+            tokio::select! {
+                _ = self.exit.changed() => return,
+                stream = self.accepted.recv() => match stream {
+                    Some(stream) => {
+                        info!(target: LOG_TARGET, "Node {} connected.", self.peer.0);
+                        connection = Some(self.connect(stream));
+                    }
+                    None => return,
+                },
+                _ = &mut reconnect, if !connected && self.dialer => match self.dial().await {
+                    Ok(stream) => {
+                        info!(target: LOG_TARGET, "Connected to node {}.", self.peer.0);
+                        connection = Some(self.connect(stream));
+                        reconnect_delay = MIN_RECONNECT_DELAY;
+                    }
+                    Err(e) => {
+                        debug!(target: LOG_TARGET, "Failed to connect to node {}: {}.", self.peer.0, e);
+                        reconnect.as_mut().reset(Instant::now() + reconnect_delay);
+                        reconnect_delay = min(reconnect_delay * 2, MAX_RECONNECT_DELAY);
+                    }
+                },
+                _ = closed(&mut connection), if connected => {
+                    info!(target: LOG_TARGET, "Lost connection with node {}.", self.peer.0);
+                    connection = None;
+                    reconnect.as_mut().reset(Instant::now() + reconnect_delay);
+                },
+                data = self.outgoing.recv(), if connected => match data {
+                    Some(data) => {
+                        let writer = &mut connection.as_mut().expect("we are connected").writer;
+                        if let Err(e) = write_frame(writer, &data.encode()).await {
+                            info!(target: LOG_TARGET, "Lost connection with node {}: {}.", self.peer.0, e);
+                            connection = None;
+                            reconnect.as_mut().reset(Instant::now() + reconnect_delay);
+                        }
+                    }
+                    None => return,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, Network};
+    use set_bft::{Network as _, NodeCount, NodeIndex, Recipient};
+    use set_bft_mock::Keychain;
+    use std::time::Duration;
+    use tokio::{net::TcpListener, time::timeout};
+
+    async fn networks(node_count: usize) -> Vec<Network<Vec<u8>>> {
+        let mut listeners = Vec::new();
+        for _ in 0..node_count {
+            listeners.push(TcpListener::bind("127.0.0.1:0").await.expect("can bind"));
+        }
+        let addresses: Vec<_> = listeners
+            .iter()
+            .map(|listener| listener.local_addr().expect("is bound"))
+            .collect();
+        listeners
+            .into_iter()
+            .enumerate()
+            .map(|(index, listener)| {
+                Network::with_listener(
+                    Config::new(addresses.clone(), 0),
+                    Keychain::new(NodeCount(node_count), NodeIndex(index)),
+                    listener,
+                )
+            })
+            .collect()
+    }
+
+    async fn receive(network: &mut Network<Vec<u8>>) -> Vec<u8> {
+        timeout(Duration::from_secs(10), network.next_event())
+            .await
+            .expect("message arrives in time")
+            .expect("network is running")
+    }
+
+    #[tokio::test]
+    async fn delivers_messages_between_all_nodes() {
+        let mut networks = networks(3).await;
+        for sender in 0..3 {
+            for recipient in 0..3 {
+                if sender != recipient {
+                    networks[sender]
+                        .send(vec![sender as u8], Recipient::Node(NodeIndex(recipient)));
+                }
+            }
+        }
+        for (recipient, network) in networks.iter_mut().enumerate() {
+            let mut received = vec![receive(network).await, receive(network).await];
+            received.sort();
+            let expected: Vec<_> = (0..3u8)
+                .filter(|sender| *sender as usize != recipient)
+                .map(|sender| vec![sender])
+                .collect();
+            assert_eq!(received, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn broadcasts_large_messages() {
+        let mut networks = networks(3).await;
+        let message = vec![7; 1024 * 1024];
+        networks[0].send(message.clone(), Recipient::Everyone);
+        assert_eq!(receive(&mut networks[1]).await, message);
+        assert_eq!(receive(&mut networks[2]).await, message);
+    }
+}
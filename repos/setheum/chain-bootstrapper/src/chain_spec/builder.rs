@@ -18,18 +18,18 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use std::string::ToString;
+use std::{collections::BTreeMap, string::ToString};
 
 use pallet_staking::{Forcing, StakerStatus};
 use primitives::{
 	staking::{MIN_NOMINATOR_BOND, MIN_VALIDATOR_BOND},
-	AccountId, SetheumNodeSessionKeys, Version as FinalityVersion, ADDRESSES_ENCODING, TOKEN_DECIMALS,
+	AccountId, Balance, SetheumNodeSessionKeys, Version as FinalityVersion, ADDRESSES_ENCODING, TOKEN_DECIMALS,
 };
 use serde_json::{Number, Value};
 use setheum_runtime::WASM_BINARY;
 use sp_runtime::Perbill;
 
-use crate::chain_spec::{cli::ChainSpecParams, keystore::AccountSessionKeys, SetheumChainSpec};
+use crate::chain_spec::{cli::ChainSpecParams, defi::DefiGenesis, keystore::AccountSessionKeys, SetheumChainSpec};
 
 fn to_account_ids(authorities: &[AccountSessionKeys]) -> impl Iterator<Item = AccountId> + '_ {
 	authorities.iter().map(|auth| auth.account_id.clone())
@@ -55,6 +55,7 @@ pub fn build_chain_spec(
 	let sudo_account = chain_params.sudo_account_id();
 	let rich_accounts = chain_params.rich_account_ids();
 	let finality_version = chain_params.finality_version();
+	let defi_genesis = chain_params.defi_genesis()?;
 
 	Ok(SetheumChainSpec::builder(WASM_BINARY.ok_or("Setheum development wasm not available")?, Default::default())
 		.with_name(chain_params.chain_name())
@@ -65,7 +66,8 @@ pub fn build_chain_spec(
 			sudo_account,
 			rich_accounts,
 			finality_version,
-		))
+			defi_genesis,
+		)?)
 		.with_properties(system_properties(token_symbol))
 		.build())
 }
@@ -82,7 +84,8 @@ fn generate_genesis_config(
 	sudo_account: AccountId,
 	rich_accounts: Option<Vec<AccountId>>,
 	finality_version: FinalityVersion,
-) -> serde_json::Value {
+	defi_genesis: Option<DefiGenesis>,
+) -> Result<serde_json::Value, String> {
 	let mut endowed_accounts = to_account_ids(&account_session_keys)
 		.chain(rich_accounts.unwrap_or_default().into_iter().chain([sudo_account.clone()]))
 		.collect::<Vec<_>>();
@@ -90,12 +93,17 @@ fn generate_genesis_config(
 	endowed_accounts.dedup();
 	let initial_endowement = calculate_initial_endowment(&endowed_accounts);
 
-	serde_json::json!({
+	let mut balances: BTreeMap<AccountId, Balance> =
+		endowed_accounts.into_iter().map(|account| (account, initial_endowement)).collect();
+	let defi_genesis = defi_genesis.unwrap_or_default();
+	for (account, amount) in defi_genesis.native_endowments() {
+		*balances.entry(account).or_default() += amount;
+	}
+	defi_genesis.validate(&balances)?;
+
+	let mut genesis = serde_json::json!({
 		"balances": {
-			"balances": endowed_accounts
-						.into_iter()
-						.map(|account| (account, initial_endowement))
-						.collect::<Vec<_>>(),
+			"balances": balances.into_iter().collect::<Vec<_>>(),
 		},
 		"sudo": {
 			"key": Some(sudo_account),
@@ -146,7 +154,12 @@ fn generate_genesis_config(
 				"committee": to_account_ids(&account_session_keys).collect::<Vec<_>>(),
 			},
 		},
-	})
+	});
+	if let Value::Object(genesis) = &mut genesis {
+		genesis.extend(defi_genesis.genesis_config_patch()?);
+	}
+
+	Ok(genesis)
 }
 
 pub fn build_chain_spec_json(
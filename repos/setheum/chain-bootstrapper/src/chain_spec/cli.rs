@@ -18,12 +18,14 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use std::path::PathBuf;
+
 use primitives::{AccountId, Version as FinalityVersion, CURRENT_FINALITY_VERSION, LEGACY_FINALITY_VERSION};
 use sc_chain_spec::ChainType;
 use sc_cli::clap::{self, Args};
 
 use crate::chain_spec::{
-	parse_account_id, parse_chaintype, CHAINTYPE_LIVE, DEFAULT_CHAIN_ID, DEFAULT_SUDO_ACCOUNT_ALICE,
+	defi::DefiGenesis, parse_account_id, parse_chaintype, CHAINTYPE_LIVE, DEFAULT_CHAIN_ID, DEFAULT_SUDO_ACCOUNT_ALICE,
 };
 
 #[derive(Debug, Args, Clone)]
@@ -65,6 +67,14 @@ pub struct ChainSpecParams {
 	/// Finality version at chain inception.
 	#[arg(long, default_value = "legacy")]
 	finality_version: String,
+
+	/// Named genesis of the DeFi modules. Possible values: "dev-defi", "local-testnet", "benchmark"
+	#[arg(long, value_name = "PRESET", conflicts_with = "genesis_config")]
+	genesis_preset: Option<String>,
+
+	/// Path to a TOML or JSON genesis of the DeFi modules, in the same format as the presets
+	#[arg(long, value_name = "PATH")]
+	genesis_config: Option<PathBuf>,
 }
 
 impl ChainSpecParams {
@@ -108,4 +118,12 @@ impl ChainSpecParams {
 		}
 		.into()
 	}
+
+	pub fn defi_genesis(&self) -> Result<Option<DefiGenesis>, String> {
+		match (&self.genesis_preset, &self.genesis_config) {
+			(Some(preset), _) => DefiGenesis::preset(preset).map(Some),
+			(None, Some(path)) => DefiGenesis::from_file(path).map(Some),
+			(None, None) => Ok(None),
+		}
+	}
 }
@@ -0,0 +1,386 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Declarative genesis of the DeFi modules: tokens in use, endowments, trading pairs, liquidity
+//! pools and oracle operators. It is read from a named preset or from a TOML or JSON file,
+//! validated against the native endowments and turned into a genesis config patch. The runtime has
+//! no asset registry and no CDP engine yet, so there is nothing to configure for them.
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fs,
+	path::Path,
+};
+
+use primitives::{currency::TokenInfo, AccountId, Balance, BlockNumber, CurrencyId, TokenSymbol, TradingPair};
+use serde::Deserialize;
+use serde_json::Value;
+
+pub const PRESET_DEV_DEFI: &str = "dev-defi";
+pub const PRESET_LOCAL_TESTNET: &str = "local-testnet";
+pub const PRESET_BENCHMARK: &str = "benchmark";
+
+/// The native token is endowed through `balances`, every other token through `tokens`.
+const NATIVE_TOKEN: TokenSymbol = TokenSymbol::SEU;
+
+/// DeFi part of the genesis. All amounts are whole tokens, scaled by the token decimals, since
+/// TOML integers cannot hold raw balances.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct DefiGenesis {
+	/// Tokens in use at genesis, besides the native one. Tokens are a fixed set in the runtime, which
+	/// has no asset registry, so the list only serves to check the rest of the description.
+	#[serde(default)]
+	pub assets: Vec<TokenSymbol>,
+	/// Initial balances, native ones are added on top of the validator endowments.
+	#[serde(default)]
+	pub endowments: Vec<Endowment>,
+	/// Trading pairs listed at genesis.
+	#[serde(default)]
+	pub trading_pairs: Vec<TradingPairConfig>,
+	/// Liquidity added to enabled trading pairs at genesis.
+	#[serde(default)]
+	pub liquidity: Vec<LiquidityConfig>,
+	/// Members of the Setheum oracle operator set.
+	#[serde(default)]
+	pub oracle_members: Vec<AccountId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Endowment {
+	pub account: AccountId,
+	pub token: TokenSymbol,
+	pub amount: u64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct TradingPairConfig {
+	pub pair: (TokenSymbol, TokenSymbol),
+	/// The pair starts in provisioning with these parameters, it is enabled right away if omitted.
+	pub provisioning: Option<ProvisioningConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ProvisioningConfig {
+	pub min_contribution: (u64, u64),
+	pub target_provision: (u64, u64),
+	pub not_before: BlockNumber,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct LiquidityConfig {
+	pub provider: AccountId,
+	pub pair: (TokenSymbol, TokenSymbol),
+	pub amounts: (u64, u64),
+}
+
+fn to_balance(token: TokenSymbol, amount: u64) -> Balance {
+	let decimals = CurrencyId::Token(token).decimals().unwrap_or_default();
+	Balance::from(amount) * 10u128.pow(decimals.into())
+}
+
+/// Returns the trading pair and the given amounts in the pair order.
+fn ordered_pair(
+	(token_a, token_b): (TokenSymbol, TokenSymbol),
+	(amount_a, amount_b): (u64, u64),
+) -> Result<(TradingPair, (Balance, Balance)), String> {
+	let pair = TradingPair::from_currency_ids(CurrencyId::Token(token_a), CurrencyId::Token(token_b))
+		.ok_or(format!("Trading pair {token_a:?}/{token_b:?} must consist of two different tokens"))?;
+	let amounts = (to_balance(token_a, amount_a), to_balance(token_b, amount_b));
+	Ok(if pair.first() == CurrencyId::Token(token_a) { (pair, amounts) } else { (pair, (amounts.1, amounts.0)) })
+}
+
+impl DefiGenesis {
+	/// Loads one of the named presets shipped with the bootstrapper.
+	pub fn preset(name: &str) -> Result<Self, String> {
+		let preset = match name {
+			PRESET_DEV_DEFI => include_str!("../../presets/dev-defi.toml"),
+			PRESET_LOCAL_TESTNET => include_str!("../../presets/local-testnet.toml"),
+			PRESET_BENCHMARK => include_str!("../../presets/benchmark.toml"),
+			_ => {
+				return Err(format!(
+					"Unknown genesis preset {name}. Possible values: {PRESET_DEV_DEFI} {PRESET_LOCAL_TESTNET} {PRESET_BENCHMARK}"
+				))
+			},
+		};
+		toml::from_str(preset).map_err(|e| format!("Genesis preset {name} is malformed: {e}"))
+	}
+
+	/// Loads a genesis description from a `.json` file, any other file is read as TOML.
+	pub fn from_file(path: &Path) -> Result<Self, String> {
+		let content = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+		match path.extension().and_then(|extension| extension.to_str()) {
+			Some("json") => serde_json::from_str(&content).map_err(|e| e.to_string()),
+			_ => toml::from_str(&content).map_err(|e| e.to_string()),
+		}
+		.map_err(|e| format!("Cannot parse {}: {e}", path.display()))
+	}
+
+	/// Native endowments requested on top of the validator ones.
+	pub fn native_endowments(&self) -> impl Iterator<Item = (AccountId, Balance)> + '_ {
+		self.endowments
+			.iter()
+			.filter(|endowment| endowment.token == NATIVE_TOKEN)
+			.map(|endowment| (endowment.account.clone(), to_balance(endowment.token, endowment.amount)))
+	}
+
+	fn is_registered(&self, token: TokenSymbol) -> bool {
+		token == NATIVE_TOKEN || self.assets.contains(&token)
+	}
+
+	/// Checks the description is consistent, `native_balances` being the final native endowments
+	/// of the chain, including the ones from [`Self::native_endowments`].
+	pub fn validate(&self, native_balances: &BTreeMap<AccountId, Balance>) -> Result<(), String> {
+		for (idx, asset) in self.assets.iter().enumerate() {
+			if *asset == NATIVE_TOKEN {
+				return Err(format!("{asset:?} is the native token and is always registered"));
+			}
+			if self.assets[..idx].contains(asset) {
+				return Err(format!("Asset {asset:?} is registered twice"));
+			}
+		}
+
+		let mut endowed = BTreeSet::new();
+		for endowment in &self.endowments {
+			if !self.is_registered(endowment.token) {
+				return Err(format!(
+					"Endowment of {} uses unregistered token {:?}",
+					endowment.account, endowment.token
+				));
+			}
+			if endowment.amount == 0 {
+				return Err(format!("Endowment of {} in {:?} is zero", endowment.account, endowment.token));
+			}
+			if !endowed.insert((endowment.account.clone(), endowment.token)) {
+				return Err(format!("{} is endowed with {:?} twice", endowment.account, endowment.token));
+			}
+		}
+		let mut balances: BTreeMap<(AccountId, TokenSymbol), Balance> = self
+			.endowments
+			.iter()
+			.filter(|endowment| endowment.token != NATIVE_TOKEN)
+			.map(|endowment| {
+				((endowment.account.clone(), endowment.token), to_balance(endowment.token, endowment.amount))
+			})
+			.chain(native_balances.iter().map(|(account, balance)| ((account.clone(), NATIVE_TOKEN), *balance)))
+			.collect();
+
+		let mut enabled_pairs = Vec::new();
+		let mut listed_pairs = Vec::new();
+		for config in &self.trading_pairs {
+			let (token_a, token_b) = config.pair;
+			for token in [token_a, token_b] {
+				if !self.is_registered(token) {
+					return Err(format!(
+						"Trading pair {token_a:?}/{token_b:?} references unregistered token {token:?}"
+					));
+				}
+			}
+			let (pair, _) = ordered_pair(config.pair, (0, 0))?;
+			if listed_pairs.contains(&pair) {
+				return Err(format!("Trading pair {token_a:?}/{token_b:?} is listed twice"));
+			}
+			listed_pairs.push(pair);
+			match &config.provisioning {
+				Some(provisioning) if provisioning.target_provision.0 == 0 || provisioning.target_provision.1 == 0 => {
+					return Err(format!("Trading pair {token_a:?}/{token_b:?} has an empty target provision"))
+				},
+				Some(_) => {},
+				None => enabled_pairs.push(pair),
+			}
+		}
+
+		for liquidity in &self.liquidity {
+			let (token_a, token_b) = liquidity.pair;
+			let (pair, _) = ordered_pair(liquidity.pair, (0, 0))?;
+			if !enabled_pairs.contains(&pair) {
+				return Err(format!(
+					"Liquidity is added to {token_a:?}/{token_b:?}, which is not an enabled trading pair"
+				));
+			}
+			for (token, amount) in [(token_a, liquidity.amounts.0), (token_b, liquidity.amounts.1)] {
+				let balance = balances.entry((liquidity.provider.clone(), token)).or_default();
+				*balance = balance
+					.checked_sub(to_balance(token, amount))
+					.ok_or(format!("Liquidity provider {} does not hold enough {token:?}", liquidity.provider))?;
+			}
+		}
+
+		for (idx, member) in self.oracle_members.iter().enumerate() {
+			if self.oracle_members[..idx].contains(member) {
+				return Err(format!("Oracle member {member} is listed twice"));
+			}
+			if native_balances.get(member).copied().unwrap_or_default() == 0 {
+				return Err(format!("Oracle member {member} is not funded"));
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Genesis config patch of `tokens`, `dex` and `operatorMembershipSetheum`.
+	pub fn genesis_config_patch(&self) -> Result<serde_json::Map<String, Value>, String> {
+		let token_balances = self
+			.endowments
+			.iter()
+			.filter(|endowment| endowment.token != NATIVE_TOKEN)
+			.map(|endowment| {
+				(
+					endowment.account.clone(),
+					CurrencyId::Token(endowment.token),
+					to_balance(endowment.token, endowment.amount),
+				)
+			})
+			.collect::<Vec<_>>();
+
+		let mut listing_pairs = Vec::new();
+		let mut enabled_pairs = Vec::new();
+		for config in &self.trading_pairs {
+			match &config.provisioning {
+				Some(provisioning) => {
+					let (pair, min_contribution) = ordered_pair(config.pair, provisioning.min_contribution)?;
+					let (_, target_provision) = ordered_pair(config.pair, provisioning.target_provision)?;
+					listing_pairs.push((pair, min_contribution, target_provision, provisioning.not_before));
+				},
+				None => enabled_pairs.push(ordered_pair(config.pair, (0, 0))?.0),
+			}
+		}
+
+		let mut pools: BTreeMap<AccountId, Vec<(TradingPair, (Balance, Balance))>> = BTreeMap::new();
+		for liquidity in &self.liquidity {
+			pools.entry(liquidity.provider.clone()).or_default().push(ordered_pair(liquidity.pair, liquidity.amounts)?);
+		}
+
+		let mut patch = serde_json::Map::new();
+		if !token_balances.is_empty() {
+			patch.insert("tokens".to_string(), serde_json::json!({ "balances": token_balances }));
+		}
+		if !self.trading_pairs.is_empty() {
+			patch.insert(
+				"dex".to_string(),
+				serde_json::json!({
+					"initialListingTradingPairs": listing_pairs,
+					"initialEnabledTradingPairs": enabled_pairs,
+					"initialAddedLiquidityPools": pools.into_iter().collect::<Vec<_>>(),
+				}),
+			);
+		}
+		if !self.oracle_members.is_empty() {
+			patch
+				.insert("operatorMembershipSetheum".to_string(), serde_json::json!({ "members": self.oracle_members }));
+		}
+		Ok(patch)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use setheum_runtime::GenesisConfig;
+	use sp_core::crypto::Ss58Codec;
+
+	use super::*;
+
+	const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+	const BOB: &str = "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty";
+
+	fn account(address: &str) -> AccountId {
+		AccountId::from_ss58check(address).unwrap()
+	}
+
+	fn native_balances(genesis: &DefiGenesis) -> BTreeMap<AccountId, Balance> {
+		let mut balances = BTreeMap::from([(account(ALICE), to_balance(NATIVE_TOKEN, 100_000_000))]);
+		for (account, amount) in genesis.native_endowments() {
+			*balances.entry(account).or_default() += amount;
+		}
+		balances
+	}
+
+	/// Applies `patch` to the default runtime genesis config, like the chain spec builder does.
+	fn build_genesis_config(patch: serde_json::Map<String, Value>) -> Result<GenesisConfig, serde_json::Error> {
+		let mut config = serde_json::to_value(GenesisConfig::default())?;
+		sc_chain_spec::json_merge(&mut config, Value::Object(patch));
+		serde_json::from_value(config)
+	}
+
+	#[test]
+	fn presets_are_valid() {
+		for preset in [PRESET_DEV_DEFI, PRESET_LOCAL_TESTNET, PRESET_BENCHMARK] {
+			let genesis = DefiGenesis::preset(preset).unwrap();
+			assert_eq!(genesis.validate(&native_balances(&genesis)), Ok(()), "{preset}");
+			let patch = genesis.genesis_config_patch().unwrap();
+			if let Err(e) = build_genesis_config(patch) {
+				panic!("{preset} does not fit the runtime genesis config: {e}");
+			}
+		}
+	}
+
+	#[test]
+	fn patches_of_pallets_missing_from_the_runtime_are_rejected() {
+		let patch = serde_json::json!({ "cdpEngine": { "collateralsParams": [] } });
+		let Value::Object(patch) = patch else { unreachable!() };
+		assert!(build_genesis_config(patch).is_err());
+	}
+
+	#[test]
+	fn trading_pairs_must_use_registered_assets() {
+		let genesis: DefiGenesis = toml::from_str(
+			r#"
+			[[trading-pairs]]
+			pair = ["SEU", "SEUSD"]
+			"#,
+		)
+		.unwrap();
+		assert!(genesis.validate(&native_balances(&genesis)).unwrap_err().contains("unregistered token SEUSD"));
+	}
+
+	#[test]
+	fn oracle_members_must_be_funded() {
+		let genesis: DefiGenesis = toml::from_str(&format!(r#"oracle-members = ["{ALICE}", "{BOB}"]"#)).unwrap();
+		assert_eq!(genesis.validate(&native_balances(&genesis)), Err(format!("Oracle member {BOB} is not funded")));
+	}
+
+	#[test]
+	fn liquidity_is_bounded_by_endowments() {
+		let genesis: DefiGenesis = toml::from_str(&format!(
+			r#"
+			assets = ["SEUSD"]
+
+			[[endowments]]
+			account = "{ALICE}"
+			token = "SEUSD"
+			amount = 10
+
+			[[trading-pairs]]
+			pair = ["SEUSD", "SEU"]
+
+			[[liquidity]]
+			provider = "{ALICE}"
+			pair = ["SEUSD", "SEU"]
+			amounts = [11, 11]
+			"#
+		))
+		.unwrap();
+		assert!(genesis.validate(&native_balances(&genesis)).unwrap_err().contains("does not hold enough SEUSD"));
+	}
+}
@@ -21,6 +21,7 @@
 mod builder;
 mod cli;
 pub mod commands;
+mod defi;
 mod keystore;
 
 pub use commands::{BootstrapChainCmd, ConvertChainspecToRawCmd};
@@ -58,8 +58,9 @@ mod service;
 mod testing;
 
 pub use crypto::{PublicKey, SecretKey};
+pub use manager::{ConnectionDirection, PeerStatus};
 pub use rate_limiting::{RateLimitingDialer, RateLimitingListener};
-pub use service::{Service, SpawnHandleT};
+pub use service::{PeerStatuses, Service, SpawnHandleT};
 
 const LOG_TARGET: &str = "network-clique";
 /// A basic alias for properties we expect basic data to satisfy.
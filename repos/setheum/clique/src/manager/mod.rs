@@ -77,6 +77,22 @@ pub enum AddResult {
 	Replaced,
 }
 
+/// Which side is responsible for establishing the connection with a peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionDirection {
+	/// The peer connects to us.
+	Incoming,
+	/// We connect to the peer.
+	Outgoing,
+}
+
+/// State of the connection with a peer we want to be connected with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerStatus {
+	pub direction: ConnectionDirection,
+	pub connected: bool,
+}
+
 pub struct ManagerStatus<PK: PublicKey + PeerId> {
 	outgoing_peers: HashSet<PK>,
 	missing_outgoing: HashSet<PK>,
@@ -106,6 +122,23 @@ impl<PK: PublicKey + PeerId> ManagerStatus<PK> {
 		ManagerStatus { incoming_peers, missing_incoming, outgoing_peers, missing_outgoing }
 	}
 
+	/// The state of the connections with all the peers we want to be connected with.
+	pub fn peer_statuses(&self) -> HashMap<PK, PeerStatus> {
+		use ConnectionDirection::*;
+		let mut statuses = HashMap::new();
+		for (peers, direction, connected) in [
+			(&self.incoming_peers, Incoming, true),
+			(&self.missing_incoming, Incoming, false),
+			(&self.outgoing_peers, Outgoing, true),
+			(&self.missing_outgoing, Outgoing, false),
+		] {
+			for peer in peers {
+				statuses.insert(peer.clone(), PeerStatus { direction, connected });
+			}
+		}
+		statuses
+	}
+
 	fn wanted_incoming(&self) -> usize {
 		self.incoming_peers.len() + self.missing_incoming.len()
 	}
@@ -258,7 +291,7 @@ impl<PK: PublicKey + PeerId, A: Data, D: Data> Manager<PK, A, D> {
 mod tests {
 	use futures::{channel::mpsc, StreamExt};
 
-	use super::{AddResult::*, Manager, SendError};
+	use super::{AddResult::*, ConnectionDirection, Manager, PeerStatus, SendError};
 	use crate::{
 		metrics::Metrics,
 		mock::{key, MockPublicKey},
@@ -336,4 +369,28 @@ mod tests {
 		// receiving should fail
 		assert!(rx.next().await.is_none());
 	}
+
+	#[test]
+	fn reports_peer_statuses() {
+		let (own_id, _) = key();
+		let mut manager = Manager::<MockPublicKey, Address, Data>::new(own_id, Metrics::noop());
+		let (peer_id, _) = key();
+		let address = String::from("43.43.43.43:43000");
+		let direction = match manager.add_peer(peer_id.clone(), address) {
+			true => ConnectionDirection::Outgoing,
+			false => ConnectionDirection::Incoming,
+		};
+		assert_eq!(
+			manager.status_report().peer_statuses().get(&peer_id),
+			Some(&PeerStatus { direction, connected: false })
+		);
+		let (tx, _rx) = mpsc::unbounded();
+		assert_eq!(manager.add_connection(peer_id.clone(), tx), Added);
+		assert_eq!(
+			manager.status_report().peer_statuses().get(&peer_id),
+			Some(&PeerStatus { direction, connected: true })
+		);
+		manager.remove_peer(&peer_id);
+		assert!(manager.status_report().peer_statuses().is_empty());
+	}
 }
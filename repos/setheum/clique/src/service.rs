@@ -36,8 +36,10 @@
 // SOFTWARE.
 
 use std::{
+	collections::HashMap,
 	fmt::{Debug, Display},
 	pin::Pin,
+	sync::Arc,
 	time::Duration,
 };
 
@@ -49,12 +51,13 @@ use futures::{
 	Future, StreamExt,
 };
 use log::{info, trace, warn};
+use parking_lot::Mutex;
 use substrate_prometheus_endpoint::Registry;
 use tokio::time;
 
 use crate::{
 	incoming::incoming,
-	manager::{AddResult, Manager},
+	manager::{AddResult, Manager, PeerStatus},
 	metrics::Metrics,
 	outgoing::outgoing,
 	protocols::ResultForService,
@@ -138,6 +141,33 @@ impl Display for Error {
 	}
 }
 
+/// Statuses of the connections with the peers of the clique network, refreshed by the service
+/// whenever a connection changes.
+#[derive(Clone)]
+pub struct PeerStatuses<PK: PublicKey + PeerId> {
+	statuses: Arc<Mutex<HashMap<PK, PeerStatus>>>,
+}
+
+impl<PK: PublicKey + PeerId> PeerStatuses<PK> {
+	fn new() -> Self {
+		PeerStatuses { statuses: Arc::new(Mutex::new(HashMap::new())) }
+	}
+
+	fn update(&self, statuses: HashMap<PK, PeerStatus>) {
+		*self.statuses.lock() = statuses;
+	}
+
+	/// The status of the connection with the given peer, if we want to be connected with it.
+	pub fn get(&self, peer: &PK) -> Option<PeerStatus> {
+		self.statuses.lock().get(peer).copied()
+	}
+
+	/// The statuses of all the peers we want to be connected with.
+	pub fn snapshot(&self) -> HashMap<PK, PeerStatus> {
+		self.statuses.lock().clone()
+	}
+}
+
 /// A service that has to be run for the clique network to work.
 pub struct Service<SK: SecretKey, D: Data, A: Data, ND: Dialer<A>, NL: Listener, SH: SpawnHandleT>
 where
@@ -151,6 +181,7 @@ where
 	spawn_handle: SH,
 	secret_key: SK,
 	metrics: Metrics,
+	peer_statuses: PeerStatuses<SK::PublicKey>,
 }
 
 impl<SK: SecretKey, D: Data, A: Data + Debug, ND: Dialer<A>, NL: Listener, SH: SpawnHandleT>
@@ -187,11 +218,22 @@ where
 				spawn_handle,
 				secret_key,
 				metrics,
+				peer_statuses: PeerStatuses::new(),
 			},
 			ServiceInterface { commands_for_service, next_from_service },
 		)
 	}
 
+	/// A handle to the connection statuses of the peers, which remains valid while the service
+	/// runs.
+	pub fn peer_statuses(&self) -> PeerStatuses<SK::PublicKey> {
+		self.peer_statuses.clone()
+	}
+
+	fn refresh_peer_statuses(&self) {
+		self.peer_statuses.update(self.manager.status_report().peer_statuses());
+	}
+
 	fn spawn_new_outgoing(
 		&mut self,
 		public_key: SK::PublicKey,
@@ -243,10 +285,12 @@ where
 				if self.manager.add_peer(public_key.clone(), address.clone()) {
 					self.spawn_new_outgoing(public_key, address, result_for_parent.clone());
 				};
+				self.refresh_peer_statuses();
 			},
 			// remove the peer from the manager all workers will be killed automatically, due to closed channels
 			DelConnection(public_key) => {
 				self.manager.remove_peer(&public_key);
+				self.refresh_peer_statuses();
 			},
 			// pass the data to the manager
 			SendData(data, public_key) => match self.manager.send_to(&public_key, data) {
@@ -331,6 +375,7 @@ where
 							maybe_data_for_network = worker_results.next() => {
 								let (public_key, maybe_data_for_network) = maybe_data_for_network.ok_or(Error::ConnectionWorker)?;
 								self.handle_data_for_network(public_key, maybe_data_for_network, &result_for_parent);
+								self.refresh_peer_statuses();
 							},
 			// periodically reporting what we are trying to do
 							_ = status_ticker.tick() => {
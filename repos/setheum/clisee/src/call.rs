@@ -0,0 +1,208 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::future::Future;
+
+use anyhow::{anyhow, Result};
+use log::info;
+use primitives::{currency::TokenInfo, CurrencyId, TokenSymbol};
+use serde::Serialize;
+use setheum_client::{
+	pallets::system::SystemApi, sp_core::hashing::blake2_256, sp_runtime::FixedU128, Balance, DynamicCall,
+	SignedConnection, SignedConnectionApi, TxInfo, TxStatus,
+};
+use subxt::{rpc::types::DryRunResult, tx::TxPayload};
+
+use crate::{commands::CallOptions, ConnectionConfig};
+
+/// Decimal places of `FixedU128`.
+const PRICE_DECIMALS: usize = 18;
+
+/// Parses a currency either from its token symbol, e.g. `SEU`, or from its JSON representation,
+/// e.g. `{"erc20":"0x..."}`.
+pub fn parse_currency_id(value: &str) -> Result<CurrencyId> {
+	if value.trim_start().starts_with('{') {
+		return serde_json::from_str(value).map_err(|e| anyhow!("Invalid currency id {value}: {e}"));
+	}
+	(0..=u8::MAX)
+		.filter_map(|index| TokenSymbol::try_from(index).ok())
+		.map(CurrencyId::Token)
+		.find(|currency| currency.symbol() == Some(value))
+		.ok_or_else(|| anyhow!("Unknown token symbol {value}"))
+}
+
+/// Parses a decimal price, e.g. `1.25`, into a `FixedU128` without going through floats.
+pub fn parse_price(value: &str) -> Result<FixedU128> {
+	let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+	if fraction.len() > PRICE_DECIMALS {
+		return Err(anyhow!("Price {value} has more than {PRICE_DECIMALS} decimal places"));
+	}
+	let digits = format!("{integer}{fraction:0<PRICE_DECIMALS$}");
+	let inner = digits.parse::<u128>().map_err(|e| anyhow!("Invalid price {value}: {e}"))?;
+
+	Ok(FixedU128::from_inner(inner))
+}
+
+/// Parses `CURRENCY=PRICE`, e.g. `SEU=1.25`.
+pub fn parse_oracle_value(value: &str) -> Result<(CurrencyId, FixedU128)> {
+	let (currency, price) = value.split_once('=').ok_or_else(|| anyhow!("Expected CURRENCY=PRICE, got {value}"))?;
+
+	Ok((parse_currency_id(currency)?, parse_price(price)?))
+}
+
+/// Parses `KEY=VALUE` NFT attribute.
+pub fn parse_attribute(value: &str) -> Result<(Vec<u8>, Vec<u8>)> {
+	let (key, value) = value.split_once('=').ok_or_else(|| anyhow!("Expected KEY=VALUE, got {value}"))?;
+
+	Ok((key.as_bytes().to_vec(), value.as_bytes().to_vec()))
+}
+
+/// Encoded call, printed instead of submitting it.
+#[derive(Serialize)]
+struct EncodedCall {
+	call: &'static str,
+	encoded_call: String,
+	call_hash: String,
+	length: usize,
+	/// What has to happen for the call to be dispatched.
+	next_step: &'static str,
+}
+
+impl EncodedCall {
+	/// Encodes the call against the metadata of the node, which also checks its arguments.
+	async fn new(
+		cfg: &ConnectionConfig,
+		name: &'static str,
+		call: &DynamicCall,
+		next_step: &'static str,
+	) -> Result<Self> {
+		let connection = cfg.get_connection().await;
+		let encoded = call.encode_call_data(&connection.as_client().metadata())?;
+		Ok(EncodedCall {
+			call: name,
+			call_hash: format!("0x{}", hex::encode(blake2_256(&encoded))),
+			length: encoded.len(),
+			encoded_call: format!("0x{}", hex::encode(encoded)),
+			next_step,
+		})
+	}
+}
+
+/// Outcome of a call executed on top of the best block, printed instead of submitting it.
+#[derive(Serialize)]
+struct DryRun {
+	call: &'static str,
+	success: bool,
+	/// Why the call would fail, `None` if it succeeds.
+	error: Option<String>,
+	/// Fee the signer would pay, without a tip.
+	partial_fee: Balance,
+}
+
+impl DryRun {
+	/// Signs the call with the next nonce of the signer and runs it via `system_dryRun`, estimating its fee via
+	/// `TransactionPaymentApi_query_info`.
+	async fn new<S: SignedConnectionApi + SystemApi>(
+		connection: &S,
+		name: &'static str,
+		call: DynamicCall,
+	) -> Result<Self> {
+		let nonce = connection.account_nonce(connection.account_id()).await?;
+		let extrinsic = connection.sign_with_params(call, Default::default(), nonce)?;
+		let error = match extrinsic.dry_run().await? {
+			DryRunResult::Success => None,
+			DryRunResult::DispatchError(e) => Some(e.to_string()),
+			DryRunResult::TransactionValidityError => {
+				Some("Transaction is invalid, e.g. the signer can't pay its fee".to_string())
+			},
+		};
+
+		Ok(DryRun { call: name, success: error.is_none(), error, partial_fee: extrinsic.partial_fee().await? })
+	}
+}
+
+pub fn print_json<T: Serialize>(value: &T) {
+	println!("{}", serde_json::to_string_pretty(value).expect("Can't encode the result as JSON"));
+}
+
+/// Submits a call signed by the `--seed` account, prints it with `--print-call` or executes it without submitting
+/// with `--dry-run`.
+pub async fn dispatch_signed<F, Fut>(
+	cfg: &ConnectionConfig,
+	options: &CallOptions,
+	name: &'static str,
+	call: DynamicCall,
+	submit: F,
+) -> Result<()>
+where
+	F: FnOnce(SignedConnection) -> Fut,
+	Fut: Future<Output = Result<TxInfo>>,
+{
+	if options.print_call {
+		print_json(&EncodedCall::new(cfg, name, &call, "submit it signed by the account of your choice").await?);
+		return Ok(());
+	}
+	if options.dry_run {
+		print_json(&DryRun::new(&cfg.get_signed_connection().await, name, call).await?);
+		return Ok(());
+	}
+
+	let tx_info = submit(cfg.get_signed_connection().await).await?;
+	print_json(&tx_info);
+
+	Ok(())
+}
+
+/// Submits a call that needs a governance origin via sudo when `--sudo` is given. Otherwise prints
+/// it, so it can be proposed as a council motion.
+pub async fn dispatch_governance(
+	cfg: &ConnectionConfig,
+	options: &CallOptions,
+	name: &'static str,
+	call: DynamicCall,
+) -> Result<()> {
+	if options.print_call {
+		print_json(&EncodedCall::new(cfg, name, &call, "submit it via sudo or a council motion").await?);
+		return Ok(());
+	}
+	if !options.sudo {
+		info!("{name} requires governance, propose the encoded call as a council motion");
+		print_json(
+			&EncodedCall::new(
+				cfg,
+				name,
+				&call,
+				"propose `encoded_call` as a council motion with `length` as its length bound",
+			)
+			.await?,
+		);
+		return Ok(());
+	}
+
+	let connection = cfg.get_root_connection().await;
+	if options.dry_run {
+		print_json(&DryRun::new(&connection, name, call.sudo_unchecked()).await?);
+		return Ok(());
+	}
+	let tx_info = connection.send_tx(call.sudo_unchecked(), TxStatus::Finalized).await?;
+	print_json(&tx_info);
+
+	Ok(())
+}
@@ -24,9 +24,15 @@ use std::{
 };
 
 use clap::{clap_derive::ValueEnum, Args, Subcommand};
-use primitives::{BlockHash, BlockNumber, CommitteeSeats, SessionIndex};
+use primitives::{nft::ClassProperty, Amount, BlockHash, BlockNumber, CommitteeSeats, CurrencyId, SessionIndex};
 use serde::{Deserialize, Serialize};
-use setheum_client::{AccountId, Balance, TxStatus};
+use setheum_client::{
+	pallets::nft::{ClassId, TokenId},
+	sp_runtime::FixedU128,
+	AccountId, Balance, TxStatus,
+};
+
+use crate::call::{parse_attribute, parse_currency_id, parse_oracle_value};
 
 #[derive(Debug, Clone, Args)]
 pub struct ContractOptions {
@@ -394,6 +400,46 @@ pub enum Command {
 	/// MoveVM commands.
 	#[clap(subcommand)]
 	Move(MoveCommand),
+
+	/// Swap, provide liquidity and manage trading pairs of the DEX.
+	Dex {
+		#[clap(flatten)]
+		options: CallOptions,
+		#[clap(subcommand)]
+		cmd: DexCommand,
+	},
+
+	/// Feed and query oracle prices.
+	Oracle {
+		#[clap(flatten)]
+		options: CallOptions,
+		#[clap(subcommand)]
+		cmd: OracleCommand,
+	},
+
+	/// Transfer and query non-native currencies.
+	Currency {
+		#[clap(flatten)]
+		options: CallOptions,
+		#[clap(subcommand)]
+		cmd: CurrencyCommand,
+	},
+
+	/// Create NFT classes, mint and move NFTs.
+	Nft {
+		#[clap(flatten)]
+		options: CallOptions,
+		#[clap(subcommand)]
+		cmd: NftCommand,
+	},
+
+	/// Lock and query prices.
+	Prices {
+		#[clap(flatten)]
+		options: CallOptions,
+		#[clap(subcommand)]
+		cmd: PricesCommand,
+	},
 }
 
 #[derive(Debug, Clone, Subcommand)]
@@ -479,3 +525,355 @@ pub struct MovePublish {
 	#[clap(long, default_value = "100000")]
 	pub gas_limit: u32,
 }
+
+/// Options shared by `dex`, `oracle`, `currency`, `nft` and `prices` commands.
+#[derive(Debug, Clone, Args)]
+pub struct CallOptions {
+	/// Print the encoded call as JSON instead of submitting it
+	#[clap(long, global = true)]
+	pub print_call: bool,
+	/// Sign the call and execute it on top of the best block instead of submitting it, printing its dispatch
+	/// result and fee as JSON. Ignored with `--print-call`
+	#[clap(long, global = true)]
+	pub dry_run: bool,
+	/// Submit calls that require governance via sudo instead of printing them for a council motion.
+	/// Only works on chains with a sudo key, e.g. local testnets
+	#[clap(long, global = true)]
+	pub sudo: bool,
+}
+
+/// Who has to authorize a call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallKind {
+	/// Read-only query, nothing is submitted.
+	Query,
+	/// Call signed by the `--seed` account.
+	Signed,
+	/// Call that needs a governance origin.
+	Governance,
+}
+
+impl CallOptions {
+	/// Whether a signer seed is needed to run a call of the given kind.
+	pub fn needs_signer(&self, kind: CallKind) -> bool {
+		match kind {
+			CallKind::Query => false,
+			CallKind::Signed => !self.print_call,
+			CallKind::Governance => self.sudo && !self.print_call,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum DexCommand {
+	/// Swap an exact amount of the first currency in the path.
+	SwapWithExactSupply {
+		/// Swap path, e.g. `--path SEU --path SEUSD`
+		#[clap(long, required = true, value_parser = parse_currency_id)]
+		path: Vec<CurrencyId>,
+		/// Amount of the first currency in the path to supply
+		#[clap(long)]
+		supply_amount: Balance,
+		/// The least acceptable amount of the last currency in the path
+		#[clap(long, default_value = "0")]
+		min_target_amount: Balance,
+	},
+	/// Swap for an exact amount of the last currency in the path.
+	SwapWithExactTarget {
+		/// Swap path, e.g. `--path SEU --path SEUSD`
+		#[clap(long, required = true, value_parser = parse_currency_id)]
+		path: Vec<CurrencyId>,
+		/// Amount of the last currency in the path to receive
+		#[clap(long)]
+		target_amount: Balance,
+		/// The most acceptable amount of the first currency in the path
+		#[clap(long)]
+		max_supply_amount: Balance,
+	},
+	/// Add liquidity to an enabled trading pair.
+	AddLiquidity {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+		#[clap(long)]
+		max_amount_a: Balance,
+		#[clap(long)]
+		max_amount_b: Balance,
+		/// The least acceptable amount of dex shares to receive
+		#[clap(long, default_value = "0")]
+		min_share_increment: Balance,
+	},
+	/// Remove liquidity from a trading pair.
+	RemoveLiquidity {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+		/// Amount of dex shares to remove
+		#[clap(long)]
+		remove_share: Balance,
+		#[clap(long, default_value = "0")]
+		min_withdrawn_a: Balance,
+		#[clap(long, default_value = "0")]
+		min_withdrawn_b: Balance,
+	},
+	/// Add provision to a provisioning trading pair.
+	AddProvision {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+		#[clap(long)]
+		amount_a: Balance,
+		#[clap(long)]
+		amount_b: Balance,
+	},
+	/// List a new provisioning trading pair. Requires governance.
+	ListProvisioning {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+		#[clap(long)]
+		min_contribution_a: Balance,
+		#[clap(long)]
+		min_contribution_b: Balance,
+		#[clap(long)]
+		target_provision_a: Balance,
+		#[clap(long)]
+		target_provision_b: Balance,
+		/// Block number before which provisioning can not end
+		#[clap(long, default_value = "0")]
+		not_before: BlockNumber,
+	},
+	/// Enable a trading pair. Requires governance.
+	EnableTradingPair {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+	},
+	/// Disable a trading pair. Requires governance.
+	DisableTradingPair {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+	},
+	/// Set trading fee of a trading pair. Requires governance.
+	SetTradingFee {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+		/// Fee numerator, e.g. 3 for a 3/1000 fee
+		#[clap(long)]
+		fee_numerator: u32,
+		/// Fee denominator, e.g. 1000 for a 3/1000 fee
+		#[clap(long)]
+		fee_denominator: u32,
+	},
+	/// Print status, reserves and trading fee of a trading pair.
+	Pool {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_a: CurrencyId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency_b: CurrencyId,
+	},
+}
+
+impl DexCommand {
+	pub fn kind(&self) -> CallKind {
+		match self {
+			DexCommand::ListProvisioning { .. }
+			| DexCommand::EnableTradingPair { .. }
+			| DexCommand::DisableTradingPair { .. }
+			| DexCommand::SetTradingFee { .. } => CallKind::Governance,
+			DexCommand::Pool { .. } => CallKind::Query,
+			_ => CallKind::Signed,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum OracleCommand {
+	/// Feed prices as an oracle operator, or as the root operator with `--sudo`.
+	FeedValues {
+		/// Currency and its price, e.g. `--value SEU=1.25`
+		#[clap(long = "value", required = true, value_parser = parse_oracle_value)]
+		values: Vec<(CurrencyId, FixedU128)>,
+	},
+	/// Print the combined oracle price of a currency.
+	Value {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency: CurrencyId,
+	},
+}
+
+impl OracleCommand {
+	pub fn kind(&self, options: &CallOptions) -> CallKind {
+		match self {
+			OracleCommand::FeedValues { .. } if options.sudo => CallKind::Governance,
+			OracleCommand::FeedValues { .. } => CallKind::Signed,
+			OracleCommand::Value { .. } => CallKind::Query,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum CurrencyCommand {
+	/// Transfer any currency via currencies pallet.
+	Transfer {
+		/// SS58 id of target account
+		#[clap(long)]
+		to: AccountId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency: CurrencyId,
+		/// Amount to transfer, in the smallest unit of the currency
+		#[clap(long)]
+		amount: Balance,
+	},
+	/// Add to or remove from the balance of an account. Requires governance.
+	UpdateBalance {
+		/// SS58 id of the account
+		#[clap(long)]
+		who: AccountId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency: CurrencyId,
+		/// Signed amount, in the smallest unit of the currency
+		#[clap(long, allow_hyphen_values = true)]
+		amount: Amount,
+	},
+	/// Print balance of an account in a non-native currency.
+	Balance {
+		/// SS58 id of the account
+		#[clap(long)]
+		account: AccountId,
+		#[clap(long, value_parser = parse_currency_id)]
+		currency: CurrencyId,
+	},
+}
+
+impl CurrencyCommand {
+	pub fn kind(&self) -> CallKind {
+		match self {
+			CurrencyCommand::Transfer { .. } => CallKind::Signed,
+			CurrencyCommand::UpdateBalance { .. } => CallKind::Governance,
+			CurrencyCommand::Balance { .. } => CallKind::Query,
+		}
+	}
+}
+
+/// Property of an NFT class.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum NftClassProperty {
+	Transferable,
+	Burnable,
+	Mintable,
+	ClassPropertiesMutable,
+}
+
+impl From<NftClassProperty> for ClassProperty {
+	fn from(property: NftClassProperty) -> Self {
+		match property {
+			NftClassProperty::Transferable => ClassProperty::Transferable,
+			NftClassProperty::Burnable => ClassProperty::Burnable,
+			NftClassProperty::Mintable => ClassProperty::Mintable,
+			NftClassProperty::ClassPropertiesMutable => ClassProperty::ClassPropertiesMutable,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum NftCommand {
+	/// Create a new NFT class owned by a pallet account the signer is a proxy of.
+	CreateClass {
+		/// Class metadata, e.g. an IPFS CID
+		#[clap(long)]
+		metadata: String,
+		/// Class properties
+		#[clap(long = "property", value_enum)]
+		properties: Vec<NftClassProperty>,
+		/// Class attribute, e.g. `--attribute name=Setheum`
+		#[clap(long = "attribute", value_parser = parse_attribute)]
+		attributes: Vec<(Vec<u8>, Vec<u8>)>,
+	},
+	/// Mint tokens of a class the signer is a proxy of the owner of.
+	Mint {
+		/// SS58 id of the account to mint to
+		#[clap(long)]
+		to: AccountId,
+		#[clap(long)]
+		class_id: ClassId,
+		/// Token metadata, e.g. an IPFS CID
+		#[clap(long)]
+		metadata: String,
+		/// Token attribute, e.g. `--attribute rarity=legendary`
+		#[clap(long = "attribute", value_parser = parse_attribute)]
+		attributes: Vec<(Vec<u8>, Vec<u8>)>,
+		#[clap(long, default_value = "1")]
+		quantity: u32,
+	},
+	/// Transfer a token.
+	Transfer {
+		/// SS58 id of target account
+		#[clap(long)]
+		to: AccountId,
+		#[clap(long)]
+		class_id: ClassId,
+		#[clap(long)]
+		token_id: TokenId,
+	},
+	/// Burn a token.
+	Burn {
+		#[clap(long)]
+		class_id: ClassId,
+		#[clap(long)]
+		token_id: TokenId,
+	},
+	/// Print the owner of a token.
+	Owner {
+		#[clap(long)]
+		class_id: ClassId,
+		#[clap(long)]
+		token_id: TokenId,
+	},
+}
+
+impl NftCommand {
+	pub fn kind(&self) -> CallKind {
+		match self {
+			NftCommand::Owner { .. } => CallKind::Query,
+			_ => CallKind::Signed,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum PricesCommand {
+	/// Lock the current price of a currency. Requires governance.
+	Lock {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency: CurrencyId,
+	},
+	/// Unlock the price of a currency. Requires governance.
+	Unlock {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency: CurrencyId,
+	},
+	/// Print the locked price of a currency, if any.
+	Locked {
+		#[clap(long, value_parser = parse_currency_id)]
+		currency: CurrencyId,
+	},
+}
+
+impl PricesCommand {
+	pub fn kind(&self) -> CallKind {
+		match self {
+			PricesCommand::Locked { .. } => CallKind::Query,
+			_ => CallKind::Governance,
+		}
+	}
+}
@@ -0,0 +1,78 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use codec::Compact;
+use primitives::CurrencyId;
+use serde::Serialize;
+use setheum_client::{
+	pallets::currencies::{CurrenciesApi, CurrenciesUserApi},
+	AccountId, Balance, DynamicCall, TxStatus,
+};
+use subxt::utils::MultiAddress;
+
+use crate::{
+	call::{dispatch_governance, dispatch_signed, print_json},
+	commands::{CallOptions, CurrencyCommand},
+	ConnectionConfig,
+};
+
+#[derive(Serialize)]
+struct CurrencyBalance {
+	account: String,
+	currency: CurrencyId,
+	free: Balance,
+	reserved: Balance,
+	frozen: Balance,
+}
+
+/// Runs `clisee currency` commands on top of `setheum_client::pallets::currencies`.
+pub async fn currency(cfg: ConnectionConfig, options: CallOptions, cmd: CurrencyCommand) -> Result<()> {
+	match cmd {
+		CurrencyCommand::Transfer { to, currency, amount } => {
+			let call = DynamicCall::new("Currencies", "transfer")
+				.arg(MultiAddress::<_, ()>::Id(to.clone()))
+				.arg(currency)
+				.arg(Compact(amount));
+			dispatch_signed(&cfg, &options, "Currencies.transfer", call, |connection| async move {
+				connection.transfer_currency(to, currency, amount, TxStatus::Finalized).await
+			})
+			.await
+		},
+		CurrencyCommand::UpdateBalance { who, currency, amount } => {
+			let call = DynamicCall::new("Currencies", "update_balance")
+				.arg(MultiAddress::<_, ()>::Id(who))
+				.arg(currency)
+				.arg(amount);
+			dispatch_governance(&cfg, &options, "Currencies.update_balance", call).await
+		},
+		CurrencyCommand::Balance { account, currency } => {
+			let data = cfg.get_connection().await.token_account(account.clone(), currency, None).await;
+			print_json(&CurrencyBalance {
+				account: account.to_string(),
+				currency,
+				free: data.free,
+				reserved: data.reserved,
+				frozen: data.frozen,
+			});
+			Ok(())
+		},
+	}
+}
@@ -0,0 +1,185 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use codec::Compact;
+use primitives::{CurrencyId, TradingPair};
+use serde::Serialize;
+use setheum_client::{
+	pallets::dex::{DexApi, DexUserApi, TradingPairStatus},
+	Balance, DynamicCall, TxStatus,
+};
+
+use crate::{
+	call::{dispatch_governance, dispatch_signed, print_json},
+	commands::{CallOptions, DexCommand},
+	ConnectionConfig,
+};
+
+#[derive(Serialize)]
+struct Pool {
+	trading_pair: TradingPair,
+	status: &'static str,
+	reserves: (Balance, Balance),
+	/// `None` if the pair uses the runtime default fee.
+	trading_fee: Option<(u32, u32)>,
+}
+
+fn trading_pair(currency_a: CurrencyId, currency_b: CurrencyId) -> Result<TradingPair> {
+	TradingPair::from_currency_ids(currency_a, currency_b)
+		.ok_or_else(|| anyhow!("{currency_a:?} and {currency_b:?} do not form a trading pair"))
+}
+
+/// Runs `clisee dex` commands on top of `setheum_client::pallets::dex`.
+pub async fn dex(cfg: ConnectionConfig, options: CallOptions, cmd: DexCommand) -> Result<()> {
+	match cmd {
+		DexCommand::SwapWithExactSupply { path: swap_path, supply_amount, min_target_amount } => {
+			let call = DynamicCall::new("Dex", "swap_with_exact_supply")
+				.arg(swap_path.clone())
+				.arg(Compact(supply_amount))
+				.arg(Compact(min_target_amount));
+			dispatch_signed(&cfg, &options, "Dex.swap_with_exact_supply", call, |connection| async move {
+				connection
+					.swap_with_exact_supply(swap_path, supply_amount, min_target_amount, TxStatus::Finalized)
+					.await
+			})
+			.await
+		},
+		DexCommand::SwapWithExactTarget { path: swap_path, target_amount, max_supply_amount } => {
+			let call = DynamicCall::new("Dex", "swap_with_exact_target")
+				.arg(swap_path.clone())
+				.arg(Compact(target_amount))
+				.arg(Compact(max_supply_amount));
+			dispatch_signed(&cfg, &options, "Dex.swap_with_exact_target", call, |connection| async move {
+				connection
+					.swap_with_exact_target(swap_path, target_amount, max_supply_amount, TxStatus::Finalized)
+					.await
+			})
+			.await
+		},
+		DexCommand::AddLiquidity { currency_a, currency_b, max_amount_a, max_amount_b, min_share_increment } => {
+			let call = DynamicCall::new("Dex", "add_liquidity")
+				.arg(currency_a)
+				.arg(currency_b)
+				.arg(Compact(max_amount_a))
+				.arg(Compact(max_amount_b))
+				.arg(Compact(min_share_increment));
+			dispatch_signed(&cfg, &options, "Dex.add_liquidity", call, |connection| async move {
+				connection
+					.add_liquidity(
+						currency_a,
+						currency_b,
+						max_amount_a,
+						max_amount_b,
+						min_share_increment,
+						TxStatus::Finalized,
+					)
+					.await
+			})
+			.await
+		},
+		DexCommand::RemoveLiquidity { currency_a, currency_b, remove_share, min_withdrawn_a, min_withdrawn_b } => {
+			let call = DynamicCall::new("Dex", "remove_liquidity")
+				.arg(currency_a)
+				.arg(currency_b)
+				.arg(Compact(remove_share))
+				.arg(Compact(min_withdrawn_a))
+				.arg(Compact(min_withdrawn_b))
+				.arg(false);
+			dispatch_signed(&cfg, &options, "Dex.remove_liquidity", call, |connection| async move {
+				connection
+					.remove_liquidity(
+						currency_a,
+						currency_b,
+						remove_share,
+						min_withdrawn_a,
+						min_withdrawn_b,
+						TxStatus::Finalized,
+					)
+					.await
+			})
+			.await
+		},
+		DexCommand::AddProvision { currency_a, currency_b, amount_a, amount_b } => {
+			let call = DynamicCall::new("Dex", "add_provision")
+				.arg(currency_a)
+				.arg(currency_b)
+				.arg(Compact(amount_a))
+				.arg(Compact(amount_b));
+			dispatch_signed(&cfg, &options, "Dex.add_provision", call, |connection| async move {
+				connection.add_provision(currency_a, currency_b, amount_a, amount_b, TxStatus::Finalized).await
+			})
+			.await
+		},
+		DexCommand::ListProvisioning {
+			currency_a,
+			currency_b,
+			min_contribution_a,
+			min_contribution_b,
+			target_provision_a,
+			target_provision_b,
+			not_before,
+		} => {
+			let call = DynamicCall::new("Dex", "list_provisioning")
+				.arg(currency_a)
+				.arg(currency_b)
+				.arg(Compact(min_contribution_a))
+				.arg(Compact(min_contribution_b))
+				.arg(Compact(target_provision_a))
+				.arg(Compact(target_provision_b))
+				.arg(Compact(not_before));
+			dispatch_governance(&cfg, &options, "Dex.list_provisioning", call).await
+		},
+		DexCommand::EnableTradingPair { currency_a, currency_b } => {
+			let call = DynamicCall::new("Dex", "enable_trading_pair").arg(currency_a).arg(currency_b);
+			dispatch_governance(&cfg, &options, "Dex.enable_trading_pair", call).await
+		},
+		DexCommand::DisableTradingPair { currency_a, currency_b } => {
+			let call = DynamicCall::new("Dex", "disable_trading_pair").arg(currency_a).arg(currency_b);
+			dispatch_governance(&cfg, &options, "Dex.disable_trading_pair", call).await
+		},
+		DexCommand::SetTradingFee { currency_a, currency_b, fee_numerator, fee_denominator } => {
+			if fee_numerator >= fee_denominator {
+				return Err(anyhow!("Trading fee must be less than 100%"));
+			}
+			let call = DynamicCall::new("Dex", "set_trading_fee")
+				.arg(currency_a)
+				.arg(currency_b)
+				.arg((fee_numerator, fee_denominator));
+			dispatch_governance(&cfg, &options, "Dex.set_trading_fee", call).await
+		},
+		DexCommand::Pool { currency_a, currency_b } => {
+			let trading_pair = trading_pair(currency_a, currency_b)?;
+			let connection = cfg.get_connection().await;
+			let status = match connection.trading_pair_status(trading_pair, None).await {
+				TradingPairStatus::Disabled => "disabled",
+				TradingPairStatus::Provisioning(_) => "provisioning",
+				TradingPairStatus::Enabled => "enabled",
+			};
+			print_json(&Pool {
+				trading_pair,
+				status,
+				reserves: connection.liquidity_pool(trading_pair, None).await,
+				trading_fee: connection.trading_fee(trading_pair, None).await,
+			});
+			Ok(())
+		},
+	}
+}
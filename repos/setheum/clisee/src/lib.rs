@@ -18,10 +18,16 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+mod call;
 mod commands;
 mod contracts;
+mod currency;
+mod dex;
 mod finalization;
 mod keys;
+mod nft;
+mod oracle;
+mod prices;
 mod runtime;
 mod secret;
 mod staking;
@@ -32,10 +38,18 @@ mod version_upgrade;
 mod vesting;
 mod vk_storage;
 
-pub use commands::{Command, VkStorage, MoveCommand, MoveExecute, MovePublish};
+pub use commands::{
+	CallKind, CallOptions, Command, CurrencyCommand, DexCommand, MoveCommand, MoveExecute, MovePublish, NftCommand,
+	OracleCommand, PricesCommand, VkStorage,
+};
 pub use contracts::{call, code_info, instantiate, instantiate_with_code, remove_code, upload_code};
+pub use currency::currency;
+pub use dex::dex;
 pub use finalization::{finalize, set_emergency_finalizer};
 pub use keys::{next_session_keys, prepare_keys, rotate_keys, set_keys};
+pub use nft::nft;
+pub use oracle::oracle;
+pub use prices::prices;
 pub use runtime::update_runtime;
 pub use secret::prompt_password_hidden;
 use setheum_client::{keypair_from_string, Connection, RootConnection, SignedConnection};
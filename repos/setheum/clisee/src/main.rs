@@ -22,11 +22,11 @@ use std::env;
 
 use clap::Parser;
 use clisee::{
-	bond, call, change_validators, code_info, finalize, force_new_era, instantiate, instantiate_with_code,
-	next_session_keys, nominate, prepare_keys, prompt_password_hidden, remove_code, rotate_keys, schedule_upgrade,
-	set_emergency_finalizer, set_keys, set_staking_limits, store_key, transfer_keep_alive, treasury_approve,
-	treasury_propose, treasury_reject, update_runtime, upload_code, validate, vest, vest_other, vested_transfer,
-	Command, ConnectionConfig, VkStorage,
+	bond, call, change_validators, code_info, currency, dex, finalize, force_new_era, instantiate,
+	instantiate_with_code, next_session_keys, nft, nominate, oracle, prepare_keys, prices, prompt_password_hidden,
+	remove_code, rotate_keys, schedule_upgrade, set_emergency_finalizer, set_keys, set_staking_limits, store_key,
+	transfer_keep_alive, treasury_approve, treasury_propose, treasury_reject, update_runtime, upload_code, validate,
+	vest, vest_other, vested_transfer, Command, ConnectionConfig, VkStorage,
 };
 use log::{error, info};
 use setheum_client::{account_from_keypair, keypair_from_string, setbft_keypair_from_string, Pair};
@@ -56,6 +56,11 @@ fn read_seed(command: &Command, seed: Option<String>) -> String {
 		| Command::RotateKeys
 		| Command::SeedToSS58 { .. }
 		| Command::ContractCodeInfo { .. } => String::new(),
+		Command::Dex { options, cmd } if !options.needs_signer(cmd.kind()) => String::new(),
+		Command::Oracle { options, cmd } if !options.needs_signer(cmd.kind(options)) => String::new(),
+		Command::Currency { options, cmd } if !options.needs_signer(cmd.kind()) => String::new(),
+		Command::Nft { options, cmd } if !options.needs_signer(cmd.kind()) => String::new(),
+		Command::Prices { options, cmd } if !options.needs_signer(cmd.kind()) => String::new(),
 		_ => read_secret(seed, "Provide seed for the signer account:"),
 	}
 }
@@ -184,6 +189,31 @@ async fn main() -> anyhow::Result<()> {
 				}
 			},
 		},
+		Command::Dex { options, cmd } => {
+			if let Err(why) = dex(cfg, options, cmd).await {
+				error!("Dex command failed {:?}", why)
+			}
+		},
+		Command::Oracle { options, cmd } => {
+			if let Err(why) = oracle(cfg, options, cmd).await {
+				error!("Oracle command failed {:?}", why)
+			}
+		},
+		Command::Currency { options, cmd } => {
+			if let Err(why) = currency(cfg, options, cmd).await {
+				error!("Currency command failed {:?}", why)
+			}
+		},
+		Command::Nft { options, cmd } => {
+			if let Err(why) = nft(cfg, options, cmd).await {
+				error!("Nft command failed {:?}", why)
+			}
+		},
+		Command::Prices { options, cmd } => {
+			if let Err(why) = prices(cfg, options, cmd).await {
+				error!("Prices command failed {:?}", why)
+			}
+		},
 	}
 	Ok(())
 }
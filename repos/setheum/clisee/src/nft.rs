@@ -0,0 +1,115 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::{anyhow, Result};
+use codec::Compact;
+use primitives::nft::{Attributes, ClassProperty, Properties};
+use serde::Serialize;
+use setheum_client::{
+	pallets::nft::{ClassId, NftApi, NftUserApi, TokenId},
+	setheum_runtime::ProxyType,
+	DynamicCall, TxStatus,
+};
+use subxt::utils::MultiAddress;
+
+use crate::{
+	call::{dispatch_signed, print_json},
+	commands::{CallOptions, NftClassProperty, NftCommand},
+	ConnectionConfig,
+};
+
+#[derive(Serialize)]
+struct TokenOwner {
+	class_id: ClassId,
+	token_id: TokenId,
+	/// `None` if the token does not exist.
+	owner: Option<String>,
+}
+
+fn properties(properties: &[NftClassProperty]) -> Properties {
+	let mut result = Properties::default();
+	for property in properties {
+		result.0 |= ClassProperty::from(*property);
+	}
+	result
+}
+
+/// Runs `clisee nft` commands on top of `setheum_client::pallets::nft`.
+pub async fn nft(cfg: ConnectionConfig, options: CallOptions, cmd: NftCommand) -> Result<()> {
+	match cmd {
+		NftCommand::CreateClass { metadata, properties: class_properties, attributes } => {
+			let properties = properties(&class_properties);
+			let attributes: Attributes = attributes.into_iter().collect();
+			let metadata = metadata.into_bytes();
+			let call =
+				DynamicCall::new("NFT", "create_class").arg(metadata.clone()).arg(properties).arg(attributes.clone());
+			dispatch_signed(&cfg, &options, "NFT.create_class", call, |connection| async move {
+				connection.create_class(metadata, properties, attributes, TxStatus::Finalized).await
+			})
+			.await
+		},
+		NftCommand::Mint { to, class_id, metadata, attributes, quantity } => {
+			// Classes are owned by pallet derived accounts, the creator mints through pallet proxy.
+			let owner = cfg
+				.get_connection()
+				.await
+				.class_owner(class_id, None)
+				.await
+				.ok_or_else(|| anyhow!("Nft class {class_id} does not exist"))?;
+			let attributes: Attributes = attributes.into_iter().collect();
+			let metadata = metadata.into_bytes();
+			let mint = DynamicCall::new("NFT", "mint")
+				.arg(MultiAddress::<_, ()>::Id(to.clone()))
+				.arg(class_id)
+				.arg(metadata.clone())
+				.arg(attributes.clone())
+				.arg(Compact(quantity));
+			let call = DynamicCall::new("Proxy", "proxy")
+				.arg(MultiAddress::<_, ()>::Id(owner))
+				.arg(None::<ProxyType>)
+				.call_arg(mint);
+			dispatch_signed(&cfg, &options, "Proxy.proxy(NFT.mint)", call, |connection| async move {
+				connection.mint(to, class_id, metadata, attributes, quantity, TxStatus::Finalized).await
+			})
+			.await
+		},
+		NftCommand::Transfer { to, class_id, token_id } => {
+			let call = DynamicCall::new("NFT", "transfer")
+				.arg(MultiAddress::<_, ()>::Id(to.clone()))
+				.arg((class_id, token_id));
+			dispatch_signed(&cfg, &options, "NFT.transfer", call, |connection| async move {
+				connection.transfer_nft(to, (class_id, token_id), TxStatus::Finalized).await
+			})
+			.await
+		},
+		NftCommand::Burn { class_id, token_id } => {
+			let call = DynamicCall::new("NFT", "burn").arg((class_id, token_id));
+			dispatch_signed(&cfg, &options, "NFT.burn", call, |connection| async move {
+				connection.burn_nft((class_id, token_id), TxStatus::Finalized).await
+			})
+			.await
+		},
+		NftCommand::Owner { class_id, token_id } => {
+			let owner = cfg.get_connection().await.token_owner((class_id, token_id), None).await;
+			print_json(&TokenOwner { class_id, token_id, owner: owner.map(|owner| owner.to_string()) });
+			Ok(())
+		},
+	}
+}
@@ -0,0 +1,66 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use primitives::CurrencyId;
+use serde::Serialize;
+use setheum_client::{
+	pallets::oracle::{OracleApi, OracleUserApi},
+	DynamicCall, TxStatus,
+};
+
+use crate::{
+	call::{dispatch_governance, dispatch_signed, print_json},
+	commands::{CallOptions, OracleCommand},
+	ConnectionConfig,
+};
+
+#[derive(Serialize)]
+struct OracleValue {
+	currency: CurrencyId,
+	/// Price as a decimal string, `None` if nothing was fed yet.
+	price: Option<String>,
+	timestamp: Option<u64>,
+}
+
+/// Runs `clisee oracle` commands on top of `setheum_client::pallets::oracle`.
+pub async fn oracle(cfg: ConnectionConfig, options: CallOptions, cmd: OracleCommand) -> Result<()> {
+	match cmd {
+		OracleCommand::FeedValues { values } => {
+			let call = DynamicCall::new("SetheumOracle", "feed_values").arg(values.clone());
+			if options.sudo {
+				return dispatch_governance(&cfg, &options, "SetheumOracle.feed_values", call).await;
+			}
+			dispatch_signed(&cfg, &options, "SetheumOracle.feed_values", call, |connection| async move {
+				connection.feed_values(values, TxStatus::Finalized).await
+			})
+			.await
+		},
+		OracleCommand::Value { currency } => {
+			let value = cfg.get_connection().await.oracle_value(currency, None).await;
+			print_json(&OracleValue {
+				currency,
+				price: value.map(|(price, _)| price.to_string()),
+				timestamp: value.map(|(_, timestamp)| timestamp),
+			});
+			Ok(())
+		},
+	}
+}
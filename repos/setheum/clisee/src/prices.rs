@@ -0,0 +1,56 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use primitives::CurrencyId;
+use serde::Serialize;
+use setheum_client::{pallets::prices::PricesApi, DynamicCall};
+
+use crate::{
+	call::{dispatch_governance, print_json},
+	commands::{CallOptions, PricesCommand},
+	ConnectionConfig,
+};
+
+#[derive(Serialize)]
+struct LockedPrice {
+	currency: CurrencyId,
+	/// Price as a decimal string, `None` if the price is not locked.
+	locked_price: Option<String>,
+}
+
+/// Runs `clisee prices` commands on top of `setheum_client::pallets::prices`.
+pub async fn prices(cfg: ConnectionConfig, options: CallOptions, cmd: PricesCommand) -> Result<()> {
+	match cmd {
+		PricesCommand::Lock { currency } => {
+			let call = DynamicCall::new("Prices", "lock_price").arg(currency);
+			dispatch_governance(&cfg, &options, "Prices.lock_price", call).await
+		},
+		PricesCommand::Unlock { currency } => {
+			let call = DynamicCall::new("Prices", "unlock_price").arg(currency);
+			dispatch_governance(&cfg, &options, "Prices.unlock_price", call).await
+		},
+		PricesCommand::Locked { currency } => {
+			let locked_price = cfg.get_connection().await.locked_price(currency, None).await;
+			print_json(&LockedPrice { currency, locked_price: locked_price.map(|price| price.to_string()) });
+			Ok(())
+		},
+	}
+}
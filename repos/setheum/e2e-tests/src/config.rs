@@ -29,12 +29,44 @@ use itertools::izip;
 use once_cell::sync::Lazy;
 use primitives::SessionIndex;
 use setheum_client::{KeyPair, RootConnection, SignedConnection};
+use setheum_local_network::LocalNetwork;
 use url::Url;
 
 use crate::accounts::{get_sudo_key, get_validators_keys, get_validators_seeds, NodeKeys};
 
+/// Network spawned for the test binary when `NODE_URL` is not set, see [`local_network`].
+static LOCAL_NETWORK: Lazy<Option<LocalNetwork>> = Lazy::new(|| {
+	if env::var("NODE_URL").is_ok() {
+		return None;
+	}
+	let config = setheum_local_network::Config::new(get_env("VALIDATOR_COUNT").unwrap_or(5));
+	// Every `#[tokio::test]` has its own runtime and blocking on another one inside it panics.
+	let network = std::thread::spawn(move || {
+		tokio::runtime::Runtime::new()
+			.expect("Failed to create a runtime")
+			.block_on(LocalNetwork::start(config))
+	});
+	let network = network
+		.join()
+		.expect("Starting the local network panicked")
+		.expect("Failed to start the local network");
+	// Statics are never dropped, so the network is stopped, and its temporary base path removed,
+	// when the test binary exits.
+	// SAFETY: `stop_local_network` neither unwinds nor registers further exit handlers.
+	unsafe { libc::atexit(stop_local_network) };
+	Some(network)
+});
+
+extern "C" fn stop_local_network() {
+	if let Some(Some(network)) = Lazy::get(&LOCAL_NETWORK) {
+		network.stop();
+	}
+}
+
 static GLOBAL_CONFIG: Lazy<Config> = Lazy::new(|| {
-	let node = get_env("NODE_URL").unwrap_or_else(|| "ws://127.0.0.1:9944".to_string());
+	let node = get_env("NODE_URL")
+		.or_else(|| local_network().map(|network| network.ws_url(0)))
+		.expect("Either NODE_URL is set or a local network is running");
 	let validator_count = get_env("VALIDATOR_COUNT").unwrap_or(5);
 	let validators_seeds = env::var("VALIDATORS_SEEDS").ok().map(|s| s.split(',').map(|s| s.to_string()).collect());
 	let sudo_seed = get_env("SUDO_SEED").unwrap_or_else(|| "//Alice".to_string());
@@ -52,6 +84,7 @@ static GLOBAL_CONFIG: Lazy<Config> = Lazy::new(|| {
 			upgrade_finalization_wait_sessions: get_env("UPGRADE_FINALIZATION_WAIT_SESSIONS"),
 			adder: get_env("ADDER"),
 			adder_metadata: get_env("ADDER_METADATA"),
+			car_wash: get_env("CAR_WASH"),
 			back_to_the_future: get_env("BACK_TO_THE_FUTURE"),
 			early_bird_special: get_env("EARLY_BIRD_SPECIAL"),
 			the_pressiah_cometh: get_env("THE_PRESSIAH_COMETH"),
@@ -84,6 +117,12 @@ pub fn setup_test() -> &'static Config {
 	&GLOBAL_CONFIG
 }
 
+/// Validators spawned by the test binary itself, when it runs without `NODE_URL`. Tests that kill,
+/// restart or partition validators need it and are skipped against an externally managed chain.
+pub fn local_network() -> Option<&'static LocalNetwork> {
+	LOCAL_NETWORK.as_ref()
+}
+
 #[derive(Clone)]
 pub struct NodeConfig {
 	node: String,
@@ -278,6 +317,9 @@ pub struct TestCaseParams {
 	/// Adder contract metadata.
 	pub adder_metadata: Option<String>,
 
+	/// Build directory of the car-wash Move example, only used by Move tests.
+	pub car_wash: Option<String>,
+
 	/// Milliseconds of network latency
 	pub out_latency: Option<u64>,
 
@@ -0,0 +1,62 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use log::info;
+use primitives::{CurrencyId, TokenSymbol};
+use setheum_client::{
+	pallets::currencies::{CurrenciesApi, CurrenciesSudoApi, CurrenciesUserApi},
+	SignedConnectionApi, TxStatus,
+};
+
+use crate::{config::setup_test, transfer::setup_for_transfer};
+
+const SEUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SEUSD);
+
+#[tokio::test]
+pub async fn multi_currency_transfer() -> anyhow::Result<()> {
+	let config = setup_test();
+	let root_connection = config.create_root_connection().await;
+	let (connection, to) = setup_for_transfer(config).await;
+	let from = connection.account_id().clone();
+
+	let mint_amount = 1_000_000;
+	root_connection
+		.update_balance(from.clone(), SEUSD, mint_amount as i128, TxStatus::Finalized)
+		.await?;
+
+	let issuance_before = connection.token_total_issuance(SEUSD, None).await;
+	let balance_before = connection.token_free_balance(to.clone(), SEUSD, None).await;
+	info!("[+] Account {} SEUSD balance before tx: {}", to, balance_before);
+
+	let transfer_value = 1000;
+	connection.transfer_currency(to.clone(), SEUSD, transfer_value, TxStatus::Finalized).await?;
+
+	let balance_after = connection.token_free_balance(to.clone(), SEUSD, None).await;
+	info!("[+] Account {} SEUSD balance after tx: {}", to, balance_after);
+
+	assert_eq!(
+		balance_before + transfer_value,
+		balance_after,
+		"before = {balance_before}, after = {balance_after}, tx = {transfer_value}"
+	);
+	assert_eq!(issuance_before, connection.token_total_issuance(SEUSD, None).await);
+
+	Ok(())
+}
@@ -0,0 +1,97 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use log::info;
+use primitives::{CurrencyId, TokenSymbol, TradingPair};
+use setheum_client::{
+	pallets::{
+		currencies::{CurrenciesApi, CurrenciesSudoApi},
+		dex::{DexApi, DexSudoApi, DexUserApi, TradingPairStatus},
+	},
+	RootConnection, SignedConnection, SignedConnectionApi, TxStatus,
+};
+
+use crate::{config::setup_test, transfer::setup_for_transfer};
+
+const SEU: CurrencyId = CurrencyId::Token(TokenSymbol::SEU);
+const SEUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SEUSD);
+
+fn trading_pair() -> TradingPair {
+	TradingPair::from_currency_ids(SEU, SEUSD).expect("SEU and SEUSD should form a trading pair")
+}
+
+/// Enables SEU/SEUSD trading pair unless it is already enabled and seeds it with liquidity.
+async fn setup_pool(root_connection: &RootConnection, provider: &SignedConnection) -> anyhow::Result<()> {
+	if !matches!(provider.trading_pair_status(trading_pair(), None).await, TradingPairStatus::Enabled) {
+		root_connection.enable_trading_pair(SEU, SEUSD, TxStatus::Finalized).await?;
+	}
+
+	let liquidity = 1_000_000_000;
+	root_connection
+		.update_balance(provider.account_id().clone(), SEUSD, liquidity as i128, TxStatus::Finalized)
+		.await?;
+	provider.add_liquidity(SEU, SEUSD, liquidity, liquidity, 0, TxStatus::Finalized).await?;
+
+	Ok(())
+}
+
+#[tokio::test]
+pub async fn swap_with_exact_supply() -> anyhow::Result<()> {
+	let config = setup_test();
+	let root_connection = config.create_root_connection().await;
+	let (connection, _) = setup_for_transfer(config).await;
+	let trader = connection.account_id().clone();
+
+	setup_pool(&root_connection, &connection).await?;
+
+	let pool_before = connection.liquidity_pool(trading_pair(), None).await;
+	let balance_before = connection.token_free_balance(trader.clone(), SEUSD, None).await;
+	info!("[+] Pool before swap: {:?}, trader SEUSD balance: {}", pool_before, balance_before);
+
+	let supply_amount = 10_000;
+	connection.swap_with_exact_supply(vec![SEU, SEUSD], supply_amount, 1, TxStatus::Finalized).await?;
+
+	let pool_after = connection.liquidity_pool(trading_pair(), None).await;
+	let balance_after = connection.token_free_balance(trader, SEUSD, None).await;
+	info!("[+] Pool after swap: {:?}, trader SEUSD balance: {}", pool_after, balance_after);
+
+	let received = balance_after - balance_before;
+	assert!(received > 0, "Swap should yield some SEUSD");
+	assert_eq!(pool_before.0 + supply_amount, pool_after.0);
+	assert_eq!(pool_before.1 - received, pool_after.1);
+
+	Ok(())
+}
+
+#[tokio::test]
+pub async fn set_trading_fee() -> anyhow::Result<()> {
+	let config = setup_test();
+	let root_connection = config.create_root_connection().await;
+	let (connection, _) = setup_for_transfer(config).await;
+
+	setup_pool(&root_connection, &connection).await?;
+
+	let trading_fee = (1, 100);
+	root_connection.set_trading_fee(SEU, SEUSD, trading_fee, TxStatus::Finalized).await?;
+
+	assert_eq!(connection.trading_fee(trading_pair(), None).await, Some(trading_fee));
+
+	Ok(())
+}
@@ -0,0 +1,84 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::Duration;
+
+use log::info;
+use serial_test::serial;
+use setheum_client::{
+	utility::BlocksApi,
+	waiting::{BlockStatus, SetheumWaiting},
+};
+use setheum_local_network::LocalNetwork;
+
+use crate::config::{local_network, setup_test};
+
+const FINALITY_TIMEOUT: Duration = Duration::from_secs(120);
+const BLOCKS_TO_FINALIZE: u32 = 5;
+
+fn require_local_network() -> Option<&'static LocalNetwork> {
+	setup_test();
+	let network = local_network();
+	if network.is_none() {
+		info!("Skipping, the test needs validators spawned by the test itself, i.e. no NODE_URL");
+	}
+	network
+}
+
+async fn finalized_block(network: &LocalNetwork, validator: usize) -> anyhow::Result<u32> {
+	let connection = network.connection(validator).await;
+	let hash = connection.get_finalized_block_hash().await?;
+	Ok(connection.get_block_number(hash).await?.unwrap_or_default())
+}
+
+/// Isolates one validator with the SetBFT network proxy, checks that the remaining supermajority
+/// keeps finalizing, and that the isolated validator catches up once the partition is healed.
+#[tokio::test]
+#[serial]
+pub async fn isolated_validator_catches_up() -> anyhow::Result<()> {
+	let Some(network) = require_local_network() else { return Ok(()) };
+	let isolated = network.validator_count() - 1;
+
+	network.partition(&[(0..isolated).collect(), vec![isolated]])?;
+	let target = finalized_block(network, 0).await? + BLOCKS_TO_FINALIZE;
+	let majority = network.connection(0).await;
+	info!("Waiting for block {target} to be finalized without validator {isolated}");
+	tokio::time::timeout(FINALITY_TIMEOUT, majority.wait_for_block(|n| n >= target, BlockStatus::Finalized)).await?;
+
+	network.heal();
+	network.wait_for_finality(target, FINALITY_TIMEOUT).await
+}
+
+/// Kills one validator, checks that the others keep finalizing, and that the validator catches up
+/// after a restart on its old database.
+#[tokio::test]
+#[serial]
+pub async fn restarted_validator_catches_up() -> anyhow::Result<()> {
+	let Some(network) = require_local_network() else { return Ok(()) };
+	let restarted = network.validator_count() - 1;
+
+	network.kill(restarted)?;
+	let target = finalized_block(network, 0).await? + BLOCKS_TO_FINALIZE;
+	info!("Waiting for block {target} to be finalized without validator {restarted}");
+	network.wait_for_finality(target, FINALITY_TIMEOUT).await?;
+
+	network.restart(restarted).await?;
+	network.wait_for_finality(target + BLOCKS_TO_FINALIZE, FINALITY_TIMEOUT).await
+}
@@ -22,6 +22,8 @@ mod adder;
 mod ban;
 mod button_game;
 mod committee_split;
+mod currencies;
+mod dex;
 mod electing_validators;
 mod emergency_finalizer;
 mod era_payout;
@@ -31,6 +33,10 @@ mod finalization;
 mod helpers;
 mod high_latency;
 mod lenient_threshold;
+mod local_network;
+mod move_vm;
+mod nft;
+mod oracle;
 mod performance;
 mod proxy;
 mod rewards;
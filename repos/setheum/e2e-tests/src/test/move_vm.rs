@@ -0,0 +1,96 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{fs, path::Path};
+
+use anyhow::Context;
+use log::info;
+use setheum_client::{
+	account_from_keypair, keypair_from_string,
+	pallets::{
+		move_vm::{Estimation, MoveModuleApi, MoveModuleUserApi},
+		system::SystemApi,
+	},
+	Balance, SignedConnection, TxStatus,
+};
+
+use crate::{
+	config::setup_test,
+	test::helpers::{setheums, transfer},
+};
+
+/// Price of a single coin, as defined in the `CarWash` module.
+const COIN_PRICE: Balance = 1_000_000_000_000;
+
+fn gas_limit(estimation: &Estimation) -> anyhow::Result<u32> {
+	u32::try_from(estimation.gas_used).context("Gas estimation does not fit into the gas limit")
+}
+
+async fn run_script(
+	connection: &SignedConnection,
+	build_dir: &Path,
+	script: &str,
+	cheque_limit: Balance,
+) -> anyhow::Result<()> {
+	let transaction = fs::read(build_dir.join(format!("script_transactions/{script}.mvt")))?;
+	let estimation = connection.estimate_gas_execute_script(transaction.clone()).await?;
+	connection.execute(transaction, gas_limit(&estimation)?, cheque_limit, TxStatus::Finalized).await?;
+	info!("[+] Executed {}", script);
+
+	Ok(())
+}
+
+/// Publishes the car-wash bundle as DeveloperBob (`//Bob`) and runs its scripts as a customer
+/// (`//Alice`), who pays for a coin in the native currency and spends it on a wash.
+#[tokio::test]
+pub async fn car_wash_publish_and_execute() -> anyhow::Result<()> {
+	let config = setup_test();
+	let build_dir = config.test_case_params.car_wash.clone().context("CAR_WASH must be set for Move tests")?;
+	let build_dir = Path::new(&build_dir);
+
+	let customer = keypair_from_string("//Alice");
+	let developer = keypair_from_string("//Bob");
+	let customer_connection = SignedConnection::new(&config.node, customer).await;
+	transfer(&customer_connection, &developer, setheums(10)).await?;
+	let developer_account = account_from_keypair(developer.signer());
+	let developer_connection = SignedConnection::new(&config.node, developer).await;
+
+	let bundle = fs::read(build_dir.join("bundles/car-wash-example.mvb"))?;
+	let estimation =
+		developer_connection.estimate_gas_publish_bundle(developer_account.clone(), bundle.clone()).await?;
+	developer_connection.publish_module_bundle(bundle, gas_limit(&estimation)?, TxStatus::Finalized).await?;
+	info!("[+] Published car-wash bundle");
+
+	let abi = developer_connection.get_module_abi(developer_account.clone(), "CarWash".to_string()).await?;
+	assert!(abi.is_some(), "CarWash module should be published");
+
+	run_script(&developer_connection, build_dir, "initial_coin_minting", 0).await?;
+
+	// From now on the developer only receives payments, so its balance grows exactly by the coin price.
+	let developer_balance = developer_connection.get_free_balance(developer_account.clone(), None).await;
+	run_script(&customer_connection, build_dir, "register_new_user", 0).await?;
+	run_script(&customer_connection, build_dir, "buy_coin", COIN_PRICE).await?;
+	run_script(&customer_connection, build_dir, "wash_car", 0).await?;
+
+	let developer_balance_after = developer_connection.get_free_balance(developer_account, None).await;
+	assert_eq!(developer_balance_after, developer_balance + COIN_PRICE);
+
+	Ok(())
+}
@@ -0,0 +1,71 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use log::info;
+use primitives::nft::{Attributes, ClassProperty, Properties};
+use setheum_client::{
+	account_from_keypair,
+	pallets::nft::{NftApi, NftUserApi},
+	KeyPair, SignedConnection, SignedConnectionApi, TxStatus,
+};
+
+use crate::{accounts::get_validators_raw_keys, config::setup_test};
+
+#[tokio::test]
+pub async fn create_class_mint_and_transfer() -> anyhow::Result<()> {
+	let config = setup_test();
+	let accounts = get_validators_raw_keys(config);
+	let connection = SignedConnection::new(&config.node, KeyPair::new(accounts[0].clone())).await;
+	let receiver = KeyPair::new(accounts[1].clone());
+	let receiver_connection = SignedConnection::new(&config.node, receiver.clone()).await;
+	let receiver = account_from_keypair(receiver.signer());
+
+	let class_id = connection.next_class_id(None).await;
+	let properties = Properties(ClassProperty::Transferable | ClassProperty::Burnable | ClassProperty::Mintable);
+	connection
+		.create_class(b"class metadata".to_vec(), properties, Attributes::new(), TxStatus::Finalized)
+		.await?;
+	info!("[+] Created nft class {}", class_id);
+
+	// Class is owned by a pallet derived account, the creator acts as its proxy.
+	let class_owner = connection.class_owner(class_id, None).await.expect("Class should exist");
+	assert_ne!(&class_owner, connection.account_id());
+
+	let token_id = connection.next_token_id(class_id, None).await;
+	connection
+		.mint(
+			connection.account_id().clone(),
+			class_id,
+			b"token metadata".to_vec(),
+			Attributes::new(),
+			1,
+			TxStatus::Finalized,
+		)
+		.await?;
+	assert_eq!(connection.token_owner((class_id, token_id), None).await.as_ref(), Some(connection.account_id()));
+
+	connection.transfer_nft(receiver.clone(), (class_id, token_id), TxStatus::Finalized).await?;
+	assert_eq!(connection.token_owner((class_id, token_id), None).await, Some(receiver));
+
+	receiver_connection.burn_nft((class_id, token_id), TxStatus::Finalized).await?;
+	assert_eq!(connection.token_owner((class_id, token_id), None).await, None);
+
+	Ok(())
+}
@@ -0,0 +1,60 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use log::info;
+use primitives::{CurrencyId, TokenSymbol};
+use setheum_client::{
+	pallets::{
+		oracle::{OracleApi, OracleSudoApi},
+		prices::{PricesApi, PricesSudoApi},
+	},
+	sp_runtime::FixedU128,
+	TxStatus,
+};
+
+use crate::config::setup_test;
+
+const SEU: CurrencyId = CurrencyId::Token(TokenSymbol::SEU);
+
+#[tokio::test]
+pub async fn feed_and_lock_price() -> anyhow::Result<()> {
+	let config = setup_test();
+	let root_connection = config.create_root_connection().await;
+
+	let price = FixedU128::from_rational(3, 2);
+	root_connection.feed_values(vec![(SEU, price)], TxStatus::Finalized).await?;
+
+	let (fed_price, timestamp) = root_connection
+		.oracle_value(SEU, None)
+		.await
+		.expect("Oracle should have a value for SEU after feeding it");
+	info!("[+] SEU oracle price: {} fed at {}", fed_price, timestamp);
+	assert_eq!(fed_price, price);
+
+	root_connection.lock_price(SEU, TxStatus::Finalized).await?;
+	let locked_price = root_connection.locked_price(SEU, None).await;
+	info!("[+] SEU locked price: {:?}", locked_price);
+	assert!(locked_price.is_some(), "Price of SEU should be locked");
+
+	root_connection.unlock_price(SEU, TxStatus::Finalized).await?;
+	assert_eq!(root_connection.locked_price(SEU, None).await, None);
+
+	Ok(())
+}
@@ -25,6 +25,7 @@
 
 use frame_support::weights::Weight;
 use module_setbft_runtime_api::*;
+use pallet_move::api::{ModuleAbi, MoveApiEstimation};
 use pallet_transaction_payment::FeeDetails;
 use pallet_transaction_payment_rpc_runtime_api::RuntimeDispatchInfo;
 use primitives::{
@@ -37,7 +38,7 @@ use sp_core::OpaqueMetadata;
 use sp_runtime::{
 	traits::Block as BlockT,
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, ExtrinsicInclusionMode,
+	ApplyExtrinsicResult, DispatchError, ExtrinsicInclusionMode,
 };
 use sp_std::vec::Vec;
 use sp_version::RuntimeVersion;
@@ -161,6 +162,32 @@ pub mod fake_runtime {
 			}
 		}
 
+		impl pallet_move::api::MoveApi<Block, AccountId> for Runtime {
+			fn estimate_gas_publish_module(_: AccountId, _: Vec<u8>) -> Result<MoveApiEstimation, DispatchError> {
+				unimplemented!()
+			}
+
+			fn estimate_gas_publish_bundle(_: AccountId, _: Vec<u8>) -> Result<MoveApiEstimation, DispatchError> {
+				unimplemented!()
+			}
+
+			fn estimate_gas_execute_script(_: Vec<u8>) -> Result<MoveApiEstimation, DispatchError> {
+				unimplemented!()
+			}
+
+			fn get_resource(_: AccountId, _: Vec<u8>) -> Result<Option<Vec<u8>>, Vec<u8>> {
+				unimplemented!()
+			}
+
+			fn get_module_abi(_: Vec<u8>) -> Result<Option<ModuleAbi>, Vec<u8>> {
+				unimplemented!()
+			}
+
+			fn get_module(_: Vec<u8>) -> Result<Option<Vec<u8>>, Vec<u8>> {
+				unimplemented!()
+			}
+		}
+
 		 impl crate::SetBFTSessionApi<Block> for Runtime {
 			fn millisecs_per_block() -> u64 {
 				unimplemented!()
@@ -28,8 +28,9 @@ mod network;
 mod performance;
 mod traits;
 
-pub use network::NetworkData;
+pub use network::{BatchedNetworkData, NetworkData};
 pub use performance::{Service as PerformanceService, ServiceIO as PerformanceServiceIO};
+pub use traits::LastOfBatch;
 
 pub use crate::setbft_primitives::CURRENT_FINALITY_VERSION as VERSION;
 use crate::{
@@ -37,38 +38,33 @@ use crate::{
         common::{unit_creation_delay_fn, MAX_ROUNDS, SESSION_LEN_LOWER_BOUND_MS},
         NetworkWrapper,
     },
-    block::UnverifiedHeader,
     crypto::Signature,
-    data_io::SetBFTData,
     network::data::Network,
     oneshot,
     party::{
         backup::SBFTBackup,
         manager::{Task, TaskCommon},
     },
-    CurrentNetworkData, Hasher, Keychain, NodeIndex, SessionId, SignatureSet, UnitCreationDelay,
+    Hasher, Keychain, NodeIndex, SessionId, SignatureSet, UnitCreationDelay,
 };
 
-type WrappedNetwork<H, ADN> = NetworkWrapper<
-    set_bft::NetworkData<Hasher, SetBFTData<H>, Signature, SignatureSet<Signature>>,
-    ADN,
->;
+type WrappedNetwork<D, ADN> =
+    NetworkWrapper<set_bft::NetworkData<Hasher, D, Signature, SignatureSet<Signature>>, ADN>;
 
-pub fn run_member<UH, ADN>(
+/// Runs the member of a session ordering `D`, which is `SetBFTData` for sessions of
+/// `UNBATCHED_FINALITY_VERSION` and batches of it for sessions of `CURRENT_FINALITY_VERSION`.
+pub fn run_member<D, ADN>(
     subtask_common: TaskCommon,
     multikeychain: Keychain,
     config: Config,
-    network: WrappedNetwork<UH, ADN>,
-    data_provider: impl set_bft::DataProvider<Output = SetBFTData<UH>> + 'static,
-    ordered_data_interpreter: impl set_bft::UnitFinalizationHandler<
-        Data = SetBFTData<UH>,
-        Hasher = Hasher,
-    >,
+    network: WrappedNetwork<D, ADN>,
+    data_provider: impl set_bft::DataProvider<Output = D> + 'static,
+    ordered_data_interpreter: impl set_bft::UnitFinalizationHandler<Data = D, Hasher = Hasher>,
     backup: SBFTBackup,
 ) -> Task
 where
-    UH: UnverifiedHeader,
-    ADN: Network<CurrentNetworkData<UH>> + 'static,
+    D: set_bft::Data,
+    ADN: Network<set_bft::NetworkData<Hasher, D, Signature, SignatureSet<Signature>>> + 'static,
 {
     let TaskCommon {
         spawn_handle,
@@ -18,19 +18,45 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use set_bft::DataBatch;
+
 use crate::{
     sbft::SignatureSet,
     block::UnverifiedHeader,
     crypto::Signature,
     data_io::{SetBFTData, SetBFTNetworkMessage},
-    Hasher,
+    setbft_primitives::{CURRENT_FINALITY_VERSION, UNBATCHED_FINALITY_VERSION},
+    Hasher, Version, Versioned,
 };
 
+/// Network data of sessions of `UNBATCHED_FINALITY_VERSION`, with units carrying one proposal.
 pub type NetworkData<UH> =
     set_bft::NetworkData<Hasher, SetBFTData<UH>, Signature, SignatureSet<Signature>>;
 
+/// Network data of sessions of `CURRENT_FINALITY_VERSION`, with units carrying batches of
+/// proposals.
+pub type BatchedNetworkData<UH> =
+    set_bft::NetworkData<Hasher, DataBatch<SetBFTData<UH>>, Signature, SignatureSet<Signature>>;
+
 impl<UH: UnverifiedHeader> SetBFTNetworkMessage<UH> for NetworkData<UH> {
     fn included_data(&self) -> Vec<SetBFTData<UH>> {
         self.included_data()
     }
 }
+
+impl<UH: UnverifiedHeader> SetBFTNetworkMessage<UH> for BatchedNetworkData<UH> {
+    fn included_data(&self) -> Vec<SetBFTData<UH>> {
+        self.included_data()
+            .into_iter()
+            .flat_map(DataBatch::into_items)
+            .collect()
+    }
+}
+
+impl<UH: UnverifiedHeader> Versioned for NetworkData<UH> {
+    const VERSION: Version = Version(UNBATCHED_FINALITY_VERSION);
+}
+
+impl<UH: UnverifiedHeader> Versioned for BatchedNetworkData<UH> {
+    const VERSION: Version = Version(CURRENT_FINALITY_VERSION);
+}
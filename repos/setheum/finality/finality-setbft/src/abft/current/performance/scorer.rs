@@ -58,6 +58,15 @@ impl Scorer {
             })
             .collect()
     }
+
+    /// The rounds of the newest units of all the nodes that were ordered so far.
+    pub fn newest_rounds(&self) -> Vec<Option<Round>> {
+        self.newest_unit_by
+            .size()
+            .into_iterator()
+            .map(|node_id| self.newest_unit_by.get(node_id).copied())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -99,6 +108,10 @@ mod tests {
             .pop()
             .expect("there is a unit");
         assert_eq!(scorer.process_batch(vec![unit]), vec![1, 1, 1, 1, 1, 1, 0]);
+        assert_eq!(
+            scorer.newest_rounds(),
+            vec![None, None, None, None, None, None, Some(0)]
+        );
     }
 
     #[test]
@@ -41,6 +41,7 @@ use crate::{
     metrics::ScoreMetrics,
     party::manager::Runnable,
     runtime_api::RuntimeApi,
+    validator_health::ValidatorHealthReporter,
     Hasher, SessionId, UnverifiedHeader,
 };
 
@@ -103,6 +104,7 @@ where
     nonce: ScoreNonce,
     scorer: Scorer,
     metrics: ScoreMetrics,
+    validator_health: ValidatorHealthReporter,
 }
 
 pub struct ServiceIO {
@@ -128,6 +130,7 @@ where
         io: ServiceIO,
         runtime_api: RA,
         metrics: ScoreMetrics,
+        validator_health: ValidatorHealthReporter,
     ) -> (
         Self,
         impl set_bft::UnitFinalizationHandler<Data = SetBFTData<UH>, Hasher = Hasher>,
@@ -153,6 +156,7 @@ where
                 nonce: 1,
                 scorer: Scorer::new(NodeCount(n_members)),
                 metrics,
+                validator_health,
             },
             FinalizationWrapper::new(finalization_handler, batches_for_us),
         )
@@ -189,6 +193,7 @@ where
                     };
                     self.metrics.report_score(points[self.my_index]);
                     if batch_counter % self.score_submission_period == 0 {
+                        self.validator_health.report_scores(self.session_id, &points, &self.scorer.newest_rounds());
                         let score = self.make_score(points);
                         let score_hash = Hashing::hash_of(&score.encode());
                         debug!(target: LOG_TARGET, "Gathering signature under SBFT score: {:?}.", score);
@@ -19,6 +19,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! Implementations and definitions of traits used in current sbft
+use set_bft::{DataBatch, OrderedUnit, UnitFinalizationHandler};
+
 use crate::{
     block::{Header, HeaderVerifier, UnverifiedHeader},
     data_io::{SetBFTData, ChainInfoProvider, DataProvider, OrderedDataInterpreter},
@@ -44,3 +46,40 @@ where
         OrderedDataInterpreter::data_finalized(self, data)
     }
 }
+
+/// Passes units carrying batches of data to a handler of units carrying single pieces of data,
+/// replacing every batch with its last item.
+///
+/// Items of a batch are proposals taken from the chain tracker in the order it made them, so the
+/// last one is the most recent view of the chain of the unit creator and finalizing it finalizes
+/// everything the earlier ones would.
+pub struct LastOfBatch<UFH> {
+    unit_finalization_handler: UFH,
+}
+
+impl<UFH> LastOfBatch<UFH> {
+    pub fn new(unit_finalization_handler: UFH) -> Self {
+        LastOfBatch {
+            unit_finalization_handler,
+        }
+    }
+}
+
+impl<UFH: UnitFinalizationHandler> UnitFinalizationHandler for LastOfBatch<UFH> {
+    type Data = DataBatch<UFH::Data>;
+    type Hasher = UFH::Hasher;
+
+    fn batch_finalized(&mut self, batch: Vec<OrderedUnit<Self::Data, Self::Hasher>>) {
+        let batch = batch
+            .into_iter()
+            .map(|unit| OrderedUnit {
+                data: unit.data.and_then(|items| items.into_items().pop()),
+                parents: unit.parents,
+                hash: unit.hash,
+                creator: unit.creator,
+                round: unit.round,
+            })
+            .collect();
+        self.unit_finalization_handler.batch_finalized(batch)
+    }
+}
@@ -39,7 +39,7 @@ use set_bft_crypto::{PartialMultisignature, Signature as SbftSignature};
 pub use crypto::Keychain;
 pub use current::{
     create_setbft_config, run_member,
-    NetworkData, PerformanceService,
+    BatchedNetworkData, LastOfBatch, NetworkData, PerformanceService,
     PerformanceServiceIO, VERSION,
 };
 pub use network::NetworkWrapper;
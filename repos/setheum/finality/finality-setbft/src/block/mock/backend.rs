@@ -25,6 +25,7 @@ use std::{
 };
 
 use futures::channel::mpsc::{self, UnboundedSender};
+use parity_scale_codec::Encode;
 use parking_lot::Mutex;
 
 use crate::{
@@ -32,7 +33,8 @@ use crate::{
         mock::{MockBlock, MockHeader, MockJustification, MockNotification},
         Block, BlockImport, BlockStatus, ChainStatus, ChainStatusNotifier,
         EquivocationProof as EquivocationProofT, FinalizationStatus, Finalizer, Header,
-        HeaderVerifier, Justification as JustificationT, JustificationVerifier, VerifiedHeader,
+        HeaderVerifier, Justification as JustificationT, JustificationVerifier, StorageProof,
+        VerifiedHeader, WarpSyncVerifier,
     },
     nodes::VERIFIER_CACHE_SIZE,
     session::{SessionBoundaryInfo, SessionId},
@@ -297,7 +299,7 @@ impl BlockImport<MockBlock> for Backend {
             .number();
         let mut storage = self.inner.lock();
 
-        let parent_id = match block.parent_id() {
+        let parent_id = match Header::parent_id(&block) {
             Some(id) => id,
             None => return,
         };
@@ -315,6 +317,20 @@ impl BlockImport<MockBlock> for Backend {
 
         self.notify_imported(block.header);
     }
+
+    fn import_history_block(&mut self, block: MockBlock) {
+        if !block.verify() {
+            return;
+        }
+
+        let mut storage = self.inner.lock();
+        let parent_present = Header::parent_id(&block)
+            .map(|parent_id| storage.blockchain.contains_key(&parent_id))
+            .unwrap_or(false);
+        if parent_present && !storage.blockchain.contains_key(&block.id()) {
+            storage.blockchain.insert(block.id(), block);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -400,6 +416,14 @@ impl ChainStatus<MockBlock, MockJustification> for Backend {
             }
         }
     }
+
+    fn next_authorities_proof(&self, id: BlockId) -> Result<StorageProof, Self::Error> {
+        match self.inner.lock().blockchain.contains_key(&id) {
+            // The mock authorities are trivial, so the proof just has to tie them to the block.
+            true => Ok(vec![id.encode()]),
+            false => Err(StatusError),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -469,6 +493,24 @@ impl JustificationVerifier<MockJustification> for Backend {
     }
 }
 
+impl WarpSyncVerifier<MockJustification> for Backend {
+    type Error = VerifierError;
+
+    fn verify_session_end(
+        &mut self,
+        justification: MockJustification,
+        next_authorities_proof: StorageProof,
+    ) -> Result<MockJustification, Self::Error> {
+        if next_authorities_proof != vec![justification.header().id().encode()] {
+            return Err(Self::Error::Session);
+        }
+        match justification.is_correct {
+            true => Ok(justification),
+            false => Err(Self::Error::Justification),
+        }
+    }
+}
+
 impl HeaderVerifier<MockHeader> for Backend {
     type EquivocationProof = EquivocationProof;
     type Error = VerifierError;
@@ -209,6 +209,10 @@ impl Block for MockBlock {
     fn header(&self) -> &Self::UnverifiedHeader {
         &self.header
     }
+
+    fn parent_id(&self) -> Option<BlockId> {
+        self.header.parent.clone()
+    }
 }
 
 #[derive(Clone, Hash, Debug, PartialEq, Eq, Encode, Decode)]
@@ -151,12 +151,51 @@ pub trait Block: Clone + Codec + Debug + Send + Sync + 'static {
 
     /// The header of the block.
     fn header(&self) -> &Self::UnverifiedHeader;
+
+    /// The identifier of this block's parent, as claimed by the block.
+    /// Only trustworthy if the identifier of the block itself is known to be correct.
+    fn parent_id(&self) -> Option<BlockId>;
 }
 
 /// The block importer.
 pub trait BlockImport<B>: Send + 'static {
     /// Import the block.
     fn import_block(&mut self, block: B, own: bool);
+
+    /// Import a block below the top finalized block, without executing it.
+    /// Used for filling in the history skipped by warp sync, the parent of the block
+    /// must already be present in the database.
+    fn import_history_block(&mut self, block: B);
+}
+
+/// Trie nodes proving some values in the state of a block.
+pub type StorageProof = Vec<Vec<u8>>;
+
+/// A verifier of the justifications in warp sync proofs.
+/// Unlike `JustificationVerifier` it cannot rely on authorities read from the local state,
+/// instead it learns the authorities of every session from proofs read from the state
+/// at the last block of the previous one.
+pub trait WarpSyncVerifier<J: Justification>: Send + 'static {
+    type Error: Display + Debug;
+
+    /// Verify the justification of the last block of the current session, and then use the
+    /// proof to switch to the authorities of the next session.
+    fn verify_session_end(
+        &mut self,
+        justification: J::Unverified,
+        next_authorities_proof: StorageProof,
+    ) -> Result<J, Self::Error>;
+}
+
+/// Imports the target of warp sync, i.e. a finalized block together with its state,
+/// without any of its ancestors.
+#[async_trait::async_trait]
+pub trait WarpTargetImport<J: Justification, I>: Send {
+    type Error: Display;
+
+    /// Download the state of the block finalized by the justification from the given peers,
+    /// and import the block as the top finalized one. Returns once the import is finished.
+    async fn import_target(&mut self, justification: J, peers: Vec<I>) -> Result<(), Self::Error>;
 }
 
 /// A facility for finalizing blocks using justifications.
@@ -251,6 +290,10 @@ where
 
     /// Children of the specified block.
     fn children(&self, id: BlockId) -> Result<Vec<J::Header>, Self::Error>;
+
+    /// A proof of the authorities of the session following the one containing the specified
+    /// block, read from its state. Only ever requested for the last blocks of sessions.
+    fn next_authorities_proof(&self, id: BlockId) -> Result<StorageProof, Self::Error>;
 }
 
 pub trait HeaderBackend<H: Header>: Send + Sync {
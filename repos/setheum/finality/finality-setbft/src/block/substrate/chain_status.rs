@@ -24,19 +24,20 @@ use std::{
 };
 
 use log::warn;
-use sc_client_api::{blockchain::HeaderBackend as _, Backend as _};
+use sc_client_api::{blockchain::HeaderBackend as _, Backend as _, TrieCacheContext};
 use sc_service::TFullBackend;
 use sp_blockchain::{Backend as _, Error as BackendError, Info};
 use sp_runtime::traits::{Block as SubstrateBlock, Header as SubstrateHeader};
+use sp_state_machine::prove_read;
 
 use crate::{
     setbft_primitives::{
         Block, BlockNumber, Hash as SetBFTHash, Header as SetBFTHeader, SETBFT_ENGINE_ID,
     },
     block::{
-        substrate::{Justification, LOG_TARGET},
+        substrate::{verification::next_authorities_keys, Justification, LOG_TARGET},
         BlockHash, BlockStatus, ChainStatus, FinalizationStatus, Header, HeaderBackend,
-        Justification as _,
+        Justification as _, StorageProof,
     },
     justification::backwards_compatible_decode,
     BlockId,
@@ -51,6 +52,7 @@ pub enum Error {
     Backend(BackendError),
     MismatchedId,
     NoGenesisBlock,
+    StateProof(String),
 }
 
 impl Display for Error {
@@ -80,6 +82,7 @@ impl Display for Error {
             }
             MismatchedId => write!(f, "the block number did not match the block hash"),
             NoGenesisBlock => write!(f, "genesis block not present in DB"),
+            StateProof(e) => write!(f, "could not prove state read: {e}"),
         }
     }
 }
@@ -264,6 +267,18 @@ impl ChainStatus<Block, Justification> for SubstrateChainStatus {
             .flatten()
             .collect())
     }
+
+    fn next_authorities_proof(&self, id: BlockId) -> Result<StorageProof, Self::Error> {
+        if self.header(&id)?.is_none() {
+            return Err(Error::MissingHash(id.hash));
+        }
+        let state = self
+            .backend
+            .state_at(id.hash, TrieCacheContext::Untrusted)?;
+        let proof = prove_read(state, next_authorities_keys())
+            .map_err(|e| Error::StateProof(e.to_string()))?;
+        Ok(proof.into_iter_nodes().collect())
+    }
 }
 
 impl HeaderBackend<SetBFTHeader> for SubstrateChainStatus {
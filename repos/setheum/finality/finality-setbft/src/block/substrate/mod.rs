@@ -34,13 +34,17 @@ mod finalizer;
 mod justification;
 mod status_notifier;
 mod verification;
+mod warp;
 
 pub use chain_status::SubstrateChainStatus;
 pub use justification::{
     InnerJustification, Justification, JustificationTranslator, TranslateError,
 };
 pub use status_notifier::SubstrateChainStatusNotifier;
-pub use verification::{SubstrateFinalizationInfo, VerifierCache};
+pub use verification::{
+    SubstrateFinalizationInfo, VerifierCache, WarpVerificationError, WarpVerifier,
+};
+pub use warp::{Error as StateDownloadError, StateDownloader};
 
 use crate::{
     block::{BestBlockSelector, BlockchainEvents},
@@ -124,6 +128,25 @@ impl BlockImport<Block> for BlockImporter {
         self.metrics.report_block(hash, Checkpoint::Importing);
         self.importer.import_blocks(origin, vec![incoming_block]);
     }
+
+    fn import_history_block(&mut self, block: Block) {
+        // Blocks below the warp sync target cannot be executed, as we lack their parents' state,
+        // they only fill the gap in the database.
+        let incoming_block = IncomingBlock::<Block> {
+            hash: block.header.hash(),
+            header: Some(block.header),
+            body: Some(block.extrinsics),
+            indexed_body: None,
+            justifications: None,
+            origin: None,
+            allow_missing_state: true,
+            skip_execution: true,
+            import_existing: false,
+            state: None,
+        };
+        self.importer
+            .import_blocks(BlockOrigin::NetworkInitialSync, vec![incoming_block]);
+    }
 }
 
 impl BlockT for Block {
@@ -133,6 +156,10 @@ impl BlockT for Block {
     fn header(&self) -> &Self::UnverifiedHeader {
         &self.header
     }
+
+    fn parent_id(&self) -> Option<BlockId> {
+        HeaderT::parent_id(&self.header)
+    }
 }
 
 impl<C: sc_client_api::BlockchainEvents<Block> + Send> BlockchainEvents<Header> for C {
@@ -41,8 +41,10 @@ use crate::{
 
 mod cache;
 mod verifier;
+mod warp;
 
 pub use cache::VerifierCache;
+pub use warp::{next_authorities_keys, WarpVerificationError, WarpVerifier};
 
 /// Substrate specific implementation of `FinalizationInfo`
 pub struct SubstrateFinalizationInfo<BE: HeaderBackend<Block>>(Arc<BE>);
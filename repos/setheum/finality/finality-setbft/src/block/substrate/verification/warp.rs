@@ -0,0 +1,171 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::{Display, Error as FmtError, Formatter};
+
+use parity_scale_codec::{Decode, Encode};
+use sp_core::twox_128;
+use sp_runtime::traits::{BlakeTwo256, Header as SubstrateHeader};
+use sp_state_machine::read_proof_check;
+use sp_trie::StorageProof as SubstrateStorageProof;
+
+use crate::{
+    block::{
+        substrate::{
+            verification::verifier::{SessionVerificationError, SessionVerifier},
+            InnerJustification, Justification,
+        },
+        StorageProof, WarpSyncVerifier,
+    },
+    session::{SessionBoundaryInfo, SessionId},
+    setbft_primitives::{BlockNumber, Header, SessionAuthorityData},
+    AuthorityId,
+};
+
+const SETBFT_PALLET: &str = "SetBFT";
+const NEXT_AUTHORITIES: &str = "NextAuthorities";
+const QUEUED_EMERGENCY_FINALIZER: &str = "QueuedEmergencyFinalizer";
+
+fn storage_value_key(pallet: &str, item: &str) -> Vec<u8> {
+    [twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// The storage keys from which the authority data of the next session can be read.
+pub fn next_authorities_keys() -> Vec<Vec<u8>> {
+    vec![
+        storage_value_key(SETBFT_PALLET, NEXT_AUTHORITIES),
+        storage_value_key(SETBFT_PALLET, QUEUED_EMERGENCY_FINALIZER),
+    ]
+}
+
+/// Ways in which a warp sync proof fragment can fail verification.
+#[derive(Debug, PartialEq, Eq)]
+pub enum WarpVerificationError {
+    NotSessionEnd(BlockNumber, SessionId),
+    GenesisJustification,
+    Justification(SessionVerificationError),
+    BadStorageProof,
+    MissingNextAuthorities,
+    AuthoritiesDecode,
+}
+
+impl Display for WarpVerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use WarpVerificationError::*;
+        match self {
+            NotSessionEnd(number, session) => write!(
+                f,
+                "block {number} is not the last block of the current session {}",
+                session.0
+            ),
+            GenesisJustification => write!(f, "unexpected genesis justification"),
+            Justification(e) => write!(f, "{e}"),
+            BadStorageProof => write!(f, "storage proof does not match the state root"),
+            MissingNextAuthorities => write!(f, "storage proof lacks the next authorities"),
+            AuthoritiesDecode => write!(f, "could not decode the next authorities"),
+        }
+    }
+}
+
+impl From<SessionVerificationError> for WarpVerificationError {
+    fn from(e: SessionVerificationError) -> Self {
+        WarpVerificationError::Justification(e)
+    }
+}
+
+/// Verifies warp sync proofs, starting from the authorities of a trusted session, e.g. the first
+/// one with authorities read from the genesis state.
+pub struct WarpVerifier {
+    session_info: SessionBoundaryInfo,
+    session: SessionId,
+    verifier: SessionVerifier,
+}
+
+impl WarpVerifier {
+    pub fn new(
+        session_info: SessionBoundaryInfo,
+        session: SessionId,
+        authority_data: SessionAuthorityData,
+    ) -> Self {
+        WarpVerifier {
+            session_info,
+            session,
+            verifier: authority_data.into(),
+        }
+    }
+
+    fn read_next_authority_data(
+        header: &Header,
+        proof: StorageProof,
+    ) -> Result<SessionAuthorityData, WarpVerificationError> {
+        use WarpVerificationError::*;
+        let [authorities_key, emergency_finalizer_key]: [Vec<u8>; 2] = next_authorities_keys()
+            .try_into()
+            .expect("there are exactly two keys; qed");
+        let mut values = read_proof_check::<BlakeTwo256, _>(
+            *header.state_root(),
+            SubstrateStorageProof::new(proof),
+            [&authorities_key, &emergency_finalizer_key],
+        )
+        .map_err(|_| BadStorageProof)?;
+        let authorities = values
+            .remove(&authorities_key)
+            .flatten()
+            .ok_or(MissingNextAuthorities)?;
+        let authorities =
+            Vec::<AuthorityId>::decode(&mut &authorities[..]).map_err(|_| AuthoritiesDecode)?;
+        if authorities.is_empty() {
+            return Err(MissingNextAuthorities);
+        }
+        let emergency_finalizer = match values.remove(&emergency_finalizer_key).flatten() {
+            Some(encoded) => {
+                Some(AuthorityId::decode(&mut &encoded[..]).map_err(|_| AuthoritiesDecode)?)
+            }
+            None => None,
+        };
+        Ok(SessionAuthorityData::new(authorities, emergency_finalizer))
+    }
+}
+
+impl WarpSyncVerifier<Justification> for WarpVerifier {
+    type Error = WarpVerificationError;
+
+    fn verify_session_end(
+        &mut self,
+        justification: Justification,
+        next_authorities_proof: StorageProof,
+    ) -> Result<Justification, Self::Error> {
+        let header = &justification.header;
+        let number = *header.number();
+        if number != self.session_info.last_block_of_session(self.session) {
+            return Err(WarpVerificationError::NotSessionEnd(number, self.session));
+        }
+        match &justification.inner_justification {
+            InnerJustification::SetBFTJustification(setbft_justification) => self
+                .verifier
+                .verify_bytes(setbft_justification, header.hash().encode())?,
+            InnerJustification::Genesis => return Err(WarpVerificationError::GenesisJustification),
+        }
+        let next_authority_data = Self::read_next_authority_data(header, next_authorities_proof)?;
+        self.verifier = next_authority_data.into();
+        self.session = self.session.next();
+        Ok(justification)
+    }
+}
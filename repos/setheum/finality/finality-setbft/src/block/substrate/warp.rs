@@ -0,0 +1,253 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    fmt::{Display, Error as FmtError, Formatter},
+    sync::Arc,
+    time::Duration,
+};
+
+use array_bytes::bytes2hex;
+use log::{debug, info};
+use prost::Message;
+use sc_client_api::ProofProvider;
+use sc_consensus::import_queue::{ImportQueueService, IncomingBlock};
+use sc_network::{IfDisconnected, NetworkRequest, PeerId, ProtocolName};
+use sc_network_sync::{
+    schema::v1::StateResponse,
+    strategy::state_sync::{ImportResult, StateSync, StateSyncProvider},
+};
+use sp_blockchain::HeaderBackend;
+use sp_consensus::BlockOrigin;
+use sp_runtime::{traits::Header as _, Justification as SubstrateJustification, Justifications};
+use tokio::time::{sleep, Instant};
+
+use crate::{
+    block::{
+        substrate::{InnerJustification, Justification, LOG_TARGET},
+        WarpTargetImport,
+    },
+    setbft_primitives::{Block, BlockHash},
+};
+
+const IMPORT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Importing the state of a mature chain takes a while, but it should not take forever.
+const IMPORT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+// How many requests in a row can fail for every peer before we give up.
+const FAILED_REQUESTS_PER_PEER: usize = 3;
+
+/// What can go wrong when downloading and importing the state of the warp sync target.
+#[derive(Debug)]
+pub enum Error {
+    NoPeers,
+    GenesisTarget,
+    ImportTimeout,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use Error::*;
+        match self {
+            NoPeers => write!(f, "no peer could provide the state"),
+            GenesisTarget => write!(f, "the genesis block cannot be a warp sync target"),
+            ImportTimeout => write!(f, "the target was not finalized after importing its state"),
+        }
+    }
+}
+
+struct StateRequester<C, N> {
+    client: Arc<C>,
+    network: Arc<N>,
+    protocol_name: ProtocolName,
+}
+
+impl<C, N> StateRequester<C, N>
+where
+    C: ProofProvider<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    N: NetworkRequest + Send + Sync + 'static,
+{
+    async fn request_state(
+        &self,
+        state_sync: &StateSync<Block, C>,
+        peer: PeerId,
+    ) -> Option<StateResponse> {
+        let request = state_sync.next_request();
+        match self
+            .network
+            .request(
+                peer,
+                self.protocol_name.clone(),
+                request.encode_to_vec(),
+                None,
+                IfDisconnected::TryConnect,
+            )
+            .await
+        {
+            Ok((response, _)) => match StateResponse::decode(&response[..]) {
+                Ok(response) => Some(response),
+                Err(e) => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Failed to decode state response from {}: {}.", peer, e
+                    );
+                    None
+                }
+            },
+            Err(e) => {
+                debug!(target: LOG_TARGET, "State request to {} failed: {}.", peer, e);
+                None
+            }
+        }
+    }
+
+    async fn download(
+        &self,
+        justification: Justification,
+        peers: Vec<PeerId>,
+    ) -> Result<IncomingBlock<Block>, Error> {
+        let Justification {
+            header,
+            inner_justification,
+        } = justification;
+        let setbft_justification = match inner_justification {
+            InnerJustification::SetBFTJustification(setbft_justification) => setbft_justification,
+            InnerJustification::Genesis => return Err(Error::GenesisTarget),
+        };
+        let justification: SubstrateJustification = setbft_justification.into();
+        let mut state_sync = StateSync::new(
+            self.client.clone(),
+            header,
+            None,
+            Some(Justifications::from(justification)),
+            false,
+        );
+        let max_failures = peers.len() * FAILED_REQUESTS_PER_PEER;
+        let mut failures = 0;
+        for peer in peers.into_iter().cycle() {
+            if failures >= max_failures {
+                break;
+            }
+            let response = match self.request_state(&state_sync, peer).await {
+                Some(response) => response,
+                None => {
+                    failures += 1;
+                    continue;
+                }
+            };
+            match state_sync.import(response) {
+                ImportResult::Import(hash, header, state, body, justifications) => {
+                    info!(
+                        target: LOG_TARGET,
+                        "Downloaded the state of warp sync target {:?}.", hash
+                    );
+                    return Ok(IncomingBlock {
+                        hash,
+                        header: Some(header),
+                        body,
+                        indexed_body: None,
+                        justifications,
+                        origin: None,
+                        allow_missing_state: true,
+                        import_existing: true,
+                        skip_execution: true,
+                        state: Some(state),
+                    });
+                }
+                ImportResult::Continue => {
+                    failures = 0;
+                    debug!(
+                        target: LOG_TARGET,
+                        "Downloading warp sync target state, {}% done.",
+                        state_sync.progress().percentage
+                    )
+                }
+                ImportResult::BadResponse => {
+                    failures += 1;
+                    debug!(target: LOG_TARGET, "Bad state response from {}.", peer)
+                }
+            }
+        }
+        Err(Error::NoPeers)
+    }
+}
+
+/// Downloads the state of the warp sync target using the Substrate state request protocol
+/// and imports it through the import queue.
+pub struct StateDownloader<C, N> {
+    requester: StateRequester<C, N>,
+    import_queue: Box<dyn ImportQueueService<Block>>,
+}
+
+impl<C, N> StateDownloader<C, N>
+where
+    C: ProofProvider<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    N: NetworkRequest + Send + Sync + 'static,
+{
+    pub fn new(
+        client: Arc<C>,
+        network: Arc<N>,
+        genesis_hash: BlockHash,
+        import_queue: Box<dyn ImportQueueService<Block>>,
+    ) -> Self {
+        // Has to match the name used by the `StateRequestHandler` without a fork id.
+        let protocol_name = format!("/{}/state/2", bytes2hex("", genesis_hash)).into();
+        StateDownloader {
+            requester: StateRequester {
+                client,
+                network,
+                protocol_name,
+            },
+            import_queue,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<C, N> WarpTargetImport<Justification, PeerId> for StateDownloader<C, N>
+where
+    C: ProofProvider<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    N: NetworkRequest + Send + Sync + 'static,
+{
+    type Error = Error;
+
+    async fn import_target(
+        &mut self,
+        justification: Justification,
+        peers: Vec<PeerId>,
+    ) -> Result<(), Self::Error> {
+        if peers.is_empty() {
+            return Err(Error::NoPeers);
+        }
+        let target = justification.header.hash();
+        let block = self.requester.download(justification, peers).await?;
+        self.import_queue
+            .import_blocks(BlockOrigin::NetworkInitialSync, vec![block]);
+        // The import queue does not report back to us, so we wait for the block import to
+        // finalize the target.
+        let start = Instant::now();
+        while self.requester.client.info().finalized_hash != target {
+            if start.elapsed() > IMPORT_TIMEOUT {
+                return Err(Error::ImportTimeout);
+            }
+            sleep(IMPORT_POLL_INTERVAL).await;
+        }
+        Ok(())
+    }
+}
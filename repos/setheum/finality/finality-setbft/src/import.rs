@@ -196,7 +196,15 @@ where
         let number = *block.header.number();
         let post_hash = block.post_hash();
 
-        let justifications = block.justifications.take();
+        let justifications = match block.with_state() {
+            // Only the target of warp sync comes with state, its justification has already been
+            // verified, so we finalize it right away and keep the justification in the database.
+            true => {
+                block.finalized = true;
+                None
+            }
+            false => block.justifications.take(),
+        };
 
         debug!(target: "setbft-justification", "Importing block {:?} {:?} {:?}", number, block.header.hash(), block.post_hash());
         let result = self.inner.import_block(block).await;
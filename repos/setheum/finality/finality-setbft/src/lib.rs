@@ -38,8 +38,9 @@ use sc_client_api::{
     Backend, BlockBackend, BlockchainEvents, Finalizer, LockImportRun, ProofProvider,
     StorageProvider,
 };
-use sc_consensus::BlockImport;
+use sc_consensus::{import_queue::ImportQueueService, BlockImport};
 use sc_keystore::LocalKeystore;
+use sc_network::NetworkService;
 use sp_api::ProvideRuntimeApi;
 use sp_blockchain::{HeaderBackend, HeaderMetadata};
 use sp_runtime::traits::{BlakeTwo256, Block};
@@ -48,12 +49,13 @@ use tokio::time::Duration;
 
 use crate::{
     sbft::{
-        CurrentNetworkData, Keychain, LegacyNetworkData, NodeCount, NodeIndex, Recipient,
-        SignatureSet, SpawnHandle, CURRENT_VERSION, LEGACY_VERSION,
+        BatchedNetworkData, CurrentNetworkData, Keychain, LegacyNetworkData, NetworkData,
+        NodeCount, NodeIndex, Recipient, SignatureSet, SpawnHandle, CURRENT_VERSION,
+        LEGACY_VERSION,
     },
     aggregation::RmcNetworkData,
     block::UnverifiedHeader,
-    network::data::split::Split,
+    network::data::{compression::Compressible, split::Split},
     session::{SessionBoundaries, SessionBoundaryInfo, SessionId},
 };
 
@@ -76,6 +78,7 @@ mod session;
 mod session_map;
 mod sync;
 mod sync_oracle;
+mod validator_health;
 #[cfg(test)]
 pub mod testing;
 
@@ -95,6 +98,9 @@ pub use crate::{
     session::SessionPeriod,
     sync::FavouriteSelectChainProvider,
     sync_oracle::SyncOracle,
+    validator_health::{
+        ConnectionDirection, ConnectionStatus, MemberHealth, SessionHealth, ValidatorHealthCache,
+    },
 };
 
 /// Constant defining how often components of finality-setbft should report their state
@@ -108,6 +114,8 @@ pub struct UnitCreationDelay(pub u64);
 
 pub type SplitData<UH> = Split<NetworkData<UH>, RmcNetworkData>;
 
+pub type BatchedSplitData<UH> = Split<Compressible<BatchedNetworkData<UH>>, RmcNetworkData>;
+
 pub trait ClientForSetBFT<B, BE>:
     LockImportRun<B, BE>
     + Finalizer<B, BE>
@@ -180,6 +188,14 @@ pub struct RateLimiterConfig {
     pub substrate_network_bit_rate: u64,
 }
 
+/// What is needed to warp sync a node that has only the genesis block finalized.
+pub struct WarpSyncConfig {
+    /// The network used for downloading the state of the warp sync target.
+    pub network: Arc<NetworkService<SetBFTBlock, BlockHash>>,
+    /// The import queue through which the downloaded state gets imported.
+    pub import_queue: Box<dyn ImportQueueService<SetBFTBlock>>,
+}
+
 pub struct SetBFTConfig<C, T> {
     pub authentication_network: ProtocolNetwork,
     pub block_sync_network: ProtocolNetwork,
@@ -202,5 +218,7 @@ pub struct SetBFTConfig<C, T> {
     pub rate_limiter_config: RateLimiterConfig,
     pub sync_oracle: SyncOracle,
     pub validator_address_cache: Option<ValidatorAddressCache>,
+    pub validator_health_cache: Option<ValidatorHealthCache>,
     pub transaction_pool: Arc<T>,
+    pub warp_sync: Option<WarpSyncConfig>,
 }
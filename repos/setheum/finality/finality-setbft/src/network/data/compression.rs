@@ -0,0 +1,284 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Optional zstd compression of data sent through a network.
+//!
+//! Every message starts with a tag byte saying whether the rest is the plain encoding of the data
+//! or its compressed encoding. This changes the encoding of the data, so compressible data is part
+//! of a finality version: all nodes of a session run the same one, and nodes in sessions of older
+//! versions never see tagged messages.
+use std::{fmt::Display, marker::PhantomData};
+
+use log::warn;
+use parity_scale_codec::{Decode, DecodeAll, Encode, Error as CodecError, Input, Output};
+
+use crate::{
+    network::{
+        data::{
+            component::{Network, NetworkExt, Receiver, Sender, SimpleNetwork},
+            SendError,
+        },
+        Data,
+    },
+    Recipient, Version, Versioned,
+};
+
+const LOG_TARGET: &str = "setbft-network";
+
+/// Encoded data smaller than this is never worth compressing.
+pub const DEFAULT_COMPRESSION_THRESHOLD: usize = 16 * 1024;
+
+/// The largest message we are willing to decompress, protecting against decompression bombs.
+pub const MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+const COMPRESSION_LEVEL: i32 = 3;
+const PLAIN_TAG: u8 = 0;
+const COMPRESSED_TAG: u8 = 1;
+
+/// Whether outgoing data should be compressed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Compression {
+    Disabled,
+    /// Compress data whose encoding is at least `threshold` bytes long.
+    Enabled {
+        threshold: usize,
+    },
+}
+
+impl Compression {
+    pub fn enabled() -> Self {
+        Compression::Enabled {
+            threshold: DEFAULT_COMPRESSION_THRESHOLD,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum CompressionError {
+    Decompress(std::io::Error),
+    Decode(CodecError),
+}
+
+impl Display for CompressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use CompressionError::*;
+        match self {
+            Decompress(e) => write!(f, "failed to decompress data: {e}"),
+            Decode(e) => write!(f, "failed to decode decompressed data: {e}"),
+        }
+    }
+}
+
+/// Data that might have been compressed before sending.
+///
+/// Decoding compressed data consumes the whole remaining input, so this has to be the last part
+/// of a message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Compressible<D: Data> {
+    Plain(D),
+    Compressed(Vec<u8>),
+}
+
+impl<D: Data> Compressible<D> {
+    /// Wraps the data, compressing it if that is enabled and actually makes it smaller.
+    pub fn new(data: D, compression: Compression) -> Self {
+        let threshold = match compression {
+            Compression::Disabled => return Compressible::Plain(data),
+            Compression::Enabled { threshold } => threshold,
+        };
+        let encoded = data.encode();
+        if encoded.len() < threshold {
+            return Compressible::Plain(data);
+        }
+        match zstd::bulk::compress(&encoded, COMPRESSION_LEVEL) {
+            Ok(compressed) if compressed.len() < encoded.len() => {
+                Compressible::Compressed(compressed)
+            }
+            Ok(_) => Compressible::Plain(data),
+            Err(e) => {
+                warn!(target: LOG_TARGET, "Failed to compress data, sending it plain: {}", e);
+                Compressible::Plain(data)
+            }
+        }
+    }
+
+    pub fn into_data(self) -> Result<D, CompressionError> {
+        match self {
+            Compressible::Plain(data) => Ok(data),
+            Compressible::Compressed(bytes) => {
+                let encoded = zstd::bulk::decompress(&bytes, MAX_DECOMPRESSED_SIZE)
+                    .map_err(CompressionError::Decompress)?;
+                D::decode_all(&mut encoded.as_slice()).map_err(CompressionError::Decode)
+            }
+        }
+    }
+}
+
+impl<D: Data> Encode for Compressible<D> {
+    fn size_hint(&self) -> usize {
+        1 + match self {
+            Compressible::Plain(data) => data.size_hint(),
+            Compressible::Compressed(bytes) => bytes.len(),
+        }
+    }
+
+    fn encode_to<T: Output + ?Sized>(&self, dest: &mut T) {
+        match self {
+            Compressible::Plain(data) => {
+                dest.push_byte(PLAIN_TAG);
+                data.encode_to(dest);
+            }
+            Compressible::Compressed(bytes) => {
+                dest.push_byte(COMPRESSED_TAG);
+                dest.write(bytes);
+            }
+        }
+    }
+}
+
+impl<D: Data> Decode for Compressible<D> {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, CodecError> {
+        match input.read_byte()? {
+            PLAIN_TAG => Ok(Compressible::Plain(D::decode(input)?)),
+            COMPRESSED_TAG => {
+                let len = input
+                    .remaining_len()?
+                    .ok_or("Compressed data requires an input of known length")?;
+                let mut bytes = vec![0; len];
+                input.read(&mut bytes)?;
+                Ok(Compressible::Compressed(bytes))
+            }
+            _ => Err("Unknown compression tag".into()),
+        }
+    }
+}
+
+impl<D: Data + Versioned> Versioned for Compressible<D> {
+    const VERSION: Version = D::VERSION;
+}
+
+#[derive(Clone)]
+pub struct CompressingSender<S> {
+    sender: S,
+    compression: Compression,
+}
+
+impl<D: Data, S: Sender<Compressible<D>>> Sender<D> for CompressingSender<S> {
+    fn send(&self, data: D, recipient: Recipient) -> Result<(), SendError> {
+        self.sender
+            .send(Compressible::new(data, self.compression), recipient)
+    }
+}
+
+pub struct DecompressingReceiver<D, R> {
+    receiver: R,
+    _phantom: PhantomData<D>,
+}
+
+#[async_trait::async_trait]
+impl<D: Data, R: Receiver<Compressible<D>>> Receiver<D> for DecompressingReceiver<D, R> {
+    async fn next(&mut self) -> Option<D> {
+        loop {
+            match self.receiver.next().await?.into_data() {
+                Ok(data) => return Some(data),
+                Err(e) => warn!(target: LOG_TARGET, "Dropping undecodable message: {}", e),
+            }
+        }
+    }
+}
+
+/// Wraps a network of possibly compressed data into one of plain data, compressing outgoing
+/// messages according to `compression`. Incoming compressed messages are always accepted.
+pub fn compressing<D: Data, N: Network<Compressible<D>>>(
+    network: N,
+    compression: Compression,
+) -> impl NetworkExt<D> {
+    let (sender, receiver) = network.into();
+    SimpleNetwork::new(
+        DecompressingReceiver {
+            receiver,
+            _phantom: PhantomData,
+        },
+        CompressingSender {
+            sender,
+            compression,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use parity_scale_codec::{Decode, Encode};
+
+    use super::{Compressible, Compression, MAX_DECOMPRESSED_SIZE};
+
+    type TestData = (u8, Vec<u8>);
+
+    fn large_data() -> TestData {
+        (0, vec![7; 64 * 1024])
+    }
+
+    #[test]
+    fn plain_data_roundtrips_tagged() {
+        let data = large_data();
+        let wrapped = Compressible::new(data.clone(), Compression::Disabled);
+        let encoded = wrapped.encode();
+        assert_eq!(encoded[0], 0);
+        assert_eq!(encoded[1..], data.encode());
+        let decoded = Compressible::<TestData>::decode(&mut encoded.as_slice())
+            .expect("plain data should decode");
+        assert_eq!(decoded.into_data().expect("is plain"), data);
+    }
+
+    #[test]
+    fn refuses_unknown_tags() {
+        let mut encoded = Compressible::new(large_data(), Compression::Disabled).encode();
+        encoded[0] = 2;
+        assert!(Compressible::<TestData>::decode(&mut encoded.as_slice()).is_err());
+    }
+
+    #[test]
+    fn small_data_is_not_compressed() {
+        let data = (0, vec![7; 16]);
+        let wrapped = Compressible::new(data.clone(), Compression::enabled());
+        assert_eq!(wrapped, Compressible::Plain(data));
+    }
+
+    #[test]
+    fn large_data_roundtrips_compressed() {
+        let data = large_data();
+        let wrapped = Compressible::new(data.clone(), Compression::enabled());
+        let encoded = wrapped.encode();
+        assert!(encoded.len() < data.encode().len());
+        let decoded = Compressible::<TestData>::decode(&mut encoded.as_slice())
+            .expect("compressed data should decode");
+        assert!(matches!(decoded, Compressible::Compressed(_)));
+        assert_eq!(decoded.into_data().expect("should decompress"), data);
+    }
+
+    #[test]
+    fn refuses_to_decompress_bombs() {
+        let data = (0, vec![0; 2 * MAX_DECOMPRESSED_SIZE]);
+        let wrapped = Compressible::new(data, Compression::enabled());
+        let decoded = Compressible::<TestData>::decode(&mut wrapped.encode().as_slice())
+            .expect("compressed data should decode");
+        assert!(decoded.into_data().is_err());
+    }
+}
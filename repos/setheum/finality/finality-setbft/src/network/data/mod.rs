@@ -22,6 +22,7 @@
 use crate::{sbft::Recipient, network::Data};
 
 pub mod component;
+pub mod compression;
 pub mod split;
 
 /// Returned when something went wrong when sending data using a Network.
@@ -38,7 +38,7 @@ use crate::{
         },
         AddressingInformation, Data, NetworkIdentity, PeerId,
     },
-    NodeIndex, SessionId,
+    NodeCount, NodeIndex, SessionId,
 };
 
 /// Commands for manipulating the reserved peers set.
@@ -393,6 +393,23 @@ impl<NI: NetworkIdentity, D: Data, VCU: ValidatorAddressCacheUpdater> Manager<NI
         }
     }
 
+    /// The known peers of all the sessions in which we are a validator.
+    pub fn validator_sessions(
+        &self,
+    ) -> Vec<(SessionId, NodeCount, HashMap<NodeIndex, NI::PeerId>)> {
+        self.sessions
+            .iter()
+            .filter(|(_, session)| session.handler.is_validator())
+            .map(|(session_id, session)| {
+                (
+                    *session_id,
+                    session.handler.node_count(),
+                    session.handler.peers(),
+                )
+            })
+            .collect()
+    }
+
     pub fn status_report(&self) {
         let mut status = String::from("Connection Manager status report: ");
 
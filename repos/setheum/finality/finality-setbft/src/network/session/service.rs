@@ -47,6 +47,7 @@ use crate::{
         },
         AddressingInformation, Data, GossipNetwork, NetworkIdentity,
     },
+    validator_health::ConnectionHealthMonitor,
     MillisecsPerBlock, NodeIndex, SessionId, SessionPeriod, STATUS_REPORT_INTERVAL,
 };
 
@@ -206,6 +207,7 @@ pub struct Service<
     messages_from_user: mpsc::UnboundedReceiver<(D, SessionId, Recipient)>,
     validator_network: CN,
     gossip_network: GN,
+    health_monitor: ConnectionHealthMonitor<NI::PeerId>,
     maintenance_period: Duration,
     initial_delay: Duration,
 }
@@ -246,6 +248,7 @@ where
         validator_network: CN,
         gossip_network: GN,
         validator_address_cache_updater: VCU,
+        health_monitor: ConnectionHealthMonitor<NI::PeerId>,
         config: Config,
     ) -> (
         Service<D, NI, CN, GN, VCU>,
@@ -270,6 +273,7 @@ where
                 messages_from_user,
                 validator_network,
                 gossip_network,
+                health_monitor,
                 maintenance_period,
                 initial_delay,
             },
@@ -280,7 +284,8 @@ where
         )
     }
 
-    fn send_data(&self, to_send: AddressedData<DataInSession<D>, NI::PeerId>) {
+    fn send_data(&mut self, to_send: AddressedData<DataInSession<D>, NI::PeerId>) {
+        self.health_monitor.on_message_sent(&to_send.1);
         self.validator_network.send(to_send.0, to_send.1)
     }
 
@@ -421,6 +426,7 @@ where
                 },
                 _ = status_ticker.tick() => {
                     self.manager.status_report();
+                    self.health_monitor.report(self.manager.validator_sessions());
                 }
             }
         }
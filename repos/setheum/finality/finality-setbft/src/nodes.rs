@@ -30,13 +30,17 @@ use rate_limiter::SharedRateLimiter;
 use sc_client_api::Backend;
 use sc_keystore::{Keystore, LocalKeystore};
 use sc_transaction_pool_api::{LocalTransactionPool, TransactionPool};
+use sp_blockchain::HeaderBackend;
 use sp_consensus_aura::AuraApi;
 
 use crate::{
     setbft_primitives::{AuraId, Block},
     block::{
-        substrate::{JustificationTranslator, SubstrateFinalizationInfo, VerifierCache},
-        BlockchainEvents, ChainStatus, FinalizationStatus, Justification,
+        substrate::{
+            JustificationTranslator, StateDownloader, SubstrateFinalizationInfo, VerifierCache,
+            WarpVerifier,
+        },
+        BlockchainEvents, ChainStatus, FinalizationStatus, Header, Justification,
     },
     crypto::AuthorityPen,
     finalization::SetBFTFinalizer,
@@ -52,12 +56,14 @@ use crate::{
         ConsensusPartyParams,
     },
     runtime_api::RuntimeApiImpl,
-    session::SessionBoundaryInfo,
+    session::{SessionBoundaryInfo, SessionId},
     session_map::{
-        AuthorityProviderImpl, FinalityNotifierImpl, FinalizedBlockProviderImpl, SessionMapUpdater,
+        AuthorityProvider, AuthorityProviderImpl, FinalityNotifierImpl, FinalizedBlockProviderImpl,
+        SessionMapUpdater,
     },
-    sync::{DatabaseIO as SyncDatabaseIO, Service as SyncService, IO as SyncIO},
-    SetBFTConfig,
+    sync::{DatabaseIO as SyncDatabaseIO, Service as SyncService, WarpSync, IO as SyncIO},
+    validator_health::{validator_health_reporter, ConnectionHealthMonitor},
+    SetBFTConfig, WarpSyncConfig,
 };
 
 // How many sessions we remember.
@@ -106,7 +112,9 @@ where
         rate_limiter_config,
         sync_oracle,
         validator_address_cache,
+        validator_health_cache,
         transaction_pool,
+        warp_sync,
     } = setbft_config;
 
     // We generate the phrase manually to only save the key in RAM, we don't want to have these
@@ -133,6 +141,7 @@ where
 
     let setbft_rate_limiter =
         SharedRateLimiter::new(rate_limiter_config.setbft_network_bit_rate.into());
+    let rate_limiter_for_health = setbft_rate_limiter.share();
     let dialer = RateLimitingDialer::new(dialer, setbft_rate_limiter.share());
     let listener = RateLimitingListener::new(listener, setbft_rate_limiter);
 
@@ -143,6 +152,7 @@ where
         spawn_handle.clone(),
         registry.clone(),
     );
+    let validator_peer_statuses = validator_network_service.peer_statuses();
     let (_validator_network_exit, exit) = oneshot::channel();
     spawn_handle.spawn("setbft/validator_network", async move {
         debug!(target: LOG_TARGET, "Validator network has started.");
@@ -204,6 +214,32 @@ where
 
     let session_authority_provider =
         AuthorityProviderImpl::new(client.clone(), runtime_api.clone());
+    // Warp sync only makes sense for a node that has not finalized anything yet.
+    let (block_sync_network, backfill) = match warp_sync {
+        Some(WarpSyncConfig {
+            network,
+            import_queue,
+        }) if client.info().finalized_number == 0 => {
+            let genesis_authority_data = session_authority_provider
+                .authority_data(0)
+                .expect("the genesis block should have authority data");
+            let warp_sync = WarpSync::new(
+                block_sync_network,
+                session_info.clone(),
+                WarpVerifier::new(session_info.clone(), SessionId(0), genesis_authority_data),
+                genesis_header.id(),
+                StateDownloader::new(
+                    client.clone(),
+                    network,
+                    client.info().genesis_hash,
+                    import_queue,
+                ),
+                sync_oracle.clone(),
+            );
+            warp_sync.run().await
+        }
+        _ => (block_sync_network, None),
+    };
     let verifier = VerifierCache::new(
         session_info.clone(),
         SubstrateFinalizationInfo::new(client.clone()),
@@ -232,6 +268,7 @@ where
         registry,
         slo_metrics,
         favourite_block_user_requests,
+        backfill,
     ) {
         Ok(x) => x,
         Err(e) => panic!("Failed to initialize Sync service: {e}"),
@@ -254,11 +291,25 @@ where
         ),
     );
 
+    let validator_health = validator_health_reporter(
+        validator_health_cache,
+        ValidatorIndexToAccountIdConverterImpl::new(
+            client.clone(),
+            session_info.clone(),
+            runtime_api.clone(),
+        ),
+    );
+
     let (connection_manager_service, connection_manager) = ConnectionManager::new(
         network_identity,
         validator_network,
         authentication_network,
         validator_address_cache_updater,
+        ConnectionHealthMonitor::new(
+            validator_health.clone(),
+            validator_peer_statuses,
+            rate_limiter_for_health,
+        ),
         ConnectionManagerConfig::with_session_period(&session_period, &millisecs_per_block),
     );
 
@@ -298,6 +349,7 @@ where
             keystore,
             runtime_api,
             score_metrics,
+            validator_health,
         ),
         session_info,
         score_submission_period,
@@ -26,16 +26,19 @@ use log::{debug, info, trace, warn};
 use network_clique::SpawnHandleExt;
 use module_setbft_runtime_api::SetBFTSessionApi;
 use sc_keystore::{Keystore, LocalKeystore};
+use set_bft::{BatchingDataProvider, MAX_DATA_BATCH_SIZE};
 use sp_application_crypto::RuntimeAppPublic;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 
 use crate::{
     sbft::{
         current_create_setbft_config, legacy_create_setbft_config, run_current_member,
-        run_legacy_member, CurrentPerformanceService, CurrentPerformanceServiceIO, SpawnHandle,
+        run_legacy_member, CurrentPerformanceService, CurrentPerformanceServiceIO, LastOfBatch,
+        SpawnHandle,
     },
     setbft_primitives::{
         crypto::SignatureSet, AuthoritySignature, BlockHash, BlockNumber, Hash, KEY_TYPE,
+        UNBATCHED_FINALITY_VERSION,
     },
     block::{
         substrate::{Justification, JustificationTranslator},
@@ -48,6 +51,7 @@ use crate::{
     network::{
         data::{
             component::{Network, NetworkMap, SimpleNetwork},
+            compression::{compressing, Compression},
             split::split,
         },
         session::{SessionManager, SessionSender},
@@ -61,6 +65,7 @@ use crate::{
     AuthorityId, BlockId, CurrentRmcNetworkData, Keychain, LegacyRmcNetworkData, NodeIndex,
     ProvideRuntimeApi, SessionBoundaries, SessionBoundaryInfo, SessionId, SessionPeriod,
     UnitCreationDelay, VersionedNetworkData,
+    validator_health::ValidatorHealthReporter,
 };
 
 mod aggregator;
@@ -90,6 +95,12 @@ type CurrentNetworkType = SimpleNetwork<
     SessionSender<CurrentRmcNetworkData>,
 >;
 
+/// Whether units of a current session carry batches of data, compressed when large.
+enum DataBatching {
+    Disabled,
+    Enabled,
+}
+
 struct SubtasksParams<H, HB, N, JS>
 where
     H: Header,
@@ -145,6 +156,7 @@ where
     keystore: Arc<LocalKeystore>,
     runtime_api: RA,
     score_metrics: ScoreMetrics,
+    validator_health: ValidatorHealthReporter,
     _phantom: PhantomData<(B, H)>,
 }
 
@@ -181,6 +193,7 @@ where
         keystore: Arc<LocalKeystore>,
         runtime_api: RA,
         score_metrics: ScoreMetrics,
+        validator_health: ValidatorHealthReporter,
     ) -> Self {
         Self {
             client,
@@ -198,6 +211,7 @@ where
             keystore,
             runtime_api,
             score_metrics,
+            validator_health,
             _phantom: PhantomData,
         }
     }
@@ -282,6 +296,7 @@ where
     fn current_subtasks<N: Network<VersionedNetworkData<B::UnverifiedHeader>> + 'static>(
         &self,
         params: SubtasksParams<H, HB, N, JS>,
+        batching: DataBatching,
     ) -> Subtasks {
         let SubtasksParams {
             n_members,
@@ -326,45 +341,91 @@ where
             },
             self.runtime_api.clone(),
             self.score_metrics.clone(),
+            self.validator_health.clone(),
         );
         let consensus_config =
             current_create_setbft_config(n_members, node_id, session_id, self.unit_creation_delay);
         let data_network = data_network.map();
 
-        let (unfiltered_setbft_network, rmc_network) =
-            split(data_network, "setbft_network", "rmc_network");
-        let (data_store, setbft_network) = DataStore::new(
-            session_boundaries.clone(),
-            self.header_backend.clone(),
-            self.client.clone(),
-            self.verifier.clone(),
-            self.block_requester.clone(),
-            Default::default(),
-            unfiltered_setbft_network,
-        );
+        let (member, aggregator, data_store) = match batching {
+            DataBatching::Disabled => {
+                let (unfiltered_setbft_network, rmc_network) =
+                    split(data_network, "setbft_network", "rmc_network");
+                let (data_store, setbft_network) = DataStore::new(
+                    session_boundaries.clone(),
+                    self.header_backend.clone(),
+                    self.client.clone(),
+                    self.verifier.clone(),
+                    self.block_requester.clone(),
+                    Default::default(),
+                    unfiltered_setbft_network,
+                );
+                (
+                    run_current_member(
+                        subtask_common.clone(),
+                        multikeychain.clone(),
+                        consensus_config,
+                        setbft_network.into(),
+                        data_provider,
+                        sbft_batch_handler,
+                        backup,
+                    ),
+                    aggregator::task(
+                        subtask_common.clone(),
+                        self.header_backend.clone(),
+                        aggregator_io,
+                        session_boundaries,
+                        self.metrics.clone(),
+                        multikeychain,
+                        AggregatorVersion::<_, LegacyNetworkType>::Current(rmc_network),
+                    ),
+                    task::task(subtask_common.clone(), data_store, "data store"),
+                )
+            }
+            DataBatching::Enabled => {
+                let (unfiltered_setbft_network, rmc_network) =
+                    split(data_network, "setbft_network", "rmc_network");
+                let unfiltered_setbft_network =
+                    compressing(unfiltered_setbft_network, Compression::enabled());
+                let (data_store, setbft_network) = DataStore::new(
+                    session_boundaries.clone(),
+                    self.header_backend.clone(),
+                    self.client.clone(),
+                    self.verifier.clone(),
+                    self.block_requester.clone(),
+                    Default::default(),
+                    unfiltered_setbft_network,
+                );
+                (
+                    run_current_member(
+                        subtask_common.clone(),
+                        multikeychain.clone(),
+                        consensus_config,
+                        setbft_network.into(),
+                        BatchingDataProvider::new(data_provider, MAX_DATA_BATCH_SIZE),
+                        LastOfBatch::new(sbft_batch_handler),
+                        backup,
+                    ),
+                    aggregator::task(
+                        subtask_common.clone(),
+                        self.header_backend.clone(),
+                        aggregator_io,
+                        session_boundaries,
+                        self.metrics.clone(),
+                        multikeychain,
+                        AggregatorVersion::<_, LegacyNetworkType>::Current(rmc_network),
+                    ),
+                    task::task(subtask_common.clone(), data_store, "data store"),
+                )
+            }
+        };
         Subtasks::new(
             exit_rx,
-            run_current_member(
-                subtask_common.clone(),
-                multikeychain.clone(),
-                consensus_config,
-                setbft_network.into(),
-                data_provider,
-                sbft_batch_handler,
-                backup,
-            ),
+            member,
             task::task(subtask_common.clone(), sbft_performance, "sbft performance"),
-            aggregator::task(
-                subtask_common.clone(),
-                self.header_backend.clone(),
-                aggregator_io,
-                session_boundaries,
-                self.metrics.clone(),
-                multikeychain,
-                AggregatorVersion::<_, LegacyNetworkType>::Current(rmc_network),
-            ),
-            task::task(subtask_common.clone(), chain_tracker, "chain tracker"),
-            task::task(subtask_common, data_store, "data store"),
+            aggregator,
+            task::task(subtask_common, chain_tracker, "chain tracker"),
+            data_store,
         )
     }
 
@@ -455,7 +516,11 @@ where
             // am lazy.
             Ok(version) if version == CURRENT_VERSION as u32 => {
                 info!(target: LOG_TARGET, "Running session with SetBFT version {}, which is current.", version);
-                self.current_subtasks(params)
+                self.current_subtasks(params, DataBatching::Enabled)
+            }
+            Ok(version) if version == UNBATCHED_FINALITY_VERSION as u32 => {
+                info!(target: LOG_TARGET, "Running session with SetBFT version {}, which is current without data batching.", version);
+                self.current_subtasks(params, DataBatching::Disabled)
             }
             Ok(version) if version == LEGACY_VERSION as u32 => {
                 info!(target: LOG_TARGET, "Running session with SetBFT version {}, which is legacy.", version);
@@ -463,7 +528,7 @@ where
             }
             Ok(version) if version > CURRENT_VERSION as u32 => {
                 panic!(
-                    "Too new version {version}. Supported versions: {LEGACY_VERSION} or {CURRENT_VERSION}. Probably outdated node."
+                    "Too new version {version}. Supported versions: {LEGACY_VERSION}, {UNBATCHED_FINALITY_VERSION} or {CURRENT_VERSION}. Probably outdated node."
                 )
             }
             Ok(version) => {
@@ -28,8 +28,8 @@ use crate::{
     setbft_primitives::MAX_BLOCK_SIZE,
     block::{Block, Header, Justification, UnverifiedHeader, UnverifiedHeaderFor},
     network::GossipNetwork,
-    sync::{PeerId, LOG_TARGET},
-    BlockId, Version,
+    sync::{warp::WarpProof, PeerId, LOG_TARGET},
+    BlockId, BlockNumber, Version,
 };
 
 /// The representation of the database state to be sent to other nodes.
@@ -150,6 +150,28 @@ impl<UH: UnverifiedHeader, I: PeerId> PreRequest<UH, I> {
     }
 }
 
+/// Data to be sent over the network, version 4, from before warp sync.
+#[derive(Clone, Debug, Encode, Decode)]
+pub enum NetworkDataV4<B: Block, J: Justification>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+{
+    /// A periodic state broadcast, so that neighbouring nodes can request what they are missing,
+    /// send what we are missing, and sometimes just use the justifications to update their own
+    /// state.
+    StateBroadcast(State<J>),
+    /// Response to a state broadcast. Contains at most two justifications that the peer will
+    /// understand.
+    StateBroadcastResponse(J::Unverified, Option<J::Unverified>),
+    /// An explicit request for data, potentially a lot of it.
+    Request(Request<J>),
+    /// Response to the request for data.
+    RequestResponse(ResponseItems<B, J>),
+    /// A request for a chain extension.
+    ChainExtensionRequest(State<J>),
+}
+
 /// Data to be sent over the network, current version.
 #[derive(Clone, Debug, Encode, Decode)]
 pub enum NetworkData<B: Block, J: Justification>
@@ -170,6 +192,57 @@ where
     RequestResponse(ResponseItems<B, J>),
     /// A request for a chain extension.
     ChainExtensionRequest(State<J>),
+    /// A request for a warp sync proof, starting after the given finalized block, either the
+    /// genesis or the last block of a session.
+    WarpProofRequest(BlockId),
+    /// Response to the request for a warp sync proof.
+    WarpProofResponse(WarpProof<J>),
+    /// A request for finalized blocks skipped by warp sync, going down from the given block
+    /// to, but excluding, the block with the given number.
+    HistoryRequest(BlockId, BlockNumber),
+    /// Response to the request for history, the blocks are ordered from the newest.
+    HistoryResponse(Vec<B>),
+}
+
+impl<B, J> From<NetworkDataV4<B, J>> for NetworkData<B, J>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+{
+    fn from(data: NetworkDataV4<B, J>) -> Self {
+        use NetworkDataV4::*;
+        match data {
+            StateBroadcast(state) => NetworkData::StateBroadcast(state),
+            StateBroadcastResponse(justification, maybe_justification) => {
+                NetworkData::StateBroadcastResponse(justification, maybe_justification)
+            }
+            Request(request) => NetworkData::Request(request),
+            RequestResponse(response_items) => NetworkData::RequestResponse(response_items),
+            ChainExtensionRequest(state) => NetworkData::ChainExtensionRequest(state),
+        }
+    }
+}
+
+impl<B, J> TryFrom<NetworkData<B, J>> for NetworkDataV4<B, J>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+{
+    type Error = NetworkData<B, J>;
+
+    fn try_from(data: NetworkData<B, J>) -> Result<Self, Self::Error> {
+        use NetworkData::*;
+        match data {
+            StateBroadcast(state) => Ok(NetworkDataV4::StateBroadcast(state)),
+            StateBroadcastResponse(justification, maybe_justification) => Ok(
+                NetworkDataV4::StateBroadcastResponse(justification, maybe_justification),
+            ),
+            Request(request) => Ok(NetworkDataV4::Request(request)),
+            RequestResponse(response_items) => Ok(NetworkDataV4::RequestResponse(response_items)),
+            ChainExtensionRequest(state) => Ok(NetworkDataV4::ChainExtensionRequest(state)),
+            data => Err(data),
+        }
+    }
 }
 
 /// Version wrapper around the network data.
@@ -181,7 +254,23 @@ where
 {
     // Most likely from the future.
     Other(Version, Vec<u8>),
-    V4(NetworkData<B, J>),
+    V4(NetworkDataV4<B, J>),
+    V5(NetworkData<B, J>),
+}
+
+impl<B, J> From<NetworkData<B, J>> for VersionedNetworkData<B, J>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+{
+    /// Data understood by nodes without warp sync is still sent as V4, only the warp sync
+    /// messages need V5. Nodes without warp sync ignore them as data of an unknown version.
+    fn from(data: NetworkData<B, J>) -> Self {
+        match NetworkDataV4::try_from(data) {
+            Ok(data) => VersionedNetworkData::V4(data),
+            Err(data) => VersionedNetworkData::V5(data),
+        }
+    }
 }
 
 // We need 32 bits, since blocks can be quite sizeable.
@@ -232,6 +321,7 @@ where
             + match self {
                 Other(_, payload) => payload.len(),
                 V4(data) => data.size_hint(),
+                V5(data) => data.size_hint(),
             }
     }
 
@@ -240,6 +330,7 @@ where
         match self {
             Other(version, payload) => encode_with_version(*version, payload),
             V4(data) => encode_with_version(Version(4), &data.encode()),
+            V5(data) => encode_with_version(Version(5), &data.encode()),
         }
     }
 }
@@ -254,7 +345,8 @@ where
         let version = Version::decode(input)?;
         let num_bytes = ByteCount::decode(input)?;
         match version {
-            Version(4) => Ok(V4(NetworkData::decode(input)?)),
+            Version(4) => Ok(V4(NetworkDataV4::decode(input)?)),
+            Version(5) => Ok(V5(NetworkData::decode(input)?)),
             _ => {
                 if num_bytes > MAX_SYNC_MESSAGE_SIZE {
                     Err("Sync message has unknown version and is encoded as more than the maximum size.")?;
@@ -291,6 +383,11 @@ where
             _phantom: PhantomData,
         }
     }
+
+    /// Unwrap the inner network.
+    pub fn into_inner(self) -> N {
+        self.inner
+    }
 }
 
 #[async_trait::async_trait]
@@ -308,7 +405,7 @@ where
         data: NetworkData<B, J>,
         peer_id: Self::PeerId,
     ) -> Result<(), Self::Error> {
-        self.inner.send_to(VersionedNetworkData::V4(data), peer_id)
+        self.inner.send_to(data.into(), peer_id)
     }
 
     fn send_to_random(
@@ -316,12 +413,11 @@ where
         data: NetworkData<B, J>,
         peer_ids: HashSet<Self::PeerId>,
     ) -> Result<(), Self::Error> {
-        self.inner
-            .send_to_random(VersionedNetworkData::V4(data), peer_ids)
+        self.inner.send_to_random(data.into(), peer_ids)
     }
 
     fn broadcast(&mut self, data: NetworkData<B, J>) -> Result<(), Self::Error> {
-        self.inner.broadcast(VersionedNetworkData::V4(data))
+        self.inner.broadcast(data.into())
     }
 
     /// Retrieves next message from the network.
@@ -338,8 +434,57 @@ where
                         "Received sync data of unsupported version {:?}.", version
                     )
                 }
-                (VersionedNetworkData::V4(data), peer_id) => return Ok((data, peer_id)),
+                (VersionedNetworkData::V4(data), peer_id) => return Ok((data.into(), peer_id)),
+                (VersionedNetworkData::V5(data), peer_id) => return Ok((data, peer_id)),
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use parity_scale_codec::{Decode, Encode};
+
+    use super::{NetworkData, State, VersionedNetworkData};
+    use crate::{
+        block::{
+            mock::{MockBlock, MockHeader, MockJustification},
+            UnverifiedHeader,
+        },
+        BlockId, Version,
+    };
+
+    type Data = NetworkData<MockBlock, MockJustification>;
+    type Versioned = VersionedNetworkData<MockBlock, MockJustification>;
+
+    fn version_of(data: Data) -> Version {
+        Version::decode(&mut &Versioned::from(data).encode()[..]).expect("should decode")
+    }
+
+    #[test]
+    fn data_known_before_warp_sync_is_sent_as_v4() {
+        let header = MockHeader::random_parentless(0);
+        let state = State::new(MockJustification::for_header(header.clone()), header);
+        assert_eq!(version_of(NetworkData::StateBroadcast(state)), Version(4));
+    }
+
+    #[test]
+    fn warp_sync_data_is_sent_as_v5() {
+        let id = MockHeader::random_parentless(0).id();
+        assert_eq!(
+            version_of(NetworkData::WarpProofRequest(id.clone())),
+            Version(5)
+        );
+        assert_eq!(version_of(NetworkData::HistoryRequest(id, 0)), Version(5));
+    }
+
+    #[test]
+    fn v5_data_decodes() {
+        let id: BlockId = MockHeader::random_parentless(7).id();
+        let encoded = Versioned::from(NetworkData::HistoryRequest(id.clone(), 3)).encode();
+        match Versioned::decode(&mut &encoded[..]).expect("should decode") {
+            VersionedNetworkData::V5(NetworkData::HistoryRequest(top, 3)) => assert_eq!(top, id),
+            _ => panic!("decoded wrong data"),
+        }
+    }
+}
@@ -24,6 +24,8 @@ use std::{
     iter,
 };
 
+use parity_scale_codec::Encode;
+
 use crate::{
     block::{
         Block, BlockImport, ChainStatus, Finalizer, Header, HeaderVerifier, Justification,
@@ -32,15 +34,16 @@ use crate::{
     },
     session::{SessionBoundaryInfo, SessionId},
     sync::{
-        data::{BranchKnowledge, NetworkData, Request, State},
+        data::{BranchKnowledge, NetworkData, Request, State, MAX_SYNC_MESSAGE_SIZE},
         forest::{
             Error as ForestError, ExtensionRequest, Forest,
             InitializationError as ForestInitializationError, Interest, Status as ForestStatus,
         },
         handler::request_handler::RequestHandler,
+        warp::{build_proof, WarpProof, WarpProofBuildError},
         PeerId,
     },
-    BlockId, SyncOracle,
+    BlockId, BlockNumber, SyncOracle,
 };
 
 mod request_handler;
@@ -48,6 +51,9 @@ pub use request_handler::{block_to_response, Action, RequestHandlerError};
 
 use crate::sync::data::{ResponseItem, ResponseItems};
 
+// Leaves some space for the rest of the message.
+const MAX_HISTORY_RESPONSE_SIZE: usize = MAX_SYNC_MESSAGE_SIZE as usize - 1024;
+
 /// Handles for interacting with the blockchain database.
 pub struct DatabaseIO<B, J, CS, F, BI>
 where
@@ -192,6 +198,7 @@ where
     Forest(ForestError),
     ForestInitialization(ForestInitializationError<B, J, CS>),
     RequestHandler(RequestHandlerError<CS::Error>),
+    WarpProof(WarpProofBuildError<CS::Error>),
     MissingJustification,
     BlockNotImportable(BlockId),
     HeaderNotRequired(BlockId),
@@ -216,6 +223,7 @@ where
             Forest(e) => write!(f, "forest error: {e}"),
             ForestInitialization(e) => write!(f, "forest initialization error: {e}"),
             RequestHandler(e) => write!(f, "request handler error: {e}"),
+            WarpProof(e) => write!(f, "warp sync proof error: {e}"),
             MissingJustification => write!(
                 f,
                 "justification for the last block of a past session missing"
@@ -440,6 +448,55 @@ where
         }
     }
 
+    /// Handle a request for a warp sync proof starting after the given finalized block.
+    pub fn handle_warp_proof_request(
+        &self,
+        begin: BlockId,
+    ) -> Result<WarpProof<J>, <Self as HandlerTypes>::Error> {
+        build_proof(&self.chain_status, &self.session_info, begin).map_err(Error::WarpProof)
+    }
+
+    /// Handle a request for finalized history, returning as many blocks going down from `top`
+    /// to, but excluding, the block with number `bottom` as fit in a single message.
+    pub fn handle_history_request(
+        &self,
+        top: BlockId,
+        bottom: BlockNumber,
+    ) -> Result<Vec<B>, <Self as HandlerTypes>::Error> {
+        let top_finalized = self
+            .chain_status
+            .top_finalized()
+            .map_err(Error::ChainStatus)?
+            .header()
+            .id();
+        let mut blocks = Vec::new();
+        if top.number() > top_finalized.number() {
+            return Ok(blocks);
+        }
+        let mut size = 0;
+        let mut next = Some(top);
+        while let Some(id) = next.filter(|id| id.number() > bottom) {
+            let block = match self.chain_status.block(id).map_err(Error::ChainStatus)? {
+                Some(block) => block,
+                None => break,
+            };
+            size += block.encoded_size();
+            if size > MAX_HISTORY_RESPONSE_SIZE && !blocks.is_empty() {
+                break;
+            }
+            next = block.parent_id();
+            blocks.push(block);
+        }
+        Ok(blocks)
+    }
+
+    /// Import blocks below the top finalized one, skipped by warp sync.
+    pub fn import_history(&mut self, blocks: Vec<B>) {
+        for block in blocks {
+            self.block_importer.import_history_block(block);
+        }
+    }
+
     /// Handle a single unverified justification.
     /// Return whether this justification was higher than the previously known highest justification.
     fn handle_justification(
@@ -2917,4 +2974,72 @@ mod tests {
         assert!(matches!(action1, HandleStateAction::Noop));
         assert!(matches!(action2, HandleStateAction::Noop));
     }
+
+    fn import_and_finalize_branch(
+        handler: &mut TestHandler,
+        backend: &mut Backend,
+        branch_length: usize,
+    ) -> Vec<MockHeader> {
+        let peer = rand::random();
+        let headers = import_branch(backend, branch_length);
+        for header in &headers {
+            handler
+                .block_imported(header.clone())
+                .expect("importing in order");
+            handler
+                .handle_justification(
+                    MockJustification::for_header(header.clone()).into_unverified(),
+                    Some(peer),
+                )
+                .expect("correct justification");
+        }
+        headers
+    }
+
+    #[test]
+    fn serves_warp_proof_of_finalized_sessions() {
+        let (mut handler, mut backend, _keep, genesis) = setup();
+        import_and_finalize_branch(&mut handler, &mut backend, 45);
+        let proof = handler
+            .handle_warp_proof_request(genesis)
+            .expect("should build proof");
+        assert!(proof.is_finished());
+        assert_eq!(proof.fragment_count(), 2);
+    }
+
+    #[test]
+    fn refuses_warp_proof_from_unknown_block() {
+        let (mut handler, mut backend, _keep, _genesis) = setup();
+        import_and_finalize_branch(&mut handler, &mut backend, 45);
+        assert!(matches!(
+            handler.handle_warp_proof_request(BlockId::new_random(19)),
+            Err(Error::WarpProof(_))
+        ));
+    }
+
+    #[test]
+    fn serves_history_down_to_requested_block() {
+        let (mut handler, mut backend, _keep, _genesis) = setup();
+        let headers = import_and_finalize_branch(&mut handler, &mut backend, 10);
+        let blocks = handler
+            .handle_history_request(headers[7].id(), 3)
+            .expect("should serve history");
+        let ids: Vec<_> = blocks.iter().map(|block| block.header().id()).collect();
+        let expected: Vec<_> = headers[3..8]
+            .iter()
+            .rev()
+            .map(|header| header.id())
+            .collect();
+        assert_eq!(ids, expected);
+    }
+
+    #[test]
+    fn does_not_serve_unfinalized_history() {
+        let (handler, mut backend, _keep, _genesis) = setup();
+        let headers = import_branch(&mut backend, 10);
+        assert!(handler
+            .handle_history_request(headers[7].id(), 0)
+            .expect("should handle request")
+            .is_empty());
+    }
 }
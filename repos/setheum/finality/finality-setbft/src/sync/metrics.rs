@@ -46,6 +46,9 @@ pub enum Event {
     HandleStateResponse,
     HandleJustificationFromUser,
     HandleInternalRequest,
+    HandleWarpProofRequest,
+    HandleHistoryRequest,
+    HandleHistoryResponse,
 }
 
 use Event::*;
@@ -67,11 +70,14 @@ impl Event {
             HandleStateResponse => "handle_state_response",
             HandleJustificationFromUser => "handle_justification_from_user",
             HandleInternalRequest => "handle_internal_request",
+            HandleWarpProofRequest => "handle_warp_proof_request",
+            HandleHistoryRequest => "handle_history_request",
+            HandleHistoryResponse => "handle_history_response",
         }
     }
 }
 
-const ALL_EVENTS: [Event; 14] = [
+const ALL_EVENTS: [Event; 17] = [
     Broadcast,
     SendRequest,
     SendTo,
@@ -86,9 +92,12 @@ const ALL_EVENTS: [Event; 14] = [
     HandleStateResponse,
     HandleJustificationFromUser,
     HandleInternalRequest,
+    HandleWarpProofRequest,
+    HandleHistoryRequest,
+    HandleHistoryResponse,
 ];
 
-const ERRORING_EVENTS: [Event; 11] = [
+const ERRORING_EVENTS: [Event; 14] = [
     Broadcast,
     SendRequest,
     SendTo,
@@ -100,6 +109,9 @@ const ERRORING_EVENTS: [Event; 11] = [
     HandleBlockImported,
     HandleJustificationFromUser,
     HandleInternalRequest,
+    HandleWarpProofRequest,
+    HandleHistoryRequest,
+    HandleHistoryResponse,
 ];
 
 pub enum Metrics {
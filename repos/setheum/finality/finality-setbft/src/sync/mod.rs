@@ -39,11 +39,13 @@ mod service;
 mod task_queue;
 mod tasks;
 mod ticker;
+mod warp;
 
 pub use data::MAX_MESSAGE_SIZE;
 pub use handler::DatabaseIO;
 pub use select_chain::FavouriteSelectChainProvider;
 pub use service::{Service, IO};
+pub use warp::{Backfill, WarpSync};
 
 const LOG_TARGET: &str = "setbft-block-sync";
 
@@ -50,14 +50,17 @@ use crate::{
         task_queue::TaskQueue,
         tasks::{Action as TaskAction, RequestTask},
         ticker::Ticker,
+        warp::{Backfill, WarpProof},
         BlockId, JustificationSubmissions, RequestBlocks, LOG_TARGET,
     },
-    SyncOracle, STATUS_REPORT_INTERVAL,
+    BlockNumber, SyncOracle, STATUS_REPORT_INTERVAL,
 };
 
 const BROADCAST_COOLDOWN: Duration = Duration::from_millis(600);
 const CHAIN_EXTENSION_COOLDOWN: Duration = Duration::from_millis(300);
 const TICK_PERIOD: Duration = Duration::from_secs(5);
+// How often we repeat a history request that was not answered.
+const HISTORY_REQUEST_PERIOD: Duration = Duration::from_secs(10);
 
 pub struct IO<B, J, N, CE, CS, F, BI>
 where
@@ -166,6 +169,12 @@ where
     metrics: Metrics,
     slo_metrics: SloMetrics,
     favourite_block_request: mpsc::UnboundedReceiver<oneshot::Sender<J::Header>>,
+    backfill: Option<Backfill<B>>,
+    // Peers that can be asked for the history, learned from their state broadcasts.
+    history_peers: HashSet<N::PeerId>,
+    // The peer asked for the history and the newest block asked for, if any request is
+    // outstanding.
+    history_request: Option<(N::PeerId, BlockId)>,
 }
 
 impl<J: Justification> JustificationSubmissions<J> for mpsc::UnboundedSender<J::Unverified> {
@@ -196,6 +205,7 @@ where
     BI: BlockImport<B>,
 {
     /// Create a new service using the provided network for communication.
+    /// If warp sync skipped some history, the backfill is used to download it.
     /// Also returns an interface for requesting blocks.
     pub fn new(
         verifier: V,
@@ -204,6 +214,7 @@ where
         metrics_registry: Option<Registry>,
         slo_metrics: SloMetrics,
         favourite_block_request: mpsc::UnboundedReceiver<oneshot::Sender<J::Header>>,
+        backfill: Option<Backfill<B>>,
     ) -> Result<(Self, impl RequestBlocks<B::UnverifiedHeader>), HandlerError<B, J, CS, V, F>> {
         let IO {
             network,
@@ -240,6 +251,9 @@ where
                 metrics,
                 slo_metrics,
                 favourite_block_request,
+                backfill,
+                history_peers: HashSet::new(),
+                history_request: None,
             },
             block_requests_for_sync,
         ))
@@ -387,6 +401,9 @@ where
             state,
             peer
         );
+        if self.backfill.is_some() {
+            self.history_peers.insert(peer.clone());
+        }
         match self.handler.handle_state(state, peer.clone()) {
             Ok((action, maybe_proof)) => {
                 self.process_equivocation_proofs(maybe_proof);
@@ -722,6 +739,144 @@ where
         }
     }
 
+    fn handle_warp_proof_request(&mut self, begin: BlockId, peer: N::PeerId) {
+        trace!(
+            target: LOG_TARGET,
+            "Handling a warp sync proof request from {:?} starting after {:?}.",
+            peer,
+            begin
+        );
+        self.metrics.report_event(Event::HandleWarpProofRequest);
+        match self.handler.handle_warp_proof_request(begin) {
+            Ok(proof) => self.send_to(NetworkData::WarpProofResponse(proof), peer),
+            Err(e) => {
+                self.metrics
+                    .report_event_error(Event::HandleWarpProofRequest);
+                debug!(
+                    target: LOG_TARGET,
+                    "Error handling warp sync proof request from {:?}: {}.", peer, e
+                );
+            }
+        }
+    }
+
+    fn handle_warp_proof_response(&mut self, proof: WarpProof<J>, peer: N::PeerId) {
+        // Proofs are only of interest before the service starts.
+        trace!(
+            target: LOG_TARGET,
+            "Ignoring a warp sync proof with {} fragments from {:?}.",
+            proof.fragment_count(),
+            peer
+        );
+    }
+
+    fn handle_history_request(&mut self, top: BlockId, bottom: BlockNumber, peer: N::PeerId) {
+        trace!(
+            target: LOG_TARGET,
+            "Handling a history request from {:?} for blocks from {:?} down to {}.",
+            peer,
+            top,
+            bottom
+        );
+        self.metrics.report_event(Event::HandleHistoryRequest);
+        match self.handler.handle_history_request(top, bottom) {
+            Ok(blocks) if blocks.is_empty() => (),
+            Ok(blocks) => self.send_to(NetworkData::HistoryResponse(blocks), peer),
+            Err(e) => {
+                self.metrics.report_event_error(Event::HandleHistoryRequest);
+                warn!(
+                    target: LOG_TARGET,
+                    "Error handling history request from {:?}: {}.", peer, e
+                );
+            }
+        }
+    }
+
+    fn request_history(&mut self) {
+        let (top, bottom) = match self.backfill.as_ref().map(Backfill::next_request) {
+            Some(Some(request)) => request,
+            Some(None) => {
+                info!(target: LOG_TARGET, "Finished importing the history skipped by warp sync.");
+                self.backfill = None;
+                self.history_peers.clear();
+                self.history_request = None;
+                return;
+            }
+            None => return,
+        };
+        // An unanswered request is repeated to another peer, if there is one.
+        let previous = self.history_request.take().map(|(peer, _)| peer);
+        let peer = match self
+            .history_peers
+            .iter()
+            .find(|peer| Some(*peer) != previous.as_ref())
+            .or(previous.as_ref())
+        {
+            Some(peer) => peer.clone(),
+            None => {
+                debug!(target: LOG_TARGET, "No peers to request history from.");
+                return;
+            }
+        };
+        match self.network.send_to(
+            NetworkData::HistoryRequest(top.clone(), bottom),
+            peer.clone(),
+        ) {
+            Ok(()) => self.history_request = Some((peer, top)),
+            Err(e) => warn!(target: LOG_TARGET, "Error sending history request: {}.", e),
+        }
+    }
+
+    fn handle_history_response(&mut self, blocks: Vec<B>, peer: N::PeerId) {
+        trace!(
+            target: LOG_TARGET,
+            "Handling a history response with {} blocks from {:?}.",
+            blocks.len(),
+            peer
+        );
+        self.metrics.report_event(Event::HandleHistoryResponse);
+        // Only the peer we asked may answer, and only with what we asked for.
+        let expected = match &self.history_request {
+            Some((requested_from, top)) if requested_from == &peer => top,
+            _ => {
+                debug!(
+                    target: LOG_TARGET,
+                    "Ignoring an unrequested history response from {:?}.", peer
+                );
+                return;
+            }
+        };
+        if blocks.first().map(|block| block.header().id()).as_ref() != Some(expected) {
+            self.metrics
+                .report_event_error(Event::HandleHistoryResponse);
+            debug!(
+                target: LOG_TARGET,
+                "History response from {:?} does not start with the requested block.", peer
+            );
+            return;
+        }
+        self.history_request = None;
+        let backfill = match self.backfill.as_mut() {
+            Some(backfill) => backfill,
+            None => return,
+        };
+        match backfill.handle_response(blocks) {
+            Ok(ready) => {
+                self.handler.import_history(ready);
+                self.request_history();
+            }
+            Err(e) => {
+                self.metrics
+                    .report_event_error(Event::HandleHistoryResponse);
+                self.history_peers.remove(&peer);
+                debug!(
+                    target: LOG_TARGET,
+                    "Bad history response from {:?}: {}.", peer, e
+                );
+            }
+        }
+    }
+
     fn handle_network_data(&mut self, data: NetworkData<B, J>, peer: N::PeerId) {
         use NetworkData::*;
         match data {
@@ -736,6 +891,10 @@ where
             }
             RequestResponse(response_items) => self.handle_request_response(response_items, peer),
             ChainExtensionRequest(state) => self.handle_chain_extension_request(state, peer),
+            WarpProofRequest(begin) => self.handle_warp_proof_request(begin, peer),
+            WarpProofResponse(proof) => self.handle_warp_proof_response(proof, peer),
+            HistoryRequest(top, bottom) => self.handle_history_request(top, bottom, peer),
+            HistoryResponse(blocks) => self.handle_history_response(blocks, peer),
         }
     }
 
@@ -781,6 +940,7 @@ where
         }
 
         let mut status_ticker = time::interval(STATUS_REPORT_INTERVAL);
+        let mut history_ticker = time::interval(HISTORY_REQUEST_PERIOD);
         loop {
             self.report_sync_state_change();
 
@@ -825,6 +985,8 @@ where
                     self.send_favourite_block(favourite_block_sender);
                 }
 
+                _ = history_ticker.tick(), if self.backfill.is_some() => self.request_history(),
+
                 _ = status_ticker.tick() => {
                     info!(target: LOG_TARGET, "{}", self.handler.status());
                 },
@@ -0,0 +1,246 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    collections::VecDeque,
+    fmt::{Display, Error as FmtError, Formatter},
+    mem,
+};
+
+use crate::{
+    block::{Block, UnverifiedHeader},
+    BlockId, BlockNumber,
+};
+
+/// What can go wrong when handling downloaded history.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    Finished,
+    UnexpectedBlock(BlockId, BlockId),
+    Disconnected(BlockId),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use Error::*;
+        match self {
+            Finished => write!(f, "received history after the backfill finished"),
+            UnexpectedBlock(expected, got) => {
+                write!(f, "expected block {expected}, got block {got}")
+            }
+            Disconnected(id) => write!(
+                f,
+                "downloaded history ended at block {id}, which does not connect to the imported one"
+            ),
+        }
+    }
+}
+
+/// Keeps track of filling in the history skipped by warp sync.
+///
+/// Goes through the skipped sessions starting from the oldest. For every session the blocks are
+/// downloaded from its verified last block downwards, so that each of them can be checked against
+/// the hash claimed by its child. Once a session connects to the already imported history, its
+/// blocks are ready to be imported, starting from the oldest.
+pub struct Backfill<B: Block> {
+    /// The verified last blocks of the sessions not backfilled yet, starting from the oldest.
+    /// The newest one is the warp sync target, which is already imported.
+    checkpoints: VecDeque<BlockId>,
+    /// The top block of the already imported history.
+    imported_top: BlockId,
+    /// The blocks of the current session downloaded so far, starting from the newest.
+    pending: Vec<B>,
+}
+
+impl<B: Block> Backfill<B> {
+    /// Start backfilling above the given imported block, using the verified session ends.
+    pub fn new(imported_top: BlockId, checkpoints: Vec<BlockId>) -> Self {
+        Backfill {
+            checkpoints: checkpoints.into(),
+            imported_top,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Whether all the skipped history has been imported.
+    pub fn is_finished(&self) -> bool {
+        self.checkpoints.is_empty()
+    }
+
+    /// The block that should be downloaded next, together with the number of the block at which
+    /// the download should stop, or `None` if the backfill is finished.
+    pub fn next_request(&self) -> Option<(BlockId, BlockNumber)> {
+        let next = match self.pending.last() {
+            Some(block) => block.parent_id()?,
+            None => self.checkpoints.front()?.clone(),
+        };
+        Some((next, self.imported_top.number()))
+    }
+
+    /// Handle downloaded blocks, ordered from the newest.
+    /// Returns the blocks ready to be imported, ordered from the oldest.
+    pub fn handle_response(&mut self, blocks: Vec<B>) -> Result<Vec<B>, Error> {
+        let mut ready = Vec::new();
+        for block in blocks {
+            let (expected, _) = self.next_request().ok_or(Error::Finished)?;
+            let id = block.header().id();
+            if id != expected {
+                return Err(Error::UnexpectedBlock(expected, id));
+            }
+            let parent = block.parent_id();
+            self.pending.push(block);
+            match parent {
+                Some(parent) if parent == self.imported_top => {
+                    ready.extend(self.complete_session())
+                }
+                Some(parent) if parent.number() > self.imported_top.number() => (),
+                _ => {
+                    self.pending.clear();
+                    return Err(Error::Disconnected(id));
+                }
+            }
+        }
+        Ok(ready)
+    }
+
+    fn complete_session(&mut self) -> Vec<B> {
+        let mut blocks = mem::take(&mut self.pending);
+        blocks.reverse();
+        self.imported_top = self
+            .checkpoints
+            .pop_front()
+            .expect("we only download history while there are checkpoints");
+        if self.checkpoints.is_empty() {
+            // The warp sync target itself is already imported.
+            blocks.pop();
+        }
+        blocks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Backfill, Error};
+    use crate::{
+        block::{mock::MockBlock, Header},
+        BlockId,
+    };
+
+    const SESSION_PERIOD: usize = 5;
+
+    fn chain(sessions: usize) -> (BlockId, Vec<MockBlock>) {
+        let genesis = BlockId::new_random(0);
+        let blocks = genesis
+            .random_branch()
+            .take(sessions * SESSION_PERIOD)
+            .map(|header| MockBlock::new(header, true))
+            .collect();
+        (genesis, blocks)
+    }
+
+    fn checkpoints(blocks: &[MockBlock]) -> Vec<BlockId> {
+        blocks
+            .iter()
+            .skip(SESSION_PERIOD - 2)
+            .step_by(SESSION_PERIOD)
+            .map(Header::id)
+            .collect()
+    }
+
+    fn ids(blocks: &[MockBlock]) -> Vec<BlockId> {
+        blocks.iter().map(Header::id).collect()
+    }
+
+    fn download(blocks: &[MockBlock], from: &BlockId, to: usize) -> Vec<MockBlock> {
+        let top = blocks
+            .iter()
+            .position(|block| &Header::id(block) == from)
+            .expect("should be in the chain");
+        blocks[..=top]
+            .iter()
+            .rev()
+            .take(top + 1 - to)
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn backfills_session_by_session() {
+        let (genesis, blocks) = chain(3);
+        let checkpoints = checkpoints(&blocks);
+        let target = checkpoints.last().expect("there are checkpoints").clone();
+        let mut backfill = Backfill::new(genesis, checkpoints);
+        let mut imported = Vec::new();
+        while let Some((from, bottom)) = backfill.next_request() {
+            assert!(bottom < from.number());
+            let response = download(&blocks, &from, bottom as usize);
+            imported.extend(backfill.handle_response(response).expect("should accept"));
+        }
+        assert!(backfill.is_finished());
+        let expected: Vec<_> = blocks
+            .iter()
+            .take_while(|block| Header::id(*block) != target)
+            .cloned()
+            .collect();
+        assert_eq!(ids(&imported), ids(&expected));
+    }
+
+    #[test]
+    fn accepts_partial_responses() {
+        let (genesis, blocks) = chain(2);
+        let checkpoints = checkpoints(&blocks);
+        let mut backfill = Backfill::new(genesis, checkpoints.clone());
+        let (from, bottom) = backfill.next_request().expect("should request");
+        let mut response = download(&blocks, &from, bottom as usize);
+        let rest = response.split_off(2);
+        assert!(backfill
+            .handle_response(response)
+            .expect("should accept")
+            .is_empty());
+        let (from, _) = backfill.next_request().expect("should request");
+        assert_eq!(Some(&from), rest.first().map(Header::id).as_ref());
+        let ready = backfill.handle_response(rest).expect("should accept");
+        assert_eq!(ready.last().map(Header::id), Some(checkpoints[0].clone()));
+    }
+
+    #[test]
+    fn rejects_unexpected_blocks() {
+        let (genesis, blocks) = chain(2);
+        let mut backfill = Backfill::new(genesis, checkpoints(&blocks));
+        let response = vec![blocks[0].clone()];
+        assert!(matches!(
+            backfill.handle_response(response),
+            Err(Error::UnexpectedBlock(_, _))
+        ));
+    }
+
+    #[test]
+    fn rejects_history_not_connecting_to_imported() {
+        let (_, blocks) = chain(2);
+        let other_genesis = BlockId::new_random(0);
+        let checkpoints = checkpoints(&blocks);
+        let mut backfill = Backfill::new(other_genesis, checkpoints.clone());
+        let response = download(&blocks, &checkpoints[0], 0);
+        assert!(matches!(
+            backfill.handle_response(response),
+            Err(Error::Disconnected(_))
+        ));
+    }
+}
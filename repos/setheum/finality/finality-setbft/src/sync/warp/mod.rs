@@ -0,0 +1,34 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Warp sync, i.e. catching up with a chain far ahead by verifying only the justifications of the
+//! last blocks of sessions, importing the state of the newest one and filling in the skipped
+//! history afterwards.
+
+mod backfill;
+mod proof;
+mod service;
+
+pub use backfill::{Backfill, Error as BackfillError};
+pub use proof::{
+    build_proof, BuildError as WarpProofBuildError, ProofVerifier,
+    VerifyError as WarpProofVerifyError, WarpProof,
+};
+pub use service::WarpSync;
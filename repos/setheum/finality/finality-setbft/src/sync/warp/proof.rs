@@ -0,0 +1,350 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::fmt::{Display, Error as FmtError, Formatter};
+
+use parity_scale_codec::{Decode, Encode};
+
+use crate::{
+    block::{
+        Block, ChainStatus, FinalizationStatus, Header, Justification, StorageProof,
+        UnverifiedHeader, UnverifiedHeaderFor, UnverifiedJustification, WarpSyncVerifier,
+    },
+    session::{SessionBoundaryInfo, SessionId},
+    sync::data::MAX_SYNC_MESSAGE_SIZE,
+    BlockId, BlockNumber,
+};
+
+// Leaves plenty of space for the rest of the message.
+const MAX_PROOF_SIZE: usize = MAX_SYNC_MESSAGE_SIZE as usize / 2;
+
+/// A justification of the last block of a session, together with a proof of the authorities
+/// of the next session read from the state of that block.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct Fragment<J: Justification> {
+    justification: J::Unverified,
+    next_authorities_proof: StorageProof,
+}
+
+/// A proof of finality of a block far ahead of the requester, consisting of justifications of
+/// the last blocks of consecutive sessions, each verifiable with the authorities proven by the
+/// previous one.
+#[derive(Clone, Debug, Encode, Decode)]
+pub struct WarpProof<J: Justification> {
+    fragments: Vec<Fragment<J>>,
+    /// Whether the proof reaches the last session ended by the responder,
+    /// if not the requester should ask for the rest.
+    is_finished: bool,
+}
+
+impl<J: Justification> WarpProof<J> {
+    pub fn is_finished(&self) -> bool {
+        self.is_finished
+    }
+
+    pub fn fragment_count(&self) -> usize {
+        self.fragments.len()
+    }
+}
+
+/// What can go wrong when building a warp sync proof.
+#[derive(Debug)]
+pub enum BuildError<CSE: Display> {
+    ChainStatus(CSE),
+    NotFinalized(BlockId),
+    MissingJustification(BlockNumber),
+}
+
+impl<CSE: Display> Display for BuildError<CSE> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use BuildError::*;
+        match self {
+            ChainStatus(e) => write!(f, "chain status error: {e}"),
+            NotFinalized(id) => write!(f, "block {id} is not finalized"),
+            MissingJustification(number) => write!(
+                f,
+                "justification for the last block {number} of a past session missing"
+            ),
+        }
+    }
+}
+
+/// The session whose last block comes first after the given one.
+fn next_session_end(session_info: &SessionBoundaryInfo, after: &BlockId) -> SessionId {
+    let session = session_info.session_id_from_block_num(after.number());
+    match session_info.last_block_of_session(session) == after.number() {
+        true => session.next(),
+        false => session,
+    }
+}
+
+/// Build a warp sync proof starting after the given finalized block, going as far as the top
+/// finalized block or the size limit allows.
+pub fn build_proof<B, J, CS>(
+    chain_status: &CS,
+    session_info: &SessionBoundaryInfo,
+    begin: BlockId,
+) -> Result<WarpProof<J>, BuildError<CS::Error>>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+    CS: ChainStatus<B, J>,
+{
+    use BuildError::*;
+    use FinalizationStatus::*;
+    let begin_header = match chain_status
+        .finalized_at(begin.number())
+        .map_err(ChainStatus)?
+    {
+        FinalizedWithJustification(justification) => justification.header().clone(),
+        FinalizedByDescendant(header) => header,
+        NotFinalized => return Err(NotFinalized(begin)),
+    };
+    if begin_header.id() != begin {
+        return Err(NotFinalized(begin));
+    }
+
+    let top_finalized = chain_status
+        .top_finalized()
+        .map_err(ChainStatus)?
+        .header()
+        .id()
+        .number();
+    let mut session = next_session_end(session_info, &begin);
+    let mut fragments = Vec::new();
+    let mut size = 0;
+    let is_finished = loop {
+        let number = session_info.last_block_of_session(session);
+        if number > top_finalized {
+            break true;
+        }
+        let justification = match chain_status.finalized_at(number).map_err(ChainStatus)? {
+            FinalizedWithJustification(justification) => justification,
+            _ => return Err(MissingJustification(number)),
+        };
+        let next_authorities_proof = chain_status
+            .next_authorities_proof(justification.header().id())
+            .map_err(ChainStatus)?;
+        let fragment = Fragment {
+            justification: justification.into_unverified(),
+            next_authorities_proof,
+        };
+        size += fragment.encoded_size();
+        if size > MAX_PROOF_SIZE && !fragments.is_empty() {
+            break false;
+        }
+        fragments.push(fragment);
+        session = session.next();
+    };
+    Ok(WarpProof {
+        fragments,
+        is_finished,
+    })
+}
+
+/// What can go wrong when verifying a warp sync proof.
+#[derive(Debug)]
+pub enum VerifyError<WVE: Display> {
+    Verifier(WVE),
+    UnexpectedBlock(BlockNumber, BlockNumber),
+    Empty,
+}
+
+impl<WVE: Display> Display for VerifyError<WVE> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), FmtError> {
+        use VerifyError::*;
+        match self {
+            Verifier(e) => write!(f, "warp sync verifier error: {e}"),
+            UnexpectedBlock(expected, got) => write!(
+                f,
+                "expected a justification of block {expected}, got one of block {got}"
+            ),
+            Empty => write!(f, "unfinished proof without any fragments"),
+        }
+    }
+}
+
+/// Verifies consecutive warp sync proofs, keeping track of the progress.
+pub struct ProofVerifier<J: Justification, WV: WarpSyncVerifier<J>> {
+    verifier: WV,
+    session_info: SessionBoundaryInfo,
+    last_verified: BlockId,
+    checkpoints: Vec<BlockId>,
+    target: Option<J>,
+}
+
+impl<J: Justification, WV: WarpSyncVerifier<J>> ProofVerifier<J, WV> {
+    /// Start verifying after the given trusted block, the verifier has to know the authorities
+    /// of the session containing the first block after it.
+    pub fn new(verifier: WV, session_info: SessionBoundaryInfo, start: BlockId) -> Self {
+        ProofVerifier {
+            verifier,
+            session_info,
+            last_verified: start,
+            checkpoints: Vec::new(),
+            target: None,
+        }
+    }
+
+    /// The block after which the next proof should start.
+    pub fn last_verified(&self) -> BlockId {
+        self.last_verified.clone()
+    }
+
+    /// Verify a proof continuing the already verified ones.
+    /// Returns whether the proof claims there is nothing more to verify.
+    pub fn handle_proof(&mut self, proof: WarpProof<J>) -> Result<bool, VerifyError<WV::Error>> {
+        let WarpProof {
+            fragments,
+            is_finished,
+        } = proof;
+        if fragments.is_empty() && !is_finished {
+            return Err(VerifyError::Empty);
+        }
+        for Fragment {
+            justification,
+            next_authorities_proof,
+        } in fragments
+        {
+            let expected = self
+                .session_info
+                .last_block_of_session(next_session_end(&self.session_info, &self.last_verified));
+            let number = justification.header().id().number();
+            if number != expected {
+                return Err(VerifyError::UnexpectedBlock(expected, number));
+            }
+            let justification = self
+                .verifier
+                .verify_session_end(justification, next_authorities_proof)
+                .map_err(VerifyError::Verifier)?;
+            let id = justification.header().id();
+            self.checkpoints.push(id.clone());
+            self.last_verified = id;
+            self.target = Some(justification);
+        }
+        Ok(is_finished)
+    }
+
+    /// The justification of the last verified session end, if any, together with the identifiers
+    /// of all the verified session ends, starting with the oldest.
+    pub fn into_target(self) -> Option<(J, Vec<BlockId>)> {
+        let ProofVerifier {
+            target,
+            checkpoints,
+            ..
+        } = self;
+        target.map(|target| (target, checkpoints))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{build_proof, BuildError, ProofVerifier, VerifyError};
+    use crate::{
+        block::{
+            mock::{Backend, MockBlock, MockHeader, MockJustification},
+            BlockImport, ChainStatus, ChainStatusNotifier, Finalizer, Header, Justification,
+        },
+        session::{SessionBoundaryInfo, SessionId, SessionPeriod},
+        BlockId,
+    };
+
+    const SESSION_BOUNDARY_INFO: SessionBoundaryInfo = SessionBoundaryInfo::new(SessionPeriod(20));
+
+    fn setup(length: u32) -> (Backend, impl ChainStatusNotifier<MockHeader>, BlockId) {
+        let (mut backend, notifier) = Backend::setup(SESSION_BOUNDARY_INFO);
+        let genesis = backend.top_finalized().expect("genesis").header().clone();
+        let mut parent = genesis.clone();
+        for _ in 0..length {
+            let header = parent.random_child();
+            backend.import_block(MockBlock::new(header.clone(), true), false);
+            backend
+                .finalize(MockJustification::for_header(header.clone()))
+                .expect("should finalize");
+            parent = header;
+        }
+        (backend, notifier, genesis.id())
+    }
+
+    fn session_end(session: u32) -> u32 {
+        SESSION_BOUNDARY_INFO.last_block_of_session(SessionId(session))
+    }
+
+    #[test]
+    fn builds_proof_up_to_top_finalized_session_end() {
+        let (backend, _keep, genesis) = setup(session_end(2) + 5);
+        let proof = build_proof(&backend, &SESSION_BOUNDARY_INFO, genesis).expect("should build");
+        assert!(proof.is_finished());
+        assert_eq!(proof.fragment_count(), 3);
+    }
+
+    #[test]
+    fn builds_empty_proof_when_nothing_to_prove() {
+        let (backend, _keep, genesis) = setup(session_end(0) - 1);
+        let proof = build_proof(&backend, &SESSION_BOUNDARY_INFO, genesis).expect("should build");
+        assert!(proof.is_finished());
+        assert_eq!(proof.fragment_count(), 0);
+    }
+
+    #[test]
+    fn refuses_to_build_proof_from_unknown_block() {
+        let (backend, _keep, _) = setup(session_end(1));
+        let unknown = BlockId::new_random(session_end(0));
+        assert!(matches!(
+            build_proof(&backend, &SESSION_BOUNDARY_INFO, unknown),
+            Err(BuildError::NotFinalized(_))
+        ));
+    }
+
+    #[test]
+    fn verifies_proof_and_returns_target() {
+        let (backend, _keep, genesis) = setup(session_end(2) + 5);
+        let proof =
+            build_proof(&backend, &SESSION_BOUNDARY_INFO, genesis.clone()).expect("should build");
+        let mut verifier = ProofVerifier::new(backend.clone(), SESSION_BOUNDARY_INFO, genesis);
+        assert!(verifier.handle_proof(proof).expect("should verify"));
+        let (target, checkpoints) = verifier.into_target().expect("there should be a target");
+        assert_eq!(target.header().id().number(), session_end(2));
+        assert_eq!(checkpoints.len(), 3);
+        assert_eq!(checkpoints.last(), Some(&target.header().id()));
+    }
+
+    #[test]
+    fn rejects_proof_skipping_sessions() {
+        let (backend, _keep, genesis) = setup(session_end(2) + 5);
+        let first_end = backend
+            .finalized_at(session_end(0))
+            .expect("should be finalized")
+            .has_justification()
+            .expect("should have justification")
+            .header()
+            .id();
+        let mut verifier = ProofVerifier::new(backend.clone(), SESSION_BOUNDARY_INFO, genesis);
+        let proof = build_proof(&backend, &SESSION_BOUNDARY_INFO, first_end).expect("should build");
+        assert!(matches!(
+            verifier.handle_proof(proof),
+            Err(VerifyError::UnexpectedBlock(_, _))
+        ));
+        let proof = build_proof(&backend, &SESSION_BOUNDARY_INFO, verifier.last_verified())
+            .expect("should build");
+        assert!(verifier.handle_proof(proof).expect("should verify"));
+        assert_eq!(verifier.last_verified().number(), session_end(2));
+    }
+}
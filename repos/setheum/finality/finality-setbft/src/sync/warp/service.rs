@@ -0,0 +1,306 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    collections::{HashMap, HashSet},
+    time::Duration,
+};
+
+use log::{debug, info, warn};
+use tokio::time::{sleep, timeout};
+
+use crate::{
+    block::{
+        Block, Header, Justification, UnverifiedHeader, UnverifiedHeaderFor,
+        UnverifiedJustification, WarpSyncVerifier, WarpTargetImport,
+    },
+    network::GossipNetwork,
+    session::SessionBoundaryInfo,
+    sync::{
+        data::{NetworkData, VersionWrapper, VersionedNetworkData},
+        warp::{Backfill, ProofVerifier, WarpProof},
+        LOG_TARGET,
+    },
+    BlockId, BlockNumber, SyncOracle,
+};
+
+// How long we listen to state broadcasts to learn how far ahead our peers are.
+const PEER_DISCOVERY_TIME: Duration = Duration::from_secs(15);
+// How long we wait for a response to a single proof request.
+const PROOF_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+// Warp sync only pays off if we are at least this many sessions behind.
+const SESSIONS_BEHIND_THRESHOLD: u32 = 2;
+
+/// Catches up with a chain far ahead using warp sync, before the regular sync starts.
+///
+/// First listens to state broadcasts to find out which peers are far enough ahead, then requests
+/// consecutive warp sync proofs from them, and finally imports the state of the last verified
+/// session end. If anything goes wrong it gives up, leaving the rest to the regular sync.
+pub struct WarpSync<B, J, N, WV, WI>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+    N: GossipNetwork<VersionedNetworkData<B, J>>,
+    WV: WarpSyncVerifier<J>,
+    WI: WarpTargetImport<J, N::PeerId>,
+{
+    network: VersionWrapper<B, J, N>,
+    session_info: SessionBoundaryInfo,
+    verifier: ProofVerifier<J, WV>,
+    start: BlockId,
+    target_import: WI,
+    sync_oracle: SyncOracle,
+    peers: HashMap<N::PeerId, BlockNumber>,
+    failed_peers: HashSet<N::PeerId>,
+}
+
+impl<B, J, N, WV, WI> WarpSync<B, J, N, WV, WI>
+where
+    J: Justification,
+    B: Block<UnverifiedHeader = UnverifiedHeaderFor<J>>,
+    N: GossipNetwork<VersionedNetworkData<B, J>>,
+    WV: WarpSyncVerifier<J>,
+    WI: WarpTargetImport<J, N::PeerId>,
+{
+    /// Create a warp sync starting after the given trusted finalized block. The verifier has to
+    /// know the authorities of the session containing the first block after it.
+    pub fn new(
+        network: N,
+        session_info: SessionBoundaryInfo,
+        verifier: WV,
+        start: BlockId,
+        target_import: WI,
+        sync_oracle: SyncOracle,
+    ) -> Self {
+        WarpSync {
+            network: VersionWrapper::new(network),
+            verifier: ProofVerifier::new(verifier, session_info.clone(), start.clone()),
+            session_info,
+            start,
+            target_import,
+            sync_oracle,
+            peers: HashMap::new(),
+            failed_peers: HashSet::new(),
+        }
+    }
+
+    fn handle_state_broadcast(&mut self, top_justification: J::Unverified, peer: N::PeerId) {
+        if self.failed_peers.contains(&peer) {
+            return;
+        }
+        let number = top_justification.header().id().number();
+        let known = self.peers.entry(peer).or_insert(number);
+        *known = (*known).max(number);
+    }
+
+    async fn discover_peers(&mut self) -> Result<(), N::Error> {
+        let deadline = sleep(PEER_DISCOVERY_TIME);
+        tokio::pin!(deadline);
+        loop {
+            tokio::select! {
+                maybe_data = self.network.next() => {
+                    if let (NetworkData::StateBroadcast(state), peer) = maybe_data? {
+                        self.handle_state_broadcast(state.top_justification(), peer);
+                    }
+                },
+                _ = &mut deadline => return Ok(()),
+            }
+        }
+    }
+
+    fn best_known(&self) -> BlockNumber {
+        self.peers
+            .values()
+            .max()
+            .copied()
+            .unwrap_or(self.start.number())
+    }
+
+    fn sessions_behind(&self) -> u32 {
+        let ours = self
+            .session_info
+            .session_id_from_block_num(self.start.number());
+        let theirs = self
+            .session_info
+            .session_id_from_block_num(self.best_known());
+        theirs.0.saturating_sub(ours.0)
+    }
+
+    /// The peer that should know the most, among the ones that did not fail us yet.
+    fn best_peer(&self) -> Option<N::PeerId> {
+        self.peers
+            .iter()
+            .max_by_key(|(_, number)| **number)
+            .map(|(peer, _)| peer.clone())
+    }
+
+    async fn next_proof(&mut self, peer: &N::PeerId) -> Result<WarpProof<J>, N::Error> {
+        loop {
+            match self.network.next().await? {
+                (NetworkData::WarpProofResponse(proof), sender) if &sender == peer => {
+                    return Ok(proof)
+                }
+                (NetworkData::StateBroadcast(state), sender) => {
+                    self.handle_state_broadcast(state.top_justification(), sender)
+                }
+                _ => (),
+            }
+        }
+    }
+
+    async fn await_proof(&mut self, peer: &N::PeerId) -> Result<Option<WarpProof<J>>, N::Error> {
+        match timeout(PROOF_REQUEST_TIMEOUT, self.next_proof(peer)).await {
+            Ok(result) => result.map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn drop_peer(&mut self, peer: N::PeerId) {
+        self.peers.remove(&peer);
+        self.failed_peers.insert(peer);
+    }
+
+    /// Download and verify proofs until one of them claims to be the last.
+    /// Returns whether this succeeded.
+    async fn download_proofs(&mut self) -> Result<bool, N::Error> {
+        while let Some(peer) = self.best_peer() {
+            let request = NetworkData::WarpProofRequest(self.verifier.last_verified());
+            if let Err(e) = self.network.send_to(request, peer.clone()) {
+                warn!(
+                    target: LOG_TARGET,
+                    "Error sending warp sync proof request: {}.", e
+                );
+                self.drop_peer(peer);
+                continue;
+            }
+            let proof = match self.await_proof(&peer).await? {
+                Some(proof) => proof,
+                None => {
+                    debug!(
+                        target: LOG_TARGET,
+                        "Peer {:?} did not send a warp sync proof in time.", peer
+                    );
+                    self.drop_peer(peer);
+                    continue;
+                }
+            };
+            match self.verifier.handle_proof(proof) {
+                Ok(true) => return Ok(true),
+                Ok(false) => debug!(
+                    target: LOG_TARGET,
+                    "Verified warp sync proof up to block {}.",
+                    self.verifier.last_verified()
+                ),
+                Err(e) => {
+                    warn!(
+                        target: LOG_TARGET,
+                        "Invalid warp sync proof from {:?}: {}.", peer, e
+                    );
+                    self.drop_peer(peer);
+                }
+            }
+        }
+        Ok(false)
+    }
+
+    /// Find out how far behind we are and, if far enough, download and verify the proofs.
+    /// Returns whether there might be a target to import.
+    async fn download(&mut self) -> Result<bool, N::Error> {
+        self.discover_peers().await?;
+        let sessions_behind = self.sessions_behind();
+        if sessions_behind < SESSIONS_BEHIND_THRESHOLD {
+            debug!(
+                target: LOG_TARGET,
+                "Only {} sessions behind, skipping warp sync.", sessions_behind
+            );
+            return Ok(false);
+        }
+        info!(
+            target: LOG_TARGET,
+            "{} sessions behind, starting warp sync.", sessions_behind
+        );
+        self.sync_oracle
+            .update_behind(self.best_known() - self.start.number());
+        if !self.download_proofs().await? {
+            warn!(
+                target: LOG_TARGET,
+                "Could not obtain a complete warp sync proof, continuing with the verified part."
+            );
+        }
+        Ok(true)
+    }
+
+    /// Perform the warp sync, returning the network for further use and, if a state was
+    /// imported, the backfill of the skipped history. Warp sync never fails the node, if anything
+    /// goes wrong the regular sync takes over.
+    pub async fn run(mut self) -> (N, Option<Backfill<B>>) {
+        match self.download().await {
+            Ok(true) => (),
+            Ok(false) => return (self.network.into_inner(), None),
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Warp sync failed: {}, falling back to regular sync.", e
+                );
+                return (self.network.into_inner(), None);
+            }
+        }
+        let WarpSync {
+            network,
+            verifier,
+            start,
+            mut target_import,
+            peers,
+            ..
+        } = self;
+        let network = network.into_inner();
+        let (target, checkpoints) = match verifier.into_target() {
+            Some(target) => target,
+            None => return (network, None),
+        };
+        let target_number = target.header().id().number();
+        let peers: HashSet<_> = peers
+            .into_iter()
+            .filter(|(_, number)| *number >= target_number)
+            .map(|(peer, _)| peer)
+            .collect();
+        info!(
+            target: LOG_TARGET,
+            "Importing the state of warp sync target {}.",
+            target.header().id()
+        );
+        match target_import
+            .import_target(target, peers.into_iter().collect())
+            .await
+        {
+            Ok(()) => {
+                info!(target: LOG_TARGET, "Warp sync finished.");
+                (network, Some(Backfill::new(start, checkpoints)))
+            }
+            Err(e) => {
+                warn!(
+                    target: LOG_TARGET,
+                    "Failed to import the warp sync target: {}, falling back to regular sync.", e
+                );
+                (network, None)
+            }
+        }
+    }
+}
@@ -0,0 +1,429 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    num::NonZeroUsize,
+    sync::Arc,
+    time::Instant,
+};
+
+use lru::LruCache;
+use network_clique::{PeerId, PeerStatuses, PublicKey};
+use parking_lot::Mutex;
+use primitives::AccountId;
+use rate_limiter::SharedRateLimiter;
+use serde::{Deserialize, Serialize};
+use set_bft::Round;
+
+use crate::{
+    idx_to_account::ValidatorIndexToAccountIdConverter, sbft::NodeIndex, session::SessionId,
+    NodeCount,
+};
+
+/// How many sessions we keep the reports for.
+const VALIDATOR_HEALTH_CACHE_SIZE: usize = 5;
+/// How many of the most recent scores of a member we remember.
+const SCORE_HISTORY_SIZE: usize = 32;
+
+/// Which side is responsible for establishing the connection with a validator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConnectionDirection {
+    Incoming,
+    Outgoing,
+}
+
+impl From<network_clique::ConnectionDirection> for ConnectionDirection {
+    fn from(direction: network_clique::ConnectionDirection) -> Self {
+        match direction {
+            network_clique::ConnectionDirection::Incoming => ConnectionDirection::Incoming,
+            network_clique::ConnectionDirection::Outgoing => ConnectionDirection::Outgoing,
+        }
+    }
+}
+
+/// State of the validator network connection with a validator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConnectionStatus {
+    pub direction: ConnectionDirection,
+    pub connected: bool,
+}
+
+/// Performance and network health of a single member of the committee in a given session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberHealth {
+    /// Index of the member in the committee.
+    pub node_index: usize,
+    /// Account of the member, if it could be determined.
+    pub account: Option<AccountId>,
+    /// Most recent scores of the member, oldest first. A score is the number of rounds the
+    /// member was behind the head of the ordered batch.
+    pub score_history: Vec<u16>,
+    /// Round of the newest unit of the member that was ordered.
+    pub last_round: Option<Round>,
+    /// State of our connection with the member, `None` if this member is us or we are not aware
+    /// of its address yet.
+    pub connection: Option<ConnectionStatus>,
+    /// How many messages per second we sent to the member since the previous report.
+    pub messages_sent_per_second: f64,
+    /// Bandwidth, in bits per second, the rate limiter currently allows for the connection with
+    /// the member, `None` when not connected.
+    pub bandwidth_share: Option<u64>,
+}
+
+impl MemberHealth {
+    fn new(node_index: usize, account: Option<AccountId>) -> Self {
+        MemberHealth {
+            node_index,
+            account,
+            score_history: Vec::new(),
+            last_round: None,
+            connection: None,
+            messages_sent_per_second: 0.0,
+            bandwidth_share: None,
+        }
+    }
+}
+
+/// Health of all the members of the committee in a given session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionHealth {
+    pub session: SessionId,
+    pub members: Vec<MemberHealth>,
+}
+
+struct SessionEntry {
+    members: Vec<MemberHealth>,
+    scores: Vec<VecDeque<u16>>,
+}
+
+/// Stores the most recent health reports of the members of the committees we took part in.
+#[derive(Clone)]
+pub struct ValidatorHealthCache {
+    data: Arc<Mutex<LruCache<SessionId, SessionEntry>>>,
+}
+
+impl ValidatorHealthCache {
+    pub fn new() -> Self {
+        Self {
+            data: Arc::new(Mutex::new(LruCache::new(
+                NonZeroUsize::try_from(VALIDATOR_HEALTH_CACHE_SIZE)
+                    .expect("the cache size is a non-zero constant"),
+            ))),
+        }
+    }
+
+    fn contains(&self, session: &SessionId) -> bool {
+        self.data.lock().contains(session)
+    }
+
+    fn insert_session(&self, session: SessionId, accounts: Vec<Option<AccountId>>) {
+        let members = accounts
+            .into_iter()
+            .enumerate()
+            .map(|(node_index, account)| MemberHealth::new(node_index, account))
+            .collect::<Vec<_>>();
+        let scores = vec![VecDeque::new(); members.len()];
+        self.data
+            .lock()
+            .put(session, SessionEntry { members, scores });
+    }
+
+    fn update_members<F: FnMut(&mut MemberHealth, &mut VecDeque<u16>)>(
+        &self,
+        session: &SessionId,
+        mut update: F,
+    ) {
+        if let Some(entry) = self.data.lock().get_mut(session) {
+            for (member, scores) in entry.members.iter_mut().zip(entry.scores.iter_mut()) {
+                update(member, scores);
+            }
+        }
+    }
+
+    /// The reports for all the remembered sessions, sorted by session.
+    pub fn snapshot(&self) -> Vec<SessionHealth> {
+        let mut result: Vec<_> = self
+            .data
+            .lock()
+            .iter()
+            .map(|(session, entry)| SessionHealth {
+                session: *session,
+                members: entry
+                    .members
+                    .iter()
+                    .zip(entry.scores.iter())
+                    .map(|(member, scores)| MemberHealth {
+                        score_history: scores.iter().copied().collect(),
+                        ..member.clone()
+                    })
+                    .collect(),
+            })
+            .collect();
+        result.sort_by_key(|report| report.session.0);
+        result
+    }
+}
+
+impl Default for ValidatorHealthCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Network side of a health report for a single member.
+pub struct MemberConnectionReport {
+    pub node_index: NodeIndex,
+    pub connection: Option<ConnectionStatus>,
+    pub messages_sent_per_second: f64,
+    pub bandwidth_share: Option<u64>,
+}
+
+/// Feeds `validator_health_cache` with reports from various components, if it is enabled.
+#[derive(Clone)]
+pub struct ValidatorHealthReporter {
+    inner: Option<(
+        ValidatorHealthCache,
+        Arc<dyn ValidatorIndexToAccountIdConverter + Send + Sync>,
+    )>,
+}
+
+/// Construct a struct that can be used to update `validator_health_cache`, if it is `Some`.
+/// If passed None, the returned struct will be a no-op.
+pub fn validator_health_reporter<C: ValidatorIndexToAccountIdConverter + Send + Sync + 'static>(
+    validator_health_cache: Option<ValidatorHealthCache>,
+    key_owner_info_provider: C,
+) -> ValidatorHealthReporter {
+    ValidatorHealthReporter {
+        inner: validator_health_cache.map(|cache| {
+            (
+                cache,
+                Arc::new(key_owner_info_provider)
+                    as Arc<dyn ValidatorIndexToAccountIdConverter + Send + Sync>,
+            )
+        }),
+    }
+}
+
+impl ValidatorHealthReporter {
+    fn cache_for(
+        &self,
+        session: SessionId,
+        node_count: NodeCount,
+    ) -> Option<&ValidatorHealthCache> {
+        let (cache, key_owner_info_provider) = self.inner.as_ref()?;
+        if !cache.contains(&session) {
+            // Resolving the accounts requires runtime calls, so we do it only once per session and
+            // without holding the lock.
+            let accounts = node_count
+                .into_iterator()
+                .map(|node_index| key_owner_info_provider.account(session, node_index))
+                .collect();
+            cache.insert_session(session, accounts);
+        }
+        Some(cache)
+    }
+
+    /// Record the score computed for the members in the given session, together with the rounds
+    /// of their newest ordered units.
+    pub fn report_scores(&self, session: SessionId, scores: &[u16], last_rounds: &[Option<Round>]) {
+        if let Some(cache) = self.cache_for(session, NodeCount(scores.len())) {
+            cache.update_members(&session, |member, history| {
+                if let Some(score) = scores.get(member.node_index) {
+                    if history.len() == SCORE_HISTORY_SIZE {
+                        history.pop_front();
+                    }
+                    history.push_back(*score);
+                }
+                if let Some(last_round) = last_rounds.get(member.node_index) {
+                    member.last_round = *last_round;
+                }
+            });
+        }
+    }
+
+    /// Record the state of the network connections with the members in the given session.
+    /// Members not present in `reports` are treated as not having any connection.
+    pub fn report_connections(
+        &self,
+        session: SessionId,
+        node_count: NodeCount,
+        reports: Vec<MemberConnectionReport>,
+    ) {
+        let mut reports: HashMap<_, _> = reports
+            .into_iter()
+            .map(|report| (report.node_index.0, report))
+            .collect();
+        if let Some(cache) = self.cache_for(session, node_count) {
+            cache.update_members(&session, |member, _| {
+                let report = reports.remove(&member.node_index);
+                member.connection = report.as_ref().and_then(|report| report.connection);
+                member.messages_sent_per_second = report
+                    .as_ref()
+                    .map(|report| report.messages_sent_per_second)
+                    .unwrap_or(0.0);
+                member.bandwidth_share = report.and_then(|report| report.bandwidth_share);
+            });
+        }
+    }
+}
+
+/// Gathers the network part of the health reports: connection statuses from the validator
+/// network, the number of messages we send and the bandwidth allowed by the rate limiter.
+pub struct ConnectionHealthMonitor<PK: PublicKey + PeerId> {
+    reporter: ValidatorHealthReporter,
+    peer_statuses: PeerStatuses<PK>,
+    rate_limiter: SharedRateLimiter,
+    messages_sent: HashMap<PK, u64>,
+    last_report: Instant,
+}
+
+impl<PK: PublicKey + PeerId> ConnectionHealthMonitor<PK> {
+    pub fn new(
+        reporter: ValidatorHealthReporter,
+        peer_statuses: PeerStatuses<PK>,
+        rate_limiter: SharedRateLimiter,
+    ) -> Self {
+        ConnectionHealthMonitor {
+            reporter,
+            peer_statuses,
+            rate_limiter,
+            messages_sent: HashMap::new(),
+            last_report: Instant::now(),
+        }
+    }
+
+    /// Note that a message was sent to the given peer.
+    pub fn on_message_sent(&mut self, peer: &PK) {
+        *self.messages_sent.entry(peer.clone()).or_default() += 1;
+    }
+
+    /// Report the state of the connections with the members of the given validator sessions and
+    /// start counting messages anew.
+    pub fn report<I: IntoIterator<Item = (SessionId, NodeCount, HashMap<NodeIndex, PK>)>>(
+        &mut self,
+        sessions: I,
+    ) {
+        let elapsed = self.last_report.elapsed().as_secs_f64().max(1.0);
+        let bandwidth_share = u64::from(self.rate_limiter.bandwidth_share());
+        for (session, node_count, peers) in sessions {
+            let reports = peers
+                .into_iter()
+                .map(|(node_index, peer)| {
+                    let connection = self
+                        .peer_statuses
+                        .get(&peer)
+                        .map(|status| ConnectionStatus {
+                            direction: status.direction.into(),
+                            connected: status.connected,
+                        });
+                    let sent = self.messages_sent.get(&peer).copied().unwrap_or(0);
+                    MemberConnectionReport {
+                        node_index,
+                        bandwidth_share: connection
+                            .filter(|connection| connection.connected)
+                            .map(|_| bandwidth_share),
+                        connection,
+                        messages_sent_per_second: sent as f64 / elapsed,
+                    }
+                })
+                .collect();
+            self.reporter
+                .report_connections(session, node_count, reports);
+        }
+        self.messages_sent.clear();
+        self.last_report = Instant::now();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        validator_health_reporter, ConnectionDirection, ConnectionStatus, MemberConnectionReport,
+        ValidatorHealthCache, SCORE_HISTORY_SIZE,
+    };
+    use crate::{idx_to_account::MockConverter, NodeCount, NodeIndex, SessionId};
+
+    #[test]
+    fn remembers_limited_score_history() {
+        let cache = ValidatorHealthCache::new();
+        let reporter = validator_health_reporter(Some(cache.clone()), MockConverter);
+        for round in 0..(SCORE_HISTORY_SIZE as u16 + 3) {
+            reporter.report_scores(SessionId(1), &[round, 0], &[Some(round), None]);
+        }
+        let report = cache.snapshot();
+        assert_eq!(report.len(), 1);
+        let members = &report[0].members;
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].score_history.len(), SCORE_HISTORY_SIZE);
+        assert_eq!(members[0].score_history.first(), Some(&3));
+        assert_eq!(
+            members[0].score_history.last(),
+            Some(&(SCORE_HISTORY_SIZE as u16 + 2))
+        );
+        assert_eq!(members[0].last_round, Some(SCORE_HISTORY_SIZE as u16 + 2));
+        assert_eq!(members[1].last_round, None);
+    }
+
+    #[test]
+    fn connection_reports_replace_previous_ones() {
+        let cache = ValidatorHealthCache::new();
+        let reporter = validator_health_reporter(Some(cache.clone()), MockConverter);
+        let connection = ConnectionStatus {
+            direction: ConnectionDirection::Outgoing,
+            connected: true,
+        };
+        reporter.report_connections(
+            SessionId(0),
+            NodeCount(3),
+            vec![MemberConnectionReport {
+                node_index: NodeIndex(1),
+                connection: Some(connection),
+                messages_sent_per_second: 2.5,
+                bandwidth_share: Some(1000),
+            }],
+        );
+        let members = cache.snapshot().remove(0).members;
+        assert_eq!(members[1].connection, Some(connection));
+        assert_eq!(members[1].bandwidth_share, Some(1000));
+        assert_eq!(members[2].connection, None);
+
+        reporter.report_connections(SessionId(0), NodeCount(3), Vec::new());
+        let members = cache.snapshot().remove(0).members;
+        assert_eq!(members[1].connection, None);
+        assert_eq!(members[1].messages_sent_per_second, 0.0);
+        assert_eq!(members[1].bandwidth_share, None);
+    }
+
+    #[test]
+    fn forgets_old_sessions() {
+        let cache = ValidatorHealthCache::new();
+        let reporter = validator_health_reporter(Some(cache.clone()), MockConverter);
+        for session in 0..10 {
+            reporter.report_scores(SessionId(session), &[0], &[None]);
+        }
+        let sessions: Vec<_> = cache
+            .snapshot()
+            .into_iter()
+            .map(|report| report.session.0)
+            .collect();
+        assert_eq!(sessions, vec![5, 6, 7, 8, 9]);
+    }
+}
@@ -63,6 +63,14 @@ impl RateLimiterFacade {
 		}
 	}
 
+	/// Part of the bandwidth that is currently allocated to each of the active connections.
+	pub fn bandwidth_share(&self) -> RatePerSecond {
+		match self {
+			RateLimiterFacade::NoTraffic => RatePerSecond::Block,
+			RateLimiterFacade::RateLimiter(shared_token_bucket) => shared_token_bucket.bandwidth_share().into(),
+		}
+	}
+
 	pub fn share(&self) -> Self {
 		match self {
 			RateLimiterFacade::NoTraffic => RateLimiterFacade::NoTraffic,
@@ -222,6 +222,14 @@ impl SharedBandwidthManager {
 		NonZeroU64::try_from(rate).map(NonZeroRatePerSecond::from).unwrap_or(MIN)
 	}
 
+	/// Part of the shared bandwidth that is currently allocated to each of the active consumers. If nobody is active, this is
+	/// the whole bandwidth.
+	pub fn bandwidth_share(&self) -> NonZeroRatePerSecond {
+		let active_children = self.peers_count.load(Ordering::SeqCst).max(1);
+		let rate = u64::from(self.max_rate) / active_children;
+		NonZeroU64::try_from(rate).map(NonZeroRatePerSecond::from).unwrap_or(MIN)
+	}
+
 	/// Allocate part of the shared bandwidth.
 	pub fn request_bandwidth(&mut self) -> NonZeroRatePerSecond {
 		let active_children =
@@ -349,6 +357,11 @@ impl<TP, SU> SharedTokenBucket<TP, SU> {
 		}
 	}
 
+	/// Part of the shared bandwidth that is currently allocated to each of the active instances.
+	pub fn bandwidth_share(&self) -> NonZeroRatePerSecond {
+		self.shared_bandwidth.bandwidth_share()
+	}
+
 	fn request_bandwidth(&mut self) -> NonZeroRatePerSecond {
 		self.need_to_notify_parent = true;
 		self.shared_bandwidth.request_bandwidth()
@@ -873,6 +886,21 @@ mod tests {
 		assert_eq!(deadline, None);
 	}
 
+	#[test]
+	fn bandwidth_share_is_split_between_active_peers() {
+		let limit_per_second = 10.try_into().expect("10 > 0 qed");
+		let mut first_peer = SharedBandwidthManager::new(limit_per_second);
+		let mut second_peer = first_peer.share();
+
+		assert_eq!(first_peer.bandwidth_share(), limit_per_second);
+		first_peer.request_bandwidth();
+		assert_eq!(second_peer.bandwidth_share(), limit_per_second);
+		second_peer.request_bandwidth();
+		assert_eq!(first_peer.bandwidth_share(), 5.try_into().expect("5 > 0 qed"));
+		first_peer.notify_idle();
+		assert_eq!(second_peer.bandwidth_share(), limit_per_second);
+	}
+
 	#[tokio::test]
 	async fn peers_receive_at_least_one_token_per_second() {
 		let limit_per_second = 1.try_into().expect("1 > 0 qed");
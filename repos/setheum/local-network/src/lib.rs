@@ -0,0 +1,370 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Spawns a network of `setheum-node` validators on localhost, bootstrapped with `chain-bootstrapper`
+//! the same way as `scripts/run_nodes.sh`. Validators can be killed, restarted and partitioned from
+//! each other while the network runs, which lets `e2e-tests` run hermetically on a single Linux box.
+
+mod node;
+mod proxy;
+mod supervisor;
+
+use std::{
+	collections::BTreeSet,
+	env, fs,
+	net::{TcpListener, TcpStream},
+	path::{Path, PathBuf},
+	process::Command,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc, Mutex,
+	},
+	time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context};
+use log::info;
+use node::{localhost, Node, Ports};
+use proxy::{Proxies, Topology};
+use setheum_client::{
+	keypair_from_string,
+	waiting::{BlockStatus, SetheumWaiting},
+	BlockNumber, Connection, KeyPair, RootConnection, SignedConnection,
+};
+use supervisor::Supervisor;
+
+const LOG_TARGET: &str = "setheum-local-network";
+const SUDO_SEED: &str = "//Alice";
+const RPC_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Configuration of a [`LocalNetwork`].
+#[derive(Clone, Debug)]
+pub struct Config {
+	/// Path to the `setheum-node` binary.
+	pub node_binary: PathBuf,
+
+	/// Path to the `chain-bootstrapper` binary.
+	pub bootstrapper_binary: PathBuf,
+
+	/// Number of validators, with seeds `//1`, `//2`, ... like the validators of `run_nodes.sh`.
+	pub validator_count: usize,
+
+	/// Directory for the chainspec, keystores, databases and logs.
+	/// Optional: by default a fresh directory in the system temporary directory, removed on drop.
+	pub base_path: Option<PathBuf>,
+
+	/// Finality version at chain inception, `legacy` or `current`.
+	pub finality_version: String,
+
+	/// Named DeFi genesis passed to `chain-bootstrapper`.
+	pub genesis_preset: Option<String>,
+}
+
+impl Config {
+	/// Configuration of `validator_count` validators, using binaries from `SETHEUM_NODE` and
+	/// `CHAIN_BOOTSTRAPPER` or, by default, from the release target directory of the workspace.
+	pub fn new(validator_count: usize) -> Self {
+		let release_binary =
+			|name: &str| Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../target/release").join(name);
+		Self {
+			node_binary: env::var_os("SETHEUM_NODE").map_or_else(|| release_binary("setheum-node"), PathBuf::from),
+			bootstrapper_binary: env::var_os("CHAIN_BOOTSTRAPPER")
+				.map_or_else(|| release_binary("chain-bootstrapper"), PathBuf::from),
+			validator_count,
+			base_path: None,
+			finality_version: "legacy".to_string(),
+			genesis_preset: None,
+		}
+	}
+}
+
+/// A running network of validators, stopped when dropped.
+pub struct LocalNetwork {
+	config: Config,
+	base_path: PathBuf,
+	remove_base_path: bool,
+	chainspec: PathBuf,
+	bootnode: String,
+	nodes: Mutex<Vec<Node>>,
+	proxies: Arc<Proxies>,
+	supervisor: Supervisor,
+}
+
+impl LocalNetwork {
+	/// Bootstraps the chain, starts all validators and waits until they finalize the first block.
+	pub async fn start(config: Config) -> anyhow::Result<Self> {
+		static NETWORK_ID: AtomicUsize = AtomicUsize::new(0);
+
+		if config.validator_count == 0 {
+			bail!("A local network needs at least one validator");
+		}
+		let remove_base_path = config.base_path.is_none();
+		let base_path = config.base_path.clone().unwrap_or_else(|| {
+			let id = NETWORK_ID.fetch_add(1, Ordering::Relaxed);
+			env::temp_dir().join(format!("setheum-local-network-{}-{id}", std::process::id()))
+		});
+		fs::create_dir_all(&base_path).with_context(|| format!("Cannot create {}", base_path.display()))?;
+
+		let mut nodes = Vec::with_capacity(config.validator_count);
+		let mut proxy_listeners = Vec::with_capacity(config.validator_count);
+		for index in 0..config.validator_count {
+			let seed = format!("//{}", index + 1);
+			let proxy = TcpListener::bind(localhost(0))?;
+			nodes.push(Node {
+				account_id: keypair_from_string(&seed).account_id().to_string(),
+				seed,
+				ports: Ports::free()?,
+				proxy_port: proxy.local_addr()?.port(),
+				process: None,
+			});
+			proxy_listeners.push(proxy);
+		}
+
+		let chainspec = base_path.join("chainspec.json");
+		bootstrap_chain(&config, &base_path, &chainspec, &nodes)?;
+		let bootnode = format!(
+			"/ip4/127.0.0.1/tcp/{}/p2p/{}",
+			nodes[0].ports.p2p,
+			peer_id(&config.node_binary, &nodes[0].base_path(&base_path).join("p2p_secret"))?
+		);
+
+		let proxies = Arc::new(Proxies::new(config.validator_count));
+		let supervisor = Supervisor::new()?;
+		for (index, (listener, node)) in proxy_listeners.into_iter().zip(&nodes).enumerate() {
+			supervisor.run(proxy::run(listener, index, node.validator_address(), proxies.clone()))?;
+		}
+
+		let network = Self {
+			config,
+			base_path,
+			remove_base_path,
+			chainspec,
+			bootnode,
+			nodes: Mutex::new(nodes),
+			proxies,
+			supervisor,
+		};
+		for index in 0..network.validator_count() {
+			network.spawn(index).await?;
+		}
+		info!(target: LOG_TARGET, "Started {} validators in {}", network.validator_count(), network.base_path.display());
+		network.wait_for_finality(1, RPC_TIMEOUT * 2).await?;
+		Ok(network)
+	}
+
+	/// Number of validators in the network, running or not.
+	pub fn validator_count(&self) -> usize {
+		self.config.validator_count
+	}
+
+	/// Directory holding the chainspec and the databases, keystores and logs of the validators.
+	pub fn base_path(&self) -> &Path {
+		&self.base_path
+	}
+
+	/// Websocket RPC endpoint of the validator.
+	pub fn ws_url(&self, validator: usize) -> String {
+		self.nodes()[validator].ws_url()
+	}
+
+	/// Seed of the validator's account, e.g. `//1` for validator `0`.
+	pub fn seed(&self, validator: usize) -> String {
+		self.nodes()[validator].seed.clone()
+	}
+
+	/// Key pair of the validator's account.
+	pub fn keypair(&self, validator: usize) -> KeyPair {
+		keypair_from_string(&self.seed(validator))
+	}
+
+	/// Whether the validator process runs, i.e. it was not killed or has been restarted since.
+	pub fn is_running(&self, validator: usize) -> bool {
+		self.nodes()[validator].process.is_some()
+	}
+
+	/// Unsigned connection to the validator.
+	pub async fn connection(&self, validator: usize) -> Connection {
+		Connection::new(&self.ws_url(validator)).await
+	}
+
+	/// Connection to the validator, signed by the validator's own account.
+	pub async fn signed_connection(&self, validator: usize) -> SignedConnection {
+		SignedConnection::new(&self.ws_url(validator), self.keypair(validator)).await
+	}
+
+	/// Connection to the validator, signed by the sudo account `//Alice`.
+	pub async fn root_connection(&self, validator: usize) -> anyhow::Result<RootConnection> {
+		RootConnection::new(&self.ws_url(validator), keypair_from_string(SUDO_SEED)).await
+	}
+
+	/// Waits until every running validator has finalized `block`.
+	pub async fn wait_for_finality(&self, block: BlockNumber, timeout: Duration) -> anyhow::Result<()> {
+		let running = (0..self.validator_count()).filter(|validator| self.is_running(*validator));
+		for validator in running.collect::<Vec<_>>() {
+			let connection = self.connection(validator).await;
+			tokio::time::timeout(timeout, connection.wait_for_block(|n| n >= block, BlockStatus::Finalized))
+				.await
+				.map_err(|_| anyhow!("Validator {validator} did not finalize block {block} within {timeout:?}"))?;
+		}
+		Ok(())
+	}
+
+	/// Kills the validator process, keeping its database and keystore.
+	pub fn kill(&self, validator: usize) -> anyhow::Result<()> {
+		let process = self.nodes()[validator].process.take();
+		if let Some(mut process) = process {
+			process.kill()?;
+			process.wait()?;
+			info!(target: LOG_TARGET, "Killed validator {validator}");
+		}
+		self.proxies.set_pid(validator, None);
+		Ok(())
+	}
+
+	/// Restarts the validator on its existing database and waits until its RPC is available.
+	pub async fn restart(&self, validator: usize) -> anyhow::Result<()> {
+		self.kill(validator)?;
+		self.spawn(validator).await?;
+		info!(target: LOG_TARGET, "Restarted validator {validator}");
+		Ok(())
+	}
+
+	/// Splits the validators into `groups`, cutting the SetBFT connections between different
+	/// groups. Every validator has to be in exactly one group.
+	///
+	/// Blocks are still synced over p2p, so cut off validators keep importing blocks but take no
+	/// part in finalizing them. Dialers are identified through `/proc`, so this fails on other
+	/// platforms than Linux.
+	pub fn partition(&self, groups: &[Vec<usize>]) -> anyhow::Result<()> {
+		if !cfg!(target_os = "linux") {
+			bail!("Partitioning validators is only supported on Linux");
+		}
+		let mut assignment = vec![None; self.validator_count()];
+		for (group, validators) in groups.iter().enumerate() {
+			for validator in validators {
+				let slot = assignment.get_mut(*validator).ok_or(anyhow!("There is no validator {validator}"))?;
+				if slot.replace(group).is_some() {
+					bail!("Validator {validator} is in more than one group");
+				}
+			}
+		}
+		let assignment = assignment
+			.into_iter()
+			.enumerate()
+			.map(|(validator, group)| group.ok_or(anyhow!("Validator {validator} is in no group")))
+			.collect::<anyhow::Result<Vec<_>>>()?;
+		info!(target: LOG_TARGET, "Partitioning validators into {groups:?}");
+		self.proxies.set_topology(Topology::partitioned(assignment));
+		Ok(())
+	}
+
+	/// Reconnects all validators after [`LocalNetwork::partition`].
+	pub fn heal(&self) {
+		info!(target: LOG_TARGET, "Healing the partition");
+		self.proxies.set_topology(Topology::default());
+	}
+
+	/// Kills all validators and removes the base path, unless it was given in the [`Config`].
+	///
+	/// This is what dropping the network does, but a network kept in a `static` is never dropped
+	/// and has to be stopped explicitly.
+	pub fn stop(&self) {
+		for validator in 0..self.validator_count() {
+			let _ = self.kill(validator);
+		}
+		if self.remove_base_path {
+			let _ = fs::remove_dir_all(&self.base_path);
+		}
+	}
+
+	fn nodes(&self) -> std::sync::MutexGuard<'_, Vec<Node>> {
+		self.nodes.lock().expect("lock is not poisoned")
+	}
+
+	async fn spawn(&self, validator: usize) -> anyhow::Result<()> {
+		let rpc_port = {
+			let mut nodes = self.nodes();
+			let node = &mut nodes[validator];
+			let command =
+				node.command(validator, &self.config.node_binary, &self.base_path, &self.chainspec, &self.bootnode)?;
+			let process = self
+				.supervisor
+				.spawn(command)
+				.with_context(|| format!("Cannot run {}", self.config.node_binary.display()))?;
+			self.proxies.set_pid(validator, Some(process.id()));
+			node.process = Some(process);
+			node.ports.rpc
+		};
+		wait_for_port(rpc_port, RPC_TIMEOUT)
+			.await
+			.with_context(|| format!("RPC of validator {validator} is not available"))
+	}
+}
+
+impl Drop for LocalNetwork {
+	fn drop(&mut self) {
+		self.stop();
+	}
+}
+
+fn bootstrap_chain(config: &Config, base_path: &Path, chainspec: &Path, nodes: &[Node]) -> anyhow::Result<()> {
+	let account_ids = nodes.iter().map(|node| node.account_id.as_str()).collect::<BTreeSet<_>>();
+	let account_ids = account_ids.into_iter().collect::<Vec<_>>().join(",");
+	let mut command = Command::new(&config.bootstrapper_binary);
+	command
+		.args(["bootstrap-chain", "--raw", "--chain-type", "local"])
+		.arg("--base-path")
+		.arg(base_path)
+		.args(["--account-ids", &account_ids])
+		.args(["--authorities-account-ids", &account_ids])
+		.args(["--rich-account-ids", &account_ids])
+		.args(["--finality-version", &config.finality_version]);
+	if let Some(preset) = &config.genesis_preset {
+		command.args(["--genesis-preset", preset]);
+	}
+	let output = command.output().with_context(|| format!("Cannot run {}", config.bootstrapper_binary.display()))?;
+	if !output.status.success() {
+		bail!("chain-bootstrapper failed: {}", String::from_utf8_lossy(&output.stderr));
+	}
+	fs::write(chainspec, output.stdout).with_context(|| format!("Cannot write {}", chainspec.display()))
+}
+
+fn peer_id(node_binary: &Path, node_key_file: &Path) -> anyhow::Result<String> {
+	let output = Command::new(node_binary)
+		.args(["key", "inspect-node-key", "--file"])
+		.arg(node_key_file)
+		.output()
+		.with_context(|| format!("Cannot run {}", node_binary.display()))?;
+	if !output.status.success() {
+		bail!("Cannot inspect node key: {}", String::from_utf8_lossy(&output.stderr));
+	}
+	Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+async fn wait_for_port(port: u16, timeout: Duration) -> anyhow::Result<()> {
+	let deadline = Instant::now() + timeout;
+	while TcpStream::connect(localhost(port)).is_err() {
+		if Instant::now() > deadline {
+			bail!("Nothing is listening on port {port} after {timeout:?}");
+		}
+		tokio::time::sleep(Duration::from_millis(200)).await;
+	}
+	Ok(())
+}
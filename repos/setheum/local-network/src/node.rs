@@ -0,0 +1,115 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	fs::File,
+	io,
+	net::{Ipv4Addr, SocketAddr, TcpListener},
+	path::{Path, PathBuf},
+	process::{Child, Command, Stdio},
+};
+
+/// Localhost ports of a single validator.
+#[derive(Clone, Copy, Debug)]
+pub struct Ports {
+	pub p2p: u16,
+	pub rpc: u16,
+	pub validator: u16,
+	pub prometheus: u16,
+}
+
+impl Ports {
+	/// Picks ports that are currently free, so that several networks can run side by side.
+	pub fn free() -> io::Result<Self> {
+		let listeners = (0..4).map(|_| TcpListener::bind(localhost(0))).collect::<io::Result<Vec<_>>>()?;
+		let ports = listeners.iter().map(|listener| listener.local_addr().map(|addr| addr.port()));
+		let ports = ports.collect::<io::Result<Vec<_>>>()?;
+		Ok(Self { p2p: ports[0], rpc: ports[1], validator: ports[2], prometheus: ports[3] })
+	}
+}
+
+/// A validator of the local network, together with its process while it is running.
+pub struct Node {
+	pub seed: String,
+	pub account_id: String,
+	pub ports: Ports,
+	/// Port of the proxy in front of the validator network, published as the validator address.
+	pub proxy_port: u16,
+	pub process: Option<Child>,
+}
+
+impl Node {
+	pub fn base_path(&self, network_base_path: &Path) -> PathBuf {
+		network_base_path.join(&self.account_id)
+	}
+
+	pub fn ws_url(&self) -> String {
+		format!("ws://127.0.0.1:{}", self.ports.rpc)
+	}
+
+	/// Command running this validator, with the same flags as `scripts/run_nodes.sh`.
+	pub fn command(
+		&self,
+		index: usize,
+		binary: &Path,
+		network_base_path: &Path,
+		chainspec: &Path,
+		bootnode: &str,
+	) -> io::Result<Command> {
+		let base_path = self.base_path(network_base_path);
+		let log = File::options()
+			.create(true)
+			.append(true)
+			.open(network_base_path.join(format!("node-{index}.log")))?;
+
+		let mut command = Command::new(binary);
+		command
+			.arg("--validator")
+			.args(["--public-validator-addresses", &format!("127.0.0.1:{}", self.proxy_port)])
+			.args(["--validator-port", &self.ports.validator.to_string()])
+			.arg("--chain")
+			.arg(chainspec)
+			.args(["--bootnodes", bootnode])
+			.arg("--base-path")
+			.arg(&base_path)
+			.args(["--name", &format!("node-{index}")])
+			.args(["--rpc-port", &self.ports.rpc.to_string()])
+			.args(["--port", &self.ports.p2p.to_string()])
+			.args(["--prometheus-port", &self.ports.prometheus.to_string()])
+			.arg("--node-key-file")
+			.arg(base_path.join("p2p_secret"))
+			.arg("--backup-path")
+			.arg(base_path.join("backup-stash"))
+			.args(["--rpc-cors=all", "--no-mdns", "--no-telemetry"])
+			.args(["--pool-limit", "1024"])
+			.arg("-lsetheum-party=debug,setheum-network=debug,setheum-finality=debug")
+			.stdout(Stdio::null())
+			.stderr(log);
+		Ok(command)
+	}
+
+	pub fn validator_address(&self) -> SocketAddr {
+		localhost(self.ports.validator)
+	}
+}
+
+pub fn localhost(port: u16) -> SocketAddr {
+	(Ipv4Addr::LOCALHOST, port).into()
+}
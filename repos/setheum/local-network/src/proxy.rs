@@ -0,0 +1,153 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	fs,
+	net::{SocketAddr, TcpListener as StdTcpListener},
+	sync::{Arc, Mutex},
+};
+
+use log::{debug, warn};
+use tokio::{
+	io::copy_bidirectional,
+	net::{TcpListener, TcpStream},
+	sync::watch,
+};
+
+const LOG_TARGET: &str = "setheum-local-network";
+
+/// Partition of the validators, `None` when every validator can reach every other one.
+#[derive(Clone, Debug, Default)]
+pub struct Topology {
+	groups: Option<Vec<usize>>,
+}
+
+impl Topology {
+	/// `groups[i]` is the partition validator `i` belongs to.
+	pub fn partitioned(groups: Vec<usize>) -> Self {
+		Self { groups: Some(groups) }
+	}
+
+	/// Whether `dialer` may reach `validator`. A dialer that could not be identified is only let
+	/// through while the network is not partitioned.
+	fn connected(&self, dialer: Option<usize>, validator: usize) -> bool {
+		match (&self.groups, dialer) {
+			(Some(groups), Some(dialer)) => groups.get(dialer) == groups.get(validator),
+			(Some(_), None) => false,
+			(None, _) => true,
+		}
+	}
+}
+
+/// State shared by the proxies of all validators of a network.
+pub struct Proxies {
+	topology: watch::Sender<Topology>,
+	pids: Mutex<Vec<Option<u32>>>,
+}
+
+impl Proxies {
+	pub fn new(validator_count: usize) -> Self {
+		Self { topology: watch::channel(Topology::default()).0, pids: Mutex::new(vec![None; validator_count]) }
+	}
+
+	pub fn set_topology(&self, topology: Topology) {
+		self.topology.send_replace(topology);
+	}
+
+	pub fn set_pid(&self, validator: usize, pid: Option<u32>) {
+		self.pids.lock().expect("lock is not poisoned")[validator] = pid;
+	}
+
+	/// Finds the validator owning the local end of a loopback connection from `port`.
+	fn dialer(&self, port: u16) -> Option<usize> {
+		let socket = format!("socket:[{}]", socket_inode(port)?);
+		let pids = self.pids.lock().expect("lock is not poisoned").clone();
+		pids.iter().position(|pid| pid.map_or(false, |pid| owns_socket(pid, &socket)))
+	}
+}
+
+/// Forwards the validator network connections of `validator` from `listener` to `target`, dropping
+/// them while the dialing validator is in another partition.
+///
+/// Only the SetBFT validator network goes through the proxy, so a partitioned validator keeps
+/// importing blocks over p2p but cannot take part in finalizing them. The dialer is looked up in
+/// `/proc` once the connection is accepted; when that fails, e.g. because it already closed the
+/// socket, the connection is refused while partitioned and the dialer has to retry.
+pub async fn run(listener: StdTcpListener, validator: usize, target: SocketAddr, proxies: Arc<Proxies>) {
+	let listener = match listener.set_nonblocking(true).and_then(|_| TcpListener::from_std(listener)) {
+		Ok(listener) => listener,
+		Err(e) => return warn!(target: LOG_TARGET, "Proxy of validator {validator} failed to start: {e}"),
+	};
+	loop {
+		match listener.accept().await {
+			Ok((inbound, peer)) => {
+				tokio::spawn(forward(inbound, peer, validator, target, proxies.clone()));
+			},
+			Err(e) => warn!(target: LOG_TARGET, "Proxy of validator {validator} failed to accept: {e}"),
+		}
+	}
+}
+
+async fn forward(
+	mut inbound: TcpStream,
+	peer: SocketAddr,
+	validator: usize,
+	target: SocketAddr,
+	proxies: Arc<Proxies>,
+) {
+	let dialer = proxies.dialer(peer.port());
+	let mut topology = proxies.topology.subscribe();
+	let connected = |topology: &Topology| topology.connected(dialer, validator);
+	if !connected(&topology.borrow_and_update()) {
+		return debug!(target: LOG_TARGET, "Refused connection from {dialer:?} to {validator}");
+	}
+	let mut outbound = match TcpStream::connect(target).await {
+		Ok(outbound) => outbound,
+		Err(e) => return debug!(target: LOG_TARGET, "Validator {validator} is unreachable: {e}"),
+	};
+	tokio::select! {
+		_ = copy_bidirectional(&mut inbound, &mut outbound) => {},
+		_ = topology.wait_for(|topology| !connected(topology)) => {
+			debug!(target: LOG_TARGET, "Cut connection from {dialer:?} to {validator}");
+		},
+	}
+}
+
+fn socket_inode(port: u16) -> Option<u64> {
+	["/proc/net/tcp", "/proc/net/tcp6"]
+		.into_iter()
+		.filter_map(|path| fs::read_to_string(path).ok())
+		.find_map(|table| {
+			table.lines().skip(1).find_map(|line| {
+				let fields: Vec<_> = line.split_whitespace().collect();
+				let local_port = u16::from_str_radix(fields.get(1)?.rsplit(':').next()?, 16).ok()?;
+				match local_port == port {
+					true => fields.get(9)?.parse().ok(),
+					false => None,
+				}
+			})
+		})
+}
+
+fn owns_socket(pid: u32, socket: &str) -> bool {
+	fs::read_dir(format!("/proc/{pid}/fd"))
+		.map(|fds| fds.filter_map(Result::ok).any(|fd| fs::read_link(fd.path()).map_or(false, |link| link == *socket)))
+		.unwrap_or(false)
+}
@@ -0,0 +1,108 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+	future::Future,
+	io,
+	os::unix::process::CommandExt,
+	pin::Pin,
+	process::{Child, Command},
+	sync::mpsc as std_mpsc,
+	thread::{self, JoinHandle},
+};
+
+use tokio::sync::mpsc;
+
+type Task = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+enum Request {
+	Spawn(Command, std_mpsc::Sender<io::Result<Child>>),
+	Task(Task),
+}
+
+/// Thread owning the node processes and the proxy tasks of a network.
+///
+/// Processes are started with `PR_SET_PDEATHSIG`, which fires when the thread that forked them
+/// exits. Forking from a dedicated thread ties their lifetime to the network rather than to
+/// whichever test runtime happened to start it, and still reaps them if the test binary is killed.
+pub struct Supervisor {
+	requests: Option<mpsc::UnboundedSender<Request>>,
+	thread: Option<JoinHandle<()>>,
+}
+
+impl Supervisor {
+	pub fn new() -> io::Result<Self> {
+		let (requests, mut receiver) = mpsc::unbounded_channel();
+		let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+		let thread = thread::Builder::new().name("local-network-supervisor".into()).spawn(move || {
+			runtime.block_on(async move {
+				while let Some(request) = receiver.recv().await {
+					match request {
+						Request::Spawn(mut command, reply) => {
+							let _ = reply.send(command.spawn());
+						},
+						Request::Task(task) => {
+							tokio::spawn(task);
+						},
+					}
+				}
+			})
+		})?;
+		Ok(Self { requests: Some(requests), thread: Some(thread) })
+	}
+
+	/// Starts `command` as a child of the supervisor thread.
+	pub fn spawn(&self, mut command: Command) -> io::Result<Child> {
+		// SAFETY: `prctl` is async-signal-safe and does not touch the parent's memory.
+		unsafe {
+			command.pre_exec(|| {
+				if libc::prctl(libc::PR_SET_PDEATHSIG, libc::SIGKILL) != 0 {
+					return Err(io::Error::last_os_error());
+				}
+				Ok(())
+			});
+		}
+		let (reply, response) = std_mpsc::channel();
+		self.send(Request::Spawn(command, reply))?;
+		response.recv().map_err(|_| stopped())?
+	}
+
+	/// Runs `task` on the supervisor thread until the supervisor is dropped.
+	pub fn run<F: Future<Output = ()> + Send + 'static>(&self, task: F) -> io::Result<()> {
+		self.send(Request::Task(Box::pin(task)))
+	}
+
+	fn send(&self, request: Request) -> io::Result<()> {
+		self.requests.as_ref().ok_or_else(stopped)?.send(request).map_err(|_| stopped())
+	}
+}
+
+impl Drop for Supervisor {
+	fn drop(&mut self) {
+		self.requests.take();
+		if let Some(thread) = self.thread.take() {
+			let _ = thread.join();
+		}
+	}
+}
+
+fn stopped() -> io::Error {
+	io::Error::new(io::ErrorKind::BrokenPipe, "local network supervisor has stopped")
+}
@@ -105,6 +105,9 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the storage of contracts and the pallets they depend on into a JSON file.
+	ExportContractsState(crate::ExportContractsStateCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -114,6 +117,9 @@ pub enum Subcommand {
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
 
+	/// Replay a runtime upgrade against a state snapshot and report its effects.
+	UpgradeDryRun(crate::UpgradeDryRunCmd),
+
 	/// The custom benchmark subcommand benchmarking runtime pallets.
 	#[cfg(feature = "runtime-benchmarks")]
 	#[clap(subcommand)]
@@ -179,6 +185,13 @@ pub struct SetheumCli {
 	/// By default collecting is enabled, as the impact on performance is negligible, if any.
 	#[clap(long, default_value_t = true)]
 	collect_validator_network_data: bool,
+
+	/// Catch up with the chain using warp sync when starting without any finalized blocks.
+	///
+	/// Only the justifications of the last blocks of sessions are verified, the state of the newest
+	/// one is downloaded, and the skipped history is filled in afterwards.
+	#[clap(long, default_value_t = false)]
+	setbft_warp_sync: bool,
 }
 
 impl SetheumCli {
@@ -228,4 +241,8 @@ impl SetheumCli {
 	pub fn collect_validator_network_data(&self) -> bool {
 		self.collect_validator_network_data
 	}
+
+	pub fn warp_sync(&self) -> bool {
+		self.setbft_warp_sync
+	}
 }
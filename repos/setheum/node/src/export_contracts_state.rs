@@ -0,0 +1,153 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+//! `export-contracts-state` dumps the raw storage of the pallets contracts depend on, together
+//! with the storage of all contracts, into a JSON file. `drink` seeds its sandbox from such files,
+//! so production bugs can be reproduced offline. The export names the pallet running contracts,
+//! so that tools only interpret the storage of contracts running on the same pallet.
+
+use std::{collections::BTreeMap, fmt::Debug, fs, path::PathBuf, str::FromStr, sync::Arc};
+
+use frame_support::traits::PalletInfoAccess;
+use sc_cli::{clap, BlockNumberOrHash, CliConfiguration, DatabaseParams, SharedParams};
+use sc_client_api::{StorageProvider, UsageProvider};
+use serde_json::{json, Value};
+use setheum_runtime::Contracts;
+use sp_blockchain::HeaderBackend;
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, ChildInfo, StorageKey},
+	twox_128,
+};
+use sp_runtime::{
+	generic::BlockId,
+	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto},
+};
+
+/// Crate of the pallet running contracts on the chain. Tools loading the export, like drink, check
+/// it before interpreting the storage of contracts.
+const CONTRACTS_PALLET_CRATE: &str = "pallet_contracts";
+
+fn default_pallets() -> Vec<String> {
+	["System", "Balances", <Contracts as PalletInfoAccess>::name()].map(String::from).to_vec()
+}
+
+/// Exports the storage of contracts and the pallets they depend on at a given block.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ExportContractsStateCmd {
+	/// Block hash or number to export the state at. Defaults to the best block.
+	#[arg(value_name = "HASH or NUMBER")]
+	pub input: Option<BlockNumberOrHash>,
+
+	/// File to write the state to.
+	#[arg(long, short, value_name = "PATH")]
+	pub output: PathBuf,
+
+	/// Pallets whose storage is exported. Storage of contracts (default child tries) is always
+	/// exported.
+	#[arg(long = "pallet", value_name = "NAME", default_values_t = default_pallets())]
+	pub pallets: Vec<String>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportContractsStateCmd {
+	/// Run the command.
+	pub async fn run<B, BA, C>(&self, client: Arc<C>) -> sc_cli::Result<()>
+	where
+		B: BlockT,
+		BA: sc_client_api::backend::Backend<B>,
+		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B>,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let hash = match &self.input {
+			Some(input) => match input.parse::<B>()? {
+				BlockId::Hash(hash) => hash,
+				BlockId::Number(number) => client.hash(number)?.ok_or_else(|| format!("Block {number} not found"))?,
+			},
+			None => client.usage_info().chain.best_hash,
+		};
+		let number = *client.header(hash)?.ok_or_else(|| format!("Block {hash} not found"))?.number();
+
+		let mut top = serde_json::Map::new();
+		for pallet in &self.pallets {
+			let prefix = StorageKey(twox_128(pallet.as_bytes()).to_vec());
+			for (key, value) in client.storage_pairs(hash, Some(&prefix), None)? {
+				top.insert(hex(&key.0), hex(&value.0).into());
+			}
+		}
+
+		// Child tries are listed under their prefixed keys in the main trie.
+		let mut children = serde_json::Map::new();
+		let child_prefix = StorageKey(DEFAULT_CHILD_STORAGE_KEY_PREFIX.to_vec());
+		for prefixed_key in client.storage_keys(hash, Some(&child_prefix), None)? {
+			let trie_id = &prefixed_key.0[DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..];
+			let child_info = ChildInfo::new_default(trie_id);
+			let mut child = BTreeMap::new();
+			for key in client.child_storage_keys(hash, child_info.clone(), None, None)? {
+				if let Some(value) = client.child_storage(hash, &child_info, &key)? {
+					child.insert(hex(&key.0), Value::from(hex(&value.0)));
+				}
+			}
+			children.insert(hex(trie_id), child.into_iter().collect::<serde_json::Map<_, _>>().into());
+		}
+
+		let entries = top.len() + children.values().filter_map(Value::as_object).map(|c| c.len()).sum::<usize>();
+		let export = json!({
+			"block_number": UniqueSaturatedInto::<u64>::unique_saturated_into(number),
+			"block_hash": format!("{hash:?}"),
+			"pallets": self.pallets,
+			"contracts_pallet": {
+				"crate": CONTRACTS_PALLET_CRATE,
+				"name": <Contracts as PalletInfoAccess>::name(),
+			},
+			"top": top,
+			"children": children,
+		});
+		let json = serde_json::to_string_pretty(&export).map_err(|e| format!("Cannot serialize the state: {e}"))?;
+		fs::write(&self.output, json).map_err(|e| format!("Cannot write {}: {e}", self.output.display()))?;
+
+		println!(
+			"Exported {entries} storage entries ({} contracts) at block #{number} ({hash:?}) to {}",
+			children.len(),
+			self.output.display()
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportContractsStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+fn hex(bytes: &[u8]) -> String {
+	format!("0x{}", HexDisplay::from(&bytes))
+}
@@ -21,12 +21,16 @@
 mod cli;
 mod config;
 mod executor;
+mod export_contracts_state;
 
 mod rpc;
 mod service;
+mod upgrade_dry_run;
 
 pub use cli::{Cli, Subcommand};
 pub use config::Validator as ConfigValidator;
 #[cfg(any(feature = "runtime-benchmarks", feature = "setheum-native-runtime"))]
 pub use executor::executor::ExecutorDispatch;
+pub use export_contracts_state::ExportContractsStateCmd;
 pub use service::{new_authority, new_partial, ServiceComponents};
+pub use upgrade_dry_run::UpgradeDryRunCmd;
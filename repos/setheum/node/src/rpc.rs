@@ -30,8 +30,8 @@ use std::{collections::HashMap, sync::Arc};
 pub use fc_rpc::{Eth, EthApiServer, Net, NetApiServer, Web3, Web3ApiServer};
 pub use fp_rpc::EthereumRuntimeRPCApi;
 use finality_setbft::{
-	BlockId, Justification, JustificationTranslator, SetheumJustification, ValidatorAddressCache,
-	ValidatorAddressingInfo,
+	BlockId, Justification, JustificationTranslator, SessionHealth, SetheumJustification, ValidatorAddressCache,
+	ValidatorAddressingInfo, ValidatorHealthCache,
 };
 use futures::channel::mpsc;
 use jsonrpsee::{
@@ -70,6 +70,7 @@ pub struct FullDeps<C, P, BE, SO> {
 	pub justification_translator: JustificationTranslator,
 	pub sync_oracle: SO,
 	pub validator_address_cache: Option<ValidatorAddressCache>,
+	pub validator_health_cache: Option<ValidatorHealthCache>,
 	/// Frontier backend.
 	pub frontier_backend: Arc<fc_db::Backend<Block, BE>>,
 	/// Eth filter pool.
@@ -100,12 +101,14 @@ where
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>
 		+ pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>
 		+ module_oracle_rpc::OracleRuntimeApi<Block, DataProviderId, CurrencyId, TimeStampedPrice>
+		+ pallet_move::api::MoveApi<Block, AccountId>
 		+ EthereumRuntimeRPCApi<Block>
 		+ BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 	SO: SyncOracle + Send + Sync + 'static,
 {
 	use module_oracle_rpc::{Oracle, OracleApiServer};
+	use pallet_move_rpc::{MoveApiServer, MovePallet};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
@@ -118,6 +121,7 @@ where
 		justification_translator,
 		sync_oracle,
 		validator_address_cache,
+		validator_health_cache,
 		frontier_backend,
 		filter_pool,
 		graph,
@@ -132,6 +136,8 @@ where
 
 	module.merge(Oracle::new(client.clone()).into_rpc())?;
 
+	module.merge(MovePallet::new(client.clone()).into_rpc())?;
+
 	module.merge(
 		Eth::new(
 			client.clone(),
@@ -158,6 +164,7 @@ where
 			client,
 			sync_oracle,
 			validator_address_cache,
+			validator_health_cache,
 		)
 		.into_rpc(),
 	)?;
@@ -198,6 +205,9 @@ pub enum Error {
 	/// Network info caching is not enabled.
 	#[error("Unable to get any data, because network info caching is not enabled.")]
 	NetworkInfoCachingNotEnabled,
+	/// Validator health caching is not enabled.
+	#[error("Unable to get any data, because validator health caching is not enabled.")]
+	ValidatorHealthCachingNotEnabled,
 }
 
 // Base code for all system errors.
@@ -222,6 +232,8 @@ const FAILED_HEADER_DECODING_ERROR: i32 = BASE_ERROR + 8;
 const UNKNOWN_HASH_ERROR: i32 = BASE_ERROR + 9;
 /// Network info caching is not enabled.
 const NETWORK_INFO_CACHING_NOT_ENABLED_ERROR: i32 = BASE_ERROR + 10;
+/// Validator health caching is not enabled.
+const VALIDATOR_HEALTH_CACHING_NOT_ENABLED_ERROR: i32 = BASE_ERROR + 11;
 
 impl From<Error> for JsonRpseeError {
 	fn from(e: Error) -> Self {
@@ -270,6 +282,11 @@ impl From<Error> for JsonRpseeError {
 				"Unable to get any data, because network info caching is not enabled.",
 				None::<()>,
 			)),
+			Error::ValidatorHealthCachingNotEnabled => CallError::Custom(ErrorObject::owned(
+				VALIDATOR_HEALTH_CACHING_NOT_ENABLED_ERROR,
+				"Unable to get any data, because validator health caching is not enabled.",
+				None::<()>,
+			)),
 		}
 		.into()
 	}
@@ -292,6 +309,12 @@ pub trait SetheumNodeApi<BE> {
 
 	#[method(name = "unstable_validatorNetworkInfo")]
 	fn validator_network_info(&self) -> RpcResult<HashMap<AccountId, ValidatorAddressingInfo>>;
+
+	/// Per-session report on the SetBFT committee members: their score history, the round of
+	/// their last ordered unit, the state of our connections with them, how many messages per
+	/// second we send them and the bandwidth the rate limiter allows for each connection.
+	#[method(name = "unstable_validatorHealth")]
+	fn validator_health(&self) -> RpcResult<Vec<SessionHealth>>;
 }
 
 /// Setheum Node API implementation
@@ -301,6 +324,7 @@ pub struct SetheumNode<Client, SO> {
 	client: Arc<Client>,
 	sync_oracle: SO,
 	validator_address_cache: Option<ValidatorAddressCache>,
+	validator_health_cache: Option<ValidatorHealthCache>,
 }
 
 impl<Client, SO> SetheumNode<Client, SO>
@@ -313,8 +337,16 @@ where
 		client: Arc<Client>,
 		sync_oracle: SO,
 		validator_address_cache: Option<ValidatorAddressCache>,
+		validator_health_cache: Option<ValidatorHealthCache>,
 	) -> Self {
-		SetheumNode { import_justification_tx, justification_translator, client, sync_oracle, validator_address_cache }
+		SetheumNode {
+			import_justification_tx,
+			justification_translator,
+			client,
+			sync_oracle,
+			validator_address_cache,
+			validator_health_cache,
+		}
 	}
 }
 
@@ -374,6 +406,13 @@ where
 			.map(|c| c.snapshot())
 			.ok_or(Error::NetworkInfoCachingNotEnabled.into())
 	}
+
+	fn validator_health(&self) -> RpcResult<Vec<SessionHealth>> {
+		self.validator_health_cache
+			.as_ref()
+			.map(|c| c.snapshot())
+			.ok_or(Error::ValidatorHealthCachingNotEnabled.into())
+	}
 }
 
 fn read_storage<
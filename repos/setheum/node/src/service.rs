@@ -30,6 +30,7 @@ use finality_setbft::{
 	build_network, get_setheum_block_import, run_validator_node, BlockImporter, BuildNetworkOutput, ChannelProvider,
 	FavouriteSelectChainProvider, Justification, JustificationTranslator, MillisecsPerBlock, RateLimiterConfig,
 	RedirectingBlockImport, SessionPeriod, SetheumNodeConfig, SubstrateChainStatus, SyncOracle, ValidatorAddressCache,
+	ValidatorHealthCache, WarpSyncConfig,
 };
 use log::warn;
 use module_setbft_runtime_api::SetBftApi;
@@ -229,6 +230,10 @@ fn get_validator_address_cache(setheum_config: &SetheumCli) -> Option<ValidatorA
 	setheum_config.collect_validator_network_data().then(ValidatorAddressCache::new)
 }
 
+fn get_validator_health_cache(setheum_config: &SetheumCli) -> Option<ValidatorHealthCache> {
+	setheum_config.collect_validator_network_data().then(ValidatorHealthCache::new)
+}
+
 fn get_proposer_factory(service_components: &ServiceComponents, config: &Configuration) -> FullProposerFactory {
 	let mut proposer_factory = FullProposerFactory::new(
 		service_components.task_manager.spawn_handle(),
@@ -321,12 +326,19 @@ pub fn new_authority(config: Configuration, setheum_config: SetheumCli) -> Resul
 		config.prometheus_config.as_ref().map(|config| config.registry.clone()),
 	)?;
 
+	let warp_sync = setheum_config.warp_sync().then(|| WarpSyncConfig {
+		network: network.clone(),
+		import_queue: service_components.import_queue.service(),
+	});
+
 	let chain_status = SubstrateChainStatus::new(service_components.backend.clone())
 		.map_err(|e| ServiceError::Other(format!("failed to set up chain status: {e}")))?;
 	let validator_address_cache = get_validator_address_cache(&setheum_config);
+	let validator_health_cache = get_validator_health_cache(&setheum_config);
 	let rpc_builder = {
 		let sync_oracle = sync_oracle.clone();
 		let validator_address_cache = validator_address_cache.clone();
+		let validator_health_cache = validator_health_cache.clone();
 		let import_justification_tx = service_components.justification_channel_provider.get_sender();
 		let chain_status = chain_status.clone();
 		let frontier_backend = service_components.frontier_backend.clone();
@@ -342,6 +354,7 @@ pub fn new_authority(config: Configuration, setheum_config: SetheumCli) -> Resul
 				justification_translator: JustificationTranslator::new(chain_status.clone()),
 				sync_oracle: sync_oracle.clone(),
 				validator_address_cache: validator_address_cache.clone(),
+				validator_health_cache: validator_health_cache.clone(),
 				frontier_backend: frontier_backend.clone(),
 				filter_pool: Some(filter_pool.clone()),
 				graph: pool.pool().clone(),
@@ -420,7 +433,9 @@ pub fn new_authority(config: Configuration, setheum_config: SetheumCli) -> Resul
 		rate_limiter_config,
 		sync_oracle,
 		validator_address_cache,
+		validator_health_cache,
 		transaction_pool: service_components.transaction_pool,
+		warp_sync,
 	};
 
 	service_components.task_manager.spawn_essential_handle().spawn_blocking(
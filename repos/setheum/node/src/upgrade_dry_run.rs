@@ -0,0 +1,416 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Setheum Developers.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! `upgrade-dry-run` replays a runtime upgrade against a state snapshot before it is enacted, e.g.
+//! with `clisee update-runtime`. It runs `TryRuntime_on_runtime_upgrade` with all checks, so every
+//! `pre_upgrade`/`post_upgrade` hook and `try_state` is exercised, then authors a few empty blocks
+//! on top of the migrated state and checks that every storage value still decodes.
+
+use std::{
+	collections::BTreeMap,
+	convert::TryFrom,
+	fs,
+	path::{Path, PathBuf},
+};
+
+use frame_metadata::{
+	v15::{RuntimeMetadataV15, StorageEntryType},
+	RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use frame_remote_externalities::{Builder, Mode, OfflineConfig, RemoteExternalities, SnapshotConfig};
+use parity_scale_codec::{Decode, Encode};
+use primitives::Block;
+use sc_cli::clap::{self, Parser};
+use sc_executor::WasmExecutor;
+use sp_consensus_aura::{Slot, SlotDuration, AURA_ENGINE_ID};
+use sp_core::{hexdisplay::HexDisplay, storage::well_known_keys, twox_128, OpaqueMetadata};
+use sp_inherents::InherentData;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	ApplyExtrinsicResult, Digest, DigestItem, ExtrinsicInclusionMode,
+};
+use sp_state_machine::{backend::BackendRuntimeCode, Backend, OverlayedChanges, StateMachine};
+use sp_version::RuntimeVersion;
+use sp_weights::Weight;
+
+type Executor = WasmExecutor<sp_io::SubstrateHostFunctions>;
+type Header = <Block as BlockT>::Header;
+
+/// SCALE encoding of `UpgradeCheckSelect::All`, which is only compiled in with `try-runtime`.
+const UPGRADE_CHECK_ALL: u8 = 1;
+const UNKNOWN_PALLET: &str = "<unknown>";
+
+/// Replays a runtime upgrade against a state snapshot, without touching any database.
+#[derive(Debug, Parser)]
+pub struct UpgradeDryRunCmd {
+	/// State snapshot, as produced by `try-runtime create-snapshot`.
+	#[clap(long, value_name = "PATH")]
+	snapshot: PathBuf,
+
+	/// Runtime Wasm to upgrade to. It has to be built with the `try-runtime` feature.
+	#[clap(long, value_name = "PATH")]
+	runtime: PathBuf,
+
+	/// Number of empty blocks authored on top of the upgraded state.
+	#[clap(long, default_value_t = 3)]
+	blocks: u32,
+}
+
+/// Storage entry that does not decode under the new metadata.
+struct DecodingFailure {
+	pallet: String,
+	entry: String,
+	failed: usize,
+	total: usize,
+	first_key: Vec<u8>,
+	first_error: String,
+}
+
+struct StorageEntry {
+	pallet: String,
+	name: String,
+	value_ty: u32,
+}
+
+type StorageDigest = BTreeMap<Vec<u8>, [u8; 32]>;
+
+/// Outcome of a runtime upgrade replayed against a state snapshot.
+struct DryRun {
+	upgrade_weight: Weight,
+	max_block_weight: Weight,
+	changed: BTreeMap<String, usize>,
+	/// Number and consumed weight of the sample blocks.
+	blocks: Vec<(u32, Weight)>,
+	failures: Vec<DecodingFailure>,
+}
+
+fn load_snapshot(path: &Path) -> sc_cli::Result<RemoteExternalities<Block>> {
+	let builder =
+		Builder::<Block>::new().mode(Mode::Offline(OfflineConfig { state_snapshot: SnapshotConfig::new(path) }));
+	futures::executor::block_on(builder.build())
+		.map_err(|e| format!("Cannot load snapshot {}: {e}", path.display()).into())
+}
+
+/// Calls `method` of the runtime stored under `:code` and commits its storage changes.
+fn call<R: Decode>(
+	ext: &mut RemoteExternalities<Block>,
+	executor: &Executor,
+	method: &str,
+	data: &[u8],
+) -> sc_cli::Result<R> {
+	let mut changes = OverlayedChanges::default();
+	let backend_runtime_code = BackendRuntimeCode::new(&ext.backend);
+	let runtime_code = backend_runtime_code.runtime_code()?;
+	let result = StateMachine::new(
+		&ext.backend,
+		&mut changes,
+		executor,
+		method,
+		data,
+		&mut Default::default(),
+		&runtime_code,
+		sp_core::traits::CallContext::Offchain,
+	)
+	.execute()
+	.map_err(|e| format!("{method} failed: {e}"))?;
+
+	let storage_changes = changes.drain_storage_changes(&ext.backend, ext.state_version)?;
+	ext.backend.apply_transaction(storage_changes.transaction_storage_root, storage_changes.transaction);
+
+	R::decode(&mut &result[..]).map_err(|e| format!("Cannot decode the result of {method}: {e}").into())
+}
+
+fn storage_pairs(ext: &RemoteExternalities<Block>) -> sc_cli::Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)> + '_> {
+	let pairs = ext.backend.pairs(Default::default()).map_err(|e| format!("Cannot iterate storage: {e}"))?;
+	Ok(pairs.filter_map(Result::ok))
+}
+
+fn storage_digest(ext: &RemoteExternalities<Block>) -> sc_cli::Result<StorageDigest> {
+	Ok(storage_pairs(ext)?.map(|(key, value)| (key, sp_core::blake2_256(&value))).collect())
+}
+
+fn storage_key(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+fn storage_value<T: Decode>(ext: &RemoteExternalities<Block>, pallet: &str, item: &str) -> sc_cli::Result<T> {
+	let value = ext
+		.backend
+		.storage(&storage_key(pallet, item))
+		.map_err(|e| format!("Cannot read {pallet}::{item}: {e}"))?;
+	let value = value.ok_or(format!("{pallet}::{item} is not set"))?;
+	T::decode(&mut &value[..]).map_err(|e| format!("Cannot decode {pallet}::{item}: {e}").into())
+}
+
+fn metadata(ext: &mut RemoteExternalities<Block>, executor: &Executor) -> sc_cli::Result<RuntimeMetadataV15> {
+	let metadata: Option<OpaqueMetadata> = call(ext, executor, "Metadata_metadata_at_version", &15u32.encode())?;
+	let metadata = metadata.ok_or("The new runtime does not provide metadata V15")?;
+	let metadata =
+		RuntimeMetadataPrefixed::decode(&mut &metadata[..]).map_err(|e| format!("Cannot decode metadata: {e}"))?;
+	match metadata.1 {
+		RuntimeMetadata::V15(metadata) => Ok(metadata),
+		_ => Err("The new runtime does not provide metadata V15".into()),
+	}
+}
+
+/// Pallet names keyed by their storage prefix hash, and storage entries keyed by their full prefix.
+fn storage_layout(metadata: &RuntimeMetadataV15) -> (BTreeMap<[u8; 16], String>, BTreeMap<[u8; 32], StorageEntry>) {
+	let mut pallets = BTreeMap::new();
+	let mut entries = BTreeMap::new();
+	for pallet in &metadata.pallets {
+		let Some(storage) = &pallet.storage else { continue };
+		let pallet_prefix = twox_128(storage.prefix.as_bytes());
+		pallets.insert(pallet_prefix, pallet.name.clone());
+		for entry in &storage.entries {
+			let value_ty = match &entry.ty {
+				StorageEntryType::Plain(ty) => ty.id,
+				StorageEntryType::Map { value, .. } => value.id,
+			};
+			let mut prefix = [0u8; 32];
+			prefix[..16].copy_from_slice(&pallet_prefix);
+			prefix[16..].copy_from_slice(&twox_128(entry.name.as_bytes()));
+			entries.insert(prefix, StorageEntry { pallet: pallet.name.clone(), name: entry.name.clone(), value_ty });
+		}
+	}
+	(pallets, entries)
+}
+
+fn changed_keys_per_pallet(
+	before: &StorageDigest,
+	after: &StorageDigest,
+	pallets: &BTreeMap<[u8; 16], String>,
+) -> BTreeMap<String, usize> {
+	let pallet_of = |key: &[u8]| {
+		key.get(..16)
+			.and_then(|prefix| pallets.get(prefix))
+			.map(String::as_str)
+			.unwrap_or(UNKNOWN_PALLET)
+			.to_string()
+	};
+	let mut changed = BTreeMap::new();
+	let modified = after.iter().filter(|(key, value)| before.get(*key) != Some(value)).map(|(key, _)| key);
+	let removed = before.keys().filter(|key| !after.contains_key(*key));
+	for key in modified.chain(removed) {
+		*changed.entry(pallet_of(key)).or_default() += 1;
+	}
+	changed
+}
+
+fn decoding_failures(
+	ext: &RemoteExternalities<Block>,
+	metadata: &RuntimeMetadataV15,
+	entries: &BTreeMap<[u8; 32], StorageEntry>,
+) -> sc_cli::Result<Vec<DecodingFailure>> {
+	let mut failures: BTreeMap<[u8; 32], DecodingFailure> = BTreeMap::new();
+	let mut totals: BTreeMap<[u8; 32], usize> = BTreeMap::new();
+	for (key, value) in storage_pairs(ext)? {
+		let Some(prefix) = key.get(..32).and_then(|prefix| <[u8; 32]>::try_from(prefix).ok()) else { continue };
+		let Some(entry) = entries.get(&prefix) else { continue };
+		*totals.entry(prefix).or_default() += 1;
+
+		let data = &mut &value[..];
+		let error = match scale_value::scale::decode_as_type(data, entry.value_ty, &metadata.types) {
+			Ok(_) if data.is_empty() => continue,
+			Ok(_) => format!("{} trailing bytes", data.len()),
+			Err(e) => e.to_string(),
+		};
+		failures
+			.entry(prefix)
+			.or_insert_with(|| DecodingFailure {
+				pallet: entry.pallet.clone(),
+				entry: entry.name.clone(),
+				failed: 0,
+				total: 0,
+				first_key: key.clone(),
+				first_error: error,
+			})
+			.failed += 1;
+	}
+	Ok(failures
+		.into_iter()
+		.map(|(prefix, failure)| DecodingFailure { total: totals[&prefix], ..failure })
+		.collect())
+}
+
+/// Records the upgrade in `System::LastRuntimeUpgrade`, as `Executive` does when it runs the
+/// migrations at the start of a block. `TryRuntime_on_runtime_upgrade` leaves it untouched, so the
+/// sample blocks would otherwise run every migration again.
+fn record_upgrade(ext: &mut RemoteExternalities<Block>, executor: &Executor) -> sc_cli::Result<()> {
+	let version: RuntimeVersion = call(ext, executor, "Core_version", &[])?;
+	let last_upgrade = frame_system::LastRuntimeUpgradeInfo::from(version);
+	ext.insert(storage_key("System", "LastRuntimeUpgrade"), last_upgrade.encode());
+	ext.commit_all()?;
+	Ok(())
+}
+
+/// Authors an empty block on top of the current state and returns its header and consumed weight.
+fn author_block(
+	ext: &mut RemoteExternalities<Block>,
+	executor: &Executor,
+	parent: &Header,
+	timestamp: u64,
+	slot_duration: SlotDuration,
+) -> sc_cli::Result<(Header, Weight)> {
+	let slot = Slot::from_timestamp(timestamp.into(), slot_duration);
+	let digest = Digest { logs: vec![DigestItem::PreRuntime(AURA_ENGINE_ID, slot.encode())] };
+	let header = Header::new(*parent.number() + 1, Default::default(), Default::default(), parent.hash(), digest);
+	let _: ExtrinsicInclusionMode = call(ext, executor, "Core_initialize_block", &header.encode())?;
+
+	let mut inherent_data = InherentData::new();
+	inherent_data
+		.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp)
+		.map_err(|e| format!("Cannot create timestamp inherent: {e}"))?;
+	let inherents: Vec<<Block as BlockT>::Extrinsic> =
+		call(ext, executor, "BlockBuilder_inherent_extrinsics", &inherent_data.encode())?;
+	for inherent in inherents {
+		let result: ApplyExtrinsicResult = call(ext, executor, "BlockBuilder_apply_extrinsic", &inherent.encode())?;
+		result
+			.map_err(|e| format!("Inherent is invalid: {e:?}"))?
+			.map_err(|e| format!("Inherent failed: {e:?}"))?;
+	}
+
+	// Normal, operational and mandatory weight, `BlockWeight` is only reset by the next block.
+	let (normal, operational, mandatory): (Weight, Weight, Weight) = storage_value(ext, "System", "BlockWeight")?;
+	let header = call(ext, executor, "BlockBuilder_finalize_block", &[])?;
+	Ok((header, normal.saturating_add(operational).saturating_add(mandatory)))
+}
+
+/// Upgrades the state of `ext` to the runtime `code`, then authors `blocks` empty blocks on top.
+fn dry_run(
+	ext: &mut RemoteExternalities<Block>,
+	executor: &Executor,
+	code: Vec<u8>,
+	blocks: u32,
+) -> sc_cli::Result<DryRun> {
+	ext.insert(well_known_keys::CODE.to_vec(), code);
+	ext.commit_all()?;
+	let before = storage_digest(ext)?;
+
+	let (upgrade_weight, max_block_weight): (Weight, Weight) =
+		call(ext, executor, "TryRuntime_on_runtime_upgrade", &UPGRADE_CHECK_ALL.encode())
+			.map_err(|e| format!("{e}\nMake sure the runtime is built with the `try-runtime` feature."))?;
+	record_upgrade(ext, executor)?;
+	let after = storage_digest(ext)?;
+
+	let metadata = metadata(ext, executor)?;
+	let (pallets, entries) = storage_layout(&metadata);
+	let changed = changed_keys_per_pallet(&before, &after, &pallets);
+	let failures = decoding_failures(ext, &metadata, &entries)?;
+
+	let slot_duration: SlotDuration = call(ext, executor, "AuraApi_slot_duration", &[])?;
+	let mut timestamp: u64 = storage_value(ext, "Timestamp", "Now")?;
+	let mut parent = ext.header.clone();
+	let mut sample_blocks = vec![];
+	for _ in 0..blocks {
+		timestamp += slot_duration.as_millis();
+		let (header, weight) = author_block(ext, executor, &parent, timestamp, slot_duration)?;
+		sample_blocks.push((*header.number(), weight));
+		parent = header;
+	}
+
+	Ok(DryRun { upgrade_weight, max_block_weight, changed, blocks: sample_blocks, failures })
+}
+
+impl UpgradeDryRunCmd {
+	pub fn run(&self) -> sc_cli::Result<()> {
+		let executor = Executor::builder().build();
+		let mut ext = load_snapshot(&self.snapshot)?;
+		let code = fs::read(&self.runtime).map_err(|e| format!("Cannot read {}: {e}", self.runtime.display()))?;
+		let parent = *ext.header.number();
+		let DryRun { upgrade_weight, max_block_weight, changed, blocks, failures } =
+			dry_run(&mut ext, &executor, code, self.blocks)?;
+
+		println!("Runtime upgrade");
+		println!(
+			"  weight: {} ref time, {} proof size ({}% of the maximum block weight)",
+			upgrade_weight.ref_time(),
+			upgrade_weight.proof_size(),
+			upgrade_weight.ref_time().saturating_mul(100) / max_block_weight.ref_time().max(1),
+		);
+		println!("Storage keys changed per pallet");
+		for (pallet, count) in &changed {
+			println!("  {pallet}: {count}");
+		}
+
+		println!("Sample blocks on top of #{parent}");
+		for (number, weight) in &blocks {
+			println!("  #{number}: {} ref time, {} proof size", weight.ref_time(), weight.proof_size());
+		}
+
+		if failures.is_empty() {
+			println!("All storage values decode under the new metadata");
+			return Ok(());
+		}
+		println!("Storage values that do not decode under the new metadata");
+		for failure in &failures {
+			println!(
+				"  {}::{}: {} of {} values, e.g. 0x{:?}: {}",
+				failure.pallet,
+				failure.entry,
+				failure.failed,
+				failure.total,
+				HexDisplay::from(&failure.first_key),
+				failure.first_error,
+			);
+		}
+		Err(format!("{} storage entries fail to decode", failures.len()).into())
+	}
+}
+
+#[cfg(all(test, feature = "try-runtime"))]
+mod tests {
+	use parity_scale_codec::Compact;
+	use setheum_runtime::{GenesisConfig, VERSION, WASM_BINARY};
+	use sp_consensus_aura::sr25519::AuthorityId as AuraId;
+	use sp_core::sr25519;
+	use sp_runtime::BuildStorage;
+
+	use super::*;
+
+	/// Genesis state of a chain last upgraded to the previous spec version, with one Aura authority.
+	fn snapshot() -> RemoteExternalities<Block> {
+		let storage = GenesisConfig::default().build_storage().expect("The default genesis config is valid");
+		let mut ext = sp_state_machine::TestExternalities::new(storage);
+		ext.insert(storage_key("Aura", "Authorities"), vec![AuraId::from(sr25519::Public::from_raw([1; 32]))].encode());
+		ext.insert(storage_key("Timestamp", "Now"), 1_700_000_000_000u64.encode());
+		let last_upgrade = (Compact(VERSION.spec_version - 1), VERSION.spec_name.to_string());
+		ext.insert(storage_key("System", "LastRuntimeUpgrade"), last_upgrade.encode());
+		ext.commit_all().unwrap();
+		let header = Header::new(0, Default::default(), Default::default(), Default::default(), Default::default());
+		RemoteExternalities { inner_ext: ext, header }
+	}
+
+	#[test]
+	fn sample_blocks_do_not_run_the_migrations_again() {
+		let mut ext = snapshot();
+		let code = WASM_BINARY.expect("The runtime is built with the `try-runtime` feature").to_vec();
+		let dry_run = dry_run(&mut ext, &Executor::builder().build(), code, 3).unwrap();
+		assert!(dry_run.failures.is_empty());
+
+		let (spec_version, spec_name): (Compact<u32>, String) =
+			storage_value(&ext, "System", "LastRuntimeUpgrade").unwrap();
+		assert_eq!((spec_version.0, spec_name), (VERSION.spec_version, VERSION.spec_name.to_string()));
+
+		// Empty blocks weigh the same, unless the first one runs the migrations again.
+		let numbers = dry_run.blocks.iter().map(|(number, _)| *number).collect::<Vec<_>>();
+		assert_eq!(numbers, [1, 2, 3]);
+		assert!(dry_run.blocks.iter().all(|(_, weight)| *weight == dry_run.blocks[2].1));
+	}
+}
@@ -0,0 +1,102 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Types of the contract call tracing runtime API.
+
+use frame_support::weights::Weight;
+use parity_scale_codec::{Codec, Decode, Encode};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// How a traced frame was entered.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub enum CallKind {
+/// A regular message call.
+	Call,
+/// A message executed with the code of another contract in the caller's context.
+	DelegateCall,
+/// A constructor call of a newly instantiated contract.
+	Instantiate,
+/// A message executed against the caller's own account which did not return successfully.
+/// `pallet_contracts` only reports whether such a frame was a reentrant or a delegate call
+/// once it returns, so it can't be told apart.
+	ReentrantOrDelegateCall,
+}
+
+/// An event emitted by a contract while executing a traced frame.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct TracedEvent<Hash> {
+/// The event topics, the first one being the event signature for non-anonymous events.
+	pub topics: Vec<Hash>,
+/// The SCALE encoded event data.
+	pub data: Vec<u8>,
+}
+
+/// A single frame of a traced contract call, together with the frames it called into.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct CallTrace<AccountId, Balance, Hash> {
+	pub kind: CallKind,
+/// The contract whose storage the frame executed against.
+	pub contract: AccountId,
+/// The code that was executed, if it could be determined.
+	pub code_hash: Option<Hash>,
+/// The input data, starting with the selector.
+	pub input: Vec<u8>,
+/// The value transferred to the contract when entering the frame.
+	pub value: Balance,
+/// Gas consumed by the frame including nested frames. `pallet_contracts` meters nested
+/// frames against their caller without reporting it, so this is only known for the
+/// outermost call.
+	pub gas_consumed: Option<Weight>,
+/// The returned data, empty if the frame trapped.
+	pub output: Vec<u8>,
+/// The frame returned with the revert flag set.
+	pub reverted: bool,
+/// The frame trapped or ran out of gas without returning.
+	pub trapped: bool,
+/// Storage deposit charged to the caller for the contract's storage.
+	pub storage_deposit_charged: Balance,
+/// Storage deposit refunded to the caller for freed contract storage.
+	pub storage_deposit_refunded: Balance,
+/// Events emitted by this frame, not including those of nested frames.
+	pub events: Vec<TracedEvent<Hash>>,
+/// Nested frames in the order they were entered.
+	pub calls: Vec<CallTrace<AccountId, Balance, Hash>>,
+}
+
+sp_api::decl_runtime_apis! {
+/// Dry runs a contract call like `ContractsApi::call`, recording the tree of nested calls.
+	pub trait ContractsTraceApi<AccountId, Balance, Hash> where
+		AccountId: Codec,
+		Balance: Codec,
+		Hash: Codec,
+	{
+/// Returns the trace of the call, or `None` if no contract code was executed.
+		fn trace_call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> Option<CallTrace<AccountId, Balance, Hash>>;
+	}
+}
@@ -23,10 +23,12 @@
 #![allow(clippy::upper_case_acronyms)]
 
 pub mod setbft;
+pub mod contracts;
 pub mod bonding;
 pub mod currency;
 pub mod launchpad;
 pub mod evm;
+pub mod move_vm;
 pub mod nft;
 pub mod signature;
 pub mod task;
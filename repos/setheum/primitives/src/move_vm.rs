@@ -10,7 +10,32 @@
 // the Free Software Foundation, either version 3 of the License, or
 // (at your option) any later version.
 
-use sp_core::H256;
+use crate::{evm::EvmAddress, CurrencyId};
 
 /// Move Address. Move uses a 32-byte address format.
 pub type MoveAddress = [u8; 32];
+
+/// Length of the zero prefix of a Move currency identifier, followed by an EVM address.
+const MOVE_CURRENCY_PREFIX_LEN: usize = 12;
+
+/// Returns the currency a Move currency identifier refers to.
+///
+/// Move identifies a currency by the EVM address of the currency, see `EvmAddress::try_from`,
+/// left-padded with zeros to a `MoveAddress`. Identifiers with a non-zero prefix refer to no
+/// currency.
+pub fn currency_id_of_move_currency(address: &MoveAddress) -> Option<CurrencyId> {
+	let (prefix, evm_address) = address.split_at(MOVE_CURRENCY_PREFIX_LEN);
+	if prefix.iter().any(|byte| *byte != 0) {
+		return None;
+	}
+	CurrencyId::try_from(EvmAddress::from_slice(evm_address)).ok()
+}
+
+/// Returns the Move currency identifier of a currency, the inverse of
+/// [`currency_id_of_move_currency`].
+pub fn move_currency_of_currency_id(currency_id: CurrencyId) -> Option<MoveAddress> {
+	let evm_address = EvmAddress::try_from(currency_id).ok()?;
+	let mut address = [0; 32];
+	address[MOVE_CURRENCY_PREFIX_LEN..].copy_from_slice(evm_address.as_bytes());
+	Some(address)
+}
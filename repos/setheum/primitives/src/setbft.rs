@@ -100,9 +100,12 @@ pub const DEFAULT_BAN_PERIOD: EraIndex = 10;
 
 /// Version returned when no version has been set.
 pub const DEFAULT_FINALITY_VERSION: Version = 0;
-/// Current version of setbft.
-pub const CURRENT_FINALITY_VERSION: u16 = LEGACY_FINALITY_VERSION + 1;
-/// Current version of setbft.
+/// Current version of setbft. Units carry batches of data and are compressed when large.
+pub const CURRENT_FINALITY_VERSION: u16 = UNBATCHED_FINALITY_VERSION + 1;
+/// Version of setbft running the current protocol with a single piece of data per unit, sent
+/// uncompressed.
+pub const UNBATCHED_FINALITY_VERSION: u16 = LEGACY_FINALITY_VERSION + 1;
+/// Legacy version of setbft.
 pub const LEGACY_FINALITY_VERSION: u16 = 2;
 pub const LENIENT_THRESHOLD: Perquintill = Perquintill::from_percent(90);
 
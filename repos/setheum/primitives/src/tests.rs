@@ -128,6 +128,26 @@ fn currency_id_try_into_evm_address_works() {
 	);
 }
 
+#[test]
+fn move_currency_roundtrips() {
+	use crate::move_vm::{currency_id_of_move_currency, move_currency_of_currency_id};
+
+	let erc20 = CurrencyId::Erc20(EvmAddress::from_str("0x1111111111111111111111111111111111111111").unwrap());
+	for currency_id in [CurrencyId::Token(TokenSymbol::SEU), CurrencyId::Token(TokenSymbol::SEUSD), erc20] {
+		let address = move_currency_of_currency_id(currency_id).unwrap();
+		assert_eq!(address[..12], [0; 12]);
+		assert_eq!(currency_id_of_move_currency(&address), Some(currency_id));
+	}
+
+	let mut address = move_currency_of_currency_id(CurrencyId::Token(TokenSymbol::SEU)).unwrap();
+	address[0] = 1;
+	assert_eq!(currency_id_of_move_currency(&address), None);
+	// `0x1`, the address of the Move standard library, is a system contract of no currency.
+	let mut address = [0; 32];
+	address[31] = 1;
+	assert_eq!(currency_id_of_move_currency(&address), None);
+}
+
 #[test]
 fn generate_function_selector_works() {
 	#[module_evm_utility_macro::generate_function_selector]
@@ -49,7 +49,7 @@ use frame_support::{
 		},
 		BalanceStatus as Status, Currency as PalletCurrency, ExistenceRequirement, Get, Imbalance,
 		LockableCurrency as PalletLockableCurrency, NamedReservableCurrency as PalletNamedReservableCurrency,
-		ReservableCurrency as PalletReservableCurrency, WithdrawReasons,
+		ReservableCurrency as PalletReservableCurrency, SignedImbalance, WithdrawReasons,
 	},
 	transactional,
 };
@@ -63,7 +63,11 @@ use module_traits::{
 	NamedBasicReservableCurrency, NamedMultiReservableCurrency,
 };
 use parity_scale_codec::{Codec, Decode, Encode};
-use primitives::{evm::EvmAddress, CurrencyId};
+use primitives::{
+	evm::EvmAddress,
+	move_vm::{currency_id_of_move_currency, MoveAddress},
+	Balance, CurrencyId,
+};
 use sp_io::hashing::blake2_256;
 use sp_runtime::{
 	traits::{CheckedAdd, CheckedSub, Convert, MaybeSerializeDeserialize, Saturating, StaticLookup, Zero},
@@ -155,6 +159,8 @@ pub mod module {
 		RealOriginNotFound,
 		/// Deposit result is not expected
 		DepositFailed,
+		/// Move currency identifier refers to no currency
+		UnknownMoveCurrency,
 	}
 
 	#[pallet::event]
@@ -1208,6 +1214,193 @@ impl<T: Config> fungibles::MutateHold<T::AccountId> for Pallet<T> {
 	}
 }
 
+impl<T: Config> module_support::MoveVmManager<T::AccountId> for Pallet<T>
+where
+	BalanceOf<T>: From<Balance> + Into<Balance>,
+{
+	fn currency_id(currency: &MoveAddress) -> Option<CurrencyId> {
+		currency_id_of_move_currency(currency)
+	}
+
+	fn get_native_balance(who: &T::AccountId) -> Balance {
+		<Self as MultiCurrency<_>>::free_balance(T::GetNativeCurrencyId::get(), who).into()
+	}
+
+	fn free_balance(currency: &MoveAddress, who: &T::AccountId) -> result::Result<Balance, DispatchError> {
+		let currency_id = Self::currency_id(currency).ok_or(Error::<T>::UnknownMoveCurrency)?;
+		Ok(<Self as MultiCurrency<_>>::free_balance(currency_id, who).into())
+	}
+
+	fn transfer(currency: &MoveAddress, from: &T::AccountId, to: &T::AccountId, amount: Balance) -> DispatchResult {
+		let currency_id = Self::currency_id(currency).ok_or(Error::<T>::UnknownMoveCurrency)?;
+		<Self as MultiCurrency<_>>::transfer(currency_id, from, to, amount.into(), ExistenceRequirement::KeepAlive)
+	}
+}
+
+/// The `Currency` of `pallet_move`, i.e. the balance adapter through which Move coins are read and
+/// transferred.
+///
+/// `pallet_move` knows a single coin, the native currency. Its balances and transfers go through
+/// `MultiCurrency` of this pallet, like those of any other currency Move reaches through
+/// [`module_support::MoveVmManager`]. Reserves, locks and imbalances are delegated to the native
+/// `Currency`.
+pub struct MoveVmCurrency<T, Currency>(marker::PhantomData<(T, Currency)>);
+
+impl<T, Currency> PalletCurrency<T::AccountId> for MoveVmCurrency<T, Currency>
+where
+	T: Config,
+	Currency: PalletCurrency<T::AccountId>,
+	PalletBalanceOf<T::AccountId, Currency>: From<Balance> + Into<Balance>,
+	BalanceOf<T>: From<Balance> + Into<Balance>,
+{
+	type Balance = PalletBalanceOf<T::AccountId, Currency>;
+	type PositiveImbalance = Currency::PositiveImbalance;
+	type NegativeImbalance = Currency::NegativeImbalance;
+
+	fn total_balance(who: &T::AccountId) -> Self::Balance {
+		Into::<Balance>::into(<Pallet<T> as MultiCurrency<_>>::total_balance(T::GetNativeCurrencyId::get(), who)).into()
+	}
+
+	fn can_slash(who: &T::AccountId, value: Self::Balance) -> bool {
+		Currency::can_slash(who, value)
+	}
+
+	fn total_issuance() -> Self::Balance {
+		Into::<Balance>::into(<Pallet<T> as MultiCurrency<_>>::total_issuance(T::GetNativeCurrencyId::get())).into()
+	}
+
+	fn minimum_balance() -> Self::Balance {
+		Currency::minimum_balance()
+	}
+
+	fn burn(amount: Self::Balance) -> Self::PositiveImbalance {
+		Currency::burn(amount)
+	}
+
+	fn issue(amount: Self::Balance) -> Self::NegativeImbalance {
+		Currency::issue(amount)
+	}
+
+	fn free_balance(who: &T::AccountId) -> Self::Balance {
+		<Pallet<T> as module_support::MoveVmManager<_>>::get_native_balance(who).into()
+	}
+
+	fn ensure_can_withdraw(
+		who: &T::AccountId,
+		amount: Self::Balance,
+		reasons: WithdrawReasons,
+		new_balance: Self::Balance,
+	) -> DispatchResult {
+		Currency::ensure_can_withdraw(who, amount, reasons, new_balance)
+	}
+
+	fn transfer(
+		source: &T::AccountId,
+		dest: &T::AccountId,
+		value: Self::Balance,
+		existence_requirement: ExistenceRequirement,
+	) -> DispatchResult {
+		<Pallet<T> as MultiCurrency<_>>::transfer(
+			T::GetNativeCurrencyId::get(),
+			source,
+			dest,
+			Into::<Balance>::into(value).into(),
+			existence_requirement,
+		)
+	}
+
+	fn slash(who: &T::AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance) {
+		Currency::slash(who, value)
+	}
+
+	fn deposit_into_existing(
+		who: &T::AccountId,
+		value: Self::Balance,
+	) -> result::Result<Self::PositiveImbalance, DispatchError> {
+		Currency::deposit_into_existing(who, value)
+	}
+
+	fn deposit_creating(who: &T::AccountId, value: Self::Balance) -> Self::PositiveImbalance {
+		Currency::deposit_creating(who, value)
+	}
+
+	fn withdraw(
+		who: &T::AccountId,
+		value: Self::Balance,
+		reasons: WithdrawReasons,
+		liveness: ExistenceRequirement,
+	) -> result::Result<Self::NegativeImbalance, DispatchError> {
+		Currency::withdraw(who, value, reasons, liveness)
+	}
+
+	fn make_free_balance_be(
+		who: &T::AccountId,
+		balance: Self::Balance,
+	) -> SignedImbalance<Self::Balance, Self::PositiveImbalance> {
+		Currency::make_free_balance_be(who, balance)
+	}
+}
+
+impl<T, Currency> PalletReservableCurrency<T::AccountId> for MoveVmCurrency<T, Currency>
+where
+	T: Config,
+	Currency: PalletReservableCurrency<T::AccountId>,
+	PalletBalanceOf<T::AccountId, Currency>: From<Balance> + Into<Balance>,
+	BalanceOf<T>: From<Balance> + Into<Balance>,
+{
+	fn can_reserve(who: &T::AccountId, value: Self::Balance) -> bool {
+		Currency::can_reserve(who, value)
+	}
+
+	fn slash_reserved(who: &T::AccountId, value: Self::Balance) -> (Self::NegativeImbalance, Self::Balance) {
+		Currency::slash_reserved(who, value)
+	}
+
+	fn reserved_balance(who: &T::AccountId) -> Self::Balance {
+		Currency::reserved_balance(who)
+	}
+
+	fn reserve(who: &T::AccountId, value: Self::Balance) -> DispatchResult {
+		Currency::reserve(who, value)
+	}
+
+	fn unreserve(who: &T::AccountId, value: Self::Balance) -> Self::Balance {
+		Currency::unreserve(who, value)
+	}
+
+	fn repatriate_reserved(
+		slashed: &T::AccountId,
+		beneficiary: &T::AccountId,
+		value: Self::Balance,
+		status: Status,
+	) -> result::Result<Self::Balance, DispatchError> {
+		Currency::repatriate_reserved(slashed, beneficiary, value, status)
+	}
+}
+
+impl<T, Currency> PalletLockableCurrency<T::AccountId> for MoveVmCurrency<T, Currency>
+where
+	T: Config,
+	Currency: PalletLockableCurrency<T::AccountId>,
+	PalletBalanceOf<T::AccountId, Currency>: From<Balance> + Into<Balance>,
+	BalanceOf<T>: From<Balance> + Into<Balance>,
+{
+	type Moment = Currency::Moment;
+	type MaxLocks = Currency::MaxLocks;
+
+	fn set_lock(id: LockIdentifier, who: &T::AccountId, amount: Self::Balance, reasons: WithdrawReasons) {
+		Currency::set_lock(id, who, amount, reasons)
+	}
+
+	fn extend_lock(id: LockIdentifier, who: &T::AccountId, amount: Self::Balance, reasons: WithdrawReasons) {
+		Currency::extend_lock(id, who, amount, reasons)
+	}
+
+	fn remove_lock(id: LockIdentifier, who: &T::AccountId) {
+		Currency::remove_lock(id, who)
+	}
+}
+
 pub struct Currency<T, GetCurrencyId>(marker::PhantomData<T>, marker::PhantomData<GetCurrencyId>);
 
 impl<T, GetCurrencyId> BasicCurrency<T::AccountId> for Currency<T, GetCurrencyId>
@@ -234,6 +234,7 @@ impl Config for Runtime {
 
 pub type NativeCurrency = Currency<Runtime, GetNativeCurrencyId>;
 pub type AdaptedBasicCurrency = BasicCurrencyAdapter<Runtime, PalletBalances, i64, u64>;
+pub type MoveVmNativeCurrency = MoveVmCurrency<Runtime, PalletBalances>;
 
 pub type SignedExtra = module_evm::SetEvmOrigin<Runtime>;
 
@@ -42,11 +42,13 @@ use crate::mock::Erc20HoldingAccount;
 use frame_support::{assert_noop, assert_ok, dispatch::GetDispatchInfo, traits::WithdrawReasons};
 use mock::{
 	alice, bob, deploy_contracts, erc20_address, erc20_address_not_exist, eva, AccountId, AdaptedBasicCurrency,
-	Balances, CouncilAccount, Currencies, DustAccount, ExtBuilder, NativeCurrency, PalletBalances, Runtime,
-	NATIVE_CURRENCY_ID, X_TOKEN_ID,
+	Balances, CouncilAccount, Currencies, DustAccount, ExtBuilder, MoveVmNativeCurrency, NativeCurrency,
+	PalletBalances, Runtime, NATIVE_CURRENCY_ID, X_TOKEN_ID,
 };
 use module_support::mocks::MockAddressMapping;
+use module_support::MoveVmManager;
 use module_support::EVM as EVMTrait;
+use primitives::move_vm::move_currency_of_currency_id;
 use sp_core::H160;
 use sp_runtime::{
 	traits::{BadOrigin, Bounded},
@@ -377,6 +379,63 @@ fn multi_currency_should_work() {
 	});
 }
 
+#[test]
+fn move_vm_manager_should_work() {
+	ExtBuilder::default().one_hundred_for_alice_n_bob().build().execute_with(|| {
+		let x_token = move_currency_of_currency_id(X_TOKEN_ID).unwrap();
+		assert_eq!(<Currencies as MoveVmManager<AccountId>>::currency_id(&x_token), Some(X_TOKEN_ID));
+		assert_eq!(<Currencies as MoveVmManager<AccountId>>::get_native_balance(&alice()), 100);
+		assert_ok!(<Currencies as MoveVmManager<AccountId>>::transfer(&x_token, &alice(), &bob(), 50));
+		assert_eq!(<Currencies as MoveVmManager<AccountId>>::free_balance(&x_token, &alice()), Ok(50));
+		assert_eq!(<Currencies as MoveVmManager<AccountId>>::free_balance(&x_token, &bob()), Ok(150));
+		assert_eq!(Currencies::free_balance(X_TOKEN_ID, &bob()), 150);
+
+		let native = move_currency_of_currency_id(NATIVE_CURRENCY_ID).unwrap();
+		assert_ok!(<Currencies as MoveVmManager<AccountId>>::transfer(&native, &alice(), &bob(), 50));
+		assert_eq!(<Currencies as MoveVmManager<AccountId>>::get_native_balance(&bob()), 150);
+	});
+}
+
+#[test]
+fn move_vm_manager_refuses_unknown_currencies() {
+	ExtBuilder::default().one_hundred_for_alice_n_bob().build().execute_with(|| {
+		let mut unknown = move_currency_of_currency_id(X_TOKEN_ID).unwrap();
+		unknown[0] = 1;
+		assert_eq!(<Currencies as MoveVmManager<AccountId>>::currency_id(&unknown), None);
+		assert_noop!(
+			<Currencies as MoveVmManager<AccountId>>::transfer(&unknown, &alice(), &bob(), 50),
+			Error::<Runtime>::UnknownMoveCurrency
+		);
+		assert_eq!(
+			<Currencies as MoveVmManager<AccountId>>::free_balance(&unknown, &alice()),
+			Err(Error::<Runtime>::UnknownMoveCurrency.into())
+		);
+	});
+}
+
+#[test]
+fn move_vm_currency_should_work() {
+	ExtBuilder::default().one_hundred_for_alice_n_bob().build().execute_with(|| {
+		assert_eq!(<MoveVmNativeCurrency as PalletCurrency<AccountId>>::free_balance(&alice()), 100);
+		assert_ok!(<MoveVmNativeCurrency as PalletCurrency<AccountId>>::transfer(
+			&alice(),
+			&bob(),
+			50,
+			ExistenceRequirement::KeepAlive
+		));
+		assert_eq!(<MoveVmNativeCurrency as PalletCurrency<AccountId>>::free_balance(&alice()), 50);
+		assert_eq!(<MoveVmNativeCurrency as PalletCurrency<AccountId>>::free_balance(&bob()), 150);
+		assert!(<MoveVmNativeCurrency as PalletCurrency<AccountId>>::transfer(
+			&alice(),
+			&bob(),
+			49,
+			ExistenceRequirement::KeepAlive
+		)
+		.is_err());
+		assert_eq!(NativeCurrency::free_balance(&alice()), 50);
+	});
+}
+
 #[test]
 fn multi_currency_extended_should_work() {
 	ExtBuilder::default().one_hundred_for_alice_n_bob().build().execute_with(|| {
@@ -317,14 +317,7 @@ pub mod module {
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T> {
 		#[allow(clippy::type_complexity)]
-		pub collaterals_params: Vec<(
-			CurrencyId,
-			Option<Rate>,
-			Option<Ratio>,
-			Option<Rate>,
-			Option<Ratio>,
-			Balance,
-		)>,
+		pub collaterals_params: Vec<(CurrencyId, Option<Ratio>, Option<Rate>, Option<Ratio>, Balance)>,
 		pub _phantom: PhantomData<T>,
 	}
 
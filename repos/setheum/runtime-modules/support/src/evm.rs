@@ -109,6 +109,8 @@ pub trait EVMStateRentTrait {
 	fn claim_rent_deposit(who: &H160, metadata: Vec<u8>) -> DispatchResult;
 }
 
-// Erc20InfoMapping removed
+/// The `CurrencyIdMapping` under the name used by the DEX, which maps LP tokens to ERC20
+/// addresses through it.
+pub use self::CurrencyIdMapping as Erc20InfoMapping;
 
 // Limits removed
@@ -80,7 +80,7 @@ pub trait Incentives<AccountId, CurrencyId, Balance> {
 	fn do_withdraw_share(who: &AccountId, lp_currency_id: CurrencyId, amount: Balance) -> DispatchResult;
 }
 
-#[cfg(feature = "std")]
+/// For runtimes without liquidity incentives, staked shares stay with their owner.
 impl<AccountId, CurrencyId, Balance> Incentives<AccountId, CurrencyId, Balance> for () {
 	fn do_deposit_share(_: &AccountId, _: CurrencyId, _: Balance) -> DispatchResult {
 		Ok(())
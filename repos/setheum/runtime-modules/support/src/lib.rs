@@ -72,6 +72,7 @@ pub mod incentives;
 pub mod launchpad;
 pub mod migration;
 pub mod mocks;
+pub mod move_vm;
 pub mod swap;
 pub mod swap_legacy;
 
@@ -83,6 +84,7 @@ pub use crate::swap::*;
 pub use crate::evm::*;
 pub use crate::incentives::*;
 pub use crate::migration::*;
+pub use crate::move_vm::*;
 
 pub type Price = FixedU128;
 pub type ExchangeRate = FixedU128;
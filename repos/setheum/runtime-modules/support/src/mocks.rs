@@ -113,6 +113,8 @@ impl CurrencyIdMapping for MockCurrencyIdMapping {
 	}
 }
 
+pub type MockErc20InfoMapping = MockCurrencyIdMapping;
+
 #[cfg(feature = "std")]
 impl<AccountId, Balance: Default + Copy, NegativeImbalance: Imbalance<Balance>>
 	TransactionPayment<AccountId, Balance, NegativeImbalance> for ()
@@ -1,8 +1,56 @@
-#![cfg_attr(not(feature = "std"), no_std)]
-use sp_std::prelude::*;
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
 
-/// Precompiles and helper functions for MoveVM integration
-/// Native Tokens (SEU, SEUSD) unwrapped integration traits
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use primitives::{move_vm::MoveAddress, Balance, CurrencyId};
+use sp_runtime::{DispatchError, DispatchResult};
+
+/// Balance access exposed to the Move VM.
+///
+/// Implemented by `module_currencies`, whose `MoveVmCurrency` adapter is the `Currency` of
+/// `pallet_move`. Move refers to currencies by their Move currency identifiers, see
+/// `primitives::move_vm`, which are resolved to a `CurrencyId` before going through
+/// `MultiCurrency`.
 pub trait MoveVmManager<AccountId> {
-    fn get_native_balance(who: &AccountId) -> u128;
+	/// The currency a Move currency identifier refers to, if any.
+	fn currency_id(currency: &MoveAddress) -> Option<CurrencyId>;
+	/// Free balance of the native currency.
+	fn get_native_balance(who: &AccountId) -> Balance;
+	/// Free balance of the currency identified by `currency`.
+	fn free_balance(currency: &MoveAddress, who: &AccountId) -> Result<Balance, DispatchError>;
+	/// Transfer `amount` of the currency identified by `currency` from `from` to `to`.
+	fn transfer(currency: &MoveAddress, from: &AccountId, to: &AccountId, amount: Balance) -> DispatchResult;
 }
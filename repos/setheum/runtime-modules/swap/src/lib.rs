@@ -55,6 +55,7 @@ use sp_runtime::{
 };
 use sp_std::{prelude::*, vec};
 
+pub mod migration;
 mod mock;
 mod tests;
 pub mod weights;
@@ -71,7 +72,7 @@ pub struct ProvisioningParameters<Balance, BlockNumber> {
 	target_provision: (Balance, Balance),
 /// accumulated provision amount for this Provisioning trading pair.
 	accumulated_provision: (Balance, Balance),
-/// accumulated provision amount for this Provisioning trading pair.
+/// trading fee rate of the trading pair once it is Enabled.
 	trading_fee: (u32, u32),
 /// The number of block that status can be converted to Enabled.
 	not_before: BlockNumber,
@@ -97,6 +98,9 @@ impl<Balance, BlockNumber> Default for TradingPairStatus<Balance, BlockNumber> {
 	}
 }
 
+const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+pub(crate) const LOG_TARGET: &str = "module-swap";
+
 #[frame_support::pallet]
 pub mod module {
 	use super::*;
@@ -108,7 +112,7 @@ pub mod module {
 /// Currency for transfer currencies
 		type Currency: MultiCurrencyExtended<Self::AccountId, CurrencyId = CurrencyId, Balance = Balance>;
 
-/// Trading fee rate
+/// Default trading fee rate, used for trading pairs without their own.
 /// The first item of the tuple is the numerator of the fee rate, second
 /// item is the denominator, fee_rate = numerator // denominator,
 /// use (u32, u32) over `Rate` type to minimize internal division
@@ -193,6 +197,8 @@ pub mod module {
 		NotAllowedRefund,
 /// Cannot swap
 		CannotSwap,
+/// The trading fee rate is not less than 100%
+		InvalidTradingFee,
 	}
 
 	#[pallet::event]
@@ -257,6 +263,11 @@ pub mod module {
 			accumulated_provision_0: Balance,
 			accumulated_provision_1: Balance,
 		},
+/// Trading fee rate of trading pair updated.
+		TradingFeeUpdated {
+			trading_pair: TradingPair,
+			trading_fee: (u32, u32),
+		},
 	}
 
 /// Liquidity pool for TradingPair.
@@ -291,6 +302,12 @@ pub mod module {
 	pub type InitialShareExchangeRates<T: Config> =
 		StorageMap<_, Twox64Concat, TradingPair, (ExchangeRate, ExchangeRate), ValueQuery>;
 
+/// Trading fee rate of TradingPair which is not Provisioning, `GetExchangeFee` if not set.
+///
+/// TradingFees: map TradingPair => Option<(u32, u32)>
+	#[pallet::storage]
+	pub type TradingFees<T: Config> = StorageMap<_, Twox64Concat, TradingPair, (u32, u32), OptionQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(frame_support::DefaultNoBound)]
 	pub struct GenesisConfig<T: Config> {
@@ -311,6 +328,7 @@ pub mod module {
 							min_contribution: *min_contribution,
 							target_provision: *target_provision,
 							accumulated_provision: Default::default(),
+							trading_fee: T::GetExchangeFee::get(),
 							not_before: *not_before,
 						}),
 					);
@@ -347,6 +365,7 @@ pub mod module {
 	}
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
@@ -560,6 +579,7 @@ pub mod module {
 					min_contribution,
 					target_provision,
 					accumulated_provision: Default::default(),
+					trading_fee: T::GetExchangeFee::get(),
 					not_before,
 				}),
 			);
@@ -604,6 +624,7 @@ pub mod module {
 							min_contribution,
 							target_provision,
 							accumulated_provision: provisioning_parameters.accumulated_provision,
+							trading_fee: provisioning_parameters.trading_fee,
 							not_before,
 						}),
 					);
@@ -671,6 +692,7 @@ pub mod module {
 
 // update trading_pair to Enabled status
 					TradingPairStatuses::<T>::insert(trading_pair, TradingPairStatus::<_, _>::Enabled);
+					TradingFees::<T>::insert(trading_pair, provisioning_parameters.trading_fee);
 
 // record initial exchange rate so that founders can use it to calculate their own shares
 					InitialShareExchangeRates::<T>::insert(
@@ -712,6 +734,7 @@ pub mod module {
 							&& provisioning_parameters.accumulated_provision.1.is_zero(),
 						Error::<T>::StillProvisioning
 					);
+					TradingFees::<T>::insert(trading_pair, provisioning_parameters.trading_fee);
 				}
 				TradingPairStatus::<_, _>::Enabled => return Err(Error::<T>::AlreadyEnabled.into()),
 			}
@@ -836,6 +859,39 @@ pub mod module {
 
 			Ok(())
 		}
+
+/// Set the trading fee rate of a trading pair.
+///
+/// - `trading_fee`: fee rate as (numerator, denominator), must be less than 100%.
+		#[pallet::call_index(13)]
+		#[pallet::weight((<T as Config>::WeightInfo::set_trading_fee(), DispatchClass::Operational))]
+		pub fn set_trading_fee(
+			origin: OriginFor<T>,
+			currency_id_a: CurrencyId,
+			currency_id_b: CurrencyId,
+			trading_fee: (u32, u32),
+		) -> DispatchResult {
+			T::ListingOrigin::ensure_origin(origin)?;
+			let trading_pair =
+				TradingPair::from_currency_ids(currency_id_a, currency_id_b).ok_or(Error::<T>::InvalidCurrencyId)?;
+			ensure!(trading_fee.0 < trading_fee.1, Error::<T>::InvalidTradingFee);
+
+			match Self::trading_pair_statuses(trading_pair) {
+				TradingPairStatus::<_, _>::Provisioning(provisioning_parameters) => {
+					TradingPairStatuses::<T>::insert(
+						trading_pair,
+						TradingPairStatus::Provisioning(ProvisioningParameters {
+							trading_fee,
+							..provisioning_parameters
+						}),
+					);
+				}
+				_ => TradingFees::<T>::insert(trading_pair, trading_fee),
+			}
+
+			Self::deposit_event(Event::TradingFeeUpdated { trading_pair, trading_fee });
+			Ok(())
+		}
 	}
 }
 
@@ -844,6 +900,11 @@ impl<T: Config> Pallet<T> {
 		T::PalletId::get().into_account_truncating()
 	}
 
+/// Trading fee rate charged when swapping through the trading pair.
+	pub fn trading_fee(trading_pair: &TradingPair) -> (u32, u32) {
+		TradingFees::<T>::get(trading_pair).unwrap_or_else(T::GetExchangeFee::get)
+	}
+
 	fn try_mutate_liquidity_pool<R, E>(
 		trading_pair: &TradingPair,
 		f: impl FnOnce((&mut Balance, &mut Balance)) -> sp_std::result::Result<R, E>,
@@ -1178,11 +1239,15 @@ impl<T: Config> Pallet<T> {
 	}
 
 /// Get how much target amount will be got for specific supply amount.
-	fn get_target_amount(supply_pool: Balance, target_pool: Balance, supply_amount: Balance) -> Balance {
+	fn get_target_amount(
+		supply_pool: Balance,
+		target_pool: Balance,
+		supply_amount: Balance,
+		(fee_numerator, fee_denominator): (u32, u32),
+	) -> Balance {
 		if supply_amount.is_zero() || supply_pool.is_zero() || target_pool.is_zero() {
 			Zero::zero()
 		} else {
-			let (fee_numerator, fee_denominator) = T::GetExchangeFee::get();
 			let supply_amount_with_fee: U256 =
 				U256::from(supply_amount).saturating_mul(U256::from(fee_denominator.saturating_sub(fee_numerator)));
 			let numerator: U256 = supply_amount_with_fee.saturating_mul(U256::from(target_pool));
@@ -1198,11 +1263,15 @@ impl<T: Config> Pallet<T> {
 	}
 
 /// Get how much supply amount will be paid for specific target amount.
-	fn get_supply_amount(supply_pool: Balance, target_pool: Balance, target_amount: Balance) -> Balance {
+	fn get_supply_amount(
+		supply_pool: Balance,
+		target_pool: Balance,
+		target_amount: Balance,
+		(fee_numerator, fee_denominator): (u32, u32),
+	) -> Balance {
 		if target_amount.is_zero() || supply_pool.is_zero() || target_pool.is_zero() {
 			Zero::zero()
 		} else {
-			let (fee_numerator, fee_denominator) = T::GetExchangeFee::get();
 			let numerator: U256 = U256::from(supply_pool)
 				.saturating_mul(U256::from(target_amount))
 				.saturating_mul(U256::from(fee_denominator));
@@ -1244,7 +1313,12 @@ impl<T: Config> Pallet<T> {
 				!supply_pool.is_zero() && !target_pool.is_zero(),
 				Error::<T>::InsufficientLiquidity
 			);
-			let target_amount = Self::get_target_amount(supply_pool, target_pool, target_amounts[i]);
+			let target_amount = Self::get_target_amount(
+				supply_pool,
+				target_pool,
+				target_amounts[i],
+				Self::trading_fee(&trading_pair),
+			);
 			ensure!(!target_amount.is_zero(), Error::<T>::ZeroTargetAmount);
 
 			target_amounts[i + 1] = target_amount;
@@ -1280,7 +1354,12 @@ impl<T: Config> Pallet<T> {
 				!supply_pool.is_zero() && !target_pool.is_zero(),
 				Error::<T>::InsufficientLiquidity
 			);
-			let supply_amount = Self::get_supply_amount(supply_pool, target_pool, supply_amounts[i]);
+			let supply_amount = Self::get_supply_amount(
+				supply_pool,
+				target_pool,
+				supply_amounts[i],
+				Self::trading_fee(&trading_pair),
+			);
 			ensure!(!supply_amount.is_zero(), Error::<T>::ZeroSupplyAmount);
 
 			supply_amounts[i - 1] = supply_amount;
@@ -0,0 +1,151 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use frame_support::{
+	pallet_prelude::{StorageVersion, Weight},
+	traits::{Get, OnRuntimeUpgrade},
+};
+use frame_system::pallet_prelude::BlockNumberFor;
+use log::info;
+use parity_scale_codec::Decode;
+use primitives::Balance;
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use sp_std::vec::Vec;
+
+/// Moves the DEX over from `module_swap_legacy`, which charged the same fee on every trading pair.
+pub mod v1 {
+	use super::*;
+	use crate::{Config, Pallet, ProvisioningParameters, TradingFees, TradingPairStatus, TradingPairStatuses, LOG_TARGET};
+	#[cfg(feature = "try-runtime")]
+	use module_support::ensure_storage_version;
+	#[cfg(feature = "try-runtime")]
+	use parity_scale_codec::Encode;
+
+	const OLD_VERSION: u16 = 0;
+	const NEW_VERSION: u16 = 1;
+
+	#[derive(Decode)]
+	pub struct ProvisioningParametersLegacy<Balance, BlockNumber> {
+		pub min_contribution: (Balance, Balance),
+		pub target_provision: (Balance, Balance),
+		pub accumulated_provision: (Balance, Balance),
+		pub not_before: BlockNumber,
+	}
+
+	#[derive(Decode)]
+	pub enum TradingPairStatusLegacy<Balance, BlockNumber> {
+		Disabled,
+		Provisioning(ProvisioningParametersLegacy<Balance, BlockNumber>),
+		Enabled,
+	}
+
+	/// Migrates the storage of `module_swap_legacy` deployed under the same pallet name.
+	///
+	/// `LiquidityPool`, `ProvisioningPool` and `InitialShareExchangeRates` are encoded the same
+	/// way by both pallets and carry over as they are. `TradingPairStatuses` is translated and
+	/// every trading pair is assigned `LegacyExchangeFee`, the fee the legacy pallet charged.
+	pub struct Migration<T, LegacyExchangeFee>(PhantomData<(T, LegacyExchangeFee)>);
+
+	impl<T: Config, LegacyExchangeFee: Get<(u32, u32)>> OnRuntimeUpgrade for Migration<T, LegacyExchangeFee> {
+		fn on_runtime_upgrade() -> Weight {
+			if StorageVersion::get::<Pallet<T>>() != StorageVersion::new(OLD_VERSION) {
+				info!(target: LOG_TARGET, "Skipping migrations from STORAGE_VERSION 0 to 1 for pallet swap.");
+				return T::DbWeight::get().reads(1);
+			};
+
+			let mut reads = 1; // StorageVersion
+			let mut writes = 1; // StorageVersion
+			info!(target: LOG_TARGET, "Running migration from STORAGE_VERSION 0 to 1 for pallet swap.");
+
+			let trading_fee = LegacyExchangeFee::get();
+			TradingPairStatuses::<T>::translate::<TradingPairStatusLegacy<Balance, BlockNumberFor<T>>, _>(
+				|trading_pair, status| {
+					reads += 1;
+					writes += 1;
+					let status = match status {
+						TradingPairStatusLegacy::Provisioning(parameters) => {
+							TradingPairStatus::Provisioning(ProvisioningParameters {
+								min_contribution: parameters.min_contribution,
+								target_provision: parameters.target_provision,
+								accumulated_provision: parameters.accumulated_provision,
+								trading_fee,
+								not_before: parameters.not_before,
+							})
+						}
+						TradingPairStatusLegacy::Disabled => TradingPairStatus::Disabled,
+						TradingPairStatusLegacy::Enabled => TradingPairStatus::Enabled,
+					};
+					if !matches!(status, TradingPairStatus::Provisioning(_)) {
+						TradingFees::<T>::insert(trading_pair, trading_fee);
+						writes += 1;
+					}
+					Some(status)
+				},
+			);
+
+			StorageVersion::new(NEW_VERSION).put::<Pallet<T>>();
+			info!(target: LOG_TARGET, "Finished migration from STORAGE_VERSION 0 to 1 for pallet swap.");
+
+			T::DbWeight::get().reads(reads) + T::DbWeight::get().writes(writes)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+			ensure_storage_version::<Pallet<T>>(OLD_VERSION)?;
+
+			Ok((TradingPairStatuses::<T>::iter_keys().count() as u32).encode())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+			ensure_storage_version::<Pallet<T>>(NEW_VERSION)?;
+
+			let trading_pairs = u32::decode(&mut &state[..]).map_err(|_| "Invalid pre upgrade state")?;
+			let mut migrated = 0;
+			for (trading_pair, status) in TradingPairStatuses::<T>::iter() {
+				migrated += 1;
+				frame_support::ensure!(
+					matches!(status, TradingPairStatus::Provisioning(_)) || TradingFees::<T>::contains_key(trading_pair),
+					"Trading pair without trading fee"
+				);
+			}
+			frame_support::ensure!(migrated == trading_pairs, "Trading pairs lost in migration");
+			Ok(())
+		}
+	}
+}
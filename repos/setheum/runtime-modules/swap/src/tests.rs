@@ -38,7 +38,7 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, parameter_types, traits::OnRuntimeUpgrade};
 use mock::{
 };
 use module_support::{Swap, SwapError};
@@ -82,6 +82,7 @@ fn list_provisioning_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (0, 0),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -189,6 +190,7 @@ fn update_provisioning_parameters_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (0, 0),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -207,6 +209,7 @@ fn update_provisioning_parameters_work() {
 				min_contribution: (2_000_000_000_000u128, 0),
 				target_provision: (3_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (0, 0),
+				trading_fee: (1, 100),
 				not_before: 50,
 			})
 		);
@@ -278,6 +281,7 @@ fn enable_provisioning_without_provision_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (0, 0),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -317,6 +321,7 @@ fn end_provisioning_trading_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (0, 0),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -351,6 +356,7 @@ fn end_provisioning_trading_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (1_000_000_000_000u128, 2_000_000_000_000u128),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -450,6 +456,7 @@ fn abort_provisioning_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (1_000_000_000_000u128, 1_000_000_000_000u128),
+				trading_fee: (1, 100),
 				not_before: 1000,
 			})
 		);
@@ -462,6 +469,7 @@ fn abort_provisioning_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
+				trading_fee: (1, 100),
 				not_before: 1000,
 			})
 		);
@@ -490,6 +498,7 @@ fn abort_provisioning_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
+				trading_fee: (1, 100),
 				not_before: 1000,
 			})
 		);
@@ -757,6 +766,7 @@ fn add_provision_work() {
 				min_contribution: (5_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000_000u128, 1_000_000_000_000_000u128),
 				accumulated_provision: (0, 0),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -775,6 +785,7 @@ fn add_provision_work() {
 				min_contribution: (5_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000_000u128, 1_000_000_000_000_000u128),
 				accumulated_provision: (5_000_000_000_000u128, 0),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -885,26 +896,110 @@ fn get_liquidity_work() {
 #[test]
 fn get_target_amount_work() {
 	ExtBuilder::default().build().execute_with(|| {
-		assert_eq!(SwapModule::get_target_amount(10000, 0, 1000), 0);
-		assert_eq!(SwapModule::get_target_amount(0, 20000, 1000), 0);
-		assert_eq!(SwapModule::get_target_amount(10000, 20000, 0), 0);
-		assert_eq!(SwapModule::get_target_amount(10000, 1, 1000000), 0);
-		assert_eq!(SwapModule::get_target_amount(10000, 20000, 10000), 9949);
-		assert_eq!(SwapModule::get_target_amount(10000, 20000, 1000), 1801);
+		assert_eq!(SwapModule::get_target_amount(10000, 0, 1000, (1, 100)), 0);
+		assert_eq!(SwapModule::get_target_amount(0, 20000, 1000, (1, 100)), 0);
+		assert_eq!(SwapModule::get_target_amount(10000, 20000, 0, (1, 100)), 0);
+		assert_eq!(SwapModule::get_target_amount(10000, 1, 1000000, (1, 100)), 0);
+		assert_eq!(SwapModule::get_target_amount(10000, 20000, 10000, (1, 100)), 9949);
+		assert_eq!(SwapModule::get_target_amount(10000, 20000, 1000, (1, 100)), 1801);
 	});
 }
 
 #[test]
 fn get_supply_amount_work() {
 	ExtBuilder::default().build().execute_with(|| {
-		assert_eq!(SwapModule::get_supply_amount(10000, 0, 1000), 0);
-		assert_eq!(SwapModule::get_supply_amount(0, 20000, 1000), 0);
-		assert_eq!(SwapModule::get_supply_amount(10000, 20000, 0), 0);
-		assert_eq!(SwapModule::get_supply_amount(10000, 1, 1), 0);
-		assert_eq!(SwapModule::get_supply_amount(10000, 20000, 9949), 9999);
-		assert_eq!(SwapModule::get_target_amount(10000, 20000, 9999), 9949);
-		assert_eq!(SwapModule::get_supply_amount(10000, 20000, 1801), 1000);
-		assert_eq!(SwapModule::get_target_amount(10000, 20000, 1000), 1801);
+		assert_eq!(SwapModule::get_supply_amount(10000, 0, 1000, (1, 100)), 0);
+		assert_eq!(SwapModule::get_supply_amount(0, 20000, 1000, (1, 100)), 0);
+		assert_eq!(SwapModule::get_supply_amount(10000, 20000, 0, (1, 100)), 0);
+		assert_eq!(SwapModule::get_supply_amount(10000, 1, 1, (1, 100)), 0);
+		assert_eq!(SwapModule::get_supply_amount(10000, 20000, 9949, (1, 100)), 9999);
+		assert_eq!(SwapModule::get_target_amount(10000, 20000, 9999, (1, 100)), 9949);
+		assert_eq!(SwapModule::get_supply_amount(10000, 20000, 1801, (1, 100)), 1000);
+		assert_eq!(SwapModule::get_target_amount(10000, 20000, 1000, (1, 100)), 1801);
+	});
+}
+
+#[test]
+fn set_trading_fee_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			SwapModule::set_trading_fee(RuntimeOrigin::signed(ALICE), SEUSD, WBTC, (3, 1000)),
+			BadOrigin
+		);
+		assert_noop!(
+			SwapModule::set_trading_fee(RuntimeOrigin::signed(ListingOrigin::get()), SEUSD, SEUSD, (3, 1000)),
+			Error::<Runtime>::InvalidCurrencyId
+		);
+		assert_noop!(
+			SwapModule::set_trading_fee(RuntimeOrigin::signed(ListingOrigin::get()), SEUSD, WBTC, (1000, 1000)),
+			Error::<Runtime>::InvalidTradingFee
+		);
+		assert_noop!(
+			SwapModule::set_trading_fee(RuntimeOrigin::signed(ListingOrigin::get()), SEUSD, WBTC, (0, 0)),
+			Error::<Runtime>::InvalidTradingFee
+		);
+
+		assert_eq!(SwapModule::trading_fee(&SEUSDWBTCPair::get()), (1, 100));
+		assert_ok!(SwapModule::set_trading_fee(
+			RuntimeOrigin::signed(ListingOrigin::get()),
+			SEUSD,
+			WBTC,
+			(3, 1000)
+		));
+		assert_eq!(TradingFees::<Runtime>::get(SEUSDWBTCPair::get()), Some((3, 1000)));
+		assert_eq!(SwapModule::trading_fee(&SEUSDWBTCPair::get()), (3, 1000));
+		System::assert_last_event(RuntimeEvent::SwapModule(crate::Event::TradingFeeUpdated {
+			trading_pair: SEUSDWBTCPair::get(),
+			trading_fee: (3, 1000),
+		}));
+	});
+}
+
+#[test]
+fn provisioning_trading_fee_applies_once_enabled() {
+	ExtBuilder::default().build().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_ok!(SwapModule::list_provisioning(
+			RuntimeOrigin::signed(ListingOrigin::get()),
+			SEUSD,
+			WBTC,
+			1_000_000_000_000u128,
+			1_000_000_000_000u128,
+			5_000_000_000_000u128,
+			2_000_000_000_000u128,
+			10,
+		));
+		assert_ok!(SwapModule::set_trading_fee(
+			RuntimeOrigin::signed(ListingOrigin::get()),
+			WBTC,
+			SEUSD,
+			(3, 1000)
+		));
+		assert_eq!(
+			SwapModule::trading_pair_statuses(SEUSDWBTCPair::get()),
+			TradingPairStatus::<_, _>::Provisioning(ProvisioningParameters {
+				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
+				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
+				accumulated_provision: (0, 0),
+				trading_fee: (3, 1000),
+				not_before: 10,
+			})
+		);
+		assert_eq!(TradingFees::<Runtime>::get(SEUSDWBTCPair::get()), None);
+
+		assert_ok!(SwapModule::enable_trading_pair(
+			RuntimeOrigin::signed(ListingOrigin::get()),
+			SEUSD,
+			WBTC
+		));
+		assert_eq!(SwapModule::trading_fee(&SEUSDWBTCPair::get()), (3, 1000));
+
+		LiquidityPool::<Runtime>::insert(SEUSDWBTCPair::get(), (10000, 20000));
+		let path = vec![SEUSDWBTCPair::get().first(), SEUSDWBTCPair::get().second()];
+		assert_eq!(SwapModule::get_target_amounts(&path, 1000), Ok(vec![1000, 1813]));
 	});
 }
 
@@ -955,7 +1050,8 @@ fn calculate_amount_for_big_number_work() {
 			SwapModule::get_supply_amount(
 				171_000_000_000_000_000_000_000,
 				56_000_000_000_000_000_000_000,
-				1_000_000_000_000_000_000_000
+				1_000_000_000_000_000_000_000,
+				(1, 100)
 			),
 			3_140_495_867_768_595_041_323
 		);
@@ -963,7 +1059,8 @@ fn calculate_amount_for_big_number_work() {
 			SwapModule::get_target_amount(
 				171_000_000_000_000_000_000_000,
 				56_000_000_000_000_000_000_000,
-				3_140_495_867_768_595_041_323
+				3_140_495_867_768_595_041_323,
+				(1, 100)
 			),
 			1_000_000_000_000_000_000_000
 		);
@@ -1652,6 +1749,7 @@ fn get_liquidity_token_address_work() {
 				min_contribution: (1_000_000_000_000u128, 1_000_000_000_000u128),
 				target_provision: (5_000_000_000_000u128, 2_000_000_000_000u128),
 				accumulated_provision: (0, 0),
+				trading_fee: (1, 100),
 				not_before: 10,
 			})
 		);
@@ -1722,3 +1820,43 @@ fn specific_joint_swap_work() {
 			);
 		});
 }
+
+parameter_types! {
+	pub const LegacyExchangeFee: (u32, u32) = (3, 1000);
+}
+
+#[test]
+fn migrate_from_swap_legacy_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let provisioning_pair = TradingPair::from_currency_ids(SEUSD, SEU).unwrap();
+		StorageVersion::new(0).put::<SwapModule>();
+		// `TradingPairStatus::Provisioning` as encoded by the legacy pallet, without a trading fee.
+		frame_support::storage::unhashed::put_raw(
+			&TradingPairStatuses::<Runtime>::hashed_key_for(provisioning_pair),
+			&(1u8, (1u128, 1u128), (5u128, 2u128), (0u128, 0u128), 10u64).encode(),
+		);
+		TradingPairStatuses::<Runtime>::insert(SEUSDWBTCPair::get(), TradingPairStatus::<_, _>::Enabled);
+		LiquidityPool::<Runtime>::insert(SEUSDWBTCPair::get(), (1000, 2000));
+
+		migration::v1::Migration::<Runtime, LegacyExchangeFee>::on_runtime_upgrade();
+
+		assert_eq!(StorageVersion::get::<SwapModule>(), StorageVersion::new(1));
+		assert_eq!(
+			SwapModule::trading_pair_statuses(provisioning_pair),
+			TradingPairStatus::<_, _>::Provisioning(ProvisioningParameters {
+				min_contribution: (1, 1),
+				target_provision: (5, 2),
+				accumulated_provision: (0, 0),
+				trading_fee: (3, 1000),
+				not_before: 10,
+			})
+		);
+		assert_eq!(TradingFees::<Runtime>::get(provisioning_pair), None);
+		assert_eq!(
+			SwapModule::trading_pair_statuses(SEUSDWBTCPair::get()),
+			TradingPairStatus::<_, _>::Enabled
+		);
+		assert_eq!(SwapModule::trading_fee(&SEUSDWBTCPair::get()), (3, 1000));
+		assert_eq!(SwapModule::liquidity_pool(SEUSDWBTCPair::get()), (1000, 2000));
+	});
+}
@@ -60,6 +60,7 @@ pub trait WeightInfo {
 	fn swap_with_exact_target(u: u32, ) -> Weight;
 	fn refund_provision() -> Weight;
 	fn abort_provisioning() -> Weight;
+	fn set_trading_fee() -> Weight;
 }
 
 /// Weights for swap_module using the Setheum node and recommended hardware.
@@ -148,6 +149,11 @@ impl<T: frame_system::Config> WeightInfo for SetheumWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(5 as u64))
 			.saturating_add(T::DbWeight::get().writes(6 as u64))
 	}
+	fn set_trading_fee() -> Weight {
+		Weight::from_parts(12_104_000, 0)
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
 }
 
 // For backwards compatibility and tests
@@ -235,4 +241,9 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(5 as u64))
 			.saturating_add(RocksDbWeight::get().writes(6 as u64))
 	}
+	fn set_trading_fee() -> Weight {
+		Weight::from_parts(12_104_000, 0)
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
 }
@@ -63,7 +63,7 @@ where
 	AccountId: Debug + Clone,
 	AddressMapping: AddressMappingT<AccountId>,
 	CurrencyIdMapping: CurrencyIdMappingT,
-	Dex: SwapManager<AccountId, CurrencyId, Balance>,
+	Dex: SwapManager<AccountId, Balance, CurrencyId>,
 {
 	fn execute(handle: &mut impl PrecompileHandle) -> result::Result<PrecompileOutput, PrecompileFailure> {
 		let input = Input::<Action, AccountId, AddressMapping, CurrencyIdMapping>::new(handle.input());
@@ -266,6 +266,7 @@ where
 					remove_share,
 					min_withdrawn_a,
 					min_withdrawn_b,
+					false,
 				)
 				.map_err(|e| {
 					let err_msg: &str = e.into();
@@ -435,22 +435,23 @@ ord_parameter_types! {
 
 parameter_types! {
 	pub const GetExchangeFee: (u32, u32) = (1, 100);
-	pub const GetStableCurrencyExchangeFee: (u32, u32) = (0, 100);
 	pub const TradingPathLimit: u32 = 3;
+	pub const ExtendedProvisioningBlocks: BlockNumber = 2000;
 	pub const DEXPalletId: PalletId = PalletId(*b"edf/swap");
 }
 
-impl swap_legacy_module::Config for Test {
-	type Event = Event;
+impl module_swap::Config for Test {
+	type RuntimeEvent = Event;
 	type Currency = Tokens;
-	type StableCurrencyIds = StableCurrencyIds;
 	type GetExchangeFee = GetExchangeFee;
-	type GetStableCurrencyExchangeFee = GetStableCurrencyExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
 	type PalletId = DEXPalletId;
-	type CurrencyIdMapping = EvmCurrencyIdMapping;
+	type Erc20InfoMapping = EvmCurrencyIdMapping;
 	type WeightInfo = ();
+	type Incentives = ();
 	type ListingOrigin = EnsureSignedBy<ListingOrigin, AccountId>;
+	type ExtendedProvisioningBlocks = ExtendedProvisioningBlocks;
+	type OnLiquidityPoolUpdated = ();
 }
 
 pub type AdaptedBasicCurrency = module_currencies::BasicCurrencyAdapter<Test, Balances, Amount, BlockNumber>;
@@ -479,7 +480,7 @@ pub type ScheduleCallPrecompile = crate::ScheduleCallPrecompile<
 	OriginCaller,
 	Test,
 >;
-pub type DexPrecompile = crate::DexPrecompile<AccountId, MockAddressMapping, EvmCurrencyIdMapping, SwapLegacyModule>;
+pub type DexPrecompile = crate::DexPrecompile<AccountId, MockAddressMapping, EvmCurrencyIdMapping, SwapModule>;
 
 parameter_types! {
 	pub NetworkContractSource: H160 = alice_evm_addr();
@@ -551,7 +552,7 @@ impl module_prices::Config for Test {
 	type SetUSDFixedPrice = SetUSDFixedPrice;
 	type SetterFixedPrice = SetterFixedPrice;
 	type LockOrigin = EnsureSignedBy<One, AccountId>;
-	type DEX = SwapLegacyModule;
+	type DEX = SwapModule;
 	type Currency = Currencies;
 	type CurrencyIdMapping = EvmCurrencyIdMapping;
 	type WeightInfo = ();
@@ -638,7 +639,7 @@ frame_support::construct_runtime!(
 		Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>},
 		Utility: pallet_utility::{Pallet, Call, Event},
 		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
-		SwapLegacyModule: swap_legacy_module::{Pallet, Storage, Call, Event<T>, Config<T>},
+		SwapModule: module_swap::{Pallet, Storage, Call, Event<T>, Config<T>},
 		ModuleEVM: module_evm::{Pallet, Config<T>, Call, Storage, Event<T>},
 	}
 );
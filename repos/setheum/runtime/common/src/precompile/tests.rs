@@ -25,7 +25,7 @@ use crate::precompile::{
 	mock::{
 		alice, alice_evm_addr, bob, bob_evm_addr, erc20_address_not_exists, get_task_id, lp_setm_setusd_evm_address,
 		new_test_ext, run_to_block, serp_evm_address, setm_evm_address, setusd_evm_address, Balances, DexPrecompile,
-		Event as TestEvent, MultiCurrencyPrecompile, Oracle, OraclePrecompile, Origin, Price, SwapLegacyModule,
+		Event as TestEvent, MultiCurrencyPrecompile, Oracle, OraclePrecompile, Origin, Price, SwapModule,
 	},
 	schedule_call::TaskInfo,
 };
@@ -579,7 +579,7 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
 // fn dex_precompile_get_liquidity_should_work() {
 // 	new_test_ext().execute_with(|| {
 
-// 		assert_ok!(SwapLegacyModule::add_liquidity(
+// 		assert_ok!(SwapModule::add_liquidity(
 // 			Origin::signed(ALICE),
 // 			SEUSD,
 // 			1_000,
@@ -616,7 +616,7 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
 // fn dex_precompile_get_liquidity_token_address_should_work() {
 // 	new_test_ext().execute_with(|| {
 
-// 		assert_ok!(SwapLegacyModule::add_liquidity(
+// 		assert_ok!(SwapModule::add_liquidity(
 // 			Origin::signed(ALICE),
 // 			SEUSD,
 // 			1_000,
@@ -662,7 +662,7 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
 // fn dex_precompile_get_swap_target_amount_should_work() {
 // 	new_test_ext().execute_with(|| {
 
-// 		assert_ok!(SwapLegacyModule::add_liquidity(
+// 		assert_ok!(SwapModule::add_liquidity(
 // 			Origin::signed(ALICE),
 // 			SEUSD,
 // 			1_000,
@@ -704,7 +704,7 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
 // fn dex_precompile_get_swap_supply_amount_should_work() {
 // 	new_test_ext().execute_with(|| {
 
-// 		assert_ok!(SwapLegacyModule::add_liquidity(
+// 		assert_ok!(SwapModule::add_liquidity(
 // 			Origin::signed(ALICE),
 // 			SEUSD,
 // 			1_000,
@@ -746,7 +746,7 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
 // fn dex_precompile_swap_with_exact_supply_should_work() {
 // 	new_test_ext().execute_with(|| {
 
-// 		assert_ok!(SwapLegacyModule::add_liquidity(
+// 		assert_ok!(SwapModule::add_liquidity(
 // 			Origin::signed(ALICE),
 // 			SEUSD,
 // 			1_000,
@@ -792,7 +792,7 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
 // fn dex_precompile_swap_with_exact_target_should_work() {
 // 	new_test_ext().execute_with(|| {
 
-// 		assert_ok!(SwapLegacyModule::add_liquidity(
+// 		assert_ok!(SwapModule::add_liquidity(
 // 			Origin::signed(ALICE),
 // 			SEUSD,
 // 			1_000,
@@ -25,7 +25,7 @@ use crate::{
 
 use frame_benchmarking::{account, whitelisted_caller};
 use frame_system::RawOrigin;
-use swap_legacy_module::TradingPairStatus;
+use swap_module::TradingPairStatus;
 use module_benchmarking::runtime_benchmarks;
 use module_traits::{MultiCurrency, MultiCurrencyExtended};
 use primitives::TradingPair;
@@ -76,7 +76,7 @@ fn inject_liquidity(
 }
 
 runtime_benchmarks! {
-	{ Runtime, swap_legacy_module }
+	{ Runtime, swap_module }
 
 // enable a Disabled trading pair
 	enable_trading_pair {
@@ -86,7 +86,7 @@ runtime_benchmarks! {
 		}
 	}: _(RawOrigin::Root, trading_pair.first(), trading_pair.second())
 	verify {
-		assert_last_event(swap_legacy_module::Event::EnableTradingPair{trading_pair: trading_pair}.into());
+		assert_last_event(swap_module::Event::EnableTradingPair{trading_pair: trading_pair}.into());
 	}
 
 // disable a Enabled trading pair
@@ -97,7 +97,7 @@ runtime_benchmarks! {
 		}
 	}: _(RawOrigin::Root, trading_pair.first(), trading_pair.second())
 	verify {
-		assert_last_event(swap_legacy_module::Event::DisableTradingPair{trading_pair}.into());
+		assert_last_event(swap_module::Event::DisableTradingPair{trading_pair}.into());
 	}
 
 // list a Provisioning trading pair
@@ -108,7 +108,7 @@ runtime_benchmarks! {
 		}
 	}: _(RawOrigin::Root, trading_pair.first(), trading_pair.second(), dollar(trading_pair.first()), dollar(trading_pair.second()), dollar(trading_pair.first()), dollar(trading_pair.second()), 10)
 	verify {
-		assert_last_event(swap_legacy_module::Event::ListProvisioning{trading_pair: trading_pair}.into());
+		assert_last_event(swap_module::Event::ListProvisioning{trading_pair: trading_pair}.into());
 	}
 
 // update parameters of a Provisioning trading pair
@@ -161,7 +161,7 @@ runtime_benchmarks! {
 		)?;
 	}: _(RawOrigin::Signed(founder), trading_pair.first(), trading_pair.second())
 	verify {
-		assert_last_event(swap_legacy_module::Event::ProvisioningToEnabled{trading_pair, pool_0: 100 * dollar(trading_pair.first()), pool_1: 100 * dollar(trading_pair.second()), share_amount: 200 * dollar(trading_pair.first())}.into())
+		assert_last_event(swap_module::Event::ProvisioningToEnabled{trading_pair, pool_0: 100 * dollar(trading_pair.first()), pool_1: 100 * dollar(trading_pair.second()), share_amount: 200 * dollar(trading_pair.first())}.into())
 	}
 
 	add_provision {
@@ -186,7 +186,7 @@ runtime_benchmarks! {
 		<Currencies as MultiCurrencyExtended<_>>::update_balance(trading_pair.second(), &founder, (10 * dollar(trading_pair.second())).unique_saturated_into())?;
 	}: _(RawOrigin::Signed(founder.clone()), trading_pair.first(), trading_pair.second(), dollar(trading_pair.first()), dollar(trading_pair.second()))
 	verify{
-		assert_last_event(swap_legacy_module::Event::AddProvision{who: founder, currency_0: trading_pair.first(), contribution_0: dollar(trading_pair.first()), currency_1: trading_pair.second(), contribution_1: dollar(trading_pair.second())}.into());
+		assert_last_event(swap_module::Event::AddProvision{who: founder, currency_0: trading_pair.first(), contribution_0: dollar(trading_pair.first()), currency_1: trading_pair.second(), contribution_1: dollar(trading_pair.second())}.into());
 	}
 
 	claim_dex_share {
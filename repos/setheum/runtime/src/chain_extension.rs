@@ -0,0 +1,321 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Chain extension exposing Setheum's DeFi modules to ink! contracts.
+//!
+//! Every function reads its SCALE encoded input from the contract buffer and writes
+//! a SCALE encoded output back. Dispatching functions return a status code through
+//! [`RetVal::Converging`] instead of trapping, so contracts can handle failures.
+
+use crate::{
+	AccountId, AggregatedDataProvider, Balance, Currencies, CurrencyId, Dex, Price, Runtime, TradingPathLimit, NFT,
+};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::{
+	log::{error, trace},
+	storage::{with_transaction, TransactionOutcome},
+	traits::{ExistenceRequirement, Get},
+	BoundedVec,
+};
+use module_currencies::WeightInfo as _;
+use module_nft::WeightInfo as _;
+use module_support::{PriceProvider, SwapLimit, SwapManager};
+use module_swap::WeightInfo as _;
+use module_traits::{DataProvider, InspectExtended, MultiCurrency};
+use pallet_contracts::chain_extension::{
+	ChainExtension, Environment, Ext, InitState, RegisteredChainExtension, RetVal,
+};
+use primitives::nft::{Attributes, CID};
+use sp_runtime::DispatchError;
+
+/// The extension id contracts use to address [`SetheumExtension`].
+pub const SETHEUM_EXTENSION_ID: u16 = 1;
+
+/// Status codes returned to the calling contract.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum StatusCode {
+	Success = 0,
+	CurrencyFailed = 1,
+	SwapFailed = 2,
+	NftFailed = 3,
+}
+
+type SwapPath = BoundedVec<CurrencyId, TradingPathLimit>;
+type ClassId = u32;
+type TokenId = u64;
+
+#[derive(Debug, PartialEq, Encode, Decode, MaxEncodedLen)]
+struct TransferInput {
+	currency_id: CurrencyId,
+	to: AccountId,
+	amount: Balance,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode, MaxEncodedLen)]
+struct SwapInput {
+	path: SwapPath,
+	/// Exact supply amount or maximum supply amount, depending on the function.
+	supply: Balance,
+	/// Minimum target amount or exact target amount, depending on the function.
+	target: Balance,
+}
+
+#[derive(Debug, PartialEq, Encode, Decode)]
+struct NftMintInput {
+	class_id: ClassId,
+	to: AccountId,
+	metadata: CID,
+	attributes: Attributes,
+	quantity: u32,
+}
+
+/// We're using enums for function IDs because contrary to raw u16 it enables
+/// exhaustive matching, which results in cleaner code.
+#[derive(Debug)]
+enum FuncId {
+	// module_currencies
+	FreeBalance,
+	TotalIssuance,
+	Transfer,
+	// DEX
+	LiquidityPool,
+	QuoteExactSupply,
+	QuoteExactTarget,
+	SwapExactSupply,
+	SwapExactTarget,
+	// module_prices and module_oracle
+	Price,
+	RelativePrice,
+	OraclePrice,
+	// module_nft
+	NftBalance,
+	NftOwner,
+	NftTransfer,
+	NftMint,
+	NftBurn,
+}
+
+impl TryFrom<u16> for FuncId {
+	type Error = DispatchError;
+
+	fn try_from(func_id: u16) -> Result<Self, Self::Error> {
+		let id = match func_id {
+			0x0001 => Self::FreeBalance,
+			0x0002 => Self::TotalIssuance,
+			0x0003 => Self::Transfer,
+			0x0101 => Self::LiquidityPool,
+			0x0102 => Self::QuoteExactSupply,
+			0x0103 => Self::QuoteExactTarget,
+			0x0104 => Self::SwapExactSupply,
+			0x0105 => Self::SwapExactTarget,
+			0x0201 => Self::Price,
+			0x0202 => Self::RelativePrice,
+			0x0203 => Self::OraclePrice,
+			0x0301 => Self::NftBalance,
+			0x0302 => Self::NftOwner,
+			0x0303 => Self::NftTransfer,
+			0x0304 => Self::NftMint,
+			0x0305 => Self::NftBurn,
+			_ => {
+				error!("Called an unregistered `func_id`: {:}", func_id);
+				return Err(DispatchError::Other("Unimplemented func_id"));
+			}
+		};
+
+		Ok(id)
+	}
+}
+
+fn reads(n: u64) -> frame_support::weights::Weight {
+	<Runtime as frame_system::Config>::DbWeight::get().reads(n)
+}
+
+/// Dispatches `f` in its own storage transaction, so that a failed dispatch leaves no
+/// changes behind when its status code is returned to the contract.
+fn transactional<R>(f: impl FnOnce() -> Result<R, DispatchError>) -> Result<R, DispatchError> {
+	with_transaction(|| {
+		let result = f();
+		if result.is_ok() {
+			TransactionOutcome::Commit(result)
+		} else {
+			TransactionOutcome::Rollback(result)
+		}
+	})
+}
+
+fn status(result: Result<(), DispatchError>, failure: StatusCode) -> RetVal {
+	match result {
+		Ok(()) => RetVal::Converging(StatusCode::Success as u32),
+		Err(err) => {
+			trace!(target: "runtime", "[ChainExtension] dispatch failed: {:?}", err);
+			RetVal::Converging(failure as u32)
+		}
+	}
+}
+
+/// Contract extension exposing `module_currencies`, the DEX, `module_prices`,
+/// `module_oracle` and `module_nft`.
+#[derive(Default)]
+pub struct SetheumExtension;
+
+impl ChainExtension<Runtime> for SetheumExtension {
+	fn call<E: Ext<T = Runtime>>(&mut self, env: Environment<E, InitState>) -> Result<RetVal, DispatchError> {
+		let func_id = FuncId::try_from(env.func_id())?;
+		let mut env = env.buf_in_buf_out();
+		trace!(target: "runtime", "[ChainExtension] SetheumExtension::{:?}", func_id);
+
+		match func_id {
+			FuncId::FreeBalance => {
+				env.charge_weight(reads(1))?;
+				let (currency_id, who): (CurrencyId, AccountId) = env.read_as()?;
+				let balance = <Currencies as MultiCurrency<AccountId>>::free_balance(currency_id, &who);
+				env.write(&balance.encode(), false, None)?;
+			}
+			FuncId::TotalIssuance => {
+				env.charge_weight(reads(1))?;
+				let currency_id: CurrencyId = env.read_as()?;
+				let issuance = <Currencies as MultiCurrency<AccountId>>::total_issuance(currency_id);
+				env.write(&issuance.encode(), false, None)?;
+			}
+			FuncId::Transfer => {
+				env.charge_weight(
+					<Runtime as module_currencies::Config>::WeightInfo::transfer_non_native_currency(),
+				)?;
+				let input: TransferInput = env.read_as()?;
+				let from = env.ext().address().clone();
+				let result = transactional(|| {
+					<Currencies as MultiCurrency<AccountId>>::transfer(
+						input.currency_id,
+						&from,
+						&input.to,
+						input.amount,
+						ExistenceRequirement::KeepAlive,
+					)
+				});
+				return Ok(status(result, StatusCode::CurrencyFailed));
+			}
+			FuncId::LiquidityPool => {
+				env.charge_weight(reads(1))?;
+				let (currency_id_a, currency_id_b): (CurrencyId, CurrencyId) = env.read_as()?;
+				let pool = Dex::get_liquidity_pool(currency_id_a, currency_id_b);
+				env.write(&pool.encode(), false, None)?;
+			}
+			FuncId::QuoteExactSupply | FuncId::QuoteExactTarget => {
+				env.charge_weight(reads(TradingPathLimit::get() as u64))?;
+				let input: SwapInput = env.read_as()?;
+				let limit = match func_id {
+					FuncId::QuoteExactSupply => SwapLimit::ExactSupply(input.supply, input.target),
+					_ => SwapLimit::ExactTarget(input.supply, input.target),
+				};
+				let quote = Dex::get_swap_amount(&input.path, limit);
+				env.write(&quote.encode(), false, None)?;
+			}
+			FuncId::SwapExactSupply | FuncId::SwapExactTarget => {
+				let input: SwapInput = env.read_as()?;
+				let path_len = input.path.len() as u32;
+				let (weight, limit) = match func_id {
+					FuncId::SwapExactSupply => (
+						<Runtime as module_swap::Config>::WeightInfo::swap_with_exact_supply(path_len),
+						SwapLimit::ExactSupply(input.supply, input.target),
+					),
+					_ => (
+						<Runtime as module_swap::Config>::WeightInfo::swap_with_exact_target(path_len),
+						SwapLimit::ExactTarget(input.supply, input.target),
+					),
+				};
+				env.charge_weight(weight)?;
+				let who = env.ext().address().clone();
+				match transactional(|| Dex::swap_with_specific_path(&who, &input.path, limit)) {
+					Ok(amounts) => env.write(&amounts.encode(), false, None)?,
+					Err(err) => return Ok(status(Err(err), StatusCode::SwapFailed)),
+				}
+			}
+			FuncId::Price => {
+				env.charge_weight(reads(2))?;
+				let currency_id: CurrencyId = env.read_as()?;
+				let price = module_prices::RealTimePriceProvider::<Runtime>::get_price(currency_id);
+				env.write(&price.encode(), false, None)?;
+			}
+			FuncId::RelativePrice => {
+				env.charge_weight(reads(4))?;
+				let (base, quote): (CurrencyId, CurrencyId) = env.read_as()?;
+				let price = module_prices::RealTimePriceProvider::<Runtime>::get_relative_price(base, quote);
+				env.write(&price.encode(), false, None)?;
+			}
+			FuncId::OraclePrice => {
+				env.charge_weight(reads(1))?;
+				let currency_id: CurrencyId = env.read_as()?;
+				let price: Option<Price> =
+					<AggregatedDataProvider as DataProvider<CurrencyId, Price>>::get(&currency_id);
+				env.write(&price.encode(), false, None)?;
+			}
+			FuncId::NftBalance => {
+				env.charge_weight(reads(1))?;
+				let who: AccountId = env.read_as()?;
+				let balance = <NFT as InspectExtended<AccountId>>::balance(&who);
+				env.write(&balance.encode(), false, None)?;
+			}
+			FuncId::NftOwner => {
+				env.charge_weight(reads(1))?;
+				let (class_id, token_id): (ClassId, TokenId) = env.read_as()?;
+				let owner = NFT::tokens(class_id, token_id).map(|token| token.owner);
+				env.write(&owner.encode(), false, None)?;
+			}
+			FuncId::NftTransfer => {
+				env.charge_weight(<Runtime as module_nft::Config>::WeightInfo::transfer())?;
+				let (to, token): (AccountId, (ClassId, TokenId)) = env.read_as()?;
+				let from = env.ext().address().clone();
+				let result = transactional(|| NFT::transfer_token(&from, &to, token));
+				return Ok(status(result, StatusCode::NftFailed));
+			}
+			FuncId::NftMint => {
+				let len = env.in_len();
+				let input: NftMintInput = env.read_as_unbounded(len)?;
+				env.charge_weight(<Runtime as module_nft::Config>::WeightInfo::mint(input.quantity))?;
+				let who = env.ext().address().clone();
+				let result = transactional(|| {
+					NFT::mint_token(
+						&who,
+						&input.to,
+						input.class_id,
+						input.metadata,
+						input.attributes,
+						input.quantity,
+					)
+				});
+				return Ok(status(result, StatusCode::NftFailed));
+			}
+			FuncId::NftBurn => {
+				env.charge_weight(<Runtime as module_nft::Config>::WeightInfo::burn())?;
+				let token: (ClassId, TokenId) = env.read_as()?;
+				let who = env.ext().address().clone();
+				let result = transactional(|| NFT::burn_token(who, token, None));
+				return Ok(status(result, StatusCode::NftFailed));
+			}
+		}
+
+		Ok(RetVal::Converging(StatusCode::Success as u32))
+	}
+}
+
+impl RegisteredChainExtension<Runtime> for SetheumExtension {
+	const ID: u16 = SETHEUM_EXTENSION_ID;
+}
@@ -0,0 +1,471 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Call tracing for contract dry runs.
+//!
+//! [`ContractsTracer`] is the `pallet_contracts` debugger of the runtime. It does nothing
+//! unless a call is executed through [`trace_call`], in which case every contract frame is
+//! recorded into a [`CallTrace`] tree. The debugger hooks only report the entry point, so
+//! the rest is recovered from the system events deposited around each frame: the
+//! `Called` and `DelegateCalled` events deposited when a frame returns name how it was
+//! entered, the balance transfer deposited before it is entered carries its value, and the
+//! `ContractEmitted` events deposited while it executed are its events.
+
+use crate::{AccountId, Balance, Contracts, Event, EventRecord, Hash, System};
+use frame_support::weights::Weight;
+use pallet_contracts::debug::{CallInterceptor, CallSpan, ExecResult, ExecReturnValue, ExportedFunction, Tracing};
+use primitives::contracts::{CallKind, CallTrace, TracedEvent};
+use sp_std::prelude::*;
+
+type Trace = CallTrace<AccountId, Balance, Hash>;
+
+/// A frame which has been entered but has not returned yet.
+struct Frame {
+	trace: Trace,
+	/// Index of the first system event deposited within the frame.
+	events_start: u32,
+	/// Index into `trace.calls` and event range of every nested frame which did not revert.
+	committed_calls: Vec<(usize, u32, u32)>,
+}
+
+#[derive(Default)]
+struct Recorder {
+	origin: Option<AccountId>,
+	stack: Vec<Frame>,
+	root: Option<Trace>,
+}
+
+environmental::environmental!(recorder: Recorder);
+
+impl Recorder {
+	fn enter(&mut self, contract: &AccountId, entry_point: ExportedFunction, input: &[u8]) {
+		let events_start = System::event_count();
+		let caller = match self.stack.last() {
+			Some(frame) => Some(&frame.trace.contract),
+			None => self.origin.as_ref(),
+		};
+		// Delegate calls and reentrant calls both execute against the caller's account. Which
+		// one it was is only known from the event deposited once the frame returns.
+		let kind = match entry_point {
+			ExportedFunction::Constructor => CallKind::Instantiate,
+			ExportedFunction::Call if caller == Some(contract) => CallKind::ReentrantOrDelegateCall,
+			ExportedFunction::Call => CallKind::Call,
+		};
+		// Value is never transferred from an account to itself.
+		let value = match (kind, caller) {
+			(CallKind::ReentrantOrDelegateCall, _) | (_, None) => 0,
+			(_, Some(caller)) => transferred_value(caller, contract, events_start),
+		};
+		let code_hash = match kind {
+			CallKind::Call => Contracts::code_hash(contract),
+			_ => None,
+		};
+
+		self.stack.push(Frame {
+			trace: CallTrace {
+				kind,
+				contract: contract.clone(),
+				code_hash,
+				input: input.to_vec(),
+				value,
+				gas_consumed: None,
+				output: Vec::new(),
+				reverted: false,
+				trapped: false,
+				storage_deposit_charged: 0,
+				storage_deposit_refunded: 0,
+				events: Vec::new(),
+				calls: Vec::new(),
+			},
+			events_start,
+			committed_calls: Vec::new(),
+		});
+	}
+
+	fn exit(&mut self, output: Option<&ExecReturnValue>) {
+		let Some(mut frame) = self.stack.pop() else { return };
+		let events_end = System::event_count();
+		match output {
+			Some(output) => {
+				frame.trace.output = output.data.clone();
+				frame.trace.reverted = output.did_revert();
+			}
+			None => frame.trace.trapped = true,
+		}
+
+		let events = read_events(frame.events_start, events_end);
+		let event_at = |index: u32| events.get(index.saturating_sub(frame.events_start) as usize);
+		for (call, _, end) in &frame.committed_calls {
+			let call = &mut frame.trace.calls[*call];
+			// `Called` or `DelegateCalled` is deposited right after a call returns successfully.
+			match event_at(*end).map(|record| &record.event) {
+				Some(Event::Contracts(pallet_contracts::Event::DelegateCalled { code_hash, .. })) => {
+					call.kind = CallKind::DelegateCall;
+					call.code_hash = Some(*code_hash);
+				}
+				Some(Event::Contracts(pallet_contracts::Event::Called { .. })) => call.kind = CallKind::Call,
+				_ => {}
+			}
+		}
+		for index in frame.events_start..events_end {
+			if frame.committed_calls.iter().any(|(_, start, end)| (*start..*end).contains(&index)) {
+				continue;
+			}
+			if let Some(EventRecord {
+				event: Event::Contracts(pallet_contracts::Event::ContractEmitted { contract, data }),
+				topics,
+				..
+			}) = event_at(index)
+			{
+				if *contract == frame.trace.contract {
+					frame.trace.events.push(TracedEvent { topics: topics.clone(), data: data.clone() });
+				}
+			}
+		}
+
+		// Events of reverted frames are rolled back, so their indices get reused by the caller.
+		let committed = !frame.trace.reverted && !frame.trace.trapped;
+		match self.stack.last_mut() {
+			Some(parent) => {
+				if committed {
+					parent.committed_calls.push((parent.trace.calls.len(), frame.events_start, events_end));
+				}
+				parent.trace.calls.push(frame.trace);
+			}
+			None => self.root = Some(frame.trace),
+		}
+	}
+}
+
+/// Reads the system events with indices in `start..end`.
+fn read_events(start: u32, end: u32) -> Vec<EventRecord> {
+	System::read_events_no_consensus()
+		.skip(start as usize)
+		.take(end.saturating_sub(start) as usize)
+		.map(|record| *record)
+		.collect()
+}
+
+/// The balance transfer into a frame is the last event deposited before it is entered.
+///
+/// A transfer made by the caller right before calling into the contract without value is
+/// indistinguishable from it, and is reported as the value of the call.
+fn transferred_value(caller: &AccountId, contract: &AccountId, events_start: u32) -> Balance {
+	match read_events(events_start.saturating_sub(1), events_start).pop() {
+		Some(EventRecord {
+			event: Event::Balances(pallet_balances::Event::Transfer { from, to, amount }),
+			..
+		}) if from == *caller && to == *contract => amount,
+		_ => 0,
+	}
+}
+
+/// Whether the frame is known to execute the code of the contract it executes against.
+fn executes_own_code(trace: &Trace) -> bool {
+	matches!(trace.kind, CallKind::Call | CallKind::Instantiate)
+}
+
+fn find_contract<'a>(trace: &'a mut Trace, contract: &AccountId) -> Option<&'a mut Trace> {
+	if executes_own_code(trace) && trace.contract == *contract {
+		return Some(trace);
+	}
+	trace.calls.iter_mut().find_map(|call| find_contract(call, contract))
+}
+
+fn fill_code_hashes(trace: &mut Trace) {
+	if trace.code_hash.is_none() && executes_own_code(trace) {
+		trace.code_hash = Contracts::code_hash(&trace.contract);
+	}
+	trace.calls.iter_mut().for_each(fill_code_hashes);
+}
+
+/// Dry runs a contract call and returns the trace of its frames.
+///
+/// Storage deposits are settled once the outermost call returns; they are attributed to the
+/// first frame executing against the contract whose storage they pay for.
+pub fn trace_call(
+	origin: AccountId,
+	dest: AccountId,
+	value: Balance,
+	gas_limit: Weight,
+	storage_deposit_limit: Option<Balance>,
+	input_data: Vec<u8>,
+) -> Option<Trace> {
+	let mut recorder = Recorder { origin: Some(origin.clone()), ..Default::default() };
+	let events_start = System::event_count();
+	let result = recorder::using(&mut recorder, || {
+		Contracts::bare_call(
+			origin,
+			dest,
+			value,
+			gas_limit,
+			storage_deposit_limit,
+			input_data,
+			pallet_contracts::DebugInfo::Skip,
+			pallet_contracts::CollectEvents::Skip,
+			pallet_contracts::Determinism::Enforced,
+		)
+	});
+	let mut root = recorder.root.take()?;
+	root.gas_consumed = Some(result.gas_consumed);
+
+	for record in read_events(events_start, System::event_count()) {
+		match record.event {
+			Event::Contracts(pallet_contracts::Event::StorageDepositTransferredAndHeld { to, amount, .. }) => {
+				if let Some(trace) = find_contract(&mut root, &to) {
+					trace.storage_deposit_charged = trace.storage_deposit_charged.saturating_add(amount);
+				}
+			}
+			Event::Contracts(pallet_contracts::Event::StorageDepositTransferredAndReleased { from, amount, .. }) => {
+				if let Some(trace) = find_contract(&mut root, &from) {
+					trace.storage_deposit_refunded = trace.storage_deposit_refunded.saturating_add(amount);
+				}
+			}
+			_ => {}
+		}
+	}
+	// Instantiated contracts are only stored once their constructor returned.
+	fill_code_hashes(&mut root);
+	Some(root)
+}
+
+/// The runtime's contracts debugger, recording call frames while [`trace_call`] runs.
+pub struct ContractsTracer;
+
+impl Tracing<crate::Runtime> for ContractsTracer {
+	type CallSpan = TraceSpan;
+
+	fn new_call_span(contract_address: &AccountId, entry_point: ExportedFunction, input_data: &[u8]) -> TraceSpan {
+		let active = recorder::with(|recorder| recorder.enter(contract_address, entry_point, input_data)).is_some();
+		TraceSpan { active }
+	}
+}
+
+impl CallInterceptor<crate::Runtime> for ContractsTracer {
+	fn intercept_call(_: &AccountId, _: &ExportedFunction, _: &[u8]) -> Option<ExecResult> {
+		None
+	}
+}
+
+/// Closes the recorded frame when the call returns, or when it is dropped because the
+/// call trapped.
+pub struct TraceSpan {
+	active: bool,
+}
+
+impl CallSpan for TraceSpan {
+	fn after_call(mut self, output: &ExecReturnValue) {
+		if self.active {
+			self.active = false;
+			recorder::with(|recorder| recorder.exit(Some(output)));
+		}
+	}
+}
+
+impl Drop for TraceSpan {
+	fn drop(&mut self) {
+		if self.active {
+			recorder::with(|recorder| recorder.exit(None));
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::{Decode, Encode};
+	use frame_support::traits::Currency;
+
+	const ALICE: AccountId = AccountId::new([1u8; 32]);
+	const A: AccountId = AccountId::new([2u8; 32]);
+	const B: AccountId = AccountId::new([3u8; 32]);
+	const C: AccountId = AccountId::new([4u8; 32]);
+
+	/// `(module (import "env" "memory" (memory 1 1)) (func (export "deploy")) (func (export "call")))`
+	const CONTRACT: [u8; 65] = hex_literal::hex!(
+		"0061736d0100000001040160000002100103656e76066d656d6f7279020101010303020000071102066465706c6f7900000463616c6c00010a070202000b02000b"
+	);
+
+	fn new_test_ext() -> sp_io::TestExternalities {
+		let mut ext: sp_io::TestExternalities =
+			frame_system::GenesisConfig::default().build_storage::<crate::Runtime>().unwrap().into();
+		// Events are not deposited in the genesis block.
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+
+	/// `ExecReturnValue` flags are not reexported by `pallet_contracts`, so it is built from its encoding.
+	fn returned(reverted: bool, data: Vec<u8>) -> ExecReturnValue {
+		ExecReturnValue::decode(&mut &(reverted as u32, data).encode()[..]).unwrap()
+	}
+
+	fn deposit(event: pallet_contracts::Event<crate::Runtime>) {
+		System::deposit_event(Event::Contracts(event));
+	}
+
+	fn called(caller: &AccountId, contract: &AccountId) {
+		deposit(pallet_contracts::Event::Called {
+			caller: pallet_contracts::Origin::from_account_id(caller.clone()),
+			contract: contract.clone(),
+		});
+	}
+
+	fn emitted(contract: &AccountId, data: Vec<u8>) {
+		deposit(pallet_contracts::Event::ContractEmitted { contract: contract.clone(), data });
+	}
+
+	#[test]
+	fn nested_calls_form_a_tree() {
+		new_test_ext().execute_with(|| {
+			let mut recorder = Recorder { origin: Some(ALICE), ..Default::default() };
+			recorder.enter(&A, ExportedFunction::Call, &[1]);
+			System::deposit_event(Event::Balances(pallet_balances::Event::Transfer { from: A, to: B, amount: 5 }));
+			recorder.enter(&B, ExportedFunction::Call, &[2]);
+			emitted(&B, vec![20]);
+			recorder.exit(Some(&returned(false, vec![3])));
+			called(&A, &B);
+			emitted(&A, vec![10]);
+			recorder.exit(Some(&returned(false, vec![4])));
+
+			let root = recorder.root.expect("Root frame returned");
+			assert_eq!(root.kind, CallKind::Call);
+			assert_eq!((root.contract.clone(), root.input.clone(), root.output.clone()), (A, vec![1], vec![4]));
+			assert_eq!(root.value, 0);
+			assert_eq!(root.events, vec![TracedEvent { topics: vec![], data: vec![10] }]);
+
+			assert_eq!(root.calls.len(), 1);
+			let call = &root.calls[0];
+			assert_eq!(call.kind, CallKind::Call);
+			assert_eq!((call.contract.clone(), call.input.clone(), call.output.clone()), (B, vec![2], vec![3]));
+			assert_eq!(call.value, 5);
+			assert_eq!(call.events, vec![TracedEvent { topics: vec![], data: vec![20] }]);
+			assert!(call.calls.is_empty());
+		});
+	}
+
+	#[test]
+	fn delegate_and_reentrant_calls_are_told_apart() {
+		new_test_ext().execute_with(|| {
+			let code_hash = Hash::repeat_byte(7);
+			let mut recorder = Recorder { origin: Some(ALICE), ..Default::default() };
+			recorder.enter(&A, ExportedFunction::Call, &[]);
+			recorder.enter(&A, ExportedFunction::Call, &[1]);
+			recorder.exit(Some(&returned(false, vec![])));
+			deposit(pallet_contracts::Event::DelegateCalled { contract: A, code_hash });
+			recorder.enter(&A, ExportedFunction::Call, &[2]);
+			recorder.exit(Some(&returned(false, vec![])));
+			called(&A, &A);
+			// Nothing is deposited for frames which did not return successfully.
+			recorder.enter(&A, ExportedFunction::Call, &[3]);
+			recorder.exit(None);
+			recorder.exit(Some(&returned(false, vec![])));
+
+			let root = recorder.root.expect("Root frame returned");
+			let kinds: Vec<_> = root.calls.iter().map(|call| (call.input.clone(), call.kind)).collect();
+			assert_eq!(
+				kinds,
+				vec![
+					(vec![1], CallKind::DelegateCall),
+					(vec![2], CallKind::Call),
+					(vec![3], CallKind::ReentrantOrDelegateCall),
+				]
+			);
+			assert_eq!(root.calls[0].code_hash, Some(code_hash));
+			assert!(root.calls[2].trapped);
+		});
+	}
+
+	#[test]
+	fn instantiations_and_reverted_calls_are_recorded() {
+		new_test_ext().execute_with(|| {
+			let mut recorder = Recorder { origin: Some(ALICE), ..Default::default() };
+			recorder.enter(&A, ExportedFunction::Call, &[]);
+			recorder.enter(&B, ExportedFunction::Constructor, &[1]);
+			emitted(&B, vec![20]);
+			recorder.exit(Some(&returned(false, vec![])));
+			recorder.enter(&C, ExportedFunction::Call, &[2]);
+			recorder.exit(Some(&returned(true, vec![9])));
+			recorder.exit(Some(&returned(false, vec![])));
+
+			let root = recorder.root.expect("Root frame returned");
+			assert_eq!(root.calls.len(), 2);
+			let instantiation = &root.calls[0];
+			assert_eq!(instantiation.kind, CallKind::Instantiate);
+			assert_eq!(instantiation.contract, B);
+			assert_eq!(instantiation.events, vec![TracedEvent { topics: vec![], data: vec![20] }]);
+			let reverted = &root.calls[1];
+			assert_eq!(reverted.kind, CallKind::Call);
+			assert!(reverted.reverted && !reverted.trapped);
+			assert_eq!(reverted.output, vec![9]);
+			assert!(root.events.is_empty());
+		});
+	}
+
+	#[test]
+	fn trace_reports_gas_of_the_call() {
+		new_test_ext().execute_with(|| {
+			let gas_limit = Weight::from_parts(100_000_000_000, 1024 * 1024);
+			Balances::make_free_balance_be(&ALICE, 1_000 * primitives::TOKEN);
+			let contract = Contracts::bare_instantiate(
+				ALICE,
+				0,
+				gas_limit,
+				None,
+				pallet_contracts::Code::Upload(CONTRACT.to_vec()),
+				vec![],
+				vec![],
+				pallet_contracts::DebugInfo::Skip,
+				pallet_contracts::CollectEvents::Skip,
+			)
+			.result
+			.expect("Instantiated")
+			.account_id;
+
+			let trace = trace_call(ALICE, contract.clone(), 0, gas_limit, None, vec![1, 2]).expect("Traced");
+			let call = Contracts::bare_call(
+				ALICE,
+				contract.clone(),
+				0,
+				gas_limit,
+				None,
+				vec![1, 2],
+				pallet_contracts::DebugInfo::Skip,
+				pallet_contracts::CollectEvents::Skip,
+				pallet_contracts::Determinism::Enforced,
+			);
+
+			assert_eq!(trace.kind, CallKind::Call);
+			assert_eq!(trace.contract, contract);
+			assert_eq!(trace.code_hash, Contracts::code_hash(&contract));
+			assert!(trace.code_hash.is_some());
+			assert_eq!(trace.input, vec![1, 2]);
+			assert_eq!(trace.gas_consumed, Some(call.gas_consumed));
+			assert!(call.gas_consumed.ref_time() > 0);
+			assert!(trace.calls.is_empty());
+		});
+	}
+
+	#[test]
+	fn nothing_is_traced_without_a_contract() {
+		new_test_ext().execute_with(|| {
+			assert_eq!(trace_call(ALICE, A, 0, Weight::from_parts(1_000_000_000, 0), None, vec![]), None);
+		});
+	}
+}
@@ -69,7 +69,7 @@ use frame_support::pallet_prelude::InvalidTransaction;
 pub use frame_support::{
 	construct_runtime, log, parameter_types,
 	traits::{
-		Contains, ContainsLengthBound, Currency as PalletCurrency, EnsureOrigin, Everything, Get, Imbalance,
+		ConstU32, Contains, ContainsLengthBound, Currency as PalletCurrency, EnsureOrigin, Everything, Get, Imbalance,
 		InstanceFilter, IsSubType, IsType, KeyOwnerProofSystem, LockIdentifier, Nothing, OnUnbalanced, Randomness,
 		SortedMembers, U128CurrencyToVote, WithdrawReasons,
 	},
@@ -79,13 +79,13 @@ pub use frame_support::{
 	},
 	PalletId, RuntimeDebug, StorageValue,
 };
-pub use frame_system::{ensure_root, EnsureOneOf, EnsureRoot, RawOrigin};
+pub use frame_system::{ensure_root, EnsureOneOf, EnsureRoot, EnsureSigned, RawOrigin};
 use module_traits::{
 	create_median_value_data_provider, parameter_type_with_key, DataFeeder, DataProviderExtended,
 // MultiCurrency,
 };
 use module_currencies::BasicCurrencyAdapter;
-use module_swap_legacy as swap_legacy_module;
+use module_swap as swap_module;
 use module_dex_oracle as dex_oracle;
 use module_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 
@@ -132,6 +132,8 @@ pub use runtime_common::{
 
 mod weights;
 mod authority;
+mod chain_extension;
+mod contracts_trace;
 pub mod constants;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -800,29 +802,31 @@ parameter_types! {
 
 parameter_types! {
 	pub const GetExchangeFee: (u32, u32) = (3, 1000);	/ 0.3%
-	pub const GetStableCurrencyExchangeFee: (u32, u32) = (1, 1000);	/ 0.1%
 	pub const TradingPathLimit: u32 = 4;
+	pub const ExtendedProvisioningBlocks: BlockNumber = 2 * DAYS;
 	pub EnabledTradingPairs: Vec<TradingPair> = vec![
 		TradingPair::from_currency_ids(SEUSD, SEU).unwrap(),
 	];
 }
 
-impl swap_legacy_module::Config for Runtime {
-	type Event = Event;
+impl swap_module::Config for Runtime {
+	type RuntimeEvent = Event;
 	type Currency = Currencies;
-	type StableCurrencyIds = StableCurrencyIds;
 	type GetExchangeFee = GetExchangeFee;
-	type GetStableCurrencyExchangeFee = GetStableCurrencyExchangeFee;
 	type TradingPathLimit = TradingPathLimit;
 	type PalletId = DEXPalletId;
-	type CurrencyIdMapping = EvmCurrencyIdMapping<Runtime>;
+/// Keeps the LP token ERC20 addresses of the legacy DEX.
+	type Erc20InfoMapping = EvmCurrencyIdMapping<Runtime>;
 	type WeightInfo = weights::module_dex::WeightInfo<Runtime>;
+	type Incentives = ();
 	type ListingOrigin = EnsureRootOrHalfFinancialCouncil;
+	type ExtendedProvisioningBlocks = ExtendedProvisioningBlocks;
+	type OnLiquidityPoolUpdated = ();
 }
 
 impl dex_oracle::Config for Runtime {
 	type Event = Event;
-	type DEX = swap_legacy_module::Pallet<Runtime>;
+	type DEX = Dex;
 }
 
 // parameter_types! {
@@ -1104,8 +1108,8 @@ impl InstanceFilter<Call> for ProxyType {
 			ProxyType::Swap => {
 				matches!(
 					c,
-					Call::Dex(swap_legacy_module::Call::swap_with_exact_supply(..))
-						| Call::Dex(swap_legacy_module::Call::swap_with_exact_target(..))
+					Call::Dex(swap_module::Call::swap_with_exact_supply { .. })
+						| Call::Dex(swap_module::Call::swap_with_exact_target { .. })
 				)
 			}
 			ProxyType::Loan => {
@@ -1455,6 +1459,59 @@ impl pallet_recovery::Config for Runtime {
 
 impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
 
+parameter_types! {
+	pub ContractsDepositPerItem: Balance = deposit(1, 0);
+	pub ContractsDepositPerByte: Balance = deposit(0, 1);
+	pub ContractsSchedule: pallet_contracts::Schedule<Runtime> = Default::default();
+	pub const ContractsDeletionQueueDepth: u32 = 128;
+	// The lazy deletion runs inside on_initialize.
+	pub ContractsDeletionWeightLimit: Weight = BlockWeights::get()
+		.per_class
+		.get(DispatchClass::Normal)
+		.max_total
+		.unwrap_or(BlockWeights::get().max_block);
+}
+
+impl pallet_contracts::Config for Runtime {
+	type Time = Timestamp;
+	type Randomness = RandomnessCollectiveFlip;
+	type Currency = Balances;
+	type Event = Event;
+	type Call = Call;
+	/// Contracts may not dispatch runtime calls; DeFi modules are reached through the chain extension.
+	type CallFilter = Nothing;
+	type WeightPrice = TransactionPayment;
+	type WeightInfo = pallet_contracts::weights::SubstrateWeight<Self>;
+	type ChainExtension = chain_extension::SetheumExtension;
+	type DeletionQueueDepth = ContractsDeletionQueueDepth;
+	type DeletionWeightLimit = ContractsDeletionWeightLimit;
+	type Schedule = ContractsSchedule;
+	type CallStack = [pallet_contracts::Frame<Self>; 5];
+	type DepositPerByte = ContractsDepositPerByte;
+	type DepositPerItem = ContractsDepositPerItem;
+	type AddressGenerator = pallet_contracts::DefaultAddressGenerator;
+	type ContractAccessWeight = pallet_contracts::DefaultContractAccessWeight<BlockWeights>;
+	type MaxCodeLen = ConstU32<{ 123 * 1024 }>;
+	type RelaxedMaxCodeLen = ConstU32<{ 256 * 1024 }>;
+	type MaxStorageKeyLen = ConstU32<128>;
+	type Debug = contracts_trace::ContractsTracer;
+}
+
+parameter_types! {
+	pub const MaxScriptSigners: u32 = 8;
+	pub const MaxLifetimeRequests: u32 = 5;
+}
+
+impl pallet_move::Config for Runtime {
+	type Currency = module_currencies::MoveVmCurrency<Runtime, Balances>;
+	type CurrencyBalance = Balance;
+	type MaxLifetimeRequests = MaxLifetimeRequests;
+	type MaxScriptSigners = MaxScriptSigners;
+	type RuntimeEvent = Event;
+	type UpdateOrigin = EnsureRootOrHalfTechnicalCommittee;
+	type WeightInfo = pallet_move::weights::SubstrateWeight<Self>;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 
 // workaround for a weird bug in macro
@@ -1475,7 +1532,7 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>} = 3,
 		Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>} = 4,
 		Prices: module_prices::{Pallet, Storage, Call, Event<T>} = 5,
-		Dex: swap_legacy_module::{Pallet, Storage, Call, Event<T>, Config<T>} = 6,
+		Dex: swap_module::{Pallet, Storage, Call, Event<T>, Config<T>} = 6,
 
 		Multisig: pallet_multisig::{Pallet, Call, Storage, Event<T>} = 7,
 		Recovery: pallet_recovery::{Pallet, Call, Storage, Event<T>} = 8,
@@ -1540,6 +1597,8 @@ construct_runtime!(
 // Smart contracts
 		EVM: pallet_evm::{Pallet, Config, Storage, Event<T>} = 43,
 		EvmAccounts: module_unified_accounts::{Pallet, Call, Storage, Event<T>} = 44,
+		Contracts: pallet_contracts::{Pallet, Call, Storage, Event<T>} = 45,
+		MoveModule: pallet_move::{Pallet, Call, Storage, Event<T>} = 46,
 		Ethereum: pallet_ethereum::{Pallet, Storage, Event, Config, Call} = 49,
 
 // Consensus - Aura + SetBFT (replacing Babe + Grandpa)
@@ -1561,11 +1620,23 @@ construct_runtime!(
 
 );
 
+// `Dex` moved from `module_swap_legacy`, which charged `GetExchangeFee` on every pair.
+type DexMigration = swap_module::migration::v1::Migration<Runtime, GetExchangeFee>;
+
 pub struct OnRuntimeUpgrade;
 impl frame_support::traits::OnRuntimeUpgrade for OnRuntimeUpgrade {
-	fn on_runtime_upgrade() -> u64 {
-// no migration
-		0
+	fn on_runtime_upgrade() -> Weight {
+		<DexMigration as frame_support::traits::OnRuntimeUpgrade>::on_runtime_upgrade()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::DispatchError> {
+		<DexMigration as frame_support::traits::OnRuntimeUpgrade>::pre_upgrade()
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::DispatchError> {
+		<DexMigration as frame_support::traits::OnRuntimeUpgrade>::post_upgrade(state)
 	}
 }
 
@@ -1597,6 +1668,8 @@ pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signatu
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+/// Event record type as stored by `frame_system` and returned by the contracts runtime API.
+pub type EventRecord = frame_system::EventRecord<Event, Hash>;
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -1759,6 +1832,32 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_move::api::MoveApi<Block, AccountId> for Runtime {
+		fn estimate_gas_publish_module(account: AccountId, bytecode: Vec<u8>) -> Result<pallet_move::api::MoveApiEstimation, sp_runtime::DispatchError> {
+			MoveModule::rpc_estimate_gas_publish_module(&account, bytecode)
+		}
+
+		fn estimate_gas_publish_bundle(account: AccountId, bytecode: Vec<u8>) -> Result<pallet_move::api::MoveApiEstimation, sp_runtime::DispatchError> {
+			MoveModule::rpc_estimate_gas_publish_bundle(&account, bytecode)
+		}
+
+		fn estimate_gas_execute_script(transaction: Vec<u8>) -> Result<pallet_move::api::MoveApiEstimation, sp_runtime::DispatchError> {
+			MoveModule::rpc_estimate_gas_execute_script(transaction)
+		}
+
+		fn get_resource(account: AccountId, tag: Vec<u8>) -> Result<Option<Vec<u8>>, Vec<u8>> {
+			MoveModule::get_resource(&account, &tag)
+		}
+
+		fn get_module_abi(module_id: Vec<u8>) -> Result<Option<pallet_move::api::ModuleAbi>, Vec<u8>> {
+			MoveModule::get_module_abi(&module_id)
+		}
+
+		fn get_module(module_id: Vec<u8>) -> Result<Option<Vec<u8>>, Vec<u8>> {
+			MoveModule::get_module(&module_id)
+		}
+	}
+
 	impl module_oracle_rpc_runtime_api::OracleApi<
 		Block,
 		DataProviderId,
@@ -1780,6 +1879,83 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_contracts::ContractsApi<Block, AccountId, Balance, BlockNumber, Hash, EventRecord> for Runtime {
+		fn call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> pallet_contracts::ContractExecResult<Balance, EventRecord> {
+			let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+			Contracts::bare_call(
+				origin,
+				dest,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				input_data,
+				pallet_contracts::DebugInfo::UnsafeDebug,
+				pallet_contracts::CollectEvents::UnsafeCollect,
+				pallet_contracts::Determinism::Enforced,
+			)
+		}
+
+		fn instantiate(
+			origin: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			code: pallet_contracts::Code<Hash>,
+			data: Vec<u8>,
+			salt: Vec<u8>,
+		) -> pallet_contracts::ContractInstantiateResult<AccountId, Balance, EventRecord> {
+			let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+			Contracts::bare_instantiate(
+				origin,
+				value,
+				gas_limit,
+				storage_deposit_limit,
+				code,
+				data,
+				salt,
+				pallet_contracts::DebugInfo::UnsafeDebug,
+				pallet_contracts::CollectEvents::UnsafeCollect,
+			)
+		}
+
+		fn upload_code(
+			origin: AccountId,
+			code: Vec<u8>,
+			storage_deposit_limit: Option<Balance>,
+			determinism: pallet_contracts::Determinism,
+		) -> pallet_contracts::CodeUploadResult<Hash, Balance> {
+			Contracts::bare_upload_code(origin, code, storage_deposit_limit, determinism)
+		}
+
+		fn get_storage(
+			address: AccountId,
+			key: Vec<u8>,
+		) -> pallet_contracts::GetStorageResult {
+			Contracts::get_storage(address, key)
+		}
+	}
+
+	impl primitives::contracts::ContractsTraceApi<Block, AccountId, Balance, Hash> for Runtime {
+		fn trace_call(
+			origin: AccountId,
+			dest: AccountId,
+			value: Balance,
+			gas_limit: Option<Weight>,
+			storage_deposit_limit: Option<Balance>,
+			input_data: Vec<u8>,
+		) -> Option<primitives::contracts::CallTrace<AccountId, Balance, Hash>> {
+			let gas_limit = gas_limit.unwrap_or(BlockWeights::get().max_block);
+			contracts_trace::trace_call(origin, dest, value, gas_limit, storage_deposit_limit, input_data)
+		}
+	}
+
 	impl frame_system_rpc_runtime_api::AccountNonceApi<Block, AccountId, Nonce> for Runtime {
 		fn account_nonce(account: AccountId) -> Nonce {
 			System::account_nonce(account)
@@ -1938,6 +2114,23 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "try-runtime")]
+	impl frame_try_runtime::TryRuntime<Block> for Runtime {
+		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
+			let weight = Executive::try_runtime_upgrade(checks).unwrap();
+			(weight, BlockWeights::get().max_block)
+		}
+
+		fn execute_block(
+			block: Block,
+			state_root_check: bool,
+			signature_check: bool,
+			select: frame_try_runtime::TryStateSelect,
+		) -> Weight {
+			Executive::try_execute_block(block, state_root_check, signature_check, select).unwrap()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (
@@ -1955,7 +2148,7 @@ impl_runtime_apis! {
 
 			list_benchmark!(list, extra, module_nft, NftBench::<Runtime>);
 
-// module_list_benchmark!(list, extra, swap_legacy_module, benchmarking::dex);
+// module_list_benchmark!(list, extra, swap_module, benchmarking::dex);
 // module_list_benchmark!(list, extra, auction_manager, benchmarking::auction_manager);
 // module_list_benchmark!(list, extra, cdp_engine, benchmarking::cdp_engine);
 // module_list_benchmark!(list, extra, emergency_shutdown, benchmarking::emergency_shutdown);
@@ -2013,7 +2206,7 @@ impl_runtime_apis! {
 			let params = (&config, &whitelist);
 
 			add_benchmark!(params, batches, module_nft, NftBench::<Runtime>);
-// module_add_benchmark!(params, batches, swap_legacy_module, benchmarking::dex);
+// module_add_benchmark!(params, batches, swap_module, benchmarking::dex);
 // module_add_benchmark!(params, batches, auction_manager, benchmarking::auction_manager);
 // module_add_benchmark!(params, batches, cdp_engine, benchmarking::cdp_engine);
 // module_add_benchmark!(params, batches, emergency_shutdown, benchmarking::emergency_shutdown);
@@ -25,9 +25,9 @@
 use frame_support::{traits::Get, weights::Weight};
 use sp_std::marker::PhantomData;
 
-/// Weight functions for swap_legacy_module.
+/// Weight functions for swap_module.
 pub struct WeightInfo<T>(PhantomData<T>);
-impl<T: frame_system::Config> swap_legacy_module::WeightInfo for WeightInfo<T> {
+impl<T: frame_system::Config> swap_module::WeightInfo for WeightInfo<T> {
 	fn enable_trading_pair() -> Weight {
 		(25_878_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(1 as Weight))
@@ -101,4 +101,19 @@ impl<T: frame_system::Config> swap_legacy_module::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().writes(2 as Weight))
 			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(u as Weight)))
 	}
+	fn refund_provision() -> Weight {
+		(105_716_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(5 as Weight))
+	}
+	fn abort_provisioning() -> Weight {
+		(78_617_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	fn set_trading_fee() -> Weight {
+		(12_104_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
 }
@@ -0,0 +1,82 @@
+#!/usr/bin/env bash
+
+# This script regenerates `setheum-client/src/setheum.rs`, the typed API that `setheum-client`
+# builds its pallet APIs on top of. It fetches metadata from a running setheum-node and feeds it
+# to `subxt codegen`.
+#
+# By default it expects a node with RPC exposed on ws://127.0.0.1:9944, e.g. one started with
+# `scripts/run_nodes.sh`. Use --url to point it elsewhere.
+#
+# A handful of `primitives` types are substituted so the client can accept them directly instead
+# of their generated mirrors, the same way `AccountId32` is wrapped in `subxt::utils::Static`.
+#
+# You need to have installed following prerequisites in order to use that script:
+#   * subxt-cli in the same version as the `subxt` workspace dependency
+
+set -euo pipefail
+
+# ------------------------ constants --------------------------------------
+
+SUBXT_VERSION="0.35.3"
+OUTPUT="setheum-client/src/setheum.rs"
+
+# ------------------------ argument parsing and usage -----------------------
+
+function usage {
+  cat << EOF
+Usage:
+  $0
+    [-u|--url URL]
+      RPC endpoint of a node to fetch metadata from, default ws://127.0.0.1:9944
+    [-o|--output FILE]
+      where to write generated code, default ${OUTPUT}
+EOF
+  exit 0
+}
+
+URL=${URL:-"ws://127.0.0.1:9944"}
+
+while [[ $# -gt 0 ]]; do
+  case "$1" in
+    -u|--url)
+      URL="$2"
+      shift;shift
+      ;;
+    -o|--output)
+      OUTPUT="$2"
+      shift;shift
+      ;;
+    -h|--help)
+      usage
+      ;;
+    *)
+      echo "Unrecognized argument $1!"
+      exit 1
+      ;;
+  esac
+done
+
+source ./scripts/common.sh
+
+if ! command -v subxt &> /dev/null; then
+  log "subxt-cli not found, install it with: cargo install subxt-cli --version ${SUBXT_VERSION} --locked"
+  exit 1
+fi
+
+installed_version=$(subxt --version | awk '{print $2}')
+if [[ "${installed_version}" != "${SUBXT_VERSION}" ]]; then
+  log "subxt-cli ${installed_version} found, but ${SUBXT_VERSION} is required"
+  exit 1
+fi
+
+log "Generating ${OUTPUT} from ${URL}"
+
+subxt codegen --url "${URL}" \
+  --derive PartialEq \
+  --derive Eq \
+  --substitute-type "primitives::currency::CurrencyId=::subxt::utils::Static<::primitives::CurrencyId>" \
+  --substitute-type "primitives::TradingPair=::subxt::utils::Static<::primitives::TradingPair>" \
+  --substitute-type "primitives::nft::BitFlags=::subxt::utils::Static<::primitives::nft::Properties>" \
+  > "${OUTPUT}"
+
+log "Done, remember to run cargo check -p setheum_client"
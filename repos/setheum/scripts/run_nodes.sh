@@ -67,6 +67,8 @@ Usage:
         if not specified, base path is ./run-nodes-local
     [--finality-version]
       which finality version should be used, default = legacy
+    [--genesis-preset PRESET]
+      DeFi genesis preset passed to chain-bootstrapper: dev-defi, local-testnet or benchmark
     [--dont-bootstrap]
       set if you don't want to bootstrap chain, ie generate keystore and chainspec
     [--dont-build]
@@ -89,6 +91,7 @@ DONT_BUILD_SETHEUM_NODE=${DONT_BUILD_SETHEUM_NODE:-""}
 DONT_DELETE_DB=${DONT_DELETE_DB:-""}
 DONT_REMOVE_ABFT_BACKUPS=${DONT_REMOVE_ABFT_BACKUPS:-""}
 FINALITY_VERSION=${FINALITY_VERSION:-"legacy"}
+GENESIS_PRESET=${GENESIS_PRESET:-""}
 
 while [[ $# -gt 0 ]]; do
   case "$1" in
@@ -108,6 +111,10 @@ while [[ $# -gt 0 ]]; do
       FINALITY_VERSION="$2"
       shift;shift
       ;;
+    --genesis-preset)
+      GENESIS_PRESET="$2"
+      shift;shift
+      ;;
     --dont-bootstrap)
       DONT_BOOTSTRAP="true"
       shift
@@ -282,6 +289,11 @@ if [[ -z "${DONT_BOOTSTRAP}" ]]; then
   # comma separated ids
   validator_ids_string="${validator_ids_string//${IFS:0:1}/,}"
 
+  genesis_args=()
+  if [[ -n "${GENESIS_PRESET}" ]]; then
+    genesis_args+=(--genesis-preset "${GENESIS_PRESET}")
+  fi
+
   info "Populating keystore for all accounts with session keys and libp2p key, and generating chainspec"
   "${CHAINSPEC_GENERATOR}" bootstrap-chain \
     --raw \
@@ -290,7 +302,8 @@ if [[ -z "${DONT_BOOTSTRAP}" ]]; then
     --authorities-account-ids "${validator_ids_string}" \
     --chain-type local > "${BASE_PATH}/chainspec.json" \
     --rich-account-ids "${all_account_ids_string}" \
-    --finality-version "${FINALITY_VERSION}"
+    --finality-version "${FINALITY_VERSION}" \
+    ${genesis_args[@]+"${genesis_args[@]}"}
 
   if [[ "${DONT_REMOVE_ABFT_BACKUPS}" == "true" ]]; then
     all_account_ids=(${validator_account_ids[@]} ${rpc_node_account_ids[@]})
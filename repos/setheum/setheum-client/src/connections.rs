@@ -46,7 +46,7 @@ use subxt::{
     blocks::ExtrinsicEvents,
     ext::sp_core::Bytes,
     metadata::DecodeWithMetadata,
-    rpc::RpcParams,
+    rpc::{types::DryRunResult, RpcParams},
     storage::{
         address::{Address, StaticStorageMapKey, Yes},
         StorageAddress,
@@ -55,8 +55,8 @@ use subxt::{
 };
 
 use crate::{
-    api, runtime_types::sp_weights::weight_v2::Weight, AccountId, BlockHash, Call, KeyPair,
-    ParamsBuilder, SetBFTConfig, SubxtClient, TxHash, TxStatus,
+    api, dynamic::DynamicStorage, runtime_types::sp_weights::weight_v2::Weight, AccountId, Balance,
+    BlockHash, Call, KeyPair, ParamsBuilder, SetBFTConfig, SubxtClient, TxHash, TxStatus,
 };
 
 /// Capable of communicating with a live SetBFT chain.
@@ -132,6 +132,33 @@ pub trait ConnectionApi: Sync {
         at: Option<BlockHash>,
     ) -> Option<T>;
 
+    /// Retrieves a decoded value of a storage entry missing from the generated api.
+    ///
+    /// # Panic
+    /// This method `panic`s, in case the address or the value does not match the node's metadata,
+    /// or in case there is no value and the entry has no default one
+    /// * `addrs` - address of the entry, see [`DynamicStorage`]
+    /// * `at` - optional block hash to query state from
+    async fn get_dynamic_storage_entry<T: Decode + Send + Sync>(
+        &self,
+        addrs: &DynamicStorage<T>,
+        at: Option<BlockHash>,
+    ) -> T;
+
+    /// Retrieves a decoded value of a storage entry missing from the generated api, or its
+    /// default value if the node declares one.
+    ///
+    /// # Panic
+    /// This method `panic`s, in case the address or the value does not match the node's metadata,
+    /// but does _not_ `panic` if there is no such value
+    /// * `addrs` - address of the entry, see [`DynamicStorage`]
+    /// * `at` - optional block hash to query state from
+    async fn get_dynamic_storage_entry_maybe<T: Decode + Send + Sync>(
+        &self,
+        addrs: &DynamicStorage<T>,
+        at: Option<BlockHash>,
+    ) -> Option<T>;
+
     /// Submit a RPC call.
     ///
     /// * `func_name` - name of a RPC call
@@ -331,6 +358,46 @@ impl<C: AsConnection + Sync> ConnectionApi for C {
         block.fetch(addrs).await.expect("Should access storage")
     }
 
+    async fn get_dynamic_storage_entry<T: Decode + Send + Sync>(
+        &self,
+        addrs: &DynamicStorage<T>,
+        at: Option<BlockHash>,
+    ) -> T {
+        self.get_dynamic_storage_entry_maybe(addrs, at)
+            .await
+            .expect("There should be a value")
+    }
+
+    async fn get_dynamic_storage_entry_maybe<T: Decode + Send + Sync>(
+        &self,
+        addrs: &DynamicStorage<T>,
+        at: Option<BlockHash>,
+    ) -> Option<T> {
+        info!(target: "setheum-client", "accessing storage at {}::{} at block {:?}", addrs.pallet_name(), addrs.entry_name(), at);
+
+        let client = self.as_connection().as_client();
+        let metadata = client.metadata();
+        let (key, default) = addrs
+            .storage_key(&metadata)
+            .expect("Storage address should match the metadata");
+        let storage = client.storage();
+        let block = match at {
+            Some(block_hash) => storage.at(block_hash),
+            None => storage.at_latest().await.expect("Should access storage"),
+        };
+
+        block
+            .fetch_raw(&key[..])
+            .await
+            .expect("Should access storage")
+            .or(default)
+            .map(|value| {
+                addrs
+                    .decode(&metadata, &value)
+                    .expect("Storage value should match the metadata")
+            })
+    }
+
     async fn rpc_call<R: Decode>(&self, func_name: String, params: RpcParams) -> anyhow::Result<R> {
         info!(target: "setheum-client", "submitting rpc call `{}`, with params {:?}", func_name, params.clone().build());
         let bytes: Bytes = self
@@ -385,6 +452,16 @@ impl SubmittableExtrinsic {
             }
         })
     }
+
+    /// Executes the extrinsic on top of the best block via `system_dryRun`, without submitting it.
+    pub async fn dry_run(&self) -> anyhow::Result<DryRunResult> {
+        Ok(self.submittable.dry_run(None).await?)
+    }
+
+    /// Estimates the fee of the extrinsic, without a tip, via `TransactionPaymentApi_query_info`.
+    pub async fn partial_fee(&self) -> anyhow::Result<Balance> {
+        Ok(self.submittable.partial_fee_estimate().await?)
+    }
 }
 
 #[async_trait::async_trait]
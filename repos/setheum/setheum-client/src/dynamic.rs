@@ -0,0 +1,268 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Calls and storage entries of pallets that are missing from the generated [`crate::api`].
+//!
+//! Arguments and keys are given as their `primitives` types and checked against the types the
+//! node's metadata declares for them before anything is sent, and values read from storage are
+//! checked the same way before being decoded. Names, key hashers and type shapes all come from
+//! the node, so unlike statically generated code there is nothing here that can get out of date
+//! with the runtime.
+
+use codec::{Decode, DecodeAll, Encode};
+use subxt::{
+    error::MetadataError,
+    ext::{
+        scale_value,
+        sp_core::hashing::{blake2_128, blake2_256, twox_128, twox_256, twox_64},
+    },
+    metadata::{
+        types::{StorageEntryModifier, StorageEntryType, StorageHasher},
+        Metadata,
+    },
+    tx::TxPayload,
+    Error,
+};
+
+use crate::runtime_types::sp_weights::weight_v2::Weight;
+
+enum Argument {
+    Encoded(Vec<u8>),
+    Call(DynamicCall),
+}
+
+/// A call of a pallet missing from the generated api.
+pub struct DynamicCall {
+    pallet: &'static str,
+    call: &'static str,
+    arguments: Vec<Argument>,
+}
+
+impl DynamicCall {
+    /// Creates a call `call` of pallet `pallet` without arguments.
+    pub fn new(pallet: &'static str, call: &'static str) -> Self {
+        DynamicCall {
+            pallet,
+            call,
+            arguments: Vec::new(),
+        }
+    }
+
+    /// Appends the next argument of the call.
+    pub fn arg<T: Encode>(mut self, argument: T) -> Self {
+        self.arguments.push(Argument::Encoded(argument.encode()));
+        self
+    }
+
+    /// Appends the next argument of the call, itself a call of the runtime.
+    pub fn call_arg(mut self, call: DynamicCall) -> Self {
+        self.arguments.push(Argument::Call(call));
+        self
+    }
+
+    /// Wraps the call into a `sudo_unchecked_weight` call of pallet sudo.
+    pub fn sudo_unchecked(self) -> Self {
+        DynamicCall::new("Sudo", "sudo_unchecked_weight")
+            .call_arg(self)
+            .arg(Weight {
+                ref_time: 0,
+                proof_size: 0,
+            })
+    }
+}
+
+impl TxPayload for DynamicCall {
+    fn encode_call_data_to(&self, metadata: &Metadata, out: &mut Vec<u8>) -> Result<(), Error> {
+        let pallet = metadata.pallet_by_name_err(self.pallet)?;
+        let call = pallet
+            .call_variant_by_name(self.call)
+            .ok_or_else(|| MetadataError::CallNameNotFound(self.call.to_owned()))?;
+        if call.fields.len() != self.arguments.len() {
+            return Err(Error::Other(format!(
+                "{}::{} takes {} arguments, {} were given",
+                self.pallet,
+                self.call,
+                call.fields.len(),
+                self.arguments.len()
+            )));
+        }
+
+        out.push(pallet.index());
+        out.push(call.index);
+        for (field, argument) in call.fields.iter().zip(&self.arguments) {
+            let encoded = match argument {
+                Argument::Encoded(encoded) => encoded.clone(),
+                Argument::Call(call) => call.encode_call_data(metadata)?,
+            };
+            check_shape(metadata, field.ty.id, &encoded)?;
+            out.extend(encoded);
+        }
+
+        Ok(())
+    }
+}
+
+/// A storage entry of a pallet missing from the generated api, holding values of type `T`.
+pub struct DynamicStorage<T> {
+    pallet: &'static str,
+    entry: &'static str,
+    keys: Vec<Vec<u8>>,
+    _phantom: std::marker::PhantomData<T>,
+}
+
+impl<T: Decode> DynamicStorage<T> {
+    /// Creates an address of storage entry `entry` of pallet `pallet`, without any keys.
+    pub fn new(pallet: &'static str, entry: &'static str) -> Self {
+        DynamicStorage {
+            pallet,
+            entry,
+            keys: Vec::new(),
+            _phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Appends the next key of a storage map.
+    pub fn key<K: Encode>(mut self, key: K) -> Self {
+        self.keys.push(key.encode());
+        self
+    }
+
+    /// Name of the pallet the entry belongs to.
+    pub fn pallet_name(&self) -> &str {
+        self.pallet
+    }
+
+    /// Name of the storage entry.
+    pub fn entry_name(&self) -> &str {
+        self.entry
+    }
+
+    /// Returns the storage key of the address, together with the encoded default value the node
+    /// declares for the entry, if any.
+    pub(crate) fn storage_key(
+        &self,
+        metadata: &Metadata,
+    ) -> Result<(Vec<u8>, Option<Vec<u8>>), Error> {
+        let entry = self.entry_metadata(metadata)?;
+        let mut key = twox_128(self.pallet.as_bytes()).to_vec();
+        key.extend(twox_128(self.entry.as_bytes()));
+
+        match entry.entry_type() {
+            StorageEntryType::Plain(_) if self.keys.is_empty() => {}
+            StorageEntryType::Map {
+                hashers, key_ty, ..
+            } if hashers.len() == self.keys.len() => {
+                check_shape(metadata, *key_ty, &self.keys.concat())?;
+                for (hasher, encoded) in hashers.iter().zip(&self.keys) {
+                    hash_key(hasher, encoded, &mut key);
+                }
+            }
+            _ => {
+                return Err(Error::Other(format!(
+                    "{}::{} does not take {} keys",
+                    self.pallet,
+                    self.entry,
+                    self.keys.len()
+                )))
+            }
+        }
+
+        let default = match entry.modifier() {
+            StorageEntryModifier::Default => Some(entry.default_bytes().to_vec()),
+            StorageEntryModifier::Optional => None,
+        };
+
+        Ok((key, default))
+    }
+
+    /// Decodes a value read from the entry.
+    pub(crate) fn decode(&self, metadata: &Metadata, encoded: &[u8]) -> Result<T, Error> {
+        let value_ty = match self.entry_metadata(metadata)?.entry_type() {
+            StorageEntryType::Plain(value_ty) => *value_ty,
+            StorageEntryType::Map { value_ty, .. } => *value_ty,
+        };
+        check_shape(metadata, value_ty, encoded)?;
+
+        Ok(T::decode_all(&mut &encoded[..])?)
+    }
+
+    fn entry_metadata<'a>(
+        &self,
+        metadata: &'a Metadata,
+    ) -> Result<&'a subxt::metadata::types::StorageEntryMetadata, Error> {
+        let pallet = metadata.pallet_by_name_err(self.pallet)?;
+        let storage = pallet
+            .storage()
+            .ok_or_else(|| MetadataError::StorageNotFoundInPallet(self.pallet.to_owned()))?;
+
+        Ok(storage
+            .entry_by_name(self.entry)
+            .ok_or_else(|| MetadataError::StorageEntryNotFound(self.entry.to_owned()))?)
+    }
+}
+
+/// Checks that `encoded` is exactly one value of type `type_id` of the node's metadata.
+fn check_shape(metadata: &Metadata, type_id: u32, encoded: &[u8]) -> Result<(), Error> {
+    let mut input = encoded;
+    scale_value::scale::decode_as_type(&mut input, type_id, metadata.types())
+        .map_err(|e| Error::Other(format!("value does not match the node's type: {e}")))?;
+    if !input.is_empty() {
+        return Err(Error::Other(
+            "value is longer than the node's type".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn hash_key(hasher: &StorageHasher, encoded: &[u8], out: &mut Vec<u8>) {
+    match hasher {
+        StorageHasher::Identity => out.extend(encoded),
+        StorageHasher::Blake2_128 => out.extend(blake2_128(encoded)),
+        StorageHasher::Blake2_256 => out.extend(blake2_256(encoded)),
+        StorageHasher::Blake2_128Concat => {
+            out.extend(blake2_128(encoded));
+            out.extend(encoded);
+        }
+        StorageHasher::Twox128 => out.extend(twox_128(encoded)),
+        StorageHasher::Twox256 => out.extend(twox_256(encoded)),
+        StorageHasher::Twox64Concat => {
+            out.extend(twox_64(encoded));
+            out.extend(encoded);
+        }
+    }
+}
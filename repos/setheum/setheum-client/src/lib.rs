@@ -72,6 +72,7 @@ mod setheum;
 
 mod connections;
 pub mod contract;
+mod dynamic;
 /// API for pallets.
 pub mod pallets;
 mod runtime_types;
@@ -103,6 +104,7 @@ pub use connections::{
     AsConnection, AsSigned, Connection, ConnectionApi, RootConnection, SignedConnection,
     SignedConnectionApi, SudoCall, TxInfo,
 };
+pub use dynamic::{DynamicCall, DynamicStorage};
 
 /// An alias for a configuration of live chain, e.g. block index type, hash type.
 pub enum SetBFTConfig {}
@@ -0,0 +1,217 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use codec::{Compact, Decode, Encode};
+use subxt::utils::MultiAddress;
+
+use crate::{
+    connections::TxInfo, AccountId, Amount, Balance, BlockHash, ConnectionApi, CurrencyId,
+    DynamicCall, DynamicStorage, RootConnection, SignedConnectionApi, TxStatus,
+};
+
+/// Balance of an account in a currency kept by pallet tokens.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct AccountData<Balance> {
+    /// Balance that can be transferred.
+    pub free: Balance,
+    /// Balance reserved by other pallets.
+    pub reserved: Balance,
+    /// Part of the free balance that cannot be transferred.
+    pub frozen: Balance,
+}
+
+/// Pallet currencies read-only api.
+///
+/// Balances of the native currency live in pallet balances, see
+/// [`BalanceApi`](crate::pallets::balances::BalanceApi). Every other currency is kept by pallet
+/// tokens, which is what this api queries.
+#[async_trait::async_trait]
+pub trait CurrenciesApi {
+    /// Returns storage `accounts` of pallet tokens for a given account and currency.
+    /// * `account` - an account to query
+    /// * `currency_id` - a currency to query
+    /// * `at` - optional hash of a block to query state from
+    async fn token_account(
+        &self,
+        account: AccountId,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> AccountData<Balance>;
+
+    /// Returns free balance of a given account in a given currency.
+    /// * `account` - an account to query
+    /// * `currency_id` - a currency to query
+    /// * `at` - optional hash of a block to query state from
+    async fn token_free_balance(
+        &self,
+        account: AccountId,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Balance;
+
+    /// Returns storage `total_issuance` of pallet tokens for a given currency.
+    /// * `currency_id` - a currency to query
+    /// * `at` - optional hash of a block to query state from
+    async fn token_total_issuance(&self, currency_id: CurrencyId, at: Option<BlockHash>)
+        -> Balance;
+}
+
+/// Pallet currencies api.
+#[async_trait::async_trait]
+pub trait CurrenciesUserApi {
+    /// API for `transfer` call.
+    /// * `dest` - an account to send tokens to
+    /// * `currency_id` - a currency to transfer
+    /// * `amount` - an amount to transfer
+    async fn transfer_currency(
+        &self,
+        dest: AccountId,
+        currency_id: CurrencyId,
+        amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `transfer_native_currency` call.
+    /// * `dest` - an account to send tokens to
+    /// * `amount` - an amount to transfer
+    async fn transfer_native_currency(
+        &self,
+        dest: AccountId,
+        amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+/// Pallet currencies api issued by the sudo account.
+#[async_trait::async_trait]
+pub trait CurrenciesSudoApi {
+    /// API for `update_balance` call.
+    /// * `who` - an account whose balance gets updated
+    /// * `currency_id` - a currency to update
+    /// * `amount` - a signed amount to add to (or, if negative, remove from) the balance
+    async fn update_balance(
+        &self,
+        who: AccountId,
+        currency_id: CurrencyId,
+        amount: Amount,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> CurrenciesApi for C {
+    async fn token_account(
+        &self,
+        account: AccountId,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> AccountData<Balance> {
+        let addrs = DynamicStorage::new("Tokens", "Accounts")
+            .key(account)
+            .key(currency_id);
+
+        self.get_dynamic_storage_entry(&addrs, at).await
+    }
+
+    async fn token_free_balance(
+        &self,
+        account: AccountId,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Balance {
+        self.token_account(account, currency_id, at).await.free
+    }
+
+    async fn token_total_issuance(
+        &self,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Balance {
+        let addrs = DynamicStorage::new("Tokens", "TotalIssuance").key(currency_id);
+
+        self.get_dynamic_storage_entry(&addrs, at).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> CurrenciesUserApi for S {
+    async fn transfer_currency(
+        &self,
+        dest: AccountId,
+        currency_id: CurrencyId,
+        amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("Currencies", "transfer")
+            .arg(MultiAddress::<_, ()>::Id(dest))
+            .arg(currency_id)
+            .arg(Compact(amount));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn transfer_native_currency(
+        &self,
+        dest: AccountId,
+        amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("Currencies", "transfer_native_currency")
+            .arg(MultiAddress::<_, ()>::Id(dest))
+            .arg(Compact(amount));
+
+        self.send_tx(tx, status).await
+    }
+}
+
+#[async_trait::async_trait]
+impl CurrenciesSudoApi for RootConnection {
+    async fn update_balance(
+        &self,
+        who: AccountId,
+        currency_id: CurrencyId,
+        amount: Amount,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("Currencies", "update_balance")
+            .arg(MultiAddress::<_, ()>::Id(who))
+            .arg(currency_id)
+            .arg(amount);
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+}
@@ -0,0 +1,414 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use codec::{Compact, Decode, Encode};
+
+use crate::{
+    connections::TxInfo, AccountId, Balance, BlockHash, BlockNumber, ConnectionApi, CurrencyId,
+    DynamicCall, DynamicStorage, RootConnection, SignedConnectionApi, TradingPair, TxStatus,
+};
+
+/// Parameters of a trading pair in the provisioning status.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct ProvisioningParameters<Balance, BlockNumber> {
+    /// Least contribution of both currencies a single provision has to make.
+    pub min_contribution: (Balance, Balance),
+    /// Provision of both currencies needed before the pair can be enabled.
+    pub target_provision: (Balance, Balance),
+    /// Provision of both currencies accumulated so far.
+    pub accumulated_provision: (Balance, Balance),
+    /// Trading fee of the pair once it is enabled.
+    pub trading_fee: (u32, u32),
+    /// Block before which the pair cannot be enabled.
+    pub not_before: BlockNumber,
+}
+
+/// Status of a trading pair.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum TradingPairStatus<Balance, BlockNumber> {
+    /// The pair cannot be traded, only liquidity can be withdrawn.
+    Disabled,
+    /// The pair collects provision before it gets enabled.
+    Provisioning(ProvisioningParameters<Balance, BlockNumber>),
+    /// The pair can be traded.
+    Enabled,
+}
+
+/// Pallet dex read-only api.
+#[async_trait::async_trait]
+pub trait DexApi {
+    /// Returns storage `liquidity_pool` for a given trading pair, i.e. pool reserves of both
+    /// currencies in the order they appear in `trading_pair`.
+    /// * `trading_pair` - a trading pair to query
+    /// * `at` - optional hash of a block to query state from
+    async fn liquidity_pool(
+        &self,
+        trading_pair: TradingPair,
+        at: Option<BlockHash>,
+    ) -> (Balance, Balance);
+
+    /// Returns storage `trading_pair_statuses` for a given trading pair.
+    /// * `trading_pair` - a trading pair to query
+    /// * `at` - optional hash of a block to query state from
+    async fn trading_pair_status(
+        &self,
+        trading_pair: TradingPair,
+        at: Option<BlockHash>,
+    ) -> TradingPairStatus<Balance, BlockNumber>;
+
+    /// Returns storage `provisioning_pool`, i.e. provision of `account` to a given trading pair.
+    /// * `trading_pair` - a trading pair to query
+    /// * `account` - a provisioning account
+    /// * `at` - optional hash of a block to query state from
+    async fn provisioning_pool(
+        &self,
+        trading_pair: TradingPair,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> (Balance, Balance);
+
+    /// Returns storage `trading_fees` for a given trading pair, `None` if the pair uses the
+    /// runtime default fee.
+    /// * `trading_pair` - a trading pair to query
+    /// * `at` - optional hash of a block to query state from
+    async fn trading_fee(
+        &self,
+        trading_pair: TradingPair,
+        at: Option<BlockHash>,
+    ) -> Option<(u32, u32)>;
+}
+
+/// Pallet dex api.
+#[async_trait::async_trait]
+pub trait DexUserApi {
+    /// API for `swap_with_exact_supply` call.
+    /// * `path` - a swap path, from the supplied currency to the target one
+    /// * `supply_amount` - an exact amount of the first currency in `path` to swap
+    /// * `min_target_amount` - the least acceptable amount of the last currency in `path`
+    async fn swap_with_exact_supply(
+        &self,
+        path: Vec<CurrencyId>,
+        supply_amount: Balance,
+        min_target_amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `swap_with_exact_target` call.
+    /// * `path` - a swap path, from the supplied currency to the target one
+    /// * `target_amount` - an exact amount of the last currency in `path` to receive
+    /// * `max_supply_amount` - the most acceptable amount of the first currency in `path`
+    async fn swap_with_exact_target(
+        &self,
+        path: Vec<CurrencyId>,
+        target_amount: Balance,
+        max_supply_amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `add_liquidity` call.
+    async fn add_liquidity(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        max_amount_a: Balance,
+        max_amount_b: Balance,
+        min_share_increment: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `add_provision` call.
+    async fn add_provision(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        amount_a: Balance,
+        amount_b: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `remove_liquidity` call.
+    async fn remove_liquidity(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        remove_share: Balance,
+        min_withdrawn_a: Balance,
+        min_withdrawn_b: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+/// Pallet dex api issued by the sudo account.
+#[async_trait::async_trait]
+pub trait DexSudoApi {
+    /// API for `list_provisioning` call.
+    async fn list_provisioning(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        min_contribution: (Balance, Balance),
+        target_provision: (Balance, Balance),
+        not_before: BlockNumber,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `enable_trading_pair` call.
+    async fn enable_trading_pair(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `disable_trading_pair` call.
+    async fn disable_trading_pair(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `set_trading_fee` call.
+    /// * `trading_fee` - fee rate as `(numerator, denominator)`
+    async fn set_trading_fee(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        trading_fee: (u32, u32),
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> DexApi for C {
+    async fn liquidity_pool(
+        &self,
+        trading_pair: TradingPair,
+        at: Option<BlockHash>,
+    ) -> (Balance, Balance) {
+        let addrs = DynamicStorage::new("Dex", "LiquidityPool").key(trading_pair);
+
+        self.get_dynamic_storage_entry(&addrs, at).await
+    }
+
+    async fn trading_pair_status(
+        &self,
+        trading_pair: TradingPair,
+        at: Option<BlockHash>,
+    ) -> TradingPairStatus<Balance, BlockNumber> {
+        let addrs = DynamicStorage::new("Dex", "TradingPairStatuses").key(trading_pair);
+
+        self.get_dynamic_storage_entry(&addrs, at).await
+    }
+
+    async fn provisioning_pool(
+        &self,
+        trading_pair: TradingPair,
+        account: AccountId,
+        at: Option<BlockHash>,
+    ) -> (Balance, Balance) {
+        let addrs = DynamicStorage::new("Dex", "ProvisioningPool")
+            .key(trading_pair)
+            .key(account);
+
+        self.get_dynamic_storage_entry(&addrs, at).await
+    }
+
+    async fn trading_fee(
+        &self,
+        trading_pair: TradingPair,
+        at: Option<BlockHash>,
+    ) -> Option<(u32, u32)> {
+        let addrs = DynamicStorage::new("Dex", "TradingFees").key(trading_pair);
+
+        self.get_dynamic_storage_entry_maybe(&addrs, at).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> DexUserApi for S {
+    async fn swap_with_exact_supply(
+        &self,
+        path: Vec<CurrencyId>,
+        supply_amount: Balance,
+        min_target_amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("Dex", "swap_with_exact_supply")
+            .arg(path)
+            .arg(Compact(supply_amount))
+            .arg(Compact(min_target_amount));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn swap_with_exact_target(
+        &self,
+        path: Vec<CurrencyId>,
+        target_amount: Balance,
+        max_supply_amount: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("Dex", "swap_with_exact_target")
+            .arg(path)
+            .arg(Compact(target_amount))
+            .arg(Compact(max_supply_amount));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn add_liquidity(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        max_amount_a: Balance,
+        max_amount_b: Balance,
+        min_share_increment: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("Dex", "add_liquidity")
+            .arg(currency_id_a)
+            .arg(currency_id_b)
+            .arg(Compact(max_amount_a))
+            .arg(Compact(max_amount_b))
+            .arg(Compact(min_share_increment));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn add_provision(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        amount_a: Balance,
+        amount_b: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("Dex", "add_provision")
+            .arg(currency_id_a)
+            .arg(currency_id_b)
+            .arg(Compact(amount_a))
+            .arg(Compact(amount_b));
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn remove_liquidity(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        remove_share: Balance,
+        min_withdrawn_a: Balance,
+        min_withdrawn_b: Balance,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("Dex", "remove_liquidity")
+            .arg(currency_id_a)
+            .arg(currency_id_b)
+            .arg(Compact(remove_share))
+            .arg(Compact(min_withdrawn_a))
+            .arg(Compact(min_withdrawn_b))
+            .arg(false);
+
+        self.send_tx(tx, status).await
+    }
+}
+
+#[async_trait::async_trait]
+impl DexSudoApi for RootConnection {
+    async fn list_provisioning(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        min_contribution: (Balance, Balance),
+        target_provision: (Balance, Balance),
+        not_before: BlockNumber,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("Dex", "list_provisioning")
+            .arg(currency_id_a)
+            .arg(currency_id_b)
+            .arg(Compact(min_contribution.0))
+            .arg(Compact(min_contribution.1))
+            .arg(Compact(target_provision.0))
+            .arg(Compact(target_provision.1))
+            .arg(Compact(not_before));
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+
+    async fn enable_trading_pair(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("Dex", "enable_trading_pair")
+            .arg(currency_id_a)
+            .arg(currency_id_b);
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+
+    async fn disable_trading_pair(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("Dex", "disable_trading_pair")
+            .arg(currency_id_a)
+            .arg(currency_id_b);
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+
+    async fn set_trading_fee(
+        &self,
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+        trading_fee: (u32, u32),
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("Dex", "set_trading_fee")
+            .arg(currency_id_a)
+            .arg(currency_id_b)
+            .arg(trading_fee);
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+}
@@ -43,6 +43,10 @@ pub mod balances;
 pub mod committee_management;
 /// Pallet contracts API
 pub mod contract;
+/// Pallet currencies API
+pub mod currencies;
+/// Pallet dex API
+pub mod dex;
 /// Pallet elections API
 pub mod elections;
 /// Pallet feature control API
@@ -51,6 +55,12 @@ pub mod feature_control;
 pub mod fee;
 /// Pallet multisig API
 pub mod multisig;
+/// Pallet nft API
+pub mod nft;
+/// Pallet oracle API
+pub mod oracle;
+/// Pallet prices API
+pub mod prices;
 /// Pallet Proxy API
 pub mod proxy;
 /// Pallet safe mode API
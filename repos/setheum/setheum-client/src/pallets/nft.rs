@@ -0,0 +1,237 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use anyhow::anyhow;
+use codec::Compact;
+use primitives::nft::{Attributes, Properties, CID};
+use subxt::utils::MultiAddress;
+
+use crate::{
+    connections::TxInfo, setheum_runtime::ProxyType, AccountId, Balance, BlockHash,
+    ConnectionApi, DynamicCall, DynamicStorage, SignedConnectionApi, TxStatus,
+};
+
+/// An alias for a nft class id type.
+pub type ClassId = u32;
+/// An alias for a nft token id type.
+pub type TokenId = u64;
+
+/// Layout of storage `classes`: metadata, total issuance, owner and data, i.e. deposit, properties
+/// and attributes.
+type ClassInfo = (CID, TokenId, AccountId, (Balance, Properties, Attributes));
+/// Layout of storage `tokens`: metadata, owner and data, i.e. deposit and attributes.
+type TokenInfo = (CID, AccountId, (Balance, Attributes));
+
+/// Pallet nft read-only api.
+#[async_trait::async_trait]
+pub trait NftApi {
+    /// Returns storage `next_class_id`, i.e. id the next created class will get.
+    /// * `at` - optional hash of a block to query state from
+    async fn next_class_id(&self, at: Option<BlockHash>) -> ClassId;
+
+    /// Returns storage `next_token_id` for a given class, i.e. id the next minted token will get.
+    /// * `class_id` - a class to query
+    /// * `at` - optional hash of a block to query state from
+    async fn next_token_id(&self, class_id: ClassId, at: Option<BlockHash>) -> TokenId;
+
+    /// Returns owner of a given class, `None` if the class does not exist.
+    /// * `class_id` - a class to query
+    /// * `at` - optional hash of a block to query state from
+    async fn class_owner(&self, class_id: ClassId, at: Option<BlockHash>) -> Option<AccountId>;
+
+    /// Returns owner of a given token, `None` if the token does not exist.
+    /// * `token` - a `(class_id, token_id)` pair to query
+    /// * `at` - optional hash of a block to query state from
+    async fn token_owner(
+        &self,
+        token: (ClassId, TokenId),
+        at: Option<BlockHash>,
+    ) -> Option<AccountId>;
+}
+
+/// Pallet nft api.
+#[async_trait::async_trait]
+pub trait NftUserApi {
+    /// API for `create_class` call.
+    /// * `metadata` - class metadata, e.g. an IPFS CID
+    /// * `properties` - class properties, e.g. whether its tokens are transferable
+    /// * `attributes` - class attributes
+    async fn create_class(
+        &self,
+        metadata: CID,
+        properties: Properties,
+        attributes: Attributes,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `mint` call. Classes are owned by a pallet derived account which the class
+    /// creator is a proxy of, so the call is dispatched via `proxy.proxy` on behalf of the owner.
+    /// * `to` - an account to mint tokens to
+    /// * `class_id` - a class to mint tokens of
+    /// * `metadata` - token metadata
+    /// * `attributes` - token attributes
+    /// * `quantity` - how many tokens to mint
+    async fn mint(
+        &self,
+        to: AccountId,
+        class_id: ClassId,
+        metadata: CID,
+        attributes: Attributes,
+        quantity: u32,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `transfer` call.
+    /// * `to` - an account to send the token to
+    /// * `token` - a `(class_id, token_id)` pair to transfer
+    async fn transfer_nft(
+        &self,
+        to: AccountId,
+        token: (ClassId, TokenId),
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+
+    /// API for `burn` call.
+    /// * `token` - a `(class_id, token_id)` pair to burn
+    async fn burn_nft(&self, token: (ClassId, TokenId), status: TxStatus)
+        -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> NftApi for C {
+    async fn next_class_id(&self, at: Option<BlockHash>) -> ClassId {
+        let addrs = DynamicStorage::new("NFT", "NextClassId");
+
+        self.get_dynamic_storage_entry(&addrs, at).await
+    }
+
+    async fn next_token_id(&self, class_id: ClassId, at: Option<BlockHash>) -> TokenId {
+        let addrs = DynamicStorage::new("NFT", "NextTokenId").key(class_id);
+
+        self.get_dynamic_storage_entry(&addrs, at).await
+    }
+
+    async fn class_owner(&self, class_id: ClassId, at: Option<BlockHash>) -> Option<AccountId> {
+        let addrs = DynamicStorage::<ClassInfo>::new("NFT", "Classes").key(class_id);
+
+        self.get_dynamic_storage_entry_maybe(&addrs, at)
+            .await
+            .map(|(_, _, owner, _)| owner)
+    }
+
+    async fn token_owner(
+        &self,
+        token: (ClassId, TokenId),
+        at: Option<BlockHash>,
+    ) -> Option<AccountId> {
+        let addrs = DynamicStorage::<TokenInfo>::new("NFT", "Tokens")
+            .key(token.0)
+            .key(token.1);
+
+        self.get_dynamic_storage_entry_maybe(&addrs, at)
+            .await
+            .map(|(_, owner, _)| owner)
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> NftUserApi for S {
+    async fn create_class(
+        &self,
+        metadata: CID,
+        properties: Properties,
+        attributes: Attributes,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("NFT", "create_class")
+            .arg(metadata)
+            .arg(properties)
+            .arg(attributes);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn mint(
+        &self,
+        to: AccountId,
+        class_id: ClassId,
+        metadata: CID,
+        attributes: Attributes,
+        quantity: u32,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let owner = self
+            .class_owner(class_id, None)
+            .await
+            .ok_or_else(|| anyhow!("Nft class {class_id} does not exist"))?;
+        let call = DynamicCall::new("NFT", "mint")
+            .arg(MultiAddress::<_, ()>::Id(to))
+            .arg(class_id)
+            .arg(metadata)
+            .arg(attributes)
+            .arg(Compact(quantity));
+        let tx = DynamicCall::new("Proxy", "proxy")
+            .arg(MultiAddress::<_, ()>::Id(owner))
+            .arg(None::<ProxyType>)
+            .call_arg(call);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn transfer_nft(
+        &self,
+        to: AccountId,
+        token: (ClassId, TokenId),
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("NFT", "transfer")
+            .arg(MultiAddress::<_, ()>::Id(to))
+            .arg(token);
+
+        self.send_tx(tx, status).await
+    }
+
+    async fn burn_nft(
+        &self,
+        token: (ClassId, TokenId),
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("NFT", "burn").arg(token);
+
+        self.send_tx(tx, status).await
+    }
+}
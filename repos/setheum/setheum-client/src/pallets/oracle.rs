@@ -0,0 +1,143 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    connections::TxInfo, sp_runtime::FixedU128, AccountId, BlockHash, ConnectionApi, CurrencyId,
+    DynamicCall, DynamicStorage, RootConnection, SignedConnectionApi, TxStatus,
+};
+
+/// Pallet oracle read-only api.
+#[async_trait::async_trait]
+pub trait OracleApi {
+    /// Returns storage `values`, i.e. the combined price of a currency together with the
+    /// timestamp it was fed at.
+    /// * `currency_id` - a currency to query
+    /// * `at` - optional hash of a block to query state from
+    async fn oracle_value(
+        &self,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Option<(FixedU128, u64)>;
+
+    /// Returns storage `raw_values`, i.e. the price of a currency as fed by `operator`, together
+    /// with the timestamp it was fed at.
+    /// * `operator` - an oracle operator account
+    /// * `currency_id` - a currency to query
+    /// * `at` - optional hash of a block to query state from
+    async fn oracle_raw_value(
+        &self,
+        operator: AccountId,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Option<(FixedU128, u64)>;
+}
+
+/// Pallet oracle api.
+#[async_trait::async_trait]
+pub trait OracleUserApi {
+    /// API for `feed_values` call. The signer must be an oracle operator.
+    /// * `values` - a list of currencies with their prices
+    async fn feed_values(
+        &self,
+        values: Vec<(CurrencyId, FixedU128)>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+/// Pallet oracle api issued by the sudo account.
+#[async_trait::async_trait]
+pub trait OracleSudoApi {
+    /// API for `feed_values` call, fed on behalf of the root operator account.
+    /// * `values` - a list of currencies with their prices
+    async fn feed_values(
+        &self,
+        values: Vec<(CurrencyId, FixedU128)>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> OracleApi for C {
+    async fn oracle_value(
+        &self,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Option<(FixedU128, u64)> {
+        let addrs = DynamicStorage::new("SetheumOracle", "Values").key(currency_id);
+
+        self.get_dynamic_storage_entry_maybe(&addrs, at).await
+    }
+
+    async fn oracle_raw_value(
+        &self,
+        operator: AccountId,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Option<(FixedU128, u64)> {
+        let addrs = DynamicStorage::new("SetheumOracle", "RawValues")
+            .key(operator)
+            .key(currency_id);
+
+        self.get_dynamic_storage_entry_maybe(&addrs, at).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: SignedConnectionApi> OracleUserApi for S {
+    async fn feed_values(
+        &self,
+        values: Vec<(CurrencyId, FixedU128)>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let tx = DynamicCall::new("SetheumOracle", "feed_values").arg(values);
+
+        self.send_tx(tx, status).await
+    }
+}
+
+#[async_trait::async_trait]
+impl OracleSudoApi for RootConnection {
+    async fn feed_values(
+        &self,
+        values: Vec<(CurrencyId, FixedU128)>,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("SetheumOracle", "feed_values").arg(values);
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+}
@@ -0,0 +1,107 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    connections::TxInfo, sp_runtime::FixedU128, BlockHash, ConnectionApi, CurrencyId,
+    DynamicCall, DynamicStorage, RootConnection, SignedConnectionApi, TxStatus,
+};
+
+/// Pallet prices read-only api.
+#[async_trait::async_trait]
+pub trait PricesApi {
+    /// Returns storage `locked_price` for a given currency, `None` if its price is not locked.
+    /// * `currency_id` - a currency to query
+    /// * `at` - optional hash of a block to query state from
+    async fn locked_price(
+        &self,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Option<FixedU128>;
+}
+
+/// Pallet prices api issued by the sudo account.
+#[async_trait::async_trait]
+pub trait PricesSudoApi {
+    /// API for `lock_price` call.
+    /// * `currency_id` - a currency whose current price gets locked
+    async fn lock_price(&self, currency_id: CurrencyId, status: TxStatus)
+        -> anyhow::Result<TxInfo>;
+
+    /// API for `unlock_price` call.
+    /// * `currency_id` - a currency whose price gets unlocked
+    async fn unlock_price(
+        &self,
+        currency_id: CurrencyId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo>;
+}
+
+#[async_trait::async_trait]
+impl<C: ConnectionApi> PricesApi for C {
+    async fn locked_price(
+        &self,
+        currency_id: CurrencyId,
+        at: Option<BlockHash>,
+    ) -> Option<FixedU128> {
+        let addrs = DynamicStorage::new("Prices", "LockedPrice").key(currency_id);
+
+        self.get_dynamic_storage_entry_maybe(&addrs, at).await
+    }
+}
+
+#[async_trait::async_trait]
+impl PricesSudoApi for RootConnection {
+    async fn lock_price(
+        &self,
+        currency_id: CurrencyId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("Prices", "lock_price").arg(currency_id);
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+
+    async fn unlock_price(
+        &self,
+        currency_id: CurrencyId,
+        status: TxStatus,
+    ) -> anyhow::Result<TxInfo> {
+        let call = DynamicCall::new("Prices", "unlock_price").arg(currency_id);
+
+        self.send_tx(call.sudo_unchecked(), status).await
+    }
+}
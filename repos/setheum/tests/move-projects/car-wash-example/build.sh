@@ -0,0 +1,30 @@
+#!/bin/bash
+
+# Builds the car-wash example with setheum-move: the module bundle and one script transaction per
+# step of the e2e test. Prints the build directory, which is what the CAR_WASH variable of e2e-tests
+# expects.
+
+set -euox pipefail
+
+# Accounts baked into the script transactions, they must match the signers used by the e2e test.
+DEVELOPER_BOB="${DEVELOPER_BOB:-5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty}"
+CUSTOMER_ALICE="${CUSTOMER_ALICE:-5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY}"
+
+SCRIPT_DIR="$(cd "$(dirname "${BASH_SOURCE[0]}")" && pwd)"
+BUILD_DIR="${SCRIPT_DIR}/build/car-wash-example"
+
+function create_transaction() {
+  setheum-move create-transaction -p "${SCRIPT_DIR}" \
+    --compiled-script-path "${BUILD_DIR}/bytecode_scripts/$1.mv" \
+    --args "signer:$2" 1>&2
+}
+
+setheum-move build -p "${SCRIPT_DIR}" 1>&2
+setheum-move bundle -p "${SCRIPT_DIR}" 1>&2
+
+create_transaction initial_coin_minting "${DEVELOPER_BOB}"
+create_transaction register_new_user "${CUSTOMER_ALICE}"
+create_transaction buy_coin "${CUSTOMER_ALICE}"
+create_transaction wash_car "${CUSTOMER_ALICE}"
+
+echo "${BUILD_DIR}"
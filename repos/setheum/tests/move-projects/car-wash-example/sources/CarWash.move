@@ -0,0 +1,70 @@
+/// Simple car wash, where coins are bought for the native currency and spent on washes.
+module DeveloperBob::CarWash {
+    use std::signer;
+    use substrate::balance;
+
+    /// Address of the module owner, who sells the coins.
+    const MODULE_OWNER: address = @DeveloperBob;
+
+    /// Price of one car wash coin, in the smallest unit of the native currency.
+    const COIN_PRICE: u128 = 1000000000000;
+
+    /// Error codes
+    const NOT_MODULE_OWNER: u64 = 0;
+    const MODULE_NOT_INITIALIZED: u64 = 1;
+    const MODULE_ALREADY_INITIALIZED: u64 = 2;
+    const USER_ALREADY_EXISTS: u64 = 3;
+    const USER_DOES_NOT_EXIST: u64 = 4;
+    const NO_COINS_AVAILABLE: u64 = 5;
+    const COIN_LIMIT_REACHED: u64 = 6;
+
+    /// Number of coins held by an account.
+    struct Balance has key {
+        coins: u8
+    }
+
+    /// Mints the whole coin supply to the module owner.
+    public fun initial_coin_minting(module_owner: &signer) {
+        let module_owner_addr = signer::address_of(module_owner);
+        assert!(module_owner_addr == MODULE_OWNER, NOT_MODULE_OWNER);
+        assert!(!exists<Balance>(module_owner_addr), MODULE_ALREADY_INITIALIZED);
+        move_to(module_owner, Balance { coins: 255 });
+    }
+
+    /// Registers a new user with an empty balance.
+    public fun register_new_user(account: &signer) {
+        assert!(exists<Balance>(MODULE_OWNER), MODULE_NOT_INITIALIZED);
+        assert!(!exists<Balance>(signer::address_of(account)), USER_ALREADY_EXISTS);
+        move_to(account, Balance { coins: 0 });
+    }
+
+    /// Buys one coin, paying `COIN_PRICE` to the module owner.
+    public fun buy_coin(user: &signer) acquires Balance {
+        let user_addr = signer::address_of(user);
+        assert!(exists<Balance>(MODULE_OWNER), MODULE_NOT_INITIALIZED);
+        assert!(exists<Balance>(user_addr), USER_DOES_NOT_EXIST);
+
+        balance::transfer(user, MODULE_OWNER, COIN_PRICE);
+        transfer_coin(MODULE_OWNER, user_addr);
+    }
+
+    /// Spends one coin on a car wash.
+    public fun wash_car(user: &signer) acquires Balance {
+        let user_addr = signer::address_of(user);
+        assert!(exists<Balance>(user_addr), USER_DOES_NOT_EXIST);
+
+        let coins = &mut borrow_global_mut<Balance>(user_addr).coins;
+        assert!(*coins > 0, NO_COINS_AVAILABLE);
+        *coins = *coins - 1;
+    }
+
+    fun transfer_coin(src: address, dst: address) acquires Balance {
+        let src_coins = &mut borrow_global_mut<Balance>(src).coins;
+        assert!(*src_coins > 0, NO_COINS_AVAILABLE);
+        *src_coins = *src_coins - 1;
+
+        let dst_coins = &mut borrow_global_mut<Balance>(dst).coins;
+        assert!(*dst_coins < 255, COIN_LIMIT_REACHED);
+        *dst_coins = *dst_coins + 1;
+    }
+}
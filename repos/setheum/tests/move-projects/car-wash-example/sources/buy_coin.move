@@ -0,0 +1,7 @@
+script {
+    use DeveloperBob::CarWash;
+
+    fun buy_coin(account: signer) {
+        CarWash::buy_coin(&account);
+    }
+}
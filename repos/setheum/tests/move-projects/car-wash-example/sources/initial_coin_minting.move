@@ -0,0 +1,7 @@
+script {
+    use DeveloperBob::CarWash;
+
+    fun initial_coin_minting(module_owner: signer) {
+        CarWash::initial_coin_minting(&module_owner);
+    }
+}
@@ -0,0 +1,7 @@
+script {
+    use DeveloperBob::CarWash;
+
+    fun register_new_user(account: signer) {
+        CarWash::register_new_user(&account);
+    }
+}
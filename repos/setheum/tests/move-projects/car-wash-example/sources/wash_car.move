@@ -0,0 +1,7 @@
+script {
+    use DeveloperBob::CarWash;
+
+    fun wash_car(account: signer) {
+        CarWash::wash_car(&account);
+    }
+}
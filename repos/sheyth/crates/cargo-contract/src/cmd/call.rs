@@ -48,6 +48,7 @@ use std::{
         Debug,
         Display,
     },
+    path::PathBuf,
     str::FromStr,
 };
 
@@ -56,6 +57,7 @@ use super::{
     display_contract_exec_result,
     display_contract_exec_result_debug,
     display_dry_run_result_warning,
+    dry_run_summary,
     parse_account,
     parse_balance,
     print_dry_running_status,
@@ -74,9 +76,11 @@ use contract_extrinsics::{
     pallet_contracts_primitives::StorageDeposit,
     CallCommandBuilder,
     CallExec,
+    ContractArtifacts,
     DisplayEvents,
     ExtrinsicOptsBuilder,
     TokenMetadata,
+    TraceDecoder,
 };
 use contract_transcode::Value;
 use sp_weights::Weight;
@@ -86,6 +90,10 @@ use subxt::{
         ExtrinsicParams,
     },
     ext::{
+        codec::{
+            Decode,
+            Encode,
+        },
         scale_decode::IntoVisitor,
         scale_encode::EncodeAsType,
     },
@@ -119,6 +127,14 @@ pub struct CallCommand {
     /// The value to be transferred as part of the call.
     #[clap(name = "value", long, default_value = "0")]
     value: String,
+    /// Dry run the call and display the tree of nested calls, delegate calls and
+    /// instantiations it performs.
+    #[clap(long, conflicts_with = "execute")]
+    trace: bool,
+    /// Metadata or bundle files of other contracts, used to decode the calls into
+    /// them when tracing.
+    #[clap(long, requires = "trace", num_args = 1..)]
+    trace_metadata: Vec<PathBuf>,
     /// Export the call output in JSON format.
     #[clap(long, conflicts_with = "verbose")]
     output_json: bool,
@@ -142,17 +158,24 @@ impl CallCommand {
         &self,
     ) -> Result<(), ErrorVariant>
     where
-        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType,
+        <C as Config>::AccountId: IntoVisitor + FromStr + EncodeAsType + Decode + Display,
         <<C as Config>::AccountId as FromStr>::Err: Display,
-        C::Balance:
-            From<u128> + Display + Default + FromStr + Serialize + Debug + EncodeAsType,
+        C::Balance: From<u128>
+            + Into<u128>
+            + Display
+            + Default
+            + FromStr
+            + Serialize
+            + Debug
+            + EncodeAsType
+            + Decode,
         <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+        C::Address: From<C::AccountId>,
     {
         let contract = parse_account(&self.contract)
             .map_err(|e| anyhow::anyhow!("Failed to parse contract option: {}", e))?;
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
         let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
         let storage_deposit_limit = self
@@ -179,11 +202,14 @@ impl CallCommand {
             .gas_limit(self.gas_limit)
             .proof_size(self.proof_size)
             .value(value)
+            .wrappers(self.extrinsic_cli_opts.offline_cli_opts.wrappers()?)?
             .done()
             .await?;
         let metadata = call_exec.client().metadata();
 
-        if !self.extrinsic_cli_opts.execute {
+        if self.trace {
+            self.display_trace(&call_exec, &token_metadata).await?;
+        } else if !self.extrinsic_cli_opts.execute {
             let result = call_exec.call_dry_run().await?;
             match result.result {
                 Ok(ref ret_val) => {
@@ -231,7 +257,10 @@ impl CallCommand {
                 self.extrinsic_cli_opts.skip_dry_run,
             )
             .await?;
-            if !self.extrinsic_cli_opts.skip_confirm {
+            let offline_cli_opts = &self.extrinsic_cli_opts.offline_cli_opts;
+            if !self.extrinsic_cli_opts.skip_confirm
+                && !offline_cli_opts.writes_unsigned()
+            {
                 prompt_confirm_tx(|| {
                     name_value_println!(
                         "Message",
@@ -250,6 +279,24 @@ impl CallCommand {
                     );
                 })?;
             }
+            if offline_cli_opts.is_set() {
+                let dry_run = if self.extrinsic_cli_opts.skip_dry_run {
+                    None
+                } else {
+                    Some(dry_run_summary(&call_exec.call_dry_run().await?))
+                };
+                let unsigned = call_exec.call_unsigned(Some(gas_limit), dry_run).await?;
+                return offline_cli_opts
+                    .dispatch(
+                        unsigned,
+                        call_exec.opts().signer(),
+                        call_exec.client(),
+                        &token_metadata,
+                        self.extrinsic_cli_opts.verbosity()?,
+                        self.output_json(),
+                    )
+                    .await;
+            }
             let events = call_exec.call(Some(gas_limit)).await?;
             let display_events =
                 DisplayEvents::from_events::<C, C>(&events, None, &metadata)?;
@@ -266,6 +313,52 @@ impl CallCommand {
         }
         Ok(())
     }
+
+    /// Dry runs the call and displays the decoded tree of the calls it performs.
+    async fn display_trace<C: Config + Environment, Signer>(
+        &self,
+        call_exec: &CallExec<C, C, Signer>,
+        token_metadata: &TokenMetadata,
+    ) -> Result<(), ErrorVariant>
+    where
+        Signer: subxt::tx::Signer<C> + Clone,
+        <C as Config>::AccountId: IntoVisitor + EncodeAsType + Decode + Encode + Display,
+        <C as Config>::Hash: Decode + AsRef<[u8]>,
+        C::Balance: Debug + EncodeAsType + Decode + Into<u128>,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let result = call_exec.call_dry_run().await?;
+        let trace = call_exec.call_trace().await?.ok_or_else(|| {
+            anyhow!(
+                "The call did not execute any contract code: {:?}",
+                result.result
+            )
+        })?;
+
+        let mut decoder = TraceDecoder::new(call_exec.contract(), call_exec.transcoder());
+        for path in &self.trace_metadata {
+            let artifacts = ContractArtifacts::from_manifest_or_file(None, Some(path))?;
+            decoder = decoder
+                .with_code(artifacts.code_hash()?, artifacts.contract_transcoder()?);
+        }
+        let trace = decoder.decode(trace)?;
+
+        if self.output_json() {
+            println!("{}", trace.to_json()?);
+        } else {
+            print!("{}", trace.display(token_metadata)?);
+            if let Err(ref err) = result.result {
+                let object = ErrorVariant::from_dispatch_error(
+                    err,
+                    &call_exec.client().metadata(),
+                )?;
+                name_value_println!("Result", object, MAX_KEY_COL_WIDTH);
+            }
+            display_dry_run_result_warning("message");
+        }
+        Ok(())
+    }
 }
 
 /// A helper function to estimate the gas required for a contract call.
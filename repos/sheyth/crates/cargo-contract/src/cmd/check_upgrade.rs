@@ -0,0 +1,1154 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use anyhow::{
+    Context,
+    Result,
+};
+use colored::Colorize;
+use contract_build::{
+    Verbosity,
+    VerbosityFlags,
+};
+use contract_metadata::ContractMetadata;
+use ink_metadata::{
+    layout::Layout,
+    ConstructorSpec,
+    EventSpec,
+    InkProject,
+    MessageParamSpec,
+    MessageSpec,
+    Selector,
+};
+use scale_info::{
+    form::PortableForm,
+    PortableRegistry,
+    TypeDef,
+};
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fmt::{
+        Display,
+        Formatter,
+    },
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// Checks whether a contract can be upgraded to a new version through
+/// `set_code_hash` without corrupting its storage or breaking its callers.
+#[derive(Debug, clap::Args)]
+#[clap(name = "check-upgrade")]
+pub struct CheckUpgradeCommand {
+    /// The currently deployed contract (`*.contract` or `*.json` metadata).
+    #[clap(value_parser)]
+    old: PathBuf,
+    /// The contract to upgrade to (`*.contract` or `*.json` metadata).
+    #[clap(value_parser)]
+    new: PathBuf,
+    /// Denotes if output should be printed to stdout.
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+    /// Output the result in JSON format
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+}
+
+impl CheckUpgradeCommand {
+    pub fn run(&self) -> Result<UpgradeCheckResult> {
+        let verbosity: Verbosity = TryFrom::<&VerbosityFlags>::try_from(&self.verbosity)?;
+        let old = load_project(&self.old)?;
+        let new = load_project(&self.new)?;
+
+        Ok(UpgradeCheckResult {
+            old: self.old.display().to_string(),
+            new: self.new.display().to_string(),
+            changes: check_upgrade(&old, &new),
+            output_json: self.output_json,
+            verbosity,
+        })
+    }
+}
+
+/// Loads the ink! project metadata from a contract bundle or metadata file.
+fn load_project(path: &Path) -> Result<InkProject> {
+    let metadata = ContractMetadata::load(path)?;
+    serde_json::from_value(serde_json::Value::Object(metadata.abi)).context(format!(
+        "Failed to deserialize ink! project metadata from file {}",
+        path.display()
+    ))
+}
+
+/// Whether a change breaks the upgraded contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Existing storage or callers break.
+    Breaking,
+    /// The contract can be upgraded safely.
+    Compatible,
+}
+
+/// The part of the contract a change affects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    Storage,
+    Message,
+    Constructor,
+    Event,
+    Error,
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Storage => write!(f, "storage"),
+            Self::Message => write!(f, "message"),
+            Self::Constructor => write!(f, "constructor"),
+            Self::Event => write!(f, "event"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A difference between the old and the new version of a contract.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Change {
+    pub severity: Severity,
+    pub category: Category,
+    pub description: String,
+}
+
+/// The result of comparing two versions of a contract.
+#[derive(serde::Serialize)]
+pub struct UpgradeCheckResult {
+    pub old: String,
+    pub new: String,
+    pub changes: Vec<Change>,
+    #[serde(skip_serializing)]
+    pub output_json: bool,
+    #[serde(skip_serializing)]
+    pub verbosity: Verbosity,
+}
+
+impl UpgradeCheckResult {
+    /// Returns the number of breaking changes.
+    pub fn breaking_changes(&self) -> usize {
+        self.changes
+            .iter()
+            .filter(|change| change.severity == Severity::Breaking)
+            .count()
+    }
+
+    /// Display the result in a fancy format
+    pub fn display(&self) -> String {
+        let mut out = String::new();
+        for change in &self.changes {
+            let severity = match change.severity {
+                Severity::Breaking => "breaking".bright_red().bold(),
+                Severity::Compatible => "compatible".bright_green().bold(),
+            };
+            out.push_str(&format!(
+                "{severity:>10} {:<11} {}\n",
+                change.category, change.description
+            ));
+        }
+        let summary = match self.breaking_changes() {
+            0 => {
+                format!(
+                    "{} {} to {}",
+                    "Safe to upgrade".bright_green().bold(),
+                    format!("`{}`", self.old).bold(),
+                    format!("`{}`", self.new).bold()
+                )
+            }
+            breaking => {
+                format!(
+                    "{} {} to {}: {breaking} breaking change(s)",
+                    "Unsafe to upgrade".bright_red().bold(),
+                    format!("`{}`", self.old).bold(),
+                    format!("`{}`", self.new).bold()
+                )
+            }
+        };
+        out.push_str(&summary);
+        out
+    }
+
+    /// Display the results in a pretty formatted JSON string.
+    pub fn serialize_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+/// Compares the storage layout and the ABI of two versions of a contract.
+pub fn check_upgrade(old: &InkProject, new: &InkProject) -> Vec<Change> {
+    let old = Contract::new(old);
+    let new = Contract::new(new);
+    let mut changes = Changes::default();
+    check_storage(&old, &new, &mut changes);
+    check_messages(&old, &new, &mut changes);
+    check_constructors(&old, &new, &mut changes);
+    check_events(&old, &new, &mut changes);
+    check_lang_error(&old, &new, &mut changes);
+    changes.0
+}
+
+#[derive(Default)]
+struct Changes(Vec<Change>);
+
+impl Changes {
+    fn breaking(&mut self, category: Category, description: String) {
+        self.push(Severity::Breaking, category, description)
+    }
+
+    fn compatible(&mut self, category: Category, description: String) {
+        self.push(Severity::Compatible, category, description)
+    }
+
+    fn push(&mut self, severity: Severity, category: Category, description: String) {
+        self.0.push(Change {
+            severity,
+            category,
+            description,
+        })
+    }
+}
+
+/// An ink! project along with the means to compare its types to those of another
+/// project, whose type ids are unrelated.
+struct Contract<'a> {
+    project: &'a InkProject,
+    storage: Vec<StorageEntry>,
+}
+
+/// A leaf of the storage layout.
+struct StorageEntry {
+    /// The field names leading to the leaf, e.g. `balances` or `config.owner`.
+    path: String,
+    root_key: u32,
+    ty: u32,
+}
+
+impl<'a> Contract<'a> {
+    fn new(project: &'a InkProject) -> Self {
+        let mut storage = Vec::new();
+        flatten_layout(project.layout(), 0, String::new(), &mut storage);
+        Self { project, storage }
+    }
+
+    fn registry(&self) -> &PortableRegistry {
+        self.project.registry()
+    }
+
+    /// Returns a representation of the SCALE encoding of the type `id`, which is
+    /// equal for types with the same encoding in both projects.
+    fn signature(&self, id: u32) -> String {
+        type_signature(self.registry(), id, &mut Vec::new())
+    }
+
+    /// Returns the human readable name of the type `id`.
+    fn name(&self, id: u32) -> String {
+        type_name(self.registry(), id)
+    }
+
+    /// Splits a `Result<T, E>` type into the ids of `T` and `E`.
+    fn result_parts(&self, id: u32) -> Option<(u32, u32)> {
+        let ty = self.registry().resolve(id)?;
+        if ty.path.segments.last().map(String::as_str) != Some("Result") {
+            return None;
+        }
+        let TypeDef::Variant(def) = &ty.type_def else {
+            return None;
+        };
+        let field = |name: &str| {
+            def.variants
+                .iter()
+                .find(|variant| variant.name == name)
+                .and_then(|variant| variant.fields.first())
+                .map(|field| field.ty.id)
+        };
+        Some((field("Ok")?, field("Err")?))
+    }
+}
+
+fn flatten_layout(
+    layout: &Layout<PortableForm>,
+    root_key: u32,
+    path: String,
+    out: &mut Vec<StorageEntry>,
+) {
+    let join = |name: &str| {
+        if path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{path}.{name}")
+        }
+    };
+    match layout {
+        Layout::Leaf(leaf) => {
+            out.push(StorageEntry {
+                path,
+                root_key,
+                ty: leaf.ty().id,
+            })
+        }
+        Layout::Root(root) => {
+            flatten_layout(root.layout(), *root.root_key().key(), path, out)
+        }
+        Layout::Hash(hash) => {
+            flatten_layout(hash.layout(), root_key, format!("{path}[_]"), out)
+        }
+        Layout::Array(array) => {
+            let path = format!("{path}[_; {}]", array.len());
+            flatten_layout(array.layout(), root_key, path, out)
+        }
+        Layout::Struct(layout) => {
+            for field in layout.fields() {
+                flatten_layout(field.layout(), root_key, join(field.name()), out)
+            }
+        }
+        Layout::Enum(layout) => {
+            for variant in layout.variants().values() {
+                let path = format!("{path}::{}", variant.name());
+                for field in variant.fields() {
+                    let path = format!("{path}.{}", field.name());
+                    flatten_layout(field.layout(), root_key, path, out)
+                }
+            }
+        }
+    }
+}
+
+fn type_signature(
+    registry: &PortableRegistry,
+    id: u32,
+    visiting: &mut Vec<u32>,
+) -> String {
+    let Some(ty) = registry.resolve(id) else {
+        return format!("<unknown type {id}>");
+    };
+    if visiting.contains(&id) {
+        // Recursive types are identified by their path.
+        return ty.path.segments.join("::");
+    }
+    visiting.push(id);
+    let mut render_fields = |fields: &[scale_info::Field<PortableForm>]| {
+        let fields = fields
+            .iter()
+            .map(|field| {
+                let ty = type_signature(registry, field.ty.id, visiting);
+                match &field.name {
+                    Some(name) => format!("{name}: {ty}"),
+                    None => ty,
+                }
+            })
+            .collect::<Vec<_>>();
+        format!("({})", fields.join(", "))
+    };
+    let signature = match &ty.type_def {
+        TypeDef::Composite(def) => render_fields(&def.fields),
+        TypeDef::Variant(def) => {
+            let variants = def
+                .variants
+                .iter()
+                .map(|variant| {
+                    format!(
+                        "{}={}{}",
+                        variant.name,
+                        variant.index,
+                        render_fields(&variant.fields)
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!("enum {{{}}}", variants.join(", "))
+        }
+        TypeDef::Sequence(def) => {
+            format!(
+                "[{}]",
+                type_signature(registry, def.type_param.id, visiting)
+            )
+        }
+        TypeDef::Array(def) => {
+            let elem = type_signature(registry, def.type_param.id, visiting);
+            format!("[{elem}; {}]", def.len)
+        }
+        TypeDef::Tuple(def) => {
+            let elems = def
+                .fields
+                .iter()
+                .map(|field| type_signature(registry, field.id, visiting))
+                .collect::<Vec<_>>();
+            format!("({})", elems.join(", "))
+        }
+        TypeDef::Primitive(def) => format!("{def:?}").to_lowercase(),
+        TypeDef::Compact(def) => {
+            format!(
+                "Compact<{}>",
+                type_signature(registry, def.type_param.id, visiting)
+            )
+        }
+        TypeDef::BitSequence(def) => {
+            format!(
+                "BitVec<{}, {}>",
+                type_signature(registry, def.bit_store_type.id, visiting),
+                type_signature(registry, def.bit_order_type.id, visiting)
+            )
+        }
+    };
+    visiting.pop();
+    signature
+}
+
+fn type_name(registry: &PortableRegistry, id: u32) -> String {
+    let Some(ty) = registry.resolve(id) else {
+        return format!("<unknown type {id}>");
+    };
+    if let Some(name) = ty.path.segments.last() {
+        let params = ty
+            .type_params
+            .iter()
+            .filter_map(|param| param.ty.as_ref())
+            .map(|param| type_name(registry, param.id))
+            .collect::<Vec<_>>();
+        return if params.is_empty() {
+            name.clone()
+        } else {
+            format!("{name}<{}>", params.join(", "))
+        };
+    }
+    match &ty.type_def {
+        TypeDef::Sequence(def) => {
+            format!("Vec<{}>", type_name(registry, def.type_param.id))
+        }
+        TypeDef::Array(def) => {
+            format!("[{}; {}]", type_name(registry, def.type_param.id), def.len)
+        }
+        TypeDef::Tuple(def) => {
+            let elems = def
+                .fields
+                .iter()
+                .map(|field| type_name(registry, field.id))
+                .collect::<Vec<_>>();
+            format!("({})", elems.join(", "))
+        }
+        TypeDef::Compact(def) => {
+            format!("Compact<{}>", type_name(registry, def.type_param.id))
+        }
+        _ => type_signature(registry, id, &mut Vec::new()),
+    }
+}
+
+fn check_storage(old: &Contract, new: &Contract, changes: &mut Changes) {
+    let new_entries: BTreeMap<_, _> = new
+        .storage
+        .iter()
+        .map(|entry| (entry.path.as_str(), entry))
+        .collect();
+    let old_paths: BTreeSet<_> = old
+        .storage
+        .iter()
+        .map(|entry| entry.path.as_str())
+        .collect();
+    let old_root_keys: BTreeSet<_> =
+        old.storage.iter().map(|entry| entry.root_key).collect();
+
+    for entry in &old.storage {
+        let path = &entry.path;
+        let Some(new_entry) = new_entries.get(path.as_str()) else {
+            changes.breaking(Category::Storage, format!("field `{path}` was removed"));
+            continue;
+        };
+        if entry.root_key != new_entry.root_key {
+            changes.breaking(
+                Category::Storage,
+                format!(
+                    "root key of field `{path}` changed from {} to {}",
+                    hex_key(entry.root_key),
+                    hex_key(new_entry.root_key)
+                ),
+            );
+        }
+        if old.signature(entry.ty) != new.signature(new_entry.ty) {
+            changes.breaking(
+                Category::Storage,
+                format!(
+                    "type of field `{path}` changed from `{}` to `{}`",
+                    old.name(entry.ty),
+                    new.name(new_entry.ty)
+                ),
+            );
+        }
+    }
+
+    for entry in &new.storage {
+        let path = &entry.path;
+        if old_paths.contains(path.as_str()) {
+            continue;
+        }
+        if old_root_keys.contains(&entry.root_key) {
+            changes.breaking(
+                Category::Storage,
+                format!(
+                    "field `{path}` was added to the cell at root key {}, which \
+                    already holds data",
+                    hex_key(entry.root_key)
+                ),
+            );
+        } else {
+            changes.compatible(
+                Category::Storage,
+                format!(
+                    "field `{path}` was added at the new root key {}",
+                    hex_key(entry.root_key)
+                ),
+            );
+        }
+    }
+
+    // Fields sharing a cell are encoded in order, so they must not be reordered.
+    for root_key in &old_root_keys {
+        let in_cell = |contract: &Contract, other: &Contract| {
+            let other_paths: BTreeSet<_> = other
+                .storage
+                .iter()
+                .filter(|entry| entry.root_key == *root_key)
+                .map(|entry| entry.path.clone())
+                .collect();
+            contract
+                .storage
+                .iter()
+                .filter(|entry| {
+                    entry.root_key == *root_key && other_paths.contains(&entry.path)
+                })
+                .map(|entry| entry.path.clone())
+                .collect::<Vec<_>>()
+        };
+        if in_cell(old, new) != in_cell(new, old) {
+            changes.breaking(
+                Category::Storage,
+                format!("fields at root key {} were reordered", hex_key(*root_key)),
+            );
+        }
+    }
+}
+
+fn check_messages(old: &Contract, new: &Contract, changes: &mut Changes) {
+    let old_messages = old.project.spec().messages();
+    let new_messages = new.project.spec().messages();
+    let mut matched = BTreeSet::new();
+
+    for message in old_messages {
+        let label = message.label();
+        let Some(new_message) = find_callable(
+            new_messages,
+            message,
+            MessageSpec::label,
+            MessageSpec::selector,
+        ) else {
+            changes.breaking(
+                Category::Message,
+                format!(
+                    "message `{label}` ({}) was removed",
+                    hex_selector(message.selector())
+                ),
+            );
+            continue;
+        };
+        matched.insert(new_message.label());
+        let new_label = new_message.label();
+        if label != new_label {
+            changes.compatible(
+                Category::Message,
+                format!("message `{label}` was renamed to `{new_label}`"),
+            );
+        }
+        if message.selector() != new_message.selector() {
+            changes.breaking(
+                Category::Message,
+                format!(
+                    "selector of message `{label}` changed from {} to {}",
+                    hex_selector(message.selector()),
+                    hex_selector(new_message.selector())
+                ),
+            );
+        }
+        check_args(
+            old,
+            new,
+            Category::Message,
+            label,
+            message.args(),
+            new_message.args(),
+            changes,
+        );
+        check_return_type(
+            old,
+            new,
+            label,
+            message.return_type().ret_type().ty().id,
+            new_message.return_type().ret_type().ty().id,
+            changes,
+        );
+        match (message.payable(), new_message.payable()) {
+            (true, false) => {
+                changes.breaking(
+                    Category::Message,
+                    format!("message `{label}` is no longer payable"),
+                )
+            }
+            (false, true) => {
+                changes.compatible(
+                    Category::Message,
+                    format!("message `{label}` is now payable"),
+                )
+            }
+            _ => {}
+        }
+        if message.mutates() != new_message.mutates() {
+            let mutability = if new_message.mutates() {
+                "mutates"
+            } else {
+                "no longer mutates"
+            };
+            changes.compatible(
+                Category::Message,
+                format!("message `{label}` now {mutability} the storage"),
+            );
+        }
+    }
+
+    for message in new_messages {
+        if !matched.contains(message.label()) {
+            changes.compatible(
+                Category::Message,
+                format!(
+                    "message `{}` ({}) was added",
+                    message.label(),
+                    hex_selector(message.selector())
+                ),
+            );
+        }
+    }
+}
+
+/// Constructors are not executed by an upgrade, so changing them only affects
+/// new instantiations.
+fn check_constructors(old: &Contract, new: &Contract, changes: &mut Changes) {
+    let old_constructors = old.project.spec().constructors();
+    let new_constructors = new.project.spec().constructors();
+    for constructor in old_constructors {
+        let label = constructor.label();
+        let Some(new_constructor) = find_callable(
+            new_constructors,
+            constructor,
+            ConstructorSpec::label,
+            ConstructorSpec::selector,
+        ) else {
+            changes.compatible(
+                Category::Constructor,
+                format!("constructor `{label}` was removed"),
+            );
+            continue;
+        };
+        let args = |contract: &Contract, args: &[MessageParamSpec<PortableForm>]| {
+            args.iter()
+                .map(|arg| contract.signature(arg.ty().ty().id))
+                .collect::<Vec<_>>()
+        };
+        if constructor.selector() != new_constructor.selector()
+            || args(old, constructor.args()) != args(new, new_constructor.args())
+        {
+            changes.compatible(
+                Category::Constructor,
+                format!("signature of constructor `{label}` changed"),
+            );
+        }
+    }
+}
+
+fn check_events(old: &Contract, new: &Contract, changes: &mut Changes) {
+    let key = |event: &EventSpec<PortableForm>| {
+        format!("{}::{}", event.module_path(), event.label())
+    };
+    let new_events: BTreeMap<_, _> = new
+        .project
+        .spec()
+        .events()
+        .iter()
+        .map(|event| (key(event), event))
+        .collect();
+
+    for event in old.project.spec().events() {
+        let path = key(event);
+        let Some(new_event) = new_events.get(&path) else {
+            changes.breaking(Category::Event, format!("event `{path}` was removed"));
+            continue;
+        };
+        if event.signature_topic() != new_event.signature_topic() {
+            changes.breaking(
+                Category::Event,
+                format!("signature topic of event `{path}` changed"),
+            );
+        }
+        let fields = |contract: &Contract, event: &EventSpec<PortableForm>| {
+            event
+                .args()
+                .iter()
+                .map(|arg| {
+                    (
+                        arg.label().clone(),
+                        arg.indexed(),
+                        contract.signature(arg.ty().ty().id),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        if fields(old, event) != fields(new, new_event) {
+            changes
+                .breaking(Category::Event, format!("fields of event `{path}` changed"));
+        }
+    }
+
+    let old_events: BTreeSet<_> = old.project.spec().events().iter().map(key).collect();
+    for path in new_events.keys() {
+        if !old_events.contains(path) {
+            changes.compatible(Category::Event, format!("event `{path}` was added"));
+        }
+    }
+}
+
+fn check_lang_error(old: &Contract, new: &Contract, changes: &mut Changes) {
+    let old_error = old.project.spec().lang_error().ty().id;
+    let new_error = new.project.spec().lang_error().ty().id;
+    if old.signature(old_error) != new.signature(new_error) {
+        changes.breaking(
+            Category::Error,
+            format!(
+                "language error type changed from `{}` to `{}`",
+                old.name(old_error),
+                new.name(new_error)
+            ),
+        );
+    }
+}
+
+/// Finds the counterpart of `callable` by its label, or else by its selector.
+fn find_callable<'a, T>(
+    callables: &'a [T],
+    callable: &T,
+    label: impl Fn(&T) -> &String,
+    selector: impl Fn(&T) -> &Selector,
+) -> Option<&'a T> {
+    callables
+        .iter()
+        .find(|other| label(other) == label(callable))
+        .or_else(|| {
+            callables
+                .iter()
+                .find(|other| selector(other) == selector(callable))
+        })
+}
+
+fn check_args(
+    old: &Contract,
+    new: &Contract,
+    category: Category,
+    label: &str,
+    old_args: &[MessageParamSpec<PortableForm>],
+    new_args: &[MessageParamSpec<PortableForm>],
+    changes: &mut Changes,
+) {
+    if old_args.len() != new_args.len() {
+        changes.breaking(
+            category,
+            format!(
+                "{category} `{label}` takes {} instead of {} argument(s)",
+                new_args.len(),
+                old_args.len()
+            ),
+        );
+        return;
+    }
+    for (arg, new_arg) in old_args.iter().zip(new_args) {
+        let (ty, new_ty) = (arg.ty().ty().id, new_arg.ty().ty().id);
+        if old.signature(ty) != new.signature(new_ty) {
+            changes.breaking(
+                category,
+                format!(
+                    "argument `{}` of {category} `{label}` changed from `{}` to `{}`",
+                    arg.label(),
+                    old.name(ty),
+                    new.name(new_ty)
+                ),
+            );
+        }
+    }
+}
+
+/// Compares the return types of a message, reporting changes to the error type
+/// of a `Result` separately.
+fn check_return_type(
+    old: &Contract,
+    new: &Contract,
+    label: &str,
+    ty: u32,
+    new_ty: u32,
+    changes: &mut Changes,
+) {
+    if old.signature(ty) == new.signature(new_ty) {
+        return;
+    }
+    // Messages return a `MessageResult<T>`, whose error is the language error.
+    let unwrap = |contract: &Contract, ty| {
+        contract.result_parts(ty).map(|(ok, _)| ok).unwrap_or(ty)
+    };
+    let (ty, new_ty) = (unwrap(old, ty), unwrap(new, new_ty));
+    if let (Some((ok, err)), Some((new_ok, new_err))) =
+        (old.result_parts(ty), new.result_parts(new_ty))
+    {
+        if old.signature(ok) != new.signature(new_ok) {
+            changes.breaking(
+                Category::Message,
+                format!(
+                    "message `{label}` returns `{}` instead of `{}`",
+                    new.name(new_ok),
+                    old.name(ok)
+                ),
+            );
+        }
+        if old.signature(err) != new.signature(new_err) {
+            changes.breaking(
+                Category::Error,
+                format!(
+                    "error type of message `{label}` changed from `{}` to `{}`",
+                    old.name(err),
+                    new.name(new_err)
+                ),
+            );
+        }
+        return;
+    }
+    if old.signature(ty) != new.signature(new_ty) {
+        changes.breaking(
+            Category::Message,
+            format!(
+                "message `{label}` returns `{}` instead of `{}`",
+                new.name(new_ty),
+                old.name(ty)
+            ),
+        );
+    }
+}
+
+fn hex_key(key: u32) -> String {
+    format!("0x{key:08x}")
+}
+
+fn hex_selector(selector: &Selector) -> String {
+    format!("0x{}", hex::encode(selector.to_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{
+        json,
+        Value,
+    };
+
+    fn result(id: u32, ok: u32, err: u32) -> Value {
+        json!({
+            "id": id,
+            "type": {
+                "path": ["Result"],
+                "params": [{ "name": "T", "type": ok }, { "name": "E", "type": err }],
+                "def": {
+                    "variant": {
+                        "variants": [
+                            { "name": "Ok", "fields": [{ "type": ok }], "index": 0 },
+                            { "name": "Err", "fields": [{ "type": err }], "index": 1 }
+                        ]
+                    }
+                }
+            }
+        })
+    }
+
+    /// Builds the metadata of a contract storing `fields` in its root struct.
+    fn project(fields: Value, messages: Value, events: Value) -> InkProject {
+        let ty = |id: u32| json!({ "type": id, "displayName": [] });
+        serde_json::from_value(json!({
+            "version": 5,
+            "types": [
+                { "id": 0, "type": { "def": { "primitive": "u32" } } },
+                { "id": 1, "type": { "def": { "primitive": "bool" } } },
+                { "id": 2, "type": { "def": { "primitive": "u64" } } },
+                {
+                    "id": 3,
+                    "type": {
+                        "path": ["ink_primitives", "LangError"],
+                        "def": {
+                            "variant": {
+                                "variants": [{ "name": "CouldNotReadInput", "index": 1 }]
+                            }
+                        }
+                    }
+                },
+                result(4, 0, 3),
+                { "id": 5, "type": { "def": { "tuple": [] } } },
+                result(6, 0, 0),
+                result(7, 0, 1),
+                result(8, 6, 3),
+                result(9, 7, 3)
+            ],
+            "storage": {
+                "root": {
+                    "root_key": "0x00000000",
+                    "layout": { "struct": { "name": "Contract", "fields": fields } },
+                    "ty": 5
+                }
+            },
+            "spec": {
+                "constructors": [],
+                "messages": messages,
+                "events": events,
+                "docs": [],
+                "lang_error": ty(3),
+                "environment": {
+                    "accountId": ty(0),
+                    "balance": ty(0),
+                    "hash": ty(0),
+                    "timestamp": ty(0),
+                    "blockNumber": ty(0),
+                    "chainExtension": ty(5),
+                    "maxEventTopics": 4,
+                    "staticBufferSize": 16384
+                }
+            }
+        }))
+        .expect("metadata must be valid")
+    }
+
+    fn leaf(name: &str, ty: u32) -> Value {
+        json!({ "name": name, "layout": { "leaf": { "key": "0x00000000", "ty": ty } } })
+    }
+
+    fn lazy(name: &str, root_key: &str, ty: u32) -> Value {
+        json!({
+            "name": name,
+            "layout": {
+                "root": {
+                    "root_key": root_key,
+                    "layout": { "leaf": { "key": root_key, "ty": ty } },
+                    "ty": ty
+                }
+            }
+        })
+    }
+
+    fn message(label: &str, selector: &str, return_type: u32) -> Value {
+        json!({
+            "label": label,
+            "selector": selector,
+            "mutates": false,
+            "payable": false,
+            "args": [],
+            "returnType": { "type": return_type, "displayName": [] },
+            "docs": [],
+            "default": false
+        })
+    }
+
+    fn event(label: &str, signature_topic: &str) -> Value {
+        json!({
+            "label": label,
+            "module_path": "contract",
+            "signature_topic": signature_topic,
+            "args": [],
+            "docs": []
+        })
+    }
+
+    fn breaking(changes: &[Change]) -> Vec<&str> {
+        changes
+            .iter()
+            .filter(|change| change.severity == Severity::Breaking)
+            .map(|change| change.description.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn identical_contracts_are_compatible() {
+        let contract = || {
+            project(
+                json!([leaf("value", 1), lazy("total", "0x01000000", 0)]),
+                json!([message("get", "0x2f865bd9", 4)]),
+                json!([event("Flipped", "0x11")]),
+            )
+        };
+
+        assert_eq!(check_upgrade(&contract(), &contract()), vec![]);
+    }
+
+    #[test]
+    fn storage_changes_are_detected() {
+        let old = project(
+            json!([
+                leaf("value", 0),
+                leaf("owner", 0),
+                lazy("total", "0x01000000", 0)
+            ]),
+            json!([]),
+            json!([]),
+        );
+        let new = project(
+            json!([
+                leaf("value", 2),
+                leaf("flag", 1),
+                lazy("total", "0x02000000", 0),
+                lazy("extra", "0x03000000", 1)
+            ]),
+            json!([]),
+            json!([]),
+        );
+
+        let changes = check_upgrade(&old, &new);
+
+        assert_eq!(
+            breaking(&changes),
+            vec![
+                "type of field `value` changed from `u32` to `u64`",
+                "field `owner` was removed",
+                "root key of field `total` changed from 0x00000001 to 0x00000002",
+                "field `flag` was added to the cell at root key 0x00000000, which \
+                already holds data",
+            ]
+        );
+        assert!(changes.contains(&Change {
+            severity: Severity::Compatible,
+            category: Category::Storage,
+            description: "field `extra` was added at the new root key 0x00000003"
+                .to_string(),
+        }));
+    }
+
+    #[test]
+    fn reordered_fields_are_breaking() {
+        let old = project(json!([leaf("a", 0), leaf("b", 0)]), json!([]), json!([]));
+        let new = project(json!([leaf("b", 0), leaf("a", 0)]), json!([]), json!([]));
+
+        assert_eq!(
+            breaking(&check_upgrade(&old, &new)),
+            vec!["fields at root key 0x00000000 were reordered"]
+        );
+    }
+
+    #[test]
+    fn message_changes_are_detected() {
+        let old = project(
+            json!([]),
+            json!([
+                message("get", "0x2f865bd9", 4),
+                message("try_get", "0x00000001", 8),
+                message("flip", "0x633aa551", 4),
+                message("burn", "0x00000002", 4)
+            ]),
+            json!([]),
+        );
+        let new = project(
+            json!([]),
+            json!([
+                message("get", "0x2f865bd0", 4),
+                message("try_get", "0x00000001", 9),
+                message("toggle", "0x633aa551", 4)
+            ]),
+            json!([]),
+        );
+
+        let changes = check_upgrade(&old, &new);
+
+        assert_eq!(
+            breaking(&changes),
+            vec![
+                "selector of message `get` changed from 0x2f865bd9 to 0x2f865bd0",
+                "error type of message `try_get` changed from `u32` to `bool`",
+                "message `burn` (0x00000002) was removed",
+            ]
+        );
+        assert!(changes.contains(&Change {
+            severity: Severity::Compatible,
+            category: Category::Message,
+            description: "message `flip` was renamed to `toggle`".to_string(),
+        }));
+    }
+
+    #[test]
+    fn event_changes_are_detected() {
+        let old = project(
+            json!([]),
+            json!([]),
+            json!([event("Flipped", "0x11"), event("Burned", "0x22")]),
+        );
+        let new = project(
+            json!([]),
+            json!([]),
+            json!([event("Flipped", "0x12"), event("Minted", "0x33")]),
+        );
+
+        let changes = check_upgrade(&old, &new);
+
+        assert_eq!(
+            breaking(&changes),
+            vec![
+                "signature topic of event `contract::Flipped` changed",
+                "event `contract::Burned` was removed",
+            ]
+        );
+        assert!(changes.contains(&Change {
+            severity: Severity::Compatible,
+            category: Category::Event,
+            description: "event `contract::Minted` was added".to_string(),
+        }));
+    }
+}
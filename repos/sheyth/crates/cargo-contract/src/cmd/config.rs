@@ -231,6 +231,50 @@ where
     }
 }
 
+/// The signer of an extrinsic: either the key given by `--suri`, or only the account
+/// given by `--signer` for an extrinsic which is signed offline.
+pub enum CLISigner<C: Config + Environment + SignerConfig<C>> {
+    Key(C::Signer),
+    Account(C::AccountId),
+}
+
+impl<C: Config + Environment + SignerConfig<C>> Clone for CLISigner<C> {
+    fn clone(&self) -> Self {
+        match self {
+            CLISigner::Key(signer) => CLISigner::Key(signer.clone()),
+            CLISigner::Account(account_id) => CLISigner::Account(account_id.clone()),
+        }
+    }
+}
+
+impl<C: Config + Environment + SignerConfig<C>> SignerT<C> for CLISigner<C>
+where
+    C::Address: From<C::AccountId>,
+{
+    fn account_id(&self) -> <C as Config>::AccountId {
+        match self {
+            CLISigner::Key(signer) => signer.account_id(),
+            CLISigner::Account(account_id) => account_id.clone(),
+        }
+    }
+
+    fn address(&self) -> C::Address {
+        match self {
+            CLISigner::Key(signer) => signer.address(),
+            CLISigner::Account(account_id) => account_id.clone().into(),
+        }
+    }
+
+    fn sign(&self, signer_payload: &[u8]) -> C::Signature {
+        match self {
+            CLISigner::Key(signer) => signer.sign(signer_payload),
+            CLISigner::Account(_) => {
+                panic!("extrinsics for `--signer` are signed with `cargo contract sign`")
+            }
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! call_with_config_internal {
     ($obj:tt ,$function:tt, $config_name:expr, $( ($config_str:literal, $config_obj:ty) ),*) => {
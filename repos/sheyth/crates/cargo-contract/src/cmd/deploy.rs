@@ -0,0 +1,251 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use super::{
+    config::SignerConfig,
+    parse_balance,
+    CLIChainOpts,
+};
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+use anyhow::{
+    anyhow,
+    Context,
+    Result,
+};
+use colored::Colorize;
+use contract_build::{
+    name_value_println,
+    ExecuteArgs,
+    ManifestPath,
+    Verbosity,
+    VerbosityFlags,
+};
+use contract_extrinsics::{
+    execute_plan,
+    ChainBackend,
+    DeployAction,
+    DeployLock,
+    DeployPlan,
+    DeployReport,
+    PlanContract,
+    StepStatus,
+    TokenMetadata,
+};
+use ink_env::Environment;
+use serde::Serialize;
+use std::{
+    fmt::{
+        Debug,
+        Display,
+    },
+    path::PathBuf,
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        codec::Decode,
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+    },
+    Config,
+};
+
+#[derive(Debug, clap::Args)]
+#[clap(
+    name = "deploy",
+    about = "Deploy a system of contracts described by a deployment plan"
+)]
+pub struct DeployCommand {
+    /// Path to the deployment plan.
+    #[clap(long, value_parser, default_value = "deploy.toml")]
+    plan: PathBuf,
+    /// Path to the lock file recording the deployed contracts. Defaults to the plan
+    /// path with a `.lock` extension.
+    #[clap(long, value_parser)]
+    lock: Option<PathBuf>,
+    /// Secret key URI for the account deploying the contracts.
+    ///
+    /// e.g.
+    /// - for a dev account "//Alice"
+    /// - with a password "//Alice///SECRET_PASSWORD"
+    #[clap(name = "suri", long, short)]
+    suri: String,
+    /// The maximum amount of balance that can be charged from the caller to pay for the
+    /// storage consumed by each extrinsic.
+    #[clap(long)]
+    storage_deposit_limit: Option<String>,
+    /// Dry-run all uploads, instantiations and calls via RPC without submitting them
+    /// or writing the lock file.
+    #[clap(long)]
+    dry_run: bool,
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+    /// Arguments required for communicating with a Substrate node.
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+    /// Export the deployment report in JSON format.
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+}
+
+impl DeployCommand {
+    /// Returns whether to export the call output in JSON format.
+    pub fn output_json(&self) -> bool {
+        self.output_json
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.lock
+            .clone()
+            .unwrap_or_else(|| self.plan.with_extension("lock"))
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C as Config>::AccountId:
+            IntoVisitor + EncodeAsType + Decode + Display + FromStr + AsRef<[u8]>,
+        <<C as Config>::AccountId as FromStr>::Err: Display,
+        C::Balance:
+            From<u128> + Display + Default + FromStr + Serialize + Debug + EncodeAsType,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+        <C as Config>::Hash: IntoVisitor + EncodeAsType + From<[u8; 32]>,
+    {
+        let verbosity = TryFrom::<&VerbosityFlags>::try_from(&self.verbosity)?;
+        let mut plan = DeployPlan::load(&self.plan)?;
+        for contract in plan
+            .contracts
+            .iter_mut()
+            .filter(|contract| !contract.is_bundle())
+        {
+            contract.path = build_contract(contract, verbosity)?;
+        }
+
+        let signer = C::Signer::from_str(&self.suri)
+            .map_err(|_| anyhow!("Failed to parse suri option"))?;
+        let chain = self.chain_cli_opts.chain();
+        let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
+        let storage_deposit_limit = self
+            .storage_deposit_limit
+            .as_ref()
+            .map(|b| parse_balance(b, &token_metadata))
+            .transpose()
+            .map_err(|e| {
+                anyhow!("Failed to parse storage_deposit_limit option: {}", e)
+            })?;
+        let mut backend = ChainBackend::<C, C, C::Signer>::new(
+            chain.url(),
+            signer,
+            storage_deposit_limit,
+            verbosity,
+        )
+        .await?;
+
+        let lock_path = self.lock_path();
+        let mut lock = DeployLock::load(&lock_path)?;
+        let report = execute_plan(&plan, &mut lock, &mut backend, self.dry_run).await;
+        // The lock is written even if the deployment failed, so that it resumes from
+        // the failed step when running the plan again.
+        if !self.dry_run {
+            lock.save(&lock_path)?;
+        }
+        let report = report?;
+
+        if self.output_json() {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            display_report(&report);
+        }
+        Ok(())
+    }
+}
+
+/// Builds the contract of the manifest at the plan contract's path, returning the path
+/// to its `.contract` bundle.
+fn build_contract(contract: &PlanContract, verbosity: Verbosity) -> Result<PathBuf> {
+    let manifest_path = if contract.path.is_dir() {
+        contract.path.join("Cargo.toml")
+    } else {
+        contract.path.clone()
+    };
+    let args = ExecuteArgs {
+        manifest_path: ManifestPath::new(manifest_path)?,
+        verbosity,
+        build_mode: contract.profile.into(),
+        ..Default::default()
+    };
+    let result = contract_build::execute(args)
+        .with_context(|| format!("Failed to build contract `{}`", contract.name))?;
+    result
+        .metadata_result
+        .map(|metadata| metadata.dest_bundle)
+        .ok_or_else(|| {
+            anyhow!("Building contract `{}` produced no bundle", contract.name)
+        })
+}
+
+fn display_report(report: &DeployReport) {
+    for step in &report.steps {
+        let action = match step.action {
+            DeployAction::Upload => "Upload",
+            DeployAction::Instantiate => "Instantiate",
+            DeployAction::Call => "Call",
+        };
+        let status = match step.status {
+            StepStatus::Executed => "".normal(),
+            StepStatus::DryRun => " (dry run)".bright_blue(),
+            StepStatus::Skipped => " (skipped)".bright_black(),
+        };
+        name_value_println!(
+            action,
+            format!("{} {}{status}", step.target.bold(), step.output)
+        );
+    }
+}
@@ -0,0 +1,1710 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use anyhow::{
+    bail,
+    Context,
+    Result,
+};
+use colored::Colorize;
+use contract_build::{
+    Verbosity,
+    VerbosityFlags,
+};
+use contract_metadata::ContractMetadata;
+use heck::{
+    ToLowerCamelCase,
+    ToSnakeCase,
+    ToUpperCamelCase,
+};
+use ink_metadata::{
+    EventSpec,
+    InkProject,
+    MessageParamSpec,
+};
+use scale_info::{
+    form::PortableForm,
+    Field,
+    PortableRegistry,
+    Type,
+    TypeDef,
+    TypeDefPrimitive,
+};
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+        HashMap,
+    },
+    fmt::Write,
+    path::{
+        Path,
+        PathBuf,
+    },
+};
+
+/// The language to generate contract bindings for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Language {
+    /// A Rust crate building `contract-extrinsics` calls.
+    Rust,
+    /// A TypeScript module wrapping the `setheum-js` contract API.
+    Typescript,
+}
+
+/// Generates typed client bindings from a contract's metadata.
+#[derive(Debug, clap::Args)]
+#[clap(name = "generate-bindings")]
+pub struct GenerateBindingsCommand {
+    /// Path to the contract bundle (`*.contract`) or metadata (`*.json`).
+    #[clap(value_parser)]
+    file: PathBuf,
+    /// The language to generate bindings for.
+    #[clap(long, value_enum)]
+    lang: Language,
+    /// The directory to write the bindings to.
+    ///
+    /// Defaults to `<contract>-bindings` in the current directory.
+    #[clap(long, short, value_parser)]
+    output: Option<PathBuf>,
+    /// Denotes if output should be printed to stdout.
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+}
+
+impl GenerateBindingsCommand {
+    pub fn run(&self) -> Result<GenerateBindingsResult> {
+        let verbosity: Verbosity = TryFrom::<&VerbosityFlags>::try_from(&self.verbosity)?;
+        let mut metadata = ContractMetadata::load(&self.file)?;
+        let project: InkProject =
+            serde_json::from_value(serde_json::Value::Object(metadata.abi.clone()))
+                .context(format!(
+                    "Failed to deserialize ink! project metadata from file {}",
+                    self.file.display()
+                ))?;
+        let name = metadata.contract.name.clone();
+        let version = metadata.contract.version.to_string();
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("{name}-bindings")));
+
+        let files = match self.lang {
+            Language::Rust => {
+                vec![
+                    ("Cargo.toml".into(), rust_manifest(&name, &version)),
+                    (
+                        "src/lib.rs".into(),
+                        rust_bindings(&name, &version, &project)?,
+                    ),
+                ]
+            }
+            Language::Typescript => {
+                // The bindings only need the ABI, not the bundled Wasm code.
+                metadata.source.wasm = None;
+                vec![
+                    (
+                        format!("{}.ts", name.to_snake_case()),
+                        typescript_bindings(&name, &version, &project)?,
+                    ),
+                    (
+                        "metadata.json".into(),
+                        serde_json::to_string_pretty(&metadata)?,
+                    ),
+                ]
+            }
+        };
+
+        let mut written = Vec::new();
+        for (file, contents) in files {
+            let path = output.join(file);
+            write_file(&path, &contents)?;
+            written.push(path);
+        }
+
+        Ok(GenerateBindingsResult {
+            lang: self.lang,
+            files: written,
+            verbosity,
+        })
+    }
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .context(format!("Failed to create directory {}", dir.display()))?;
+    }
+    std::fs::write(path, contents)
+        .context(format!("Failed to write bindings to {}", path.display()))
+}
+
+/// The files written by `cargo contract generate-bindings`.
+pub struct GenerateBindingsResult {
+    pub lang: Language,
+    pub files: Vec<PathBuf>,
+    pub verbosity: Verbosity,
+}
+
+impl GenerateBindingsResult {
+    /// Display the result in a fancy format
+    pub fn display(&self) -> String {
+        let lang = match self.lang {
+            Language::Rust => "Rust",
+            Language::Typescript => "TypeScript",
+        };
+        let mut out = format!("{} {lang} bindings:\n", "Generated".bright_green().bold());
+        for file in &self.files {
+            out.push_str(&format!("  - {}\n", file.display()));
+        }
+        out
+    }
+}
+
+/// A type of the contract's registry as referenced by the bindings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TypeExpr {
+    Primitive(TypeDefPrimitive),
+    /// A generic parameter of the custom type being defined.
+    Param(String),
+    /// A custom type along with its generic arguments.
+    Custom(String, Vec<TypeExpr>),
+    Option(Box<TypeExpr>),
+    Result(Box<TypeExpr>, Box<TypeExpr>),
+    Map(Box<TypeExpr>, Box<TypeExpr>),
+    Sequence(Box<TypeExpr>),
+    Array(Box<TypeExpr>, u32),
+    Tuple(Vec<TypeExpr>),
+    Compact(Box<TypeExpr>),
+}
+
+/// A custom type defined by the bindings, i.e. a registry type with a path.
+///
+/// Every instantiation of a generic type shares a single definition, which uses
+/// the instantiation in `ty` as its template.
+struct CustomType<'a> {
+    name: String,
+    ty: &'a Type<PortableForm>,
+    /// The generic parameters along with their type ids in the template.
+    params: Vec<(String, u32)>,
+}
+
+/// Names the types of a contract's registry for the code generators.
+struct Types<'a> {
+    registry: &'a PortableRegistry,
+    /// The position of each custom type in `custom`, by path.
+    index: HashMap<&'a [String], usize>,
+    custom: Vec<CustomType<'a>>,
+}
+
+impl<'a> Types<'a> {
+    /// Collects every type reachable from the constructors, messages, events and
+    /// the language error of `project`.
+    fn new(project: &'a InkProject) -> Result<Self> {
+        let registry = project.registry();
+        let spec = project.spec();
+        let mut roots = vec![spec.lang_error().ty().id];
+        for constructor in spec.constructors() {
+            roots.extend(constructor.args().iter().map(|arg| arg.ty().ty().id));
+            roots.push(constructor.return_type().ret_type().ty().id);
+        }
+        for message in spec.messages() {
+            roots.extend(message.args().iter().map(|arg| arg.ty().ty().id));
+            roots.push(message.return_type().ret_type().ty().id);
+        }
+        for event in spec.events() {
+            roots.extend(event.args().iter().map(|arg| arg.ty().ty().id));
+        }
+
+        let mut visited = BTreeSet::new();
+        let mut instances: BTreeMap<&'a [String], Vec<&'a Type<PortableForm>>> =
+            BTreeMap::new();
+        let mut first_seen = Vec::new();
+        while let Some(id) = roots.pop() {
+            if !visited.insert(id) {
+                continue
+            }
+            let ty = resolve(registry, id)?;
+            if !ty.path.segments.is_empty() && builtin(ty).is_none() {
+                let path = ty.path.segments.as_slice();
+                if !instances.contains_key(path) {
+                    first_seen.push(path);
+                }
+                instances.entry(path).or_default().push(ty);
+            }
+            roots.extend(
+                ty.type_params
+                    .iter()
+                    .filter_map(|param| param.ty.map(|ty| ty.id)),
+            );
+            match &ty.type_def {
+                TypeDef::Composite(def) => {
+                    roots.extend(def.fields.iter().map(|f| f.ty.id))
+                }
+                TypeDef::Variant(def) => {
+                    roots.extend(
+                        def.variants
+                            .iter()
+                            .flat_map(|variant| variant.fields.iter().map(|f| f.ty.id)),
+                    )
+                }
+                TypeDef::Sequence(def) => roots.push(def.type_param.id),
+                TypeDef::Array(def) => roots.push(def.type_param.id),
+                TypeDef::Tuple(def) => roots.extend(def.fields.iter().map(|f| f.id)),
+                TypeDef::Compact(def) => roots.push(def.type_param.id),
+                TypeDef::Primitive(_) => {}
+                TypeDef::BitSequence(_) => {
+                    bail!("Bit sequences are not supported by the contract bindings")
+                }
+            }
+        }
+
+        let names = unique_names(&first_seen);
+        let index = first_seen
+            .iter()
+            .enumerate()
+            .map(|(i, path)| (*path, i))
+            .collect();
+        let custom = first_seen
+            .iter()
+            .map(|path| {
+                let ty = template(&instances[path]);
+                // Parameters not mentioned by any field, e.g. of `PhantomData`
+                // fields skipped by `scale-info`, are dropped.
+                let fields: Vec<_> = match &ty.type_def {
+                    TypeDef::Composite(def) => def.fields.iter().collect(),
+                    TypeDef::Variant(def) => {
+                        def.variants.iter().flat_map(|v| v.fields.iter()).collect()
+                    }
+                    _ => Vec::new(),
+                };
+                let params = ty
+                    .type_params
+                    .iter()
+                    .filter(|param| {
+                        fields.iter().any(|field| mentions(field, &param.name))
+                    })
+                    .filter_map(|param| param.ty.map(|ty| (param.name.clone(), ty.id)))
+                    .collect();
+                CustomType {
+                    name: names[path].clone(),
+                    ty,
+                    params,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            registry,
+            index,
+            custom,
+        })
+    }
+
+    /// Returns the expression for the type `id`.
+    fn expr(&self, id: u32) -> Result<TypeExpr> {
+        self.expr_with(id, &HashMap::new())
+    }
+
+    /// Returns the expression for the type `id`, referring to the types in `params`
+    /// by the names of their generic parameters.
+    fn expr_with(&self, id: u32, params: &HashMap<u32, String>) -> Result<TypeExpr> {
+        if let Some(param) = params.get(&id) {
+            return Ok(TypeExpr::Param(param.clone()))
+        }
+        let ty = resolve(self.registry, id)?;
+        let args = |count: usize| -> Result<Vec<TypeExpr>> {
+            let args = ty
+                .type_params
+                .iter()
+                .filter_map(|param| param.ty)
+                .map(|param| self.expr_with(param.id, params))
+                .collect::<Result<Vec<_>>>()?;
+            if args.len() != count {
+                bail!(
+                    "Expected {count} type parameters for `{}`",
+                    ty.path.segments.join("::")
+                );
+            }
+            Ok(args)
+        };
+        if let Some(builtin) = builtin(ty) {
+            return Ok(match builtin {
+                Builtin::Option => {
+                    let [some] = <[_; 1]>::try_from(args(1)?).expect("checked above");
+                    TypeExpr::Option(Box::new(some))
+                }
+                Builtin::Result => {
+                    let [ok, err] = <[_; 2]>::try_from(args(2)?).expect("checked above");
+                    TypeExpr::Result(Box::new(ok), Box::new(err))
+                }
+                Builtin::Map => {
+                    let [key, value] =
+                        <[_; 2]>::try_from(args(2)?).expect("checked above");
+                    TypeExpr::Map(Box::new(key), Box::new(value))
+                }
+            })
+        }
+        if !ty.path.segments.is_empty() {
+            let custom = &self.custom[self.index[ty.path.segments.as_slice()]];
+            let args = ty
+                .type_params
+                .iter()
+                .filter(|param| custom.params.iter().any(|(name, _)| name == &param.name))
+                .filter_map(|param| param.ty)
+                .map(|param| self.expr_with(param.id, params))
+                .collect::<Result<Vec<_>>>()?;
+            return Ok(TypeExpr::Custom(custom.name.clone(), args))
+        }
+        Ok(match &ty.type_def {
+            TypeDef::Primitive(primitive) => TypeExpr::Primitive(primitive.clone()),
+            TypeDef::Sequence(def) => {
+                TypeExpr::Sequence(Box::new(self.expr_with(def.type_param.id, params)?))
+            }
+            TypeDef::Array(def) => {
+                TypeExpr::Array(
+                    Box::new(self.expr_with(def.type_param.id, params)?),
+                    def.len,
+                )
+            }
+            TypeDef::Tuple(def) => {
+                TypeExpr::Tuple(
+                    def.fields
+                        .iter()
+                        .map(|field| self.expr_with(field.id, params))
+                        .collect::<Result<_>>()?,
+                )
+            }
+            TypeDef::Compact(def) => {
+                TypeExpr::Compact(Box::new(self.expr_with(def.type_param.id, params)?))
+            }
+            TypeDef::Composite(_) | TypeDef::Variant(_) | TypeDef::BitSequence(_) => {
+                bail!("Unsupported anonymous type {id}")
+            }
+        })
+    }
+
+    /// Returns the expression for a field of a custom type.
+    ///
+    /// The registry only knows concrete types, so a field refers to a generic
+    /// parameter if its declared type name mentions it.
+    fn field_expr(
+        &self,
+        custom: &CustomType,
+        field: &Field<PortableForm>,
+    ) -> Result<TypeExpr> {
+        let params = custom
+            .params
+            .iter()
+            .filter(|(name, _)| mentions(field, name))
+            .map(|(name, id)| (*id, name.clone()))
+            .collect();
+        self.expr_with(field.ty.id, &params)
+    }
+}
+
+/// Whether the declared type name of `field` mentions the generic parameter `param`.
+fn mentions(field: &Field<PortableForm>, param: &str) -> bool {
+    field
+        .type_name
+        .as_deref()
+        .unwrap_or_default()
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|ident| ident == param)
+}
+
+fn resolve(registry: &PortableRegistry, id: u32) -> Result<&Type<PortableForm>> {
+    registry
+        .resolve(id)
+        .with_context(|| format!("Type {id} not found in the contract's type registry"))
+}
+
+/// Types with a path that the bindings map to a type of the target language.
+enum Builtin {
+    Option,
+    Result,
+    Map,
+}
+
+fn builtin(ty: &Type<PortableForm>) -> Option<Builtin> {
+    match ty
+        .path
+        .segments
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>()[..]
+    {
+        ["Option"] => Some(Builtin::Option),
+        ["Result"] => Some(Builtin::Result),
+        ["BTreeMap"] => Some(Builtin::Map),
+        _ => None,
+    }
+}
+
+/// Picks the instantiation of a generic type to derive its definition from.
+///
+/// Instantiations with distinct types for all parameters are preferred, since their
+/// fields can be attributed to a parameter unambiguously.
+fn template<'a>(instances: &[&'a Type<PortableForm>]) -> &'a Type<PortableForm> {
+    instances
+        .iter()
+        .copied()
+        .find(|ty| {
+            let ids: Vec<_> = ty
+                .type_params
+                .iter()
+                .filter_map(|p| p.ty.map(|ty| ty.id))
+                .collect();
+            ids.iter().collect::<BTreeSet<_>>().len() == ids.len()
+        })
+        .unwrap_or(instances[0])
+}
+
+/// Names custom types after the last segment of their path, qualifying the name
+/// with the module when it is ambiguous.
+fn unique_names<'a>(paths: &[&'a [String]]) -> HashMap<&'a [String], String> {
+    let mut by_name: HashMap<&str, usize> = HashMap::new();
+    for path in paths {
+        *by_name.entry(path[path.len() - 1].as_str()).or_default() += 1;
+    }
+    let mut names = HashMap::new();
+    let mut taken = BTreeSet::new();
+    for path in paths {
+        let last = &path[path.len() - 1];
+        let mut name = if by_name[last.as_str()] == 1 {
+            last.to_upper_camel_case()
+        } else {
+            let module = path.len().checked_sub(2).map(|i| path[i].as_str());
+            format!("{}{}", module.unwrap_or_default(), last).to_upper_camel_case()
+        };
+        if taken.contains(&name) {
+            name = path.join("_").to_upper_camel_case();
+        }
+        taken.insert(name.clone());
+        names.insert(*path, name);
+    }
+    names
+}
+
+/// Strips the namespace of trait messages, e.g. `Erc20::transfer`.
+fn label_ident(label: &str) -> String {
+    label.replace("::", "_")
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "static", "struct", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final",
+    "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+fn rust_ident(name: &str) -> String {
+    match name {
+        "self" | "Self" | "super" | "crate" => format!("{name}_"),
+        _ if RUST_KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_string(),
+    }
+}
+
+fn rust_docs(out: &mut String, indent: &str, docs: &[String]) {
+    for line in docs {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(out, "{indent}///").unwrap();
+        } else {
+            writeln!(out, "{indent}///{}", with_leading_space(line)).unwrap();
+        }
+    }
+}
+
+fn with_leading_space(line: &str) -> String {
+    if line.starts_with(' ') {
+        line.to_string()
+    } else {
+        format!(" {line}")
+    }
+}
+
+/// Renders a type as Rust, qualifying custom types with `prefix`.
+fn rust_type(expr: &TypeExpr, prefix: &str) -> String {
+    match expr {
+        TypeExpr::Primitive(primitive) => {
+            match primitive {
+                TypeDefPrimitive::Bool => "bool",
+                TypeDefPrimitive::Char => "char",
+                TypeDefPrimitive::Str => "String",
+                TypeDefPrimitive::U8 => "u8",
+                TypeDefPrimitive::U16 => "u16",
+                TypeDefPrimitive::U32 => "u32",
+                TypeDefPrimitive::U64 => "u64",
+                TypeDefPrimitive::U128 => "u128",
+                TypeDefPrimitive::I8 => "i8",
+                TypeDefPrimitive::I16 => "i16",
+                TypeDefPrimitive::I32 => "i32",
+                TypeDefPrimitive::I64 => "i64",
+                TypeDefPrimitive::I128 => "i128",
+                // Little endian bytes, as encoded by SCALE.
+                TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => "[u8; 32]",
+            }
+            .to_string()
+        }
+        TypeExpr::Param(name) => name.clone(),
+        TypeExpr::Custom(name, args) if args.is_empty() => format!("{prefix}{name}"),
+        TypeExpr::Custom(name, args) => {
+            format!("{prefix}{name}<{}>", rust_types(args, prefix))
+        }
+        TypeExpr::Option(some) => format!("Option<{}>", rust_type(some, prefix)),
+        TypeExpr::Result(ok, err) => {
+            format!(
+                "Result<{}, {}>",
+                rust_type(ok, prefix),
+                rust_type(err, prefix)
+            )
+        }
+        TypeExpr::Map(key, value) => {
+            format!(
+                "std::collections::BTreeMap<{}, {}>",
+                rust_type(key, prefix),
+                rust_type(value, prefix)
+            )
+        }
+        TypeExpr::Sequence(elem) => format!("Vec<{}>", rust_type(elem, prefix)),
+        TypeExpr::Array(elem, len) => format!("[{}; {len}]", rust_type(elem, prefix)),
+        TypeExpr::Tuple(elems) if elems.len() == 1 => {
+            format!("({},)", rust_type(&elems[0], prefix))
+        }
+        TypeExpr::Tuple(elems) => format!("({})", rust_types(elems, prefix)),
+        TypeExpr::Compact(inner) => {
+            format!("parity_scale_codec::Compact<{}>", rust_type(inner, prefix))
+        }
+    }
+}
+
+fn rust_types(exprs: &[TypeExpr], prefix: &str) -> String {
+    exprs
+        .iter()
+        .map(|expr| rust_type(expr, prefix))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn rust_manifest(name: &str, version: &str) -> String {
+    let cli_version = env!("CARGO_PKG_VERSION");
+    format!(
+        r#"[package]
+name = "{name}-bindings"
+version = "{version}"
+edition = "2021"
+description = "Typed bindings for the `{name}` contract"
+publish = false
+
+[dependencies]
+contract-extrinsics = "{cli_version}"
+ink_env = "5"
+parity-scale-codec = {{ version = "3", features = ["derive"] }}
+subxt = "0.35"
+"#
+    )
+}
+
+/// Emits the definitions of the custom types into the `types` module.
+fn rust_custom_types(out: &mut String, types: &Types) -> Result<()> {
+    writeln!(out, "/// Custom types used by the contract.").unwrap();
+    writeln!(out, "pub mod types {{").unwrap();
+    writeln!(out, "    use parity_scale_codec::{{Decode, Encode}};").unwrap();
+    for custom in &types.custom {
+        let generics = if custom.params.is_empty() {
+            String::new()
+        } else {
+            let names: Vec<_> = custom
+                .params
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            format!("<{}>", names.join(", "))
+        };
+        writeln!(out).unwrap();
+        rust_docs(out, "    ", &custom.ty.docs);
+        writeln!(
+            out,
+            "    #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]"
+        )
+        .unwrap();
+        match &custom.ty.type_def {
+            TypeDef::Composite(def) => {
+                let name = &custom.name;
+                if def.fields.is_empty() {
+                    writeln!(out, "    pub struct {name}{generics};").unwrap();
+                } else if def.fields.iter().all(|field| field.name.is_none()) {
+                    let fields = def
+                        .fields
+                        .iter()
+                        .map(|field| {
+                            Ok(format!(
+                                "pub {}",
+                                rust_type(&types.field_expr(custom, field)?, "")
+                            ))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    writeln!(
+                        out,
+                        "    pub struct {name}{generics}({});",
+                        fields.join(", ")
+                    )
+                    .unwrap();
+                } else {
+                    writeln!(out, "    pub struct {name}{generics} {{").unwrap();
+                    rust_named_fields(
+                        out,
+                        "        ",
+                        types,
+                        custom,
+                        &def.fields,
+                        "pub ",
+                    )?;
+                    writeln!(out, "    }}").unwrap();
+                }
+            }
+            TypeDef::Variant(def) => {
+                writeln!(out, "    pub enum {}{generics} {{", custom.name).unwrap();
+                for variant in &def.variants {
+                    rust_docs(out, "        ", &variant.docs);
+                    writeln!(out, "        #[codec(index = {})]", variant.index).unwrap();
+                    let name = rust_ident(&variant.name);
+                    if variant.fields.is_empty() {
+                        writeln!(out, "        {name},").unwrap();
+                    } else if variant.fields.iter().all(|field| field.name.is_none()) {
+                        let fields = variant
+                            .fields
+                            .iter()
+                            .map(|field| {
+                                Ok(rust_type(&types.field_expr(custom, field)?, ""))
+                            })
+                            .collect::<Result<Vec<_>>>()?;
+                        writeln!(out, "        {name}({}),", fields.join(", ")).unwrap();
+                    } else {
+                        writeln!(out, "        {name} {{").unwrap();
+                        rust_named_fields(
+                            out,
+                            "            ",
+                            types,
+                            custom,
+                            &variant.fields,
+                            "",
+                        )?;
+                        writeln!(out, "        }},").unwrap();
+                    }
+                }
+                writeln!(out, "    }}").unwrap();
+            }
+            _ => {
+                bail!(
+                    "Unsupported definition of type `{}`",
+                    custom.ty.path.segments.join("::")
+                )
+            }
+        }
+    }
+    writeln!(out, "}}").unwrap();
+    Ok(())
+}
+
+fn rust_named_fields(
+    out: &mut String,
+    indent: &str,
+    types: &Types,
+    custom: &CustomType,
+    fields: &[Field<PortableForm>],
+    visibility: &str,
+) -> Result<()> {
+    for (i, field) in fields.iter().enumerate() {
+        let name = field.name.clone().unwrap_or_else(|| format!("field_{i}"));
+        let ty = rust_type(&types.field_expr(custom, field)?, "");
+        rust_docs(out, indent, &field.docs);
+        writeln!(out, "{indent}{visibility}{}: {ty},", rust_ident(&name)).unwrap();
+    }
+    Ok(())
+}
+
+fn rust_args(types: &Types, args: &[MessageParamSpec<PortableForm>]) -> Result<String> {
+    Ok(args
+        .iter()
+        .map(|arg| {
+            Ok(format!(
+                "{}: {}",
+                rust_ident(arg.label()),
+                rust_type(&types.expr(arg.ty().ty().id)?, "types::")
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?
+        .join(", "))
+}
+
+fn rust_encode_args(out: &mut String, args: &[MessageParamSpec<PortableForm>]) {
+    for arg in args {
+        writeln!(
+            out,
+            "        {}.encode_to(&mut data);",
+            rust_ident(arg.label())
+        )
+        .unwrap();
+    }
+}
+
+fn rust_selector(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|byte| format!("0x{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+const RUST_RUNTIME: &str = r#"
+/// The encoded call of a constructor of the contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Constructor {
+    label: &'static str,
+    data: Vec<u8>,
+}
+
+impl Constructor {
+    /// The label of the constructor.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// The selector followed by the encoded arguments.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Prepares the instantiation of the contract.
+    pub fn instantiate<C, E, S>(
+        self,
+        extrinsic_opts: ExtrinsicOpts<C, E, S>,
+    ) -> InstantiateCommandBuilder<C, E, S>
+    where
+        C: Config,
+        C::Hash: From<[u8; 32]>,
+        E: Environment,
+        E::Balance: Default,
+        S: Signer<C> + Clone,
+    {
+        InstantiateCommandBuilder::new(extrinsic_opts)
+            .constructor(self.label)
+            .data(self.data)
+    }
+}
+
+/// The encoded call of a message of the contract returning `R`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message<R> {
+    label: &'static str,
+    data: Vec<u8>,
+    mutates: bool,
+    _return: PhantomData<fn() -> R>,
+}
+
+impl<R: Decode> Message<R> {
+    /// The label of the message.
+    pub fn label(&self) -> &'static str {
+        self.label
+    }
+
+    /// The selector followed by the encoded arguments.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Whether the message mutates the contract's storage.
+    pub fn mutates(&self) -> bool {
+        self.mutates
+    }
+
+    /// Prepares a call of the message on the contract at `contract`.
+    pub fn call<C, E, S>(
+        self,
+        contract: C::AccountId,
+        extrinsic_opts: ExtrinsicOpts<C, E, S>,
+    ) -> CallCommandBuilder<C, E, S>
+    where
+        C: Config,
+        E: Environment,
+        E::Balance: Default,
+        S: Signer<C> + Clone,
+    {
+        CallCommandBuilder::new(contract, self.label, extrinsic_opts).call_data(self.data)
+    }
+
+    /// Decodes the value returned by the message, e.g. from a dry run.
+    pub fn decode_return(&self, mut data: &[u8]) -> Result<R, parity_scale_codec::Error> {
+        R::decode(&mut data)
+    }
+}
+"#;
+
+/// Generates the `lib.rs` of a crate with typed bindings for a contract.
+fn rust_bindings(name: &str, version: &str, project: &InkProject) -> Result<String> {
+    let types = Types::new(project)?;
+    let spec = project.spec();
+    let mut out = String::new();
+    writeln!(
+        out,
+        "//! Typed bindings for the `{name}` contract, version {version}."
+    )
+    .unwrap();
+    writeln!(out, "//!").unwrap();
+    writeln!(
+        out,
+        "//! Generated by `cargo contract generate-bindings`, do not edit."
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "#![allow(clippy::all, dead_code, non_camel_case_types, non_snake_case, unused_mut)]").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "use contract_extrinsics::{{extrinsic_opts::ExtrinsicOpts, CallCommandBuilder, InstantiateCommandBuilder}};").unwrap();
+    writeln!(out, "use ink_env::Environment;").unwrap();
+    writeln!(out, "use parity_scale_codec::{{Decode, Encode}};").unwrap();
+    writeln!(out, "use std::marker::PhantomData;").unwrap();
+    writeln!(out, "use subxt::{{tx::Signer, Config}};").unwrap();
+    writeln!(out).unwrap();
+    rust_custom_types(&mut out, &types)?;
+    writeln!(out).unwrap();
+    writeln!(out, "pub use types::*;").unwrap();
+    out.push_str(RUST_RUNTIME);
+
+    writeln!(out).unwrap();
+    writeln!(out, "/// The constructors of the contract.").unwrap();
+    writeln!(out, "pub mod constructors {{").unwrap();
+    writeln!(out, "    use super::*;").unwrap();
+    for constructor in spec.constructors() {
+        writeln!(out).unwrap();
+        rust_docs(&mut out, "    ", constructor.docs());
+        writeln!(
+            out,
+            "    pub fn {}({}) -> Constructor {{",
+            rust_ident(&label_ident(constructor.label()).to_snake_case()),
+            rust_args(&types, constructor.args())?
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        let mut data = vec![{}];",
+            rust_selector(constructor.selector().to_bytes())
+        )
+        .unwrap();
+        rust_encode_args(&mut out, constructor.args());
+        writeln!(
+            out,
+            "        Constructor {{ label: {:?}, data }}",
+            constructor.label()
+        )
+        .unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+
+    writeln!(out).unwrap();
+    writeln!(out, "/// The messages of the contract.").unwrap();
+    writeln!(out, "pub mod messages {{").unwrap();
+    writeln!(out, "    use super::*;").unwrap();
+    for message in spec.messages() {
+        let ret = types.expr(message.return_type().ret_type().ty().id)?;
+        writeln!(out).unwrap();
+        rust_docs(&mut out, "    ", message.docs());
+        writeln!(
+            out,
+            "    pub fn {}({}) -> Message<{}> {{",
+            rust_ident(&label_ident(message.label()).to_snake_case()),
+            rust_args(&types, message.args())?,
+            rust_type(&ret, "types::")
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        let mut data = vec![{}];",
+            rust_selector(message.selector().to_bytes())
+        )
+        .unwrap();
+        rust_encode_args(&mut out, message.args());
+        writeln!(
+            out,
+            "        Message {{ label: {:?}, data, mutates: {}, _return: PhantomData }}",
+            message.label(),
+            message.mutates()
+        )
+        .unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+
+    rust_events(&mut out, &types, spec.events())?;
+    Ok(out)
+}
+
+fn rust_events(
+    out: &mut String,
+    types: &Types,
+    events: &[EventSpec<PortableForm>],
+) -> Result<()> {
+    writeln!(out).unwrap();
+    writeln!(out, "/// The events of the contract.").unwrap();
+    writeln!(out, "pub mod events {{").unwrap();
+    writeln!(out, "    use super::*;").unwrap();
+    for event in events {
+        writeln!(out).unwrap();
+        rust_docs(out, "    ", event.docs());
+        writeln!(
+            out,
+            "    #[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]"
+        )
+        .unwrap();
+        writeln!(out, "    pub struct {} {{", event.label()).unwrap();
+        for arg in event.args() {
+            rust_docs(out, "        ", arg.docs());
+            writeln!(
+                out,
+                "        pub {}: {},",
+                rust_ident(arg.label()),
+                rust_type(&types.expr(arg.ty().ty().id)?, "types::")
+            )
+            .unwrap();
+        }
+        writeln!(out, "    }}").unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+
+    writeln!(out).unwrap();
+    writeln!(out, "/// An event emitted by the contract.").unwrap();
+    writeln!(out, "#[derive(Debug, Clone, PartialEq, Eq)]").unwrap();
+    writeln!(out, "pub enum Event {{").unwrap();
+    for event in events {
+        writeln!(out, "    {0}(events::{0}),", event.label()).unwrap();
+    }
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "impl Event {{").unwrap();
+    writeln!(
+        out,
+        "    /// Decodes an event from its signature topic and data."
+    )
+    .unwrap();
+    writeln!(out, "    ///").unwrap();
+    writeln!(
+        out,
+        "    /// Returns `None` if the topic belongs to no event of the contract."
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    /// Anonymous events have no signature topic and are never decoded."
+    )
+    .unwrap();
+    writeln!(out, "    pub fn decode(").unwrap();
+    writeln!(out, "        signature_topic: &[u8],").unwrap();
+    writeln!(out, "        mut data: &[u8],").unwrap();
+    writeln!(
+        out,
+        "    ) -> Option<Result<Self, parity_scale_codec::Error>> {{"
+    )
+    .unwrap();
+    writeln!(out, "        match signature_topic {{").unwrap();
+    for event in events {
+        if let Some(topic) = event.signature_topic() {
+            writeln!(
+                out,
+                "            [{}] => Some(events::{1}::decode(&mut data).map(Self::{1})),",
+                rust_selector(topic.as_bytes()),
+                event.label()
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out, "            _ => None,").unwrap();
+    writeln!(out, "        }}").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    Ok(())
+}
+
+const TS_RESERVED: &[&str] = &[
+    "arguments",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+];
+
+/// The key of a constructor or message in the `setheum-js` contract API.
+fn ts_key(label: &str) -> String {
+    label_ident(label).to_lower_camel_case()
+}
+
+fn ts_ident(name: &str) -> String {
+    let ident = name.to_lower_camel_case();
+    if TS_RESERVED.contains(&ident.as_str()) {
+        format!("{ident}_")
+    } else {
+        ident
+    }
+}
+
+fn ts_docs(out: &mut String, indent: &str, docs: &[String]) {
+    if docs.iter().all(|line| line.trim().is_empty()) {
+        return
+    }
+    writeln!(out, "{indent}/**").unwrap();
+    for line in docs {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(out, "{indent} *").unwrap();
+        } else {
+            writeln!(out, "{indent} *{}", with_leading_space(line)).unwrap();
+        }
+    }
+    writeln!(out, "{indent} */").unwrap();
+}
+
+/// Renders a type as TypeScript, following the JSON representation of the
+/// `setheum-js` codecs.
+fn ts_type(expr: &TypeExpr) -> String {
+    match expr {
+        TypeExpr::Primitive(primitive) => {
+            match primitive {
+                TypeDefPrimitive::Bool => "boolean",
+                TypeDefPrimitive::Char | TypeDefPrimitive::Str => "string",
+                TypeDefPrimitive::U8
+                | TypeDefPrimitive::U16
+                | TypeDefPrimitive::U32
+                | TypeDefPrimitive::I8
+                | TypeDefPrimitive::I16
+                | TypeDefPrimitive::I32 => "number",
+                TypeDefPrimitive::U64
+                | TypeDefPrimitive::U128
+                | TypeDefPrimitive::U256
+                | TypeDefPrimitive::I64
+                | TypeDefPrimitive::I128
+                | TypeDefPrimitive::I256 => "Numeric",
+            }
+            .to_string()
+        }
+        TypeExpr::Param(name) => name.clone(),
+        TypeExpr::Custom(name, args) if args.is_empty() => name.clone(),
+        TypeExpr::Custom(name, args) => format!("{name}<{}>", ts_types(args)),
+        TypeExpr::Option(some) => format!("{} | null", ts_type(some)),
+        TypeExpr::Result(ok, err) => {
+            format!("{{ ok: {} }} | {{ err: {} }}", ts_type(ok), ts_type(err))
+        }
+        // Maps are represented as objects keyed by the JSON of their keys.
+        TypeExpr::Map(_, value) => format!("Record<string, {}>", ts_type(value)),
+        // Byte sequences and arrays are represented as hex strings.
+        TypeExpr::Sequence(elem) | TypeExpr::Array(elem, _)
+            if **elem == TypeExpr::Primitive(TypeDefPrimitive::U8) =>
+        {
+            "string".to_string()
+        }
+        TypeExpr::Sequence(elem) | TypeExpr::Array(elem, _) => {
+            format!("Array<{}>", ts_type(elem))
+        }
+        TypeExpr::Tuple(elems) if elems.is_empty() => "null".to_string(),
+        TypeExpr::Tuple(elems) => format!("[{}]", ts_types(elems)),
+        TypeExpr::Compact(inner) => ts_type(inner),
+    }
+}
+
+fn ts_types(exprs: &[TypeExpr]) -> String {
+    exprs.iter().map(ts_type).collect::<Vec<_>>().join(", ")
+}
+
+fn ts_fields(
+    types: &Types,
+    custom: &CustomType,
+    fields: &[Field<PortableForm>],
+) -> Result<String> {
+    if fields.iter().all(|field| field.name.is_none()) {
+        let elems = fields
+            .iter()
+            .map(|field| Ok(ts_type(&types.field_expr(custom, field)?)))
+            .collect::<Result<Vec<_>>>()?;
+        return Ok(match elems.len() {
+            0 => "null".to_string(),
+            1 => elems[0].clone(),
+            _ => format!("[{}]", elems.join(", ")),
+        });
+    }
+    let fields = fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let name = field.name.clone().unwrap_or_else(|| format!("field_{i}"));
+            Ok(format!(
+                "{}: {}",
+                name.to_lower_camel_case(),
+                ts_type(&types.field_expr(custom, field)?)
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(format!("{{ {} }}", fields.join("; ")))
+}
+
+fn ts_custom_types(out: &mut String, types: &Types) -> Result<()> {
+    for custom in &types.custom {
+        let generics = if custom.params.is_empty() {
+            String::new()
+        } else {
+            let names: Vec<_> = custom
+                .params
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            format!("<{}>", names.join(", "))
+        };
+        writeln!(out).unwrap();
+        ts_docs(out, "", &custom.ty.docs);
+        let def = match &custom.ty.type_def {
+            TypeDef::Composite(def) => ts_fields(types, custom, &def.fields)?,
+            TypeDef::Variant(def) if def.variants.iter().all(|v| v.fields.is_empty()) => {
+                def.variants
+                    .iter()
+                    .map(|variant| format!("'{}'", variant.name))
+                    .collect::<Vec<_>>()
+                    .join(" | ")
+            }
+            TypeDef::Variant(def) => {
+                def.variants
+                    .iter()
+                    .map(|variant| {
+                        Ok(format!(
+                            "{{ {}: {} }}",
+                            variant.name.to_lower_camel_case(),
+                            ts_fields(types, custom, &variant.fields)?
+                        ))
+                    })
+                    .collect::<Result<Vec<_>>>()?
+                    .join(" | ")
+            }
+            _ => {
+                bail!(
+                    "Unsupported definition of type `{}`",
+                    custom.ty.path.segments.join("::")
+                )
+            }
+        };
+        writeln!(out, "export type {}{generics} = {def};", custom.name).unwrap();
+    }
+    Ok(())
+}
+
+fn ts_args(
+    types: &Types,
+    args: &[MessageParamSpec<PortableForm>],
+) -> Result<Vec<(String, String)>> {
+    args.iter()
+        .map(|arg| {
+            Ok((
+                ts_ident(arg.label()),
+                ts_type(&types.expr(arg.ty().ty().id)?),
+            ))
+        })
+        .collect()
+}
+
+fn ts_params(args: &[(String, String)]) -> String {
+    args.iter()
+        .map(|(name, ty)| format!(", {name}: {ty}"))
+        .collect()
+}
+
+fn ts_call_args(args: &[(String, String)]) -> String {
+    args.iter().map(|(name, _)| format!(", {name}")).collect()
+}
+
+const TS_RUNTIME: &str = r#"
+/** Integers wider than 32 bits, as accepted and returned by the codecs. */
+export type Numeric = number | bigint | string;
+
+/** The outcome of a dry run along with its decoded return value. */
+export interface QueryResult<T> {
+  value: T | undefined;
+  outcome: ContractCallOutcome;
+}
+
+function queryResult<T>(outcome: ContractCallOutcome): QueryResult<T> {
+  return {
+    value: outcome.output ? (outcome.output.toJSON() as unknown as T) : undefined,
+    outcome
+  };
+}
+"#;
+
+/// Generates a TypeScript module with typed wrappers for a contract.
+fn typescript_bindings(
+    name: &str,
+    version: &str,
+    project: &InkProject,
+) -> Result<String> {
+    let types = Types::new(project)?;
+    let spec = project.spec();
+    let class = name.to_upper_camel_case();
+    let mut out = String::new();
+    writeln!(
+        out,
+        "// Typed bindings for the `{name}` contract, version {version}."
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "// Generated by `cargo contract generate-bindings`, do not edit."
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "import type {{ ApiPromise }} from '@polkadot/api';").unwrap();
+    writeln!(
+        out,
+        "import type {{ SubmittableExtrinsic }} from '@polkadot/api/types';"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "import {{ CodePromise, ContractPromise }} from '@polkadot/api-contract';"
+    )
+    .unwrap();
+    writeln!(out, "import type {{ ContractCallOutcome, ContractOptions }} from '@polkadot/api-contract/types';").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "import metadata from './metadata.json';").unwrap();
+    out.push_str(TS_RUNTIME);
+    ts_custom_types(&mut out, &types)?;
+
+    for event in spec.events() {
+        let fields = event
+            .args()
+            .iter()
+            .map(|arg| {
+                Ok(format!(
+                    "{}: {}",
+                    arg.label().to_lower_camel_case(),
+                    ts_type(&types.expr(arg.ty().ty().id)?)
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        writeln!(out).unwrap();
+        ts_docs(&mut out, "", event.docs());
+        writeln!(
+            out,
+            "export interface {}Event {{ {} }}",
+            event.label(),
+            fields.join("; ")
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+    writeln!(out, "/** An event emitted by the contract. */").unwrap();
+    let events = spec
+        .events()
+        .iter()
+        .map(|event| format!("{{ name: '{0}'; args: {0}Event }}", event.label()))
+        .collect::<Vec<_>>();
+    let events = if events.is_empty() {
+        "never".to_string()
+    } else {
+        events.join(" | ")
+    };
+    writeln!(out, "export type {class}Event = {events};").unwrap();
+
+    writeln!(out).unwrap();
+    ts_docs(&mut out, "", spec.docs());
+    writeln!(out, "export class {class} {{").unwrap();
+    writeln!(out, "  readonly contract: ContractPromise;").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "  constructor(api: ApiPromise, address: string) {{").unwrap();
+    writeln!(
+        out,
+        "    this.contract = new ContractPromise(api, metadata, address);"
+    )
+    .unwrap();
+    writeln!(out, "  }}").unwrap();
+
+    for constructor in spec.constructors() {
+        let args = ts_args(&types, constructor.args())?;
+        writeln!(out).unwrap();
+        ts_docs(&mut out, "  ", constructor.docs());
+        writeln!(
+            out,
+            "  static {}(api: ApiPromise, wasm: Uint8Array | string, options: ContractOptions{}): SubmittableExtrinsic<'promise'> {{",
+            ts_key(constructor.label()),
+            ts_params(&args)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    return new CodePromise(api, metadata, wasm).tx['{}'](options{});",
+            ts_key(constructor.label()),
+            ts_call_args(&args)
+        )
+        .unwrap();
+        writeln!(out, "  }}").unwrap();
+    }
+
+    writeln!(out).unwrap();
+    writeln!(out, "  /** Dry runs of the contract's messages. */").unwrap();
+    writeln!(out, "  readonly query = {{").unwrap();
+    for message in spec.messages() {
+        let args = ts_args(&types, message.args())?;
+        let ret = ts_type(&types.expr(message.return_type().ret_type().ty().id)?);
+        ts_docs(&mut out, "    ", message.docs());
+        writeln!(
+            out,
+            "    {0}: async (caller: string, options: ContractOptions{1}): Promise<QueryResult<{2}>> =>",
+            ts_key(message.label()),
+            ts_params(&args),
+            ret
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "      queryResult<{}>(await this.contract.query['{}'](caller, options{})),",
+            ret,
+            ts_key(message.label()),
+            ts_call_args(&args)
+        )
+        .unwrap();
+    }
+    writeln!(out, "  }};").unwrap();
+
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "  /** Transactions calling the contract's messages. */"
+    )
+    .unwrap();
+    writeln!(out, "  readonly tx = {{").unwrap();
+    for message in spec.messages().iter().filter(|message| message.mutates()) {
+        let args = ts_args(&types, message.args())?;
+        ts_docs(&mut out, "    ", message.docs());
+        writeln!(
+            out,
+            "    {0}: (options: ContractOptions{1}): SubmittableExtrinsic<'promise'> =>",
+            ts_key(message.label()),
+            ts_params(&args)
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "      this.contract.tx['{}'](options{}),",
+            ts_key(message.label()),
+            ts_call_args(&args)
+        )
+        .unwrap();
+    }
+    writeln!(out, "  }};").unwrap();
+    writeln!(out, "}}").unwrap();
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{
+        json,
+        Value,
+    };
+
+    fn result(id: u32, ok: u32, err: u32) -> Value {
+        json!({
+            "id": id,
+            "type": {
+                "path": ["Result"],
+                "params": [{ "name": "T", "type": ok }, { "name": "E", "type": err }],
+                "def": {
+                    "variant": {
+                        "variants": [
+                            { "name": "Ok", "fields": [{ "type": ok }], "index": 0 },
+                            { "name": "Err", "fields": [{ "type": err }], "index": 1 }
+                        ]
+                    }
+                }
+            }
+        })
+    }
+
+    fn error(id: u32, path: Value, variant: &str) -> Value {
+        json!({
+            "id": id,
+            "type": {
+                "path": path,
+                "def": { "variant": { "variants": [{ "name": variant, "index": 0 }] } }
+            }
+        })
+    }
+
+    fn ty(id: u32) -> Value {
+        json!({ "type": id, "displayName": [] })
+    }
+
+    /// The metadata of a token with a generic type and two types named `Error`.
+    fn project() -> InkProject {
+        serde_json::from_value(json!({
+            "version": 5,
+            "types": [
+                { "id": 0, "type": { "def": { "primitive": "u128" } } },
+                { "id": 1, "type": { "def": { "primitive": "bool" } } },
+                error(2, json!(["ink_primitives", "LangError"]), "CouldNotReadInput"),
+                { "id": 3, "type": { "def": { "tuple": [] } } },
+                result(4, 3, 2),
+                error(5, json!(["erc20", "erc20", "Error"]), "InsufficientBalance"),
+                error(6, json!(["ownable", "Error"]), "NotOwner"),
+                result(7, 3, 5),
+                result(8, 7, 2),
+                {
+                    "id": 9,
+                    "type": {
+                        "path": ["erc20", "Pair"],
+                        "params": [{ "name": "A", "type": 0 }, { "name": "B", "type": 1 }],
+                        "def": {
+                            "composite": {
+                                "fields": [
+                                    { "name": "first", "type": 0, "typeName": "A" },
+                                    { "name": "second", "type": 1, "typeName": "B" }
+                                ]
+                            }
+                        }
+                    }
+                },
+                {
+                    "id": 10,
+                    "type": {
+                        "path": ["Option"],
+                        "params": [{ "name": "T", "type": 9 }],
+                        "def": {
+                            "variant": {
+                                "variants": [
+                                    { "name": "None", "index": 0 },
+                                    { "name": "Some", "fields": [{ "type": 9 }], "index": 1 }
+                                ]
+                            }
+                        }
+                    }
+                },
+                result(11, 10, 2),
+                result(12, 3, 6),
+                result(13, 12, 2)
+            ],
+            "storage": {
+                "root": {
+                    "root_key": "0x00000000",
+                    "layout": { "struct": { "name": "Erc20", "fields": [] } },
+                    "ty": 3
+                }
+            },
+            "spec": {
+                "constructors": [{
+                    "label": "new",
+                    "selector": "0x9bae9d5e",
+                    "payable": false,
+                    "args": [{ "label": "total_supply", "type": ty(0) }],
+                    "returnType": ty(4),
+                    "docs": ["Creates a new token."],
+                    "default": false
+                }],
+                "messages": [
+                    {
+                        "label": "transfer",
+                        "selector": "0x84a15da1",
+                        "mutates": true,
+                        "payable": false,
+                        "args": [{ "label": "value", "type": ty(0) }],
+                        "returnType": ty(8),
+                        "docs": [],
+                        "default": false
+                    },
+                    {
+                        "label": "pair",
+                        "selector": "0x00000001",
+                        "mutates": false,
+                        "payable": false,
+                        "args": [],
+                        "returnType": ty(11),
+                        "docs": [],
+                        "default": false
+                    },
+                    {
+                        "label": "Ownable::check_owner",
+                        "selector": "0x00000002",
+                        "mutates": false,
+                        "payable": false,
+                        "args": [],
+                        "returnType": ty(13),
+                        "docs": [],
+                        "default": false
+                    }
+                ],
+                "events": [{
+                    "label": "Transfer",
+                    "module_path": "erc20",
+                    "signature_topic":
+                        "0x1111111111111111111111111111111111111111111111111111111111111111",
+                    "args": [{
+                        "label": "value",
+                        "indexed": false,
+                        "type": ty(0),
+                        "docs": []
+                    }],
+                    "docs": []
+                }],
+                "docs": [],
+                "lang_error": ty(2),
+                "environment": {
+                    "accountId": ty(0),
+                    "balance": ty(0),
+                    "hash": ty(0),
+                    "timestamp": ty(0),
+                    "blockNumber": ty(0),
+                    "chainExtension": ty(3),
+                    "maxEventTopics": 4,
+                    "staticBufferSize": 16384
+                }
+            }
+        }))
+        .expect("metadata must be valid")
+    }
+
+    #[test]
+    fn rust_bindings_are_typed() {
+        let bindings = rust_bindings("erc20", "1.0.0", &project()).unwrap();
+
+        for expected in [
+            "pub enum LangError {",
+            "pub enum Erc20Error {",
+            "pub enum OwnableError {",
+            "pub struct Pair<A, B> {",
+            "        pub first: A,",
+            "        pub second: B,",
+            "    pub fn new(total_supply: u128) -> Constructor {",
+            "        let mut data = vec![0x9b, 0xae, 0x9d, 0x5e];",
+            "        total_supply.encode_to(&mut data);",
+            "    pub fn transfer(value: u128) -> Message<Result<Result<(), \
+             types::Erc20Error>, types::LangError>> {",
+            "    pub fn pair() -> Message<Result<Option<types::Pair<u128, bool>>, \
+             types::LangError>> {",
+            "    pub fn ownable_check_owner() -> Message<Result<Result<(), \
+             types::OwnableError>, types::LangError>> {",
+            "        pub value: u128,",
+            "    Transfer(events::Transfer),",
+        ] {
+            assert!(
+                bindings.contains(expected),
+                "missing `{expected}`:\n{bindings}"
+            );
+        }
+    }
+
+    #[test]
+    fn typescript_bindings_are_typed() {
+        let bindings = typescript_bindings("erc20", "1.0.0", &project()).unwrap();
+
+        for expected in [
+            "export type Erc20Error = 'InsufficientBalance';",
+            "export type Pair<A, B> = { first: A; second: B };",
+            "export interface TransferEvent { value: Numeric }",
+            "export type Erc20Event = { name: 'Transfer'; args: TransferEvent };",
+            "export class Erc20 {",
+            "  static new(api: ApiPromise, wasm: Uint8Array | string, options: \
+             ContractOptions, totalSupply: Numeric)",
+            "    pair: async (caller: string, options: ContractOptions): \
+             Promise<QueryResult<{ ok: Pair<Numeric, boolean> | null } | { err: \
+             LangError }>> =>",
+            "    ownableCheckOwner: async",
+            "    transfer: (options: ContractOptions, value: Numeric): \
+             SubmittableExtrinsic<'promise'> =>",
+        ] {
+            assert!(
+                bindings.contains(expected),
+                "missing `{expected}`:\n{bindings}"
+            );
+        }
+        // Messages which don't mutate the contract are only dry run.
+        assert!(!bindings.contains("    pair: (options"));
+    }
+
+    #[test]
+    fn ambiguous_names_are_qualified_by_module() {
+        let paths = [
+            vec!["erc20".to_string(), "Error".to_string()],
+            vec!["ownable".to_string(), "Error".to_string()],
+            vec!["erc20".to_string(), "Balance".to_string()],
+        ];
+        let paths: Vec<&[String]> = paths.iter().map(Vec::as_slice).collect();
+        let names = unique_names(&paths);
+
+        assert_eq!(names[paths[0]], "Erc20Error");
+        assert_eq!(names[paths[1]], "OwnableError");
+        assert_eq!(names[paths[2]], "Balance");
+    }
+}
@@ -36,10 +36,14 @@
 // SOFTWARE.
 
 use super::{
-    config::SignerConfig,
+    config::{
+        CLISigner,
+        SignerConfig,
+    },
     display_contract_exec_result,
     display_contract_exec_result_debug,
     display_dry_run_result_warning,
+    dry_run_summary,
     parse_balance,
     print_dry_running_status,
     print_gas_required_success,
@@ -159,9 +163,9 @@ impl InstantiateCommand {
         <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
         <C as Config>::Hash: From<[u8; 32]> + IntoVisitor + EncodeAsType,
+        C::Address: From<C::AccountId>,
     {
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
         let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
 
@@ -191,6 +195,7 @@ impl InstantiateCommand {
                 .gas_limit(self.gas_limit)
                 .proof_size(self.proof_size)
                 .salt(self.salt.clone())
+                .wrappers(self.extrinsic_cli_opts.offline_cli_opts.wrappers()?)?
                 .done()
                 .await?;
 
@@ -236,7 +241,10 @@ impl InstantiateCommand {
                 self.extrinsic_cli_opts.skip_dry_run,
             )
             .await?;
-            if !self.extrinsic_cli_opts.skip_confirm {
+            let offline_cli_opts = &self.extrinsic_cli_opts.offline_cli_opts;
+            if !self.extrinsic_cli_opts.skip_confirm
+                && !offline_cli_opts.writes_unsigned()
+            {
                 prompt_confirm_tx(|| {
                     print_default_instantiate_preview(&instantiate_exec, gas_limit);
                     if let Code::Existing(code_hash) =
@@ -250,6 +258,28 @@ impl InstantiateCommand {
                     }
                 })?;
             }
+            if offline_cli_opts.is_set() {
+                let dry_run = if self.extrinsic_cli_opts.skip_dry_run {
+                    None
+                } else {
+                    Some(dry_run_summary(
+                        &instantiate_exec.instantiate_dry_run().await?,
+                    ))
+                };
+                let unsigned = instantiate_exec
+                    .instantiate_unsigned(Some(gas_limit), dry_run)
+                    .await?;
+                return offline_cli_opts
+                    .dispatch(
+                        unsigned,
+                        instantiate_exec.opts().signer(),
+                        instantiate_exec.client(),
+                        &token_metadata,
+                        self.extrinsic_cli_opts.verbosity()?,
+                        self.output_json(),
+                    )
+                    .await;
+            }
             let instantiate_result =
                 instantiate_exec.instantiate(Some(gas_limit)).await?;
             display_result(
@@ -269,12 +299,12 @@ impl InstantiateCommand {
 async fn pre_submit_dry_run_gas_estimate_instantiate<
     C: Config + Environment + SignerConfig<C>,
 >(
-    instantiate_exec: &InstantiateExec<C, C, C::Signer>,
+    instantiate_exec: &InstantiateExec<C, C, CLISigner<C>>,
     output_json: bool,
     skip_dry_run: bool,
 ) -> Result<Weight>
 where
-    C::Signer: subxt::tx::Signer<C> + Clone,
+    C::Address: From<C::AccountId>,
     <C as Config>::AccountId: IntoVisitor + Display + Decode,
     <C as Config>::Hash: IntoVisitor + EncodeAsType,
     C::Balance: Serialize + Debug + EncodeAsType,
@@ -333,13 +363,14 @@ where
 /// Displays the results of contract instantiation, including contract address,
 /// events, and optional code hash.
 pub async fn display_result<C: Config + Environment + SignerConfig<C>>(
-    instantiate_exec: &InstantiateExec<C, C, C::Signer>,
+    instantiate_exec: &InstantiateExec<C, C, CLISigner<C>>,
     instantiate_exec_result: InstantiateExecResult<C>,
     token_metadata: &TokenMetadata,
     output_json: bool,
     verbosity: Verbosity,
 ) -> Result<(), ErrorVariant>
 where
+    C::Address: From<C::AccountId>,
     <C as Config>::AccountId: IntoVisitor + EncodeAsType + Display + Decode,
     <C as Config>::Hash: IntoVisitor + EncodeAsType,
     C::Balance: Serialize + From<u128> + Display + EncodeAsType,
@@ -372,10 +403,10 @@ where
 }
 
 pub fn print_default_instantiate_preview<C: Config + Environment + SignerConfig<C>>(
-    instantiate_exec: &InstantiateExec<C, C, C::Signer>,
+    instantiate_exec: &InstantiateExec<C, C, CLISigner<C>>,
     gas_limit: Weight,
 ) where
-    C::Signer: subxt::tx::Signer<C> + Clone,
+    C::Address: From<C::AccountId>,
     <C as Config>::AccountId: IntoVisitor + EncodeAsType + Display + Decode,
     <C as Config>::Hash: IntoVisitor + EncodeAsType,
     C::Balance: Serialize + EncodeAsType,
@@ -40,10 +40,14 @@ mod prod_chains;
 
 pub mod build;
 pub mod call;
+pub mod check_upgrade;
 pub mod decode;
+pub mod deploy;
 pub mod encode;
+pub mod generate_bindings;
 pub mod info;
 pub mod instantiate;
+pub mod offline;
 pub mod remove;
 pub mod rpc;
 pub mod schema;
@@ -57,12 +61,19 @@ pub(crate) use self::{
         CheckCommand,
     },
     call::CallCommand,
+    check_upgrade::CheckUpgradeCommand,
     decode::DecodeCommand,
+    deploy::DeployCommand,
+    generate_bindings::GenerateBindingsCommand,
     info::{
         ExtendedContractInfo,
         InfoCommand,
     },
     instantiate::InstantiateCommand,
+    offline::{
+        SignCommand,
+        SubmitCommand,
+    },
     prod_chains::ProductionChain,
     remove::RemoveCommand,
     rpc::RpcCommand,
@@ -75,6 +86,13 @@ pub(crate) use self::{
     verify::VerifyCommand,
 };
 
+use self::{
+    config::{
+        CLISigner,
+        SignerConfig,
+    },
+    offline::CLIOfflineOpts,
+};
 use crate::{
     anyhow,
     PathBuf,
@@ -97,6 +115,8 @@ use contract_extrinsics::{
     BalanceVariant,
     TokenMetadata,
 };
+use ink_env::Environment;
+use subxt::Config;
 
 use std::{
     fmt::{
@@ -125,8 +145,8 @@ pub struct CLIExtrinsicOpts {
     /// e.g.
     /// - for a dev account "//Alice"
     /// - with a password "//Alice///SECRET_PASSWORD"
-    #[clap(name = "suri", long, short)]
-    suri: String,
+    #[clap(name = "suri", long, short, required_unless_present = "signer")]
+    suri: Option<String>,
     #[clap(flatten)]
     verbosity: VerbosityFlags,
     /// Submit the extrinsic for on-chain execution.
@@ -145,6 +165,9 @@ pub struct CLIExtrinsicOpts {
     /// Arguments required for communicating with a Substrate node.
     #[clap(flatten)]
     chain_cli_opts: CLIChainOpts,
+    /// Arguments for signing offline and dispatching through a multisig or proxy.
+    #[clap(flatten)]
+    offline_cli_opts: CLIOfflineOpts,
 }
 
 impl CLIExtrinsicOpts {
@@ -152,6 +175,29 @@ impl CLIExtrinsicOpts {
     pub fn verbosity(&self) -> Result<Verbosity> {
         TryFrom::try_from(&self.verbosity)
     }
+
+    /// Returns the signer given by either `--suri` or `--signer`.
+    pub fn signer<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<CLISigner<C>>
+    where
+        C::AccountId: FromStr,
+        <C::AccountId as FromStr>::Err: Display,
+    {
+        match (&self.suri, self.offline_cli_opts.signer()) {
+            (Some(suri), _) => {
+                C::Signer::from_str(suri)
+                    .map(CLISigner::Key)
+                    .map_err(|_| anyhow!("Failed to parse suri option"))
+            }
+            (None, Some(account)) => {
+                parse_account(account)
+                    .map(CLISigner::Account)
+                    .map_err(|e| anyhow!("Failed to parse signer option: {e}"))
+            }
+            (None, None) => Err(anyhow!("Either `--suri` or `--signer` is required")),
+        }
+    }
 }
 
 /// Arguments required for communicating with a Substrate node.
@@ -246,6 +292,23 @@ where
     Ok(())
 }
 
+/// Summarizes the result of a `instantiate` or `call` dry-run for review before signing.
+pub fn dry_run_summary<R, Balance>(
+    result: &ContractResult<R, Balance>,
+) -> serde_json::Value
+where
+    R: Debug,
+    Balance: serde::Serialize,
+{
+    serde_json::json!({
+        "result": format!("{:?}", result.result),
+        "gas_consumed": result.gas_consumed,
+        "gas_required": result.gas_required,
+        "storage_deposit": result.storage_deposit,
+        "debug_message": String::from_utf8_lossy(&result.debug_message),
+    })
+}
+
 pub fn display_contract_exec_result_debug<R, const WIDTH: usize, Balance>(
     result: &ContractResult<R, Balance>,
 ) -> Result<()> {
@@ -0,0 +1,453 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use crate::{
+    call_with_config,
+    ErrorVariant,
+};
+
+use super::{
+    config::{
+        CLISigner,
+        SignerConfig,
+    },
+    parse_account,
+    CLIChainOpts,
+};
+use anyhow::{
+    Context,
+    Result,
+};
+use colored::Colorize;
+use contract_build::{
+    name_value_println,
+    Verbosity,
+    VerbosityFlags,
+};
+use contract_extrinsics::{
+    url_to_string,
+    CallWrapper,
+    DisplayEvents,
+    SignedExtrinsic,
+    Timepoint,
+    TokenMetadata,
+    UnsignedExtrinsic,
+};
+use ink_env::Environment;
+use std::{
+    fmt::Display,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::scale_decode::IntoVisitor,
+    Config,
+    OnlineClient,
+};
+
+/// Arguments for signing extrinsics offline and dispatching them through a multisig
+/// or proxy.
+#[derive(Clone, Debug, clap::Args)]
+pub struct CLIOfflineOpts {
+    /// Write the unsigned extrinsic, along with its dry-run result and estimated
+    /// weight, to this file instead of submitting it. Sign it with `cargo contract
+    /// sign` and submit it with `cargo contract submit`.
+    #[clap(long, value_parser, requires = "execute")]
+    unsigned: Option<PathBuf>,
+    /// The account signing the unsigned extrinsic offline, in place of `--suri`.
+    #[clap(long, requires = "unsigned", conflicts_with = "suri")]
+    signer: Option<String>,
+    /// Dispatch the extrinsic from a multisig account with this threshold, approving
+    /// it as one of its signatories via `Multisig::as_multi`.
+    #[clap(long, requires = "multisig_signatories")]
+    multisig_threshold: Option<u16>,
+    /// The other signatories of the multisig account.
+    #[clap(long, num_args = 1.., requires = "multisig_threshold")]
+    multisig_signatories: Vec<String>,
+    /// The timepoint `<height>:<index>` of the first approval, required by all further
+    /// approvals of the multisig call.
+    #[clap(long, requires = "multisig_threshold")]
+    multisig_timepoint: Option<Timepoint>,
+    /// Dispatch the extrinsic on behalf of this account via `Proxy::proxy`. With
+    /// `--multisig-threshold` the multisig account is the proxy.
+    #[clap(long)]
+    proxy: Option<String>,
+    /// The proxy type of the runtime to restrict the proxy call to, e.g. `Any`.
+    #[clap(long, requires = "proxy")]
+    proxy_type: Option<String>,
+}
+
+impl CLIOfflineOpts {
+    /// Returns whether the extrinsic is signed offline or dispatched through a
+    /// multisig or proxy.
+    pub fn is_set(&self) -> bool {
+        self.unsigned.is_some()
+            || self.multisig_threshold.is_some()
+            || self.proxy.is_some()
+    }
+
+    /// Returns whether the unsigned extrinsic is written to a file.
+    pub fn writes_unsigned(&self) -> bool {
+        self.unsigned.is_some()
+    }
+
+    /// Returns the account given by `--signer`.
+    pub fn signer(&self) -> Option<&str> {
+        self.signer.as_deref()
+    }
+
+    /// Returns the wrappers of the call, innermost first.
+    pub fn wrappers<AccountId>(&self) -> Result<Vec<CallWrapper<AccountId>>>
+    where
+        AccountId: FromStr,
+        <AccountId as FromStr>::Err: Display,
+    {
+        let mut wrappers = Vec::new();
+        if let Some(real) = &self.proxy {
+            wrappers.push(CallWrapper::Proxy {
+                real: parse_account(real)
+                    .map_err(|e| anyhow::anyhow!("Failed to parse proxy option: {e}"))?,
+                proxy_type: self.proxy_type.clone(),
+            });
+        }
+        if let Some(threshold) = self.multisig_threshold {
+            let other_signatories = self
+                .multisig_signatories
+                .iter()
+                .map(|account| parse_account(account))
+                .collect::<Result<_>>()
+                .map_err(|e| {
+                    anyhow::anyhow!("Failed to parse multisig signatories option: {e}")
+                })?;
+            wrappers.push(CallWrapper::Multisig {
+                threshold,
+                other_signatories,
+                timepoint: self.multisig_timepoint,
+            });
+        }
+        Ok(wrappers)
+    }
+
+    /// Writes the unsigned extrinsic to the file given by `--unsigned`, or signs it
+    /// with the `--suri` key and submits it.
+    pub async fn dispatch<C: Config + Environment + SignerConfig<C>>(
+        &self,
+        unsigned: UnsignedExtrinsic,
+        signer: &CLISigner<C>,
+        client: &OnlineClient<C>,
+        token_metadata: &TokenMetadata,
+        verbosity: Verbosity,
+        output_json: bool,
+    ) -> Result<(), ErrorVariant>
+    where
+        C::Address: From<C::AccountId>,
+        C::AccountId: IntoVisitor,
+        C::Balance: Display + From<u128>,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        if let Some(path) = &self.unsigned {
+            write_json(path, &unsigned)?;
+            if output_json {
+                println!("{}", serde_json::json!({ "unsigned": path }));
+            } else {
+                println!(
+                    "{} unsigned extrinsic to {}",
+                    "Written".green().bold(),
+                    path.display()
+                );
+                name_value_println!("Call", unsigned.description);
+                name_value_println!("Weight", unsigned.estimated_weight.to_string());
+                name_value_println!("Nonce", unsigned.nonce.to_string());
+            }
+            return Ok(())
+        }
+        let events = unsigned.sign::<C, _>(signer)?.submit(client).await?;
+        display_events(&events, client, token_metadata, verbosity, output_json)
+    }
+}
+
+fn write_json<T: serde::Serialize>(path: &Path, value: &T) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(value)?)
+        .context(format!("Failed to write {}", path.display()))
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let json = std::fs::read_to_string(path)
+        .context(format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&json).context(format!("Failed to parse {}", path.display()))
+}
+
+fn display_events<C: Config + Environment>(
+    events: &subxt::blocks::ExtrinsicEvents<C>,
+    client: &OnlineClient<C>,
+    token_metadata: &TokenMetadata,
+    verbosity: Verbosity,
+    output_json: bool,
+) -> Result<(), ErrorVariant>
+where
+    C::AccountId: IntoVisitor,
+    C::Balance: Display + From<u128>,
+{
+    let display_events =
+        DisplayEvents::from_events::<C, C>(events, None, &client.metadata())?;
+    let output = if output_json {
+        display_events.to_json()?
+    } else {
+        display_events.display_events::<C>(verbosity, token_metadata)?
+    };
+    println!("{output}");
+    Ok(())
+}
+
+/// Signs an unsigned extrinsic written by `--unsigned`, without connecting to a node.
+#[derive(Debug, clap::Args)]
+#[clap(name = "sign")]
+pub struct SignCommand {
+    /// Path to the unsigned extrinsic.
+    #[clap(value_parser)]
+    file: PathBuf,
+    /// Secret key URI for the account signing the extrinsic.
+    #[clap(name = "suri", long, short)]
+    suri: String,
+    /// Chain config of the chain the extrinsic was prepared for.
+    #[clap(name = "config", long, default_value = "Polkadot")]
+    config: String,
+    /// Path to write the signed extrinsic to.
+    ///
+    /// Defaults to the unsigned extrinsic's path with the extension `signed.json`.
+    #[clap(long, short, value_parser)]
+    output: Option<PathBuf>,
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+}
+
+impl SignCommand {
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.config.as_str())
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        let signer = C::Signer::from_str(&self.suri)
+            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let unsigned: UnsignedExtrinsic = read_json(&self.file)?;
+        let verbosity: Verbosity = TryFrom::try_from(&self.verbosity)?;
+        if verbosity.is_verbose() {
+            name_value_println!("Call", unsigned.description);
+            name_value_println!("Weight", unsigned.estimated_weight.to_string());
+            if let Some(dry_run) = &unsigned.dry_run {
+                name_value_println!("Dry-run", serde_json::to_string_pretty(dry_run)?);
+            }
+        }
+
+        let signed = unsigned.sign::<C, _>(&signer)?;
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| self.file.with_extension("signed.json"));
+        write_json(&output, &signed)?;
+        println!(
+            "{} signed extrinsic to {}",
+            "Written".green().bold(),
+            output.display()
+        );
+        Ok(())
+    }
+}
+
+/// Submits an extrinsic signed by `cargo contract sign`.
+#[derive(Debug, clap::Args)]
+#[clap(name = "submit")]
+pub struct SubmitCommand {
+    /// Path to the signed extrinsic.
+    #[clap(value_parser)]
+    file: PathBuf,
+    #[clap(flatten)]
+    chain_cli_opts: CLIChainOpts,
+    #[clap(flatten)]
+    verbosity: VerbosityFlags,
+    /// Export the events in JSON format.
+    #[clap(long, conflicts_with = "verbose")]
+    output_json: bool,
+}
+
+impl SubmitCommand {
+    /// Returns whether to export the call output in JSON format.
+    pub fn output_json(&self) -> bool {
+        self.output_json
+    }
+
+    pub async fn handle(&self) -> Result<(), ErrorVariant> {
+        call_with_config!(self, run, self.chain_cli_opts.chain().config())
+    }
+
+    async fn run<C: Config + Environment + SignerConfig<C>>(
+        &self,
+    ) -> Result<(), ErrorVariant>
+    where
+        C::AccountId: IntoVisitor,
+        C::Balance: Display + From<u128>,
+    {
+        let signed: SignedExtrinsic = read_json(&self.file)?;
+        let url = self.chain_cli_opts.chain().url();
+        let client = OnlineClient::<C>::from_url(url_to_string(&url)).await?;
+        let token_metadata = TokenMetadata::query::<C>(&url).await?;
+
+        let events = signed.submit(&client).await?;
+        display_events(
+            &events,
+            &client,
+            &token_metadata,
+            TryFrom::try_from(&self.verbosity)?,
+            self.output_json,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::Pair as _;
+    use sp_weights::Weight;
+
+    const METADATA: &[u8] = include_bytes!(
+        "../../../contract-extrinsics/src/test_runtime_api/metadata_v15.scale"
+    );
+    const CALL_DATA: [u8; 3] = [9, 6, 0];
+
+    fn to_hex(bytes: &[u8]) -> String {
+        format!("0x{}", hex::encode(bytes))
+    }
+
+    /// An extrinsic as prepared by `--unsigned`, to be signed by `suri`.
+    fn unsigned(suri: &str) -> UnsignedExtrinsic {
+        let signer = sp_core::sr25519::Pair::from_string(suri, None).unwrap();
+        UnsignedExtrinsic {
+            description: "Contracts::call".into(),
+            signer: to_hex(&signer.public().0),
+            call_data: to_hex(&CALL_DATA),
+            estimated_weight: Weight::from_parts(1_000, 10),
+            dry_run: Some(serde_json::json!({ "reverted": false })),
+            nonce: 0,
+            genesis_hash: to_hex(&[2; 32]),
+            spec_version: 1,
+            transaction_version: 1,
+            metadata: to_hex(METADATA),
+        }
+    }
+
+    fn sign_command(file: &Path, suri: &str, output: Option<PathBuf>) -> SignCommand {
+        SignCommand {
+            file: file.to_path_buf(),
+            suri: suri.into(),
+            config: "Polkadot".into(),
+            output,
+            verbosity: Default::default(),
+        }
+    }
+
+    #[test]
+    fn unsigned_extrinsic_roundtrips_through_its_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("call.json");
+        let unsigned = unsigned("//Alice");
+
+        write_json(&path, &unsigned).unwrap();
+
+        assert_eq!(read_json::<UnsignedExtrinsic>(&path).unwrap(), unsigned);
+        assert!(read_json::<SignedExtrinsic>(&path).is_err());
+        assert!(
+            read_json::<UnsignedExtrinsic>(&dir.path().join("missing.json")).is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn signed_extrinsic_is_written_next_to_the_unsigned_one() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("call.json");
+        write_json(&path, &unsigned("//Alice")).unwrap();
+
+        sign_command(&path, "//Alice", None).handle().await.unwrap();
+
+        // This is the file `cargo contract submit` reads.
+        let signed: SignedExtrinsic =
+            read_json(&dir.path().join("call.signed.json")).unwrap();
+        assert_eq!(signed.description, "Contracts::call");
+        assert_eq!(signed.genesis_hash, to_hex(&[2; 32]));
+        let extrinsic = hex::decode(signed.extrinsic.trim_start_matches("0x")).unwrap();
+        assert!(extrinsic.ends_with(&CALL_DATA));
+    }
+
+    #[tokio::test]
+    async fn signed_extrinsic_is_written_to_the_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("call.json");
+        let output = dir.path().join("signed.json");
+        write_json(&path, &unsigned("//Alice")).unwrap();
+
+        sign_command(&path, "//Alice", Some(output.clone()))
+            .handle()
+            .await
+            .unwrap();
+
+        assert!(read_json::<SignedExtrinsic>(&output).is_ok());
+        assert!(!dir.path().join("call.signed.json").exists());
+    }
+
+    #[tokio::test]
+    async fn extrinsic_is_only_signed_by_its_signer() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("call.json");
+        write_json(&path, &unsigned("//Alice")).unwrap();
+
+        assert!(sign_command(&path, "//Bob", None).handle().await.is_err());
+        assert!(!dir.path().join("call.signed.json").exists());
+    }
+}
@@ -120,9 +120,9 @@ impl RemoveCommand {
         <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
         <C as Config>::Hash: IntoVisitor + EncodeAsType + From<[u8; 32]>,
+        C::Address: From<C::AccountId>,
     {
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
         let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
         let storage_deposit_limit = self
@@ -151,6 +151,22 @@ impl RemoveCommand {
             .code_hash(code_hash)
             .done()
             .await?;
+        let offline_cli_opts = &self.extrinsic_cli_opts.offline_cli_opts;
+        if offline_cli_opts.is_set() {
+            let unsigned = remove_exec
+                .remove_code_unsigned(&offline_cli_opts.wrappers()?)
+                .await?;
+            return offline_cli_opts
+                .dispatch(
+                    unsigned,
+                    remove_exec.opts().signer(),
+                    remove_exec.client(),
+                    &token_metadata,
+                    self.extrinsic_cli_opts.verbosity()?,
+                    self.output_json(),
+                )
+                .await;
+        }
         let remove_result = remove_exec.remove_code().await?;
         let display_events = DisplayEvents::from_events::<C, C>(
             &remove_result.events,
@@ -118,9 +118,9 @@ impl UploadCommand {
         <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
             From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
         <C as Config>::Hash: IntoVisitor + EncodeAsType + From<[u8; 32]>,
+        C::Address: From<C::AccountId>,
     {
-        let signer = C::Signer::from_str(&self.extrinsic_cli_opts.suri)
-            .map_err(|_| anyhow::anyhow!("Failed to parse suri option"))?;
+        let signer = self.extrinsic_cli_opts.signer::<C>()?;
         let chain = self.extrinsic_cli_opts.chain_cli_opts.chain();
         let token_metadata = TokenMetadata::query::<C>(&chain.url()).await?;
         let storage_deposit_limit = self
@@ -174,6 +174,28 @@ impl UploadCommand {
                     prompt_confirm_unverifiable_upload(&chain.to_string())?
                 }
             }
+            let offline_cli_opts = &self.extrinsic_cli_opts.offline_cli_opts;
+            if offline_cli_opts.is_set() {
+                let dry_run = if self.extrinsic_cli_opts.skip_dry_run {
+                    None
+                } else {
+                    let result = upload_exec.upload_code_rpc().await?;
+                    Some(serde_json::json!({ "result": format!("{result:?}") }))
+                };
+                let unsigned = upload_exec
+                    .upload_code_unsigned(&offline_cli_opts.wrappers()?, dry_run)
+                    .await?;
+                return offline_cli_opts
+                    .dispatch(
+                        unsigned,
+                        upload_exec.opts().signer(),
+                        upload_exec.client(),
+                        &token_metadata,
+                        self.extrinsic_cli_opts.verbosity()?,
+                        self.output_json(),
+                    )
+                    .await;
+            }
             let upload_result = upload_exec.upload_code().await?;
             let display_events = DisplayEvents::from_events::<C, C>(
                 &upload_result.events,
@@ -206,7 +228,7 @@ impl UploadCommand {
                 return Err(anyhow::anyhow!(
                     "This contract has already been uploaded with code hash: 0x{code_hash}"
                 )
-                .into())
+                .into());
             }
         }
         Ok(())
@@ -43,14 +43,19 @@ use self::cmd::{
     BuildCommand,
     CallCommand,
     CheckCommand,
+    CheckUpgradeCommand,
     DecodeCommand,
+    DeployCommand,
     ErrorVariant,
+    GenerateBindingsCommand,
     GenerateSchemaCommand,
     InfoCommand,
     InstantiateCommand,
     RemoveCommand,
     RpcCommand,
+    SignCommand,
     StorageCommand,
+    SubmitCommand,
     UploadCommand,
     VerifyCommand,
     VerifySchemaCommand,
@@ -151,6 +156,9 @@ enum Command {
     /// Call a contract
     #[clap(name = "call")]
     Call(CallCommand),
+    /// Deploy a system of contracts described by a deployment plan
+    #[clap(name = "deploy")]
+    Deploy(DeployCommand),
     /// Encodes a contracts input calls and their arguments
     #[clap(name = "encode")]
     Encode(EncodeCommand),
@@ -160,6 +168,12 @@ enum Command {
     /// Remove contract code
     #[clap(name = "remove")]
     Remove(RemoveCommand),
+    /// Sign an unsigned extrinsic offline
+    #[clap(name = "sign")]
+    Sign(SignCommand),
+    /// Submit a signed extrinsic
+    #[clap(name = "submit")]
+    Submit(SubmitCommand),
     /// Display information about a contract
     #[clap(name = "info")]
     Info(InfoCommand),
@@ -170,6 +184,13 @@ enum Command {
     /// workspace.
     #[clap(name = "verify")]
     Verify(VerifyCommand),
+    /// Checks whether a contract can be upgraded to a new version without corrupting
+    /// its storage or breaking its callers.
+    #[clap(name = "check-upgrade")]
+    CheckUpgrade(CheckUpgradeCommand),
+    /// Generates typed Rust or TypeScript client bindings from the contract metadata.
+    #[clap(name = "generate-bindings")]
+    GenerateBindings(GenerateBindingsCommand),
     /// Generates schema from the current metadata specification.
     #[clap(name = "generate-schema")]
     GenerateSchema(GenerateSchemaCommand),
@@ -237,6 +258,14 @@ fn exec(cmd: Command) -> Result<()> {
                     .map_err(|err| map_extrinsic_err(err, instantiate.output_json()))
             })
         }
+        Command::Deploy(deploy) => {
+            runtime.block_on(async {
+                deploy
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, deploy.output_json()))
+            })
+        }
         Command::Call(call) => {
             runtime.block_on(async {
                 call.handle()
@@ -254,6 +283,17 @@ fn exec(cmd: Command) -> Result<()> {
                     .map_err(|err| map_extrinsic_err(err, remove.output_json()))
             })
         }
+        Command::Sign(sign) => {
+            runtime.block_on(async { sign.handle().await.map_err(format_err) })
+        }
+        Command::Submit(submit) => {
+            runtime.block_on(async {
+                submit
+                    .handle()
+                    .await
+                    .map_err(|err| map_extrinsic_err(err, submit.output_json()))
+            })
+        }
         Command::Info(info) => {
             runtime.block_on(async { info.handle().await.map_err(format_err) })
         }
@@ -270,6 +310,31 @@ fn exec(cmd: Command) -> Result<()> {
             }
             Ok(())
         }
+        Command::CheckUpgrade(check) => {
+            let result = check.run().map_err(format_err)?;
+
+            if result.output_json {
+                println!("{}", result.serialize_json()?)
+            } else if result.verbosity.is_verbose() {
+                println!("{}", result.display())
+            }
+            match result.breaking_changes() {
+                0 => Ok(()),
+                breaking => {
+                    Err(anyhow!(
+                        "{} {breaking} breaking change(s) found",
+                        "ERROR:".bright_red().bold()
+                    ))
+                }
+            }
+        }
+        Command::GenerateBindings(generate) => {
+            let result = generate.run().map_err(format_err)?;
+            if result.verbosity.is_verbose() {
+                println!("{}", result.display())
+            }
+            Ok(())
+        }
         Command::GenerateSchema(generate) => {
             let result = generate.run().map_err(format_err)?;
             println!("{}", result);
@@ -36,11 +36,15 @@
 // SOFTWARE.
 
 use super::{
+    dispatch_origin,
     pallet_contracts_primitives::ContractExecResult,
     state_call,
     submit_extrinsic,
+    trace::CallTrace,
+    CallWrapper,
     ContractMessageTranscoder,
     ErrorVariant,
+    UnsignedExtrinsic,
 };
 use crate::{
     check_env_types,
@@ -53,7 +57,10 @@ use anyhow::{
     Result,
 };
 use ink_env::Environment;
-use scale::Encode;
+use scale::{
+    Decode,
+    Encode,
+};
 use sp_weights::Weight;
 
 use subxt::{
@@ -80,10 +87,13 @@ pub struct CallCommandBuilder<C: Config, E: Environment, Signer: Clone> {
     contract: C::AccountId,
     message: String,
     args: Vec<String>,
+    call_data: Option<Vec<u8>>,
     extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
     gas_limit: Option<u64>,
     proof_size: Option<u64>,
     value: E::Balance,
+    wrappers: Vec<CallWrapper<C::AccountId>>,
+    origin: Option<C::AccountId>,
 }
 
 impl<C: Config, E: Environment, Signer> CallCommandBuilder<C, E, Signer>
@@ -101,10 +111,13 @@ where
             contract,
             message: message.to_string(),
             args: Vec::new(),
+            call_data: None,
             extrinsic_opts,
             gas_limit: None,
             proof_size: None,
             value: Default::default(),
+            wrappers: Vec::new(),
+            origin: None,
         }
     }
 
@@ -115,6 +128,15 @@ where
         this
     }
 
+    /// Sets already encoded call data, e.g. produced by typed contract bindings.
+    ///
+    /// The message arguments are not transcoded when the call data is set.
+    pub fn call_data(self, call_data: Vec<u8>) -> Self {
+        let mut this = self;
+        this.call_data = Some(call_data);
+        this
+    }
+
     /// Sets the maximum amount of gas to be used for this command.
     pub fn gas_limit(self, gas_limit: Option<u64>) -> Self {
         let mut this = self;
@@ -136,6 +158,21 @@ where
         this
     }
 
+    /// Sets the multisig and proxy calls wrapping the contract call, innermost first.
+    ///
+    /// Dry runs and gas estimation are then performed from the account dispatching the
+    /// contract call rather than from the signer.
+    pub fn wrappers(self, wrappers: Vec<CallWrapper<C::AccountId>>) -> Result<Self>
+    where
+        C::AccountId: Decode,
+    {
+        let mut this = self;
+        let signer = this.extrinsic_opts.signer().account_id();
+        this.origin = Some(dispatch_origin(&signer, &wrappers)?);
+        this.wrappers = wrappers;
+        Ok(this)
+    }
+
     /// Preprocesses contract artifacts and options for subsequent contract calls.
     ///
     /// This function prepares the necessary data for making a contract call based on the
@@ -149,7 +186,10 @@ where
         let artifacts = self.extrinsic_opts.contract_artifacts()?;
         let transcoder = artifacts.contract_transcoder()?;
 
-        let call_data = transcoder.encode(&self.message, &self.args)?;
+        let call_data = match self.call_data {
+            Some(call_data) => call_data,
+            None => transcoder.encode(&self.message, &self.args)?,
+        };
         tracing::debug!("Message data: {:?}", hex::encode(&call_data));
 
         let url = self.extrinsic_opts.url();
@@ -158,6 +198,9 @@ where
         let rpc = LegacyRpcMethods::new(rpc);
         check_env_types(&client, &transcoder, self.extrinsic_opts.verbosity())?;
 
+        let origin = self
+            .origin
+            .unwrap_or_else(|| self.extrinsic_opts.signer().account_id());
         Ok(CallExec {
             contract: self.contract,
             message: self.message.clone(),
@@ -170,6 +213,8 @@ where
             client,
             transcoder,
             call_data,
+            wrappers: self.wrappers,
+            origin,
         })
     }
 }
@@ -186,6 +231,9 @@ pub struct CallExec<C: Config, E: Environment, Signer: Clone> {
     client: OnlineClient<C>,
     transcoder: ContractMessageTranscoder,
     call_data: Vec<u8>,
+    wrappers: Vec<CallWrapper<C::AccountId>>,
+    /// The account dispatching the contract call, the signer unless it is wrapped.
+    origin: C::AccountId,
 }
 
 impl<C: Config, E: Environment, Signer> CallExec<C, E, Signer>
@@ -208,7 +256,7 @@ where
     pub async fn call_dry_run(&self) -> Result<ContractExecResult<E::Balance>> {
         let storage_deposit_limit = self.opts.storage_deposit_limit();
         let call_request = CallRequest {
-            origin: self.opts.signer().account_id(),
+            origin: self.origin.clone(),
             dest: self.contract.clone(),
             value: self.value,
             gas_limit: None,
@@ -218,6 +266,31 @@ where
         state_call(&self.rpc, "ContractsApi_call", call_request).await
     }
 
+    /// Simulates a contract call like [`Self::call_dry_run`], recording the tree of
+    /// nested calls, delegate calls and instantiations it performs.
+    ///
+    /// Requires the node to expose the `ContractsTraceApi` runtime API. Returns `None`
+    /// if no contract code was executed.
+    pub async fn call_trace(
+        &self,
+    ) -> Result<Option<CallTrace<C::AccountId, E::Balance, C::Hash>>>
+    where
+        C::AccountId: Decode,
+        E::Balance: Decode,
+        C::Hash: Decode,
+    {
+        let storage_deposit_limit = self.opts.storage_deposit_limit();
+        let call_request = CallRequest {
+            origin: self.origin.clone(),
+            dest: self.contract.clone(),
+            value: self.value,
+            gas_limit: None,
+            storage_deposit_limit,
+            input_data: self.call_data.clone(),
+        };
+        state_call(&self.rpc, "ContractsTraceApi_trace_call", call_request).await
+    }
+
     /// Calls a contract on the blockchain with a specified gas limit.
     ///
     /// This function facilitates the process of invoking a contract, specifying the gas
@@ -270,6 +343,41 @@ where
         Ok(result)
     }
 
+    /// Prepares the contract call to be signed offline, dispatched through the
+    /// multisig and proxy calls set by [`CallCommandBuilder::wrappers`].
+    ///
+    /// Returns the [`UnsignedExtrinsic`] to be signed by the signer of the extrinsic
+    /// options, including `dry_run` for review before signing.
+    pub async fn call_unsigned(
+        &self,
+        gas_limit: Option<Weight>,
+        dry_run: Option<serde_json::Value>,
+    ) -> Result<UnsignedExtrinsic> {
+        let gas_limit = match gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => self.estimate_gas().await?,
+        };
+        let call = Call::new(
+            self.contract.clone().into(),
+            self.value,
+            gas_limit,
+            self.opts.storage_deposit_limit(),
+            self.call_data.clone(),
+        )
+        .build();
+
+        UnsignedExtrinsic::prepare(
+            &self.client,
+            &self.rpc,
+            &call,
+            "Contracts::call",
+            &self.opts.signer().account_id(),
+            &self.wrappers,
+            dry_run,
+        )
+        .await
+    }
+
     /// Estimates the gas required for a contract call without modifying the blockchain.
     ///
     /// This function provides a gas estimation for contract calls, considering the
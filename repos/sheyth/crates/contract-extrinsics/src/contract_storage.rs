@@ -330,6 +330,151 @@ impl Display for StorageVec {
     }
 }
 
+#[derive(Serialize, Debug)]
+pub struct IterableMapping {
+    #[serde(flatten)]
+    root: RootKeyEntry,
+    len: u32,
+    map: Vec<(Value, Value)>,
+}
+
+impl IterableMapping {
+    /// Create new `IterableMapping`.
+    pub fn new(
+        root: RootKeyEntry,
+        len: u32,
+        value: Vec<(Value, Value)>,
+    ) -> IterableMapping {
+        IterableMapping {
+            root,
+            len,
+            map: value,
+        }
+    }
+
+    /// Return the root key entry of the `IterableMapping`.
+    pub fn root(&self) -> &RootKeyEntry {
+        &self.root
+    }
+
+    /// Return the len of the `IterableMapping`.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Iterate all key-value pairs in insertion order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &(Value, Value)> {
+        self.map.iter()
+    }
+}
+
+impl Display for IterableMapping {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let len = self.map.len();
+        for (i, e) in self.map.iter().enumerate() {
+            write!(f, "IterableMapping [{}] {{ {} => {} }}", self.len, e.0, e.1)?;
+            if i + 1 < len {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct StorageSet {
+    #[serde(flatten)]
+    root: RootKeyEntry,
+    len: u32,
+    set: Vec<Value>,
+}
+
+impl StorageSet {
+    /// Create new `StorageSet`.
+    pub fn new(root: RootKeyEntry, len: u32, value: Vec<Value>) -> StorageSet {
+        StorageSet {
+            root,
+            len,
+            set: value,
+        }
+    }
+
+    /// Return the root key entry of the `StorageSet`.
+    pub fn root(&self) -> &RootKeyEntry {
+        &self.root
+    }
+
+    /// Return the len of the `StorageSet`.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Return the iterator over the `StorageSet` values in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.set.iter()
+    }
+}
+
+impl Display for StorageSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let len = self.set.len();
+        for (i, v) in self.set.iter().enumerate() {
+            write!(f, "StorageSet [{}] {{ {} }}", self.len, v)?;
+            if i + 1 < len {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct StorageDeque {
+    #[serde(flatten)]
+    root: RootKeyEntry,
+    len: u32,
+    deque: Vec<Value>,
+}
+
+impl StorageDeque {
+    /// Create new `StorageDeque`.
+    pub fn new(root: RootKeyEntry, len: u32, value: Vec<Value>) -> StorageDeque {
+        StorageDeque {
+            root,
+            len,
+            deque: value,
+        }
+    }
+
+    /// Return the root key entry of the `StorageDeque`.
+    pub fn root(&self) -> &RootKeyEntry {
+        &self.root
+    }
+
+    /// Return the len of the `StorageDeque`.
+    pub fn len(&self) -> u32 {
+        self.len
+    }
+
+    /// Return the iterator over the `StorageDeque` values, starting at the front.
+    pub fn values(&self) -> impl Iterator<Item = &Value> {
+        self.deque.iter()
+    }
+}
+
+impl Display for StorageDeque {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let len = self.deque.len();
+        for (i, v) in self.deque.iter().enumerate() {
+            write!(f, "StorageDeque [{}] {{ [{}] => {} }}", self.len, i, v)?;
+            if i + 1 < len {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Serialize, Debug)]
 pub struct Packed {
     #[serde(flatten)]
@@ -366,6 +511,9 @@ pub enum ContractStorageCell {
     Mapping(Mapping),
     Lazy(Lazy),
     StorageVec(StorageVec),
+    IterableMapping(IterableMapping),
+    StorageSet(StorageSet),
+    StorageDeque(StorageDeque),
     Packed(Packed),
 }
 
@@ -375,6 +523,9 @@ impl ContractStorageCell {
             Self::Mapping(mapping) => mapping.root(),
             Self::Lazy(lazy) => lazy.root(),
             Self::StorageVec(storage_vec) => storage_vec.root(),
+            Self::IterableMapping(mapping) => mapping.root(),
+            Self::StorageSet(set) => set.root(),
+            Self::StorageDeque(deque) => deque.root(),
             Self::Packed(packed) => packed.root(),
         }
     }
@@ -401,6 +552,9 @@ impl Display for ContractStorageCell {
             Self::Mapping(mapping) => mapping.fmt(f),
             Self::Lazy(lazy) => lazy.fmt(f),
             Self::StorageVec(storage_vec) => storage_vec.fmt(f),
+            Self::IterableMapping(mapping) => mapping.fmt(f),
+            Self::StorageSet(set) => set.fmt(f),
+            Self::StorageDeque(deque) => deque.fmt(f),
             Self::Packed(value) => value.fmt(f),
         }
     }
@@ -482,6 +636,48 @@ impl ContractStorageLayout {
                             root, len, value,
                         )))
                     }
+                    "ink_storage::lazy::iterable_mapping::IterableMapping" => {
+                        let key_type_id = Self::param_type_id(type_def, "K")
+                            .ok_or(anyhow!("Param `K` not found in type registry"))?;
+                        let value_type_id = Self::param_type_id(type_def, "V")
+                            .ok_or(anyhow!("Param `V` not found in type registry"))?;
+                        let (len, entries) = Self::decode_to_linked_entries(
+                            data,
+                            key_type_id,
+                            Some(value_type_id),
+                            decoder,
+                        )?;
+                        let value = entries
+                            .into_iter()
+                            .filter_map(|(k, v)| Some((k, v?)))
+                            .collect();
+                        Ok(ContractStorageCell::IterableMapping(IterableMapping::new(
+                            root, len, value,
+                        )))
+                    }
+                    "ink_storage::lazy::set::StorageSet" => {
+                        let value_type_id = Self::param_type_id(type_def, "T")
+                            .ok_or(anyhow!("Param `T` not found in type registry"))?;
+                        let (len, entries) = Self::decode_to_linked_entries(
+                            data,
+                            value_type_id,
+                            None,
+                            decoder,
+                        )?;
+                        let value = entries.into_iter().map(|(k, _)| k).collect();
+                        Ok(ContractStorageCell::StorageSet(StorageSet::new(
+                            root, len, value,
+                        )))
+                    }
+                    "ink_storage::lazy::deque::StorageDeque" => {
+                        let value_type_id = Self::param_type_id(type_def, "V")
+                            .ok_or(anyhow!("Param `V` not found in type registry"))?;
+                        let (len, value) =
+                            Self::decode_to_deque(data, value_type_id, decoder)?;
+                        Ok(ContractStorageCell::StorageDeque(StorageDeque::new(
+                            root, len, value,
+                        )))
+                    }
                     "ink_storage::lazy::Lazy" => {
                         let value_type_id = Self::param_type_id(type_def, "V")
                             .ok_or(anyhow!("Param `V` not found in type registry"))?;
@@ -541,6 +737,97 @@ impl ContractStorageLayout {
             .collect()
     }
 
+    /// Decode the entries of an `IterableMapping` or a `StorageSet` in insertion order.
+    ///
+    /// The root cell holds `(len, first_key, last_key)` and every entry holds
+    /// `(value, prev_key, next_key)`. The entries are visited by following the encoded
+    /// keys from the first to the last one. Entries of a `StorageSet` carry no value.
+    fn decode_to_linked_entries(
+        data: Vec<(Option<Bytes>, Bytes)>,
+        key_type_id: u32,
+        value_type_id: Option<u32>,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<(u32, Vec<(Value, Option<Value>)>)> {
+        let mut header = None;
+        let mut entries = BTreeMap::new();
+        for (k, v) in data {
+            match k {
+                Some(k) => {
+                    entries.insert(k.0, v.0);
+                }
+                None => header = Some(v.0),
+            }
+        }
+        let header = header.ok_or(anyhow!("Header of the linked entries not found"))?;
+        let input = &mut &header[..];
+        let len = u32::decode(input)?;
+        let mut next = Self::decode_raw_key(input, key_type_id, decoder)?;
+
+        let mut decoded = Vec::new();
+        while let Some(raw_key) = next {
+            let raw_entry = entries.remove(&raw_key).ok_or(anyhow!(
+                "Linked entry 0x{} not found",
+                hex::encode(&raw_key)
+            ))?;
+            let key = decoder.decode(key_type_id, &mut &raw_key[..])?;
+            let input = &mut &raw_entry[..];
+            let value = value_type_id
+                .map(|type_id| decoder.decode(type_id, input))
+                .transpose()?;
+            // Skip the key of the previous entry.
+            Self::decode_raw_key(input, key_type_id, decoder)?;
+            next = Self::decode_raw_key(input, key_type_id, decoder)?;
+            decoded.push((key, value));
+        }
+        Ok((len, decoded))
+    }
+
+    /// Decode an `Option` of a key, returning the raw encoded key.
+    fn decode_raw_key(
+        input: &mut &[u8],
+        key_type_id: u32,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<Option<Vec<u8>>> {
+        match u8::decode(input)? {
+            0 => Ok(None),
+            1 => {
+                let start = *input;
+                decoder.decode(key_type_id, input)?;
+                Ok(Some(start[..start.len() - input.len()].to_vec()))
+            }
+            _ => Err(anyhow!("Invalid Option discriminant of a linked key")),
+        }
+    }
+
+    /// Decode the elements of a `StorageDeque` starting at the front.
+    ///
+    /// The root cell holds `(front_slot, len)`, the elements are stored under their
+    /// slots which wrap around at `u32::MAX`.
+    fn decode_to_deque(
+        data: Vec<(Option<Bytes>, Bytes)>,
+        value_type_id: u32,
+        decoder: &ContractMessageTranscoder,
+    ) -> Result<(u32, Vec<Value>)> {
+        let raw_bounds = data
+            .iter()
+            .find(|(k, _)| k.is_none())
+            .ok_or(anyhow!("Length of the StorageDeque not found"))?
+            .1
+            .clone();
+        let (front, len) = <(u32, u32)>::decode(&mut raw_bounds.as_bytes_ref())?;
+        let mut elements = data
+            .into_iter()
+            .filter_map(|(k, v)| Some((k?, v)))
+            .map(|(k, v)| {
+                let slot = u32::decode(&mut k.as_bytes_ref())?;
+                let value = decoder.decode(value_type_id, &mut v.as_bytes_ref())?;
+                Ok((slot.wrapping_sub(front), value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        elements.sort_by_key(|(index, _)| *index);
+        Ok((len, elements.into_iter().map(|(_, v)| v).collect()))
+    }
+
     fn collect_root_key_entries(
         layout: &Layout<PortableForm>,
         path: &mut Vec<String>,
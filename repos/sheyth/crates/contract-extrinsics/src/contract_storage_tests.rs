@@ -65,8 +65,11 @@ use ink::{
             Storable,
             StorageLayout,
         },
+        IterableMapping,
         Lazy,
         Mapping,
+        StorageDeque,
+        StorageSet,
     },
     ConstructorResult,
     MessageResult,
@@ -257,3 +260,207 @@ fn storage_decode_mapping_type_works() {
     );
     assert_eq!(cell.root_key(), hex::encode(lazy_type_root_encoded));
 }
+
+#[test]
+fn storage_decode_iterable_mapping_type_works() {
+    let root_key_encoded = Encode::encode(&ROOT_KEY);
+    let lazy_type_root_encoded = Encode::encode(&LAZY_TYPE_ROOT_KEY);
+    #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+    struct Data {
+        a: IterableMapping<u8, u8, ManualKey<LAZY_TYPE_ROOT_KEY>>,
+    }
+
+    let Struct(data_layout) = <Data as StorageLayout>::layout(&ROOT_KEY) else {
+        panic!("Layout shall be created");
+    };
+    let storage_layout: Layout = RootLayout::new(
+        LayoutKey::from(ROOT_KEY),
+        data_layout,
+        scale_info::meta_type::<Data>(),
+    )
+    .into();
+
+    let metadata = InkProject::new(storage_layout, contract_default_spec());
+    let decoder = ContractMessageTranscoder::new(metadata);
+
+    let value = Data {
+        a: IterableMapping::new(),
+    };
+    // Cannot be set on struct directly because it issues storage calls.
+    // The keys are inserted in reverse order to check that insertion order is kept.
+    let header = (2u32, Some(4u8), Some(2u8));
+    let first = (4u8, (8u8, None::<u8>, Some(2u8)));
+    let second = (2u8, (6u8, Some(4u8), None::<u8>));
+
+    let key = [BASE_KEY_RAW.to_vec(), root_key_encoded.clone()].concat();
+    let header_key = [BASE_KEY_RAW.to_vec(), lazy_type_root_encoded.clone()].concat();
+    let entry_key = |k: u8| {
+        [
+            BASE_KEY_RAW.to_vec(),
+            lazy_type_root_encoded.clone(),
+            Encode::encode(&k),
+        ]
+        .concat()
+    };
+
+    let mut map = BTreeMap::new();
+    map.insert(Bytes::from(key), encode_storage_value(&value));
+    map.insert(Bytes::from(header_key), encode_storage_value(&header));
+    map.insert(
+        Bytes::from(entry_key(first.0)),
+        encode_storage_value(&first.1),
+    );
+    map.insert(
+        Bytes::from(entry_key(second.0)),
+        encode_storage_value(&second.1),
+    );
+
+    let data = ContractStorageData::new(map);
+    let layout = ContractStorageLayout::new(data, &decoder)
+        .expect("Contract storage layout shall be created");
+    let mut iter = layout.iter();
+    let cell = iter.next().expect("Root cell shall be in layout");
+    assert_eq!(cell.to_string(), "Data { a: IterableMapping }".to_string());
+
+    let cell = iter
+        .next()
+        .expect("IterableMapping type cell shall be in layout");
+    assert_eq!(
+        cell.to_string(),
+        "IterableMapping [2] { 4 => 8 }\nIterableMapping [2] { 2 => 6 }".to_string()
+    );
+    assert_eq!(cell.root_key(), hex::encode(lazy_type_root_encoded));
+}
+
+#[test]
+fn storage_decode_storage_set_type_works() {
+    let root_key_encoded = Encode::encode(&ROOT_KEY);
+    let lazy_type_root_encoded = Encode::encode(&LAZY_TYPE_ROOT_KEY);
+    #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+    struct Data {
+        a: StorageSet<u8, ManualKey<LAZY_TYPE_ROOT_KEY>>,
+    }
+
+    let Struct(data_layout) = <Data as StorageLayout>::layout(&ROOT_KEY) else {
+        panic!("Layout shall be created");
+    };
+    let storage_layout: Layout = RootLayout::new(
+        LayoutKey::from(ROOT_KEY),
+        data_layout,
+        scale_info::meta_type::<Data>(),
+    )
+    .into();
+
+    let metadata = InkProject::new(storage_layout, contract_default_spec());
+    let decoder = ContractMessageTranscoder::new(metadata);
+
+    let value = Data {
+        a: StorageSet::new(),
+    };
+    // Cannot be set on struct directly because it issues storage calls
+    let header = (2u32, Some(5u8), Some(3u8));
+    let first = (5u8, ((), None::<u8>, Some(3u8)));
+    let second = (3u8, ((), Some(5u8), None::<u8>));
+
+    let key = [BASE_KEY_RAW.to_vec(), root_key_encoded.clone()].concat();
+    let header_key = [BASE_KEY_RAW.to_vec(), lazy_type_root_encoded.clone()].concat();
+    let entry_key = |k: u8| {
+        [
+            BASE_KEY_RAW.to_vec(),
+            lazy_type_root_encoded.clone(),
+            Encode::encode(&k),
+        ]
+        .concat()
+    };
+
+    let mut map = BTreeMap::new();
+    map.insert(Bytes::from(key), encode_storage_value(&value));
+    map.insert(Bytes::from(header_key), encode_storage_value(&header));
+    map.insert(
+        Bytes::from(entry_key(first.0)),
+        encode_storage_value(&first.1),
+    );
+    map.insert(
+        Bytes::from(entry_key(second.0)),
+        encode_storage_value(&second.1),
+    );
+
+    let data = ContractStorageData::new(map);
+    let layout = ContractStorageLayout::new(data, &decoder)
+        .expect("Contract storage layout shall be created");
+    let mut iter = layout.iter();
+    let cell = iter.next().expect("Root cell shall be in layout");
+    assert_eq!(cell.to_string(), "Data { a: StorageSet }".to_string());
+
+    let cell = iter
+        .next()
+        .expect("StorageSet type cell shall be in layout");
+    assert_eq!(
+        cell.to_string(),
+        "StorageSet [2] { 5 }\nStorageSet [2] { 3 }".to_string()
+    );
+    assert_eq!(cell.root_key(), hex::encode(lazy_type_root_encoded));
+}
+
+#[test]
+fn storage_decode_storage_deque_type_works() {
+    let root_key_encoded = Encode::encode(&ROOT_KEY);
+    let lazy_type_root_encoded = Encode::encode(&LAZY_TYPE_ROOT_KEY);
+    #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+    struct Data {
+        a: StorageDeque<u8, ManualKey<LAZY_TYPE_ROOT_KEY>>,
+    }
+
+    let Struct(data_layout) = <Data as StorageLayout>::layout(&ROOT_KEY) else {
+        panic!("Layout shall be created");
+    };
+    let storage_layout: Layout = RootLayout::new(
+        LayoutKey::from(ROOT_KEY),
+        data_layout,
+        scale_info::meta_type::<Data>(),
+    )
+    .into();
+
+    let metadata = InkProject::new(storage_layout, contract_default_spec());
+    let decoder = ContractMessageTranscoder::new(metadata);
+
+    let value = Data {
+        a: StorageDeque::new(),
+    };
+    // Cannot be set on struct directly because it issues storage calls.
+    // The front element was pushed to the front, so its slot wrapped around.
+    let bounds = (u32::MAX, 2u32);
+    let elements = [(u32::MAX, 7u8), (0u32, 9u8)];
+
+    let key = [BASE_KEY_RAW.to_vec(), root_key_encoded.clone()].concat();
+    let bounds_key = [BASE_KEY_RAW.to_vec(), lazy_type_root_encoded.clone()].concat();
+
+    let mut map = BTreeMap::new();
+    map.insert(Bytes::from(key), encode_storage_value(&value));
+    map.insert(Bytes::from(bounds_key), encode_storage_value(&bounds));
+    for (slot, element) in elements {
+        let element_key = [
+            BASE_KEY_RAW.to_vec(),
+            lazy_type_root_encoded.clone(),
+            Encode::encode(&slot),
+        ]
+        .concat();
+        map.insert(Bytes::from(element_key), encode_storage_value(&element));
+    }
+
+    let data = ContractStorageData::new(map);
+    let layout = ContractStorageLayout::new(data, &decoder)
+        .expect("Contract storage layout shall be created");
+    let mut iter = layout.iter();
+    let cell = iter.next().expect("Root cell shall be in layout");
+    assert_eq!(cell.to_string(), "Data { a: StorageDeque }".to_string());
+
+    let cell = iter
+        .next()
+        .expect("StorageDeque type cell shall be in layout");
+    assert_eq!(
+        cell.to_string(),
+        "StorageDeque [2] { [0] => 7 }\nStorageDeque [2] { [1] => 9 }".to_string()
+    );
+    assert_eq!(cell.root_key(), hex::encode(lazy_type_root_encoded));
+}
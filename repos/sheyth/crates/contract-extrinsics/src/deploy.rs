@@ -0,0 +1,1217 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use super::{
+    url_to_string,
+    BalanceVariant,
+    CallCommandBuilder,
+    ContractArtifacts,
+    ErrorVariant,
+    ExtrinsicOptsBuilder,
+    InstantiateCommandBuilder,
+    TokenMetadata,
+    UploadCommandBuilder,
+};
+use crate::extrinsic_opts::ExtrinsicOpts;
+use anyhow::{
+    anyhow,
+    bail,
+    Context,
+    Result,
+};
+use contract_build::{
+    util::decode_hex,
+    BuildMode,
+    Verbosity,
+};
+use ink_env::Environment;
+use scale::{
+    Decode,
+    Encode,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_core::Bytes;
+use std::{
+    collections::{
+        BTreeMap,
+        BTreeSet,
+    },
+    fmt::Display,
+    fs,
+    path::{
+        Path,
+        PathBuf,
+    },
+    str::FromStr,
+};
+use subxt::{
+    config::{
+        DefaultExtrinsicParams,
+        ExtrinsicParams,
+    },
+    ext::{
+        scale_decode::IntoVisitor,
+        scale_encode::EncodeAsType,
+        scale_value::Value,
+    },
+    tx,
+    Config,
+    OnlineClient,
+};
+use url::Url;
+
+/// A declarative description of a system of contracts to deploy, read from a TOML
+/// file.
+///
+/// Contracts are deployed in the order they are listed, so their arguments may refer
+/// to the outputs of the contracts listed before them as `${<name>.address}` and
+/// `${<name>.code_hash}`. The calls are made after all contracts are deployed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeployPlan {
+    /// The contracts to upload and instantiate.
+    #[serde(rename = "contract", default)]
+    pub contracts: Vec<PlanContract>,
+    /// The messages to call once the contracts are deployed.
+    #[serde(rename = "call", default)]
+    pub calls: Vec<PlanCall>,
+}
+
+/// A contract of a [`DeployPlan`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanContract {
+    /// The name other entries of the plan refer to the contract by.
+    pub name: String,
+    /// The path to the `Cargo.toml` of the contract, or to its `.contract` bundle.
+    pub path: PathBuf,
+    /// The profile to build the contract with.
+    #[serde(default)]
+    pub profile: BuildProfile,
+    /// The constructor to instantiate the contract with. The code is only uploaded if
+    /// it is not set.
+    pub constructor: Option<String>,
+    /// The constructor arguments.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The initial balance to transfer to the instantiated contract.
+    pub value: Option<String>,
+    /// The hex encoded salt used in the address derivation of the contract.
+    pub salt: Option<String>,
+}
+
+/// A message call of a [`DeployPlan`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlanCall {
+    /// The name of the contract to call.
+    pub contract: String,
+    /// The message to call.
+    pub message: String,
+    /// The message arguments.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// The balance to transfer to the contract with the call.
+    pub value: Option<String>,
+}
+
+/// The profile a contract of a [`DeployPlan`] is built with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildProfile {
+    #[default]
+    Release,
+    Debug,
+    Verifiable,
+}
+
+impl From<BuildProfile> for BuildMode {
+    fn from(profile: BuildProfile) -> Self {
+        match profile {
+            BuildProfile::Release => BuildMode::Release,
+            BuildProfile::Debug => BuildMode::Debug,
+            BuildProfile::Verifiable => BuildMode::Verifiable,
+        }
+    }
+}
+
+impl DeployPlan {
+    /// Loads the plan from `path`, resolving the contract paths relative to the
+    /// directory of the plan.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path).with_context(|| {
+            format!("Failed to read deployment plan {}", path.display())
+        })?;
+        let mut plan: DeployPlan = toml::from_str(&content).with_context(|| {
+            format!("Failed to parse deployment plan {}", path.display())
+        })?;
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        for contract in &mut plan.contracts {
+            if contract.path.is_relative() {
+                contract.path = base.join(&contract.path);
+            }
+        }
+        plan.validate()?;
+        Ok(plan)
+    }
+
+    /// Checks that the contract names are unique and that all references point to
+    /// contracts deployed before them.
+    pub fn validate(&self) -> Result<()> {
+        let mut deployed = BTreeMap::new();
+        for contract in &self.contracts {
+            let context = format!("contract `{}`", contract.name);
+            for input in contract.inputs() {
+                check_references(input, &deployed, &context)?;
+            }
+            if deployed
+                .insert(contract.name.as_str(), contract.constructor.is_some())
+                .is_some()
+            {
+                bail!("Contract `{}` is listed more than once", contract.name)
+            }
+        }
+        for call in &self.calls {
+            let context = format!("call `{}::{}`", call.contract, call.message);
+            match deployed.get(call.contract.as_str()) {
+                Some(true) => (),
+                Some(false) => {
+                    bail!(
+                        "Can not make {context}, `{}` has no constructor",
+                        call.contract
+                    )
+                }
+                None => bail!("Can not make {context}, the contract is not in the plan"),
+            }
+            for input in call.args.iter().chain(&call.value) {
+                check_references(input, &deployed, &context)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PlanContract {
+    /// Returns whether the path of the contract is a `.contract` bundle, rather than a
+    /// manifest to build it from.
+    pub fn is_bundle(&self) -> bool {
+        self.path.extension().is_some_and(|ext| ext == "contract")
+    }
+
+    fn bundle(&self) -> Result<&Path> {
+        if !self.is_bundle() {
+            bail!(
+                "Contract `{}` has not been built, {} is not a `.contract` bundle",
+                self.name,
+                self.path.display()
+            )
+        }
+        Ok(&self.path)
+    }
+
+    fn inputs(&self) -> impl Iterator<Item = &String> {
+        self.args.iter().chain(&self.value).chain(&self.salt)
+    }
+}
+
+/// Replaces each `${<contract>.<output>}` reference in `input` with the result of
+/// `lookup(contract, output)`.
+fn substitute(
+    input: &str,
+    mut lookup: impl FnMut(&str, &str) -> Result<String>,
+) -> Result<String> {
+    let mut substituted = String::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        substituted.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| anyhow!("Unterminated reference in `{input}`"))?;
+        let reference = &rest[start + 2..end];
+        let (contract, output) = reference.split_once('.').ok_or_else(|| {
+            anyhow!(
+                "Invalid reference `${{{reference}}}`, expected \
+                 `${{<contract>.<output>}}`"
+            )
+        })?;
+        substituted.push_str(&lookup(contract, output)?);
+        rest = &rest[end + 1..];
+    }
+    substituted.push_str(rest);
+    Ok(substituted)
+}
+
+fn check_references(
+    input: &str,
+    deployed: &BTreeMap<&str, bool>,
+    context: &str,
+) -> Result<()> {
+    substitute(input, |contract, output| {
+        match (deployed.get(contract), output) {
+            (Some(_), "code_hash") | (Some(true), "address") => Ok(String::new()),
+            (Some(false), "address") => {
+                bail!("`{contract}` of {context} has no constructor, so no address")
+            }
+            (Some(_), output) => {
+                bail!(
+                    "Unknown output `{output}` of `{contract}` in {context}, expected \
+                     `address` or `code_hash`"
+                )
+            }
+            (None, _) => {
+                bail!("{context} refers to `{contract}`, which is not deployed before it")
+            }
+        }
+    })
+    .map(|_| ())
+}
+
+/// The constructor call a contract instance was created with.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Instantiation {
+    pub constructor: String,
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+}
+
+/// A message call made on a contract instance.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageCall {
+    pub contract: String,
+    pub address: String,
+    pub message: String,
+    pub args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// Records the code and instances deployed by a [`DeployPlan`] on each network, so
+/// that running the plan again only deploys what has changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeployLock {
+    #[serde(default)]
+    pub networks: BTreeMap<String, NetworkLock>,
+}
+
+/// The deployments of a [`DeployPlan`] on a single network.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkLock {
+    #[serde(default)]
+    pub contracts: BTreeMap<String, LockedContract>,
+    #[serde(rename = "call", default)]
+    pub calls: Vec<MessageCall>,
+}
+
+/// A contract deployed by a [`DeployPlan`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedContract {
+    pub code_hash: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instantiation: Option<Instantiation>,
+}
+
+impl DeployLock {
+    /// Loads the lock file at `path`, or returns an empty lock if it does not exist.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default())
+        }
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read lock file {}", path.display()))?;
+        toml::from_str(&content)
+            .with_context(|| format!("Failed to parse lock file {}", path.display()))
+    }
+
+    /// Writes the lock file to `path`.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = format!(
+            "# This file is generated by `cargo contract deploy`, do not edit it.\n\n{}",
+            toml::to_string_pretty(self)?
+        );
+        fs::write(path, content)
+            .with_context(|| format!("Failed to write lock file {}", path.display()))
+    }
+}
+
+/// Where the contracts of a [`DeployPlan`] are deployed to, e.g. a node with
+/// [`ChainBackend`] or a sandboxed runtime for testing the plan.
+///
+/// Code hashes and addresses are passed around in the textual form the backend accepts
+/// them in, i.e. as they are written to the lock file.
+#[allow(async_fn_in_trait)]
+pub trait DeployBackend {
+    /// Identifies the network, used as the key of its deployments in the lock file.
+    fn network(&self) -> String;
+
+    /// Returns the code hash of the contract bundle at `artifact`.
+    async fn code_hash(&mut self, artifact: &Path) -> Result<String>;
+
+    /// Returns whether code with the given hash has been uploaded.
+    async fn code_exists(&mut self, code_hash: &str) -> Result<bool>;
+
+    /// Returns whether a contract exists at the given address.
+    async fn contract_exists(&mut self, address: &str) -> Result<bool>;
+
+    /// Uploads the code of the contract bundle at `artifact`, returning its code hash.
+    async fn upload(&mut self, artifact: &Path, dry_run: bool) -> Result<String>;
+
+    /// Instantiates the contract bundle at `artifact`, returning the address of the
+    /// new instance.
+    async fn instantiate(
+        &mut self,
+        artifact: &Path,
+        instantiation: &Instantiation,
+        dry_run: bool,
+    ) -> Result<String>;
+
+    /// Calls a message of a contract instance of the bundle at `artifact`, returning
+    /// a description of the result.
+    async fn call(
+        &mut self,
+        artifact: &Path,
+        call: &MessageCall,
+        dry_run: bool,
+    ) -> Result<String>;
+}
+
+/// An action taken by a [`DeployPlan`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeployAction {
+    Upload,
+    Instantiate,
+    Call,
+}
+
+/// Whether an action of a [`DeployPlan`] was executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepStatus {
+    /// The action was executed.
+    Executed,
+    /// The action was simulated with a dry run.
+    DryRun,
+    /// The action was skipped, because it was already executed by a previous
+    /// deployment, or because it can not be simulated.
+    Skipped,
+}
+
+/// An action of a [`DeployPlan`] and its outcome.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DeployStep {
+    pub action: DeployAction,
+    /// The contract, or `<contract>::<message>` for calls.
+    pub target: String,
+    pub status: StepStatus,
+    /// The code hash, address or call result.
+    pub output: String,
+}
+
+/// The outcome of executing a [`DeployPlan`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct DeployReport {
+    pub steps: Vec<DeployStep>,
+}
+
+impl DeployReport {
+    fn push(
+        &mut self,
+        action: DeployAction,
+        target: &str,
+        status: StepStatus,
+        output: String,
+    ) {
+        self.steps.push(DeployStep {
+            action,
+            target: target.to_string(),
+            status,
+            output,
+        })
+    }
+}
+
+/// The outputs of a deployed contract that later entries of the plan can refer to.
+struct Deployed<'a> {
+    artifact: &'a Path,
+    code_hash: String,
+    address: Option<String>,
+    simulated: bool,
+}
+
+/// Executes `plan` on `backend`, skipping the code and instances recorded in `lock`
+/// that still exist on the network.
+///
+/// The lock is updated after each executed action, so that a failed deployment can be
+/// resumed. With `dry_run` set nothing is submitted and the lock is left untouched.
+pub async fn execute_plan<B: DeployBackend>(
+    plan: &DeployPlan,
+    lock: &mut DeployLock,
+    backend: &mut B,
+    dry_run: bool,
+) -> Result<DeployReport> {
+    let network = backend.network();
+    let mut dry_run_state;
+    let state = if dry_run {
+        dry_run_state = lock.networks.get(&network).cloned().unwrap_or_default();
+        &mut dry_run_state
+    } else {
+        lock.networks.entry(network).or_default()
+    };
+    let executed = if dry_run {
+        StepStatus::DryRun
+    } else {
+        StepStatus::Executed
+    };
+    let mut report = DeployReport::default();
+    let mut deployed: BTreeMap<&str, Deployed> = BTreeMap::new();
+
+    for contract in &plan.contracts {
+        let artifact = contract.bundle()?;
+        let resolve_input = |input: &String| resolve(input, &deployed);
+
+        let code_hash = backend.code_hash(artifact).await?;
+        let mut simulated = false;
+        if backend.code_exists(&code_hash).await? {
+            report.push(
+                DeployAction::Upload,
+                &contract.name,
+                StepStatus::Skipped,
+                code_hash.clone(),
+            );
+        } else {
+            let uploaded = backend
+                .upload(artifact, dry_run)
+                .await
+                .with_context(|| format!("Failed to upload `{}`", contract.name))?;
+            report.push(DeployAction::Upload, &contract.name, executed, uploaded);
+            simulated = dry_run;
+        }
+
+        let locked = state
+            .contracts
+            .get(&contract.name)
+            .filter(|locked| locked.code_hash == code_hash);
+        let mut address = None;
+        let mut instantiation = None;
+        if let Some(constructor) = &contract.constructor {
+            let wanted = Instantiation {
+                constructor: constructor.clone(),
+                args: contract
+                    .args
+                    .iter()
+                    .map(resolve_input)
+                    .collect::<Result<_>>()?,
+                value: contract.value.as_ref().map(resolve_input).transpose()?,
+                salt: contract.salt.as_ref().map(resolve_input).transpose()?,
+            };
+            let existing = match locked {
+                Some(LockedContract {
+                    address: Some(address),
+                    instantiation: Some(instantiation),
+                    ..
+                }) if instantiation == &wanted => {
+                    backend
+                        .contract_exists(address)
+                        .await?
+                        .then(|| address.clone())
+                }
+                _ => None,
+            };
+            let instance = match existing {
+                Some(existing) => {
+                    report.push(
+                        DeployAction::Instantiate,
+                        &contract.name,
+                        StepStatus::Skipped,
+                        existing.clone(),
+                    );
+                    existing
+                }
+                _ => {
+                    let instance = backend
+                        .instantiate(artifact, &wanted, dry_run)
+                        .await
+                        .with_context(|| {
+                            format!("Failed to instantiate `{}`", contract.name)
+                        })?;
+                    report.push(
+                        DeployAction::Instantiate,
+                        &contract.name,
+                        executed,
+                        instance.clone(),
+                    );
+                    simulated = dry_run;
+                    instance
+                }
+            };
+            address = Some(instance);
+            instantiation = Some(wanted);
+        }
+
+        state.contracts.insert(
+            contract.name.clone(),
+            LockedContract {
+                code_hash: code_hash.clone(),
+                address: address.clone(),
+                instantiation,
+            },
+        );
+        deployed.insert(
+            contract.name.as_str(),
+            Deployed {
+                artifact,
+                code_hash,
+                address,
+                simulated,
+            },
+        );
+    }
+
+    for call in &plan.calls {
+        let target = format!("{}::{}", call.contract, call.message);
+        let contract = &deployed[call.contract.as_str()];
+        let message_call = MessageCall {
+            contract: call.contract.clone(),
+            address: contract
+                .address
+                .clone()
+                .expect("calls are only made on instantiated contracts"),
+            message: call.message.clone(),
+            args: call
+                .args
+                .iter()
+                .map(|arg| resolve(arg, &deployed))
+                .collect::<Result<_>>()?,
+            value: call
+                .value
+                .as_ref()
+                .map(|value| resolve(value, &deployed))
+                .transpose()?,
+        };
+        if state.calls.contains(&message_call) {
+            report.push(
+                DeployAction::Call,
+                &target,
+                StepStatus::Skipped,
+                "already called".to_string(),
+            );
+        } else if contract.simulated {
+            report.push(
+                DeployAction::Call,
+                &target,
+                StepStatus::Skipped,
+                "the contract is not deployed yet".to_string(),
+            );
+        } else {
+            let output = backend
+                .call(contract.artifact, &message_call, dry_run)
+                .await
+                .with_context(|| format!("Failed to call `{target}`"))?;
+            report.push(DeployAction::Call, &target, executed, output);
+            state.calls.push(message_call);
+        }
+    }
+
+    let names = plan
+        .contracts
+        .iter()
+        .map(|contract| contract.name.as_str())
+        .collect::<BTreeSet<_>>();
+    state
+        .contracts
+        .retain(|name, _| names.contains(name.as_str()));
+    Ok(report)
+}
+
+fn resolve(input: &str, deployed: &BTreeMap<&str, Deployed>) -> Result<String> {
+    substitute(input, |name, output| {
+        let contract = deployed
+            .get(name)
+            .ok_or_else(|| anyhow!("Contract `{name}` is not deployed"))?;
+        match output {
+            "code_hash" => Ok(contract.code_hash.clone()),
+            "address" => {
+                contract
+                    .address
+                    .clone()
+                    .ok_or_else(|| anyhow!("Contract `{name}` has no address"))
+            }
+            output => bail!("Unknown output `{output}`"),
+        }
+    })
+}
+
+fn to_hex(bytes: impl AsRef<[u8]>) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Deploys the contracts of a [`DeployPlan`] to a node.
+pub struct ChainBackend<C: Config, E: Environment, Signer: Clone> {
+    url: Url,
+    signer: Signer,
+    storage_deposit_limit: Option<E::Balance>,
+    verbosity: Verbosity,
+    client: OnlineClient<C>,
+    token_metadata: TokenMetadata,
+}
+
+impl<C: Config, E: Environment, Signer> ChainBackend<C, E, Signer>
+where
+    Signer: tx::Signer<C> + Clone,
+{
+    /// Connects to the node at `url`, submitting the extrinsics signed by `signer`.
+    pub async fn new(
+        url: Url,
+        signer: Signer,
+        storage_deposit_limit: Option<E::Balance>,
+        verbosity: Verbosity,
+    ) -> Result<Self> {
+        let client = OnlineClient::from_url(url_to_string(&url)).await?;
+        let token_metadata = TokenMetadata::query::<C>(&url).await?;
+        Ok(Self {
+            url,
+            signer,
+            storage_deposit_limit,
+            verbosity,
+            client,
+            token_metadata,
+        })
+    }
+
+    fn extrinsic_opts(&self, artifact: &Path) -> ExtrinsicOpts<C, E, Signer> {
+        ExtrinsicOptsBuilder::new(self.signer.clone())
+            .file(Some(artifact))
+            .url(self.url.clone())
+            .storage_deposit_limit(self.storage_deposit_limit)
+            .verbosity(self.verbosity)
+            .done()
+    }
+
+    fn balance(&self, value: Option<&String>) -> Result<E::Balance>
+    where
+        E::Balance: FromStr + From<u128> + Default + Clone,
+    {
+        match value {
+            Some(value) => {
+                BalanceVariant::<E::Balance>::from_str(value)
+                    .and_then(|balance| balance.denominate_balance(&self.token_metadata))
+                    .with_context(|| format!("Failed to parse value `{value}`"))
+            }
+            None => Ok(Default::default()),
+        }
+    }
+
+    async fn storage_exists(&self, entry: &str, key: &[u8]) -> Result<bool> {
+        let address =
+            subxt::dynamic::storage("Contracts", entry, vec![Value::from_bytes(key)]);
+        let value = self
+            .client
+            .storage()
+            .at_latest()
+            .await?
+            .fetch(&address)
+            .await?;
+        Ok(value.is_some())
+    }
+}
+
+impl<C: Config, E: Environment, Signer> DeployBackend for ChainBackend<C, E, Signer>
+where
+    C::Hash: From<[u8; 32]> + IntoVisitor + EncodeAsType,
+    C::AccountId: IntoVisitor + EncodeAsType + Decode + Display + FromStr + AsRef<[u8]>,
+    <C::AccountId as FromStr>::Err: Display,
+    E::Balance: FromStr + From<u128> + Default + Clone + Serialize + EncodeAsType,
+    <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+        From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    Signer: tx::Signer<C> + Clone,
+{
+    fn network(&self) -> String {
+        to_hex(self.client.genesis_hash().encode())
+    }
+
+    async fn code_hash(&mut self, artifact: &Path) -> Result<String> {
+        let artifacts = ContractArtifacts::from_manifest_or_file(
+            None,
+            Some(&artifact.to_path_buf()),
+        )?;
+        Ok(to_hex(artifacts.code_hash()?))
+    }
+
+    async fn code_exists(&mut self, code_hash: &str) -> Result<bool> {
+        let code_hash = decode_hex(code_hash)
+            .with_context(|| format!("Failed to parse code hash `{code_hash}`"))?;
+        self.storage_exists("PristineCode", &code_hash).await
+    }
+
+    async fn contract_exists(&mut self, address: &str) -> Result<bool> {
+        let account = C::AccountId::from_str(address)
+            .map_err(|err| anyhow!("Failed to parse address `{address}`: {err}"))?;
+        self.storage_exists("ContractInfoOf", account.as_ref())
+            .await
+    }
+
+    async fn upload(&mut self, artifact: &Path, dry_run: bool) -> Result<String> {
+        let upload_exec = UploadCommandBuilder::new(self.extrinsic_opts(artifact))
+            .done()
+            .await?;
+        if dry_run {
+            return match upload_exec.upload_code_rpc().await? {
+                Ok(result) => Ok(to_hex(result.code_hash.encode())),
+                Err(err) => {
+                    let metadata = self.client.metadata();
+                    bail!("{}", ErrorVariant::from_dispatch_error(&err, &metadata)?)
+                }
+            };
+        }
+        let result = upload_exec
+            .upload_code()
+            .await
+            .map_err(|err| anyhow!("{err}"))?;
+        match result.code_stored {
+            Some(code_stored) => Ok(to_hex(code_stored.code_hash.encode())),
+            None => self.code_hash(artifact).await,
+        }
+    }
+
+    async fn instantiate(
+        &mut self,
+        artifact: &Path,
+        instantiation: &Instantiation,
+        dry_run: bool,
+    ) -> Result<String> {
+        let salt = instantiation
+            .salt
+            .as_deref()
+            .map(decode_hex)
+            .transpose()
+            .context("Failed to parse salt")?
+            .map(Bytes::from);
+        let instantiate_exec =
+            InstantiateCommandBuilder::new(self.extrinsic_opts(artifact))
+                .constructor(instantiation.constructor.as_str())
+                .args(instantiation.args.clone())
+                .value(self.balance(instantiation.value.as_ref())?)
+                .salt(salt)
+                .done()
+                .await?;
+        if dry_run {
+            let result = instantiate_exec.instantiate_dry_run().await?;
+            let result = instantiate_exec
+                .decode_instantiate_dry_run(&result)
+                .await
+                .map_err(|err| anyhow!("{err}"))?;
+            if result.reverted {
+                bail!("The constructor reverted with {}", result.result)
+            }
+            return Ok(result.contract)
+        }
+        let result = instantiate_exec
+            .instantiate(None)
+            .await
+            .map_err(|err| anyhow!("{err}"))?;
+        Ok(result.contract_address.to_string())
+    }
+
+    async fn call(
+        &mut self,
+        artifact: &Path,
+        call: &MessageCall,
+        dry_run: bool,
+    ) -> Result<String> {
+        let contract = C::AccountId::from_str(&call.address).map_err(|err| {
+            anyhow!("Failed to parse address `{}`: {err}", call.address)
+        })?;
+        let call_exec = CallCommandBuilder::new(
+            contract,
+            &call.message,
+            self.extrinsic_opts(artifact),
+        )
+        .args(call.args.clone())
+        .value(self.balance(call.value.as_ref())?)
+        .done()
+        .await?;
+        let mutates = call_exec
+            .transcoder()
+            .metadata()
+            .spec()
+            .messages()
+            .iter()
+            .any(|message| message.label() == &call.message && message.mutates());
+        if dry_run || !mutates {
+            let result = call_exec.call_dry_run().await?;
+            return match result.result {
+                Ok(ref ret_val) => {
+                    let value = call_exec
+                        .transcoder()
+                        .decode_message_return(&call.message, &mut &ret_val.data[..])?;
+                    if ret_val.did_revert() {
+                        bail!("The message reverted with {value}")
+                    }
+                    Ok(value.to_string())
+                }
+                Err(ref err) => {
+                    let metadata = self.client.metadata();
+                    bail!("{}", ErrorVariant::from_dispatch_error(err, &metadata)?)
+                }
+            };
+        }
+        let events = call_exec.call(None).await.map_err(|err| anyhow!("{err}"))?;
+        Ok(format!("extrinsic {:?}", events.extrinsic_hash()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAN: &str = r#"
+        [[contract]]
+        name = "token"
+        path = "token.contract"
+        constructor = "new"
+        args = ["1000"]
+
+        [[contract]]
+        name = "pair"
+        path = "contracts/pair/Cargo.toml"
+        profile = "debug"
+
+        [[contract]]
+        name = "dex"
+        path = "dex.contract"
+        constructor = "new"
+        args = ["${token.address}", "${pair.code_hash}"]
+
+        [[call]]
+        contract = "token"
+        message = "approve"
+        args = ["${dex.address}", "1000"]
+    "#;
+
+    /// Deploys to an in-memory network, recording every action.
+    #[derive(Default)]
+    struct MockBackend {
+        codes: BTreeSet<String>,
+        contracts: BTreeMap<String, Instantiation>,
+        calls: Vec<MessageCall>,
+    }
+
+    impl DeployBackend for MockBackend {
+        fn network(&self) -> String {
+            "mock".to_string()
+        }
+
+        async fn code_hash(&mut self, artifact: &Path) -> Result<String> {
+            Ok(format!("{}-hash", artifact.display()))
+        }
+
+        async fn code_exists(&mut self, code_hash: &str) -> Result<bool> {
+            Ok(self.codes.contains(code_hash))
+        }
+
+        async fn contract_exists(&mut self, address: &str) -> Result<bool> {
+            Ok(self.contracts.contains_key(address))
+        }
+
+        async fn upload(&mut self, artifact: &Path, dry_run: bool) -> Result<String> {
+            let code_hash = self.code_hash(artifact).await?;
+            if !dry_run {
+                self.codes.insert(code_hash.clone());
+            }
+            Ok(code_hash)
+        }
+
+        async fn instantiate(
+            &mut self,
+            artifact: &Path,
+            instantiation: &Instantiation,
+            dry_run: bool,
+        ) -> Result<String> {
+            let address = format!("{}-{}", artifact.display(), self.contracts.len());
+            if !dry_run {
+                self.contracts
+                    .insert(address.clone(), instantiation.clone());
+            }
+            Ok(address)
+        }
+
+        async fn call(
+            &mut self,
+            _artifact: &Path,
+            call: &MessageCall,
+            dry_run: bool,
+        ) -> Result<String> {
+            if !dry_run {
+                self.calls.push(call.clone());
+            }
+            Ok("Ok(())".to_string())
+        }
+    }
+
+    fn built_plan() -> DeployPlan {
+        let mut plan: DeployPlan = toml::from_str(PLAN).unwrap();
+        plan.contracts[1].path = PathBuf::from("pair.contract");
+        plan.validate().unwrap();
+        plan
+    }
+
+    fn statuses(report: &DeployReport) -> Vec<(DeployAction, &str, StepStatus)> {
+        report
+            .steps
+            .iter()
+            .map(|step| (step.action, step.target.as_str(), step.status))
+            .collect()
+    }
+
+    #[test]
+    fn plan_loads_relative_to_its_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deploy.toml");
+        fs::write(&path, PLAN).unwrap();
+
+        let plan = DeployPlan::load(&path).unwrap();
+
+        assert_eq!(plan.contracts.len(), 3);
+        assert_eq!(plan.calls.len(), 1);
+        assert_eq!(plan.contracts[0].path, dir.path().join("token.contract"));
+        assert!(plan.contracts[0].is_bundle());
+        assert_eq!(plan.contracts[0].profile, BuildProfile::Release);
+        assert!(!plan.contracts[1].is_bundle());
+        assert_eq!(plan.contracts[1].profile, BuildProfile::Debug);
+        assert_eq!(plan.contracts[1].constructor, None);
+    }
+
+    #[test]
+    fn plan_validation_rejects_invalid_references() {
+        let plan = |toml: &str| toml::from_str::<DeployPlan>(toml).unwrap().validate();
+
+        let forward = plan(
+            r#"
+            [[contract]]
+            name = "dex"
+            path = "dex.contract"
+            constructor = "new"
+            args = ["${token.address}"]
+
+            [[contract]]
+            name = "token"
+            path = "token.contract"
+            constructor = "new"
+            "#,
+        );
+        assert!(forward
+            .unwrap_err()
+            .to_string()
+            .contains("not deployed before"));
+
+        let no_address = plan(
+            r#"
+            [[contract]]
+            name = "pair"
+            path = "pair.contract"
+
+            [[contract]]
+            name = "dex"
+            path = "dex.contract"
+            constructor = "new"
+            args = ["${pair.address}"]
+            "#,
+        );
+        assert!(no_address.unwrap_err().to_string().contains("no address"));
+
+        let unknown_call = plan(
+            r#"
+            [[call]]
+            contract = "token"
+            message = "approve"
+            "#,
+        );
+        assert!(unknown_call
+            .unwrap_err()
+            .to_string()
+            .contains("not in the plan"));
+
+        let duplicate = plan(
+            r#"
+            [[contract]]
+            name = "token"
+            path = "token.contract"
+
+            [[contract]]
+            name = "token"
+            path = "token.contract"
+            "#,
+        );
+        assert!(duplicate
+            .unwrap_err()
+            .to_string()
+            .contains("more than once"));
+    }
+
+    #[test]
+    fn substitute_replaces_all_references() {
+        let substituted =
+            substitute("[${a.address}, ${b.code_hash}]", |contract, output| {
+                Ok(format!("{contract}:{output}"))
+            })
+            .unwrap();
+        assert_eq!(substituted, "[a:address, b:code_hash]");
+        assert!(substitute("${a.address", |_, _| Ok(String::new())).is_err());
+        assert!(substitute("${a}", |_, _| Ok(String::new())).is_err());
+    }
+
+    #[tokio::test]
+    async fn execute_plan_is_idempotent() {
+        let plan = built_plan();
+        let mut backend = MockBackend::default();
+        let mut lock = DeployLock::default();
+
+        let report = execute_plan(&plan, &mut lock, &mut backend, false)
+            .await
+            .unwrap();
+        assert!(report
+            .steps
+            .iter()
+            .all(|step| step.status == StepStatus::Executed));
+        assert_eq!(report.steps.len(), 6);
+        assert_eq!(
+            backend.contracts["dex.contract-1"].args,
+            vec!["token.contract-0", "pair.contract-hash"]
+        );
+        assert_eq!(backend.calls[0].args, vec!["dex.contract-1", "1000"]);
+
+        let report = execute_plan(&plan, &mut lock, &mut backend, false)
+            .await
+            .unwrap();
+        assert!(report
+            .steps
+            .iter()
+            .all(|step| step.status == StepStatus::Skipped));
+        assert_eq!(backend.contracts.len(), 2);
+        assert_eq!(backend.calls.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_plan_redeploys_changed_instances() {
+        let mut plan = built_plan();
+        let mut backend = MockBackend::default();
+        let mut lock = DeployLock::default();
+        execute_plan(&plan, &mut lock, &mut backend, false)
+            .await
+            .unwrap();
+
+        plan.contracts[2].args.push("0".to_string());
+        let report = execute_plan(&plan, &mut lock, &mut backend, false)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            statuses(&report),
+            vec![
+                (DeployAction::Upload, "token", StepStatus::Skipped),
+                (DeployAction::Instantiate, "token", StepStatus::Skipped),
+                (DeployAction::Upload, "pair", StepStatus::Skipped),
+                (DeployAction::Upload, "dex", StepStatus::Skipped),
+                (DeployAction::Instantiate, "dex", StepStatus::Executed),
+                (DeployAction::Call, "token::approve", StepStatus::Executed),
+            ]
+        );
+        assert_eq!(backend.calls[1].args, vec!["dex.contract-2", "1000"]);
+        assert_eq!(
+            lock.networks["mock"].contracts["dex"].address.as_deref(),
+            Some("dex.contract-2")
+        );
+    }
+
+    #[tokio::test]
+    async fn dry_run_leaves_lock_and_network_untouched() {
+        let plan = built_plan();
+        let mut backend = MockBackend::default();
+        let mut lock = DeployLock::default();
+
+        let report = execute_plan(&plan, &mut lock, &mut backend, true)
+            .await
+            .unwrap();
+
+        assert_eq!(report.steps.len(), 6);
+        assert_eq!(
+            report.steps.last().map(|step| step.status),
+            Some(StepStatus::Skipped)
+        );
+        assert!(report.steps[..5]
+            .iter()
+            .all(|step| step.status == StepStatus::DryRun));
+        assert_eq!(lock, DeployLock::default());
+        assert!(backend.codes.is_empty());
+        assert!(backend.contracts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn lock_roundtrips_through_toml() {
+        let plan = built_plan();
+        let mut lock = DeployLock::default();
+        execute_plan(&plan, &mut lock, &mut MockBackend::default(), false)
+            .await
+            .unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deploy.lock");
+        lock.save(&path).unwrap();
+
+        assert_eq!(DeployLock::load(&path).unwrap(), lock);
+        assert_eq!(
+            DeployLock::load(&dir.path().join("missing.lock")).unwrap(),
+            DeployLock::default()
+        );
+    }
+
+    #[test]
+    fn contracts_must_be_built_before_deployment() {
+        let plan: DeployPlan = toml::from_str(PLAN).unwrap();
+        let err = plan.contracts[1].bundle().unwrap_err();
+        assert!(err.to_string().contains("has not been built"));
+    }
+}
@@ -36,6 +36,7 @@
 // SOFTWARE.
 
 use super::{
+    dispatch_origin,
     events::{
         CodeStored,
         ContractInstantiated,
@@ -46,8 +47,10 @@ use super::{
     },
     state_call,
     submit_extrinsic,
+    CallWrapper,
     ContractMessageTranscoder,
     ErrorVariant,
+    UnsignedExtrinsic,
 };
 use crate::{
     check_env_types,
@@ -96,11 +99,14 @@ use subxt::{
 pub struct InstantiateCommandBuilder<C: Config, E: Environment, Signer: Clone> {
     constructor: String,
     args: Vec<String>,
+    data: Option<Vec<u8>>,
     extrinsic_opts: ExtrinsicOpts<C, E, Signer>,
     value: E::Balance,
     gas_limit: Option<u64>,
     proof_size: Option<u64>,
     salt: Option<Bytes>,
+    wrappers: Vec<CallWrapper<C::AccountId>>,
+    origin: Option<C::AccountId>,
 }
 
 impl<C: Config, E: Environment, Signer> InstantiateCommandBuilder<C, E, Signer>
@@ -116,11 +122,14 @@ where
         InstantiateCommandBuilder {
             constructor: String::from("new"),
             args: Vec::new(),
+            data: None,
             extrinsic_opts,
             value: Default::default(),
             gas_limit: None,
             proof_size: None,
             salt: None,
+            wrappers: Vec::new(),
+            origin: None,
         }
     }
 
@@ -138,6 +147,15 @@ where
         this
     }
 
+    /// Sets already encoded constructor data, e.g. produced by typed contract bindings.
+    ///
+    /// The constructor arguments are not transcoded when the data is set.
+    pub fn data(self, data: Vec<u8>) -> Self {
+        let mut this = self;
+        this.data = Some(data);
+        this
+    }
+
     /// Sets the initial balance to transfer to the instantiated contract.
     pub fn value(self, value: E::Balance) -> Self {
         let mut this = self;
@@ -166,6 +184,21 @@ where
         this
     }
 
+    /// Sets the multisig and proxy calls wrapping the instantiation, innermost first.
+    ///
+    /// Dry runs and gas estimation are then performed from the account dispatching the
+    /// instantiation rather than from the signer.
+    pub fn wrappers(self, wrappers: Vec<CallWrapper<C::AccountId>>) -> Result<Self>
+    where
+        C::AccountId: Decode,
+    {
+        let mut this = self;
+        let signer = this.extrinsic_opts.signer().account_id();
+        this.origin = Some(dispatch_origin(&signer, &wrappers)?);
+        this.wrappers = wrappers;
+        Ok(this)
+    }
+
     /// Preprocesses contract artifacts and options for instantiation.
     ///
     /// This function prepares the required data for instantiating a contract based on the
@@ -178,7 +211,10 @@ where
     pub async fn done(self) -> Result<InstantiateExec<C, E, Signer>> {
         let artifacts = self.extrinsic_opts.contract_artifacts()?;
         let transcoder = artifacts.contract_transcoder()?;
-        let data = transcoder.encode(&self.constructor, &self.args)?;
+        let data = match self.data {
+            Some(data) => data,
+            None => transcoder.encode(&self.constructor, &self.args)?,
+        };
         let url = self.extrinsic_opts.url();
         let code = if let Some(code) = artifacts.code {
             Code::Upload(code.0)
@@ -205,12 +241,17 @@ where
             salt,
         };
 
+        let origin = self
+            .origin
+            .unwrap_or_else(|| self.extrinsic_opts.signer().account_id());
         Ok(InstantiateExec {
             args,
             opts: self.extrinsic_opts,
             rpc,
             client,
             transcoder,
+            wrappers: self.wrappers,
+            origin,
         })
     }
 }
@@ -279,6 +320,9 @@ pub struct InstantiateExec<C: Config, E: Environment, Signer: Clone> {
     rpc: LegacyRpcMethods<C>,
     client: OnlineClient<C>,
     transcoder: ContractMessageTranscoder,
+    wrappers: Vec<CallWrapper<C::AccountId>>,
+    /// The account dispatching the instantiation, the signer unless it is wrapped.
+    origin: C::AccountId,
 }
 
 impl<C: Config, E: Environment, Signer> InstantiateExec<C, E, Signer>
@@ -343,7 +387,7 @@ where
     ) -> Result<ContractInstantiateResult<C::AccountId, E::Balance>> {
         let storage_deposit_limit = self.args.storage_deposit_limit;
         let call_request = InstantiateRequest::<C, E> {
-            origin: self.opts.signer().account_id(),
+            origin: self.origin.clone(),
             value: self.args.value,
             gas_limit: None,
             storage_deposit_limit,
@@ -484,6 +528,67 @@ where
         }
     }
 
+    /// Prepares the contract instantiation to be signed offline, dispatched through the
+    /// multisig and proxy calls set by [`InstantiateCommandBuilder::wrappers`].
+    ///
+    /// Returns the [`UnsignedExtrinsic`] to be signed by the signer of the extrinsic
+    /// options, including `dry_run` for review before signing.
+    pub async fn instantiate_unsigned(
+        &self,
+        gas_limit: Option<Weight>,
+        dry_run: Option<serde_json::Value>,
+    ) -> Result<UnsignedExtrinsic> {
+        let gas_limit = match gas_limit {
+            Some(gas_limit) => gas_limit,
+            None => self.estimate_gas().await?,
+        };
+        let account_id = self.opts.signer().account_id();
+        match self.args.code.clone() {
+            Code::Upload(code) => {
+                let call = InstantiateWithCode::new(
+                    self.args.value,
+                    gas_limit,
+                    self.args.storage_deposit_limit,
+                    code,
+                    self.args.data.clone(),
+                    self.args.salt.clone(),
+                )
+                .build();
+                UnsignedExtrinsic::prepare(
+                    &self.client,
+                    &self.rpc,
+                    &call,
+                    "Contracts::instantiate_with_code",
+                    &account_id,
+                    &self.wrappers,
+                    dry_run,
+                )
+                .await
+            }
+            Code::Existing(code_hash) => {
+                let call = Instantiate::<C::Hash, E::Balance>::new(
+                    self.args.value,
+                    gas_limit,
+                    self.args.storage_deposit_limit,
+                    code_hash,
+                    self.args.data.clone(),
+                    self.args.salt.clone(),
+                )
+                .build();
+                UnsignedExtrinsic::prepare(
+                    &self.client,
+                    &self.rpc,
+                    &call,
+                    "Contracts::instantiate",
+                    &account_id,
+                    &self.wrappers,
+                    dry_run,
+                )
+                .await
+            }
+        }
+    }
+
     /// Returns the extrinsic options.
     pub fn opts(&self) -> &ExtrinsicOpts<C, E, Signer> {
         &self.opts
@@ -40,15 +40,18 @@ mod call;
 mod contract_artifacts;
 mod contract_info;
 mod contract_storage;
+mod deploy;
 mod env_check;
 mod error;
 pub mod events;
 pub mod extrinsic_calls;
 pub mod extrinsic_opts;
 mod instantiate;
+mod offline;
 pub mod pallet_contracts_primitives;
 mod remove;
 mod rpc;
+mod trace;
 pub mod upload;
 
 #[cfg(test)]
@@ -107,6 +110,24 @@ pub use contract_storage::{
     ContractStorageRpc,
 };
 pub use contract_transcode::ContractMessageTranscoder;
+pub use deploy::{
+    execute_plan,
+    BuildProfile,
+    ChainBackend,
+    DeployAction,
+    DeployBackend,
+    DeployLock,
+    DeployPlan,
+    DeployReport,
+    DeployStep,
+    Instantiation,
+    LockedContract,
+    MessageCall,
+    NetworkLock,
+    PlanCall,
+    PlanContract,
+    StepStatus,
+};
 pub use error::{
     ErrorVariant,
     GenericError,
@@ -121,11 +142,25 @@ pub use instantiate::{
     InstantiateExec,
     InstantiateExecResult,
 };
+pub use offline::{
+    dispatch_origin,
+    CallWrapper,
+    SignedExtrinsic,
+    Timepoint,
+    UnsignedExtrinsic,
+};
 pub use remove::{
     RemoveCommandBuilder,
     RemoveExec,
     RemoveResult,
 };
+pub use trace::{
+    CallKind,
+    CallTrace,
+    DecodedCallTrace,
+    TraceDecoder,
+    TracedEvent,
+};
 
 pub use upload::{
     UploadCommandBuilder,
@@ -180,12 +215,22 @@ where
     let params = DefaultExtrinsicParamsBuilder::new()
         .nonce(account_nonce)
         .build();
-    let mut tx = client
+    let tx = client
         .tx()
         .create_signed_offline(call, signer, params.into())?
         .submit_and_watch()
         .await?;
 
+    wait_for_in_block(tx).await
+}
+
+/// Wait for a submitted transaction to be included successfully into a block.
+async fn wait_for_in_block<C>(
+    mut tx: tx::TxProgress<C, OnlineClient<C>>,
+) -> core::result::Result<blocks::ExtrinsicEvents<C>, subxt::Error>
+where
+    C: Config,
+{
     // Below we use the low level API to replicate the `wait_for_in_block` behaviour which
     // was removed in subxt 0.33.0. See https://github.com/paritytech/subxt/pull/1237.
     //
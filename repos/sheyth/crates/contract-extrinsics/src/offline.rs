@@ -0,0 +1,630 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use super::{
+    get_account_nonce,
+    wait_for_in_block,
+    ErrorVariant,
+};
+use anyhow::{
+    anyhow,
+    bail,
+    Context,
+    Result,
+};
+use scale::{
+    Compact,
+    Decode,
+    Encode,
+};
+use scale_info::{
+    PortableRegistry,
+    TypeDef,
+};
+use serde::{
+    Deserialize,
+    Serialize,
+};
+use sp_weights::Weight;
+use std::str::FromStr;
+use subxt::{
+    backend::{
+        legacy::LegacyRpcMethods,
+        RuntimeVersion,
+    },
+    blocks::ExtrinsicEvents,
+    config::{
+        DefaultExtrinsicParams,
+        DefaultExtrinsicParamsBuilder,
+        ExtrinsicParams,
+    },
+    tx::{
+        self,
+        SubmittableExtrinsic,
+    },
+    Config,
+    Metadata,
+    OfflineClient,
+    OnlineClient,
+};
+
+/// Dispatches a call through `pallet_multisig` or `pallet_proxy` of the runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallWrapper<AccountId> {
+    /// Wraps the call in `as_multi`, approving it as one signatory of a multisig
+    /// account.
+    Multisig {
+        /// The number of approvals required to dispatch the call.
+        threshold: u16,
+        /// The signatories of the multisig account other than the signer.
+        other_signatories: Vec<AccountId>,
+        /// The timepoint of the first approval, required by all further approvals.
+        timepoint: Option<Timepoint>,
+    },
+    /// Wraps the call in `proxy`, dispatching it on behalf of the proxied account.
+    Proxy {
+        /// The proxied account.
+        real: AccountId,
+        /// The name of the runtime's proxy type to restrict the call to.
+        proxy_type: Option<String>,
+    },
+}
+
+impl<AccountId: Encode> CallWrapper<AccountId> {
+    /// The name of the wrapping call.
+    pub fn call_name(&self) -> &'static str {
+        match self {
+            CallWrapper::Multisig { .. } => "Multisig::as_multi",
+            CallWrapper::Proxy { .. } => "Proxy::proxy",
+        }
+    }
+
+    /// Encodes the wrapping call of `call`, whose weight is `call_weight`.
+    fn wrap(
+        &self,
+        metadata: &Metadata,
+        call: Vec<u8>,
+        call_weight: Weight,
+    ) -> Result<Vec<u8>> {
+        match self {
+            CallWrapper::Multisig {
+                threshold,
+                other_signatories,
+                timepoint,
+            } => {
+                let mut data = call_index(metadata, "Multisig", "as_multi")?.to_vec();
+                threshold.encode_to(&mut data);
+                // The other signatories must be passed in ascending order.
+                let mut signatories: Vec<_> =
+                    other_signatories.iter().map(Encode::encode).collect();
+                signatories.sort();
+                signatories.dedup();
+                Compact(signatories.len() as u32).encode_to(&mut data);
+                signatories.iter().for_each(|s| data.extend_from_slice(s));
+                timepoint.encode_to(&mut data);
+                data.extend(call);
+                call_weight.encode_to(&mut data);
+                Ok(data)
+            }
+            CallWrapper::Proxy { real, proxy_type } => {
+                let mut data = call_index(metadata, "Proxy", "proxy")?.to_vec();
+                let types = metadata.types();
+                let real_type = proxy_argument(metadata, "real")?;
+                encode_account(types, real_type, real, &mut data)?;
+                match proxy_type {
+                    Some(proxy_type) => {
+                        let option = proxy_argument(metadata, "force_proxy_type")?;
+                        data.push(1);
+                        data.push(proxy_type_index(types, option, proxy_type)?);
+                    }
+                    None => data.push(0),
+                }
+                data.extend(call);
+                Ok(data)
+            }
+        }
+    }
+}
+
+impl<AccountId: Encode + Decode + Clone> CallWrapper<AccountId> {
+    /// The account the wrapped call is dispatched from, if the wrapping call is
+    /// dispatched from `origin`.
+    pub fn dispatch_origin(&self, origin: &AccountId) -> Result<AccountId> {
+        match self {
+            CallWrapper::Multisig {
+                threshold,
+                other_signatories,
+                ..
+            } => {
+                let mut signatories: Vec<_> = other_signatories
+                    .iter()
+                    .chain(Some(origin))
+                    .map(Encode::encode)
+                    .collect();
+                signatories.sort();
+                signatories.dedup();
+                multi_account_id(&signatories, *threshold)
+            }
+            CallWrapper::Proxy { real, .. } => Ok(real.clone()),
+        }
+    }
+}
+
+/// The account a call wrapped by `wrappers`, innermost first, is dispatched from when
+/// signed by `signer`.
+///
+/// Dry runs of wrapped calls have to use this origin, as it is the one executing the
+/// call on chain.
+pub fn dispatch_origin<AccountId: Encode + Decode + Clone>(
+    signer: &AccountId,
+    wrappers: &[CallWrapper<AccountId>],
+) -> Result<AccountId> {
+    wrappers
+        .iter()
+        .rev()
+        .try_fold(signer.clone(), |origin, wrapper| {
+            wrapper.dispatch_origin(&origin)
+        })
+}
+
+/// Derives the account of a multisig from its encoded and sorted `signatories`, the
+/// same way `pallet_multisig` does.
+fn multi_account_id<AccountId: Decode>(
+    signatories: &[Vec<u8>],
+    threshold: u16,
+) -> Result<AccountId> {
+    use blake2::digest::{
+        consts::U32,
+        Digest as _,
+    };
+
+    let mut entropy = b"modlpy/utilisuba".to_vec();
+    Compact(signatories.len() as u32).encode_to(&mut entropy);
+    signatories.iter().for_each(|s| entropy.extend_from_slice(s));
+    threshold.encode_to(&mut entropy);
+    let mut blake2 = blake2::Blake2b::<U32>::new();
+    blake2.update(&entropy);
+    let hash = blake2.finalize();
+    // Accounts longer than the hash are padded with zeros, like `TrailingZeroInput` does.
+    let padded = [hash.as_slice(), &[0; 32]].concat();
+    AccountId::decode(&mut &padded[..]).context("Failed to derive the multisig account")
+}
+
+/// The block height and extrinsic index of the first approval of a multisig call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct Timepoint {
+    pub height: u32,
+    pub index: u32,
+}
+
+impl FromStr for Timepoint {
+    type Err = anyhow::Error;
+
+    /// Parses a timepoint in the format `<height>:<index>`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (height, index) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Expected a timepoint as `<height>:<index>`"))?;
+        Ok(Self {
+            height: height.parse().context("Invalid timepoint height")?,
+            index: index.parse().context("Invalid timepoint index")?,
+        })
+    }
+}
+
+/// Returns the pallet and call index of a call of the runtime.
+fn call_index(metadata: &Metadata, pallet: &str, call: &str) -> Result<[u8; 2]> {
+    let pallet_metadata = metadata
+        .pallet_by_name(pallet)
+        .ok_or_else(|| anyhow!("The runtime does not provide the `{pallet}` pallet"))?;
+    let variant = pallet_metadata
+        .call_variant_by_name(call)
+        .ok_or_else(|| anyhow!("The `{pallet}` pallet has no `{call}` call"))?;
+    Ok([pallet_metadata.index(), variant.index])
+}
+
+/// Returns the type of the argument `name` of `Proxy::proxy` in the runtime.
+fn proxy_argument(metadata: &Metadata, name: &str) -> Result<u32> {
+    metadata
+        .pallet_by_name("Proxy")
+        .and_then(|pallet| pallet.call_variant_by_name("proxy"))
+        .ok_or_else(|| anyhow!("The runtime does not provide `Proxy::proxy`"))?
+        .fields
+        .iter()
+        .find(|field| field.name.as_deref() == Some(name))
+        .map(|field| field.ty.id)
+        .ok_or_else(|| anyhow!("`Proxy::proxy` of the runtime has no `{name}` argument"))
+}
+
+/// Encodes `account` as a value of the runtime type `ty`, which is either the account
+/// id itself or a lookup source like `MultiAddress`, whose `Id` variant holds it.
+fn encode_account<AccountId: Encode>(
+    types: &PortableRegistry,
+    ty: u32,
+    account: &AccountId,
+    out: &mut Vec<u8>,
+) -> Result<()> {
+    let account_type = types
+        .resolve(ty)
+        .ok_or_else(|| anyhow!("Failed to resolve the account type of the runtime"))?;
+    if let TypeDef::Variant(def) = &account_type.type_def {
+        let id = def
+            .variants
+            .iter()
+            .find(|variant| variant.name == "Id")
+            .ok_or_else(|| anyhow!("The account lookup of the runtime has no `Id`"))?;
+        out.push(id.index);
+    }
+    account.encode_to(out);
+    Ok(())
+}
+
+/// Returns the index of the proxy type named `name` in `option`, the runtime type of
+/// an optional proxy type.
+fn proxy_type_index(types: &PortableRegistry, option: u32, name: &str) -> Result<u8> {
+    let proxy_type = types
+        .resolve(option)
+        .and_then(|option| option.type_params.first())
+        .and_then(|param| param.ty)
+        .and_then(|ty| types.resolve(ty.id))
+        .ok_or_else(|| anyhow!("Failed to resolve the proxy type of the runtime"))?;
+    let TypeDef::Variant(def) = &proxy_type.type_def else {
+        bail!("The proxy type of the runtime is not an enum")
+    };
+    def.variants
+        .iter()
+        .find(|variant| variant.name == name)
+        .map(|variant| variant.index)
+        .ok_or_else(|| {
+            let names: Vec<_> = def.variants.iter().map(|v| v.name.as_str()).collect();
+            anyhow!(
+                "Unknown proxy type `{name}`, expected one of: {}",
+                names.join(", ")
+            )
+        })
+}
+
+/// A call which has already been encoded.
+struct RawCall(Vec<u8>);
+
+impl tx::Payload for RawCall {
+    fn encode_call_data_to(
+        &self,
+        _metadata: &Metadata,
+        out: &mut Vec<u8>,
+    ) -> core::result::Result<(), subxt::Error> {
+        out.extend_from_slice(&self.0);
+        Ok(())
+    }
+}
+
+/// The leading field of `RuntimeDispatchInfo`, as returned by
+/// `TransactionPaymentApi_query_info`.
+#[derive(Decode)]
+struct DispatchInfo {
+    weight: Weight,
+}
+
+/// Queries the weight of a call from the runtime.
+async fn query_weight<C: Config>(
+    client: &OnlineClient<C>,
+    rpc: &LegacyRpcMethods<C>,
+    call: &[u8],
+) -> Result<Weight> {
+    let extrinsic = client.tx().create_unsigned(&RawCall(call.to_vec()))?;
+    let mut params = extrinsic.encoded().to_vec();
+    (params.len() as u32).encode_to(&mut params);
+    let bytes = rpc
+        .state_call("TransactionPaymentApi_query_info", Some(&params), None)
+        .await?;
+    Ok(DispatchInfo::decode(&mut bytes.as_ref())?.weight)
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn from_hex(input: &str) -> Result<Vec<u8>> {
+    hex::decode(input.trim_start_matches("0x")).context("Invalid hex encoding")
+}
+
+/// An extrinsic prepared for signing on a machine without access to the chain.
+///
+/// Besides the call it holds everything required to sign it offline: the nonce of
+/// the signer and the genesis hash, runtime version and metadata of the chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedExtrinsic {
+    /// The call, e.g. `Multisig::as_multi(Contracts::call)`.
+    pub description: String,
+    /// The encoded account which has to sign the extrinsic.
+    pub signer: String,
+    /// The encoded call.
+    pub call_data: String,
+    /// The weight of the call, as estimated by the runtime.
+    pub estimated_weight: Weight,
+    /// The result of dry running the contracts call, if any.
+    pub dry_run: Option<serde_json::Value>,
+    pub nonce: u64,
+    pub genesis_hash: String,
+    pub spec_version: u32,
+    pub transaction_version: u32,
+    /// The encoded metadata of the runtime.
+    pub metadata: String,
+}
+
+impl UnsignedExtrinsic {
+    /// Prepares `call` to be signed by `account`, wrapping it by each of `wrappers`
+    /// in turn.
+    pub async fn prepare<C, Call>(
+        client: &OnlineClient<C>,
+        rpc: &LegacyRpcMethods<C>,
+        call: &Call,
+        description: &str,
+        account: &C::AccountId,
+        wrappers: &[CallWrapper<C::AccountId>],
+        dry_run: Option<serde_json::Value>,
+    ) -> Result<Self>
+    where
+        C: Config,
+        Call: tx::Payload,
+    {
+        let metadata = client.metadata();
+        let mut call_data = client.tx().call_data(call)?;
+        let mut description = description.to_string();
+        for wrapper in wrappers {
+            let weight = query_weight(client, rpc, &call_data).await?;
+            call_data = wrapper.wrap(&metadata, call_data, weight)?;
+            description = format!("{}({description})", wrapper.call_name());
+        }
+        let estimated_weight = query_weight(client, rpc, &call_data).await?;
+        let nonce = get_account_nonce(client, rpc, account).await?;
+        let runtime_metadata = rpc.state_get_metadata(None).await?.into_raw();
+        let runtime_version = client.runtime_version();
+
+        Ok(Self {
+            description,
+            signer: to_hex(&account.encode()),
+            call_data: to_hex(&call_data),
+            estimated_weight,
+            dry_run,
+            nonce,
+            genesis_hash: to_hex(&client.genesis_hash().encode()),
+            spec_version: runtime_version.spec_version,
+            transaction_version: runtime_version.transaction_version,
+            metadata: to_hex(&runtime_metadata),
+        })
+    }
+
+    /// Signs the extrinsic without connecting to the chain.
+    ///
+    /// The extrinsic is immortal, so it stays valid however long it takes to get the
+    /// signature back to a machine submitting it, as long as the nonce is unused.
+    pub fn sign<C, Signer>(&self, signer: &Signer) -> Result<SignedExtrinsic>
+    where
+        C: Config,
+        Signer: tx::Signer<C>,
+        <C::ExtrinsicParams as ExtrinsicParams<C>>::Params:
+            From<<DefaultExtrinsicParams<C> as ExtrinsicParams<C>>::Params>,
+    {
+        if to_hex(&signer.account_id().encode()) != self.signer {
+            bail!(
+                "The extrinsic has to be signed by the account {}",
+                self.signer
+            )
+        }
+        let genesis_hash = C::Hash::decode(&mut &from_hex(&self.genesis_hash)?[..])
+            .context("Invalid genesis hash")?;
+        let metadata = Metadata::decode(&mut &from_hex(&self.metadata)?[..])
+            .context("Invalid runtime metadata")?;
+        let runtime_version = RuntimeVersion {
+            spec_version: self.spec_version,
+            transaction_version: self.transaction_version,
+        };
+        let client = OfflineClient::<C>::new(genesis_hash, runtime_version, metadata);
+
+        let params = DefaultExtrinsicParamsBuilder::new()
+            .nonce(self.nonce)
+            .build();
+        let call = RawCall(from_hex(&self.call_data)?);
+        let extrinsic = client
+            .tx()
+            .create_signed_offline(&call, signer, params.into())?;
+
+        Ok(SignedExtrinsic {
+            description: self.description.clone(),
+            genesis_hash: self.genesis_hash.clone(),
+            extrinsic: to_hex(extrinsic.encoded()),
+        })
+    }
+}
+
+/// A signed extrinsic, ready to be submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedExtrinsic {
+    /// The call, e.g. `Multisig::as_multi(Contracts::call)`.
+    pub description: String,
+    pub genesis_hash: String,
+    /// The encoded extrinsic.
+    pub extrinsic: String,
+}
+
+impl SignedExtrinsic {
+    /// Submits the extrinsic and waits for it to be included into a block.
+    pub async fn submit<C: Config>(
+        &self,
+        client: &OnlineClient<C>,
+    ) -> Result<ExtrinsicEvents<C>, ErrorVariant> {
+        if to_hex(&client.genesis_hash().encode()) != self.genesis_hash {
+            return Err(anyhow!(
+                "The extrinsic was signed for the chain with genesis hash {}",
+                self.genesis_hash
+            )
+            .into())
+        }
+        let extrinsic =
+            SubmittableExtrinsic::from_bytes(client.clone(), from_hex(&self.extrinsic)?);
+        let progress = extrinsic.submit_and_watch().await?;
+        Ok(wait_for_in_block(progress).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timepoint_parses() {
+        assert_eq!(
+            "1024:3".parse::<Timepoint>().unwrap(),
+            Timepoint {
+                height: 1024,
+                index: 3
+            }
+        );
+        assert!("1024".parse::<Timepoint>().is_err());
+        assert!("a:3".parse::<Timepoint>().is_err());
+    }
+
+    #[test]
+    fn dry_runs_use_the_account_dispatching_the_wrapped_call() {
+        let signer = [1u8; 32];
+        let real = [2u8; 32];
+        let multisig = CallWrapper::Multisig {
+            threshold: 2,
+            other_signatories: vec![[3u8; 32]],
+            timepoint: None,
+        };
+        let proxy = CallWrapper::Proxy {
+            real,
+            proxy_type: None,
+        };
+
+        assert_eq!(dispatch_origin(&signer, &[]).unwrap(), signer);
+        assert_eq!(dispatch_origin(&signer, &[proxy.clone()]).unwrap(), real);
+
+        let multi = dispatch_origin(&signer, &[multisig.clone()]).unwrap();
+        let multi_of_real = dispatch_origin(&signer, &[multisig.clone(), proxy]).unwrap();
+        assert_ne!(multi, signer);
+        assert_ne!(multi_of_real, multi);
+        // The order of the signatories does not matter.
+        let reordered = CallWrapper::Multisig {
+            threshold: 2,
+            other_signatories: vec![signer],
+            timepoint: None,
+        };
+        assert_eq!(dispatch_origin(&[3u8; 32], &[reordered]).unwrap(), multi);
+    }
+
+    /// A lookup source like `MultiAddress`, with its `Id` variant at another index.
+    #[derive(scale_info::TypeInfo)]
+    #[allow(dead_code)]
+    enum Lookup {
+        #[codec(index = 2)]
+        Index(u32),
+        #[codec(index = 5)]
+        Id([u8; 32]),
+    }
+
+    #[derive(scale_info::TypeInfo)]
+    #[allow(dead_code)]
+    enum ProxyType {
+        Any,
+        #[codec(index = 3)]
+        Staking,
+    }
+
+    #[derive(scale_info::TypeInfo)]
+    #[allow(dead_code)]
+    enum NoId {
+        Index(u32),
+    }
+
+    fn registry_with<T: scale_info::TypeInfo + 'static>() -> (PortableRegistry, u32) {
+        let mut registry = scale_info::Registry::new();
+        let ty = registry.register_type(&scale_info::meta_type::<T>()).id;
+        (registry.into(), ty)
+    }
+
+    #[test]
+    fn accounts_are_encoded_as_their_runtime_type() {
+        let account = [7u8; 32];
+
+        let (types, ty) = registry_with::<Lookup>();
+        let mut out = Vec::new();
+        encode_account(&types, ty, &account, &mut out).unwrap();
+        assert_eq!(out, [&[5][..], &account].concat());
+
+        let (types, ty) = registry_with::<[u8; 32]>();
+        let mut out = Vec::new();
+        encode_account(&types, ty, &account, &mut out).unwrap();
+        assert_eq!(out, account);
+
+        let (types, ty) = registry_with::<NoId>();
+        assert!(encode_account(&types, ty, &account, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn proxy_types_are_resolved_by_name() {
+        let (types, ty) = registry_with::<Option<ProxyType>>();
+        assert_eq!(proxy_type_index(&types, ty, "Any").unwrap(), 0);
+        assert_eq!(proxy_type_index(&types, ty, "Staking").unwrap(), 3);
+        assert!(proxy_type_index(&types, ty, "Governance").is_err());
+    }
+
+    #[test]
+    fn unsigned_extrinsic_roundtrips_through_json() {
+        let unsigned = UnsignedExtrinsic {
+            description: "Proxy::proxy(Contracts::call)".into(),
+            signer: to_hex(&[1; 32]),
+            call_data: to_hex(&[9, 6, 0]),
+            estimated_weight: Weight::from_parts(1_000, 10),
+            dry_run: Some(serde_json::json!({ "reverted": false })),
+            nonce: 7,
+            genesis_hash: to_hex(&[2; 32]),
+            spec_version: 1,
+            transaction_version: 1,
+            metadata: to_hex(b"meta"),
+        };
+        let json = serde_json::to_string(&unsigned).unwrap();
+
+        assert_eq!(
+            serde_json::from_str::<UnsignedExtrinsic>(&json).unwrap(),
+            unsigned
+        );
+        assert_eq!(from_hex(&unsigned.call_data).unwrap(), vec![9, 6, 0]);
+    }
+}
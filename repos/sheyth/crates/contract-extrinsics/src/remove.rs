@@ -38,8 +38,10 @@
 use super::{
     events::CodeRemoved,
     submit_extrinsic,
+    CallWrapper,
     ContractMessageTranscoder,
     ErrorVariant,
+    UnsignedExtrinsic,
 };
 use crate::{
     extrinsic_calls::RemoveCode,
@@ -186,6 +188,26 @@ where
         })
     }
 
+    /// Prepares the code removal to be signed offline, optionally dispatched through a
+    /// multisig or proxy by `wrappers`.
+    pub async fn remove_code_unsigned(
+        &self,
+        wrappers: &[CallWrapper<C::AccountId>],
+    ) -> Result<UnsignedExtrinsic> {
+        let call = RemoveCode::new(self.final_code_hash).build();
+
+        UnsignedExtrinsic::prepare(
+            &self.client,
+            &self.rpc,
+            &call,
+            "Contracts::remove_code",
+            &self.opts.signer().account_id(),
+            wrappers,
+            None,
+        )
+        .await
+    }
+
     /// Returns the final code hash.
     pub fn final_code_hash(&self) -> C::Hash {
         self.final_code_hash
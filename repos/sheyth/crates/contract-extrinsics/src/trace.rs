@@ -0,0 +1,559 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Decoding of the nested call trees returned by the `ContractsTraceApi` runtime API.
+
+use super::{
+    BalanceVariant,
+    TokenMetadata,
+};
+use crate::DEFAULT_KEY_COL_WIDTH;
+
+use anyhow::Result;
+use colored::Colorize as _;
+use contract_transcode::{
+    ContractMessageTranscoder,
+    Hex,
+    Value,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+use serde::Serialize;
+use sp_weights::Weight;
+use std::{
+    fmt::{
+        Display,
+        Write,
+    },
+    str::FromStr,
+};
+
+// A copy of the types returned by `ContractsTraceApi::trace_call`, required for RPC calls.
+
+/// How a traced frame was entered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, Serialize)]
+pub enum CallKind {
+    /// A regular message call.
+    Call,
+    /// A message executed with the code of another contract in the caller's context.
+    DelegateCall,
+    /// A constructor call of a newly instantiated contract.
+    Instantiate,
+    /// A message executed against the caller's own account which did not return
+    /// successfully, so that it is unknown whether it was reentrant or delegated.
+    ReentrantOrDelegateCall,
+}
+
+impl Display for CallKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallKind::Call => f.write_str("Call"),
+            CallKind::DelegateCall => f.write_str("Delegate call"),
+            CallKind::Instantiate => f.write_str("Instantiate"),
+            CallKind::ReentrantOrDelegateCall => {
+                f.write_str("Reentrant or delegate call")
+            }
+        }
+    }
+}
+
+/// An event emitted by a contract while executing a traced frame.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct TracedEvent<Hash> {
+    /// The event topics, the first one being the event signature for non-anonymous
+    /// events.
+    pub topics: Vec<Hash>,
+    /// The SCALE encoded event data.
+    pub data: Vec<u8>,
+}
+
+/// A single frame of a traced contract call, together with the frames it called into.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct CallTrace<AccountId, Balance, Hash> {
+    pub kind: CallKind,
+    /// The contract whose storage the frame executed against.
+    pub contract: AccountId,
+    /// The code that was executed, if it could be determined.
+    pub code_hash: Option<Hash>,
+    /// The input data, starting with the selector.
+    pub input: Vec<u8>,
+    /// The value transferred to the contract when entering the frame.
+    pub value: Balance,
+    /// Gas consumed by the frame including nested frames, only known for the outermost
+    /// call.
+    pub gas_consumed: Option<Weight>,
+    /// The returned data, empty if the frame trapped.
+    pub output: Vec<u8>,
+    /// The frame returned with the revert flag set.
+    pub reverted: bool,
+    /// The frame trapped or ran out of gas without returning.
+    pub trapped: bool,
+    /// Storage deposit charged to the caller for the contract's storage.
+    pub storage_deposit_charged: Balance,
+    /// Storage deposit refunded to the caller for freed contract storage.
+    pub storage_deposit_refunded: Balance,
+    /// Events emitted by this frame, not including those of nested frames.
+    pub events: Vec<TracedEvent<Hash>>,
+    /// Nested frames in the order they were entered.
+    pub calls: Vec<CallTrace<AccountId, Balance, Hash>>,
+}
+
+/// A frame of a call trace, decoded with the metadata of the contracts it executed.
+#[derive(Debug, Serialize)]
+pub struct DecodedCallTrace {
+    pub kind: CallKind,
+    pub contract: String,
+    pub code_hash: Option<String>,
+    /// The message or constructor, if the metadata of the executed code is known.
+    pub label: Option<String>,
+    /// The decoded message or constructor with its arguments, or the raw input.
+    pub input: Value,
+    pub value: u128,
+    /// Gas consumed by the frame including nested frames.
+    ///
+    /// `pallet_contracts` meters nested frames against their caller without reporting
+    /// it, so this is only known for the outermost call.
+    pub gas_consumed: Option<Weight>,
+    pub storage_deposit_charged: u128,
+    pub storage_deposit_refunded: u128,
+    /// The decoded return value or revert reason, or the raw output.
+    pub output: Value,
+    pub reverted: bool,
+    pub trapped: bool,
+    pub events: Vec<Value>,
+    pub calls: Vec<DecodedCallTrace>,
+}
+
+/// Decodes call traces with the metadata of the contracts involved.
+///
+/// Frames are decoded with the metadata registered for their code hash. Frames of the
+/// called contract fall back to its own metadata, and frames whose code is unknown, like
+/// reverted delegate calls, are decoded with the first metadata matching the selector.
+pub struct TraceDecoder<'a> {
+    contract: Vec<u8>,
+    transcoder: &'a ContractMessageTranscoder,
+    code: Vec<([u8; 32], ContractMessageTranscoder)>,
+}
+
+impl<'a> TraceDecoder<'a> {
+    /// Creates a decoder for calls into `contract`, described by `transcoder`.
+    pub fn new<AccountId: Encode>(
+        contract: &AccountId,
+        transcoder: &'a ContractMessageTranscoder,
+    ) -> Self {
+        Self {
+            contract: contract.encode(),
+            transcoder,
+            code: Vec::new(),
+        }
+    }
+
+    /// Registers the metadata of the contract code with the given hash.
+    pub fn with_code(
+        mut self,
+        code_hash: [u8; 32],
+        transcoder: ContractMessageTranscoder,
+    ) -> Self {
+        self.code.push((code_hash, transcoder));
+        self
+    }
+
+    /// Decodes a call trace.
+    pub fn decode<AccountId, Balance, Hash>(
+        &self,
+        trace: CallTrace<AccountId, Balance, Hash>,
+    ) -> Result<DecodedCallTrace>
+    where
+        AccountId: Encode + Display,
+        Balance: Into<u128>,
+        Hash: AsRef<[u8]>,
+    {
+        let decoded_input = self.candidates(&trace).into_iter().find_map(|transcoder| {
+            let input = &mut &trace.input[..];
+            let decoded = match trace.kind {
+                CallKind::Instantiate => transcoder.decode_contract_constructor(input),
+                CallKind::Call
+                | CallKind::DelegateCall
+                | CallKind::ReentrantOrDelegateCall => {
+                    transcoder.decode_contract_message(input)
+                }
+            };
+            decoded.ok().map(|value| (transcoder, value))
+        });
+
+        let transcoder = decoded_input.as_ref().map(|(transcoder, _)| *transcoder);
+        let (label, input, output) = match decoded_input {
+            Some((transcoder, input)) => {
+                let label = match &input {
+                    Value::Map(map) => map.ident(),
+                    _ => None,
+                };
+                let output = match (&label, trace.trapped) {
+                    (Some(label), false) => {
+                        let output = &mut &trace.output[..];
+                        match trace.kind {
+                            CallKind::Instantiate => {
+                                transcoder.decode_constructor_return(label, output)
+                            }
+                            CallKind::Call
+                            | CallKind::DelegateCall
+                            | CallKind::ReentrantOrDelegateCall => {
+                                transcoder.decode_message_return(label, output)
+                            }
+                        }
+                        .or_else(|_| raw_value(&trace.output))?
+                    }
+                    _ => raw_value(&trace.output)?,
+                };
+                (label, input, output)
+            }
+            None => (None, raw_value(&trace.input)?, raw_value(&trace.output)?),
+        };
+        let events = trace
+            .events
+            .iter()
+            .map(|event| decode_event(transcoder, event))
+            .collect::<Result<_>>()?;
+
+        Ok(DecodedCallTrace {
+            kind: trace.kind,
+            contract: trace.contract.to_string(),
+            code_hash: trace
+                .code_hash
+                .as_ref()
+                .map(|hash| format!("0x{}", hex::encode(hash))),
+            label,
+            input,
+            value: trace.value.into(),
+            gas_consumed: trace.gas_consumed,
+            storage_deposit_charged: trace.storage_deposit_charged.into(),
+            storage_deposit_refunded: trace.storage_deposit_refunded.into(),
+            output,
+            reverted: trace.reverted,
+            trapped: trace.trapped,
+            events,
+            calls: trace
+                .calls
+                .into_iter()
+                .map(|call| self.decode(call))
+                .collect::<Result<_>>()?,
+        })
+    }
+
+    /// Returns the transcoders to try for decoding the frame, in order of preference.
+    fn candidates<AccountId: Encode, Balance, Hash: AsRef<[u8]>>(
+        &self,
+        trace: &CallTrace<AccountId, Balance, Hash>,
+    ) -> Vec<&ContractMessageTranscoder> {
+        match &trace.code_hash {
+            Some(code_hash) => {
+                let known = self
+                    .code
+                    .iter()
+                    .find(|(hash, _)| hash.as_slice() == code_hash.as_ref())
+                    .map(|(_, transcoder)| transcoder);
+                let called =
+                    (matches!(trace.kind, CallKind::Call | CallKind::Instantiate)
+                        && trace.contract.encode() == self.contract)
+                        .then_some(self.transcoder);
+                known.or(called).into_iter().collect()
+            }
+            None => {
+                std::iter::once(self.transcoder)
+                    .chain(self.code.iter().map(|(_, transcoder)| transcoder))
+                    .collect()
+            }
+        }
+    }
+}
+
+impl DecodedCallTrace {
+    /// Returns the call trace in JSON format.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Displays the call tree in a human readable format.
+    pub fn display(&self, token_metadata: &TokenMetadata) -> Result<String> {
+        let mut out = format!(
+            "{:>width$}\n",
+            "Trace".bright_purple().bold(),
+            width = DEFAULT_KEY_COL_WIDTH
+        );
+        self.write_tree(&mut out, "", true, token_metadata)?;
+        Ok(out)
+    }
+
+    fn write_tree(
+        &self,
+        out: &mut String,
+        indent: &str,
+        last: bool,
+        token_metadata: &TokenMetadata,
+    ) -> Result<()> {
+        let (branch, nested) = if last {
+            ("└─ ", "   ")
+        } else {
+            ("├─ ", "│  ")
+        };
+        let nested = format!("{indent}{nested}");
+        let _ = writeln!(
+            out,
+            "{indent}{branch}{} {} ➜ {}",
+            self.kind.to_string().bright_green().bold(),
+            self.contract.bright_white(),
+            self.input.to_string().bright_white().bold(),
+        );
+
+        let balance = |value: u128| -> Result<String> {
+            Ok(BalanceVariant::<u128>::from(value, Some(token_metadata))?.to_string())
+        };
+        if self.value != 0 {
+            let _ = writeln!(out, "{nested}  value: {}", balance(self.value)?);
+        }
+        if let Some(gas_consumed) = self.gas_consumed {
+            let _ = writeln!(out, "{nested}  gas consumed: {gas_consumed}");
+        }
+        if self.storage_deposit_charged != 0 {
+            let _ = writeln!(
+                out,
+                "{nested}  storage deposit charged: {}",
+                balance(self.storage_deposit_charged)?
+            );
+        }
+        if self.storage_deposit_refunded != 0 {
+            let _ = writeln!(
+                out,
+                "{nested}  storage deposit refunded: {}",
+                balance(self.storage_deposit_refunded)?
+            );
+        }
+        for event in &self.events {
+            let _ = writeln!(out, "{nested}  {}: {event}", "event".bright_purple());
+        }
+        if self.trapped {
+            let _ = writeln!(out, "{nested}  {}", "trapped".red().bold());
+        } else if self.reverted {
+            let _ = writeln!(
+                out,
+                "{nested}  {}: {}",
+                "reverted".red().bold(),
+                self.output
+            );
+        } else {
+            let _ = writeln!(out, "{nested}  returned: {}", self.output);
+        }
+
+        for (i, call) in self.calls.iter().enumerate() {
+            call.write_tree(out, &nested, i + 1 == self.calls.len(), token_metadata)?;
+        }
+        Ok(())
+    }
+}
+
+/// Decodes a contract event by its signature topic, displaying its data as hex if the
+/// event is anonymous or not found in the metadata.
+fn decode_event<Hash: AsRef<[u8]>>(
+    transcoder: Option<&ContractMessageTranscoder>,
+    event: &TracedEvent<Hash>,
+) -> Result<Value> {
+    if let (Some(transcoder), Some(topic)) = (transcoder, event.topics.first()) {
+        // The transcoder expects the data to be prefixed with its length.
+        let data = event.data.encode();
+        if let Ok(value) = transcoder.decode_contract_event(topic, &mut &data[..]) {
+            return Ok(value)
+        }
+    }
+    raw_value(&event.data)
+}
+
+fn raw_value(bytes: &[u8]) -> Result<Value> {
+    Ok(Value::Hex(Hex::from_str(&hex::encode(bytes))?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::{
+        metadata::{
+            layout::{
+                Layout::{
+                    self,
+                    Struct,
+                },
+                LayoutKey,
+                RootLayout,
+            },
+            ContractSpec,
+            InkProject,
+            LangError,
+            MessageSpec,
+            ReturnTypeSpec,
+            TypeSpec,
+        },
+        storage::traits::{
+            Storable,
+            StorageLayout,
+        },
+        MessageResult,
+    };
+
+    type Trace = CallTrace<u8, u128, [u8; 32]>;
+
+    const INC: [u8; 4] = [231, 208, 89, 15];
+
+    fn transcoder() -> ContractMessageTranscoder {
+        #[derive(scale_info::TypeInfo, StorageLayout, Storable)]
+        struct Data {
+            a: i32,
+        }
+
+        let Struct(data_layout) = <Data as StorageLayout>::layout(&0u32) else {
+            panic!("Layout shall be created");
+        };
+        let layout: Layout = RootLayout::new(
+            LayoutKey::from(0u32),
+            data_layout,
+            scale_info::meta_type::<Data>(),
+        )
+        .into();
+        let spec = ContractSpec::new()
+            .constructors(Vec::new())
+            .messages(vec![MessageSpec::from_label("inc")
+                .selector(INC)
+                .mutates(true)
+                .payable(true)
+                .args(Vec::new())
+                .returns(ReturnTypeSpec::new(TypeSpec::with_name_str::<
+                    MessageResult<()>,
+                >(
+                    "ink_primitives::MessageResult"
+                )))
+                .done()])
+            .events(Vec::new())
+            .lang_error(TypeSpec::with_name_segs::<LangError, _>(
+                ["ink", "LangError"].into_iter().map(AsRef::as_ref),
+            ))
+            .done();
+        ContractMessageTranscoder::new(InkProject::new(layout, spec))
+    }
+
+    fn trace(contract: u8, input: &[u8], calls: Vec<Trace>) -> Trace {
+        CallTrace {
+            kind: CallKind::Call,
+            contract,
+            code_hash: None,
+            input: input.to_vec(),
+            value: 0,
+            gas_consumed: None,
+            output: MessageResult::Ok(()).encode(),
+            reverted: false,
+            trapped: false,
+            storage_deposit_charged: 0,
+            storage_deposit_refunded: 0,
+            events: Vec::new(),
+            calls,
+        }
+    }
+
+    #[test]
+    fn trace_roundtrips_through_scale() {
+        let trace = trace(1, &INC, vec![trace(2, &INC, Vec::new())]);
+        let encoded = trace.encode();
+        assert_eq!(Trace::decode(&mut &encoded[..]).unwrap(), trace);
+    }
+
+    #[test]
+    fn frames_are_decoded_with_known_metadata() {
+        let transcoder = transcoder();
+        let decoder =
+            TraceDecoder::new(&1u8, &transcoder).with_code([7; 32], transcoder());
+        let mut known = trace(2, &INC, Vec::new());
+        known.code_hash = Some([7; 32]);
+        let mut unknown = trace(3, &INC, Vec::new());
+        unknown.code_hash = Some([8; 32]);
+        unknown.reverted = true;
+        unknown.events.push(TracedEvent {
+            topics: vec![[1; 32]],
+            data: vec![0x2a],
+        });
+
+        let decoded = decoder
+            .decode(trace(1, &INC, vec![known, unknown]))
+            .unwrap();
+
+        assert_eq!(decoded.label.as_deref(), Some("inc"));
+        assert_eq!(decoded.output.to_string(), "Ok(())");
+        assert_eq!(decoded.calls[0].label.as_deref(), Some("inc"));
+        assert_eq!(
+            decoded.calls[0].code_hash,
+            Some(format!("0x{}", "07".repeat(32)))
+        );
+        let unknown = &decoded.calls[1];
+        assert_eq!(unknown.label, None);
+        assert_eq!(unknown.input.to_string(), "0xe7d0590f");
+        assert!(unknown.reverted);
+        assert_eq!(unknown.events[0].to_string(), "0x2a");
+    }
+
+    #[test]
+    fn display_renders_nested_calls() {
+        let transcoder = transcoder();
+        let decoder = TraceDecoder::new(&1u8, &transcoder);
+        let mut trapped = trace(3, &[0xde, 0xad, 0xbe, 0xef], Vec::new());
+        trapped.trapped = true;
+        let mut root = trace(1, &INC, vec![trace(1, &INC, Vec::new()), trapped]);
+        root.gas_consumed = Some(Weight::from_parts(10, 20));
+        let decoded = decoder.decode(root).unwrap();
+        let token_metadata = TokenMetadata {
+            token_decimals: 12,
+            symbol: String::from("SEE"),
+        };
+
+        colored::control::set_override(false);
+        let out = decoded.display(&token_metadata).unwrap();
+        let lines: Vec<_> = out.lines().skip(1).collect();
+        assert_eq!(lines[0], "└─ Call 1 ➜ inc");
+        assert!(lines[1].contains("gas consumed"));
+        assert_eq!(lines[2], "     returned: Ok(())");
+        assert_eq!(lines[3], "   ├─ Call 1 ➜ inc");
+        assert_eq!(lines[5], "   └─ Call 3 ➜ 0xdeadbeef");
+        assert_eq!(lines[6], "        trapped");
+    }
+}
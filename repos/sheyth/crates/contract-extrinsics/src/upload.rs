@@ -40,7 +40,9 @@ use super::{
     pallet_contracts_primitives::CodeUploadResult,
     state_call,
     submit_extrinsic,
+    CallWrapper,
     ErrorVariant,
+    UnsignedExtrinsic,
     WasmCode,
 };
 use crate::{
@@ -184,6 +186,32 @@ where
         })
     }
 
+    /// Prepares the code upload to be signed offline, optionally dispatched through a
+    /// multisig or proxy by `wrappers`.
+    pub async fn upload_code_unsigned(
+        &self,
+        wrappers: &[CallWrapper<C::AccountId>],
+        dry_run: Option<serde_json::Value>,
+    ) -> Result<UnsignedExtrinsic> {
+        let call = UploadCode::new(
+            self.code.clone(),
+            self.opts.storage_deposit_limit(),
+            Determinism::Enforced,
+        )
+        .build();
+
+        UnsignedExtrinsic::prepare(
+            &self.client,
+            &self.rpc,
+            &call,
+            "Contracts::upload_code",
+            &self.opts.signer().account_id(),
+            wrappers,
+            dry_run,
+        )
+        .await
+    }
+
     /// Returns the extrinsic options.
     pub fn opts(&self) -> &ExtrinsicOpts<C, E, Signer> {
         &self.opts
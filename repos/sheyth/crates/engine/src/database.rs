@@ -35,12 +35,16 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::types::Balance;
+use crate::types::{
+    Balance,
+    ContractCode,
+};
 use scale::KeyedVec;
 use std::collections::HashMap;
 
 const BALANCE_OF: &[u8] = b"balance:";
 const STORAGE_OF: &[u8] = b"contract-storage:";
+const CODE_HASH_OF: &[u8] = b"code-hash:";
 
 /// Returns the database key under which to find the balance for account `who`.
 pub fn balance_of_key(who: &[u8]) -> [u8; 32] {
@@ -50,6 +54,14 @@ pub fn balance_of_key(who: &[u8]) -> [u8; 32] {
     hashed_key
 }
 
+/// Returns the database key under which to find the code hash for account `who`.
+pub fn code_hash_of_key(who: &[u8]) -> [u8; 32] {
+    let keyed = who.to_vec().to_keyed_vec(CODE_HASH_OF);
+    let mut hashed_key: [u8; 32] = [0; 32];
+    super::hashing::blake2b_256(&keyed[..], &mut hashed_key);
+    hashed_key
+}
+
 /// Returns the database key under which to find the balance for account `who`.
 pub fn storage_of_contract_key(who: &[u8], key: &[u8]) -> [u8; 32] {
     let keyed = who.to_vec().to_keyed_vec(key).to_keyed_vec(STORAGE_OF);
@@ -62,9 +74,11 @@ pub fn storage_of_contract_key(who: &[u8], key: &[u8]) -> [u8; 32] {
 ///
 /// Everything is stored in here: accounts, balances, contract storage, etc..
 /// Just like in Substrate a prefix hash is computed for every contract.
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Database {
     hmap: HashMap<Vec<u8>, Vec<u8>>,
+    /// Registered contract code, by code hash.
+    code: HashMap<Vec<u8>, ContractCode>,
 }
 
 impl Database {
@@ -72,6 +86,7 @@ impl Database {
     pub fn new() -> Self {
         Database {
             hmap: HashMap::new(),
+            code: HashMap::new(),
         }
     }
 
@@ -128,9 +143,10 @@ impl Database {
         self.hmap.insert(key, value)
     }
 
-    /// Clears the database, removing all key-value pairs.
+    /// Clears the database, removing all key-value pairs and registered code.
     pub fn clear(&mut self) {
         self.hmap.clear();
+        self.code.clear();
     }
 
     /// Returns the balance of `account_id`, if available.
@@ -151,6 +167,28 @@ impl Database {
             .and_modify(|v| *v = encoded_balance.clone())
             .or_insert(encoded_balance);
     }
+
+    /// Returns the code hash of the contract at `account_id`, if it is a contract.
+    pub fn get_code_hash(&self, account_id: &[u8]) -> Option<Vec<u8>> {
+        let hashed_key = code_hash_of_key(account_id);
+        self.get(&hashed_key).cloned()
+    }
+
+    /// Sets the code hash of the contract at `account_id`.
+    pub fn set_code_hash(&mut self, account_id: &[u8], code_hash: &[u8]) {
+        let hashed_key = code_hash_of_key(account_id);
+        self.hmap.insert(hashed_key.to_vec(), code_hash.to_vec());
+    }
+
+    /// Returns the contract code registered under `code_hash`, if any.
+    pub fn get_code(&self, code_hash: &[u8]) -> Option<ContractCode> {
+        self.code.get(code_hash).copied()
+    }
+
+    /// Registers `code` under `code_hash`, replacing any code registered before.
+    pub fn insert_code(&mut self, code_hash: &[u8], code: ContractCode) {
+        self.code.insert(code_hash.to_vec(), code);
+    }
 }
 
 #[cfg(test)]
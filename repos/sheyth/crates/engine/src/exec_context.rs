@@ -68,6 +68,24 @@ pub struct ExecContext {
     pub block_timestamp: BlockTimestamp,
     /// Known contract accounts
     pub contracts: Vec<Vec<u8>>,
+    /// The input of the current contract execution.
+    pub input: Vec<u8>,
+    /// The suspended executions of the contracts which called into the current one,
+    /// outermost first.
+    pub call_stack: Vec<CallFrame>,
+}
+
+/// The part of an execution context which is specific to a single contract call.
+#[cfg_attr(test, derive(Debug, PartialEq, Eq))]
+pub struct CallFrame {
+    /// The caller of the contract execution.
+    pub caller: Option<AccountId>,
+    /// The callee of the contract execution.
+    pub callee: Option<AccountId>,
+    /// The value transferred to the contract as part of the call.
+    pub value_transferred: Balance,
+    /// The input of the contract execution.
+    pub input: Vec<u8>,
 }
 
 impl ExecContext {
@@ -85,6 +103,45 @@ impl ExecContext {
             .into()
     }
 
+    /// Returns the account which started the outermost contract execution.
+    pub fn origin(&self) -> Option<&AccountId> {
+        match self.call_stack.first() {
+            Some(frame) => frame.caller.as_ref(),
+            None => self.caller.as_ref(),
+        }
+    }
+
+    /// Suspends the current contract execution in favour of the one described by
+    /// `frame`.
+    pub fn push_frame(&mut self, frame: CallFrame) {
+        let suspended = CallFrame {
+            caller: core::mem::replace(&mut self.caller, frame.caller),
+            callee: core::mem::replace(&mut self.callee, frame.callee),
+            value_transferred: core::mem::replace(
+                &mut self.value_transferred,
+                frame.value_transferred,
+            ),
+            input: core::mem::replace(&mut self.input, frame.input),
+        };
+        self.call_stack.push(suspended);
+    }
+
+    /// Resumes the most recently suspended contract execution.
+    ///
+    /// # Panics
+    ///
+    /// If there is no suspended execution.
+    pub fn pop_frame(&mut self) {
+        let frame = self
+            .call_stack
+            .pop()
+            .expect("no suspended contract execution to resume");
+        self.caller = frame.caller;
+        self.callee = frame.callee;
+        self.value_transferred = frame.value_transferred;
+        self.input = frame.input;
+    }
+
     /// Resets the execution context
     pub fn reset(&mut self) {
         *self = Default::default();
@@ -105,6 +162,7 @@ impl ExecContext {
 mod tests {
     use super::{
         AccountId,
+        CallFrame,
         ExecContext,
     };
 
@@ -122,4 +180,29 @@ mod tests {
         let new_exec_cont = ExecContext::new();
         assert_eq!(exec_cont, new_exec_cont);
     }
+
+    #[test]
+    fn frames_are_suspended_and_resumed() {
+        let mut exec_cont = ExecContext::new();
+        exec_cont.caller = Some(AccountId::from_bytes(&[1]));
+        exec_cont.callee = Some(AccountId::from_bytes(&[2]));
+        exec_cont.value_transferred = 3;
+
+        exec_cont.push_frame(CallFrame {
+            caller: Some(AccountId::from_bytes(&[2])),
+            callee: Some(AccountId::from_bytes(&[4])),
+            value_transferred: 5,
+            input: vec![6],
+        });
+        assert_eq!(exec_cont.callee(), vec![4]);
+        assert_eq!(exec_cont.value_transferred, 5);
+        assert_eq!(exec_cont.input, vec![6]);
+        assert_eq!(exec_cont.origin(), Some(&AccountId::from_bytes(&[1])));
+
+        exec_cont.pop_frame();
+        assert_eq!(exec_cont.callee(), vec![2]);
+        assert_eq!(exec_cont.value_transferred, 3);
+        assert!(exec_cont.input.is_empty());
+        assert!(exec_cont.call_stack.is_empty());
+    }
 }
@@ -38,7 +38,15 @@
 use crate::{
     chain_extension::ChainExtensionHandler,
     database::Database,
-    exec_context::ExecContext,
+    exec_context::{
+        CallFrame,
+        ExecContext,
+    },
+    gas::{
+        GasMeter,
+        Schedule,
+        Weight,
+    },
     test_api::{
         DebugInfo,
         EmittedEvent,
@@ -50,8 +58,14 @@ use crate::{
     },
 };
 pub use pallet_contracts_uapi::ReturnErrorCode as Error;
+use pallet_contracts_uapi::ReturnFlags;
 use scale::Encode;
-use std::panic::panic_any;
+use std::panic::{
+    catch_unwind,
+    panic_any,
+    resume_unwind,
+    AssertUnwindSafe,
+};
 
 /// The off-chain engine.
 pub struct Engine {
@@ -67,6 +81,44 @@ pub struct Engine {
     pub chain_spec: ChainSpec,
     /// Handler for registered chain extensions.
     pub chain_extension_handler: ChainExtensionHandler,
+    /// Used to derive the code hashes of uploaded contract code.
+    pub(crate) code_nonce: u64,
+    /// Runs the entry points of called contracts.
+    entry_point_runner: EntryPointRunner,
+    /// The weights charged for the host functions.
+    pub schedule: Schedule,
+    /// Charges the weight of host functions against the gas limit of the current
+    /// contract execution.
+    pub(crate) gas_meter: GasMeter,
+}
+
+/// Runs the entry point of a called contract on behalf of `engine`, returning
+/// whether it finished or unwound.
+///
+/// The entry point accesses the engine through the environment rather than
+/// through `engine`, so environments which keep the engine in a global have to
+/// make it available there while the entry point runs.
+pub type EntryPointRunner =
+    fn(engine: &mut Engine, entry_point: fn()) -> std::thread::Result<()>;
+
+fn run_entry_point(
+    _engine: &mut Engine,
+    entry_point: fn(),
+) -> std::thread::Result<()> {
+    catch_unwind(AssertUnwindSafe(entry_point))
+}
+
+/// The value returned by a contract, carried by the unwinding that halts its
+/// execution.
+struct ReturnValue {
+    flags: ReturnFlags,
+    data: Vec<u8>,
+}
+
+/// The state to roll back to if a contract execution is reverted.
+struct Checkpoint {
+    database: Database,
+    events: usize,
 }
 
 /// The chain specification.
@@ -106,6 +158,10 @@ impl Engine {
             debug_info: DebugInfo::new(),
             chain_spec: ChainSpec::default(),
             chain_extension_handler: ChainExtensionHandler::new(),
+            code_nonce: 0,
+            entry_point_runner: run_entry_point,
+            schedule: Schedule::default(),
+            gas_meter: GasMeter::new(Weight::MAX),
         }
     }
 }
@@ -117,8 +173,14 @@ impl Default for Engine {
 }
 
 impl Engine {
+    /// Sets how the entry points of called contracts are run.
+    pub fn set_entry_point_runner(&mut self, runner: EntryPointRunner) {
+        self.entry_point_runner = runner;
+    }
+
     /// Transfers value from the contract to the destination account.
     pub fn transfer(&mut self, account_id: &[u8], mut value: &[u8]) -> Result<(), Error> {
+        self.charge("transfer", self.schedule.transfer);
         // Note that a transfer of `0` is allowed here
         let increment = <u128 as scale::Decode>::decode(&mut value)
             .map_err(|_| Error::TransferFailed)?;
@@ -159,6 +221,16 @@ impl Engine {
         } else {
             Vec::new()
         };
+        let topics_weight = self
+            .schedule
+            .event_topic
+            .saturating_mul(topics_count as u64);
+        let weight = self
+            .schedule
+            .deposit_event
+            .weight(data.len())
+            .saturating_add(topics_weight);
+        self.charge("deposit_event", weight);
 
         self.debug_info.record_event(EmittedEvent {
             topics: topics_vec,
@@ -169,6 +241,10 @@ impl Engine {
     /// Writes the encoded value into the storage at the given key.
     /// Returns the size of the previously stored value at the key if any.
     pub fn set_storage(&mut self, key: &[u8], encoded_value: &[u8]) -> Option<u32> {
+        self.charge(
+            "set_storage",
+            self.schedule.set_storage.weight(encoded_value.len()),
+        );
         let callee = self.get_callee();
         let account_id = AccountId::from_bytes(&callee[..]);
 
@@ -185,6 +261,11 @@ impl Engine {
     pub fn get_storage(&mut self, key: &[u8]) -> Result<&[u8], Error> {
         let callee = self.get_callee();
         let account_id = AccountId::from_bytes(&callee[..]);
+        let weight = self
+            .schedule
+            .get_storage
+            .weight(self.stored_len(&callee, key));
+        self.charge("get_storage", weight);
 
         self.debug_info.inc_reads(account_id);
         match self.database.get_from_contract_storage(&callee, key) {
@@ -198,6 +279,11 @@ impl Engine {
     pub fn take_storage(&mut self, key: &[u8]) -> Result<Vec<u8>, Error> {
         let callee = self.get_callee();
         let account_id = AccountId::from_bytes(&callee[..]);
+        let weight = self
+            .schedule
+            .take_storage
+            .weight(self.stored_len(&callee, key));
+        self.charge("take_storage", weight);
 
         self.debug_info.inc_writes(account_id);
         match self.database.remove_contract_storage(&callee, key) {
@@ -210,6 +296,11 @@ impl Engine {
     pub fn contains_storage(&mut self, key: &[u8]) -> Option<u32> {
         let callee = self.get_callee();
         let account_id = AccountId::from_bytes(&callee[..]);
+        let weight = self
+            .schedule
+            .contains_storage
+            .weight(self.stored_len(&callee, key));
+        self.charge("contains_storage", weight);
 
         self.debug_info.inc_reads(account_id);
         self.database
@@ -222,6 +313,11 @@ impl Engine {
     pub fn clear_storage(&mut self, key: &[u8]) -> Option<u32> {
         let callee = self.get_callee();
         let account_id = AccountId::from_bytes(&callee[..]);
+        let weight = self
+            .schedule
+            .clear_storage
+            .weight(self.stored_len(&callee, key));
+        self.charge("clear_storage", weight);
         self.debug_info.inc_writes(account_id.clone());
         let _ = self
             .debug_info
@@ -237,6 +333,7 @@ impl Engine {
     /// execution of the destroyed contract is halted. Or it failed during the
     /// termination which is considered fatal.
     pub fn terminate(&mut self, beneficiary: &[u8]) -> ! {
+        self.charge("terminate", self.schedule.terminate);
         // Send the remaining balance to the beneficiary
         let contract = self.get_callee();
         let all = self
@@ -338,8 +435,19 @@ impl Engine {
         set_output(output, &block_timestamp[..])
     }
 
-    pub fn gas_left(&self, _output: &mut &mut [u8]) {
-        unimplemented!("off-chain environment does not yet support `gas_left`");
+    /// Returns the reference time left of the gas limit of the current contract
+    /// execution.
+    pub fn gas_left(&self, output: &mut &mut [u8]) {
+        let gas_left = scale::Encode::encode(&self.gas_meter.gas_left().ref_time);
+        set_output(output, &gas_left[..])
+    }
+
+    /// Charges the weight of hashing `input`.
+    ///
+    /// The hash functions themselves do not have access to the engine, so the
+    /// environment has to charge them separately.
+    pub fn charge_hashing(&mut self, input: &[u8]) {
+        self.charge("hash", self.schedule.hash.weight(input.len()));
     }
 
     /// Returns the minimum balance that is required for creating an account
@@ -350,29 +458,293 @@ impl Engine {
         set_output(output, &minimum_balance[..])
     }
 
+    /// Instantiates the contract code registered under `code_hash` by running the
+    /// constructor selected by `input`.
+    ///
+    /// The address of the new contract is derived from the instantiating contract,
+    /// the code hash, the input and the salt, like the default address generator of
+    /// the `contracts` pallet does.
+    ///
+    /// If a contract already exists at that address the pallet fails with
+    /// `DuplicateContract`, which has no return code of its own and traps the
+    /// instantiating contract, so this fails with [`Error::CalleeTrapped`].
     #[allow(clippy::too_many_arguments)]
     pub fn instantiate(
         &mut self,
-        _code_hash: &[u8],
-        _gas_limit: u64,
-        _endowment: &[u8],
-        _input: &[u8],
-        _out_address: &mut &mut [u8],
-        _out_return_value: &mut &mut [u8],
-        _salt: &[u8],
+        code_hash: &[u8],
+        gas_limit: u64,
+        endowment: &[u8],
+        input: &[u8],
+        out_address: &mut &mut [u8],
+        out_return_value: &mut &mut [u8],
+        salt: &[u8],
     ) -> Result<(), Error> {
-        unimplemented!("off-chain environment does not yet support `instantiate`");
+        self.charge(
+            "instantiate",
+            self.schedule.instantiate.weight(input.len() + salt.len()),
+        );
+        let code = self
+            .database
+            .get_code(code_hash)
+            .ok_or(Error::CodeNotFound)?;
+        let endowment = <u128 as scale::Decode>::decode(&mut &endowment[..])
+            .map_err(|_| Error::TransferFailed)?;
+        let deployer = self.get_callee();
+        let address = contract_address(&deployer, code_hash, input, salt);
+        if self.database.get_code_hash(&address).is_some() {
+            return Err(Error::CalleeTrapped)
+        }
+
+        let checkpoint = self.checkpoint();
+        self.database.set_code_hash(&address, code_hash);
+        if let Err(err) = self.transfer_value(&deployer, &address, endowment) {
+            self.revert_to(checkpoint);
+            return Err(err)
+        }
+        let result = self.execute(
+            checkpoint,
+            gas_limit,
+            CallFrame {
+                caller: Some(AccountId::from_bytes(&deployer)),
+                callee: Some(AccountId::from_bytes(&address)),
+                value_transferred: endowment,
+                input: input.to_vec(),
+            },
+            code.deploy,
+            out_return_value,
+        );
+        if result.is_ok() {
+            set_output(out_address, &address);
+        }
+        result
     }
 
+    /// Calls the contract at `callee` with `input`, transferring `value` to it.
+    ///
+    /// The return value of the callee is written into `output`, which is shrunk to
+    /// its size. If the callee reverts, all of its changes are rolled back.
     pub fn call(
         &mut self,
-        _callee: &[u8],
-        _gas_limit: u64,
-        _value: &[u8],
-        _input: &[u8],
-        _output: &mut &mut [u8],
+        callee: &[u8],
+        gas_limit: u64,
+        value: &[u8],
+        input: &[u8],
+        output: &mut &mut [u8],
+    ) -> Result<(), Error> {
+        self.charge("call", self.schedule.call.weight(input.len()));
+        let code_hash = self
+            .database
+            .get_code_hash(callee)
+            .ok_or(Error::NotCallable)?;
+        let code = self
+            .database
+            .get_code(&code_hash)
+            .ok_or(Error::CodeNotFound)?;
+        let value = <u128 as scale::Decode>::decode(&mut &value[..])
+            .map_err(|_| Error::TransferFailed)?;
+        let caller = self.get_callee();
+
+        let checkpoint = self.checkpoint();
+        if let Err(err) = self.transfer_value(&caller, callee, value) {
+            self.revert_to(checkpoint);
+            return Err(err)
+        }
+        self.execute(
+            checkpoint,
+            gas_limit,
+            CallFrame {
+                caller: Some(AccountId::from_bytes(&caller)),
+                callee: Some(AccountId::from_bytes(callee)),
+                value_transferred: value,
+                input: input.to_vec(),
+            },
+            code.call,
+            output,
+        )
+    }
+
+    /// Executes the contract code registered under `code_hash` in the context of the
+    /// currently executing contract.
+    pub fn delegate_call(
+        &mut self,
+        code_hash: &[u8],
+        input: &[u8],
+        output: &mut &mut [u8],
+    ) -> Result<(), Error> {
+        self.charge("delegate_call", self.schedule.call.weight(input.len()));
+        let code = self
+            .database
+            .get_code(code_hash)
+            .ok_or(Error::CodeNotFound)?;
+
+        let checkpoint = self.checkpoint();
+        self.execute(
+            checkpoint,
+            0,
+            CallFrame {
+                caller: self.exec_context.caller.clone(),
+                callee: self.exec_context.callee.clone(),
+                value_transferred: self.exec_context.value_transferred,
+                input: input.to_vec(),
+            },
+            code.call,
+            output,
+        )
+    }
+
+    /// Halts the execution of the current contract, returning `data` to its caller.
+    ///
+    /// This unwinds up to the `call`, `delegate_call` or `instantiate` which started
+    /// the execution.
+    ///
+    /// # Panics
+    ///
+    /// If no contract was called, as there is nobody to return to.
+    pub fn return_value(&mut self, flags: ReturnFlags, data: &[u8]) -> ! {
+        if self.exec_context.call_stack.is_empty() {
+            unimplemented!(
+                "the off-chain env does not implement `return_value` outside of contract calls"
+            )
+        }
+        resume_unwind(Box::new(ReturnValue {
+            flags,
+            data: data.to_vec(),
+        }))
+    }
+
+    /// Returns the input of the current contract execution.
+    pub fn input(&self, output: &mut &mut [u8]) {
+        set_output(output, &self.exec_context.input)
+    }
+
+    /// Returns the code hash of the contract at `account_id`.
+    pub fn code_hash(&self, account_id: &[u8], output: &mut [u8]) -> Result<(), Error> {
+        let code_hash = self
+            .database
+            .get_code_hash(account_id)
+            .ok_or(Error::KeyNotFound)?;
+        set_output(&mut &mut output[..], &code_hash);
+        Ok(())
+    }
+
+    /// Returns the code hash of the currently executing contract.
+    pub fn own_code_hash(&self, output: &mut [u8]) -> Result<(), Error> {
+        let callee = self.get_callee();
+        self.code_hash(&callee, output)
+    }
+
+    /// Replaces the code of the currently executing contract with the code
+    /// registered under `code_hash`.
+    pub fn set_code_hash(&mut self, code_hash: &[u8]) -> Result<(), Error> {
+        if self.database.get_code(code_hash).is_none() {
+            return Err(Error::CodeNotFound)
+        }
+        let callee = self.get_callee();
+        self.database.set_code_hash(&callee, code_hash);
+        Ok(())
+    }
+
+    /// Returns whether the caller of the current contract execution is the account
+    /// which started the outermost one.
+    pub fn caller_is_origin(&self) -> bool {
+        self.exec_context.caller.is_some()
+            && self.exec_context.caller.as_ref() == self.exec_context.origin()
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            database: self.database.clone(),
+            events: self.debug_info.count_events(),
+        }
+    }
+
+    fn revert_to(&mut self, checkpoint: Checkpoint) {
+        self.database = checkpoint.database;
+        self.debug_info.revert_events(checkpoint.events);
+    }
+
+    /// Charges `weight` for an invocation of `host_fn` against the gas limit.
+    ///
+    /// Traps the current contract execution if it runs out of gas.
+    fn charge(&mut self, host_fn: &'static str, weight: Weight) {
+        if let Err(err) = self.gas_meter.charge(host_fn, weight) {
+            panic!(
+                "out of gas: `{}` needs {:?}, but only {:?} is left",
+                err.host_fn, err.needed, err.left
+            )
+        }
+    }
+
+    /// Returns the size of the value stored at `key` by `callee`, or `0` if there is
+    /// none.
+    fn stored_len(&self, callee: &[u8], key: &[u8]) -> usize {
+        self.database
+            .get_from_contract_storage(callee, key)
+            .map_or(0, |value| value.len())
+    }
+
+    /// Moves `value` from `from` to `to`, failing if `from` can not afford it.
+    fn transfer_value(
+        &mut self,
+        from: &[u8],
+        to: &[u8],
+        value: Balance,
+    ) -> Result<(), Error> {
+        if value == 0 {
+            return Ok(())
+        }
+        let from_balance = self.database.get_balance(from).unwrap_or_default();
+        if from_balance < value {
+            return Err(Error::TransferFailed)
+        }
+        let to_balance = self.database.get_balance(to).unwrap_or_default();
+        self.database.set_balance(from, from_balance - value);
+        self.database.set_balance(to, to_balance + value);
+        Ok(())
+    }
+
+    /// Runs `entry_point` in the context described by `frame`, with a gas limit of
+    /// `gas_limit`, or whatever gas is left if it is `0`.
+    ///
+    /// Rolls back to `checkpoint` if the execution reverts or traps, which includes
+    /// running out of gas.
+    fn execute(
+        &mut self,
+        checkpoint: Checkpoint,
+        gas_limit: u64,
+        frame: CallFrame,
+        entry_point: fn(),
+        output: &mut &mut [u8],
     ) -> Result<(), Error> {
-        unimplemented!("off-chain environment does not yet support `call`");
+        let nested_meter = self.gas_meter.nested(gas_limit);
+        let meter = core::mem::replace(&mut self.gas_meter, nested_meter);
+        self.exec_context.push_frame(frame);
+        let outcome = (self.entry_point_runner)(self, entry_point);
+        self.exec_context.pop_frame();
+        let nested_meter = core::mem::replace(&mut self.gas_meter, meter);
+        self.gas_meter.absorb(nested_meter);
+
+        let (flags, data) = match outcome {
+            // The contract finished without explicitly returning a value.
+            Ok(()) => (ReturnFlags::empty(), Vec::new()),
+            Err(payload) => {
+                match payload.downcast::<ReturnValue>() {
+                    Ok(returned) => (returned.flags, returned.data),
+                    Err(_) => {
+                        self.revert_to(checkpoint);
+                        return Err(Error::CalleeTrapped)
+                    },
+                }
+            },
+        };
+        set_output(output, &data);
+        let written = core::mem::take(output);
+        *output = &mut written[..data.len()];
+        if flags.contains(ReturnFlags::REVERT) {
+            self.revert_to(checkpoint);
+            return Err(Error::CalleeReverted)
+        }
+        Ok(())
     }
 
     /// Emulates gas price calculation.
@@ -389,6 +761,10 @@ impl Engine {
         input: &[u8],
         output: &mut &mut [u8],
     ) {
+        self.charge(
+            "call_chain_extension",
+            self.schedule.call_chain_extension.weight(input.len()),
+        );
         let encoded_input = input.encode();
         let (status_code, out) = self
             .chain_extension_handler
@@ -411,6 +787,7 @@ impl Engine {
         message_hash: &[u8; 32],
         output: &mut [u8; 33],
     ) -> Result<(), Error> {
+        self.charge("ecdsa_recover", self.schedule.ecdsa_recover);
         use secp256k1::{
             ecdsa::{
                 RecoverableSignature,
@@ -450,6 +827,19 @@ impl Engine {
     }
 }
 
+/// Derives the address of a contract instantiated by `deployer`.
+fn contract_address(
+    deployer: &[u8],
+    code_hash: &[u8],
+    input: &[u8],
+    salt: &[u8],
+) -> Vec<u8> {
+    let buf = [b"contract_addr_v1", deployer, code_hash, input, salt].concat();
+    let mut address = [0; 32];
+    super::hashing::blake2b_256(&buf, &mut address);
+    address.to_vec()
+}
+
 /// Copies the `slice` into `output`.
 ///
 /// Panics if the slice is too large and does not fit.
@@ -0,0 +1,360 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Weight metering of the host functions of the off-chain engine.
+//!
+//! The default [`Schedule`] is only an approximation of the weights of the
+//! `contracts` pallet. It is meant to catch messages whose weight grows out of
+//! proportion, not to predict the exact fees of a message.
+
+use std::collections::BTreeMap;
+
+/// The weight of an operation, measured like in the `contracts` pallet.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Weight {
+    /// The computational time of the operation, in picoseconds.
+    pub ref_time: u64,
+    /// The size of the storage proof required by the operation, in bytes.
+    pub proof_size: u64,
+}
+
+impl Weight {
+    /// The weight of an operation which costs nothing.
+    pub const ZERO: Self = Self::from_parts(0, 0);
+
+    /// The largest possible weight, which never runs out.
+    pub const MAX: Self = Self::from_parts(u64::MAX, u64::MAX);
+
+    /// Creates a new weight from its components.
+    pub const fn from_parts(ref_time: u64, proof_size: u64) -> Self {
+        Self {
+            ref_time,
+            proof_size,
+        }
+    }
+
+    /// Adds `other` to `self`, saturating at the numeric bounds.
+    pub const fn saturating_add(self, other: Self) -> Self {
+        Self::from_parts(
+            self.ref_time.saturating_add(other.ref_time),
+            self.proof_size.saturating_add(other.proof_size),
+        )
+    }
+
+    /// Subtracts `other` from `self`, saturating at zero.
+    pub const fn saturating_sub(self, other: Self) -> Self {
+        Self::from_parts(
+            self.ref_time.saturating_sub(other.ref_time),
+            self.proof_size.saturating_sub(other.proof_size),
+        )
+    }
+
+    /// Multiplies both components of `self` by `factor`, saturating at the numeric
+    /// bounds.
+    pub const fn saturating_mul(self, factor: u64) -> Self {
+        Self::from_parts(
+            self.ref_time.saturating_mul(factor),
+            self.proof_size.saturating_mul(factor),
+        )
+    }
+
+    /// Returns the component-wise minimum of `self` and `other`.
+    pub fn min(self, other: Self) -> Self {
+        Self::from_parts(
+            self.ref_time.min(other.ref_time),
+            self.proof_size.min(other.proof_size),
+        )
+    }
+
+    /// Returns `true` if any component of `self` is greater than that of `other`.
+    pub const fn any_gt(self, other: Self) -> bool {
+        self.ref_time > other.ref_time || self.proof_size > other.proof_size
+    }
+}
+
+/// The weight of a host function which depends on the size of its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HostFnWeight {
+    /// The weight charged for every invocation.
+    pub base: Weight,
+    /// The weight charged for every byte the host function processes.
+    pub per_byte: Weight,
+}
+
+impl HostFnWeight {
+    /// Creates a new host function weight.
+    pub const fn new(base: Weight, per_byte: Weight) -> Self {
+        Self { base, per_byte }
+    }
+
+    /// Returns the weight of an invocation processing `len` bytes.
+    pub fn weight(&self, len: usize) -> Weight {
+        self.base
+            .saturating_add(self.per_byte.saturating_mul(len as u64))
+    }
+}
+
+/// The weights the off-chain engine charges for its host functions.
+///
+/// Storage functions are charged by the size of the value they read or write,
+/// hashing by the size of its input and calls by the size of their input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    /// Reading a value from the contract storage.
+    pub get_storage: HostFnWeight,
+    /// Writing a value into the contract storage.
+    pub set_storage: HostFnWeight,
+    /// Removing a value from the contract storage.
+    pub clear_storage: HostFnWeight,
+    /// Checking whether the contract storage contains a value.
+    pub contains_storage: HostFnWeight,
+    /// Removing a value from the contract storage and returning it.
+    pub take_storage: HostFnWeight,
+    /// Hashing an input with any of the supported hash functions.
+    pub hash: HostFnWeight,
+    /// Depositing an event, by the size of its data.
+    pub deposit_event: HostFnWeight,
+    /// Every topic of a deposited event.
+    pub event_topic: Weight,
+    /// Transferring value to another account.
+    pub transfer: Weight,
+    /// Terminating the current contract.
+    pub terminate: Weight,
+    /// Calling or delegating to another contract.
+    pub call: HostFnWeight,
+    /// Instantiating a contract.
+    pub instantiate: HostFnWeight,
+    /// Calling a chain extension method, by the size of its input.
+    pub call_chain_extension: HostFnWeight,
+    /// Recovering an ECDSA public key.
+    pub ecdsa_recover: Weight,
+}
+
+/// The default weights are in the order of magnitude of those the `contracts`
+/// pallet benchmarks on reference hardware.
+impl Default for Schedule {
+    fn default() -> Self {
+        let storage = HostFnWeight::new(
+            Weight::from_parts(40_000_000, 200),
+            Weight::from_parts(1_000, 1),
+        );
+        Self {
+            get_storage: storage,
+            set_storage: HostFnWeight::new(
+                Weight::from_parts(60_000_000, 200),
+                Weight::from_parts(2_000, 1),
+            ),
+            clear_storage: storage,
+            contains_storage: storage,
+            take_storage: storage,
+            hash: HostFnWeight::new(
+                Weight::from_parts(1_000_000, 0),
+                Weight::from_parts(4_000, 0),
+            ),
+            deposit_event: HostFnWeight::new(
+                Weight::from_parts(4_000_000, 0),
+                Weight::from_parts(1_000, 0),
+            ),
+            event_topic: Weight::from_parts(4_000_000, 0),
+            transfer: Weight::from_parts(200_000_000, 2_600),
+            terminate: Weight::from_parts(1_000_000_000, 7_000),
+            call: HostFnWeight::new(
+                Weight::from_parts(300_000_000, 4_000),
+                Weight::from_parts(500, 0),
+            ),
+            instantiate: HostFnWeight::new(
+                Weight::from_parts(600_000_000, 8_000),
+                Weight::from_parts(1_500, 0),
+            ),
+            call_chain_extension: HostFnWeight::new(
+                Weight::from_parts(50_000_000, 0),
+                Weight::from_parts(1_000, 0),
+            ),
+            ecdsa_recover: Weight::from_parts(45_000_000, 0),
+        }
+    }
+}
+
+/// The weight consumed by the invocations of a single host function.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HostFnUsage {
+    /// How often the host function was invoked.
+    pub invocations: u32,
+    /// The total weight of those invocations.
+    pub weight: Weight,
+}
+
+/// The weight consumed since the gas meter was last reset.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WeightReport {
+    /// The total weight consumed.
+    pub consumed: Weight,
+    /// The weight consumed by each host function, keyed by its name.
+    pub host_fns: BTreeMap<&'static str, HostFnUsage>,
+}
+
+/// The weight needed by a host function exceeded what is left of the gas limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OutOfGas {
+    /// The host function which ran out of gas.
+    pub host_fn: &'static str,
+    /// The weight the host function needed.
+    pub needed: Weight,
+    /// The weight that was left.
+    pub left: Weight,
+}
+
+/// Charges the weight of host functions against a gas limit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasMeter {
+    limit: Weight,
+    report: WeightReport,
+}
+
+impl GasMeter {
+    /// Creates a new gas meter which allows to consume up to `limit`.
+    pub fn new(limit: Weight) -> Self {
+        Self {
+            limit,
+            report: WeightReport::default(),
+        }
+    }
+
+    /// Returns the gas limit of this meter.
+    pub fn limit(&self) -> Weight {
+        self.limit
+    }
+
+    /// Returns the weight which can still be consumed.
+    pub fn gas_left(&self) -> Weight {
+        self.limit.saturating_sub(self.report.consumed)
+    }
+
+    /// Returns the weight consumed so far.
+    pub fn report(&self) -> &WeightReport {
+        &self.report
+    }
+
+    /// Charges `weight` for an invocation of `host_fn`.
+    ///
+    /// Nothing is charged if the weight exceeds what is left of the gas limit.
+    pub fn charge(
+        &mut self,
+        host_fn: &'static str,
+        weight: Weight,
+    ) -> Result<(), OutOfGas> {
+        let consumed = self.report.consumed.saturating_add(weight);
+        if consumed.any_gt(self.limit) {
+            return Err(OutOfGas {
+                host_fn,
+                needed: weight,
+                left: self.gas_left(),
+            })
+        }
+        self.report.consumed = consumed;
+        let usage = self.report.host_fns.entry(host_fn).or_default();
+        usage.invocations += 1;
+        usage.weight = usage.weight.saturating_add(weight);
+        Ok(())
+    }
+
+    /// Creates a meter for a nested call, limited to `ref_time_limit` if it is not
+    /// `0` and to what is left of this meter in any case.
+    pub fn nested(&self, ref_time_limit: u64) -> Self {
+        let left = self.gas_left();
+        let limit = match ref_time_limit {
+            0 => left,
+            ref_time => left.min(Weight::from_parts(ref_time, u64::MAX)),
+        };
+        Self::new(limit)
+    }
+
+    /// Adds the weight consumed by the `nested` meter to this one.
+    pub fn absorb(&mut self, nested: GasMeter) {
+        let report = &mut self.report;
+        report.consumed = report.consumed.saturating_add(nested.report.consumed);
+        for (host_fn, nested_usage) in nested.report.host_fns {
+            let usage = report.host_fns.entry(host_fn).or_default();
+            usage.invocations += nested_usage.invocations;
+            usage.weight = usage.weight.saturating_add(nested_usage.weight);
+        }
+    }
+
+    /// Forgets all weight consumed so far, keeping the gas limit.
+    pub fn reset(&mut self) {
+        self.report = WeightReport::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        GasMeter,
+        Weight,
+    };
+
+    #[test]
+    fn charging_beyond_the_limit_fails() {
+        let mut meter = GasMeter::new(Weight::from_parts(100, 10));
+
+        assert_eq!(meter.charge("a", Weight::from_parts(60, 5)), Ok(()));
+        let err = meter
+            .charge("b", Weight::from_parts(60, 1))
+            .expect_err("ref time is exhausted");
+
+        assert_eq!(err.left, Weight::from_parts(40, 5));
+        assert_eq!(meter.gas_left(), Weight::from_parts(40, 5));
+        assert!(!meter.report().host_fns.contains_key("b"));
+    }
+
+    #[test]
+    fn nested_meters_are_limited_and_absorbed() {
+        let mut meter = GasMeter::new(Weight::from_parts(100, 10));
+        meter.charge("a", Weight::from_parts(30, 2)).unwrap();
+
+        let mut nested = meter.nested(0);
+        assert_eq!(nested.limit(), Weight::from_parts(70, 8));
+        assert_eq!(meter.nested(20).limit(), Weight::from_parts(20, 8));
+        nested.charge("a", Weight::from_parts(10, 1)).unwrap();
+        meter.absorb(nested);
+
+        let usage = meter.report().host_fns["a"];
+        assert_eq!(usage.invocations, 2);
+        assert_eq!(usage.weight, Weight::from_parts(40, 3));
+        assert_eq!(meter.gas_left(), Weight::from_parts(60, 7));
+    }
+}
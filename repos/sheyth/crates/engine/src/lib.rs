@@ -41,6 +41,7 @@
 )]
 
 pub mod ext;
+pub mod gas;
 pub mod test_api;
 
 mod chain_extension;
@@ -53,7 +54,10 @@ mod types;
 mod tests;
 
 pub use chain_extension::ChainExtension;
-pub use types::AccountError;
+pub use types::{
+    AccountError,
+    ContractCode,
+};
 
 use derive_more::From;
 
@@ -37,6 +37,11 @@
 
 use crate::{
     ext::Engine,
+    gas::{
+        GasMeter,
+        Weight,
+        WeightReport,
+    },
     types::{
         AccountId,
         Balance,
@@ -44,6 +49,7 @@ use crate::{
         BlockTimestamp,
     },
     AccountError,
+    ContractCode,
     Error,
 };
 use std::collections::HashMap;
@@ -193,6 +199,16 @@ impl DebugInfo {
     pub fn record_event(&mut self, event: EmittedEvent) {
         self.emitted_events.push(event);
     }
+
+    /// Returns the number of events recorded so far.
+    pub fn count_events(&self) -> usize {
+        self.emitted_events.len()
+    }
+
+    /// Discards the events recorded after the first `count` ones.
+    pub fn revert_events(&mut self, count: usize) {
+        self.emitted_events.truncate(count);
+    }
 }
 
 impl Engine {
@@ -201,6 +217,7 @@ impl Engine {
         self.exec_context.reset();
         self.database.clear();
         self.debug_info.reset();
+        self.gas_meter = GasMeter::new(Weight::MAX);
     }
 
     /// Returns the total number of reads and writes of the contract's storage.
@@ -264,6 +281,29 @@ impl Engine {
     /// Returns boolean value indicating whether the account is a contract
     pub fn is_contract(&self, account_id: Vec<u8>) -> bool {
         self.exec_context.contracts.contains(&account_id)
+            || self.database.get_code_hash(&account_id).is_some()
+    }
+
+    /// Registers `code` under `code_hash`, so that it can be instantiated and called
+    /// by other contracts.
+    pub fn register_code(&mut self, code_hash: Vec<u8>, code: ContractCode) {
+        self.database.insert_code(&code_hash, code);
+    }
+
+    /// Registers `code` under a fresh code hash and returns it.
+    pub fn upload_code(&mut self, code: ContractCode) -> Vec<u8> {
+        let mut code_hash = [0; 32];
+        let nonce = self.code_nonce.to_le_bytes();
+        self.code_nonce += 1;
+        super::hashing::blake2b_256(&nonce, &mut code_hash);
+        self.register_code(code_hash.to_vec(), code);
+        code_hash.to_vec()
+    }
+
+    /// Turns `account_id` into a contract running the code registered under
+    /// `code_hash`, without running any of its constructors.
+    pub fn set_contract_code(&mut self, account_id: Vec<u8>, code_hash: Vec<u8>) {
+        self.database.set_code_hash(&account_id, &code_hash);
     }
 
     /// Returns the contents of the past performed environmental `debug_message` in order.
@@ -302,6 +342,26 @@ impl Engine {
     pub fn set_block_number(&mut self, new_block_number: BlockNumber) {
         self.exec_context.block_number = new_block_number;
     }
+
+    /// Sets the gas limit for the next call and resets the weight consumed so far.
+    pub fn set_gas_limit(&mut self, limit: Weight) {
+        self.gas_meter = GasMeter::new(limit);
+    }
+
+    /// Returns the weight left of the gas limit.
+    pub fn get_gas_left(&self) -> Weight {
+        self.gas_meter.gas_left()
+    }
+
+    /// Returns the weight consumed since the gas limit was last set or reset.
+    pub fn get_weight_report(&self) -> WeightReport {
+        self.gas_meter.report().clone()
+    }
+
+    /// Resets the weight consumed so far, keeping the gas limit.
+    pub fn reset_weight_report(&mut self) {
+        self.gas_meter.reset();
+    }
 }
 
 #[cfg(test)]
@@ -35,9 +35,13 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-use crate::ext::{
-    Engine,
-    Error,
+use crate::{
+    ext::{
+        Engine,
+        Error,
+    },
+    gas::Weight,
+    ContractCode,
 };
 use secp256k1::{
     ecdsa::RecoverableSignature,
@@ -310,3 +314,211 @@ fn setting_getting_block_number() {
         .expect("decoding value transferred failed");
     assert_eq!(output, new_block_number);
 }
+
+fn noop() {}
+
+fn trap() {
+    panic!("the contract trapped");
+}
+
+#[test]
+fn calling_a_contract_transfers_value() {
+    // given
+    let mut engine = Engine::new();
+    let caller = vec![1; 32];
+    let callee = vec![2; 32];
+    let code_hash = engine.upload_code(ContractCode {
+        call: noop,
+        deploy: noop,
+    });
+    engine.set_callee(caller.clone());
+    engine.set_balance(caller.clone(), 100);
+    engine.set_contract_code(callee.clone(), code_hash);
+    let value = scale::Encode::encode(&42u128);
+
+    // when
+    let mut buffer = get_buffer();
+    let output = &mut &mut buffer[..];
+    let res = engine.call(&callee, 0, &value, &[], output);
+
+    // then
+    assert_eq!(res, Ok(()));
+    assert!(output.is_empty());
+    assert_eq!(engine.get_balance(caller.clone()), Ok(58));
+    assert_eq!(engine.get_balance(callee), Ok(42));
+    assert_eq!(engine.get_callee(), caller);
+}
+
+#[test]
+fn trapped_call_is_rolled_back() {
+    // given
+    let mut engine = Engine::new();
+    let caller = vec![1; 32];
+    let callee = vec![2; 32];
+    let code_hash = engine.upload_code(ContractCode {
+        call: trap,
+        deploy: noop,
+    });
+    engine.set_callee(caller.clone());
+    engine.set_balance(caller.clone(), 100);
+    engine.set_contract_code(callee.clone(), code_hash);
+    let value = scale::Encode::encode(&42u128);
+
+    // when
+    let res = engine.call(&callee, 0, &value, &[], &mut &mut get_buffer()[..]);
+
+    // then
+    assert_eq!(res, Err(Error::CalleeTrapped));
+    assert_eq!(engine.get_balance(caller.clone()), Ok(100));
+    assert!(engine.get_balance(callee).is_err());
+    assert_eq!(engine.get_callee(), caller);
+}
+
+#[test]
+fn calling_unknown_contracts_fails() {
+    // given
+    let mut engine = Engine::new();
+    engine.set_callee(vec![1; 32]);
+    let value = scale::Encode::encode(&0u128);
+    let output = &mut &mut get_buffer()[..];
+
+    // then
+    assert_eq!(
+        engine.call(&[2; 32], 0, &value, &[], output),
+        Err(Error::NotCallable)
+    );
+    assert_eq!(
+        engine.instantiate(
+            &[3; 32],
+            0,
+            &value,
+            &[],
+            &mut &mut get_buffer()[..],
+            output,
+            &[],
+        ),
+        Err(Error::CodeNotFound)
+    );
+}
+
+#[test]
+fn instantiating_a_contract_sets_its_code_hash() {
+    // given
+    let mut engine = Engine::new();
+    let deployer = vec![1; 32];
+    let code_hash = engine.upload_code(ContractCode {
+        call: noop,
+        deploy: noop,
+    });
+    engine.set_callee(deployer.clone());
+    engine.set_balance(deployer, 100);
+    let endowment = scale::Encode::encode(&10u128);
+
+    // when
+    let mut address = get_buffer();
+    let res = engine.instantiate(
+        &code_hash,
+        0,
+        &endowment,
+        &[],
+        &mut &mut address[..],
+        &mut &mut get_buffer()[..],
+        &[],
+    );
+
+    // then
+    assert_eq!(res, Ok(()));
+    let address = address[..32].to_vec();
+    assert!(engine.is_contract(address.clone()));
+    assert_eq!(engine.get_balance(address.clone()), Ok(10));
+    let mut output = [0; 32];
+    assert_eq!(engine.code_hash(&address, &mut output), Ok(()));
+    assert_eq!(output.to_vec(), code_hash);
+}
+
+#[test]
+fn instantiating_a_contract_twice_at_the_same_address_fails() {
+    // given
+    let mut engine = Engine::new();
+    let deployer = vec![1; 32];
+    let code_hash = engine.upload_code(ContractCode {
+        call: noop,
+        deploy: noop,
+    });
+    engine.set_callee(deployer.clone());
+    engine.set_balance(deployer.clone(), 100);
+    let endowment = scale::Encode::encode(&10u128);
+    let instantiate = |engine: &mut Engine| {
+        engine.instantiate(
+            &code_hash,
+            0,
+            &endowment,
+            &[],
+            &mut &mut get_buffer()[..],
+            &mut &mut get_buffer()[..],
+            &[],
+        )
+    };
+    assert_eq!(instantiate(&mut engine), Ok(()));
+
+    // when
+    let res = instantiate(&mut engine);
+
+    // then
+    assert_eq!(res, Err(Error::CalleeTrapped));
+    assert_eq!(engine.get_balance(deployer), Ok(90));
+}
+
+#[test]
+fn storage_writes_are_charged_by_size() {
+    // given
+    let mut engine = Engine::new();
+    engine.set_callee(vec![1; 32]);
+    let schedule = engine.schedule.clone();
+
+    // when
+    engine.set_storage(&[0; 32], &[0; 4]);
+    engine.set_storage(&[1; 32], &[0; 400]);
+
+    // then
+    let report = engine.get_weight_report();
+    let usage = report.host_fns["set_storage"];
+    let expected = schedule
+        .set_storage
+        .weight(4)
+        .saturating_add(schedule.set_storage.weight(400));
+    assert_eq!(usage.invocations, 2);
+    assert_eq!(usage.weight, expected);
+    assert_eq!(report.consumed, expected);
+}
+
+#[test]
+fn gas_left_is_reduced_by_charged_weight() {
+    // given
+    let mut engine = Engine::new();
+    engine.set_callee(vec![1; 32]);
+    let limit = Weight::from_parts(1_000_000_000, 100_000);
+    engine.set_gas_limit(limit);
+    let weight = engine.schedule.set_storage.weight(16);
+
+    // when
+    engine.set_storage(&[0; 32], &[0; 16]);
+
+    // then
+    let output = &mut &mut get_buffer()[..];
+    engine.gas_left(output);
+    let gas_left = <u64 as scale::Decode>::decode(&mut &output[..8])
+        .expect("decoding gas left failed");
+    assert_eq!(gas_left, limit.ref_time - weight.ref_time);
+    assert_eq!(engine.get_gas_left(), limit.saturating_sub(weight));
+}
+
+#[test]
+#[should_panic(expected = "out of gas: `set_storage`")]
+fn running_out_of_gas_traps() {
+    let mut engine = Engine::new();
+    engine.set_callee(vec![1; 32]);
+    engine.set_gas_limit(Weight::from_parts(1, 1));
+
+    engine.set_storage(&[0; 32], &[0; 4]);
+}
@@ -63,6 +63,18 @@ impl AccountId {
     }
 }
 
+/// The entry points of a contract registered with the off-chain engine.
+///
+/// Both read their input from and report their output to the engine, just like
+/// the `call` and `deploy` exports of a compiled contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractCode {
+    /// Dispatches a message call to the contract.
+    pub call: fn(),
+    /// Dispatches a constructor call to the contract.
+    pub deploy: fn(),
+}
+
 /// Key into the database.
 ///
 /// Used to identify contract storage cells for read and write operations.
@@ -92,8 +92,6 @@ cfg_if! {
     }
 }
 
-// We only use this function when 1) compiling to Wasm 2) compiling for tests.
-#[cfg_attr(all(feature = "std", not(test)), allow(dead_code))]
 pub(crate) fn decode_instantiate_result<I, E, ContractRef, R>(
     instantiate_result: EnvResult<()>,
     out_address: &mut I,
@@ -120,7 +118,6 @@ where
     }
 }
 
-#[cfg_attr(all(feature = "std", not(test)), allow(dead_code))]
 fn decode_instantiate_err<I, E, ContractRef, R>(
     out_return_value: &mut I,
 ) -> EnvResult<ConstructorResult<<R as ConstructorReturnType<ContractRef>>::Output>>
@@ -60,6 +60,7 @@ use crate::{
         Keccak256,
         Sha2x256,
     },
+    CallFlags,
     Clear,
     EnvBackend,
     Environment,
@@ -193,6 +194,84 @@ impl EnvInstance {
         ext_fn(&self.engine, full_scope);
         scale::Decode::decode(&mut &full_scope[..]).map_err(Into::into)
     }
+
+    /// Returns the input for a call with the given `call_flags`, which is either
+    /// `input` or the input of the currently executing contract.
+    fn call_input<Args>(&self, call_flags: &CallFlags, input: &Args) -> Vec<u8>
+    where
+        Args: scale::Encode,
+    {
+        if call_flags.contains(CallFlags::FORWARD_INPUT)
+            || call_flags.contains(CallFlags::CLONE_INPUT)
+        {
+            self.engine.exec_context.input.clone()
+        } else {
+            scale::Encode::encode(input)
+        }
+    }
+
+    /// Calls the contract at `callee` and decodes its return value.
+    fn invoke_contract_impl<R>(
+        &mut self,
+        callee: &[u8],
+        gas_limit: u64,
+        transferred_value: &[u8],
+        input: &[u8],
+    ) -> Result<ink_primitives::MessageResult<R>>
+    where
+        R: scale::Decode,
+    {
+        let mut buffer: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+        let mut output = &mut buffer[..];
+        let call_result =
+            self.engine
+                .call(callee, gas_limit, transferred_value, input, &mut output);
+        match call_result {
+            Ok(()) | Err(ReturnErrorCode::CalleeReverted) => {
+                let decoded = scale::DecodeAll::decode_all(&mut &output[..])?;
+                Ok(decoded)
+            }
+            Err(actual_error) => Err(actual_error.into()),
+        }
+    }
+
+    /// Instantiates a contract from the registered code and decodes the result.
+    fn instantiate_contract_impl<E, ContractRef, R>(
+        &mut self,
+        code_hash: &[u8],
+        gas_limit: u64,
+        endowment: &[u8],
+        input: &[u8],
+        salt: &[u8],
+    ) -> Result<
+        ink_primitives::ConstructorResult<
+            <R as ConstructorReturnType<ContractRef>>::Output,
+        >,
+    >
+    where
+        E: Environment,
+        ContractRef: FromAccountId<E>,
+        R: ConstructorReturnType<ContractRef>,
+    {
+        let mut out_address: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+        let mut buffer: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+        let mut out_return_value = &mut buffer[..];
+        let instantiate_result = self.engine.instantiate(
+            code_hash,
+            gas_limit,
+            endowment,
+            input,
+            &mut &mut out_address[..],
+            &mut out_return_value,
+            salt,
+        );
+
+        crate::engine::decode_instantiate_result::<_, E, ContractRef, R>(
+            instantiate_result.map_err(Into::into),
+            &mut &out_address[..],
+            &mut &out_return_value[..],
+        )
+    }
 }
 
 impl EnvBackend for EnvInstance {
@@ -254,14 +333,15 @@ impl EnvBackend for EnvInstance {
     where
         T: scale::Decode,
     {
-        unimplemented!("the off-chain env does not implement `input`")
+        self.get_property::<T>(Engine::input)
     }
 
-    fn return_value<R>(&mut self, _flags: ReturnFlags, _return_value: &R) -> !
+    fn return_value<R>(&mut self, flags: ReturnFlags, return_value: &R) -> !
     where
         R: scale::Encode,
     {
-        unimplemented!("the off-chain env does not implement `return_value`")
+        let encoded = scale::Encode::encode(return_value);
+        self.engine.return_value(flags, &encoded[..])
     }
 
     fn debug_message(&mut self, message: &str) {
@@ -272,6 +352,7 @@ impl EnvBackend for EnvInstance {
     where
         H: CryptoHash,
     {
+        self.engine.charge_hashing(input);
         <H as CryptoHash>::hash(input, output)
     }
 
@@ -281,6 +362,7 @@ impl EnvBackend for EnvInstance {
         T: scale::Encode,
     {
         let enc_input = &scale::Encode::encode(input)[..];
+        self.engine.charge_hashing(enc_input);
         <H as CryptoHash>::hash(enc_input, output)
     }
 
@@ -391,8 +473,8 @@ impl EnvBackend for EnvInstance {
         Ok(decoded)
     }
 
-    fn set_code_hash(&mut self, _code_hash: &[u8]) -> Result<()> {
-        unimplemented!("off-chain environment does not support `set_code_hash`")
+    fn set_code_hash(&mut self, code_hash: &[u8]) -> Result<()> {
+        self.engine.set_code_hash(code_hash).map_err(Into::into)
     }
 }
 
@@ -464,26 +546,42 @@ impl TypedEnvBackend for EnvInstance {
 
     fn invoke_contract_v1<E, Args, R>(
         &mut self,
-        _params: &CallParams<E, CallV1<E>, Args, R>,
+        params: &CallParams<E, CallV1<E>, Args, R>,
     ) -> Result<ink_primitives::MessageResult<R>>
     where
         E: Environment,
         Args: scale::Encode,
         R: scale::Decode,
     {
-        unimplemented!("off-chain environment does not support contract invocation")
+        let enc_callee = scale::Encode::encode(params.callee());
+        let enc_transferred_value = scale::Encode::encode(params.transferred_value());
+        let enc_input = self.call_input(params.call_flags(), params.exec_input());
+        self.invoke_contract_impl(
+            &enc_callee[..],
+            params.gas_limit(),
+            &enc_transferred_value[..],
+            &enc_input[..],
+        )
     }
 
     fn invoke_contract<E, Args, R>(
         &mut self,
-        _params: &CallParams<E, Call<E>, Args, R>,
+        params: &CallParams<E, Call<E>, Args, R>,
     ) -> Result<ink_primitives::MessageResult<R>>
     where
         E: Environment,
         Args: scale::Encode,
         R: scale::Decode,
     {
-        unimplemented!("off-chain environment does not support contract invocation")
+        let enc_callee = scale::Encode::encode(params.callee());
+        let enc_transferred_value = scale::Encode::encode(params.transferred_value());
+        let enc_input = self.call_input(params.call_flags(), params.exec_input());
+        self.invoke_contract_impl(
+            &enc_callee[..],
+            params.ref_time_limit(),
+            &enc_transferred_value[..],
+            &enc_input[..],
+        )
     }
 
     fn invoke_contract_delegate<E, Args, R>(
@@ -495,10 +593,20 @@ impl TypedEnvBackend for EnvInstance {
         Args: scale::Encode,
         R: scale::Decode,
     {
-        let _code_hash = params.code_hash();
-        unimplemented!(
-            "off-chain environment does not support delegated contract invocation"
-        )
+        let enc_code_hash = scale::Encode::encode(params.code_hash());
+        let enc_input = self.call_input(params.call_flags(), params.exec_input());
+        let mut buffer: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+        let mut output = &mut buffer[..];
+        let call_result =
+            self.engine
+                .delegate_call(&enc_code_hash[..], &enc_input[..], &mut output);
+        match call_result {
+            Ok(()) | Err(ReturnErrorCode::CalleeReverted) => {
+                let decoded = scale::DecodeAll::decode_all(&mut &output[..])?;
+                Ok(decoded)
+            },
+            Err(actual_error) => Err(actual_error.into()),
+        }
     }
 
     fn instantiate_contract<E, ContractRef, Args, Salt, R>(
@@ -516,14 +624,16 @@ impl TypedEnvBackend for EnvInstance {
         Salt: AsRef<[u8]>,
         R: ConstructorReturnType<ContractRef>,
     {
-        let _code_hash = params.code_hash();
-        let _ref_time_limit = params.ref_time_limit();
-        let _proof_size_limit = params.proof_size_limit();
-        let _storage_deposit_limit = params.storage_deposit_limit();
-        let _endowment = params.endowment();
-        let _input = params.exec_input();
-        let _salt_bytes = params.salt_bytes();
-        unimplemented!("off-chain environment does not support contract instantiation")
+        let enc_code_hash = scale::Encode::encode(params.code_hash());
+        let enc_endowment = scale::Encode::encode(params.endowment());
+        let enc_input = scale::Encode::encode(params.exec_input());
+        self.instantiate_contract_impl::<E, ContractRef, R>(
+            &enc_code_hash[..],
+            params.ref_time_limit(),
+            &enc_endowment[..],
+            &enc_input[..],
+            params.salt_bytes().as_ref(),
+        )
     }
 
     fn instantiate_contract_v1<E, ContractRef, Args, Salt, R>(
@@ -541,12 +651,16 @@ impl TypedEnvBackend for EnvInstance {
         Salt: AsRef<[u8]>,
         R: ConstructorReturnType<ContractRef>,
     {
-        let _code_hash = params.code_hash();
-        let _ref_time_limit = params.gas_limit();
-        let _endowment = params.endowment();
-        let _input = params.exec_input();
-        let _salt_bytes = params.salt_bytes();
-        unimplemented!("off-chain environment does not support contract instantiation")
+        let enc_code_hash = scale::Encode::encode(params.code_hash());
+        let enc_endowment = scale::Encode::encode(params.endowment());
+        let enc_input = scale::Encode::encode(params.exec_input());
+        self.instantiate_contract_impl::<E, ContractRef, R>(
+            &enc_code_hash[..],
+            params.gas_limit(),
+            &enc_endowment[..],
+            &enc_input[..],
+            params.salt_bytes().as_ref(),
+        )
     }
 
     fn terminate_contract<E>(&mut self, beneficiary: E::AccountId) -> !
@@ -587,21 +701,28 @@ impl TypedEnvBackend for EnvInstance {
     where
         E: Environment,
     {
-        unimplemented!("off-chain environment does not support cross-contract calls")
+        self.engine.caller_is_origin()
     }
 
-    fn code_hash<E>(&mut self, _account: &E::AccountId) -> Result<E::Hash>
+    fn code_hash<E>(&mut self, account: &E::AccountId) -> Result<E::Hash>
     where
         E: Environment,
     {
-        unimplemented!("off-chain environment does not support `code_hash`")
+        let mut output: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+        let enc_account = scale::Encode::encode(account);
+        self.engine.code_hash(&enc_account[..], &mut output[..])?;
+        let hash = scale::Decode::decode(&mut &output[..])?;
+        Ok(hash)
     }
 
     fn own_code_hash<E>(&mut self) -> Result<E::Hash>
     where
         E: Environment,
     {
-        unimplemented!("off-chain environment does not support `own_code_hash`")
+        let mut output: [u8; BUFFER_SIZE] = [0; BUFFER_SIZE];
+        self.engine.own_code_hash(&mut output[..])?;
+        let hash = scale::Decode::decode(&mut &output[..])?;
+        Ok(hash)
     }
 
     fn call_runtime<E, Call>(&mut self, _call: &Call) -> Result<()>
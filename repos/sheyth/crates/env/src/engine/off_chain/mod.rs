@@ -46,28 +46,62 @@ mod tests;
 use super::OnInstance;
 use crate::Error;
 
+use core::cell::RefCell;
 use derive_more::From;
 use ink_engine::ext::Engine;
+use std::{
+    panic::AssertUnwindSafe,
+    rc::Rc,
+};
 
 /// The off-chain environment.
 pub struct EnvInstance {
     engine: Engine,
 }
 
+impl EnvInstance {
+    fn new() -> Self {
+        let mut engine = Engine::new();
+        engine.set_entry_point_runner(run_entry_point);
+        EnvInstance { engine }
+    }
+}
+
+thread_local!(
+    /// The instances used by the ongoing contract executions, innermost last.
+    static INSTANCES: RefCell<Vec<Rc<RefCell<EnvInstance>>>> =
+        RefCell::new(vec![Rc::new(RefCell::new(EnvInstance::new()))])
+);
+
+/// Runs the entry point of a called contract.
+///
+/// The caller still holds its access to the instance, so the engine is moved into
+/// a new instance for the duration of the call and moved back afterwards.
+fn run_entry_point(engine: &mut Engine, entry_point: fn()) -> std::thread::Result<()> {
+    let instance = Rc::new(RefCell::new(EnvInstance {
+        engine: core::mem::take(engine),
+    }));
+    INSTANCES.with(|instances| instances.borrow_mut().push(instance.clone()));
+    let outcome = std::panic::catch_unwind(AssertUnwindSafe(entry_point));
+    INSTANCES.with(|instances| instances.borrow_mut().pop());
+    *engine = core::mem::take(&mut instance.borrow_mut().engine);
+    outcome
+}
+
 impl OnInstance for EnvInstance {
     fn on_instance<F, R>(f: F) -> R
     where
         F: FnOnce(&mut Self) -> R,
     {
-        use core::cell::RefCell;
-        thread_local!(
-            static INSTANCE: RefCell<EnvInstance> = RefCell::new(
-                EnvInstance {
-                    engine: Engine::new()
-                }
-            )
-        );
-        INSTANCE.with(|instance| f(&mut instance.borrow_mut()))
+        let instance = INSTANCES.with(|instances| {
+            instances
+                .borrow()
+                .last()
+                .cloned()
+                .expect("the outermost instance is never removed")
+        });
+        let result = f(&mut instance.borrow_mut());
+        result
     }
 }
 
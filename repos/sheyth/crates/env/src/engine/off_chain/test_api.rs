@@ -50,6 +50,13 @@ use std::panic::UnwindSafe;
 pub use super::call_data::CallData;
 pub use ink_engine::{
     ext::ChainSpec,
+    gas::{
+        HostFnUsage,
+        HostFnWeight,
+        Schedule,
+        Weight,
+        WeightReport,
+    },
     ChainExtension,
 };
 
@@ -134,6 +141,78 @@ where
     })
 }
 
+/// The entry points of a contract, through which the off-chain environment executes
+/// it when it is called or instantiated by another contract.
+///
+/// The `#[ink::contract]` macro implements this for both the contract's storage
+/// type and its contract reference type.
+pub trait ContractEntryPoints {
+    /// Dispatches the message selected by the input of the current execution.
+    fn call();
+    /// Dispatches the constructor selected by the input of the current execution.
+    fn deploy();
+}
+
+/// Registers the code of contract `C` under `code_hash`.
+///
+/// Other contracts can then instantiate it with `code_hash` and call or delegate to
+/// its instances, all within the same test.
+pub fn register_contract<E, C>(code_hash: E::Hash)
+where
+    E: Environment,
+    C: ContractEntryPoints,
+{
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.engine.register_code(
+            scale::Encode::encode(&code_hash),
+            ink_engine::ContractCode {
+                call: C::call,
+                deploy: C::deploy,
+            },
+        );
+    })
+}
+
+/// Registers the code of contract `C` under a fresh code hash, which is returned.
+///
+/// # Usage
+///
+/// ```no_compile
+/// let code_hash = ink::env::test::upload_code::<Environment, OtherContractRef>();
+/// let other = OtherContractRef::new()
+///     .code_hash(code_hash)
+///     .salt_bytes([0u8; 0])
+///     .instantiate();
+/// ```
+pub fn upload_code<E, C>() -> E::Hash
+where
+    E: Environment,
+    C: ContractEntryPoints,
+{
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        let code_hash = instance.engine.upload_code(ink_engine::ContractCode {
+            call: C::call,
+            deploy: C::deploy,
+        });
+        scale::Decode::decode(&mut &code_hash[..])
+            .unwrap_or_else(|err| panic!("decoding code hash failed: {err}"))
+    })
+}
+
+/// Makes `contract` run the code registered under `code_hash`, without executing
+/// any of its constructors.
+pub fn set_contract_code<E>(contract: E::AccountId, code_hash: E::Hash)
+where
+    E: Environment,
+{
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.engine.set_contract_code(
+            scale::Encode::encode(&contract),
+            scale::Encode::encode(&code_hash),
+        );
+    })
+}
+
 /// Returns the contents of the past performed environmental debug messages in order.
 pub fn recorded_debug_messages() -> RecordedDebugMessages {
     <EnvInstance as OnInstance>::on_instance(|instance| {
@@ -325,6 +404,55 @@ where
     })
 }
 
+/// Sets the weights charged for the host functions.
+pub fn set_schedule(schedule: Schedule) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.engine.schedule = schedule;
+    })
+}
+
+/// Sets the gas limit for the following calls and resets the weight consumed so far.
+///
+/// A host function which needs more weight than is left panics with an "out of gas"
+/// message, or traps the contract if it is executed by a cross-contract call.
+pub fn set_gas_limit(limit: Weight) {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.engine.set_gas_limit(limit);
+    })
+}
+
+/// Returns the weight consumed since the gas limit was last set or the report was
+/// last reset.
+pub fn weight_report() -> WeightReport {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.engine.get_weight_report()
+    })
+}
+
+/// Resets the weight consumed so far, keeping the gas limit.
+pub fn reset_weight_report() {
+    <EnvInstance as OnInstance>::on_instance(|instance| {
+        instance.engine.reset_weight_report();
+    })
+}
+
+/// Returns the result of `f` along with the weight it consumed.
+///
+/// # Usage
+///
+/// ```no_compile
+/// let (_, report) = ink::env::test::measure_weight(|| contract.flip());
+/// assert!(report.consumed.ref_time < 1_000_000_000);
+/// ```
+pub fn measure_weight<F, R>(f: F) -> (R, WeightReport)
+where
+    F: FnOnce() -> R,
+{
+    reset_weight_report();
+    let result = f();
+    (result, weight_report())
+}
+
 /// Runs the given closure test function with the default configuration
 /// for the off-chain environment.
 pub fn run_test<T, F>(f: F) -> Result<()>
@@ -98,3 +98,94 @@ fn test_set_account_balance() -> Result<()> {
         Ok(())
     })
 }
+
+/// Doubles its argument, but reverts any storage changes when the argument is zero.
+struct Doubler;
+
+impl crate::test::ContractEntryPoints for Doubler {
+    fn call() {
+        let (_selector, value) = crate::decode_input::<([u8; 4], u32)>()
+            .expect("input must be a selector followed by a `u32`");
+        crate::set_contract_storage(&0u32, &value);
+        let flags = if value == 0 {
+            crate::ReturnFlags::REVERT
+        } else {
+            crate::ReturnFlags::empty()
+        };
+        crate::return_value::<ink_primitives::MessageResult<u32>>(flags, &Ok(value * 2))
+    }
+
+    fn deploy() {}
+}
+
+#[test]
+fn calls_registered_contract() -> Result<()> {
+    use crate::call::{
+        build_call,
+        ExecutionInput,
+        Selector,
+    };
+
+    crate::test::run_test::<DefaultEnvironment, _>(|accounts| {
+        let code_hash = crate::test::upload_code::<DefaultEnvironment, Doubler>();
+        crate::test::set_contract_code::<DefaultEnvironment>(accounts.charlie, code_hash);
+        let call = |value: u32| {
+            build_call::<DefaultEnvironment>()
+                .call(accounts.charlie)
+                .exec_input(ExecutionInput::new(Selector::new([0; 4])).push_arg(value))
+                .returns::<u32>()
+                .try_invoke()
+        };
+
+        assert_eq!(call(21)?, Ok(42));
+        assert_eq!(call(0)?, Ok(0));
+
+        crate::test::set_callee::<DefaultEnvironment>(accounts.charlie);
+        assert_eq!(crate::get_contract_storage::<u32, u32>(&0)?, Some(21));
+        Ok(())
+    })
+}
+
+#[test]
+fn called_contract_runs_out_of_gas() -> Result<()> {
+    use crate::call::{
+        build_call,
+        ExecutionInput,
+        Selector,
+    };
+    use pallet_contracts_uapi::ReturnErrorCode;
+
+    crate::test::run_test::<DefaultEnvironment, _>(|accounts| {
+        let code_hash = crate::test::upload_code::<DefaultEnvironment, Doubler>();
+        crate::test::set_contract_code::<DefaultEnvironment>(accounts.charlie, code_hash);
+        let call = |ref_time_limit: u64| {
+            build_call::<DefaultEnvironment>()
+                .call(accounts.charlie)
+                .ref_time_limit(ref_time_limit)
+                .exec_input(ExecutionInput::new(Selector::new([0; 4])).push_arg(21u32))
+                .returns::<u32>()
+                .try_invoke()
+        };
+
+        let (result, report) = crate::test::measure_weight(|| call(0));
+        assert_eq!(result?, Ok(42));
+        assert_eq!(report.host_fns["call"].invocations, 1);
+        assert_eq!(report.host_fns["set_storage"].invocations, 1);
+
+        let (result, report) = crate::test::measure_weight(|| call(1));
+        assert_eq!(
+            result,
+            Err(crate::Error::ReturnError(ReturnErrorCode::CalleeTrapped))
+        );
+        assert!(!report.host_fns.contains_key("set_storage"));
+        Ok(())
+    })
+}
+
+#[test]
+#[should_panic(expected = "outside of contract calls")]
+fn return_value_outside_of_contract_calls_is_unsupported() {
+    let _ = crate::test::run_test::<DefaultEnvironment, _>(|_| {
+        crate::return_value::<u32>(crate::ReturnFlags::empty(), &0)
+    });
+}
@@ -161,6 +161,17 @@ impl ContractRef<'_> {
                 impl ::ink::env::ContractEnv for #ref_ident {
                     type Env = <#storage_ident as ::ink::env::ContractEnv>::Env;
                 }
+
+                #[cfg(feature = "std")]
+                impl ::ink::env::test::ContractEntryPoints for #ref_ident {
+                    fn call() {
+                        <#storage_ident as ::ink::env::test::ContractEntryPoints>::call()
+                    }
+
+                    fn deploy() {
+                        <#storage_ident as ::ink::env::test::ContractEntryPoints>::deploy()
+                    }
+                }
             };
         )
     }
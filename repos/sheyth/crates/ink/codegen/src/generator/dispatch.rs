@@ -105,7 +105,10 @@ impl GenerateCode for Dispatch<'_> {
         let constructor_decoder_type =
             self.generate_constructor_decoder_type(&constructors);
         let message_decoder_type = self.generate_message_decoder_type(&messages);
-        let entry_points = self.generate_entry_points(&constructors, &messages);
+        let dispatch_functions =
+            self.generate_dispatch_functions(&constructors, &messages);
+        let entry_points = self.generate_entry_points();
+        let off_chain_entry_points = self.generate_off_chain_entry_points();
         quote! {
             #contract_dispatchable_constructor_infos
             #contract_dispatchable_messages_infos
@@ -114,8 +117,15 @@ impl GenerateCode for Dispatch<'_> {
 
             #[cfg(not(any(test, feature = "std", feature = "ink-as-dependency")))]
             const _: () = {
+                #dispatch_functions
                 #entry_points
             };
+
+            #[cfg(feature = "std")]
+            const _: () = {
+                #dispatch_functions
+                #off_chain_entry_points
+            };
         }
     }
 }
@@ -359,11 +369,11 @@ impl Dispatch<'_> {
         )
     }
 
-    /// Generates code for the entry points of the root ink! smart contract.
+    /// Generates the dispatch functions of the root ink! smart contract.
     ///
-    /// This generates the `deploy` and `call` functions with which the smart
-    /// contract runtime mainly interacts with the ink! smart contract.
-    fn generate_entry_points(
+    /// This generates the `internal_deploy` and `internal_call` functions which
+    /// back the entry points of both the compiled and the off-chain contract.
+    fn generate_dispatch_functions(
         &self,
         constructors: &[ConstructorDispatchable],
         messages: &[MessageDispatchable],
@@ -445,7 +455,13 @@ impl Dispatch<'_> {
                     ::core::panic!("dispatching ink! message failed: {}", error)
                 })
             }
+        )
+    }
 
+    /// Generates code for the exported entry points of the compiled contract.
+    fn generate_entry_points(&self) -> TokenStream2 {
+        let span = self.contract.module().storage().span();
+        quote_spanned!(span=>
             #[cfg(target_arch = "wasm32")]
             #[no_mangle]
             pub extern "C" fn call() {
@@ -471,6 +487,24 @@ impl Dispatch<'_> {
         )
     }
 
+    /// Generates code for executing the ink! smart contract in the off-chain
+    /// environment, e.g. when another contract calls it from within an `#[ink::test]`.
+    fn generate_off_chain_entry_points(&self) -> TokenStream2 {
+        let span = self.contract.module().storage().span();
+        let storage_ident = self.contract.module().storage().ident();
+        quote_spanned!(span=>
+            impl ::ink::env::test::ContractEntryPoints for #storage_ident {
+                fn call() {
+                    internal_call()
+                }
+
+                fn deploy() {
+                    internal_deploy()
+                }
+            }
+        )
+    }
+
     /// Generates code for the ink! constructor decoder type of the ink! smart contract.
     ///
     /// This type can be used in order to decode the input bytes received by a call to
@@ -80,8 +80,11 @@ pub mod storage {
         pub use ink_storage::traits::*;
     }
     pub use ink_storage::{
+        IterableMapping,
         Lazy,
         Mapping,
+        StorageDeque,
+        StorageSet,
         StorageVec,
     };
 }
@@ -547,6 +547,28 @@ where
     }
 }
 
+impl StructLayout {
+    /// Creates the layout of an entry of a storage collection which links its entries in
+    /// insertion order, like `IterableMapping` and `StorageSet` do.
+    ///
+    /// Such an entry holds its value followed by the keys of type `K` of the previous
+    /// and the next entry, which are `None` at the ends of the list.
+    pub fn linked_entry<K, L>(key: LayoutKey, value: L) -> Self
+    where
+        K: TypeInfo + 'static,
+        L: Into<Layout>,
+    {
+        Self::new(
+            "LinkedEntry",
+            [
+                FieldLayout::new("value", value),
+                FieldLayout::new("prev", LeafLayout::from_key::<Option<K>>(key)),
+                FieldLayout::new("next", LeafLayout::from_key::<Option<K>>(key)),
+            ],
+        )
+    }
+}
+
 impl IntoPortable for StructLayout {
     type Output = StructLayout<PortableForm>;
 
@@ -93,6 +93,53 @@ fn named_fields_work() {
     assert_eq!(json, expected);
 }
 
+#[test]
+fn linked_entry_works() {
+    let key = LayoutKey::new(345u32);
+    let value = LeafLayout::from_key::<i32>(key);
+    let layout: Layout = StructLayout::linked_entry::<i64, _>(key, value).into();
+    let mut registry = Registry::new();
+    let compacted = layout.into_portable(&mut registry);
+    let json = serde_json::to_value(&compacted).unwrap();
+    let expected = serde_json::json! {
+        {
+            "struct": {
+                "fields": [
+                    {
+                        "layout": {
+                            "leaf": {
+                                "key": "0x59010000",
+                                "ty": 0,
+                            }
+                        },
+                        "name": "value",
+                    },
+                    {
+                        "layout": {
+                            "leaf": {
+                                "key": "0x59010000",
+                                "ty": 1,
+                            }
+                        },
+                        "name": "prev",
+                    },
+                    {
+                        "layout": {
+                            "leaf": {
+                                "key": "0x59010000",
+                                "ty": 1,
+                            }
+                        },
+                        "name": "next",
+                    }
+                ],
+                "name": "LinkedEntry",
+            }
+        }
+    };
+    assert_eq!(json, expected);
+}
+
 fn tuple_struct_layout(key: &Key) -> Layout {
     StructLayout::new(
         "(A, B)",
@@ -1100,6 +1100,11 @@ where
         &self.label
     }
 
+    /// Returns the module path to the event type definition.
+    pub fn module_path(&self) -> &F::String {
+        &self.module_path
+    }
+
     /// The event arguments.
     pub fn args(&self) -> &[EventParamSpec<F>] {
         &self.args
@@ -0,0 +1,507 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use core::cell::Cell;
+use ink_primitives::Key;
+use ink_storage_traits::{
+    AutoKey,
+    Packed,
+    Storable,
+    StorableHint,
+    StorageKey,
+};
+use scale::EncodeLike;
+
+use crate::{
+    Lazy,
+    Mapping,
+};
+
+/// A double-ended queue of values (elements) directly on contract storage.
+///
+/// # Important
+///
+/// [StorageDeque] requires its own pre-defined storage key where to store values. By
+/// default, the is automatically calculated using [`AutoKey`](crate::traits::AutoKey)
+/// during compilation. However, anyone can specify a storage key using
+/// [`ManualKey`](crate::traits::ManualKey). Specifying the storage key can be helpful for
+/// upgradeable contracts or you want to be resistant to future changes of storage key
+/// calculation strategy.
+///
+/// # Differences between [StorageVec](crate::StorageVec) and [StorageDeque]
+///
+/// Like a [StorageVec](crate::StorageVec), each element is stored in its own storage
+/// cell and only loaded when it is accessed. Additionally, elements can be pushed to and
+/// popped from the front in O(1), which makes [StorageDeque] a good fit for queues of
+/// pending requests, withdrawals and the like.
+///
+/// The length is limited at 2 ^ 32 elements.
+///
+/// # Storage Layout
+///
+/// At given [StorageKey] `K`, the slot of the front element and the length of the
+/// [StorageDeque] is hold. Each element `E` is then stored under a combination of the
+/// [StorageDeque] key `K` and its slot. Slots wrap around, the `N`th element lives in
+/// slot `front.wrapping_add(N)`:
+///
+/// `E = scale::Encode((K, front.wrapping_add(N)))`
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct StorageDeque<V: Packed, KeyType: StorageKey = AutoKey> {
+    /// The slot of the front element followed by the number of elements.
+    ///
+    /// # Note
+    ///
+    /// Because of caching, never operate on this field directly!
+    /// Always use `fn bounds()` an `fn set_bounds()` instead.
+    bounds: Lazy<(u32, u32), KeyType>,
+    /// The bounds only change upon pushing to or popping from the deque.
+    /// Hence we can cache them to prevent unnecessary reads from storage.
+    ///
+    /// # Note
+    ///
+    /// Because of caching, never operate on this field directly!
+    /// Always use `fn bounds()` an `fn set_bounds()` instead.
+    #[cfg_attr(feature = "std", codec(skip))]
+    bounds_cached: CachedBounds,
+    /// Each element is living in storage under `&(KeyType::KEY, slot)`.
+    elements: Mapping<u32, V, KeyType>,
+}
+
+#[derive(Debug)]
+struct CachedBounds(Cell<Option<(u32, u32)>>);
+
+impl<V, KeyType> Default for StorageDeque<V, KeyType>
+where
+    V: Packed,
+    KeyType: StorageKey,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, KeyType> Storable for StorageDeque<V, KeyType>
+where
+    V: Packed,
+    KeyType: StorageKey,
+{
+    #[inline]
+    fn encode<T: scale::Output + ?Sized>(&self, _dest: &mut T) {}
+
+    #[inline]
+    fn decode<I: scale::Input>(_input: &mut I) -> Result<Self, scale::Error> {
+        Ok(Default::default())
+    }
+
+    #[inline]
+    fn encoded_size(&self) -> usize {
+        0
+    }
+}
+
+impl<V, Key, InnerKey> StorableHint<Key> for StorageDeque<V, InnerKey>
+where
+    V: Packed,
+    Key: StorageKey,
+    InnerKey: StorageKey,
+{
+    type Type = StorageDeque<V, Key>;
+    type PreferredKey = InnerKey;
+}
+
+impl<V, KeyType> StorageKey for StorageDeque<V, KeyType>
+where
+    V: Packed,
+    KeyType: StorageKey,
+{
+    const KEY: Key = KeyType::KEY;
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use crate::traits::StorageLayout;
+    use ink_metadata::layout::{
+        Layout,
+        LayoutKey,
+        RootLayout,
+    };
+
+    impl<V, KeyType> StorageLayout for StorageDeque<V, KeyType>
+    where
+        V: Packed + StorageLayout + scale_info::TypeInfo + 'static,
+        KeyType: StorageKey + scale_info::TypeInfo + 'static,
+    {
+        fn layout(_: &Key) -> Layout {
+            Layout::Root(RootLayout::new(
+                LayoutKey::from(&KeyType::KEY),
+                <V as StorageLayout>::layout(&KeyType::KEY),
+                scale_info::meta_type::<Self>(),
+            ))
+        }
+    }
+};
+
+impl<V, KeyType> StorageDeque<V, KeyType>
+where
+    V: Packed,
+    KeyType: StorageKey,
+{
+    /// Creates a new empty `StorageDeque`.
+    pub const fn new() -> Self {
+        Self {
+            bounds: Lazy::new(),
+            bounds_cached: CachedBounds(Cell::new(None)),
+            elements: Mapping::new(),
+        }
+    }
+
+    /// Returns the slot of the front element and the number of elements.
+    ///
+    /// The bounds are cached; subsequent calls (without writing to the deque) won't
+    /// trigger additional storage reads.
+    fn bounds(&self) -> (u32, u32) {
+        let cached_bounds = self.bounds_cached.0.get();
+
+        debug_assert!(cached_bounds.is_none() || self.bounds.get() == cached_bounds);
+
+        cached_bounds.unwrap_or_else(|| {
+            let value = self.bounds.get();
+            self.bounds_cached.0.set(value);
+            value.unwrap_or((0, 0))
+        })
+    }
+
+    /// Overwrite the bounds. Writes directly to contract storage.
+    fn set_bounds(&mut self, front: u32, len: u32) {
+        self.bounds.set(&(front, len));
+        self.bounds_cached.0.set(Some((front, len)));
+    }
+
+    /// Returns the storage slot of the element at `index`.
+    fn slot(&self, index: u32) -> u32 {
+        self.bounds().0.wrapping_add(index)
+    }
+
+    /// Returns the number of elements in the deque, also referred to as its length.
+    pub fn len(&self) -> u32 {
+        self.bounds().1
+    }
+
+    /// Returns `true` if the deque contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends an element to the back of the deque.
+    ///
+    /// # Panics
+    ///
+    /// * If the deque is at capacity (max. of 2 ^ 32 elements).
+    /// * If the value overgrows the static buffer size.
+    pub fn push_back<T>(&mut self, value: &T)
+    where
+        T: Storable + EncodeLike<V>,
+    {
+        let (front, len) = self.bounds();
+        self.set_bounds(front, len.checked_add(1).unwrap());
+
+        assert!(self
+            .elements
+            .insert(front.wrapping_add(len), value)
+            .is_none());
+    }
+
+    /// Prepends an element to the front of the deque.
+    ///
+    /// # Panics
+    ///
+    /// * If the deque is at capacity (max. of 2 ^ 32 elements).
+    /// * If the value overgrows the static buffer size.
+    pub fn push_front<T>(&mut self, value: &T)
+    where
+        T: Storable + EncodeLike<V>,
+    {
+        let (front, len) = self.bounds();
+        let front = front.wrapping_sub(1);
+        self.set_bounds(front, len.checked_add(1).unwrap());
+
+        assert!(self.elements.insert(front, value).is_none());
+    }
+
+    /// Clears the last element from the storage and returns it.
+    ///
+    /// Returns `None` if the deque is empty.
+    ///
+    /// # Panics
+    ///
+    /// * If the value overgrows the static buffer size.
+    pub fn pop_back(&mut self) -> Option<V> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let (front, len) = self.bounds();
+        let len = len.checked_sub(1).unwrap();
+        self.set_bounds(front, len);
+
+        self.elements.take(front.wrapping_add(len))
+    }
+
+    /// Clears the first element from the storage and returns it.
+    ///
+    /// Returns `None` if the deque is empty.
+    ///
+    /// # Panics
+    ///
+    /// * If the value overgrows the static buffer size.
+    pub fn pop_front(&mut self) -> Option<V> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let (front, len) = self.bounds();
+        self.set_bounds(front.wrapping_add(1), len.checked_sub(1).unwrap());
+
+        self.elements.take(front)
+    }
+
+    /// Get a copy of the first element without removing it from storage.
+    pub fn front(&self) -> Option<V> {
+        self.get(0)
+    }
+
+    /// Get a copy of the last element without removing it from storage.
+    pub fn back(&self) -> Option<V> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    /// Access an element at given `index`, counted from the front.
+    ///
+    /// Returns `None` if the `index` is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// * If encoding the element exceeds the static buffer size.
+    pub fn get(&self, index: u32) -> Option<V> {
+        if index >= self.len() {
+            return None;
+        }
+
+        self.elements.get(self.slot(index))
+    }
+
+    /// Set the `value` at given `index`, counted from the front.
+    ///
+    /// Returns the size in bytes of the replaced value.
+    ///
+    /// # Panics
+    ///
+    /// * If the index is out of bounds.
+    /// * If encoding the element exceeds the static buffer size.
+    pub fn set<T>(&mut self, index: u32, value: &T) -> Option<u32>
+    where
+        T: Storable + EncodeLike<V>,
+    {
+        assert!(index < self.len());
+
+        let slot = self.slot(index);
+        self.elements.insert(slot, value)
+    }
+
+    /// Delete all elements from storage.
+    ///
+    /// # Warning
+    ///
+    /// This iterates through all elements in the deque; complexity is O(n).
+    /// It might not be possible to clear large deques within a single block!
+    pub fn clear(&mut self) {
+        let (front, len) = self.bounds();
+        for index in 0..len {
+            self.elements.remove(front.wrapping_add(index));
+        }
+        self.set_bounds(0, 0);
+    }
+}
+
+impl<V, KeyType> FromIterator<V> for StorageDeque<V, KeyType>
+where
+    V: Packed + EncodeLike<V>,
+    KeyType: StorageKey,
+{
+    fn from_iter<T: IntoIterator<Item = V>>(iter: T) -> Self {
+        let mut result = StorageDeque::<V, KeyType>::new();
+
+        for element in iter {
+            result.push_back(&element);
+        }
+
+        result
+    }
+}
+
+impl<V, KeyType> ::core::fmt::Debug for StorageDeque<V, KeyType>
+where
+    V: Packed,
+    KeyType: StorageKey,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("StorageDeque")
+            .field("key", &KeyType::KEY)
+            .field("bounds", &self.bounds)
+            .field("bounds_cached", &self.bounds_cached)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ManualKey;
+
+    #[test]
+    fn empty_deque_works_as_expected() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut deque: StorageDeque<String> = StorageDeque::new();
+
+            assert_eq!(deque.pop_front(), None);
+            assert_eq!(deque.pop_back(), None);
+            assert_eq!(deque.front(), None);
+            assert_eq!(deque.back(), None);
+            assert!(deque.is_empty());
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn push_and_pop_on_both_ends_work() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut deque: StorageDeque<u32> = StorageDeque::new();
+
+            deque.push_back(&2);
+            deque.push_front(&1);
+            deque.push_back(&3);
+            deque.push_front(&0);
+
+            assert_eq!(deque.len(), 4);
+            assert_eq!(deque.front(), Some(0));
+            assert_eq!(deque.back(), Some(3));
+            assert_eq!(
+                (0..4).map(|i| deque.get(i)).collect::<Vec<_>>(),
+                vec![Some(0), Some(1), Some(2), Some(3)]
+            );
+
+            assert_eq!(deque.pop_front(), Some(0));
+            assert_eq!(deque.pop_back(), Some(3));
+            assert_eq!(deque.pop_back(), Some(2));
+            assert_eq!(deque.pop_back(), Some(1));
+            assert_eq!(deque.pop_back(), None);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn storage_keys_are_correct() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            const BASE: u32 = 123;
+            let mut deque: StorageDeque<u8, ManualKey<BASE>> = StorageDeque::new();
+
+            deque.push_back(&1);
+            deque.push_front(&0);
+
+            let bounds = ink_env::get_contract_storage::<_, (u32, u32)>(&BASE);
+            assert_eq!(bounds, Ok(Some((u32::MAX, 2))));
+
+            let front = ink_env::get_contract_storage::<_, u8>(&(BASE, u32::MAX));
+            assert_eq!(front, Ok(Some(0)));
+            let back = ink_env::get_contract_storage::<_, u8>(&(BASE, 0u32));
+            assert_eq!(back, Ok(Some(1)));
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn set_and_get_work() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut deque: StorageDeque<u8> = (0..3).collect();
+
+            deque.set(1, &9);
+            assert_eq!(deque.get(1), Some(9));
+            assert_eq!(deque.get(3), None);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_panics_on_oob() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            StorageDeque::<u8>::new().set(0, &0);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn clear_works() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut deque: StorageDeque<u128> = (0..64).collect();
+            deque.push_front(&64);
+
+            deque.clear();
+
+            assert_eq!(deque.len(), 0);
+            assert_eq!(deque.pop_front(), None);
+
+            // Pushing asserts that the slots were cleared.
+            deque.push_front(&1);
+            deque.push_back(&2);
+            assert_eq!(deque.len(), 2);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+}
@@ -0,0 +1,549 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use ink_primitives::Key;
+use ink_storage_traits::{
+    AutoKey,
+    Packed,
+    Storable,
+    StorableHint,
+    StorageKey,
+};
+use scale::EncodeLike;
+
+use crate::{
+    Lazy,
+    Mapping,
+};
+
+/// A mapping of key-value pairs directly into contract storage that remembers the
+/// order in which the keys were inserted.
+///
+/// # Important
+///
+/// [IterableMapping] requires its own pre-defined storage key where to store values. By
+/// default, the is automatically calculated using [`AutoKey`](crate::traits::AutoKey)
+/// during compilation. However, anyone can specify a storage key using
+/// [`ManualKey`](crate::traits::ManualKey). Specifying the storage key can be helpful for
+/// upgradeable contracts or you want to be resistant to future changes of storage key
+/// calculation strategy.
+///
+/// # Differences between [Mapping] and [IterableMapping]
+///
+/// A [Mapping] can not enumerate its keys. [IterableMapping] keeps every entry in a
+/// doubly linked list: next to its value, each entry stores the keys of the entries
+/// inserted right before and after it. This allows iterating over all entries in
+/// insertion order, while inserting and removing an entry only touches the entry itself,
+/// its two neighbours and the list header; removal is O(1).
+///
+/// The price is paid on every write: each entry is larger than in a plain [Mapping], and
+/// inserting or removing an entry costs up to three additional storage writes.
+///
+/// # Caveats
+///
+/// Iterating reads one storage cell per entry. It might not be possible to iterate
+/// over (or [`clear`](IterableMapping::clear)) large mappings within a single block!
+///
+/// Re-inserting an existing key replaces its value but keeps its position.
+///
+/// # Storage Layout
+///
+/// At given [StorageKey] `K`, the header `(len, first_key, last_key)` is hold, of type
+/// `(u32, Option<Key>, Option<Key>)`. Each entry `E` is then stored under a combination
+/// of the [IterableMapping] key `K` and the key of the entry, the same way a [Mapping]
+/// does:
+///
+/// `E = scale::Encode((K, key))`
+///
+/// The value of an entry is the tuple `(value, previous_key, next_key)`, of type
+/// `(V, Option<Key>, Option<Key>)`, which the storage layout describes as a
+/// `LinkedEntry` struct with the fields `value`, `prev` and `next`.
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct IterableMapping<K: Packed, V: Packed, KeyType: StorageKey = AutoKey> {
+    /// The number of entries, followed by the first and the last key in insertion order.
+    ///
+    /// # Note
+    ///
+    /// Never operate on this field directly!
+    /// Always use `fn header()` and `fn set_header()` instead.
+    header: Lazy<(u32, Option<K>, Option<K>), KeyType>,
+    /// Each entry is living in storage under `&(KeyType::KEY, key)` and holds the value
+    /// followed by the keys of its previous and next entry.
+    entries: Mapping<K, (V, Option<K>, Option<K>), KeyType>,
+}
+
+impl<K, V, KeyType> Default for IterableMapping<K, V, KeyType>
+where
+    K: Packed,
+    V: Packed,
+    KeyType: StorageKey,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, KeyType> Storable for IterableMapping<K, V, KeyType>
+where
+    K: Packed,
+    V: Packed,
+    KeyType: StorageKey,
+{
+    #[inline]
+    fn encode<T: scale::Output + ?Sized>(&self, _dest: &mut T) {}
+
+    #[inline]
+    fn decode<I: scale::Input>(_input: &mut I) -> Result<Self, scale::Error> {
+        Ok(Default::default())
+    }
+
+    #[inline]
+    fn encoded_size(&self) -> usize {
+        0
+    }
+}
+
+impl<K, V, Key, InnerKey> StorableHint<Key> for IterableMapping<K, V, InnerKey>
+where
+    K: Packed,
+    V: Packed,
+    Key: StorageKey,
+    InnerKey: StorageKey,
+{
+    type Type = IterableMapping<K, V, Key>;
+    type PreferredKey = InnerKey;
+}
+
+impl<K, V, KeyType> StorageKey for IterableMapping<K, V, KeyType>
+where
+    K: Packed,
+    V: Packed,
+    KeyType: StorageKey,
+{
+    const KEY: Key = KeyType::KEY;
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use crate::traits::StorageLayout;
+    use ink_metadata::layout::{
+        Layout,
+        LayoutKey,
+        RootLayout,
+        StructLayout,
+    };
+
+    impl<K, V, KeyType> StorageLayout for IterableMapping<K, V, KeyType>
+    where
+        K: Packed + scale_info::TypeInfo + 'static,
+        V: Packed + StorageLayout + scale_info::TypeInfo + 'static,
+        KeyType: StorageKey + scale_info::TypeInfo + 'static,
+    {
+        fn layout(_: &Key) -> Layout {
+            Layout::Root(RootLayout::new(
+                LayoutKey::from(&KeyType::KEY),
+                StructLayout::linked_entry::<K, _>(
+                    LayoutKey::from(&KeyType::KEY),
+                    <V as StorageLayout>::layout(&KeyType::KEY),
+                ),
+                scale_info::meta_type::<Self>(),
+            ))
+        }
+    }
+};
+
+impl<K, V, KeyType> IterableMapping<K, V, KeyType>
+where
+    K: Packed,
+    V: Packed,
+    KeyType: StorageKey,
+{
+    /// Creates a new empty `IterableMapping`.
+    pub const fn new() -> Self {
+        Self {
+            header: Lazy::new(),
+            entries: Mapping::new(),
+        }
+    }
+}
+
+impl<K, V, KeyType> IterableMapping<K, V, KeyType>
+where
+    K: Packed + Clone + EncodeLike<K>,
+    V: Packed + EncodeLike<V>,
+    KeyType: StorageKey,
+{
+    /// Returns the number of entries in the mapping.
+    pub fn len(&self) -> u32 {
+        self.header().0
+    }
+
+    /// Returns `true` if the mapping contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of entries, the first and the last key.
+    fn header(&self) -> (u32, Option<K>, Option<K>) {
+        self.header.get().unwrap_or((0, None, None))
+    }
+
+    /// Overwrite the header. Writes directly to contract storage.
+    fn set_header(&mut self, len: u32, head: Option<K>, tail: Option<K>) {
+        self.header.set(&(len, head, tail));
+    }
+
+    /// Insert the given `value` under `key`.
+    ///
+    /// If the key was not present yet, the entry is appended after the most recently
+    /// inserted one. Otherwise only the value is replaced and the entry keeps its
+    /// position.
+    ///
+    /// Returns the previous value under `key` if any.
+    ///
+    /// # Panics
+    ///
+    /// * If the mapping is at capacity (max. of 2 ^ 32 entries).
+    /// * If encoding the `key` together with the entry doesn't fit into the static
+    ///   buffer.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some((previous, prev, next)) = self.entries.get(&key) {
+            self.entries.insert(&key, &(value, prev, next));
+            return Some(previous);
+        }
+
+        let (len, head, tail) = self.header();
+        let len = len.checked_add(1).unwrap();
+
+        if let Some(tail) = &tail {
+            self.set_next(tail, Some(key.clone()));
+        }
+        self.entries.insert(&key, &(value, tail, None));
+        self.set_header(len, head.or_else(|| Some(key.clone())), Some(key));
+
+        None
+    }
+
+    /// Get the `value` at `key` from the contract storage.
+    ///
+    /// Returns `None` if no `value` exists at the given `key`.
+    ///
+    /// # Panics
+    ///
+    /// Traps if the the encoded `key` or entry doesn't fit into the static buffer.
+    pub fn get<Q>(&self, key: Q) -> Option<V>
+    where
+        Q: EncodeLike<K>,
+    {
+        self.entries.get(key).map(|(value, _, _)| value)
+    }
+
+    /// Checks if a value is stored at the given `key` in the contract storage.
+    pub fn contains<Q>(&self, key: Q) -> bool
+    where
+        Q: EncodeLike<K>,
+    {
+        self.entries.contains(key)
+    }
+
+    /// Removes the entry at `key`, returning its value.
+    ///
+    /// The neighbours of the entry are linked to each other, the order of the remaining
+    /// entries is not changed.
+    ///
+    /// Returns `None` if no `value` exists at the given `key`.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let (value, prev, next) = self.entries.get(key)?;
+        self.entries.remove(key);
+
+        let (len, mut head, mut tail) = self.header();
+        match &prev {
+            Some(prev) => self.set_next(prev, next.clone()),
+            None => head = next.clone(),
+        }
+        match &next {
+            Some(next) => self.set_prev(next, prev),
+            None => tail = prev,
+        }
+        self.set_header(len.checked_sub(1).unwrap(), head, tail);
+
+        Some(value)
+    }
+
+    /// Returns the first key in insertion order.
+    pub fn first(&self) -> Option<K> {
+        self.header().1
+    }
+
+    /// Returns the last key in insertion order.
+    pub fn last(&self) -> Option<K> {
+        self.header().2
+    }
+
+    /// Returns an iterator over all entries in insertion order.
+    ///
+    /// Every step of the iterator reads a single entry from storage.
+    pub fn iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
+        let mut next = self.first();
+        core::iter::from_fn(move || {
+            let key = next.take()?;
+            let (value, _, following) = self.entries.get(&key)?;
+            next = following;
+            Some((key, value))
+        })
+    }
+
+    /// Returns an iterator over all keys in insertion order.
+    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+        self.iter().map(|(key, _)| key)
+    }
+
+    /// Delete all entries from storage.
+    ///
+    /// # Warning
+    ///
+    /// This iterates through all entries in the mapping; complexity is O(n).
+    /// It might not be possible to clear large mappings within a single block!
+    pub fn clear(&mut self) {
+        let mut next = self.first();
+        while let Some(key) = next {
+            next = self.entries.get(&key).and_then(|(_, _, next)| next);
+            self.entries.remove(&key);
+        }
+        self.set_header(0, None, None);
+    }
+
+    /// Points the entry at `key` to a new previous entry.
+    fn set_prev(&mut self, key: &K, prev: Option<K>) {
+        let (value, _, next) = self
+            .entries
+            .get(key)
+            .expect("linked entries are always present in storage; qed");
+        self.entries.insert(key, &(value, prev, next));
+    }
+
+    /// Points the entry at `key` to a new next entry.
+    fn set_next(&mut self, key: &K, next: Option<K>) {
+        let (value, prev, _) = self
+            .entries
+            .get(key)
+            .expect("linked entries are always present in storage; qed");
+        self.entries.insert(key, &(value, prev, next));
+    }
+}
+
+impl<K, V, KeyType> FromIterator<(K, V)> for IterableMapping<K, V, KeyType>
+where
+    K: Packed + Clone + EncodeLike<K>,
+    V: Packed + EncodeLike<V>,
+    KeyType: StorageKey,
+{
+    fn from_iter<T: IntoIterator<Item = (K, V)>>(iter: T) -> Self {
+        let mut result = IterableMapping::<K, V, KeyType>::new();
+
+        for (key, value) in iter {
+            result.insert(key, value);
+        }
+
+        result
+    }
+}
+
+impl<K, V, KeyType> ::core::fmt::Debug for IterableMapping<K, V, KeyType>
+where
+    K: Packed,
+    V: Packed,
+    KeyType: StorageKey,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("IterableMapping")
+            .field("key", &KeyType::KEY)
+            .field("header", &self.header)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ManualKey;
+
+    #[test]
+    fn insert_and_get_work() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut mapping: IterableMapping<u8, u8> = IterableMapping::new();
+            assert!(mapping.is_empty());
+
+            assert_eq!(mapping.insert(1, 2), None);
+            assert_eq!(mapping.get(1), Some(2));
+            assert!(mapping.contains(1));
+            assert!(!mapping.contains(2));
+            assert_eq!(mapping.len(), 1);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn iteration_follows_insertion_order() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut mapping: IterableMapping<u32, u32> = IterableMapping::new();
+            for key in [7, 3, 9, 1] {
+                mapping.insert(key, key * 10);
+            }
+
+            assert_eq!(mapping.keys().collect::<Vec<_>>(), vec![7, 3, 9, 1]);
+            assert_eq!(
+                mapping.iter().collect::<Vec<_>>(),
+                vec![(7, 70), (3, 30), (9, 90), (1, 10)]
+            );
+            assert_eq!(mapping.first(), Some(7));
+            assert_eq!(mapping.last(), Some(1));
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn reinsert_keeps_position() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut mapping: IterableMapping<u8, String> = IterableMapping::new();
+            mapping.insert(1, "a".to_string());
+            mapping.insert(2, "b".to_string());
+
+            assert_eq!(mapping.insert(1, "c".to_string()), Some("a".to_string()));
+            assert_eq!(mapping.len(), 2);
+            assert_eq!(
+                mapping.iter().collect::<Vec<_>>(),
+                vec![(1, "c".to_string()), (2, "b".to_string())]
+            );
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn remove_relinks_neighbours() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut mapping: IterableMapping<u8, u8> = (0..5).map(|i| (i, i)).collect();
+
+            // middle
+            assert_eq!(mapping.remove(&2), Some(2));
+            assert_eq!(mapping.keys().collect::<Vec<_>>(), vec![0, 1, 3, 4]);
+            // head
+            assert_eq!(mapping.remove(&0), Some(0));
+            assert_eq!(mapping.keys().collect::<Vec<_>>(), vec![1, 3, 4]);
+            // tail
+            assert_eq!(mapping.remove(&4), Some(4));
+            assert_eq!(mapping.keys().collect::<Vec<_>>(), vec![1, 3]);
+            // missing
+            assert_eq!(mapping.remove(&4), None);
+
+            assert_eq!(mapping.len(), 2);
+            assert_eq!(mapping.first(), Some(1));
+            assert_eq!(mapping.last(), Some(3));
+
+            mapping.insert(0, 0);
+            assert_eq!(mapping.keys().collect::<Vec<_>>(), vec![1, 3, 0]);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn removing_all_entries_empties_mapping() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut mapping: IterableMapping<u8, u8> = IterableMapping::new();
+            mapping.insert(1, 1);
+            mapping.remove(&1);
+
+            assert!(mapping.is_empty());
+            assert_eq!(mapping.first(), None);
+            assert_eq!(mapping.last(), None);
+            assert_eq!(mapping.iter().next(), None);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn clear_works() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut mapping: IterableMapping<u32, u128> =
+                (0..64).map(|i| (i, i as u128)).collect();
+
+            mapping.clear();
+
+            assert_eq!(mapping.len(), 0);
+            assert_eq!(mapping.get(0u32), None);
+            assert_eq!(mapping.get(63u32), None);
+            assert_eq!(mapping.iter().next(), None);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn storage_keys_are_correct() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            const BASE: u32 = 123;
+            let mut mapping: IterableMapping<u8, u8, ManualKey<BASE>> =
+                IterableMapping::new();
+
+            mapping.insert(1, 10);
+            mapping.insert(2, 20);
+
+            let header =
+                ink_env::get_contract_storage::<_, (u32, Option<u8>, Option<u8>)>(&BASE);
+            assert_eq!(header, Ok(Some((2, Some(1), Some(2)))));
+
+            let entry = ink_env::get_contract_storage::<_, (u8, Option<u8>, Option<u8>)>(
+                &(BASE, 1u8),
+            );
+            assert_eq!(entry, Ok(Some((10, None, Some(2)))));
+
+            Ok(())
+        })
+        .unwrap()
+    }
+}
@@ -35,11 +35,19 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+mod deque;
+mod iterable_mapping;
 mod mapping;
+mod set;
 mod vec;
 
 #[doc(inline)]
 pub use self::mapping::Mapping;
+pub use self::{
+    deque::StorageDeque,
+    iterable_mapping::IterableMapping,
+    set::StorageSet,
+};
 pub use self::vec::StorageVec;
 
 use crate::traits::{
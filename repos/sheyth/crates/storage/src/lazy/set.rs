@@ -0,0 +1,337 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+use ink_primitives::Key;
+use ink_storage_traits::{
+    AutoKey,
+    Packed,
+    Storable,
+    StorableHint,
+    StorageKey,
+};
+use scale::EncodeLike;
+
+use crate::IterableMapping;
+
+/// A set of unique values directly on contract storage.
+///
+/// # Important
+///
+/// [StorageSet] requires its own pre-defined storage key where to store values. By
+/// default, the is automatically calculated using [`AutoKey`](crate::traits::AutoKey)
+/// during compilation. However, anyone can specify a storage key using
+/// [`ManualKey`](crate::traits::ManualKey). Specifying the storage key can be helpful for
+/// upgradeable contracts or you want to be resistant to future changes of storage key
+/// calculation strategy.
+///
+/// # Differences between `ink::prelude::collections::BTreeSet` and [StorageSet]
+///
+/// Any `BTreeSet<T>` will exhibit [Packed] storage layout, hence reading or writing it
+/// loads or stores _all_ of its elements. [StorageSet] stores each element under its own
+/// storage key, so membership checks, insertions and removals only touch a few storage
+/// cells regardless of the size of the set.
+///
+/// Elements are iterated in insertion order.
+///
+/// # Storage Layout
+///
+/// [StorageSet] is an [IterableMapping] from the elements to `()`, see its documentation
+/// for the details. Given [StorageSet] under key `K`, the element `T` is stored under:
+///
+/// `E = scale::Encode((K, T))`
+///
+/// The element itself is only part of the storage key, the stored value is the tuple
+/// `((), previous_element, next_element)`.
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub struct StorageSet<T: Packed, KeyType: StorageKey = AutoKey> {
+    /// The elements of the set, mapped to nothing.
+    elements: IterableMapping<T, (), KeyType>,
+}
+
+impl<T, KeyType> Default for StorageSet<T, KeyType>
+where
+    T: Packed,
+    KeyType: StorageKey,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, KeyType> Storable for StorageSet<T, KeyType>
+where
+    T: Packed,
+    KeyType: StorageKey,
+{
+    #[inline]
+    fn encode<O: scale::Output + ?Sized>(&self, _dest: &mut O) {}
+
+    #[inline]
+    fn decode<I: scale::Input>(_input: &mut I) -> Result<Self, scale::Error> {
+        Ok(Default::default())
+    }
+
+    #[inline]
+    fn encoded_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T, Key, InnerKey> StorableHint<Key> for StorageSet<T, InnerKey>
+where
+    T: Packed,
+    Key: StorageKey,
+    InnerKey: StorageKey,
+{
+    type Type = StorageSet<T, Key>;
+    type PreferredKey = InnerKey;
+}
+
+impl<T, KeyType> StorageKey for StorageSet<T, KeyType>
+where
+    T: Packed,
+    KeyType: StorageKey,
+{
+    const KEY: Key = KeyType::KEY;
+}
+
+#[cfg(feature = "std")]
+const _: () = {
+    use crate::traits::StorageLayout;
+    use ink_metadata::layout::{
+        Layout,
+        LayoutKey,
+        RootLayout,
+        StructLayout,
+    };
+
+    impl<T, KeyType> StorageLayout for StorageSet<T, KeyType>
+    where
+        T: Packed + StorageLayout + scale_info::TypeInfo + 'static,
+        KeyType: StorageKey + scale_info::TypeInfo + 'static,
+    {
+        fn layout(_: &Key) -> Layout {
+            Layout::Root(RootLayout::new(
+                LayoutKey::from(&KeyType::KEY),
+                StructLayout::linked_entry::<T, _>(
+                    LayoutKey::from(&KeyType::KEY),
+                    <() as StorageLayout>::layout(&KeyType::KEY),
+                ),
+                scale_info::meta_type::<Self>(),
+            ))
+        }
+    }
+};
+
+impl<T, KeyType> StorageSet<T, KeyType>
+where
+    T: Packed,
+    KeyType: StorageKey,
+{
+    /// Creates a new empty `StorageSet`.
+    pub const fn new() -> Self {
+        Self {
+            elements: IterableMapping::new(),
+        }
+    }
+}
+
+impl<T, KeyType> StorageSet<T, KeyType>
+where
+    T: Packed + Clone + EncodeLike<T>,
+    KeyType: StorageKey,
+{
+    /// Returns the number of elements in the set.
+    pub fn len(&self) -> u32 {
+        self.elements.len()
+    }
+
+    /// Returns `true` if the set contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Adds a value to the set.
+    ///
+    /// Returns `true` if the value was not present in the set yet.
+    ///
+    /// # Panics
+    ///
+    /// * If the set is at capacity (max. of 2 ^ 32 elements).
+    /// * If the encoded value doesn't fit into the static buffer.
+    pub fn insert(&mut self, value: T) -> bool {
+        if self.elements.contains(&value) {
+            return false;
+        }
+        self.elements.insert(value, ());
+        true
+    }
+
+    /// Returns `true` if the set contains the given `value`.
+    pub fn contains<Q>(&self, value: Q) -> bool
+    where
+        Q: EncodeLike<T>,
+    {
+        self.elements.contains(value)
+    }
+
+    /// Removes a value from the set.
+    ///
+    /// Returns `true` if the value was present in the set.
+    pub fn remove(&mut self, value: &T) -> bool {
+        self.elements.remove(value).is_some()
+    }
+
+    /// Returns an iterator over all elements in insertion order.
+    ///
+    /// Every step of the iterator reads a single element from storage.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.elements.keys()
+    }
+
+    /// Delete all elements from storage.
+    ///
+    /// # Warning
+    ///
+    /// This iterates through all elements in the set; complexity is O(n).
+    /// It might not be possible to clear large sets within a single block!
+    pub fn clear(&mut self) {
+        self.elements.clear()
+    }
+}
+
+impl<T, KeyType> FromIterator<T> for StorageSet<T, KeyType>
+where
+    T: Packed + Clone + EncodeLike<T>,
+    KeyType: StorageKey,
+{
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut result = StorageSet::<T, KeyType>::new();
+
+        for value in iter {
+            result.insert(value);
+        }
+
+        result
+    }
+}
+
+impl<T, KeyType> ::core::fmt::Debug for StorageSet<T, KeyType>
+where
+    T: Packed,
+    KeyType: StorageKey,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_struct("StorageSet")
+            .field("key", &KeyType::KEY)
+            .field("elements", &self.elements)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::ManualKey;
+
+    #[test]
+    fn insert_and_contains_work() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut set: StorageSet<u32> = StorageSet::new();
+            assert!(set.is_empty());
+
+            assert!(set.insert(1));
+            assert!(!set.insert(1));
+            assert!(set.contains(1u32));
+            assert!(!set.contains(2u32));
+            assert_eq!(set.len(), 1);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn remove_works() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut set: StorageSet<String> =
+                ["a", "b", "c"].into_iter().map(String::from).collect();
+
+            assert!(set.remove(&"b".to_string()));
+            assert!(!set.remove(&"b".to_string()));
+            assert_eq!(set.len(), 2);
+            assert_eq!(
+                set.iter().collect::<Vec<_>>(),
+                vec!["a".to_string(), "c".to_string()]
+            );
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn clear_works() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut set: StorageSet<u8> = (0..32).collect();
+
+            set.clear();
+
+            assert!(set.is_empty());
+            assert!(!set.contains(0u8));
+            assert_eq!(set.iter().next(), None);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn two_sets_with_same_manual_key_share_elements() {
+        ink_env::test::run_test::<ink_env::DefaultEnvironment, _>(|_| {
+            let mut set: StorageSet<u8, ManualKey<123>> = StorageSet::new();
+            set.insert(7);
+
+            let set2: StorageSet<u8, ManualKey<123>> = StorageSet::new();
+            assert!(set2.contains(7u8));
+            assert_eq!(set2.len(), 1);
+
+            Ok(())
+        })
+        .unwrap()
+    }
+}
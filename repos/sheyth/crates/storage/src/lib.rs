@@ -66,7 +66,10 @@ pub(crate) mod lazy;
 
 #[doc(inline)]
 pub use self::lazy::{
+    IterableMapping,
     Lazy,
     Mapping,
+    StorageDeque,
+    StorageSet,
     StorageVec,
 };
@@ -0,0 +1,380 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+use ink::{
+    env::Environment,
+    prelude::{
+        collections::BTreeMap,
+        vec::Vec,
+    },
+};
+
+type DefaultAccountId = <ink::env::DefaultEnvironment as Environment>::AccountId;
+type DefaultBalance = <ink::env::DefaultEnvironment as Environment>::Balance;
+
+/// A price as a fixed point number with 18 decimals, i.e. the inner value of the
+/// runtime's `FixedU128`.
+pub type Price = u128;
+/// The NFT class id used by `module_nft`.
+pub type ClassId = u32;
+/// The NFT token id used by `module_nft`.
+pub type TokenId = u64;
+
+/// Mirrors `primitives::TokenSymbol` of the Setheum runtime.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum TokenSymbol {
+    SEU = 0,
+    SEUSD = 1,
+}
+
+/// Mirrors `primitives::DexShare` of the Setheum runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum DexShare {
+    Token(TokenSymbol),
+    Erc20([u8; 20]),
+    ForeignAsset(u16),
+}
+
+/// Mirrors `primitives::CurrencyId` of the Setheum runtime.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum CurrencyId {
+    Token(TokenSymbol),
+    DexShare(DexShare, DexShare),
+    Erc20([u8; 20]),
+    ForeignAsset(u16),
+}
+
+/// Gives ink! contracts access to Setheum's currencies, DEX, prices and NFTs.
+///
+/// Dispatching functions act on behalf of the calling contract's account.
+#[ink::chain_extension(extension = 1)]
+pub trait SetheumDefiExtension {
+    type ErrorCode = SetheumError;
+
+    // module_currencies
+
+    #[ink(function = 0x0001)]
+    fn free_balance(currency_id: CurrencyId, who: DefaultAccountId) -> DefaultBalance;
+
+    #[ink(function = 0x0002)]
+    fn total_issuance(currency_id: CurrencyId) -> DefaultBalance;
+
+    #[ink(function = 0x0003)]
+    fn transfer(currency_id: CurrencyId, to: DefaultAccountId, amount: DefaultBalance);
+
+    // DEX
+
+    #[ink(function = 0x0101)]
+    fn liquidity_pool(
+        currency_id_a: CurrencyId,
+        currency_id_b: CurrencyId,
+    ) -> (DefaultBalance, DefaultBalance);
+
+    /// Returns the `(supply, target)` amounts for supplying exactly `supply`.
+    #[ink(function = 0x0102)]
+    fn quote_exact_supply(
+        path: Vec<CurrencyId>,
+        supply: DefaultBalance,
+        min_target: DefaultBalance,
+    ) -> Option<(DefaultBalance, DefaultBalance)>;
+
+    /// Returns the `(supply, target)` amounts for receiving exactly `target`.
+    #[ink(function = 0x0103)]
+    fn quote_exact_target(
+        path: Vec<CurrencyId>,
+        max_supply: DefaultBalance,
+        target: DefaultBalance,
+    ) -> Option<(DefaultBalance, DefaultBalance)>;
+
+    #[ink(function = 0x0104)]
+    fn swap_exact_supply(
+        path: Vec<CurrencyId>,
+        supply: DefaultBalance,
+        min_target: DefaultBalance,
+    ) -> (DefaultBalance, DefaultBalance);
+
+    #[ink(function = 0x0105)]
+    fn swap_exact_target(
+        path: Vec<CurrencyId>,
+        max_supply: DefaultBalance,
+        target: DefaultBalance,
+    ) -> (DefaultBalance, DefaultBalance);
+
+    // module_prices and module_oracle
+
+    #[ink(function = 0x0201)]
+    fn price(currency_id: CurrencyId) -> Option<Price>;
+
+    #[ink(function = 0x0202)]
+    fn relative_price(base: CurrencyId, quote: CurrencyId) -> Option<Price>;
+
+    #[ink(function = 0x0203)]
+    fn oracle_price(currency_id: CurrencyId) -> Option<Price>;
+
+    // module_nft
+
+    #[ink(function = 0x0301)]
+    fn nft_balance(who: DefaultAccountId) -> u64;
+
+    #[ink(function = 0x0302)]
+    fn nft_owner(class_id: ClassId, token_id: TokenId) -> Option<DefaultAccountId>;
+
+    #[ink(function = 0x0303)]
+    fn nft_transfer(to: DefaultAccountId, class_id: ClassId, token_id: TokenId);
+
+    #[ink(function = 0x0304)]
+    fn nft_mint(
+        class_id: ClassId,
+        to: DefaultAccountId,
+        metadata: Vec<u8>,
+        attributes: BTreeMap<Vec<u8>, Vec<u8>>,
+        quantity: u32,
+    );
+
+    #[ink(function = 0x0305)]
+    fn nft_burn(class_id: ClassId, token_id: TokenId);
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[ink::scale_derive(Encode, Decode, TypeInfo)]
+pub enum SetheumError {
+    CurrencyFailed,
+    SwapFailed,
+    NftFailed,
+}
+
+impl ink::env::chain_extension::FromStatusCode for SetheumError {
+    fn from_status_code(status_code: u32) -> core::result::Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            1 => Err(Self::CurrencyFailed),
+            2 => Err(Self::SwapFailed),
+            3 => Err(Self::NftFailed),
+            _ => panic!("encountered unknown status code"),
+        }
+    }
+}
+
+/// An environment using default ink environment types, with the Setheum DeFi extension
+/// included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[ink::scale_derive(TypeInfo)]
+pub enum SetheumEnvironment {}
+
+impl Environment for SetheumEnvironment {
+    const MAX_EVENT_TOPICS: usize =
+        <ink::env::DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = DefaultAccountId;
+    type Balance = DefaultBalance;
+    type Hash = <ink::env::DefaultEnvironment as Environment>::Hash;
+    type Timestamp = <ink::env::DefaultEnvironment as Environment>::Timestamp;
+    type BlockNumber = <ink::env::DefaultEnvironment as Environment>::BlockNumber;
+
+    type ChainExtension = crate::SetheumDefiExtension;
+}
+
+#[ink::contract(env = crate::SetheumEnvironment)]
+mod setheum_defi {
+    use super::{
+        ClassId,
+        CurrencyId,
+        Price,
+        SetheumError,
+        TokenId,
+        Vec,
+    };
+
+    /// A vault that holds currencies and NFTs on behalf of its owner.
+    #[ink(storage)]
+    pub struct SetheumDefi {
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Swapped {
+        supply: Balance,
+        target: Balance,
+    }
+
+    impl SetheumDefi {
+        /// Creates a new vault owned by the caller.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Returns the vault's free balance of `currency_id`.
+        #[ink(message)]
+        pub fn balance(&self, currency_id: CurrencyId) -> Result<Balance, SetheumError> {
+            self.env()
+                .extension()
+                .free_balance(currency_id, self.env().account_id())
+        }
+
+        /// Sends `amount` of `currency_id` from the vault to `to`.
+        #[ink(message)]
+        pub fn withdraw(
+            &mut self,
+            currency_id: CurrencyId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), SetheumError> {
+            self.ensure_owner();
+            self.env().extension().transfer(currency_id, to, amount)
+        }
+
+        /// Returns the expected target amount for swapping exactly `supply`.
+        #[ink(message)]
+        pub fn quote(
+            &self,
+            path: Vec<CurrencyId>,
+            supply: Balance,
+        ) -> Result<Option<Balance>, SetheumError> {
+            let quote = self.env().extension().quote_exact_supply(path, supply, 0)?;
+            Ok(quote.map(|(_, target)| target))
+        }
+
+        /// Swaps exactly `supply` held by the vault along `path`.
+        #[ink(message)]
+        pub fn swap(
+            &mut self,
+            path: Vec<CurrencyId>,
+            supply: Balance,
+            min_target: Balance,
+        ) -> Result<Balance, SetheumError> {
+            self.ensure_owner();
+            let (supply, target) = self
+                .env()
+                .extension()
+                .swap_exact_supply(path, supply, min_target)?;
+            self.env().emit_event(Swapped { supply, target });
+            Ok(target)
+        }
+
+        /// Returns the real-time price of `currency_id`.
+        #[ink(message)]
+        pub fn price(
+            &self,
+            currency_id: CurrencyId,
+        ) -> Result<Option<Price>, SetheumError> {
+            self.env().extension().price(currency_id)
+        }
+
+        /// Returns the owner of an NFT.
+        #[ink(message)]
+        pub fn nft_owner(
+            &self,
+            class_id: ClassId,
+            token_id: TokenId,
+        ) -> Result<Option<AccountId>, SetheumError> {
+            self.env().extension().nft_owner(class_id, token_id)
+        }
+
+        /// Sends an NFT held by the vault to `to`.
+        #[ink(message)]
+        pub fn withdraw_nft(
+            &mut self,
+            to: AccountId,
+            class_id: ClassId,
+            token_id: TokenId,
+        ) -> Result<(), SetheumError> {
+            self.ensure_owner();
+            self.env().extension().nft_transfer(to, class_id, token_id)
+        }
+
+        fn ensure_owner(&self) {
+            assert_eq!(self.env().caller(), self.owner, "caller is not the owner");
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::TokenSymbol;
+
+        const SEU: CurrencyId = CurrencyId::Token(TokenSymbol::SEU);
+        const SEUSD: CurrencyId = CurrencyId::Token(TokenSymbol::SEUSD);
+
+        struct MockedSetheumExtension;
+        impl ink::env::test::ChainExtension for MockedSetheumExtension {
+            fn ext_id(&self) -> u16 {
+                1
+            }
+
+            fn call(&mut self, func_id: u16, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                match func_id {
+                    0x0001 => ink::scale::Encode::encode_to(&100u128, output),
+                    0x0102 => {
+                        ink::scale::Encode::encode_to(&Some((10u128, 20u128)), output)
+                    },
+                    0x0104 => return 2,
+                    _ => unimplemented!("unexpected func_id"),
+                }
+                0
+            }
+        }
+
+        #[ink::test]
+        fn queries_work() {
+            ink::env::test::register_chain_extension(MockedSetheumExtension);
+            let vault = SetheumDefi::new();
+
+            assert_eq!(vault.balance(SEU), Ok(100));
+            assert_eq!(vault.quote(Vec::from([SEU, SEUSD]), 10), Ok(Some(20)));
+        }
+
+        #[ink::test]
+        fn failed_swap_returns_error() {
+            ink::env::test::register_chain_extension(MockedSetheumExtension);
+            let mut vault = SetheumDefi::new();
+
+            assert_eq!(
+                vault.swap(Vec::from([SEU, SEUSD]), 10, 0),
+                Err(SetheumError::SwapFailed)
+            );
+        }
+    }
+}
@@ -47,7 +47,6 @@ pub struct Contract {
     pub name: String,
     pub address: H160,
     pub base_path: PathBuf,
-    #[allow(dead_code)]
     pub transcoder: Arc<ContractMessageTranscoder>,
 }
 
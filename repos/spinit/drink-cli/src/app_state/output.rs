@@ -43,6 +43,7 @@ pub struct Output {
     offset: u16,
     scrolling: bool,
     window_height: u16,
+    errors: usize,
 }
 
 impl Output {
@@ -54,6 +55,15 @@ impl Output {
         self.content.push(line)
     }
 
+    pub fn note_error(&mut self) {
+        self.errors += 1;
+    }
+
+    /// Number of errors printed since the output was last cleared.
+    pub fn error_count(&self) -> usize {
+        self.errors
+    }
+
     pub fn clear(&mut self) {
         *self = Default::default();
     }
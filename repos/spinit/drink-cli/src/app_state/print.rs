@@ -68,6 +68,7 @@ impl AppState {
     }
 
     pub fn print_error(&mut self, err: &str) {
+        self.ui_state.output.note_error();
         self.print_sequence(
             err.split('\n'),
             Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
@@ -35,6 +35,8 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
+use std::path::PathBuf;
+
 use clap::Parser;
 use drink::{AccountId32, Ss58Codec};
 
@@ -66,8 +68,17 @@ pub enum CliCommand {
         proof_size: u64,
     },
 
+    Save {
+        path: PathBuf,
+    },
+    Load {
+        path: PathBuf,
+    },
+
     #[clap(alias = "b")]
     Build,
+    #[clap(alias = "u")]
+    Upload,
     #[clap(alias = "d")]
     Deploy {
         #[clap(long, default_value = "new")]
@@ -80,6 +91,15 @@ pub enum CliCommand {
         message: String,
         args: Vec<String>,
     },
+    #[clap(alias = "dr")]
+    DryRun {
+        message: String,
+        args: Vec<String>,
+    },
+    #[clap(alias = "e")]
+    Events,
+    #[clap(alias = "s")]
+    Storage,
 }
 
 #[cfg(test)]
@@ -43,6 +43,10 @@ use std::{
 
 use contract_build::{BuildMode, ExecuteArgs, ManifestPath, Verbosity};
 use contract_transcode::ContractMessageTranscoder;
+use drink::{minimal::MinimalSandboxRuntime, pallet_revive, Sandbox};
+use ink_metadata::layout::{Layout, RootLayout};
+use parity_scale_codec::Encode;
+use scale_info::form::PortableForm;
 
 use crate::{
     app_state::{print::format_contract_action, AppState, Contract},
@@ -82,6 +86,36 @@ pub fn build(app_state: &mut AppState) {
     }
 }
 
+/// Read the name and the raw code of the contract built in the current directory.
+fn read_contract_blob(app_state: &mut AppState) -> Option<(String, Vec<u8>)> {
+    let Some((contract_name, contract_file)) = find_contract_blob(&app_state.ui_state.cwd) else {
+        app_state.print_error("Failed to find contract file");
+        return None;
+    };
+
+    match fs::read(contract_file) {
+        Ok(bytes) => Some((contract_name, bytes)),
+        Err(err) => {
+            app_state.print_error(&format!("Failed to read contract bytes\n{err}"));
+            None
+        }
+    }
+}
+
+/// Upload the code of the contract from the current directory without instantiating it.
+pub fn upload(app_state: &mut AppState) {
+    let Some((contract_name, contract_bytes)) = read_contract_blob(app_state) else {
+        return;
+    };
+
+    match app_state.session.upload(contract_bytes) {
+        Ok(code_hash) => app_state.print(&format!(
+            "Code of {contract_name} uploaded with hash {code_hash:?}"
+        )),
+        Err(err) => app_state.print_error(&format!("Failed to upload contract\n{err}")),
+    }
+}
+
 pub fn deploy(
     app_state: &mut AppState,
     constructor: String,
@@ -89,19 +123,10 @@ pub fn deploy(
     salt: Option<[u8; 32]>,
 ) {
     // Get raw contract bytes
-    let Some((contract_name, contract_file)) = find_contract_blob(&app_state.ui_state.cwd) else {
-        app_state.print_error("Failed to find contract file");
+    let Some((contract_name, contract_bytes)) = read_contract_blob(app_state) else {
         return;
     };
 
-    let contract_bytes = match fs::read(contract_file) {
-        Ok(bytes) => bytes,
-        Err(err) => {
-            app_state.print_error(&format!("Failed to read contract bytes\n{err}"));
-            return;
-        }
-    };
-
     // Read contract metadata and prepare transcoder
     let metadata_path = app_state
         .ui_state
@@ -159,6 +184,155 @@ pub fn call(app_state: &mut AppState, message: String, args: Vec<String>) {
     }
 }
 
+/// Call a message of the current contract without persisting any of its effects.
+pub fn dry_run(app_state: &mut AppState, message: String, args: Vec<String>) {
+    let Some(contract) = app_state.contracts.current_contract() else {
+        app_state.print_error("No deployed contract");
+        return;
+    };
+    let address = contract.address;
+    let transcoder = contract.transcoder.clone();
+
+    let result = match app_state
+        .session
+        .dry_run_call(address, &message, &args, None)
+    {
+        Ok(result) => result,
+        Err(err) => {
+            app_state.print_error(&format!("Failed to dry-run contract\n{err}"));
+            return;
+        }
+    };
+
+    match &result.result {
+        Ok(exec_result) => {
+            let value = transcoder
+                .decode_message_return(&message, &mut exec_result.data.as_slice())
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| format!("0x{}", hex::encode(&exec_result.data)));
+            match exec_result.did_revert() {
+                true => app_state.print_error(&format!("Reverted: {value}")),
+                false => app_state.print(&format!("Result: {value}")),
+            }
+        }
+        Err(err) => app_state.print_error(&format!("Dry run failed before execution: {err:?}")),
+    }
+    app_state.print(&format_contract_action(&result));
+}
+
+/// List the events recorded during the session, decoding contract events with the metadata of
+/// the current contract.
+pub fn events(app_state: &mut AppState) {
+    let transcoder = app_state
+        .contracts
+        .current_contract()
+        .map(|contract| contract.transcoder.clone());
+    let batches = app_state.session.record().event_batches();
+    if batches.is_empty() {
+        app_state.print("No events recorded");
+        return;
+    }
+
+    let mut lines = vec![];
+    for (index, batch) in batches.iter().enumerate() {
+        let contract_events = batch.contract_events();
+        lines.push(format!(
+            "Interaction {index}: {} runtime events, {} contract events",
+            batch.all_events().len(),
+            contract_events.len()
+        ));
+        match &transcoder {
+            Some(transcoder) => lines.extend(
+                batch
+                    .contract_events_decoded(transcoder)
+                    .into_iter()
+                    .map(|event| format!("  {event}")),
+            ),
+            None => lines.extend(
+                contract_events
+                    .into_iter()
+                    .map(|data| format!("  0x{}", hex::encode(data))),
+            ),
+        }
+    }
+    app_state.print(&lines.join("\n"));
+}
+
+/// Print the storage cells of the current contract, decoded with its storage layout.
+///
+/// Every root of the layout (the contract itself and each `Lazy` field) is a single cell. Entries
+/// of a `Mapping` live under hashed keys that cannot be enumerated, so mappings are only named.
+pub fn storage(app_state: &mut AppState) {
+    let Some(contract) = app_state.contracts.current_contract() else {
+        app_state.print_error("No deployed contract");
+        return;
+    };
+    let address = contract.address;
+    let transcoder = contract.transcoder.clone();
+
+    let mut roots = vec![];
+    collect_roots(transcoder.metadata().layout(), &mut roots);
+
+    let mut lines = vec![];
+    for root in roots {
+        let key = *root.root_key().key();
+        let type_id = root.ty().id;
+        let value = app_state.session.sandbox().execute_with(|| {
+            pallet_revive::Pallet::<MinimalSandboxRuntime>::get_storage_var_key(
+                address,
+                key.encode(),
+            )
+        });
+
+        let line = match value {
+            Ok(Some(value)) => match transcoder.decode(type_id, &mut value.as_slice()) {
+                Ok(decoded) => decoded.to_string(),
+                Err(err) => format!("0x{} (failed to decode: {err})", hex::encode(&value)),
+            },
+            Ok(None) if is_mapping(&transcoder, type_id) => {
+                "<mapping, entries are not enumerable>".to_string()
+            }
+            Ok(None) => "<empty>".to_string(),
+            Err(err) => format!("<inaccessible: {err:?}>"),
+        };
+        lines.push(format!("0x{key:08x}: {line}"));
+    }
+    app_state.print(&lines.join("\n"));
+}
+
+fn collect_roots<'a>(
+    layout: &'a Layout<PortableForm>,
+    roots: &mut Vec<&'a RootLayout<PortableForm>>,
+) {
+    match layout {
+        Layout::Root(root) => {
+            roots.push(root);
+            collect_roots(root.layout(), roots);
+        }
+        Layout::Struct(layout) => layout
+            .fields()
+            .iter()
+            .for_each(|field| collect_roots(field.layout(), roots)),
+        Layout::Enum(layout) => layout
+            .variants()
+            .values()
+            .flat_map(|variant| variant.fields())
+            .for_each(|field| collect_roots(field.layout(), roots)),
+        Layout::Array(layout) => collect_roots(layout.layout(), roots),
+        Layout::Hash(layout) => collect_roots(layout.layout(), roots),
+        Layout::Leaf(_) => {}
+    }
+}
+
+fn is_mapping(transcoder: &ContractMessageTranscoder, type_id: u32) -> bool {
+    transcoder
+        .metadata()
+        .registry()
+        .resolve(type_id)
+        .and_then(|ty| ty.path.segments.last())
+        .is_some_and(|name| name == "Mapping")
+}
+
 fn find_contract_blob(cwd: &Path) -> Option<(String, PathBuf)> {
     let Ok(entries) = fs::read_dir(cwd.join("target/ink")) else {
         return None;
@@ -37,6 +37,7 @@
 
 mod contract;
 mod error;
+mod snapshot;
 
 use std::env;
 
@@ -55,7 +56,12 @@ fn vec_u8_to_array_32(bytes: Vec<u8>) -> [u8; 32] {
 
 pub fn execute(app_state: &mut AppState) -> Result<()> {
     let command = app_state.ui_state.user_input.current_input().to_string();
-    app_state.print_command(&command);
+    execute_command(app_state, &command)
+}
+
+/// Executes a single command line, printing its outcome to the output.
+pub fn execute_command(app_state: &mut AppState, command: &str) -> Result<()> {
+    app_state.print_command(command);
 
     let command = command
         .split_ascii_whitespace()
@@ -96,7 +102,10 @@ pub fn execute(app_state: &mut AppState) -> Result<()> {
             app_state.chain_info.gas_limit = Weight::from_parts(ref_time, proof_size);
             app_state.print("Gas limit was set");
         }
+        CliCommand::Save { path } => snapshot::save(app_state, &path),
+        CliCommand::Load { path } => snapshot::load(app_state, &path),
         CliCommand::Build => contract::build(app_state),
+        CliCommand::Upload => contract::upload(app_state),
         CliCommand::Deploy {
             constructor,
             args,
@@ -105,6 +114,9 @@ pub fn execute(app_state: &mut AppState) -> Result<()> {
             contract::deploy(app_state, constructor, args, Some(vec_u8_to_array_32(salt)));
         }
         CliCommand::Call { message, args } => contract::call(app_state, message, args),
+        CliCommand::DryRun { message, args } => contract::dry_run(app_state, message, args),
+        CliCommand::Events => contract::events(app_state),
+        CliCommand::Storage => contract::storage(app_state),
     }
 
     Ok(())
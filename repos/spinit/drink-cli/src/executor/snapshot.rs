@@ -0,0 +1,224 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Saving and loading the whole CLI session to and from disk.
+//!
+//! Besides the sandbox storage, a session file keeps the actor, the gas limit and the deployed
+//! contracts. Transcoders are not stored, they are loaded again from the metadata in each
+//! contract's directory.
+//!
+//! The file starts with [`MAGIC`] and the format version, followed by the SCALE encoded
+//! session. Files of other versions are rejected rather than misread.
+
+use std::{fs, path::Path, sync::Arc};
+
+use anyhow::{bail, Context, Result};
+use contract_transcode::ContractMessageTranscoder;
+use drink::{pallet_revive::evm::H160, AccountId32, Sandbox, Weight};
+use ink_sandbox::{Snapshot, H256};
+use parity_scale_codec::{Decode, Encode};
+
+use crate::app_state::{AppState, Contract, ContractRegistry};
+
+/// Marks a file as a drink-cli session.
+const MAGIC: [u8; 8] = *b"drinkses";
+/// Version of the session file format, bumped on every change of [`SessionFile`].
+const VERSION: u16 = 1;
+
+#[derive(Encode, Decode)]
+struct SavedContract {
+    name: String,
+    address: H160,
+    base_path: String,
+}
+
+#[derive(Encode, Decode)]
+struct SessionFile {
+    storage: Vec<(Vec<u8>, (Vec<u8>, i32))>,
+    storage_root: H256,
+    block_height: u32,
+    actor: AccountId32,
+    gas_limit: Weight,
+    contracts: Vec<SavedContract>,
+}
+
+impl SessionFile {
+    fn to_bytes(&self) -> Vec<u8> {
+        (MAGIC, VERSION, self).encode()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut input = bytes;
+        if input.len() < MAGIC.len() || input[..MAGIC.len()] != MAGIC {
+            bail!("Not a drink-cli session file");
+        }
+        input = &input[MAGIC.len()..];
+        let version = u16::decode(&mut input).context("Invalid session file")?;
+        if version != VERSION {
+            bail!("Unsupported session file version {version}, expected {VERSION}");
+        }
+        Self::decode(&mut input).context("Invalid session file")
+    }
+}
+
+pub fn save(app_state: &mut AppState, path: &Path) {
+    match save_result(app_state, path) {
+        Ok(()) => app_state.print(&format!("Session saved to {}", path.display())),
+        Err(err) => app_state.print_error(&format!("Failed to save session\n{err:#}")),
+    }
+}
+
+pub fn load(app_state: &mut AppState, path: &Path) {
+    match load_result(app_state, path) {
+        Ok(()) => app_state.print(&format!("Session loaded from {}", path.display())),
+        Err(err) => app_state.print_error(&format!("Failed to load session\n{err:#}")),
+    }
+}
+
+fn save_result(app_state: &mut AppState, path: &Path) -> Result<()> {
+    let Snapshot {
+        storage,
+        storage_root,
+    } = app_state.session.sandbox().take_snapshot();
+    let file = SessionFile {
+        storage,
+        storage_root,
+        block_height: app_state.chain_info.block_height,
+        actor: app_state.chain_info.actor.clone(),
+        gas_limit: app_state.chain_info.gas_limit,
+        contracts: app_state
+            .contracts
+            .get_all()
+            .iter()
+            .map(|contract| SavedContract {
+                name: contract.name.clone(),
+                address: contract.address,
+                base_path: contract.base_path.to_string_lossy().to_string(),
+            })
+            .collect(),
+    };
+    let path = app_state.ui_state.cwd.join(path);
+    fs::write(&path, file.to_bytes()).with_context(|| format!("Cannot write {}", path.display()))
+}
+
+fn load_result(app_state: &mut AppState, path: &Path) -> Result<()> {
+    let path = app_state.ui_state.cwd.join(path);
+    let bytes = fs::read(&path).with_context(|| format!("Cannot read {}", path.display()))?;
+    let file = SessionFile::from_bytes(&bytes)?;
+
+    // Load all transcoders first, so that a failure leaves the current session untouched.
+    let mut contracts = ContractRegistry::default();
+    for saved in file.contracts {
+        let metadata_path =
+            Path::new(&saved.base_path).join(format!("target/ink/{}.json", saved.name));
+        let transcoder = ContractMessageTranscoder::load(&metadata_path)
+            .with_context(|| format!("Cannot load metadata {}", metadata_path.display()))?;
+        contracts.add(Contract {
+            name: saved.name,
+            address: saved.address,
+            base_path: saved.base_path.into(),
+            transcoder: Arc::new(transcoder),
+        });
+    }
+
+    app_state.session.sandbox().restore_snapshot(Snapshot {
+        storage: file.storage,
+        storage_root: file.storage_root,
+    });
+    for contract in contracts.get_all() {
+        app_state
+            .session
+            .set_transcoder(contract.address, &contract.transcoder);
+    }
+    app_state.session.set_actor(file.actor.clone());
+    app_state.session.set_gas_limit(file.gas_limit);
+    app_state.chain_info.block_height = file.block_height;
+    app_state.chain_info.actor = file.actor;
+    app_state.chain_info.gas_limit = file.gas_limit;
+    app_state.contracts = contracts;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn session_survives_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("drink-cli-session-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let mut app_state = AppState::new(Some(dir.clone()));
+        app_state.chain_info.block_height = 7;
+        app_state.chain_info.actor = AccountId32::new([1; 32]);
+        app_state.chain_info.gas_limit = Weight::from_parts(11, 13);
+        let storage_root = app_state.session.sandbox().take_snapshot().storage_root;
+        save_result(&mut app_state, Path::new("session")).unwrap();
+
+        let mut loaded = AppState::new(Some(dir.clone()));
+        load_result(&mut loaded, Path::new("session")).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.chain_info.block_height, 7);
+        assert_eq!(loaded.chain_info.actor, AccountId32::new([1; 32]));
+        assert_eq!(loaded.chain_info.gas_limit, Weight::from_parts(11, 13));
+        assert_eq!(
+            loaded.session.sandbox().take_snapshot().storage_root,
+            storage_root
+        );
+    }
+
+    #[test]
+    fn foreign_and_newer_files_are_rejected() {
+        let file = SessionFile {
+            storage: vec![],
+            storage_root: H256::zero(),
+            block_height: 0,
+            actor: AccountId32::new([0; 32]),
+            gas_limit: Weight::zero(),
+            contracts: vec![],
+        };
+        let bytes = file.to_bytes();
+        assert!(SessionFile::from_bytes(&bytes).is_ok());
+        assert!(SessionFile::from_bytes(&file.encode()).is_err());
+
+        let mut newer = bytes;
+        newer[MAGIC.len()..MAGIC.len() + 2].copy_from_slice(&(VERSION + 1).to_le_bytes());
+        let err = SessionFile::from_bytes(&newer).err().unwrap();
+        assert!(err.to_string().contains("Unsupported session file version"));
+    }
+}
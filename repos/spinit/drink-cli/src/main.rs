@@ -40,11 +40,12 @@ use std::path::PathBuf;
 use anyhow::Result;
 use clap::Parser;
 
-use crate::ui::run_ui;
+use crate::{app_state::AppState, script::run_script, ui::run_ui};
 
 mod app_state;
 mod cli;
 mod executor;
+mod script;
 mod ui;
 
 #[derive(Parser, Debug)]
@@ -53,9 +54,16 @@ struct Args {
     /// Starts the CLI in the provided directory
     #[arg(short, long, value_name = "DIRECTORY")]
     path: Option<PathBuf>,
+
+    /// Runs the commands from the script file instead of starting the TUI
+    #[arg(short, long, value_name = "FILE")]
+    script: Option<PathBuf>,
 }
 
 fn main() -> Result<()> {
     let args = Args::parse();
-    run_ui(args.path)
+    match args.script {
+        Some(script) => run_script(&script, &mut AppState::new(args.path)),
+        None => run_ui(args.path),
+    }
 }
@@ -0,0 +1,210 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Non-interactive mode, replaying a script of commands and checking their outputs.
+//!
+//! Every non-empty line of a script is either a command, exactly as typed in the TUI, or one of
+//! the assertions about the output of the preceding command:
+//! - `expect <text>` - the command succeeded and its output contains `<text>`,
+//! - `expect-error [<text>]` - the command failed (and its output contains `<text>`).
+//!
+//! Lines starting with `#` are comments. A command that fails without a following `expect-error`
+//! fails the whole script.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{app_state::AppState, executor::execute_command};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Step<'a> {
+    Command(&'a str),
+    Expect(&'a str),
+    ExpectError(Option<&'a str>),
+}
+
+fn parse_line(line: &str) -> Option<Step<'_>> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    Some(match keyword {
+        "expect" => Step::Expect(rest),
+        "expect-error" => Step::ExpectError((!rest.is_empty()).then_some(rest)),
+        _ => Step::Command(line),
+    })
+}
+
+struct Outcome {
+    line: usize,
+    output: String,
+    failed: bool,
+    failure_expected: bool,
+}
+
+pub fn run_script(script: &Path, app_state: &mut AppState) -> Result<()> {
+    let content = fs::read_to_string(script)
+        .with_context(|| format!("Cannot read script {}", script.display()))?;
+
+    let mut last: Option<Outcome> = None;
+    for (index, line) in content.lines().enumerate() {
+        let line_number = index + 1;
+        let Some(step) = parse_line(line) else {
+            continue;
+        };
+
+        let Step::Command(command) = step else {
+            let Some(outcome) = last.as_mut() else {
+                bail!("Line {line_number}: assertion before any command");
+            };
+            check(&step, outcome)
+                .with_context(|| format!("Line {line_number}: `{}` does not hold", line.trim()))?;
+            continue;
+        };
+
+        if let Some(outcome) = last.take() {
+            ensure_handled(&outcome)?;
+        }
+        last = Some(execute(app_state, command, line_number)?);
+    }
+
+    match last {
+        Some(outcome) => ensure_handled(&outcome),
+        None => Ok(()),
+    }
+}
+
+fn execute(app_state: &mut AppState, command: &str, line: usize) -> Result<Outcome> {
+    let output = &app_state.ui_state.output;
+    let (lines_before, errors_before) = (output.content().len(), output.error_count());
+
+    execute_command(app_state, command)?;
+
+    let output = &app_state.ui_state.output;
+    // `clear` empties the output, in which case everything left in it is new.
+    let (lines_before, errors_before) = match output.content().len() < lines_before {
+        true => (0, 0),
+        false => (lines_before, errors_before),
+    };
+    let printed = output.content()[lines_before..]
+        .iter()
+        .map(|line| {
+            line.spans
+                .iter()
+                .map(|span| span.content.as_ref())
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    println!("{printed}");
+
+    Ok(Outcome {
+        line,
+        output: printed,
+        failed: output.error_count() > errors_before,
+        failure_expected: false,
+    })
+}
+
+fn check(step: &Step, outcome: &mut Outcome) -> Result<()> {
+    match step {
+        Step::Expect(_) if outcome.failed => bail!("the command failed"),
+        Step::ExpectError(_) if !outcome.failed => bail!("the command succeeded"),
+        Step::Expect(text) | Step::ExpectError(Some(text)) if !outcome.output.contains(text) => {
+            bail!("the output does not contain `{text}`")
+        }
+        Step::ExpectError(_) => outcome.failure_expected = true,
+        _ => {}
+    }
+    Ok(())
+}
+
+fn ensure_handled(outcome: &Outcome) -> Result<()> {
+    match outcome.failed && !outcome.failure_expected {
+        true => bail!(
+            "Line {}: command failed without `expect-error`\n{}",
+            outcome.line,
+            outcome.output
+        ),
+        false => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_script_lines() {
+        assert_eq!(parse_line("  # comment"), None);
+        assert_eq!(parse_line(""), None);
+        assert_eq!(
+            parse_line("call get 1 2"),
+            Some(Step::Command("call get 1 2"))
+        );
+        assert_eq!(
+            parse_line("expect Result: Ok(true)"),
+            Some(Step::Expect("Result: Ok(true)"))
+        );
+        assert_eq!(parse_line("expect-error"), Some(Step::ExpectError(None)));
+        assert_eq!(
+            parse_line("expect-error No deployed contract"),
+            Some(Step::ExpectError(Some("No deployed contract")))
+        );
+    }
+
+    #[test]
+    fn expectations_are_checked_against_outcome() {
+        let mut outcome = Outcome {
+            line: 1,
+            output: "Result: Ok(true)".to_string(),
+            failed: false,
+            failure_expected: false,
+        };
+        assert!(check(&Step::Expect("Ok(true)"), &mut outcome).is_ok());
+        assert!(check(&Step::Expect("Ok(false)"), &mut outcome).is_err());
+        assert!(check(&Step::ExpectError(None), &mut outcome).is_err());
+
+        outcome.failed = true;
+        assert!(ensure_handled(&outcome).is_err());
+        assert!(check(&Step::ExpectError(Some("Ok")), &mut outcome).is_ok());
+        assert!(ensure_handled(&outcome).is_ok());
+    }
+}
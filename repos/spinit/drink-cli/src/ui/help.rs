@@ -55,7 +55,31 @@ pub(super) fn build(_app_state: &AppState) -> impl Widget {
             "deploy / d [--constructor <name>] [--salt <salt>]",
             "deploy contract using <constructor> (`new` by default) and <salt> (empty by default)",
         ),
+        command(
+            "upload / u",
+            "upload contract code from the current directory without instantiating it",
+        ),
         command("call <message>", "call contract's message"),
+        command(
+            "dry-run / dr <message>",
+            "call contract's message without persisting its effects",
+        ),
+        command(
+            "events / e",
+            "list events recorded in the session, decoded with the current contract's metadata",
+        ),
+        command(
+            "storage / s",
+            "print the decoded storage of the current contract",
+        ),
+        command(
+            "save <path>",
+            "save the session (state and contracts) to <path>",
+        ),
+        command(
+            "load <path>",
+            "load a session saved with `save` from <path>",
+        ),
         command(
             "next-block / nb [count]",
             "build next <count> blocks (by default a single block)",
@@ -48,6 +48,9 @@ pub enum Error {
     /// Bundle loading and parsing has failed
     #[error("Loading the contract bundle has failed: {0}")]
     BundleLoadFailed(String),
+    /// State export loading and parsing has failed
+    #[error("Loading the state export has failed: {0}")]
+    StateLoadFailed(String),
 }
 
 /// Every contract message wraps its return value in `Result<T, LangResult>`. This is the error
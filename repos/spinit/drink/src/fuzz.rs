@@ -0,0 +1,872 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Property-based fuzzing of contract messages.
+//!
+//! A [`Fuzzer`] deploys the contract into a fresh [`Session`] for every run and then calls random
+//! messages, with arguments generated from the contract's type registry, on behalf of random
+//! callers and with random values for payable messages. All the registered invariants are checked
+//! after the deployment and after every call. A failing sequence is shrunk to a minimal one and
+//! reported together with a [`FuzzCase`], which replays it deterministically.
+//!
+//! Reverted and failed calls are regular outcomes; only violated invariants fail a run.
+//!
+//! # Example
+//!
+//! ```rust, ignore
+//! #[drink::fuzz(runs = 64, steps = 32)]
+//! fn supply_is_preserved(fuzzer: Fuzzer<MinimalSandbox>) -> Result<(), Box<dyn Error>> {
+//!     fuzzer
+//!         .with_bundle(BundleProvider::local()?)
+//!         .with_invariant("total supply", |session, address| {
+//!             let supply: u128 = session
+//!                 .call_with_address(address, "total_supply", NO_ARGS, NO_ENDOWMENT)
+//!                 .map_err(|err| err.to_string())?
+//!                 .map_err(|err| err.to_string())?;
+//!             (supply == 1_000).then_some(()).ok_or(format!("supply is {supply}"))
+//!         })
+//!         .run()?;
+//!     Ok(())
+//! }
+//! ```
+
+mod arg;
+
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use contract_transcode::ContractMessageTranscoder;
+use frame_support::sp_runtime::traits::Bounded;
+use ink_sandbox::{
+    api::prelude::*,
+    pallet_revive::{
+        evm::{H160, U256},
+        AddressMapper, MomentOf,
+    },
+    AccountIdFor, Sandbox, H256,
+};
+use parity_scale_codec::Encode;
+use rand::{Rng, SeedableRng};
+use rand_pcg::Pcg64Mcg;
+use thiserror::Error;
+
+use self::arg::{shrink_each, shrink_uint, Arg, ArgGenerator};
+use crate::{
+    pallet_balances,
+    pallet_revive::Config,
+    session::{error::SessionError, BalanceOf, ContractBundle, Session},
+};
+
+/// Environment variable overriding the seed of the first run.
+pub const SEED_ENV_VAR: &str = "DRINK_FUZZ_SEED";
+
+/// Fuzzing specific errors.
+#[derive(Clone, Error, Debug)]
+pub enum FuzzError {
+    /// The fuzzer has been misconfigured.
+    #[error("Invalid fuzzer setup: {0}")]
+    Setup(String),
+    /// Arguments of a constructor or message could not be generated.
+    #[error("Cannot generate arguments for `{0}`: {1}")]
+    Generation(String, String),
+    /// A fuzz case could not be parsed.
+    #[error("Invalid fuzz case: {0}")]
+    InvalidCase(String),
+    /// An invariant has been violated.
+    #[error("{0}")]
+    InvariantViolated(Box<FuzzFailure>),
+}
+
+/// Configuration of a [`Fuzzer`].
+#[derive(Clone, Debug)]
+pub struct FuzzConfig {
+    /// Number of runs, each of them starting with a freshly deployed contract.
+    pub runs: usize,
+    /// Number of messages called in every run.
+    pub steps: usize,
+    /// Seed of the first run, each next run using the following one. If `DRINK_FUZZ_SEED` is
+    /// set, it takes precedence. If neither is set, the seed is random.
+    pub seed: Option<u64>,
+    /// Maximum value transferred with payable constructors and messages.
+    pub max_value: u128,
+    /// Balance minted to every caller before the contract is deployed.
+    pub caller_endowment: u128,
+    /// Maximum number of sequences replayed while shrinking a failing one.
+    pub max_shrink_replays: usize,
+}
+
+impl Default for FuzzConfig {
+    fn default() -> Self {
+        Self {
+            runs: 32,
+            steps: 16,
+            seed: None,
+            max_value: 1_000_000_000_000,
+            caller_endowment: 1_000_000_000_000_000_000,
+            max_shrink_replays: 1_000,
+        }
+    }
+}
+
+/// A single constructor or message call of a [`FuzzCase`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzCall {
+    /// Index of the caller among the callers of the fuzzer.
+    pub caller: usize,
+    /// Value transferred with the call.
+    pub value: u128,
+    /// Label of the called constructor or message.
+    pub label: String,
+    selector: Vec<u8>,
+    args: Vec<Arg>,
+}
+
+impl FuzzCall {
+    /// Returns the call data, i.e. the selector followed by the SCALE encoded arguments.
+    pub fn data(&self) -> Vec<u8> {
+        let mut data = self.selector.clone();
+        self.args.iter().for_each(|arg| arg.encode_to(&mut data));
+        data
+    }
+
+    /// Returns simpler versions of the call.
+    fn shrink(&self) -> Vec<FuzzCall> {
+        let mut candidates = Vec::new();
+        if self.caller != 0 {
+            candidates.push(FuzzCall {
+                caller: 0,
+                ..self.clone()
+            });
+        }
+        for value in shrink_uint(self.value) {
+            candidates.push(FuzzCall {
+                value,
+                ..self.clone()
+            });
+        }
+        for args in shrink_each(&self.args) {
+            candidates.push(FuzzCall {
+                args,
+                ..self.clone()
+            });
+        }
+        candidates
+    }
+}
+
+impl fmt::Display for FuzzCall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} 0x{} value={} caller={}",
+            self.label,
+            hex::encode(self.data()),
+            self.value,
+            self.caller
+        )
+    }
+}
+
+impl FromStr for FuzzCall {
+    type Err = FuzzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            FuzzError::InvalidCase(format!(
+                "expected `<label> <data> value=<value> caller=<caller>`, got `{s}`"
+            ))
+        };
+        let [label, data, value, caller] = s.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(invalid());
+        };
+        let data = hex::decode(data.trim_start_matches("0x")).map_err(|_| invalid())?;
+        if data.len() < 4 {
+            return Err(invalid());
+        }
+        let value = value
+            .strip_prefix("value=")
+            .and_then(|value| value.parse().ok());
+        let caller = caller
+            .strip_prefix("caller=")
+            .and_then(|caller| caller.parse().ok());
+
+        Ok(FuzzCall {
+            caller: caller.ok_or_else(invalid)?,
+            value: value.ok_or_else(invalid)?,
+            label: label.to_string(),
+            selector: data[..4].to_vec(),
+            args: vec![Arg::Raw(data[4..].to_vec())],
+        })
+    }
+}
+
+/// A contract deployment followed by a sequence of message calls.
+///
+/// Its string form has a call per line and can be passed to [`Fuzzer::replay`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct FuzzCase {
+    /// The constructor call deploying the contract.
+    pub constructor: FuzzCall,
+    /// Messages called on the deployed contract.
+    pub steps: Vec<FuzzCall>,
+}
+
+impl FuzzCase {
+    fn calls(&self) -> impl Iterator<Item = &FuzzCall> {
+        std::iter::once(&self.constructor).chain(&self.steps)
+    }
+
+    /// Returns simpler versions of the case, the ones with fewer steps first.
+    fn shrink(&self) -> Vec<FuzzCase> {
+        let mut candidates: Vec<_> = (0..self.steps.len())
+            .map(|index| {
+                let mut steps = self.steps.clone();
+                steps.remove(index);
+                FuzzCase {
+                    steps,
+                    ..self.clone()
+                }
+            })
+            .collect();
+        // Changing the caller of a single call mostly makes it fail, so callers are swapped with
+        // the first one in all the calls at once.
+        let mut callers: Vec<_> = self.calls().map(|call| call.caller).collect();
+        callers.sort_unstable();
+        callers.dedup();
+        for caller in callers.into_iter().filter(|caller| *caller != 0) {
+            let swap = |call: &FuzzCall| FuzzCall {
+                caller: match call.caller {
+                    0 => caller,
+                    other if other == caller => 0,
+                    other => other,
+                },
+                ..call.clone()
+            };
+            candidates.push(FuzzCase {
+                constructor: swap(&self.constructor),
+                steps: self.steps.iter().map(swap).collect(),
+            });
+        }
+        for constructor in self.constructor.shrink() {
+            candidates.push(FuzzCase {
+                constructor,
+                ..self.clone()
+            });
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            for call in step.shrink() {
+                let mut steps = self.steps.clone();
+                steps[index] = call;
+                candidates.push(FuzzCase {
+                    steps,
+                    ..self.clone()
+                });
+            }
+        }
+        candidates
+    }
+}
+
+impl fmt::Display for FuzzCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.constructor)?;
+        self.steps.iter().try_for_each(|step| write!(f, "\n{step}"))
+    }
+}
+
+impl FromStr for FuzzCase {
+    type Err = FuzzError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut calls = s
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(FuzzCall::from_str);
+        let constructor = calls
+            .next()
+            .ok_or_else(|| FuzzError::InvalidCase("no constructor call".to_string()))??;
+
+        Ok(FuzzCase {
+            constructor,
+            steps: calls.collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// A violated invariant, together with the shrunk case violating it.
+#[derive(Clone)]
+pub struct FuzzFailure {
+    /// Name of the violated invariant.
+    pub invariant: String,
+    /// Error returned by the invariant.
+    pub message: String,
+    /// Seed of the run which found the failure, `None` for replayed cases.
+    pub seed: Option<u64>,
+    /// Number of steps of the case before it was shrunk.
+    pub original_steps: usize,
+    /// The shrunk case.
+    pub case: FuzzCase,
+    /// Human readable form of the calls of `case`.
+    described_calls: Vec<String>,
+}
+
+impl fmt::Display for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Invariant `{}` violated: {}",
+            self.invariant, self.message
+        )?;
+        match self.seed {
+            Some(seed) => writeln!(
+                f,
+                "Found with seed {seed} and shrunk from {} to {} steps:",
+                self.original_steps,
+                self.case.steps.len()
+            )?,
+            None => writeln!(f, "Replayed case:")?,
+        }
+        for (index, call) in self.described_calls.iter().enumerate() {
+            writeln!(f, "  {index:>3}. {call}")?;
+        }
+        write!(
+            f,
+            "Replay it with:\n\nfuzzer.replay(r#\"\n{}\n\"#)",
+            self.case
+        )
+    }
+}
+
+// Test harnesses print errors with `Debug`, so it shows the whole report as well.
+impl fmt::Debug for FuzzFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self}")
+    }
+}
+
+/// A user declared property of the contract, checked after every step.
+type Invariant<T> = Box<dyn Fn(&mut Session<T>, H160) -> Result<(), String>>;
+
+/// A constructor or message which can be called by the fuzzer.
+struct Entry {
+    label: String,
+    selector: Vec<u8>,
+    payable: bool,
+    mutates: bool,
+    /// Type ids of the arguments.
+    args: Vec<u32>,
+}
+
+/// The first invariant violated while executing a case.
+struct Violation {
+    /// Number of steps executed before the violation.
+    steps: usize,
+    invariant: String,
+    message: String,
+}
+
+/// Outcome of executing a case.
+enum Outcome {
+    Passed,
+    NotDeployed(SessionError),
+    Violated(Violation),
+}
+
+/// Metadata driven fuzzer of a contract, usually created by `#[drink::fuzz]`.
+///
+/// By default, the fuzzer deploys the contract with any of its constructors and calls any of its
+/// mutating messages, on behalf of the default actor and two more accounts.
+pub struct Fuzzer<T: Sandbox>
+where
+    T::Runtime: Config,
+{
+    config: FuzzConfig,
+    bundle: Option<ContractBundle>,
+    constructors: Vec<String>,
+    messages: Vec<String>,
+    excluded_messages: Vec<String>,
+    callers: Vec<AccountIdFor<T::Runtime>>,
+    invariants: Vec<(String, Invariant<T>)>,
+}
+
+impl<T: Sandbox> Fuzzer<T>
+where
+    T::Runtime: Config + pallet_balances::Config,
+    T: Default,
+    BalanceOf<T::Runtime>: Into<U256> + TryFrom<U256> + Bounded,
+    MomentOf<T::Runtime>: Into<U256>,
+    <<T as Sandbox>::Runtime as frame_system::Config>::Hash: frame_support::traits::IsType<H256>,
+    <T::Runtime as pallet_balances::Config>::Balance: TryFrom<u128>,
+    AccountIdFor<T::Runtime>: From<[u8; 32]>,
+{
+    /// Creates a new fuzzer with the given configuration.
+    pub fn new(config: FuzzConfig) -> Self {
+        let mut callers = vec![T::default_actor()];
+        for account in [[2u8; 32], [3u8; 32]].map(AccountIdFor::<T::Runtime>::from) {
+            if !callers.contains(&account) {
+                callers.push(account);
+            }
+        }
+
+        Self {
+            config,
+            bundle: None,
+            constructors: Vec::new(),
+            messages: Vec::new(),
+            excluded_messages: Vec::new(),
+            callers,
+            invariants: Vec::new(),
+        }
+    }
+
+    /// Sets the contract to be fuzzed and returns updated `self`.
+    pub fn with_bundle(self, bundle: ContractBundle) -> Self {
+        Self {
+            bundle: Some(bundle),
+            ..self
+        }
+    }
+
+    /// Restricts the constructors used to deploy the contract and returns updated `self`.
+    pub fn with_constructors(self, constructors: &[&str]) -> Self {
+        Self {
+            constructors: constructors.iter().map(|label| label.to_string()).collect(),
+            ..self
+        }
+    }
+
+    /// Restricts the called messages and returns updated `self`. Unlike by default, immutable
+    /// messages are called if listed.
+    pub fn with_messages(self, messages: &[&str]) -> Self {
+        Self {
+            messages: messages.iter().map(|label| label.to_string()).collect(),
+            ..self
+        }
+    }
+
+    /// Excludes messages from being called and returns updated `self`.
+    pub fn without_messages(self, messages: &[&str]) -> Self {
+        Self {
+            excluded_messages: messages.iter().map(|label| label.to_string()).collect(),
+            ..self
+        }
+    }
+
+    /// Sets the accounts calling the contract and returns updated `self`. The first one deploys
+    /// the contract in shrunk cases.
+    pub fn with_callers(self, callers: Vec<AccountIdFor<T::Runtime>>) -> Self {
+        Self { callers, ..self }
+    }
+
+    /// Declares an invariant and returns updated `self`.
+    ///
+    /// The invariant receives the session and the address of the fuzzed contract. It is checked
+    /// after the deployment and after every call, and returns an error describing the violation.
+    pub fn with_invariant(
+        mut self,
+        name: &str,
+        invariant: impl Fn(&mut Session<T>, H160) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.invariants
+            .push((name.to_string(), Box::new(invariant)));
+        self
+    }
+
+    /// Runs the fuzzer. Fails with [`FuzzError::InvariantViolated`] if any invariant has been
+    /// violated, reporting the shrunk case.
+    pub fn run(&self) -> Result<(), FuzzError> {
+        let bundle = self.bundle()?;
+        let (constructors, messages) = self.entries(&bundle.transcoder)?;
+        let seed = match std::env::var(SEED_ENV_VAR) {
+            Ok(seed) => seed
+                .parse()
+                .map_err(|_| FuzzError::Setup(format!("{SEED_ENV_VAR} is not a number")))?,
+            Err(_) => self.config.seed.unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|time| time.as_nanos() as u64)
+                    .unwrap_or_default()
+            }),
+        };
+
+        let mut deployment_error = None;
+        let mut deployed = false;
+        for run in 0..self.config.runs as u64 {
+            let seed = seed.wrapping_add(run);
+            let mut rng = Pcg64Mcg::seed_from_u64(seed);
+
+            let mut session = self.session()?;
+            let generator = self.generator(&mut session, &bundle.transcoder, None);
+            let constructor = self.generate_call(&mut rng, &generator, &constructors)?;
+            let address = match self.deploy(&mut session, bundle, &constructor)? {
+                Ok(address) => address,
+                Err(err) => {
+                    deployment_error = Some(err);
+                    continue;
+                }
+            };
+            deployed = true;
+            let generator = self.generator(&mut session, &bundle.transcoder, Some(address));
+
+            let mut case = FuzzCase {
+                constructor,
+                steps: Vec::new(),
+            };
+            let mut violation = self.check(&mut session, address, 0);
+            while violation.is_none() && case.steps.len() < self.config.steps {
+                let call = self.generate_call(&mut rng, &generator, &messages)?;
+                self.call(&mut session, address, &call)?;
+                case.steps.push(call);
+                violation = self.check(&mut session, address, case.steps.len());
+            }
+
+            if let Some(violation) = violation {
+                let original_steps = case.steps.len();
+                let (case, violation) = self.shrink(bundle, case, violation)?;
+                return Err(self.failure(bundle, case, violation, Some(seed), original_steps));
+            }
+        }
+
+        match deployment_error {
+            Some(err) if !deployed => Err(FuzzError::Setup(format!(
+                "the contract could not be deployed in any run: {err}"
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replays a case, as reported by a failing run. Fails with [`FuzzError::InvariantViolated`]
+    /// if any invariant is still violated.
+    pub fn replay(&self, case: &str) -> Result<(), FuzzError> {
+        let bundle = self.bundle()?;
+        let case: FuzzCase = case.parse()?;
+        let steps = case.steps.len();
+        match self.execute(bundle, &case)? {
+            Outcome::Passed => Ok(()),
+            Outcome::NotDeployed(err) => Err(FuzzError::Setup(format!(
+                "the contract could not be deployed: {err}"
+            ))),
+            Outcome::Violated(violation) => Err(self.failure(bundle, case, violation, None, steps)),
+        }
+    }
+
+    fn bundle(&self) -> Result<&ContractBundle, FuzzError> {
+        self.bundle
+            .as_ref()
+            .ok_or_else(|| FuzzError::Setup("no contract bundle to fuzz".to_string()))
+    }
+
+    /// Returns the constructors and messages to be called.
+    fn entries(
+        &self,
+        transcoder: &ContractMessageTranscoder,
+    ) -> Result<(Vec<Entry>, Vec<Entry>), FuzzError> {
+        let spec = transcoder.metadata().spec();
+        let constructors = spec.constructors().iter().map(|constructor| Entry {
+            label: constructor.label().clone(),
+            selector: constructor.selector().to_bytes().to_vec(),
+            payable: *constructor.payable(),
+            mutates: true,
+            args: constructor
+                .args()
+                .iter()
+                .map(|arg| arg.ty().ty().id)
+                .collect(),
+        });
+        let messages = spec.messages().iter().map(|message| Entry {
+            label: message.label().clone(),
+            selector: message.selector().to_bytes().to_vec(),
+            payable: message.payable(),
+            mutates: message.mutates(),
+            args: message.args().iter().map(|arg| arg.ty().ty().id).collect(),
+        });
+
+        let constructors = select(constructors.collect(), &self.constructors, &[])?;
+        let messages = select(messages.collect(), &self.messages, &self.excluded_messages)?;
+        if constructors.is_empty() || messages.is_empty() {
+            return Err(FuzzError::Setup(
+                "no constructor or message left to call".to_string(),
+            ));
+        }
+        Ok((constructors, messages))
+    }
+
+    /// Creates a fresh session with all the callers endowed.
+    fn session(&self) -> Result<Session<T>, FuzzError> {
+        if self.callers.is_empty() {
+            return Err(FuzzError::Setup("no callers".to_string()));
+        }
+        let endowment: <T::Runtime as pallet_balances::Config>::Balance =
+            self.config.caller_endowment.try_into().map_err(|_| {
+                FuzzError::Setup("caller endowment does not fit the balance type".to_string())
+            })?;
+
+        let mut session = Session::<T>::default();
+        for caller in &self.callers {
+            session
+                .sandbox()
+                .mint_into(caller, endowment)
+                .map_err(|err| FuzzError::Setup(format!("failed to endow a caller: {err:?}")))?;
+        }
+        Ok(session)
+    }
+
+    /// Creates a generator using the callers, and the contract if deployed already, for account
+    /// ids and addresses.
+    fn generator<'a>(
+        &self,
+        session: &mut Session<T>,
+        transcoder: &'a ContractMessageTranscoder,
+        contract: Option<H160>,
+    ) -> ArgGenerator<'a> {
+        let accounts = self.callers.iter().map(Encode::encode).collect();
+        let mut addresses: Vec<_> = contract.into_iter().map(|address| address.0).collect();
+        for caller in &self.callers {
+            let address = session
+                .sandbox()
+                .execute_with(|| <T::Runtime as Config>::AddressMapper::to_address(caller));
+            addresses.push(address.0);
+        }
+        ArgGenerator::new(transcoder.metadata().registry(), accounts, addresses)
+    }
+
+    fn generate_call(
+        &self,
+        rng: &mut Pcg64Mcg,
+        generator: &ArgGenerator,
+        entries: &[Entry],
+    ) -> Result<FuzzCall, FuzzError> {
+        let entry = &entries[rng.gen_range(0..entries.len())];
+        let args = entry
+            .args
+            .iter()
+            .map(|type_id| generator.generate(rng, *type_id))
+            .collect::<Result<_, _>>()
+            .map_err(|err| FuzzError::Generation(entry.label.clone(), err))?;
+
+        Ok(FuzzCall {
+            caller: rng.gen_range(0..self.callers.len()),
+            value: if entry.payable {
+                rng.gen_range(0..=self.config.max_value)
+            } else {
+                0
+            },
+            label: entry.label.clone(),
+            selector: entry.selector.clone(),
+            args,
+        })
+    }
+
+    fn set_caller(&self, session: &mut Session<T>, call: &FuzzCall) -> Result<(), FuzzError> {
+        let caller = self
+            .callers
+            .get(call.caller)
+            .ok_or_else(|| FuzzError::InvalidCase(format!("there is no caller {}", call.caller)))?;
+        session.set_actor(caller.clone());
+        Ok(())
+    }
+
+    fn deploy(
+        &self,
+        session: &mut Session<T>,
+        bundle: &ContractBundle,
+        call: &FuzzCall,
+    ) -> Result<Result<H160, SessionError>, FuzzError> {
+        self.set_caller(session, call)?;
+        Ok(session.deploy_with_data(
+            bundle.binary.clone(),
+            call.data(),
+            None,
+            Some(balance::<T>(call.value)?),
+            &bundle.transcoder,
+        ))
+    }
+
+    fn call(
+        &self,
+        session: &mut Session<T>,
+        address: H160,
+        call: &FuzzCall,
+    ) -> Result<(), FuzzError> {
+        self.set_caller(session, call)?;
+        // Reverted and failed calls are regular outcomes, which the invariants may inspect
+        // through the session record.
+        let _ = session.call_with_data::<()>(address, call.data(), Some(balance::<T>(call.value)?));
+        Ok(())
+    }
+
+    fn check(&self, session: &mut Session<T>, address: H160, steps: usize) -> Option<Violation> {
+        self.invariants.iter().find_map(|(name, invariant)| {
+            invariant(session, address).err().map(|message| Violation {
+                steps,
+                invariant: name.clone(),
+                message,
+            })
+        })
+    }
+
+    /// Executes a case in a fresh session, stopping at the first violation.
+    fn execute(&self, bundle: &ContractBundle, case: &FuzzCase) -> Result<Outcome, FuzzError> {
+        let mut session = self.session()?;
+        let address = match self.deploy(&mut session, bundle, &case.constructor)? {
+            Ok(address) => address,
+            Err(err) => return Ok(Outcome::NotDeployed(err)),
+        };
+        if let Some(violation) = self.check(&mut session, address, 0) {
+            return Ok(Outcome::Violated(violation));
+        }
+        for (index, step) in case.steps.iter().enumerate() {
+            self.call(&mut session, address, step)?;
+            if let Some(violation) = self.check(&mut session, address, index + 1) {
+                return Ok(Outcome::Violated(violation));
+            }
+        }
+        Ok(Outcome::Passed)
+    }
+
+    /// Greedily replaces the case with the first simpler one violating the same invariant, until
+    /// there is none or the replay budget is spent.
+    fn shrink(
+        &self,
+        bundle: &ContractBundle,
+        mut case: FuzzCase,
+        mut violation: Violation,
+    ) -> Result<(FuzzCase, Violation), FuzzError> {
+        let mut replays = 0;
+        'shrinking: while replays < self.config.max_shrink_replays {
+            for candidate in case.shrink() {
+                if replays == self.config.max_shrink_replays {
+                    break 'shrinking;
+                }
+                replays += 1;
+                match self.execute(bundle, &candidate)? {
+                    Outcome::Violated(shrunk) if shrunk.invariant == violation.invariant => {
+                        case = candidate;
+                        case.steps.truncate(shrunk.steps);
+                        violation = shrunk;
+                        continue 'shrinking;
+                    }
+                    _ => {}
+                }
+            }
+            break;
+        }
+        Ok((case, violation))
+    }
+
+    fn failure(
+        &self,
+        bundle: &ContractBundle,
+        mut case: FuzzCase,
+        violation: Violation,
+        seed: Option<u64>,
+        original_steps: usize,
+    ) -> FuzzError {
+        case.steps.truncate(violation.steps);
+        let transcoder = &bundle.transcoder;
+        let constructor = transcoder
+            .decode_contract_constructor(&mut &case.constructor.data()[..])
+            .map(|value| value.to_string());
+        let mut described_calls = vec![describe(&case.constructor, constructor)];
+        for step in &case.steps {
+            let message = transcoder
+                .decode_contract_message(&mut &step.data()[..])
+                .map(|value| value.to_string());
+            described_calls.push(describe(step, message));
+        }
+
+        FuzzError::InvariantViolated(Box::new(FuzzFailure {
+            invariant: violation.invariant,
+            message: violation.message,
+            seed,
+            original_steps,
+            case,
+            described_calls,
+        }))
+    }
+}
+
+/// Keeps the entries listed in `included` (all mutating ones if empty), except for the ones in
+/// `excluded`.
+fn select(
+    entries: Vec<Entry>,
+    included: &[String],
+    excluded: &[String],
+) -> Result<Vec<Entry>, FuzzError> {
+    if let Some(label) = included
+        .iter()
+        .chain(excluded)
+        .find(|label| !entries.iter().any(|entry| entry.label == **label))
+    {
+        return Err(FuzzError::Setup(format!(
+            "no constructor or message `{label}`"
+        )));
+    }
+    Ok(entries
+        .into_iter()
+        .filter(|entry| {
+            if included.is_empty() {
+                entry.mutates
+            } else {
+                included.contains(&entry.label)
+            }
+        })
+        .filter(|entry| !excluded.contains(&entry.label))
+        .collect())
+}
+
+fn balance<T: Sandbox>(value: u128) -> Result<BalanceOf<T::Runtime>, FuzzError>
+where
+    T::Runtime: Config,
+    BalanceOf<T::Runtime>: TryFrom<U256>,
+{
+    BalanceOf::<T::Runtime>::try_from(U256::from(value))
+        .map_err(|_| FuzzError::Setup(format!("{value} does not fit the balance type")))
+}
+
+/// Describes a call by its decoded data, falling back to its label.
+fn describe<E>(call: &FuzzCall, decoded: Result<String, E>) -> String {
+    let mut description = decoded.unwrap_or_else(|_| call.label.clone());
+    description.push_str(&format!(" by caller {}", call.caller));
+    if call.value > 0 {
+        description.push_str(&format!(" with value {}", call.value));
+    }
+    description
+}
@@ -0,0 +1,393 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+//! Generation and shrinking of arbitrary SCALE encoded values, driven by the contract's type
+//! registry.
+
+use parity_scale_codec::{Compact, Encode};
+use rand::Rng;
+use scale_info::{form::PortableForm, PortableRegistry, Type, TypeDef, TypeDefPrimitive};
+
+/// Nesting depth past which only the smallest values are generated, so that recursive types
+/// terminate.
+const MAX_DEPTH: usize = 8;
+/// Maximum length of generated sequences and strings.
+const MAX_LEN: usize = 8;
+
+/// A generated value. It is kept structured (rather than encoded right away), so that it can be
+/// shrunk.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Arg {
+    /// An unsigned integer, encoded with `width` bytes.
+    Uint {
+        value: u128,
+        width: usize,
+    },
+    /// A signed integer, encoded with `width` bytes.
+    Int {
+        value: i128,
+        width: usize,
+    },
+    Bool(bool),
+    Char(char),
+    Str(String),
+    /// Bytes which are encoded as they are and never shrunk, like account ids.
+    Raw(Vec<u8>),
+    /// A sequence, encoded with its compact length prefix.
+    Seq(Vec<Arg>),
+    /// Fields of a composite, tuple or array.
+    Fields(Vec<Arg>),
+    Variant {
+        index: u8,
+        fields: Vec<Arg>,
+    },
+    Compact(u128),
+}
+
+impl Arg {
+    /// Appends the SCALE encoding of the value to `out`.
+    pub(crate) fn encode_to(&self, out: &mut Vec<u8>) {
+        match self {
+            Arg::Uint { value, width } => encode_int(value.to_le_bytes(), 0, *width, out),
+            Arg::Int { value, width } => {
+                let extension = if *value < 0 { u8::MAX } else { 0 };
+                encode_int(value.to_le_bytes(), extension, *width, out)
+            }
+            Arg::Bool(value) => value.encode_to(out),
+            Arg::Char(value) => (*value as u32).encode_to(out),
+            Arg::Str(value) => value.encode_to(out),
+            Arg::Raw(bytes) => out.extend_from_slice(bytes),
+            Arg::Seq(items) => {
+                Compact(items.len() as u32).encode_to(out);
+                items.iter().for_each(|item| item.encode_to(out));
+            }
+            Arg::Fields(fields) => fields.iter().for_each(|field| field.encode_to(out)),
+            Arg::Variant { index, fields } => {
+                out.push(*index);
+                fields.iter().for_each(|field| field.encode_to(out));
+            }
+            Arg::Compact(value) => Compact(*value).encode_to(out),
+        }
+    }
+
+    /// Returns simpler versions of the value, the simplest first.
+    pub(crate) fn shrink(&self) -> Vec<Arg> {
+        match self {
+            Arg::Uint { value, width } => shrink_uint(*value)
+                .into_iter()
+                .map(|value| Arg::Uint {
+                    value,
+                    width: *width,
+                })
+                .collect(),
+            Arg::Int { value, width } => shrink_int(*value)
+                .into_iter()
+                .map(|value| Arg::Int {
+                    value,
+                    width: *width,
+                })
+                .collect(),
+            Arg::Bool(true) => vec![Arg::Bool(false)],
+            Arg::Char(value) if *value != 'a' => vec![Arg::Char('a')],
+            Arg::Str(value) if !value.is_empty() => {
+                let half = value.chars().take(value.chars().count() / 2).collect();
+                let mut candidates = vec![Arg::Str(String::new()), Arg::Str(half)];
+                candidates.dedup();
+                candidates
+            }
+            Arg::Seq(items) if !items.is_empty() => {
+                let mut candidates = vec![Arg::Seq(Vec::new())];
+                if items.len() > 1 {
+                    candidates.extend((0..items.len()).map(|index| {
+                        let mut fewer = items.clone();
+                        fewer.remove(index);
+                        Arg::Seq(fewer)
+                    }));
+                }
+                candidates.extend(shrink_each(items).into_iter().map(Arg::Seq));
+                candidates
+            }
+            Arg::Fields(fields) => shrink_each(fields).into_iter().map(Arg::Fields).collect(),
+            Arg::Variant { index, fields } => shrink_each(fields)
+                .into_iter()
+                .map(|fields| Arg::Variant {
+                    index: *index,
+                    fields,
+                })
+                .collect(),
+            Arg::Compact(value) => shrink_uint(*value).into_iter().map(Arg::Compact).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Writes the `width` lowest bytes of a little endian integer, extending it past 128 bits with
+/// `extension`.
+fn encode_int(bytes: [u8; 16], extension: u8, width: usize, out: &mut Vec<u8>) {
+    out.extend(
+        bytes
+            .into_iter()
+            .chain(std::iter::repeat(extension))
+            .take(width),
+    );
+}
+
+/// Returns smaller unsigned integers, zero first.
+pub(crate) fn shrink_uint(value: u128) -> Vec<u128> {
+    let mut candidates = vec![0, value / 2, value.saturating_sub(1)];
+    candidates.retain(|candidate| *candidate != value);
+    candidates.dedup();
+    candidates
+}
+
+/// Returns signed integers closer to zero, zero first.
+fn shrink_int(value: i128) -> Vec<i128> {
+    let mut candidates = vec![0, value / 2, value - value.signum()];
+    candidates.retain(|candidate| *candidate != value);
+    candidates.dedup();
+    candidates
+}
+
+/// Returns the copies of `items` with a single item shrunk.
+pub(crate) fn shrink_each(items: &[Arg]) -> Vec<Vec<Arg>> {
+    items
+        .iter()
+        .enumerate()
+        .flat_map(|(index, item)| {
+            item.shrink().into_iter().map(move |candidate| {
+                let mut items = items.to_vec();
+                items[index] = candidate;
+                items
+            })
+        })
+        .collect()
+}
+
+/// Generates arbitrary values of the types in a contract's type registry.
+pub(crate) struct ArgGenerator<'a> {
+    registry: &'a PortableRegistry,
+    /// Encoded account ids to use for `AccountId` values.
+    accounts: Vec<Vec<u8>>,
+    /// Addresses to use for `H160` values.
+    addresses: Vec<[u8; 20]>,
+}
+
+impl<'a> ArgGenerator<'a> {
+    pub(crate) fn new(
+        registry: &'a PortableRegistry,
+        accounts: Vec<Vec<u8>>,
+        addresses: Vec<[u8; 20]>,
+    ) -> Self {
+        Self {
+            registry,
+            accounts,
+            addresses,
+        }
+    }
+
+    /// Generates a value of the type with the given id.
+    pub(crate) fn generate<R: Rng>(&self, rng: &mut R, type_id: u32) -> Result<Arg, String> {
+        self.generate_nested(rng, type_id, 0)
+    }
+
+    fn generate_nested<R: Rng>(
+        &self,
+        rng: &mut R,
+        type_id: u32,
+        depth: usize,
+    ) -> Result<Arg, String> {
+        let ty = self
+            .registry
+            .resolve(type_id)
+            .ok_or_else(|| format!("type {type_id} is missing from the registry"))?;
+        if let Some(arg) = self.known_value(rng, ty) {
+            return Ok(arg);
+        }
+
+        let depth = depth + 1;
+        let fields = |rng: &mut R, ids: &mut dyn Iterator<Item = u32>| {
+            ids.map(|id| self.generate_nested(rng, id, depth))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        match &ty.type_def {
+            TypeDef::Composite(composite) => {
+                fields(rng, &mut composite.fields.iter().map(|field| field.ty.id)).map(Arg::Fields)
+            }
+            TypeDef::Tuple(tuple) => {
+                fields(rng, &mut tuple.fields.iter().map(|field| field.id)).map(Arg::Fields)
+            }
+            TypeDef::Array(array) => {
+                let id = array.type_param.id;
+                fields(rng, &mut (0..array.len).map(|_| id)).map(Arg::Fields)
+            }
+            TypeDef::Sequence(sequence) => {
+                let len = if depth > MAX_DEPTH {
+                    0
+                } else {
+                    rng.gen_range(0..=MAX_LEN)
+                };
+                let id = sequence.type_param.id;
+                fields(rng, &mut (0..len).map(|_| id)).map(Arg::Seq)
+            }
+            TypeDef::Variant(def) => {
+                let variant = if depth > MAX_DEPTH {
+                    def.variants
+                        .iter()
+                        .min_by_key(|variant| variant.fields.len())
+                } else if def.variants.is_empty() {
+                    None
+                } else {
+                    def.variants.get(rng.gen_range(0..def.variants.len()))
+                }
+                .ok_or_else(|| format!("type {type_id} has no variants"))?;
+                let fields = fields(rng, &mut variant.fields.iter().map(|field| field.ty.id))?;
+                Ok(Arg::Variant {
+                    index: variant.index,
+                    fields,
+                })
+            }
+            TypeDef::Compact(compact) => {
+                let width = match self.registry.resolve(compact.type_param.id) {
+                    Some(Type {
+                        type_def: TypeDef::Primitive(primitive),
+                        ..
+                    }) => uint_width(primitive),
+                    _ => None,
+                }
+                .ok_or_else(|| format!("compact type {type_id} is not an unsigned integer"))?;
+                Ok(Arg::Compact(gen_uint(rng, width)))
+            }
+            TypeDef::Primitive(primitive) => Ok(gen_primitive(rng, primitive)),
+            TypeDef::BitSequence(_) => Err(format!("bit sequence type {type_id} is not supported")),
+        }
+    }
+
+    /// Random account ids and addresses rarely point to anything, so they are mostly picked from
+    /// the known ones.
+    fn known_value<R: Rng>(&self, rng: &mut R, ty: &Type<PortableForm>) -> Option<Arg> {
+        let known: Vec<&[u8]> = match ty.path.ident()?.as_str() {
+            "AccountId" | "AccountId32" => self.accounts.iter().map(Vec::as_slice).collect(),
+            "H160" | "Address" => self.addresses.iter().map(|address| &address[..]).collect(),
+            _ => return None,
+        };
+        if known.is_empty() || rng.gen_ratio(1, 8) {
+            return None;
+        }
+        Some(Arg::Raw(known[rng.gen_range(0..known.len())].to_vec()))
+    }
+}
+
+fn uint_width(primitive: &TypeDefPrimitive) -> Option<usize> {
+    match primitive {
+        TypeDefPrimitive::U8 => Some(1),
+        TypeDefPrimitive::U16 => Some(2),
+        TypeDefPrimitive::U32 => Some(4),
+        TypeDefPrimitive::U64 => Some(8),
+        TypeDefPrimitive::U128 => Some(16),
+        TypeDefPrimitive::U256 => Some(32),
+        _ => None,
+    }
+}
+
+fn int_width(primitive: &TypeDefPrimitive) -> Option<usize> {
+    match primitive {
+        TypeDefPrimitive::I8 => Some(1),
+        TypeDefPrimitive::I16 => Some(2),
+        TypeDefPrimitive::I32 => Some(4),
+        TypeDefPrimitive::I64 => Some(8),
+        TypeDefPrimitive::I128 => Some(16),
+        TypeDefPrimitive::I256 => Some(32),
+        _ => None,
+    }
+}
+
+fn gen_primitive<R: Rng>(rng: &mut R, primitive: &TypeDefPrimitive) -> Arg {
+    if let Some(width) = uint_width(primitive) {
+        return Arg::Uint {
+            value: gen_uint(rng, width),
+            width,
+        };
+    }
+    if let Some(width) = int_width(primitive) {
+        return Arg::Int {
+            value: gen_int(rng, width),
+            width,
+        };
+    }
+    match primitive {
+        TypeDefPrimitive::Bool => Arg::Bool(rng.gen()),
+        TypeDefPrimitive::Char => Arg::Char(rng.gen_range('a'..='z')),
+        _ => {
+            let len = rng.gen_range(0..=MAX_LEN);
+            Arg::Str((0..len).map(|_| rng.gen_range('a'..='z')).collect())
+        }
+    }
+}
+
+/// Generates an unsigned integer of the given width, favouring the edge cases. 256 bit integers
+/// are limited to the 128 bit range.
+fn gen_uint<R: Rng>(rng: &mut R, width: usize) -> u128 {
+    let max = match width {
+        16.. => u128::MAX,
+        _ => (1 << (8 * width)) - 1,
+    };
+    match rng.gen_range(0..8) {
+        0 => 0,
+        1 => 1,
+        2 => max,
+        3 => max - 1,
+        4 | 5 => rng.gen_range(0..=max.min(1_000)),
+        _ => rng.gen_range(0..=max),
+    }
+}
+
+/// Generates a signed integer of the given width, favouring the edge cases. 256 bit integers are
+/// limited to the 128 bit range.
+fn gen_int<R: Rng>(rng: &mut R, width: usize) -> i128 {
+    let max = match width {
+        16.. => i128::MAX,
+        _ => (1 << (8 * width - 1)) - 1,
+    };
+    let min = -max - 1;
+    match rng.gen_range(0..8) {
+        0 => 0,
+        1 => -1,
+        2 => max,
+        3 => min,
+        4 | 5 => rng.gen_range(min.max(-1_000)..=max.min(1_000)),
+        _ => rng.gen_range(min..=max),
+    }
+}
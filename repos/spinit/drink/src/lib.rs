@@ -41,16 +41,20 @@
 #![warn(missing_docs)]
 
 pub mod errors;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
 #[cfg(feature = "session")]
 pub mod session;
 
 #[cfg(feature = "macros")]
 pub use drink_test_macro::{contract_bundle_provider, test};
+#[cfg(all(feature = "macros", feature = "fuzz"))]
+pub use drink_test_macro::fuzz;
 pub use errors::Error;
 pub use frame_support;
 pub use ink_sandbox::{
-    api as sandbox_api, create_sandbox, pallet_balances, pallet_revive, pallet_timestamp,
-    sp_externalities, AccountId32, DispatchError, Sandbox, Ss58Codec, Weight,
+    api as sandbox_api, create_sandbox, pallet_balances, pallet_contracts, pallet_revive,
+    pallet_timestamp, sp_externalities, AccountId32, DispatchError, Sandbox, Ss58Codec, Weight,
 };
 #[cfg(feature = "session")]
 pub use session::mock::{mock_message, ContractMock, MessageMock, MockedCallResult, Selector};
@@ -46,26 +46,42 @@ use std::{
 pub use contract_transcode;
 use contract_transcode::ContractMessageTranscoder;
 use error::SessionError;
-use frame_support::{sp_runtime::traits::Bounded, traits::fungible::Inspect, weights::Weight};
+use frame_support::{
+    sp_runtime::traits::{Bounded, UniqueSaturatedInto},
+    traits::fungible::Inspect,
+    weights::Weight,
+};
 use ink_primitives::DepositLimit;
 use ink_sandbox::{
     api::prelude::*,
     pallet_revive::{
         evm::{H160, U256},
-        MomentOf,
+        AddressMapper, MomentOf, StorageDeposit,
     },
     AccountIdFor, ContractExecResultFor, ContractResultInstantiate, Sandbox, H256,
 };
 use parity_scale_codec::Decode;
+pub use profile::Profile;
 pub use record::{EventBatch, Record};
+pub use state::{ContractsPallet, StateDiff, StateExport};
 
-use crate::{minimal::MinimalSandboxRuntime, pallet_revive::Config, session::mock::MockRegistry};
+use crate::{
+    minimal::MinimalSandboxRuntime, pallet_contracts, pallet_revive::Config,
+    session::mock::MockRegistry,
+};
 
 pub mod bundle;
+#[cfg(feature = "coverage")]
+pub mod coverage;
+#[cfg(feature = "deploy")]
+mod deploy;
 pub mod error;
 pub mod mock;
 pub mod mocking_api;
+pub mod profile;
 mod record;
+pub mod report;
+pub mod state;
 mod transcoding;
 
 pub use bundle::ContractBundle;
@@ -74,10 +90,14 @@ use self::mocking_api::MockingApi;
 use crate::{
     errors::MessageResult,
     // minimal::MinimalSandboxRuntime,
-    session::transcoding::TranscoderRegistry,
+    session::{
+        profile::{EntryKind, Sample},
+        state::{ContractsPallet, Storage},
+        transcoding::TranscoderRegistry,
+    },
 };
 
-type BalanceOf<R> = <<R as Config>::Currency as Inspect<AccountIdFor<R>>>::Balance;
+pub(crate) type BalanceOf<R> = <<R as Config>::Currency as Inspect<AccountIdFor<R>>>::Balance;
 type RuntimeOrigin<R> = <R as frame_system::Config>::RuntimeOrigin;
 
 /// Convenient value for an empty sequence of call/instantiation arguments.
@@ -183,6 +203,7 @@ where
 
     transcoders: TranscoderRegistry,
     record: Record<T::Runtime>,
+    profile: Profile,
     mocks: Arc<Mutex<MockRegistry>>,
 }
 
@@ -211,6 +232,7 @@ where
             storage_deposit_limit: BalanceOf::<T::Runtime>::max_value(),
             transcoders: TranscoderRegistry::new(),
             record: Default::default(),
+            profile: Default::default(),
         }
     }
 }
@@ -234,13 +256,17 @@ where
 
     /// Sets a new actor and returns the old one.
     pub fn set_actor(&mut self, actor: AccountIdFor<T::Runtime>) -> AccountIdFor<T::Runtime> {
-        let actor = mem::replace(&mut self.actor, actor);
         let origin = T::convert_account_to_origin(actor.clone());
-        self.sandbox
-            .map_account(origin.clone())
-            .expect("Failed to map actor account");
-        let _ = mem::replace(&mut self.origin, origin);
-        actor
+        let mapped = self
+            .sandbox
+            .execute_with(|| <T::Runtime as Config>::AddressMapper::is_mapped(&actor));
+        if !mapped {
+            self.sandbox
+                .map_account(origin.clone())
+                .expect("Failed to map actor account");
+        }
+        self.origin = origin;
+        mem::replace(&mut self.actor, actor)
     }
 
     /// Sets a new gas limit and returns updated `self`.
@@ -308,11 +334,64 @@ where
         &self.record
     }
 
+    /// Returns the weight and storage deposit profile of the contract interactions in the session.
+    pub fn profile(&self) -> &Profile {
+        &self.profile
+    }
+
     /// Returns a reference for mocking API.
     pub fn mocking_api(&mut self) -> &mut impl MockingApi<T::Runtime> {
         self
     }
 
+    /// Writes the storage exported from a chain into the sandbox and returns updated `self`.
+    pub fn with_state(mut self, state: &StateExport) -> Result<Self, SessionError>
+    where
+        T::Runtime: pallet_contracts::Config,
+    {
+        self.load_state(state)?;
+        Ok(self)
+    }
+
+    /// Writes the storage exported from a chain into the sandbox.
+    ///
+    /// Contracts found in the export can be called once their transcoders are registered with
+    /// `set_transcoder`. Exports of chains running contracts on another pallet than the sandbox
+    /// are refused (see [`StateExport::ensure_loadable`]).
+    pub fn load_state(&mut self, state: &StateExport) -> Result<(), SessionError>
+    where
+        T::Runtime: pallet_contracts::Config,
+    {
+        state.ensure_loadable::<T::Runtime>()?;
+        self.sandbox.execute_with(|| state.write());
+        Ok(())
+    }
+
+    /// Exports the storage of `pallets` and of all contracts, in the format of
+    /// `setheum-node export-contracts-state`.
+    pub fn export_state(&mut self, pallets: &[&str]) -> StateExport
+    where
+        T::Runtime: pallet_contracts::Config,
+    {
+        let block_number = self
+            .sandbox
+            .execute_with(frame_system::Pallet::<T::Runtime>::block_number)
+            .unique_saturated_into();
+        Storage::read(&mut self.sandbox).into_export(
+            pallets,
+            block_number,
+            ContractsPallet::contracts::<T::Runtime>(),
+        )
+    }
+
+    /// Runs `f` on the session and returns its result together with the storage it changed.
+    pub fn diff_state<V>(&mut self, f: impl FnOnce(&mut Self) -> V) -> (V, StateDiff) {
+        let before = Storage::read(&mut self.sandbox);
+        let result = f(self);
+        let after = Storage::read(&mut self.sandbox);
+        (result, StateDiff::new(&before, &after, &T::get_metadata()))
+    }
+
     /// Deploys a contract with a given constructor, arguments, salt and endowment. In case of
     /// success, returns `self`.
     pub fn deploy_and<S: AsRef<str> + Debug>(
@@ -342,6 +421,38 @@ where
         result
     }
 
+    fn sample(
+        failed: bool,
+        gas_consumed: Weight,
+        deposit: &StorageDeposit<BalanceOf<T::Runtime>>,
+    ) -> Sample {
+        let amount = |balance: &BalanceOf<T::Runtime>| {
+            let balance: U256 = (*balance).into();
+            u128::try_from(balance).unwrap_or(u128::MAX)
+        };
+        Sample {
+            failed,
+            gas_consumed,
+            deposit: match deposit {
+                StorageDeposit::Charge(balance) => StorageDeposit::Charge(amount(balance)),
+                StorageDeposit::Refund(balance) => StorageDeposit::Refund(amount(balance)),
+            },
+        }
+    }
+
+    fn record_profile(
+        &mut self,
+        transcoder: Option<&ContractMessageTranscoder>,
+        address: H160,
+        kind: EntryKind,
+        selector: &[u8],
+        sample: Sample,
+    ) {
+        self.profile
+            .record(transcoder, address, kind, selector, &sample);
+        profile::update_suite(|suite| suite.record(transcoder, address, kind, selector, &sample));
+    }
+
     /// Deploys a contract with a given constructor, arguments, salt and endowment. In case of
     /// success, returns the address of the deployed contract.
     pub fn deploy<S: AsRef<str> + Debug>(
@@ -356,8 +467,24 @@ where
         let data = transcoder
             .encode(constructor, args)
             .map_err(|err| SessionError::Encoding(err.to_string()))?;
+        self.deploy_with_data(contract_bytes, data, salt, endowment, transcoder)
+    }
 
-        let result = self.record_events(|session| {
+    /// Similar to `deploy` but takes the already encoded constructor call data (selector
+    /// followed by the SCALE encoded arguments).
+    pub fn deploy_with_data(
+        &mut self,
+        contract_bytes: Vec<u8>,
+        data: Vec<u8>,
+        salt: Option<[u8; 32]>,
+        endowment: Option<BalanceOf<T::Runtime>>,
+        transcoder: &Arc<ContractMessageTranscoder>,
+    ) -> Result<H160, SessionError> {
+        #[cfg(feature = "coverage")]
+        let contract_bytes = coverage::instrument_if_enabled(contract_bytes)?;
+        let selector = data.get(..4).unwrap_or_default().to_vec();
+        #[allow(unused_mut)]
+        let mut result = self.record_events(|session| {
             session.sandbox.deploy_contract(
                 contract_bytes,
                 endowment.unwrap_or_default(),
@@ -368,6 +495,8 @@ where
                 DepositLimit::Balance(session.storage_deposit_limit),
             )
         });
+        #[cfg(feature = "coverage")]
+        coverage::take_hits(&mut result.debug_message);
 
         let ret = match &result.result {
             Ok(exec_result) if exec_result.result.did_revert() => {
@@ -383,6 +512,19 @@ where
             Err(err) => Err(SessionError::DeploymentFailed(*err)),
         };
 
+        self.profile.declare(transcoder);
+        profile::update_suite(|suite| suite.declare(transcoder));
+        self.record_profile(
+            Some(transcoder),
+            result
+                .result
+                .as_ref()
+                .map_or(H160::zero(), |exec_result| exec_result.addr),
+            EntryKind::Constructor,
+            &selector,
+            Self::sample(ret.is_err(), result.gas_consumed, &result.storage_deposit),
+        );
+
         self.record.push_deploy_result(result);
         ret
     }
@@ -421,8 +563,14 @@ where
             .transcoder
             .encode(constructor, args)
             .map_err(|err| SessionError::Encoding(err.to_string()))?;
+        #[cfg(feature = "coverage")]
+        let contract_file = ContractBundle {
+            binary: coverage::instrument_if_enabled(contract_file.binary)?,
+            ..contract_file
+        };
 
-        Ok(self.sandbox.dry_run(|sandbox| {
+        #[allow(unused_mut)]
+        let mut result = self.sandbox.dry_run(|sandbox| {
             sandbox.deploy_contract(
                 contract_file.binary,
                 endowment.unwrap_or_default(),
@@ -432,7 +580,10 @@ where
                 self.gas_limit,
                 DepositLimit::Balance(self.storage_deposit_limit),
             )
-        }))
+        });
+        #[cfg(feature = "coverage")]
+        coverage::take_hits(&mut result.debug_message);
+        Ok(result)
     }
 
     /// Similar to `deploy_and` but takes the parsed contract file (`ContractBundle`) as a first argument.
@@ -457,6 +608,8 @@ where
 
     /// Uploads a raw contract code. In case of success returns the code hash.
     pub fn upload(&mut self, contract_bytes: Vec<u8>) -> Result<H256, SessionError> {
+        #[cfg(feature = "coverage")]
+        let contract_bytes = coverage::instrument_if_enabled(contract_bytes)?;
         let result = self.sandbox.upload_contract(
             contract_bytes,
             self.origin.clone(),
@@ -563,7 +716,8 @@ where
             .encode(message, args)
             .map_err(|err| SessionError::Encoding(err.to_string()))?;
 
-        Ok(self.sandbox.dry_run(|sandbox| {
+        #[allow(unused_mut)]
+        let mut result = self.sandbox.dry_run(|sandbox| {
             sandbox.call_contract(
                 address,
                 endowment.unwrap_or_default(),
@@ -572,7 +726,10 @@ where
                 self.gas_limit,
                 DepositLimit::Balance(self.storage_deposit_limit),
             )
-        }))
+        });
+        #[cfg(feature = "coverage")]
+        coverage::take_hits(&mut result.debug_message);
+        Ok(result)
     }
 
     fn call_internal<S: AsRef<str> + Debug, V: Decode>(
@@ -598,8 +755,21 @@ where
             .ok_or(SessionError::NoTranscoder)?
             .encode(message, args)
             .map_err(|err| SessionError::Encoding(err.to_string()))?;
+        self.call_with_data(address, data, endowment)
+    }
 
-        let result = self.record_events(|session| {
+    /// Calls a contract with a given address, passing the already encoded call data (selector
+    /// followed by the SCALE encoded arguments). In case of a successful call, returns the
+    /// decoded result.
+    pub fn call_with_data<V: Decode>(
+        &mut self,
+        address: H160,
+        data: Vec<u8>,
+        endowment: Option<BalanceOf<T::Runtime>>,
+    ) -> Result<MessageResult<V>, SessionError> {
+        let selector = data.get(..4).unwrap_or_default().to_vec();
+        #[allow(unused_mut)]
+        let mut result = self.record_events(|session| {
             session.sandbox.call_contract(
                 address,
                 endowment.unwrap_or_default(),
@@ -609,6 +779,8 @@ where
                 DepositLimit::Balance(session.storage_deposit_limit),
             )
         });
+        #[cfg(feature = "coverage")]
+        coverage::take_hits(&mut result.debug_message);
 
         let ret = match &result.result {
             Ok(exec_result) if exec_result.did_revert() => {
@@ -621,6 +793,14 @@ where
             Err(err) => Err(SessionError::CallFailed(*err)),
         };
 
+        let transcoder = self.transcoders.get(&address);
+        self.record_profile(
+            transcoder.as_deref(),
+            address,
+            EntryKind::Message,
+            &selector,
+            Self::sample(ret.is_err(), result.gas_consumed, &result.storage_deposit),
+        );
         self.record.push_call_result(result);
         ret
     }
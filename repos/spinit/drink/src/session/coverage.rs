@@ -0,0 +1,1200 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+
+//! Line level code coverage of contracts.
+//!
+//! If the [`COVERAGE_ENV_VAR`] environment variable points to a directory, `Session` instruments
+//! every Wasm blob before uploading it, and `#[drink::test]` builds contracts in debug mode with
+//! debug symbols.
+//!
+//! The DWARF line table of a contract maps its instructions to source lines. Instrumentation adds
+//! a counter global for every line and increments it wherever the execution enters code of the
+//! line: where the line changes and at the beginning of every block. Functions named in the name
+//! section get a counter of their calls as well, located at the line of their first instruction.
+//! Before the contract returns or traps, the non-zero counters are flushed to the debug buffer
+//! with the `debug_message` host function. `Session` removes these markers from the debug buffers
+//! of deployments, calls and dry runs and adds them up, so read-only calls are covered as well.
+//! The coverage report is written in the lcov format and covers the Rust sources of the tested
+//! crate.
+//!
+//! Instrumentation has a price: counting and flushing consume weight, so don't compare weight
+//! profiles of instrumented and regular runs. Contracts running out of gas don't flush their
+//! counters. Blobs that are not Wasm, or have no debug symbols, are left untouched.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    env,
+    error::Error,
+    fmt::Write as _,
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, OnceLock},
+};
+
+use gimli::{Dwarf, EndianSlice, FileEntry, LineProgramHeader, LittleEndian, Reader, Unit};
+use wasm_instrument::parity_wasm::{
+    self,
+    elements::{
+        BlockType, External, Func, FuncBody, FunctionType, GlobalEntry, GlobalType,
+        ImportCountType, ImportEntry, InitExpr, Instruction, Instructions, Internal, Local, Module,
+        Section, Type, ValueType,
+    },
+};
+use wasmparser::{KnownCustom, Name, Parser, Payload};
+
+use super::{error::SessionError, report::report_stem};
+
+/// Environment variable with the directory to write the coverage report to.
+pub const COVERAGE_ENV_VAR: &str = "DRINK_COVERAGE";
+
+/// Prefix of the debug messages carrying the hits of a counter.
+const MARKER_PREFIX: &str = "drink-cov:";
+/// Length of a marker: the prefix, then the module index, the counter index and the hits in fixed
+/// width hex, separated by colons, and a newline.
+const MARKER_LEN: u32 = MARKER_PREFIX.len() as u32 + 8 + 1 + 8 + 1 + 16 + 1;
+
+static REGISTRY: Mutex<Registry> = Mutex::new(Registry {
+    modules: Vec::new(),
+    by_code: BTreeMap::new(),
+});
+
+/// Serializes writing of the report by tests finishing concurrently.
+static WRITING: Mutex<()> = Mutex::new(());
+
+/// Contracts instrumented in the current process.
+struct Registry {
+    modules: Vec<InstrumentedModule>,
+    /// Index of the module by its original code.
+    by_code: BTreeMap<Vec<u8>, usize>,
+}
+
+struct InstrumentedModule {
+    code: Vec<u8>,
+    counters: Vec<Counter>,
+    hits: Vec<u64>,
+}
+
+/// What a counter global of an instrumented module counts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Counter {
+    /// Entries into code of a line.
+    Line(Location),
+    /// Calls of the named function starting at the line.
+    Function(String, Location),
+}
+
+/// A line of a source file.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Location {
+    file: PathBuf,
+    line: u64,
+}
+
+/// Coverage of a single source line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineCoverage {
+    /// Source file, as recorded in the debug symbols.
+    pub file: PathBuf,
+    /// Line number, starting from 1.
+    pub line: u64,
+    /// Number of times the execution entered code of the line.
+    pub hits: u64,
+}
+
+/// Coverage of a single function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FunctionCoverage {
+    /// Function name, as recorded in the name section of the module.
+    pub name: String,
+    /// Source file, as recorded in the debug symbols.
+    pub file: PathBuf,
+    /// Line of the first instruction of the function.
+    pub line: u64,
+    /// Number of calls.
+    pub hits: u64,
+}
+
+/// Returns the directory to write the coverage report to, if coverage is enabled.
+pub fn output_dir() -> Option<&'static Path> {
+    static OUTPUT_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    OUTPUT_DIR
+        .get_or_init(|| env::var_os(COVERAGE_ENV_VAR).map(PathBuf::from))
+        .as_deref()
+}
+
+/// Returns the coverage of all the lines of the contracts instrumented so far, ordered by file
+/// and line. Lines shared by several contracts are merged.
+pub fn lines() -> Vec<LineCoverage> {
+    lock_registry().lines()
+}
+
+/// Returns the coverage of all the named functions of the contracts instrumented so far, ordered
+/// by file, line and name. Functions shared by several contracts are merged.
+pub fn functions() -> Vec<FunctionCoverage> {
+    lock_registry().functions()
+}
+
+/// Renders the coverage of the functions and lines of the Rust sources under `source_root` in the
+/// lcov format.
+pub fn lcov(source_root: &Path) -> String {
+    let registry = lock_registry();
+    render_lcov(source_root, &registry.functions(), &registry.lines())
+}
+
+/// Writes the coverage of the tested crate to `<dir>/<test binary>.lcov`.
+pub(crate) fn write_report(dir: &Path) -> io::Result<()> {
+    let _writing = WRITING
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let source_root = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => env::current_dir()?,
+    };
+    fs::create_dir_all(dir)?;
+    fs::write(
+        dir.join(format!("{}.lcov", report_stem())),
+        lcov(&source_root),
+    )
+}
+
+/// Instruments `code` if coverage is enabled and `code` is a Wasm blob. Otherwise, returns `code`
+/// unchanged.
+pub(crate) fn instrument_if_enabled(code: Vec<u8>) -> Result<Vec<u8>, SessionError> {
+    if output_dir().is_none() || !code.starts_with(b"\0asm") {
+        return Ok(code);
+    }
+
+    let mut registry = lock_registry();
+    if let Some(&index) = registry.by_code.get(&code) {
+        return Ok(registry.modules[index].code.clone());
+    }
+
+    let index = registry.modules.len();
+    let (instrumented, counters) =
+        instrument(&code, index as u32).map_err(SessionError::Instrumentation)?;
+    registry.modules.push(InstrumentedModule {
+        code: instrumented.clone(),
+        hits: vec![0; counters.len()],
+        counters,
+    });
+    registry.by_code.insert(code, index);
+    Ok(instrumented)
+}
+
+/// Counts the hits flushed to `debug_message` by instrumented contracts and removes the markers,
+/// leaving the messages of the contracts themselves.
+pub(crate) fn take_hits(debug_message: &mut Vec<u8>) {
+    if output_dir().is_some() {
+        lock_registry().take_hits(debug_message);
+    }
+}
+
+impl Registry {
+    fn take_hits(&mut self, debug_message: &mut Vec<u8>) {
+        let mut messages = Vec::with_capacity(debug_message.len());
+        for message in debug_message.split_inclusive(|byte| *byte == b'\n') {
+            let Some((module, counter, hits)) = marker(message) else {
+                messages.extend_from_slice(message);
+                continue;
+            };
+            if let Some(counter) = self
+                .modules
+                .get_mut(module as usize)
+                .and_then(|module| module.hits.get_mut(counter as usize))
+            {
+                *counter += hits;
+            }
+        }
+        *debug_message = messages;
+    }
+
+    fn lines(&self) -> Vec<LineCoverage> {
+        let mut lines = BTreeMap::<&Location, u64>::new();
+        for module in &self.modules {
+            for (counter, hits) in module.counters.iter().zip(&module.hits) {
+                if let Counter::Line(location) = counter {
+                    *lines.entry(location).or_default() += hits;
+                }
+            }
+        }
+        lines
+            .into_iter()
+            .map(|(location, hits)| LineCoverage {
+                file: location.file.clone(),
+                line: location.line,
+                hits,
+            })
+            .collect()
+    }
+
+    fn functions(&self) -> Vec<FunctionCoverage> {
+        let mut functions = BTreeMap::<(&Location, &str), u64>::new();
+        for module in &self.modules {
+            for (counter, hits) in module.counters.iter().zip(&module.hits) {
+                if let Counter::Function(name, location) = counter {
+                    *functions.entry((location, name)).or_default() += hits;
+                }
+            }
+        }
+        functions
+            .into_iter()
+            .map(|((location, name), hits)| FunctionCoverage {
+                name: name.to_string(),
+                file: location.file.clone(),
+                line: location.line,
+                hits,
+            })
+            .collect()
+    }
+}
+
+/// Renders the coverage of the functions and lines of the files under `source_root`.
+fn render_lcov(
+    source_root: &Path,
+    functions: &[FunctionCoverage],
+    lines: &[LineCoverage],
+) -> String {
+    #[derive(Default)]
+    struct File<'a> {
+        functions: Vec<&'a FunctionCoverage>,
+        lines: Vec<&'a LineCoverage>,
+    }
+
+    let mut files = BTreeMap::<&Path, File>::new();
+    for function in functions {
+        if function.file.starts_with(source_root) {
+            files
+                .entry(&function.file)
+                .or_default()
+                .functions
+                .push(function);
+        }
+    }
+    for line in lines {
+        if line.file.starts_with(source_root) {
+            files.entry(&line.file).or_default().lines.push(line);
+        }
+    }
+
+    let mut report = String::new();
+    for (file, coverage) in files {
+        let _ = writeln!(report, "TN:\nSF:{}", file.display());
+        for function in &coverage.functions {
+            let _ = writeln!(report, "FN:{},{}", function.line, function.name);
+        }
+        for function in &coverage.functions {
+            let _ = writeln!(report, "FNDA:{},{}", function.hits, function.name);
+        }
+        let hit = coverage
+            .functions
+            .iter()
+            .filter(|function| function.hits > 0);
+        let _ = writeln!(
+            report,
+            "FNF:{}\nFNH:{}",
+            coverage.functions.len(),
+            hit.count()
+        );
+        for line in &coverage.lines {
+            let _ = writeln!(report, "DA:{},{}", line.line, line.hits);
+        }
+        let hit = coverage.lines.iter().filter(|line| line.hits > 0);
+        let _ = writeln!(
+            report,
+            "LF:{}\nLH:{}\nend_of_record",
+            coverage.lines.len(),
+            hit.count()
+        );
+    }
+    report
+}
+
+fn lock_registry() -> MutexGuard<'static, Registry> {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Parses a marker into the module index, the counter index and the hits.
+fn marker(message: &[u8]) -> Option<(u32, u32, u64)> {
+    let fields = message
+        .strip_prefix(MARKER_PREFIX.as_bytes())?
+        .strip_suffix(b"\n")?;
+    let mut fields = std::str::from_utf8(fields).ok()?.split(':');
+    let module = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let counter = u32::from_str_radix(fields.next()?, 16).ok()?;
+    let hits = u64::from_str_radix(fields.next()?, 16).ok()?;
+    fields.next().is_none().then_some((module, counter, hits))
+}
+
+/// Adds a counter for every source line found in the line table of the module, and for every
+/// named function with a line. Returns the instrumented code together with what the counters
+/// count, in the order of their globals.
+fn instrument(code: &[u8], module_index: u32) -> Result<(Vec<u8>, Vec<Counter>), String> {
+    let debug_info = DebugInfo::parse(code).map_err(|err| err.to_string())?;
+    if debug_info.rows.is_empty() {
+        return Ok((code.to_vec(), Vec::new()));
+    }
+
+    let mut module =
+        parity_wasm::deserialize_buffer::<Module>(code).map_err(|err| err.to_string())?;
+    let stack_pointer = stack_pointer(&module)?;
+    // The name section refers to the functions by their original indices.
+    let named_functions = module.import_count(ImportCountType::Function) as u32;
+    let debug_message = import_function(
+        &mut module,
+        &["debug_message", "seal_debug_message"],
+        FunctionType::new(vec![ValueType::I32; 2], vec![ValueType::I32]),
+    )?;
+    let seal_return = imported_function(&module, &["seal_return", "return"]);
+
+    let imported_functions = module.import_count(ImportCountType::Function) as u32;
+    let entry_points = module.export_section().map_or(Vec::new(), |exports| {
+        exports
+            .entries()
+            .iter()
+            .filter(|entry| matches!(entry.field(), "call" | "deploy"))
+            .filter_map(|entry| match entry.internal() {
+                Internal::Function(index) => Some(*index),
+                _ => None,
+            })
+            .collect()
+    });
+    let first_counter = module.import_count(ImportCountType::Global) as u32
+        + module
+            .global_section()
+            .map_or(0, |section| section.entries().len()) as u32;
+
+    let bodies = module
+        .code_section_mut()
+        .ok_or("module has no code section")?
+        .bodies_mut();
+    if bodies.len() != debug_info.functions.len() {
+        return Err("functions do not match the code section".to_string());
+    }
+    // The functions flushing the counters come right after the ones defined by the module.
+    let emit = imported_functions + bodies.len() as u32;
+    let flush = emit + 1;
+
+    let location = |(file, line): (usize, u64)| Location {
+        file: debug_info.files[file].clone(),
+        line,
+    };
+    let mut counters = Vec::new();
+    let mut line_counters = HashMap::new();
+    for (function, (body, offsets)) in bodies.iter_mut().zip(&debug_info.functions).enumerate() {
+        let instructions = body.code_mut().elements_mut();
+        if instructions.len() != offsets.len() {
+            return Err(format!(
+                "instructions of function {function} do not match its code"
+            ));
+        }
+        let entry_point = entry_points.contains(&(imported_functions + function as u32));
+
+        let mut instrumented = Vec::with_capacity(instructions.len());
+        let name = debug_info.names.get(&(named_functions + function as u32));
+        let start = offsets
+            .iter()
+            .find_map(|offset| debug_info.line_at(*offset));
+        if let (Some(name), Some(start)) = (name, start) {
+            counters.push(Counter::Function(name.clone(), location(start)));
+            instrumented.extend(increment(first_counter + counters.len() as u32 - 1));
+        }
+        let mut previous = None;
+        let mut block_start = true;
+        for (index, (instruction, offset)) in instructions.drain(..).zip(offsets).enumerate() {
+            use Instruction::*;
+
+            // `else` and `end` belong to no line of their own.
+            if !matches!(instruction, Else | End) {
+                let line = debug_info.line_at(*offset);
+                if let Some(line) = line.filter(|_| block_start || line != previous) {
+                    let counter = *line_counters.entry(line).or_insert_with(|| {
+                        counters.push(Counter::Line(location(line)));
+                        counters.len() as u32 - 1
+                    });
+                    instrumented.extend(increment(first_counter + counter));
+                }
+                previous = line;
+            }
+
+            let flushes = match instruction {
+                Call(callee) => Some(callee) == seal_return,
+                Unreachable => true,
+                Return => entry_point,
+                End => entry_point && index + 1 == offsets.len(),
+                _ => false,
+            };
+            if flushes {
+                instrumented.push(Call(flush));
+            }
+            block_start = matches!(instruction, Block(_) | Loop(_) | If(_) | Else | End);
+            instrumented.push(instruction);
+        }
+        *instructions = instrumented;
+    }
+
+    let globals = module
+        .global_section_mut()
+        .ok_or("module has no globals")?
+        .entries_mut();
+    for _ in 0..counters.len() {
+        globals.push(GlobalEntry::new(
+            GlobalType::new(ValueType::I64, true),
+            InitExpr::new(vec![Instruction::I64Const(0), Instruction::End]),
+        ));
+    }
+    add_function(
+        &mut module,
+        FunctionType::new(vec![ValueType::I64; 2], vec![]),
+        emit_function(stack_pointer, debug_message, module_index),
+    )?;
+    add_function(
+        &mut module,
+        FunctionType::new(vec![], vec![]),
+        flush_function(first_counter, counters.len() as u32, emit),
+    )?;
+
+    // Function indices and code offsets have changed, so the names and the debug symbols would be
+    // misleading anyway.
+    module.sections_mut().retain(|section| match section {
+        Section::Name(_) => false,
+        Section::Custom(custom) => custom.name() != "name" && !custom.name().starts_with(".debug_"),
+        _ => true,
+    });
+
+    let code = parity_wasm::serialize(module).map_err(|err| err.to_string())?;
+    Ok((code, counters))
+}
+
+fn increment(global: u32) -> [Instruction; 4] {
+    use Instruction::*;
+
+    [GetGlobal(global), I64Const(1), I64Add, SetGlobal(global)]
+}
+
+/// What instrumentation needs from the debug symbols of a module.
+struct DebugInfo {
+    /// Offsets of the instructions of every defined function, relative to the code section, like
+    /// the addresses in the line table.
+    functions: Vec<Vec<u64>>,
+    /// Names of the functions by their indices, imported functions included.
+    names: HashMap<u32, String>,
+    files: Vec<PathBuf>,
+    /// Rows of the line table, ordered by address. Rows without a location end a sequence.
+    rows: Vec<(u64, Option<(usize, u64)>)>,
+}
+
+impl DebugInfo {
+    fn parse(code: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut functions = Vec::new();
+        let mut names = HashMap::new();
+        let mut sections = HashMap::new();
+        let mut code_start = 0;
+        for payload in Parser::new(0).parse_all(code) {
+            match payload? {
+                Payload::CodeSectionStart { range, .. } => code_start = range.start,
+                Payload::CodeSectionEntry(body) => {
+                    let mut operators = body.get_operators_reader()?;
+                    let mut offsets = Vec::new();
+                    while !operators.eof() {
+                        let (_, offset) = operators.read_with_offset()?;
+                        offsets.push((offset - code_start) as u64);
+                    }
+                    functions.push(offsets);
+                }
+                Payload::CustomSection(section) if section.name().starts_with(".debug_") => {
+                    sections.insert(section.name(), section.data());
+                }
+                Payload::CustomSection(section) => {
+                    let KnownCustom::Name(reader) = section.as_known() else {
+                        continue;
+                    };
+                    for subsection in reader {
+                        if let Name::Function(map) = subsection? {
+                            for naming in map {
+                                let naming = naming?;
+                                names.insert(naming.index, naming.name.to_string());
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut debug_info = DebugInfo {
+            functions,
+            names,
+            files: Vec::new(),
+            rows: Vec::new(),
+        };
+        debug_info.read_line_table(&sections)?;
+        Ok(debug_info)
+    }
+
+    fn read_line_table(&mut self, sections: &HashMap<&str, &[u8]>) -> Result<(), gimli::Error> {
+        let dwarf = Dwarf::load(|id| -> Result<_, gimli::Error> {
+            let data = sections.get(id.name()).copied().unwrap_or_default();
+            Ok(EndianSlice::new(data, LittleEndian))
+        })?;
+
+        let mut files = HashMap::new();
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            let Some(program) = unit.line_program.clone() else {
+                continue;
+            };
+            let mut unit_files = HashMap::new();
+            let mut sequence = Vec::new();
+            let mut rows = program.rows();
+            while let Some((header, row)) = rows.next_row()? {
+                if row.end_sequence() {
+                    // Sequences of functions removed by the linker start at address 0.
+                    if sequence.first().is_some_and(|(address, _)| *address != 0) {
+                        self.rows.append(&mut sequence);
+                        self.rows.push((row.address(), None));
+                    }
+                    sequence.clear();
+                    continue;
+                }
+
+                let location = match (row.file(header), row.line()) {
+                    (Some(file), Some(line)) => {
+                        let index = match unit_files.get(&row.file_index()) {
+                            Some(&index) => index,
+                            None => {
+                                let path = file_path(&dwarf, &unit, header, file)?;
+                                let index = *files.entry(path.clone()).or_insert(self.files.len());
+                                if index == self.files.len() {
+                                    self.files.push(path);
+                                }
+                                unit_files.insert(row.file_index(), index);
+                                index
+                            }
+                        };
+                        Some((index, line.get()))
+                    }
+                    _ => None,
+                };
+                sequence.push((row.address(), location));
+            }
+        }
+        // A sequence may start where another one ends.
+        self.rows
+            .sort_by_key(|(address, location)| (*address, location.is_some()));
+        Ok(())
+    }
+
+    /// Returns the file index and the line of the instruction at `offset`.
+    fn line_at(&self, offset: u64) -> Option<(usize, u64)> {
+        let position = self.rows.partition_point(|(address, _)| *address <= offset);
+        self.rows.get(position.checked_sub(1)?)?.1
+    }
+}
+
+fn file_path<R: Reader>(
+    dwarf: &Dwarf<R>,
+    unit: &Unit<R>,
+    header: &LineProgramHeader<R>,
+    file: &FileEntry<R>,
+) -> Result<PathBuf, gimli::Error> {
+    // Absolute components replace the preceding ones.
+    let mut path = PathBuf::new();
+    if let Some(comp_dir) = &unit.comp_dir {
+        path.push(&*comp_dir.to_string_lossy()?);
+    }
+    if let Some(directory) = file.directory(header) {
+        path.push(&*dwarf.attr_string(unit, directory)?.to_string_lossy()?);
+    }
+    path.push(
+        &*dwarf
+            .attr_string(unit, file.path_name())?
+            .to_string_lossy()?,
+    );
+    Ok(path)
+}
+
+/// The stack pointer is the first mutable `i32` global defined by the module.
+fn stack_pointer(module: &Module) -> Result<u32, String> {
+    let imported = module.import_count(ImportCountType::Global) as u32;
+    module
+        .global_section()
+        .and_then(|section| {
+            section.entries().iter().position(|global| {
+                global.global_type().is_mutable()
+                    && global.global_type().content_type() == ValueType::I32
+            })
+        })
+        .map(|position| imported + position as u32)
+        .ok_or_else(|| "module has no stack pointer".to_string())
+}
+
+/// Returns the index of the host function imported from `seal0` under any of `names`.
+fn imported_function(module: &Module, names: &[&str]) -> Option<u32> {
+    module
+        .import_section()?
+        .entries()
+        .iter()
+        .filter(|entry| matches!(entry.external(), External::Function(_)))
+        .position(|entry| entry.module() == "seal0" && names.contains(&entry.field()))
+        .map(|position| position as u32)
+}
+
+/// Returns the index of the host function imported from `seal0` under any of `names`, importing
+/// it under the first one if needed.
+fn import_function(
+    module: &mut Module,
+    names: &[&str],
+    signature: FunctionType,
+) -> Result<u32, String> {
+    if let Some(index) = imported_function(module, names) {
+        return Ok(index);
+    }
+
+    let function_index = module.import_count(ImportCountType::Function) as u32;
+    let type_index = type_index(module, signature)?;
+    module
+        .import_section_mut()
+        .ok_or("module has no imports")?
+        .entries_mut()
+        .push(ImportEntry::new(
+            "seal0".into(),
+            names[0].into(),
+            External::Function(type_index),
+        ));
+
+    // The new import comes right after the imported functions, shifting all the defined ones.
+    shift_functions(module, function_index);
+    Ok(function_index)
+}
+
+fn type_index(module: &mut Module, signature: FunctionType) -> Result<u32, String> {
+    let signature = Type::Function(signature);
+    let types = module
+        .type_section_mut()
+        .ok_or("module has no type section")?
+        .types_mut();
+    Ok(
+        match types.iter().position(|existing| *existing == signature) {
+            Some(position) => position,
+            None => {
+                types.push(signature);
+                types.len() - 1
+            }
+        } as u32,
+    )
+}
+
+fn add_function(
+    module: &mut Module,
+    signature: FunctionType,
+    body: FuncBody,
+) -> Result<(), String> {
+    let type_index = type_index(module, signature)?;
+    module
+        .function_section_mut()
+        .ok_or("module has no function section")?
+        .entries_mut()
+        .push(Func::new(type_index));
+    module
+        .code_section_mut()
+        .ok_or("module has no code section")?
+        .bodies_mut()
+        .push(body);
+    Ok(())
+}
+
+fn shift_functions(module: &mut Module, from: u32) {
+    let shift = |index: &mut u32| {
+        if *index >= from {
+            *index += 1
+        }
+    };
+
+    if let Some(code) = module.code_section_mut() {
+        for body in code.bodies_mut() {
+            for instruction in body.code_mut().elements_mut() {
+                if let Instruction::Call(index) = instruction {
+                    shift(index)
+                }
+            }
+        }
+    }
+    if let Some(exports) = module.export_section_mut() {
+        for entry in exports.entries_mut() {
+            if let Internal::Function(index) = entry.internal_mut() {
+                shift(index)
+            }
+        }
+    }
+    if let Some(elements) = module.elements_section_mut() {
+        for segment in elements.entries_mut() {
+            segment.members_mut().iter_mut().for_each(shift);
+        }
+    }
+    if let Some(mut start) = module.start_section() {
+        shift(&mut start);
+        module.set_start_section(start);
+    }
+}
+
+/// Function flushing the non-zero counters with `emit` and resetting them, so that nothing is
+/// counted twice.
+fn flush_function(first_counter: u32, counters: u32, emit: u32) -> FuncBody {
+    use Instruction::*;
+
+    let mut code = Vec::new();
+    for counter in 0..counters {
+        let global = first_counter + counter;
+        code.extend([
+            GetGlobal(global),
+            I64Eqz,
+            I32Eqz,
+            If(BlockType::NoResult),
+            I64Const(counter as i64),
+            GetGlobal(global),
+            Call(emit),
+            I64Const(0),
+            SetGlobal(global),
+            End,
+        ]);
+    }
+    code.push(End);
+    FuncBody::new(Vec::new(), Instructions::new(code))
+}
+
+/// Function writing the marker of a counter, given its index and its hits, to the debug buffer. The marker is text, as the host function rejects messages that are not UTF-8.
+fn emit_function(stack_pointer: u32, debug_message: u32, module_index: u32) -> FuncBody {
+    use Instruction::*;
+
+    let mut marker = MarkerWriter {
+        code: vec![
+            // The marker is written right below the stack pointer, i.e. to the unused part of the
+            // stack.
+            GetGlobal(stack_pointer),
+            I32Const(MARKER_LEN as i32),
+            I32Sub,
+            SetLocal(MarkerWriter::BASE),
+        ],
+        offset: 0,
+    };
+    marker.text(&format!("{MARKER_PREFIX}{module_index:08x}:"));
+    marker.hex(0, 8);
+    marker.text(":");
+    marker.hex(1, 16);
+    marker.text("\n");
+
+    let mut code = marker.code;
+    code.extend([
+        GetLocal(MarkerWriter::BASE),
+        I32Const(MARKER_LEN as i32),
+        Call(debug_message),
+        Drop,
+        End,
+    ]);
+    FuncBody::new(vec![Local::new(2, ValueType::I32)], Instructions::new(code))
+}
+
+/// Generates code storing the marker byte by byte.
+struct MarkerWriter {
+    code: Vec<Instruction>,
+    offset: u32,
+}
+
+impl MarkerWriter {
+    /// Local with the address of the marker, following the two parameters.
+    const BASE: u32 = 2;
+    /// Local with the digit being written.
+    const NIBBLE: u32 = 3;
+
+    fn text(&mut self, text: &str) {
+        for byte in text.bytes() {
+            self.code.extend([
+                Instruction::GetLocal(Self::BASE),
+                Instruction::I32Const(byte as i32),
+                Instruction::I32Store8(0, self.offset),
+            ]);
+            self.offset += 1;
+        }
+    }
+
+    /// Writes the lowest `digits` hex digits of the `i64` parameter `local`.
+    fn hex(&mut self, local: u32, digits: u32) {
+        use Instruction::*;
+
+        for digit in (0..digits).rev() {
+            self.code.extend([
+                GetLocal(local),
+                I64Const(4 * digit as i64),
+                I64ShrU,
+                I64Const(0xf),
+                I64And,
+                I32WrapI64,
+                SetLocal(Self::NIBBLE),
+                GetLocal(Self::BASE),
+                // `'0' + nibble`, skipping to `'a'` past 9.
+                GetLocal(Self::NIBBLE),
+                I32Const(b'0' as i32),
+                I32Add,
+                I32Const((b'a' - b'0' - 10) as i32),
+                I32Const(0),
+                GetLocal(Self::NIBBLE),
+                I32Const(9),
+                I32GtU,
+                Select,
+                I32Add,
+                I32Store8(0, self.offset),
+            ]);
+            self.offset += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gimli::{
+        write::{Address, AttributeValue, DwarfUnit, EndianVec, LineProgram, LineString, Sections},
+        Encoding, Format, LineEncoding,
+    };
+
+    use super::*;
+
+    /// A contract with two functions, without debug symbols.
+    const CONTRACT: &str = r#"
+        (module
+            (import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+            (import "env" "memory" (memory 1 1))
+            (global $stack_pointer (mut i32) (i32.const 65536))
+            (func $deploy
+                nop)
+            (func $call
+                i32.const 0
+                i32.const 0
+                i32.const 0
+                call $seal_return)
+            (export "deploy" (func $deploy))
+            (export "call" (func $call)))
+    "#;
+
+    fn location(file: &str, line: u64) -> Location {
+        Location {
+            file: PathBuf::from(file),
+            line,
+        }
+    }
+
+    fn marker_of(module: u32, counter: u32, hits: u64) -> String {
+        format!("{MARKER_PREFIX}{module:08x}:{counter:08x}:{hits:016x}\n")
+    }
+
+    /// Appends a line table to `code`, mapping the instructions of `deploy` to line 3 of
+    /// `/src/lib.rs`, and those of `call` to lines 10 and 11.
+    fn with_line_table(code: Vec<u8>) -> Vec<u8> {
+        let offsets = DebugInfo::parse(&code).expect("Valid module").functions;
+        let (deploy, call) = (&offsets[0], &offsets[1]);
+
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size: 4,
+        };
+        let comp_dir = LineString::String(b"/src".to_vec());
+        let mut program = LineProgram::new(
+            encoding,
+            LineEncoding::default(),
+            comp_dir.clone(),
+            LineString::String(b"lib.rs".to_vec()),
+            None,
+        );
+        let directory = program.default_directory();
+        let file = program.add_file(LineString::String(b"lib.rs".to_vec()), directory, None);
+        let mut sequence = |start: u64, rows: &[(u64, u64)], end: u64| {
+            program.begin_sequence(Some(Address::Constant(start)));
+            for (address, line) in rows {
+                program.row().address_offset = address - start;
+                program.row().file = file;
+                program.row().line = *line;
+                program.generate_row();
+            }
+            program.end_sequence(end - start);
+        };
+        sequence(deploy[0], &[(deploy[0], 3)], call[0]);
+        sequence(call[0], &[(call[0], 10), (call[3], 11)], call[4] + 1);
+        // A function removed by the linker.
+        sequence(0, &[(0, 99)], 2);
+
+        let mut unit = DwarfUnit::new(encoding);
+        unit.unit.line_program = program;
+        let root = unit.unit.root();
+        unit.unit.get_mut(root).set(
+            gimli::DW_AT_comp_dir,
+            AttributeValue::String(b"/src".to_vec()),
+        );
+        let mut sections = Sections::new(EndianVec::new(gimli::LittleEndian));
+        unit.write(&mut sections).expect("Valid debug info");
+
+        let mut code = code;
+        sections
+            .for_each(|id, section| -> Result<(), ()> {
+                if !section.slice().is_empty() {
+                    custom_section(&mut code, id.name(), section.slice());
+                }
+                Ok(())
+            })
+            .expect("Infallible");
+        code
+    }
+
+    fn custom_section(code: &mut Vec<u8>, name: &str, data: &[u8]) {
+        let mut payload = Vec::new();
+        leb128(&mut payload, name.len());
+        payload.extend_from_slice(name.as_bytes());
+        payload.extend_from_slice(data);
+        code.push(0);
+        leb128(code, payload.len());
+        code.extend(payload);
+    }
+
+    fn leb128(bytes: &mut Vec<u8>, mut value: usize) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                bytes.push(byte);
+                return;
+            }
+            bytes.push(byte | 0x80);
+        }
+    }
+
+    #[test]
+    fn parses_markers() {
+        let message = marker_of(1, 0x2a, 0x1234_5678_9abc);
+        assert_eq!(message.len(), MARKER_LEN as usize);
+        assert_eq!(
+            marker(message.as_bytes()),
+            Some((1, 0x2a, 0x1234_5678_9abc))
+        );
+
+        assert_eq!(
+            marker(b"drink-cov:00000001:0000002a:0000000000000001"),
+            None
+        );
+        assert_eq!(marker(b"drink-cov:00000001:0000002a\n"), None);
+        assert_eq!(marker(b"drink-cov:00000001:0000002a:1:2\n"), None);
+        assert_eq!(
+            marker(b"drink-cov:00000001:0000002g:0000000000000001\n"),
+            None
+        );
+        assert_eq!(marker(b"Hello, drink-cov:00000001:0000002a:1\n"), None);
+    }
+
+    #[test]
+    fn takes_hits_and_strips_markers() {
+        let mut registry = Registry {
+            modules: vec![InstrumentedModule {
+                code: Vec::new(),
+                counters: vec![
+                    Counter::Line(location("/src/lib.rs", 3)),
+                    Counter::Line(location("/src/lib.rs", 4)),
+                ],
+                hits: vec![0, 0],
+            }],
+            by_code: BTreeMap::new(),
+        };
+        let mut debug_message = format!(
+            "Hello\n{}{}{}world",
+            marker_of(0, 1, 2),
+            marker_of(0, 1, 3),
+            // Markers of unknown counters are dropped as well.
+            marker_of(1, 0, 1),
+        )
+        .into_bytes();
+
+        registry.take_hits(&mut debug_message);
+
+        assert_eq!(debug_message, b"Hello\nworld");
+        assert_eq!(registry.modules[0].hits, vec![0, 5]);
+    }
+
+    #[test]
+    fn merges_coverage_of_modules() {
+        let module = |hits| InstrumentedModule {
+            code: Vec::new(),
+            counters: vec![
+                Counter::Function("flip".to_string(), location("/src/lib.rs", 3)),
+                Counter::Line(location("/src/lib.rs", 3)),
+            ],
+            hits,
+        };
+        let registry = Registry {
+            modules: vec![module(vec![1, 2]), module(vec![0, 5])],
+            by_code: BTreeMap::new(),
+        };
+
+        assert_eq!(
+            registry.lines(),
+            vec![LineCoverage {
+                file: PathBuf::from("/src/lib.rs"),
+                line: 3,
+                hits: 7,
+            }]
+        );
+        assert_eq!(
+            registry.functions(),
+            vec![FunctionCoverage {
+                name: "flip".to_string(),
+                file: PathBuf::from("/src/lib.rs"),
+                line: 3,
+                hits: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn maps_instructions_to_lines() {
+        let code = with_line_table(wat::parse_str(CONTRACT).expect("Valid module"));
+        let debug_info = DebugInfo::parse(&code).expect("Valid debug info");
+        let (deploy, call) = (&debug_info.functions[0], &debug_info.functions[1]);
+
+        assert_eq!(debug_info.files, vec![PathBuf::from("/src/lib.rs")]);
+        assert_eq!(debug_info.line_at(deploy[0]), Some((0, 3)));
+        assert_eq!(debug_info.line_at(call[0]), Some((0, 10)));
+        assert_eq!(debug_info.line_at(call[2]), Some((0, 10)));
+        assert_eq!(debug_info.line_at(call[3]), Some((0, 11)));
+        assert_eq!(debug_info.line_at(call[4]), Some((0, 11)));
+        // Past the end of the last sequence, and in the one of the removed function.
+        assert_eq!(debug_info.line_at(call[4] + 1), None);
+        assert_eq!(debug_info.line_at(1), None);
+    }
+
+    #[test]
+    fn reads_function_names() {
+        let code = wat::parse_str(CONTRACT).expect("Valid module");
+        let debug_info = DebugInfo::parse(&code).expect("Valid module");
+
+        assert_eq!(debug_info.functions.len(), 2);
+        assert_eq!(debug_info.names[&0], "seal_return");
+        assert_eq!(debug_info.names[&1], "deploy");
+        assert_eq!(debug_info.names[&2], "call");
+        assert!(debug_info.rows.is_empty());
+    }
+
+    #[test]
+    fn leaves_modules_without_line_table_untouched() {
+        let code = wat::parse_str(CONTRACT).expect("Valid module");
+        assert_eq!(instrument(&code, 0), Ok((code, Vec::new())));
+    }
+
+    #[test]
+    fn counts_functions_and_lines() {
+        let code = with_line_table(wat::parse_str(CONTRACT).expect("Valid module"));
+        let (instrumented, counters) = instrument(&code, 0).expect("Instrumentable");
+
+        assert_eq!(
+            counters,
+            vec![
+                Counter::Function("deploy".to_string(), location("/src/lib.rs", 3)),
+                Counter::Line(location("/src/lib.rs", 3)),
+                Counter::Function("call".to_string(), location("/src/lib.rs", 10)),
+                Counter::Line(location("/src/lib.rs", 10)),
+                Counter::Line(location("/src/lib.rs", 11)),
+            ]
+        );
+        wasmparser::validate(&instrumented).expect("Valid instrumented module");
+        let debug_info = DebugInfo::parse(&instrumented).expect("Valid module");
+        assert!(debug_info.names.is_empty());
+        assert!(debug_info.rows.is_empty());
+    }
+
+    #[test]
+    fn renders_lcov() {
+        let functions = [
+            FunctionCoverage {
+                name: "flip".to_string(),
+                file: PathBuf::from("/src/lib.rs"),
+                line: 3,
+                hits: 2,
+            },
+            FunctionCoverage {
+                name: "get".to_string(),
+                file: PathBuf::from("/src/lib.rs"),
+                line: 8,
+                hits: 0,
+            },
+            FunctionCoverage {
+                name: "fmt".to_string(),
+                file: PathBuf::from("/rustc/core/fmt.rs"),
+                line: 1,
+                hits: 1,
+            },
+        ];
+        let lines = [
+            LineCoverage {
+                file: PathBuf::from("/src/lib.rs"),
+                line: 4,
+                hits: 2,
+            },
+            LineCoverage {
+                file: PathBuf::from("/src/lib.rs"),
+                line: 9,
+                hits: 0,
+            },
+            LineCoverage {
+                file: PathBuf::from("/src/other.rs"),
+                line: 1,
+                hits: 1,
+            },
+            LineCoverage {
+                file: PathBuf::from("/rustc/core/fmt.rs"),
+                line: 2,
+                hits: 1,
+            },
+        ];
+
+        assert_eq!(
+            render_lcov(Path::new("/src"), &functions, &lines),
+            "TN:\nSF:/src/lib.rs\n\
+             FN:3,flip\nFN:8,get\nFNDA:2,flip\nFNDA:0,get\nFNF:2\nFNH:1\n\
+             DA:4,2\nDA:9,0\nLF:2\nLH:1\nend_of_record\n\
+             TN:\nSF:/src/other.rs\n\
+             FNF:0\nFNH:0\n\
+             DA:1,1\nLF:1\nLH:1\nend_of_record\n"
+        );
+    }
+}
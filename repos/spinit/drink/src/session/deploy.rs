@@ -0,0 +1,214 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Deploying `cargo contract deploy` plans to a [`Session`], for testing them without a
+//! running node.
+
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use contract_extrinsics::{DeployBackend, Instantiation, MessageCall};
+use frame_support::{
+    sp_runtime::traits::Bounded,
+    storage::{storage_prefix, unhashed},
+    traits::PalletInfoAccess,
+};
+use ink_sandbox::{
+    api::prelude::*,
+    pallet_revive::{
+        self,
+        evm::{H160, U256},
+        MomentOf,
+    },
+    Sandbox, H256,
+};
+
+use super::{BalanceOf, ContractBundle, Session};
+use crate::pallet_revive::Config;
+
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+fn from_hex<const N: usize>(input: &str, what: &str) -> Result<[u8; N]> {
+    let bytes = hex::decode(input.trim_start_matches("0x"))
+        .with_context(|| format!("Failed to parse {what} `{input}`"))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow!("Failed to parse {what} `{input}`, expected {N} bytes"))
+}
+
+fn load_bundle(artifact: &Path) -> Result<ContractBundle> {
+    ContractBundle::load(artifact).map_err(|err| anyhow!("{err:?}"))
+}
+
+impl<T: Sandbox> Session<T>
+where
+    T::Runtime: Config,
+    BalanceOf<T::Runtime>: Into<U256> + TryFrom<U256> + Bounded,
+    MomentOf<T::Runtime>: Into<U256>,
+    <<T as Sandbox>::Runtime as frame_system::Config>::Hash: frame_support::traits::IsType<H256>,
+{
+    /// Returns whether `key` is stored in the `storage` map of `pallet_revive`.
+    fn revive_storage_contains(&mut self, storage: &[u8], key: &[u8]) -> bool {
+        let pallet = <pallet_revive::Pallet<T::Runtime> as PalletInfoAccess>::name();
+        let mut storage_key = storage_prefix(pallet.as_bytes(), storage).to_vec();
+        storage_key.extend_from_slice(key);
+        self.sandbox.execute_with(|| unhashed::exists(&storage_key))
+    }
+
+    fn endowment(&self, value: Option<&String>) -> Result<Option<BalanceOf<T::Runtime>>> {
+        value
+            .map(|value| {
+                let value: u128 = value
+                    .parse()
+                    .with_context(|| format!("Failed to parse value `{value}`"))?;
+                BalanceOf::<T::Runtime>::try_from(U256::from(value))
+                    .map_err(|_| anyhow!("Value `{value}` does not fit the balance type"))
+            })
+            .transpose()
+    }
+}
+
+/// Deploys plans to the sandbox, so that they can be tested before deploying them to a
+/// node.
+///
+/// Dry runs leave the sandbox untouched. Values are plain balances, without a token
+/// denomination.
+impl<T: Sandbox> DeployBackend for Session<T>
+where
+    T::Runtime: Config,
+    BalanceOf<T::Runtime>: Into<U256> + TryFrom<U256> + Bounded,
+    MomentOf<T::Runtime>: Into<U256>,
+    <<T as Sandbox>::Runtime as frame_system::Config>::Hash: frame_support::traits::IsType<H256>,
+{
+    fn network(&self) -> String {
+        "drink".to_string()
+    }
+
+    async fn code_hash(&mut self, artifact: &Path) -> Result<String> {
+        let binary = load_bundle(artifact)?.binary;
+        let origin = self.origin.clone();
+        let storage_deposit_limit = self.storage_deposit_limit;
+        let result = self
+            .sandbox
+            .dry_run(|sandbox| sandbox.upload_contract(binary, origin, storage_deposit_limit));
+        result
+            .map(|upload_result| to_hex(upload_result.code_hash.as_bytes()))
+            .map_err(|err| anyhow!("Code upload failed: {err:?}"))
+    }
+
+    async fn code_exists(&mut self, code_hash: &str) -> Result<bool> {
+        let code_hash = from_hex::<32>(code_hash, "code hash")?;
+        Ok(self.revive_storage_contains(b"PristineCode", &code_hash))
+    }
+
+    async fn contract_exists(&mut self, address: &str) -> Result<bool> {
+        let address = from_hex::<20>(address, "address")?;
+        Ok(self.revive_storage_contains(b"ContractInfoOf", &address))
+    }
+
+    async fn upload(&mut self, artifact: &Path, dry_run: bool) -> Result<String> {
+        if dry_run {
+            return self.code_hash(artifact).await;
+        }
+        let code_hash = self.upload_bundle(load_bundle(artifact)?)?;
+        Ok(to_hex(code_hash.as_bytes()))
+    }
+
+    async fn instantiate(
+        &mut self,
+        artifact: &Path,
+        instantiation: &Instantiation,
+        dry_run: bool,
+    ) -> Result<String> {
+        let bundle = load_bundle(artifact)?;
+        let salt = instantiation
+            .salt
+            .as_deref()
+            .map(|salt| from_hex::<32>(salt, "salt"))
+            .transpose()?;
+        let endowment = self.endowment(instantiation.value.as_ref())?;
+        let address = if dry_run {
+            let result = self.dry_run_deployment(
+                bundle,
+                &instantiation.constructor,
+                &instantiation.args,
+                salt,
+                endowment,
+            )?;
+            match result.result {
+                Ok(exec_result) if exec_result.result.did_revert() => {
+                    bail!("Contract deployment has been reverted")
+                }
+                Ok(exec_result) => exec_result.addr,
+                Err(err) => bail!("Contract deployment failed before execution: {err:?}"),
+            }
+        } else {
+            self.deploy_bundle(
+                bundle,
+                &instantiation.constructor,
+                &instantiation.args,
+                salt,
+                endowment,
+            )?
+        };
+        Ok(to_hex(address.as_bytes()))
+    }
+
+    async fn call(&mut self, artifact: &Path, call: &MessageCall, dry_run: bool) -> Result<String> {
+        let transcoder = load_bundle(artifact)?.transcoder;
+        let address = H160::from(from_hex::<20>(&call.address, "address")?);
+        let endowment = self.endowment(call.value.as_ref())?;
+        let data = if dry_run {
+            let result = self.dry_run_call(address, &call.message, &call.args, endowment)?;
+            match result.result {
+                Ok(exec_result) if exec_result.did_revert() => {
+                    bail!("Contract call has been reverted")
+                }
+                Ok(exec_result) => exec_result.data,
+                Err(err) => bail!("Contract call failed before execution: {err:?}"),
+            }
+        } else {
+            self.call_with_address::<_, ()>(address, &call.message, &call.args, endowment)?
+                .map_err(|err| anyhow!("Contract call failed: {err:?}"))?;
+            self.record().last_call_return().to_vec()
+        };
+        let value = transcoder.decode_message_return(&call.message, &mut &data[..])?;
+        Ok(value.to_string())
+    }
+}
@@ -76,6 +76,10 @@ pub enum SessionError {
     /// There is no registered transcoder to encode/decode messages for the called contract.
     #[error("Missing transcoder")]
     NoTranscoder,
+    /// Contract code could not be instrumented for coverage.
+    #[cfg(feature = "coverage")]
+    #[error("Instrumenting contract code for coverage failed: {0}")]
+    Instrumentation(String),
 }
 
 impl SessionError {
@@ -0,0 +1,548 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Weight and storage deposit profile of contract interactions.
+//!
+//! Every deployment and call made through a [`Session`](super::Session) is accounted to the
+//! constructor or message it invoked, recognized by the selector in the call data. Besides the
+//! profile of a single session (`Session::profile`), drink keeps a profile of all the sessions in
+//! the process. If the [`PROFILE_ENV_VAR`] environment variable points to a directory, this suite
+//! profile is written there at the end of every `#[drink::test]`.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    path::Path,
+    sync::{Mutex, MutexGuard},
+};
+
+use contract_transcode::{ink_metadata::layout::Layout, ContractMessageTranscoder};
+use frame_support::weights::Weight;
+use ink_sandbox::pallet_revive::{evm::H160, StorageDeposit};
+
+/// Environment variable with the directory to write the suite profile to.
+pub const PROFILE_ENV_VAR: &str = "DRINK_PROFILE";
+
+/// Profile of all the sessions in the process.
+static SUITE: Mutex<Profile> = Mutex::new(Profile {
+    entries: BTreeMap::new(),
+});
+
+/// Kind of the profiled entry point.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EntryKind {
+    /// A constructor, invoked by a deployment.
+    Constructor,
+    /// A message, invoked by a call.
+    Message,
+}
+
+/// Minimum, maximum and total of a profiled quantity.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The smallest sample.
+    pub min: u64,
+    /// The largest sample.
+    pub max: u64,
+    /// The sum of all the samples.
+    pub total: u64,
+}
+
+impl Stats {
+    fn add(&mut self, samples: u64, value: u64) {
+        self.min = if samples == 0 {
+            value
+        } else {
+            self.min.min(value)
+        };
+        self.max = self.max.max(value);
+        self.total = self.total.saturating_add(value);
+    }
+
+    fn merge(&mut self, samples: u64, other: &Stats, other_samples: u64) {
+        if other_samples == 0 {
+            return;
+        }
+        self.min = if samples == 0 {
+            other.min
+        } else {
+            self.min.min(other.min)
+        };
+        self.max = self.max.max(other.max);
+        self.total = self.total.saturating_add(other.total);
+    }
+}
+
+/// Profile of a single constructor or message.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct EntryProfile {
+    /// Number of deployments or calls.
+    pub calls: u64,
+    /// Number of deployments or calls that were reverted or failed.
+    pub failures: u64,
+    /// Consumed `ref_time`.
+    pub ref_time: Stats,
+    /// Consumed `proof_size`.
+    pub proof_size: Stats,
+    /// Total storage deposit charged.
+    pub deposit_charged: u128,
+    /// Total storage deposit refunded.
+    pub deposit_refunded: u128,
+}
+
+impl EntryProfile {
+    fn add(&mut self, sample: &Sample) {
+        self.ref_time
+            .add(self.calls, sample.gas_consumed.ref_time());
+        self.proof_size
+            .add(self.calls, sample.gas_consumed.proof_size());
+        match sample.deposit {
+            StorageDeposit::Charge(amount) => {
+                self.deposit_charged = self.deposit_charged.saturating_add(amount)
+            }
+            StorageDeposit::Refund(amount) => {
+                self.deposit_refunded = self.deposit_refunded.saturating_add(amount)
+            }
+        }
+        self.calls += 1;
+        self.failures += sample.failed as u64;
+    }
+
+    fn merge(&mut self, other: &EntryProfile) {
+        self.ref_time
+            .merge(self.calls, &other.ref_time, other.calls);
+        self.proof_size
+            .merge(self.calls, &other.proof_size, other.calls);
+        self.deposit_charged = self.deposit_charged.saturating_add(other.deposit_charged);
+        self.deposit_refunded = self.deposit_refunded.saturating_add(other.deposit_refunded);
+        self.calls += other.calls;
+        self.failures += other.failures;
+    }
+}
+
+/// Outcome of a single deployment or call.
+pub(crate) struct Sample {
+    pub failed: bool,
+    pub gas_consumed: Weight,
+    pub deposit: StorageDeposit<u128>,
+}
+
+/// Weight and storage deposit profile of constructors and messages, grouped by contract.
+///
+/// Contracts are named after their storage struct. Constructors and messages of every deployed
+/// contract are listed even if they have never been invoked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Profile {
+    entries: BTreeMap<(String, EntryKind, String), EntryProfile>,
+}
+
+impl Profile {
+    /// Returns a copy of the profile of all the sessions in the process.
+    pub fn suite() -> Profile {
+        lock_suite().clone()
+    }
+
+    /// Iterates over the profiled entries as `(contract, kind, label, profile)`.
+    pub fn entries(&self) -> impl Iterator<Item = (&str, EntryKind, &str, &EntryProfile)> {
+        self.entries
+            .iter()
+            .map(|((contract, kind, label), profile)| {
+                (contract.as_str(), *kind, label.as_str(), profile)
+            })
+    }
+
+    /// Returns the profile of the `label` constructor or message of `contract`.
+    pub fn get(&self, contract: &str, label: &str) -> Option<&EntryProfile> {
+        self.entries()
+            .find(|(c, _, l, _)| *c == contract && *l == label)
+            .map(|(_, _, _, profile)| profile)
+    }
+
+    /// Adds all the entries of `other` to this profile.
+    pub fn merge(&mut self, other: &Profile) {
+        for (key, profile) in &other.entries {
+            self.entries.entry(key.clone()).or_default().merge(profile);
+        }
+    }
+
+    /// Lists all the constructors and messages of the contract described by `transcoder`.
+    pub(crate) fn declare(&mut self, transcoder: &ContractMessageTranscoder) {
+        let contract = contract_name(transcoder, None);
+        let spec = transcoder.metadata().spec();
+        let constructors = spec
+            .constructors()
+            .iter()
+            .map(|c| (EntryKind::Constructor, c.label().clone()));
+        let messages = spec
+            .messages()
+            .iter()
+            .map(|m| (EntryKind::Message, m.label().clone()));
+        for (kind, label) in constructors.chain(messages) {
+            self.entries
+                .entry((contract.clone(), kind, label))
+                .or_default();
+        }
+    }
+
+    /// Accounts `sample` to the entry point selected by `data`.
+    pub(crate) fn record(
+        &mut self,
+        transcoder: Option<&ContractMessageTranscoder>,
+        address: H160,
+        kind: EntryKind,
+        data: &[u8],
+        sample: &Sample,
+    ) {
+        let contract = match transcoder {
+            Some(transcoder) => contract_name(transcoder, Some(address)),
+            None => format!("{address:?}"),
+        };
+        let label = entry_label(transcoder, kind, data);
+        self.entries
+            .entry((contract, kind, label))
+            .or_default()
+            .add(sample);
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:<24} {:<24} {:>6} {:>6} {:>14} {:>14} {:>14} {:>10} {:>14} {:>14}",
+            "contract",
+            "entry",
+            "calls",
+            "failed",
+            "ref_time min",
+            "ref_time avg",
+            "ref_time max",
+            "proof avg",
+            "deposit +",
+            "deposit -",
+        )?;
+        for (contract, kind, label, profile) in self.entries() {
+            let label = match kind {
+                EntryKind::Constructor => format!("{label} (constructor)"),
+                EntryKind::Message => label.to_string(),
+            };
+            let avg = |stats: &Stats| stats.total.checked_div(profile.calls).unwrap_or_default();
+            writeln!(
+                f,
+                "{:<24} {:<24} {:>6} {:>6} {:>14} {:>14} {:>14} {:>10} {:>14} {:>14}",
+                contract,
+                label,
+                profile.calls,
+                profile.failures,
+                profile.ref_time.min,
+                avg(&profile.ref_time),
+                profile.ref_time.max,
+                avg(&profile.proof_size),
+                profile.deposit_charged,
+                profile.deposit_refunded,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies `update` to the suite profile.
+pub(crate) fn update_suite(update: impl FnOnce(&mut Profile)) {
+    update(&mut lock_suite())
+}
+
+/// Writes the suite profile to `<dir>/<test binary>.profile`.
+pub(crate) fn write_suite(dir: &Path) -> io::Result<()> {
+    // Holding the lock serializes the writes of tests finishing concurrently.
+    let suite = lock_suite();
+    fs::create_dir_all(dir)?;
+    fs::write(
+        dir.join(format!("{}.profile", super::report::report_stem())),
+        suite.to_string(),
+    )
+}
+
+fn lock_suite() -> MutexGuard<'static, Profile> {
+    // A test panicking while holding the lock doesn't leave the profile inconsistent.
+    SUITE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Contracts are named after their storage struct, which in practice is the name of the contract.
+fn contract_name(transcoder: &ContractMessageTranscoder, address: Option<H160>) -> String {
+    let name = match transcoder.metadata().layout() {
+        Layout::Root(root) => match root.layout() {
+            Layout::Struct(layout) => Some(layout.name().to_string()),
+            _ => None,
+        },
+        _ => None,
+    };
+    match (name, address) {
+        (Some(name), _) => name,
+        (None, Some(address)) => format!("{address:?}"),
+        (None, None) => "<unknown>".to_string(),
+    }
+}
+
+fn entry_label(
+    transcoder: Option<&ContractMessageTranscoder>,
+    kind: EntryKind,
+    data: &[u8],
+) -> String {
+    let selector = data.get(..4).unwrap_or(data);
+    let label = transcoder.and_then(|transcoder| {
+        let spec = transcoder.metadata().spec();
+        match kind {
+            EntryKind::Constructor => spec
+                .constructors()
+                .iter()
+                .find(|c| c.selector().to_bytes() == selector)
+                .map(|c| c.label().clone()),
+            EntryKind::Message => spec
+                .messages()
+                .iter()
+                .find(|m| m.selector().to_bytes() == selector)
+                .map(|m| m.label().clone()),
+        }
+    });
+    label.unwrap_or_else(|| {
+        let hex: String = selector.iter().map(|byte| format!("{byte:02x}")).collect();
+        format!("0x{hex}")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(ref_time: u64, deposit: StorageDeposit<u128>, failed: bool) -> Sample {
+        Sample {
+            failed,
+            gas_consumed: Weight::from_parts(ref_time, ref_time / 10),
+            deposit,
+        }
+    }
+
+    #[test]
+    fn stats_track_min_max_and_total() {
+        let mut stats = Stats::default();
+        stats.add(0, 7);
+        stats.add(1, 3);
+        stats.add(2, 5);
+        assert_eq!(
+            stats,
+            Stats {
+                min: 3,
+                max: 7,
+                total: 15
+            }
+        );
+
+        // The minimum of no samples is not a sample.
+        let mut merged = Stats::default();
+        merged.merge(0, &stats, 3);
+        assert_eq!(merged, stats);
+        merged.merge(3, &Stats::default(), 0);
+        assert_eq!(merged, stats);
+        merged.merge(
+            3,
+            &Stats {
+                min: 1,
+                max: 2,
+                total: 3,
+            },
+            2,
+        );
+        assert_eq!(
+            merged,
+            Stats {
+                min: 1,
+                max: 7,
+                total: 18
+            }
+        );
+    }
+
+    #[test]
+    fn entries_add_up_samples() {
+        let mut entry = EntryProfile::default();
+        entry.add(&sample(100, StorageDeposit::Charge(40), false));
+        entry.add(&sample(300, StorageDeposit::Refund(15), true));
+
+        assert_eq!(
+            entry,
+            EntryProfile {
+                calls: 2,
+                failures: 1,
+                ref_time: Stats {
+                    min: 100,
+                    max: 300,
+                    total: 400
+                },
+                proof_size: Stats {
+                    min: 10,
+                    max: 30,
+                    total: 40
+                },
+                deposit_charged: 40,
+                deposit_refunded: 15,
+            }
+        );
+    }
+
+    #[test]
+    fn entries_are_recognized_by_selector() {
+        let address = H160::repeat_byte(1);
+        let contract = format!("{address:?}");
+        let mut profile = Profile::default();
+        let charge = StorageDeposit::Charge(1);
+        profile.record(
+            None,
+            address,
+            EntryKind::Message,
+            &[0xde, 0xad, 0xbe, 0xef, 1, 2],
+            &sample(100, charge.clone(), false),
+        );
+        profile.record(
+            None,
+            address,
+            EntryKind::Message,
+            &[0xde, 0xad, 0xbe, 0xef],
+            &sample(200, charge.clone(), false),
+        );
+        // Data too short for a selector is taken as a whole.
+        profile.record(
+            None,
+            address,
+            EntryKind::Constructor,
+            &[0x01],
+            &sample(300, charge, false),
+        );
+
+        assert_eq!(
+            profile
+                .entries()
+                .map(|(contract, kind, label, entry)| (contract, kind, label, entry.calls))
+                .collect::<Vec<_>>(),
+            vec![
+                (contract.as_str(), EntryKind::Constructor, "0x01", 1),
+                (contract.as_str(), EntryKind::Message, "0xdeadbeef", 2),
+            ]
+        );
+        assert_eq!(
+            profile
+                .get(&contract, "0xdeadbeef")
+                .map(|entry| entry.ref_time.total),
+            Some(300)
+        );
+        assert!(profile.get(&contract, "0x01020304").is_none());
+    }
+
+    #[test]
+    fn profiles_merge_entries() {
+        let address = H160::repeat_byte(1);
+        let contract = format!("{address:?}");
+        let record = |profile: &mut Profile, ref_time| {
+            profile.record(
+                None,
+                address,
+                EntryKind::Message,
+                &[0, 0, 0, 1],
+                &sample(ref_time, StorageDeposit::Charge(10), false),
+            )
+        };
+        let mut suite = Profile::default();
+        record(&mut suite, 100);
+        let mut session = Profile::default();
+        record(&mut session, 50);
+        record(&mut session, 150);
+
+        suite.merge(&session);
+
+        let entry = suite.get(&contract, "0x00000001").expect("Merged");
+        assert_eq!(entry.calls, 3);
+        assert_eq!(
+            entry.ref_time,
+            Stats {
+                min: 50,
+                max: 150,
+                total: 300
+            }
+        );
+        assert_eq!(entry.deposit_charged, 30);
+    }
+
+    #[test]
+    fn displays_averages() {
+        let address = H160::repeat_byte(1);
+        let mut profile = Profile::default();
+        for (ref_time, failed) in [(100, false), (300, true)] {
+            profile.record(
+                None,
+                address,
+                EntryKind::Constructor,
+                &[0, 0, 0, 1],
+                &sample(ref_time, StorageDeposit::Refund(5), failed),
+            );
+        }
+
+        let display = profile.to_string();
+        let mut rows = display.lines();
+        assert!(rows.next().expect("Header").starts_with("contract"));
+        assert_eq!(
+            rows.next()
+                .expect("Entry")
+                .split_whitespace()
+                .collect::<Vec<_>>(),
+            vec![
+                format!("{address:?}").as_str(),
+                "0x00000001",
+                "(constructor)",
+                "2",
+                "1",
+                "100",
+                "200",
+                "300",
+                "20",
+                "0",
+                "10"
+            ]
+        );
+        assert_eq!(rows.next(), None);
+    }
+}
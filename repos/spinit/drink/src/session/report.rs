@@ -0,0 +1,87 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Reports written at the end of every `#[drink::test]`.
+//!
+//! Reports are opt-in: the suite profile is written if [`PROFILE_ENV_VAR`] is set, and the
+//! coverage report (with the `coverage` feature) if `DRINK_COVERAGE` is set. Both variables
+//! name a directory. Every test binary writes its own files there and rewrites them whenever a
+//! test finishes, so they always cover all the tests run so far.
+
+use std::{env, io, path::PathBuf};
+
+pub use super::profile::PROFILE_ENV_VAR;
+
+/// Writes the enabled reports when dropped.
+///
+/// `#[drink::test]` creates one for every test, so that a test is reported no matter whether it
+/// passed. A test whose reports cannot be written fails.
+pub struct ReportGuard;
+
+impl Drop for ReportGuard {
+    fn drop(&mut self) {
+        let Err(err) = write_reports() else {
+            return;
+        };
+        // Panicking while the test is already panicking would abort the whole test binary.
+        if std::thread::panicking() {
+            eprintln!("Failed to write drink reports: {err}");
+        } else {
+            panic!("Failed to write drink reports: {err}");
+        }
+    }
+}
+
+/// Writes all the enabled reports.
+pub fn write_reports() -> io::Result<()> {
+    if let Some(dir) = env::var_os(PROFILE_ENV_VAR) {
+        super::profile::write_suite(&PathBuf::from(dir))?;
+    }
+    #[cfg(feature = "coverage")]
+    if let Some(dir) = super::coverage::output_dir() {
+        super::coverage::write_report(dir)?;
+    }
+    Ok(())
+}
+
+/// Base name of the report files of the current test binary.
+pub(crate) fn report_stem() -> String {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| Some(exe.file_stem()?.to_string_lossy().into_owned()))
+        .unwrap_or_else(|| format!("drink-{}", std::process::id()))
+}
@@ -0,0 +1,522 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! Seeding sessions with the state of a real chain and diffing the state changed by a session.
+//!
+//! `setheum-node export-contracts-state` writes the raw storage of the pallets contracts depend on
+//! (`System`, `Balances` and the contracts pallet by default), together with the storage of every
+//! contract, into a JSON file. Loading such a [`StateExport`] into a session reproduces the chain
+//! state offline, so that contracts can be deployed and called against it without any RPC
+//! connection. The sandbox runtime must include the exported pallets under the same names (and
+//! storage versions) as the chain, otherwise it does not understand the loaded storage.
+//!
+//! Sandboxes run contracts on `pallet_contracts`, like Setheum does. The export names the pallet
+//! running contracts on the chain, and exports of chains running contracts on another pallet, or
+//! under another name, are refused: the sandbox would not understand the storage of their contracts.
+//!
+//! [`StateDiff`] lists the storage entries changed by a piece of a session, labelled with the
+//! pallet storage item they belong to.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Display, Formatter},
+    path::Path,
+};
+
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_support::traits::PalletInfoAccess;
+use ink_sandbox::{Sandbox, TestExternalities, H256};
+use serde_json::{json, Map, Value};
+use sp_core::{storage::well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, twox_128};
+use sp_io::{default_child_storage, storage};
+
+use crate::{pallet_contracts, DrinkResult, Error};
+
+/// Pallets contracts depend on in a sandbox with `Runtime`: `System`, `Balances` and
+/// `pallet_contracts`, under the name given to it by `Runtime`.
+pub fn default_pallets<Runtime: pallet_contracts::Config>() -> [&'static str; 3] {
+    [
+        "System",
+        "Balances",
+        <pallet_contracts::Pallet<Runtime> as PalletInfoAccess>::name(),
+    ]
+}
+
+/// Key/value pairs of a storage trie.
+pub type Entries = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// Pallet running contracts on the chain a state has been exported from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContractsPallet {
+    /// Crate of the pallet, like `pallet_contracts`.
+    pub crate_name: String,
+    /// Name of the pallet in the runtime.
+    pub name: String,
+}
+
+impl ContractsPallet {
+    /// `pallet_contracts`, under the name given to it by `Runtime`.
+    pub fn contracts<Runtime: pallet_contracts::Config>() -> Self {
+        Self {
+            crate_name: "pallet_contracts".to_string(),
+            name: <pallet_contracts::Pallet<Runtime> as PalletInfoAccess>::name().to_string(),
+        }
+    }
+}
+
+/// Raw storage of a chain, as written by `setheum-node export-contracts-state`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateExport {
+    /// Number of the block the state was exported at.
+    pub block_number: u64,
+    /// Hash of the block the state was exported at, if the state comes from a chain.
+    pub block_hash: Option<H256>,
+    /// Pallets whose storage has been exported.
+    pub pallets: Vec<String>,
+    /// Pallet running contracts on the chain, if known.
+    pub contracts_pallet: Option<ContractsPallet>,
+    /// Entries of the main trie.
+    pub top: Entries,
+    /// Entries of the default child tries (the storage of contracts), by trie id.
+    pub children: BTreeMap<Vec<u8>, Entries>,
+}
+
+impl StateExport {
+    /// Load a state export from the JSON file under `path`.
+    pub fn load<P: AsRef<Path>>(path: P) -> DrinkResult<Self> {
+        let json = std::fs::read_to_string(&path).map_err(|e| {
+            Error::StateLoadFailed(format!("Failed to read {}: {e}", path.as_ref().display()))
+        })?;
+        Self::from_json(&json)
+    }
+
+    /// Parse a state export from its JSON representation.
+    pub fn from_json(json: &str) -> DrinkResult<Self> {
+        let invalid = |what: &str| Error::StateLoadFailed(format!("Invalid {what}"));
+        let export: Value = serde_json::from_str(json)
+            .map_err(|e| Error::StateLoadFailed(format!("Failed to parse the JSON: {e}")))?;
+
+        let block_hash = match export.get("block_hash").and_then(Value::as_str) {
+            Some(hash) => {
+                let bytes = decode_hex(hash).filter(|bytes| bytes.len() == 32);
+                Some(H256::from_slice(
+                    &bytes.ok_or_else(|| invalid("block hash"))?,
+                ))
+            }
+            None => None,
+        };
+        let pallets = export
+            .get("pallets")
+            .and_then(Value::as_array)
+            .map(|pallets| {
+                pallets
+                    .iter()
+                    .map(|pallet| pallet.as_str().map(String::from))
+                    .collect::<Option<_>>()
+            })
+            .unwrap_or(Some(vec![]))
+            .ok_or_else(|| invalid("pallet list"))?;
+
+        let contracts_pallet = match export.get("contracts_pallet") {
+            Some(pallet) => {
+                let field = |name: &str| pallet.get(name).and_then(Value::as_str).map(String::from);
+                Some(ContractsPallet {
+                    crate_name: field("crate").ok_or_else(|| invalid("contracts pallet"))?,
+                    name: field("name").ok_or_else(|| invalid("contracts pallet"))?,
+                })
+            }
+            None => None,
+        };
+
+        let children = match export.get("children") {
+            Some(Value::Object(children)) => children
+                .iter()
+                .map(|(trie_id, entries)| {
+                    let trie_id = decode_hex(trie_id).ok_or_else(|| invalid("child trie id"))?;
+                    Ok((trie_id, parse_entries(entries)?))
+                })
+                .collect::<DrinkResult<_>>()?,
+            None => BTreeMap::new(),
+            Some(_) => return Err(invalid("child tries")),
+        };
+
+        Ok(Self {
+            block_number: export
+                .get("block_number")
+                .and_then(Value::as_u64)
+                .unwrap_or_default(),
+            block_hash,
+            pallets,
+            contracts_pallet,
+            top: parse_entries(export.get("top").ok_or_else(|| invalid("main trie"))?)?,
+            children,
+        })
+    }
+
+    /// JSON representation of the export, in the format of `export-contracts-state`.
+    pub fn to_json(&self) -> String {
+        let entries = |entries: &Entries| {
+            entries
+                .iter()
+                .map(|(key, value)| (encode_hex(key), Value::from(encode_hex(value))))
+                .collect::<Map<_, _>>()
+        };
+        let export = json!({
+            "block_number": self.block_number,
+            "block_hash": self.block_hash.map(|hash| encode_hex(hash.as_bytes())),
+            "pallets": self.pallets,
+            "contracts_pallet": self.contracts_pallet.as_ref().map(|pallet| json!({
+                "crate": pallet.crate_name,
+                "name": pallet.name,
+            })),
+            "top": entries(&self.top),
+            "children": self
+                .children
+                .iter()
+                .map(|(trie_id, child)| (encode_hex(trie_id), Value::from(entries(child))))
+                .collect::<Map<_, _>>(),
+        });
+        serde_json::to_string_pretty(&export).expect("Serializing JSON values cannot fail")
+    }
+
+    /// Write the export as JSON to `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        std::fs::write(path, self.to_json())
+    }
+
+    /// Check that the storage of contracts can be understood by a sandbox running
+    /// `pallet_contracts` under the name given to it by `Runtime`.
+    pub fn ensure_loadable<Runtime: pallet_contracts::Config>(&self) -> DrinkResult<()> {
+        let expected = ContractsPallet::contracts::<Runtime>();
+        match &self.contracts_pallet {
+            Some(pallet) if *pallet == expected => Ok(()),
+            Some(pallet) => Err(Error::StateLoadFailed(format!(
+                "Contracts of the exported chain run on `{}` named `{}`, while the sandbox runs \
+                 `{}` named `{}`",
+                pallet.crate_name, pallet.name, expected.crate_name, expected.name
+            ))),
+            None => Err(Error::StateLoadFailed(
+                "The export does not name the pallet running contracts".to_string(),
+            )),
+        }
+    }
+
+    /// Write the exported storage into the current externalities.
+    pub(crate) fn write(&self) {
+        for (key, value) in &self.top {
+            // Child trie roots are recomputed from the child entries.
+            if !key.starts_with(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+                storage::set(key, value);
+            }
+        }
+        for (trie_id, entries) in &self.children {
+            for (key, value) in entries {
+                default_child_storage::set(trie_id, key, value);
+            }
+        }
+    }
+}
+
+/// The whole storage of a sandbox, with child trie roots left out.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Storage {
+    pub top: Entries,
+    pub children: BTreeMap<Vec<u8>, Entries>,
+}
+
+impl Storage {
+    /// Read the whole storage of `sandbox`.
+    ///
+    /// Child tries are only listed in the main trie once committed, so the storage is read from a
+    /// snapshot rather than from the sandbox externalities.
+    pub fn read<T: Sandbox>(sandbox: &mut T) -> Self {
+        let snapshot = sandbox.take_snapshot();
+        let mut ext = TestExternalities::from_raw_snapshot(
+            snapshot.storage,
+            snapshot.storage_root,
+            Default::default(),
+        );
+        ext.execute_with(|| {
+            let mut storage = Self::default();
+            let mut key = vec![];
+            while let Some(next) = storage::next_key(&key) {
+                if let Some(trie_id) = next.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+                    storage
+                        .children
+                        .insert(trie_id.to_vec(), read_child(trie_id));
+                } else if let Some(value) = storage::get(&next) {
+                    storage.top.insert(next.clone(), value.to_vec());
+                }
+                key = next;
+            }
+            storage
+        })
+    }
+
+    /// Keep only the main trie entries of `pallets`.
+    pub fn into_export(
+        self,
+        pallets: &[&str],
+        block_number: u64,
+        contracts_pallet: ContractsPallet,
+    ) -> StateExport {
+        let prefixes = pallets
+            .iter()
+            .map(|pallet| twox_128(pallet.as_bytes()))
+            .collect::<Vec<_>>();
+        StateExport {
+            block_number,
+            block_hash: None,
+            pallets: pallets.iter().map(|pallet| pallet.to_string()).collect(),
+            contracts_pallet: Some(contracts_pallet),
+            top: self
+                .top
+                .into_iter()
+                .filter(|(key, _)| prefixes.iter().any(|prefix| key.starts_with(prefix)))
+                .collect(),
+            children: self.children,
+        }
+    }
+}
+
+fn read_child(trie_id: &[u8]) -> Entries {
+    let mut entries = Entries::new();
+    let mut key = vec![];
+    while let Some(next) = default_child_storage::next_key(trie_id, &key) {
+        if let Some(value) = default_child_storage::get(trie_id, &next) {
+            entries.insert(next.clone(), value);
+        }
+        key = next;
+    }
+    entries
+}
+
+/// Storage entry changed by a session.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StorageChange {
+    /// Id of the child trie holding the entry (the storage of a contract), if any.
+    pub child: Option<Vec<u8>>,
+    /// Raw key of the entry.
+    pub key: Vec<u8>,
+    /// Pallet and storage item of a main trie entry, if found in the runtime metadata.
+    pub item: Option<(String, String)>,
+    /// Value before the change, `None` if the entry has been inserted.
+    pub before: Option<Vec<u8>>,
+    /// Value after the change, `None` if the entry has been removed.
+    pub after: Option<Vec<u8>>,
+}
+
+impl StorageChange {
+    /// Whether the entry belongs to the storage of `pallet`.
+    pub fn is_in_pallet(&self, pallet: &str) -> bool {
+        matches!(&self.item, Some((name, _)) if name == pallet)
+    }
+}
+
+impl Display for StorageChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let sign = match (&self.before, &self.after) {
+            (None, _) => '+',
+            (_, None) => '-',
+            _ => '~',
+        };
+        write!(f, "{sign} ")?;
+        match (&self.child, &self.item) {
+            (Some(trie_id), _) => write!(f, "contract {} ", encode_hex(trie_id))?,
+            (None, Some((pallet, item))) => write!(f, "{pallet}::{item} ")?,
+            (None, None) => {}
+        }
+        write!(f, "{}", encode_hex(&self.key))?;
+        match (&self.before, &self.after) {
+            (Some(before), Some(after)) => {
+                write!(f, ": {} -> {}", encode_hex(before), encode_hex(after))
+            }
+            (Some(value), None) | (None, Some(value)) => write!(f, ": {}", encode_hex(value)),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/// Storage entries changed by a piece of a session, see [`crate::session::Session::diff_state`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    changes: Vec<StorageChange>,
+}
+
+impl StateDiff {
+    pub(crate) fn new(
+        before: &Storage,
+        after: &Storage,
+        metadata: &RuntimeMetadataPrefixed,
+    ) -> Self {
+        let items = storage_items(metadata);
+        let item_of = |key: &[u8]| key.get(..32).and_then(|prefix| items.get(prefix)).cloned();
+
+        let mut changes = vec![];
+        diff_entries(&before.top, &after.top, |key, before, after| {
+            changes.push(StorageChange {
+                child: None,
+                key,
+                item: None,
+                before,
+                after,
+            })
+        });
+        for change in &mut changes {
+            change.item = item_of(&change.key);
+        }
+
+        let empty = Entries::new();
+        let trie_ids = before.children.keys().chain(after.children.keys());
+        for trie_id in trie_ids.collect::<BTreeSet<_>>() {
+            let before = before.children.get(trie_id).unwrap_or(&empty);
+            let after = after.children.get(trie_id).unwrap_or(&empty);
+            diff_entries(before, after, |key, before, after| {
+                changes.push(StorageChange {
+                    child: Some(trie_id.clone()),
+                    key,
+                    item: None,
+                    before,
+                    after,
+                })
+            });
+        }
+
+        Self { changes }
+    }
+
+    /// All the changed entries, main trie entries first.
+    pub fn changes(&self) -> &[StorageChange] {
+        &self.changes
+    }
+
+    /// Whether no entry has changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Changed entries of the storage of `pallet`.
+    pub fn in_pallet<'a>(&'a self, pallet: &'a str) -> impl Iterator<Item = &'a StorageChange> {
+        self.changes
+            .iter()
+            .filter(move |change| change.is_in_pallet(pallet))
+    }
+
+    /// Changed entries of the storage of contracts.
+    pub fn in_contracts(&self) -> impl Iterator<Item = &StorageChange> {
+        self.changes.iter().filter(|change| change.child.is_some())
+    }
+}
+
+impl Display for StateDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+fn diff_entries(
+    before: &Entries,
+    after: &Entries,
+    mut push: impl FnMut(Vec<u8>, Option<Vec<u8>>, Option<Vec<u8>>),
+) {
+    for key in before.keys().chain(after.keys()).collect::<BTreeSet<_>>() {
+        let (old, new) = (before.get(key), after.get(key));
+        if old != new {
+            push(key.clone(), old.cloned(), new.cloned());
+        }
+    }
+}
+
+/// Pallet and item names of all storage items, by the prefix of their keys.
+fn storage_items(metadata: &RuntimeMetadataPrefixed) -> BTreeMap<Vec<u8>, (String, String)> {
+    fn item(prefix: &str, name: &str) -> (Vec<u8>, (String, String)) {
+        let key = [twox_128(prefix.as_bytes()), twox_128(name.as_bytes())].concat();
+        (key, (prefix.to_string(), name.to_string()))
+    }
+
+    match &metadata.1 {
+        RuntimeMetadata::V14(metadata) => metadata
+            .pallets
+            .iter()
+            .filter_map(|pallet| pallet.storage.as_ref())
+            .flat_map(|storage| {
+                storage
+                    .entries
+                    .iter()
+                    .map(|entry| item(&storage.prefix, &entry.name))
+            })
+            .collect(),
+        RuntimeMetadata::V15(metadata) => metadata
+            .pallets
+            .iter()
+            .filter_map(|pallet| pallet.storage.as_ref())
+            .flat_map(|storage| {
+                storage
+                    .entries
+                    .iter()
+                    .map(|entry| item(&storage.prefix, &entry.name))
+            })
+            .collect(),
+        // Changes are still listed, just without the item names.
+        _ => BTreeMap::new(),
+    }
+}
+
+fn parse_entries(entries: &Value) -> DrinkResult<Entries> {
+    let invalid = || Error::StateLoadFailed("Invalid storage entries".to_string());
+    entries
+        .as_object()
+        .ok_or_else(invalid)?
+        .iter()
+        .map(|(key, value)| {
+            let value = value.as_str().and_then(decode_hex);
+            Some((decode_hex(key)?, value?))
+        })
+        .collect::<Option<_>>()
+        .ok_or_else(invalid)
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    hex::decode(hex.strip_prefix("0x").unwrap_or(hex)).ok()
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
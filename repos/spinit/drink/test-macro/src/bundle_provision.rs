@@ -88,16 +88,13 @@ impl BundleProviderGenerator {
             }
         };
 
-        let (contract_names, matches): (Vec<_>, Vec<_>) = self
+        let (contract_names, paths): (Vec<_>, Vec<_>) = self
             .bundles
             .keys()
             .map(|name| {
                 let name_ident = Ident::new(name, Span::call_site());
                 let path = self.bundles[name].to_str().expect("Invalid path");
-                let matcher = quote! {
-                    #enum_name::#name_ident => ::drink::session::ContractBundle::load(#path),
-                };
-                (name_ident, matcher)
+                (name_ident, path)
             })
             .unzip();
 
@@ -112,8 +109,12 @@ impl BundleProviderGenerator {
                 #local
 
                 pub fn bundle(self) -> ::drink::DrinkResult<::drink::session::ContractBundle> {
+                    ::drink::session::ContractBundle::load(self.path())
+                }
+
+                pub fn path(self) -> &'static ::std::path::Path {
                     match self {
-                        #(#matches)*
+                        #(#enum_name::#contract_names => ::std::path::Path::new(#paths),)*
                     }
                 }
             }
@@ -52,6 +52,9 @@ use crate::bundle_provision::BundleProviderGenerator;
 /// Contract package differentiator.
 const INK_AS_DEPENDENCY_FEATURE: &str = "ink-as-dependency";
 
+/// Environment variable enabling code coverage (see `drink::session::coverage`).
+pub const COVERAGE_ENV_VAR: &str = "DRINK_COVERAGE";
+
 /// Stores the manifest paths of all contracts that have already been built.
 ///
 /// This prevents from building the same contract for every testcase separately.
@@ -153,15 +156,21 @@ fn build_contract_crate(pkg: FeaturedPackage) -> (String, PathBuf) {
     {
         Entry::Occupied(ready) => ready.get().clone(),
         Entry::Vacant(todo) => {
+            // Coverage needs functions that are not inlined and that keep their names.
+            let coverage = std::env::var_os(COVERAGE_ENV_VAR).is_some();
             let args = ExecuteArgs {
                 manifest_path,
                 verbosity: Verbosity::Default,
-                build_mode: BuildMode::Release,
+                build_mode: if coverage {
+                    BuildMode::Debug
+                } else {
+                    BuildMode::Release
+                },
                 features,
                 network: Network::Online,
                 build_artifact: BuildArtifacts::All,
                 unstable_flags: UnstableFlags::default(),
-                keep_debug_symbols: false,
+                keep_debug_symbols: coverage,
                 extra_lints: false,
                 output_type: OutputType::HumanReadable,
                 metadata_spec: MetadataSpec::Ink,
@@ -48,7 +48,7 @@ use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
 use syn::{ItemEnum, ItemFn};
 
-use crate::contract_building::build_contracts;
+use crate::contract_building::{build_contracts, COVERAGE_ENV_VAR};
 
 type SynResult<T> = Result<T, syn::Error>;
 
@@ -87,6 +87,12 @@ type SynResult<T> = Result<T, syn::Error>;
 ///
 /// By default, the macro will use `drink::minimal::MinimalSandbox`.
 ///
+/// # Reports
+///
+/// When the test finishes, the reports enabled with the `DRINK_PROFILE` and `DRINK_COVERAGE`
+/// environment variables are written (see `drink::session::report`). With `DRINK_COVERAGE` set,
+/// contracts are built in debug mode with debug symbols.
+///
 /// # Example
 ///
 /// ```rust, ignore
@@ -130,17 +136,110 @@ fn test_internal(attr: TokenStream2, item: TokenStream2) -> SynResult<TokenStrea
     let sandbox = macro_args
         .sandbox
         .unwrap_or(syn::parse2(quote! { ::drink::minimal::MinimalSandbox })?);
+    let coverage_env_var = COVERAGE_ENV_VAR;
 
     Ok(quote! {
         #[test]
         #(#fn_attrs)*
         #fn_vis #fn_async #fn_const #fn_unsafety fn #fn_name #fn_generics () #fn_output {
+            // Makes cargo rebuild the test (and thus its contracts) when coverage is toggled.
+            let _ = ::core::option_env!(#coverage_env_var);
+            let _reports = ::drink::session::report::ReportGuard;
             let mut session = Session::<#sandbox>::default();
             #fn_block
         }
     })
 }
 
+/// Defines a drink!-based fuzzing test.
+///
+/// # Requirements
+///
+/// The same as for `#[drink::test]`. Besides, `drink` must have the `fuzz` feature enabled.
+///
+/// # Impact
+///
+/// Contracts are built exactly like for `#[drink::test]`.
+///
+/// # Creating a fuzzer object
+///
+/// Instead of a session, the macro will create a new `drink::fuzz::Fuzzer` object and pass it to
+/// the decorated function by value. Thus, your testcase function should accept a single argument:
+/// `fuzzer: Fuzzer<_>`. The function is expected to set the contract bundle and the invariants,
+/// and then call `fuzzer.run()`.
+///
+/// The macro accepts the following (optional) attributes:
+///  - `sandbox`: path to a type implementing `ink_sandbox::Sandbox` trait (by default,
+///    `drink::minimal::MinimalSandbox`),
+///  - `runs`: number of runs, each of them starting with a freshly deployed contract,
+///  - `steps`: number of messages called in every run,
+///  - `seed`: seed of the first run (can be overridden with `DRINK_FUZZ_SEED` environment variable).
+///
+/// # Example
+///
+/// ```rust, ignore
+/// #[drink::fuzz(runs = 64, steps = 32)]
+/// fn testcase(fuzzer: Fuzzer<MinimalSandbox>) -> Result<(), Box<dyn Error>> {
+///     fuzzer
+///         .with_bundle(BundleProvider::local()?)
+///         .with_invariant("never panics", |session, _| Ok(()))
+///         .run()?;
+///     Ok(())
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn fuzz(attr: TokenStream, item: TokenStream) -> TokenStream {
+    match fuzz_internal(attr.into(), item.into()) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+#[derive(FromMeta)]
+struct FuzzAttributes {
+    sandbox: Option<syn::Path>,
+    runs: Option<usize>,
+    steps: Option<usize>,
+    seed: Option<u64>,
+}
+
+/// Auxiliary function to enter ?-based error propagation.
+fn fuzz_internal(attr: TokenStream2, item: TokenStream2) -> SynResult<TokenStream2> {
+    let item_fn = syn::parse2::<ItemFn>(item)?;
+    let macro_args = FuzzAttributes::from_list(&NestedMeta::parse_meta_list(attr)?)?;
+
+    build_contracts();
+
+    let fn_vis = item_fn.vis;
+    let fn_attrs = item_fn.attrs;
+    let fn_block = item_fn.block;
+    let fn_name = item_fn.sig.ident;
+    let fn_output = item_fn.sig.output;
+
+    let sandbox = macro_args
+        .sandbox
+        .unwrap_or(syn::parse2(quote! { ::drink::minimal::MinimalSandbox })?);
+    let runs = macro_args.runs.map(|runs| quote! { runs: #runs, });
+    let steps = macro_args.steps.map(|steps| quote! { steps: #steps, });
+    let seed = macro_args
+        .seed
+        .map(|seed| quote! { seed: ::core::option::Option::Some(#seed), });
+
+    Ok(quote! {
+        #[test]
+        #(#fn_attrs)*
+        #fn_vis fn #fn_name () #fn_output {
+            let fuzzer = ::drink::fuzz::Fuzzer::<#sandbox>::new(::drink::fuzz::FuzzConfig {
+                #runs
+                #steps
+                #seed
+                ..::core::default::Default::default()
+            });
+            #fn_block
+        }
+    })
+}
+
 /// Defines a contract bundle provider.
 ///
 /// # Requirements
@@ -164,7 +263,8 @@ fn test_internal(attr: TokenStream2, item: TokenStream2) -> SynResult<TokenStrea
 /// Besides that, the enum will receive a method `bundle(self)` that returns the contract bundle
 /// for corresponding contract variant.
 ///
-/// Both methods return `DrinkResult<ContractBundle>`.
+/// Both methods return `DrinkResult<ContractBundle>`. The path of the `.contract` file of a variant
+/// is returned by `path(self)`.
 ///
 /// # Example
 ///
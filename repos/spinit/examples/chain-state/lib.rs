@@ -0,0 +1,189 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// A counter, used to show how a session is seeded with an exported chain state.
+#[ink::contract]
+mod chain_state {
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Counter {
+        value: u32,
+    }
+
+    impl Counter {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        #[ink(message)]
+        pub fn inc(&mut self) {
+            self.value += 1;
+        }
+
+        #[ink(message)]
+        pub fn get(&self) -> u32 {
+            self.value
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use drink::{
+        minimal::{MinimalSandbox, MinimalSandboxRuntime},
+        sandbox_api::prelude::{BalanceAPI, SystemAPI},
+        session::{
+            error::SessionError, state::default_pallets, ContractsPallet, Session, StateExport,
+            NO_ARGS, NO_ENDOWMENT, NO_SALT,
+        },
+        AccountId32, Error as DrinkError,
+    };
+
+    const ALICE: [u8; 32] = [
+        0xd4, 0x35, 0x93, 0xc7, 0x15, 0xfd, 0xd3, 0x1c, 0x61, 0x14, 0x1a, 0xbd, 0x04, 0xa9, 0x9f,
+        0xd6, 0x82, 0x2c, 0x85, 0x58, 0x85, 0x4c, 0xcd, 0xe3, 0x9a, 0x56, 0x84, 0xe7, 0xa5, 0x6d,
+        0xa2, 0x7d,
+    ];
+
+    #[drink::contract_bundle_provider]
+    enum BundleProvider {}
+
+    /// In a real setup, the export comes from `setheum-node export-contracts-state --output
+    /// state.json` and is committed next to the tests. Here, it is exported from another session.
+    #[drink::test]
+    fn calling_a_contract_from_an_exported_state(
+        mut session: Session,
+    ) -> Result<(), Box<dyn Error>> {
+        let contract = BundleProvider::local()?;
+        let transcoder = contract.transcoder.clone();
+        let address = session.deploy_bundle(contract, "new", NO_ARGS, NO_SALT, NO_ENDOWMENT)?;
+        session.call::<_, ()>("inc", NO_ARGS, NO_ENDOWMENT)??;
+
+        let path = std::env::temp_dir().join(format!("drink-state-{}.json", std::process::id()));
+        session
+            .export_state(&default_pallets::<MinimalSandboxRuntime>())
+            .save(&path)?;
+        let state = StateExport::load(&path)?;
+        std::fs::remove_file(&path)?;
+        assert_eq!(state.children.len(), 1);
+
+        let mut replay = Session::<MinimalSandbox>::default()
+            .with_state(&state)?
+            .with_transcoder(address, &transcoder);
+        replay.call_with_address::<_, ()>(address, "inc", NO_ARGS, NO_ENDOWMENT)??;
+        let value: u32 = replay.call_with_address(address, "get", NO_ARGS, NO_ENDOWMENT)??;
+        assert_eq!(value, 2);
+
+        Ok(())
+    }
+
+    /// `setheum-export.json` is in the format written by `setheum-node export-contracts-state`:
+    /// Alice has uploaded the code of a contract and instantiated it at block 1234.
+    #[drink::test]
+    fn loading_a_node_export(mut session: Session) -> Result<(), Box<dyn Error>> {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/setheum-export.json");
+        let state = StateExport::load(path)?;
+        assert_eq!(state.block_number, 1234);
+        assert_eq!(state.pallets, default_pallets::<MinimalSandboxRuntime>());
+        assert_eq!(
+            state.contracts_pallet,
+            Some(ContractsPallet::contracts::<MinimalSandboxRuntime>())
+        );
+        assert_eq!(state.children.len(), 1);
+
+        session.load_state(&state)?;
+        assert_eq!(session.sandbox().block_number(), 1234);
+        assert_eq!(
+            session.sandbox().free_balance(&AccountId32::new(ALICE)),
+            999_995_890_000_000_000
+        );
+
+        let loaded = session.export_state(&default_pallets::<MinimalSandboxRuntime>());
+        for (key, value) in &state.top {
+            assert_eq!(loaded.top.get(key), Some(value));
+        }
+        assert_eq!(loaded.children, state.children);
+
+        // Exports of chains running contracts on another pallet are refused.
+        let mut other = state.clone();
+        other.contracts_pallet = Some(ContractsPallet {
+            crate_name: "pallet_revive".to_string(),
+            name: "Revive".to_string(),
+        });
+        assert!(matches!(
+            session.load_state(&other),
+            Err(SessionError::Drink(DrinkError::StateLoadFailed(message)))
+                if message.contains("pallet_revive")
+        ));
+
+        Ok(())
+    }
+
+    #[drink::test]
+    fn diffing_the_state_changed_by_a_call(mut session: Session) -> Result<(), Box<dyn Error>> {
+        let contract = BundleProvider::local()?;
+        session.deploy_bundle(contract, "new", NO_ARGS, NO_SALT, NO_ENDOWMENT)?;
+
+        let (result, diff) =
+            session.diff_state(|session| session.call::<_, ()>("inc", NO_ARGS, NO_ENDOWMENT));
+        result??;
+        println!("{diff}");
+
+        // The counter is the only entry in the storage of the contract, and it has been updated.
+        let counter = diff.in_contracts().collect::<Vec<_>>();
+        assert_eq!(counter.len(), 1);
+        assert!(counter[0].before.is_some() && counter[0].after.is_some());
+
+        // Events of the call are stored by `System`.
+        assert!(diff.in_pallet("System").any(|change| change
+            .item
+            .as_ref()
+            .is_some_and(|(_, item)| item == "Events")));
+
+        // Reading the state changes nothing.
+        let (_, diff) =
+            session.diff_state(|session| session.call::<_, u32>("get", NO_ARGS, NO_ENDOWMENT));
+        assert!(diff.in_contracts().next().is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,179 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Supply minted to the deployer.
+pub const INITIAL_SUPPLY: u128 = 1_000_000;
+
+#[ink::contract]
+mod ledger {
+    use ink::{storage::Mapping, H160};
+
+    use crate::INITIAL_SUPPLY;
+
+    #[ink(storage)]
+    pub struct Ledger {
+        balances: Mapping<H160, u128>,
+        total_supply: u128,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        InsufficientBalance,
+    }
+
+    impl Ledger {
+        #[ink(constructor)]
+        #[allow(clippy::new_without_default)]
+        pub fn new() -> Self {
+            let mut balances = Mapping::new();
+            balances.insert(Self::env().caller(), &INITIAL_SUPPLY);
+            Self {
+                balances,
+                total_supply: INITIAL_SUPPLY,
+            }
+        }
+
+        #[ink(message)]
+        pub fn transfer(&mut self, to: H160, amount: u128) -> Result<(), Error> {
+            let from = self.env().caller();
+            let from_balance = self.balance_of(from);
+            if from_balance < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            self.balances.insert(from, &(from_balance - amount));
+            let to_balance = self.balance_of(to);
+            self.balances.insert(to, &(to_balance + amount));
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn burn(&mut self, amount: u128) -> Result<(), Error> {
+            let owner = self.env().caller();
+            let balance = self.balance_of(owner);
+            if balance < amount {
+                return Err(Error::InsufficientBalance);
+            }
+            self.balances.insert(owner, &(balance - amount));
+            self.total_supply -= amount;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn balance_of(&self, owner: H160) -> u128 {
+            self.balances.get(owner).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn total_supply(&self) -> u128 {
+            self.total_supply
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use drink::{
+        fuzz::{FuzzError, Fuzzer},
+        minimal::MinimalSandbox,
+        pallet_revive::evm::H160,
+        session::{Session, NO_ARGS, NO_ENDOWMENT},
+    };
+
+    use crate::INITIAL_SUPPLY;
+
+    #[drink::contract_bundle_provider]
+    enum BundleProvider {}
+
+    fn total_supply(session: &mut Session<MinimalSandbox>, address: H160) -> Result<u128, String> {
+        session
+            .call_with_address(address, "total_supply", NO_ARGS, NO_ENDOWMENT)
+            .map_err(|err| err.to_string())?
+            .map_err(|err| err.to_string())
+    }
+
+    fn supply_is_constant(
+        session: &mut Session<MinimalSandbox>,
+        address: H160,
+    ) -> Result<(), String> {
+        match total_supply(session, address)? {
+            INITIAL_SUPPLY => Ok(()),
+            supply => Err(format!("total supply changed to {supply}")),
+        }
+    }
+
+    #[drink::fuzz(runs = 16, steps = 32)]
+    fn transfers_preserve_supply(fuzzer: Fuzzer<MinimalSandbox>) -> Result<(), Box<dyn Error>> {
+        // Random transfers between the callers, the contract and random addresses never mint nor
+        // burn tokens.
+        fuzzer
+            .with_bundle(BundleProvider::local()?)
+            .without_messages(&["burn"])
+            .with_invariant("constant supply", supply_is_constant)
+            .run()?;
+        Ok(())
+    }
+
+    #[drink::fuzz(runs = 16, steps = 32, seed = 0)]
+    fn burning_is_found_and_shrunk(fuzzer: Fuzzer<MinimalSandbox>) -> Result<(), Box<dyn Error>> {
+        let result = fuzzer
+            .with_bundle(BundleProvider::local()?)
+            .with_invariant("constant supply", supply_is_constant)
+            .run();
+
+        // The shrunk case burns a single token from the deployer's balance, right after the
+        // deployment.
+        let Err(FuzzError::InvariantViolated(failure)) = result else {
+            panic!("Burning should have violated the invariant");
+        };
+        assert_eq!(failure.case.constructor.caller, 0);
+        assert_eq!(failure.case.steps.len(), 1);
+        assert_eq!(failure.case.steps[0].label, "burn");
+        assert_eq!(failure.message, "total supply changed to 999999");
+
+        // The reported case replays the failure.
+        let fuzzer = Fuzzer::<MinimalSandbox>::new(Default::default())
+            .with_bundle(BundleProvider::local()?)
+            .with_invariant("constant supply", supply_is_constant);
+        assert!(fuzzer.replay(&failure.case.to_string()).is_err());
+
+        Ok(())
+    }
+}
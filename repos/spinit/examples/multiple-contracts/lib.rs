@@ -75,7 +75,9 @@ mod checker {
 mod tests {
     use std::error::Error;
 
+    use contract_extrinsics::{execute_plan, DeployLock, DeployPlan, StepStatus};
     use drink::session::{Session, NO_ARGS, NO_ENDOWMENT};
+    use futures::executor::block_on;
 
     #[drink::contract_bundle_provider]
     enum BundleProvider {}
@@ -103,4 +105,50 @@ mod tests {
 
         Ok(())
     }
+
+    #[drink::test]
+    fn deployment_plans_work_correctly(mut session: Session) -> Result<(), Box<dyn Error>> {
+        let plan: DeployPlan = toml::from_str(&format!(
+            r#"
+            [[contract]]
+            name = "flipper"
+            path = "{}"
+            constructor = "new"
+            args = ["true"]
+
+            [[contract]]
+            name = "checker"
+            path = "{}"
+            constructor = "new"
+            args = ["${{flipper.address}}"]
+
+            [[call]]
+            contract = "flipper"
+            message = "flip"
+            "#,
+            BundleProvider::Flipper.path().display(),
+            BundleProvider::MultipleContracts.path().display(),
+        ))?;
+        plan.validate()?;
+        let mut lock = DeployLock::default();
+
+        let report = block_on(execute_plan(&plan, &mut lock, &mut session, false))?;
+        assert_eq!(report.steps.len(), 5);
+        assert!(report
+            .steps
+            .iter()
+            .all(|step| step.status == StepStatus::Executed));
+
+        let value: bool = session.call("check", NO_ARGS, NO_ENDOWMENT)??;
+        assert!(!value);
+
+        // Everything recorded in the lock still exists, so running the plan again is a no-op.
+        let report = block_on(execute_plan(&plan, &mut lock, &mut session, false))?;
+        assert!(report
+            .steps
+            .iter()
+            .all(|step| step.status == StepStatus::Skipped));
+
+        Ok(())
+    }
 }
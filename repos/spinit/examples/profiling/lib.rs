@@ -0,0 +1,177 @@
+// بِسْمِ اللَّهِ الرَّحْمَنِ الرَّحِيم
+// This file is part of Setheum.
+
+// Copyright (C) 2019-Present Afsall Labs.
+// SPDX-License-Identifier: Apache-2.0 OR MIT
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Alternatively, this file is available under the MIT License:
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// A simple registry of names, used to show weight and storage deposit profiles of messages that
+/// add and remove storage items.
+#[ink::contract]
+mod profiling {
+    use ink::{prelude::string::String, storage::Mapping};
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Registry {
+        names: Mapping<AccountId, String>,
+        count: u32,
+    }
+
+    impl Registry {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        #[ink(message)]
+        pub fn register(&mut self, name: String) {
+            if self.names.insert(self.env().caller(), &name).is_none() {
+                self.count += 1;
+            }
+        }
+
+        #[ink(message)]
+        pub fn unregister(&mut self) {
+            if self.names.take(self.env().caller()).is_some() {
+                self.count -= 1;
+            }
+        }
+
+        #[ink(message)]
+        pub fn count(&self) -> u32 {
+            self.count
+        }
+
+        #[ink(message)]
+        pub fn name_of(&self, account: AccountId) -> Option<String> {
+            self.names.get(account)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+
+    use drink::session::{coverage, Profile, Session, NO_ARGS, NO_ENDOWMENT, NO_SALT};
+
+    #[drink::contract_bundle_provider]
+    enum BundleProvider {}
+
+    /// Run with `DRINK_PROFILE=target/drink` to get the profile of the whole suite in a file.
+    #[drink::test]
+    fn profile_of_a_session(mut session: Session) -> Result<(), Box<dyn Error>> {
+        let contract = BundleProvider::local()?;
+        session
+            .deploy_bundle_and(contract, "new", NO_ARGS, NO_SALT, NO_ENDOWMENT)?
+            .call_and("register", &["\"Alice\""], NO_ENDOWMENT)?
+            .call_and("register", &["\"Alice the Great\""], NO_ENDOWMENT)?
+            .call_and("unregister", NO_ARGS, NO_ENDOWMENT)?;
+
+        let profile = session.profile();
+        let register = profile.get("Registry", "register").expect("Profiled");
+        assert_eq!(register.calls, 2);
+        assert_eq!(register.failures, 0);
+        assert!(register.ref_time.min > 0);
+        assert!(register.deposit_charged > 0);
+
+        let unregister = profile.get("Registry", "unregister").expect("Profiled");
+        assert!(unregister.deposit_refunded > 0);
+
+        // Messages that have never been called are listed as well.
+        assert_eq!(profile.get("Registry", "count").expect("Listed").calls, 0);
+
+        println!("{profile}");
+        assert!(Profile::suite().get("Registry", "register").is_some());
+
+        Ok(())
+    }
+
+    /// Coverage is enabled for this example in `.cargo/config.toml`, which also writes the lcov
+    /// report of the suite to `target/drink`.
+    #[drink::test]
+    fn coverage_of_messages(mut session: Session) -> Result<(), Box<dyn Error>> {
+        let contract = BundleProvider::local()?;
+        session
+            .deploy_bundle_and(contract, "new", NO_ARGS, NO_SALT, NO_ENDOWMENT)?
+            .call_and("register", &["\"Bob\""], NO_ENDOWMENT)?;
+
+        // Read-only calls are covered as well.
+        let address = *session.record().deploy_returns().last().expect("Deployed");
+        let count = session.dry_run_call(address, "count", NO_ARGS, NO_ENDOWMENT)?;
+
+        assert!(coverage::output_dir().is_some(), "Coverage is enabled");
+        let line_of = |code: &str| {
+            include_str!("lib.rs")
+                .lines()
+                .position(|line| line.trim() == code)
+                .expect("In the sources") as u64
+                + 1
+        };
+        let hits = |code: &str| {
+            coverage::lines()
+                .into_iter()
+                .filter(|covered| covered.file.ends_with("lib.rs") && covered.line == line_of(code))
+                .map(|covered| covered.hits)
+                .sum::<u64>()
+        };
+        assert!(hits("self.count += 1;") > 0);
+        assert!(hits("self.count") > 0);
+        // No test looks names up.
+        assert_eq!(hits("self.names.get(account)"), 0);
+
+        // Functions are located at their signatures.
+        let calls = |signature: &str| {
+            coverage::functions()
+                .into_iter()
+                .filter(|covered| {
+                    covered.file.ends_with("lib.rs") && covered.line == line_of(signature)
+                })
+                .map(|covered| covered.hits)
+                .sum::<u64>()
+        };
+        assert!(calls("pub fn register(&mut self, name: String) {") > 0);
+        assert_eq!(
+            calls("pub fn name_of(&self, account: AccountId) -> Option<String> {"),
+            0
+        );
+
+        // Markers of the instrumented code never make it to the debug buffer.
+        assert!(!String::from_utf8_lossy(&count.debug_message).contains("drink-cov:"));
+
+        Ok(())
+    }
+}